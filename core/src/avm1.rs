@@ -5,6 +5,7 @@ use crate::avm1::property::Attribute;
 use crate::avm1::return_value::ReturnValue;
 use crate::backend::navigator::{NavigationMethod, RequestOptions};
 use crate::context::UpdateContext;
+use crate::policy;
 use crate::prelude::*;
 use enumset::EnumSet;
 use gc_arena::{GcCell, MutationContext};
@@ -21,19 +22,21 @@ use crate::tag_utils::SwfSlice;
 
 #[cfg(test)]
 #[macro_use]
-mod test_utils;
+pub(crate) mod test_utils;
 
 #[macro_use]
 pub mod listeners;
 
 mod activation;
+mod bitmap_data_object;
+mod date_object;
 pub mod debug;
 pub mod error;
 mod fscommand;
 pub mod function;
 pub mod globals;
 pub mod object;
-mod property;
+pub mod property;
 mod return_value;
 mod scope;
 pub mod script_object;
@@ -41,7 +44,7 @@ pub mod shared_object;
 mod sound_object;
 mod stage_object;
 mod super_object;
-mod value;
+pub mod value;
 mod value_object;
 pub mod xml_attributes_object;
 pub mod xml_idmap_object;
@@ -393,6 +396,30 @@ impl<'gc> Avm1<'gc> {
         self.stack_frames.push(frame);
     }
 
+    /// Runs a function inside a stack frame on the root movie, then runs any code it started.
+    ///
+    /// This is used to call into ActionScript from outside of the AVM, such as when the host
+    /// or another player sends a message. Returns `None` if there is no root movie to run on.
+    pub fn run_in_root_frame<F, R>(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        f: F,
+    ) -> Option<R>
+    where
+        F: FnOnce(&mut Self, &mut UpdateContext<'_, 'gc, '_>) -> R,
+    {
+        let root = *context.levels.get(&0)?;
+        let swf_version = context.swf.header().version;
+        let frame = GcCell::allocate(
+            context.gc_context,
+            Activation::from_nothing(swf_version, self.globals, context.gc_context, root),
+        );
+        self.insert_stack_frame(frame);
+        let result = f(self, context);
+        let _ = self.run_current_frame(context, frame);
+        Some(result)
+    }
+
     /// Retrieve the current AVM execution frame.
     ///
     /// Yields None if there is no stack frame.
@@ -1927,10 +1954,13 @@ impl<'gc> Avm1<'gc> {
                 Ok(level_id) => {
                     let fetch = context.navigator.fetch(&url, RequestOptions::get());
                     let level = self.resolve_level(level_id, context);
+                    let url =
+                        policy::resolve_url(self.target_clip_or_root().movie().as_deref(), &url);
 
                     let process = context.load_manager.load_movie_into_clip(
                         context.player.clone().unwrap(),
                         level,
+                        url,
                         fetch,
                         None,
                     );
@@ -1947,7 +1977,7 @@ impl<'gc> Avm1<'gc> {
         }
 
         if let Some(fscommand) = fscommand::parse(url) {
-            return fscommand::handle(fscommand, self, context);
+            return fscommand::handle(fscommand, target, self, context);
         }
 
         context
@@ -1970,11 +2000,11 @@ impl<'gc> Avm1<'gc> {
         let url_val = self.pop();
         let url = url_val.coerce_to_string(self, context)?;
 
+        let window_target = target.coerce_to_string(self, context)?;
         if let Some(fscommand) = fscommand::parse(&url) {
-            return fscommand::handle(fscommand, self, context);
+            return fscommand::handle(fscommand, &window_target, self, context);
         }
 
-        let window_target = target.coerce_to_string(self, context)?;
         let clip_target: Option<DisplayObject<'gc>> = if is_target_sprite {
             if let Value::Object(target) = target {
                 target.as_display_object()
@@ -1998,7 +2028,12 @@ impl<'gc> Avm1<'gc> {
                     url,
                     NavigationMethod::from_send_vars_method(swf_method),
                 );
-                let fetch = context.navigator.fetch(&url, opts);
+                let fetch = policy::fetch_data(
+                    context,
+                    self.target_clip_or_root().movie().as_deref(),
+                    &url,
+                    opts,
+                );
                 let process = context.load_manager.load_form_into_object(
                     context.player.clone().unwrap(),
                     target_obj,
@@ -2017,9 +2052,11 @@ impl<'gc> Avm1<'gc> {
                     NavigationMethod::from_send_vars_method(swf_method),
                 );
                 let fetch = context.navigator.fetch(&url, opts);
+                let url = policy::resolve_url(self.target_clip_or_root().movie().as_deref(), &url);
                 let process = context.load_manager.load_movie_into_clip(
                     context.player.clone().unwrap(),
                     clip_target,
+                    url,
                     fetch,
                     None,
                 );
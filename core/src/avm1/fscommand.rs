@@ -13,13 +13,18 @@ pub fn parse(url: &str) -> Option<&str> {
     }
 }
 
-/// TODO: FSCommand URL handling
+/// Passes an FSCommand to the hosts of the player.
+///
+/// The arguments of the command are given as the target of the `getURL` call.
 pub fn handle<'gc>(
     fscommand: &str,
+    args: &str,
     _avm: &mut Avm1,
-    _ac: &mut UpdateContext,
+    context: &mut UpdateContext,
 ) -> Result<(), Error<'gc>> {
-    log::warn!("Unhandled FSCommand: {}", fscommand);
+    if !context.external_interface.on_fs_command(fscommand, args) {
+        log::warn!("Unhandled FSCommand: {}", fscommand);
+    }
 
     //This should be an error.
     Ok(())
@@ -5,18 +5,25 @@ use crate::avm1::listeners::SystemListeners;
 use crate::avm1::return_value::ReturnValue;
 use crate::avm1::{Avm1, Object, ScriptObject, TObject, UpdateContext, Value};
 use crate::backend::navigator::NavigationMethod;
+use crate::timer::TimerCallback;
 use enumset::EnumSet;
 use gc_arena::MutationContext;
 use rand::Rng;
 use std::f64;
 
 mod array;
+mod as_broadcaster;
+mod bitmap_data;
 pub(crate) mod boolean;
 pub(crate) mod button;
 mod color;
+mod date;
 pub(crate) mod display_object;
+mod external_interface;
 mod function;
 mod key;
+mod load_vars;
+mod local_connection;
 mod math;
 mod matrix;
 pub(crate) mod mouse;
@@ -26,6 +33,7 @@ pub(crate) mod number;
 mod object;
 mod point;
 mod rectangle;
+mod selection;
 pub(crate) mod shared_object;
 mod sound;
 mod stage;
@@ -37,6 +45,7 @@ pub(crate) mod system_security;
 pub(crate) mod text_field;
 mod text_format;
 mod xml;
+mod xml_socket;
 
 #[allow(non_snake_case, unused_must_use)] //can't use errors yet
 pub fn getURL<'a, 'gc>(
@@ -48,16 +57,17 @@ pub fn getURL<'a, 'gc>(
     //TODO: Error behavior if no arguments are present
     if let Some(url_val) = args.get(0) {
         let url = url_val.coerce_to_string(avm, context)?;
-        if let Some(fscommand) = fscommand::parse(&url) {
-            fscommand::handle(fscommand, avm, context);
-            return Ok(Value::Undefined.into());
-        }
-
         let window = if let Some(window) = args.get(1) {
             Some(window.coerce_to_string(avm, context)?.to_string())
         } else {
             None
         };
+        if let Some(fscommand) = fscommand::parse(&url) {
+            let fsargs = window.as_deref().unwrap_or_default();
+            fscommand::handle(fscommand, fsargs, avm, context);
+            return Ok(Value::Undefined.into());
+        }
+
         let method = match args.get(2) {
             Some(Value::String(s)) if s == "GET" => Some(NavigationMethod::GET),
             Some(Value::String(s)) if s == "POST" => Some(NavigationMethod::POST),
@@ -73,6 +83,87 @@ pub fn getURL<'a, 'gc>(
     Ok(Value::Undefined.into())
 }
 
+pub fn set_interval<'a, 'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'a, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    create_timer(avm, context, args, false)
+}
+
+pub fn set_timeout<'a, 'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'a, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    create_timer(avm, context, args, true)
+}
+
+/// Creates a timer from the arguments of `setInterval` or `setTimeout`, which take either the
+/// form `(function, interval, ...params)` or `(object, methodName, interval, ...params)`.
+fn create_timer<'a, 'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'a, 'gc, '_>,
+    args: &[Value<'gc>],
+    is_timeout: bool,
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let (callback, interval_index) = match args.get(0) {
+        Some(Value::Object(function)) if function.as_executable().is_some() => {
+            (TimerCallback::Function(*function), 1)
+        }
+        Some(Value::Object(this)) => {
+            let method_name = args
+                .get(1)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_string(avm, context)?
+                .to_string();
+            (
+                TimerCallback::Method {
+                    this: *this,
+                    method_name,
+                },
+                2,
+            )
+        }
+        _ => return Ok(Value::Undefined.into()),
+    };
+
+    let interval = match args.get(interval_index) {
+        Some(interval) => interval.coerce_to_f64(avm, context)?,
+        None => return Ok(Value::Undefined.into()),
+    };
+    let interval = if interval.is_finite() && interval > 0.0 {
+        interval.min(f64::from(u32::MAX)) as u32
+    } else {
+        0
+    };
+    let params = args.get(interval_index + 1..).unwrap_or_default().to_vec();
+
+    let cur_time = context.navigator.time_since_launch().as_millis() as u64;
+    let id = context
+        .timers
+        .add_timer(callback, params, cur_time, interval, is_timeout);
+
+    Ok(id.into())
+}
+
+pub fn clear_interval<'a, 'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'a, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let id = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_i32(avm, context)?;
+    context.timers.remove(id);
+
+    Ok(Value::Undefined.into())
+}
+
 pub fn random<'gc>(
     _avm: &mut Avm1<'gc>,
     action_context: &mut UpdateContext<'_, 'gc, '_>,
@@ -138,14 +229,17 @@ pub struct SystemPrototypes<'gc> {
     pub text_format: Object<'gc>,
     pub array: Object<'gc>,
     pub xml_node: Object<'gc>,
+    pub xml: Object<'gc>,
     pub string: Object<'gc>,
     pub number: Object<'gc>,
     pub boolean: Object<'gc>,
+    pub date: Object<'gc>,
     pub matrix: Object<'gc>,
     pub point: Object<'gc>,
     pub rectangle: Object<'gc>,
     pub rectangle_constructor: Object<'gc>,
     pub shared_object: Object<'gc>,
+    pub bitmap_data: Object<'gc>,
 }
 
 unsafe impl<'gc> gc_arena::Collect for SystemPrototypes<'gc> {
@@ -160,14 +254,17 @@ unsafe impl<'gc> gc_arena::Collect for SystemPrototypes<'gc> {
         self.text_format.trace(cc);
         self.array.trace(cc);
         self.xml_node.trace(cc);
+        self.xml.trace(cc);
         self.string.trace(cc);
         self.number.trace(cc);
         self.boolean.trace(cc);
+        self.date.trace(cc);
         self.matrix.trace(cc);
         self.point.trace(cc);
         self.rectangle.trace(cc);
         self.rectangle_constructor.trace(cc);
         self.shared_object.trace(cc);
+        self.bitmap_data.trace(cc);
     }
 }
 
@@ -207,10 +304,19 @@ pub fn create_globals<'gc>(
     let number_proto: Object<'gc> = number::create_proto(gc_context, object_proto, function_proto);
     let boolean_proto: Object<'gc> =
         boolean::create_proto(gc_context, object_proto, function_proto);
+    let date_proto: Object<'gc> = date::create_proto(gc_context, object_proto, function_proto);
+    let load_vars_proto: Object<'gc> =
+        load_vars::create_proto(gc_context, object_proto, function_proto);
     let matrix_proto: Object<'gc> = matrix::create_proto(gc_context, object_proto, function_proto);
     let point_proto: Object<'gc> = point::create_proto(gc_context, object_proto, function_proto);
     let rectangle_proto: Object<'gc> =
         rectangle::create_proto(gc_context, object_proto, function_proto);
+    let bitmap_data_proto: Object<'gc> =
+        bitmap_data::create_proto(gc_context, object_proto, function_proto);
+    let local_connection_proto: Object<'gc> =
+        local_connection::create_proto(gc_context, object_proto, function_proto);
+    let xml_socket_proto: Object<'gc> =
+        xml_socket::create_proto(gc_context, object_proto, function_proto);
 
     //TODO: These need to be constructors and should also set `.prototype` on each one
     let object = object::create_object_object(gc_context, object_proto, function_proto);
@@ -245,6 +351,24 @@ pub fn create_globals<'gc>(
         Some(function_proto),
         Some(movie_clip_loader_proto),
     );
+    let load_vars = FunctionObject::function(
+        gc_context,
+        Executable::Native(load_vars::constructor),
+        Some(function_proto),
+        Some(load_vars_proto),
+    );
+    let local_connection = FunctionObject::function(
+        gc_context,
+        Executable::Native(local_connection::constructor),
+        Some(function_proto),
+        Some(local_connection_proto),
+    );
+    let xml_socket = FunctionObject::function(
+        gc_context,
+        Executable::Native(xml_socket::constructor),
+        Some(function_proto),
+        Some(xml_socket_proto),
+    );
     let sound = FunctionObject::function(
         gc_context,
         Executable::Native(sound::constructor),
@@ -280,9 +404,11 @@ pub fn create_globals<'gc>(
     let number = number::create_number_object(gc_context, Some(number_proto), Some(function_proto));
     let boolean =
         boolean::create_boolean_object(gc_context, Some(boolean_proto), Some(function_proto));
+    let date = date::create_date_object(gc_context, Some(date_proto), Some(function_proto));
 
     let flash = ScriptObject::object(gc_context, Some(object_proto));
     let geom = ScriptObject::object(gc_context, Some(object_proto));
+    let display = ScriptObject::object(gc_context, Some(object_proto));
     let matrix = matrix::create_matrix_object(gc_context, Some(matrix_proto), Some(function_proto));
 
     let point = point::create_point_object(gc_context, Some(point_proto), Some(function_proto));
@@ -294,11 +420,30 @@ pub fn create_globals<'gc>(
     geom.define_value(gc_context, "Point", point.into(), EnumSet::empty());
     geom.define_value(gc_context, "Rectangle", rectangle.into(), EnumSet::empty());
 
+    let bitmap_data = bitmap_data::create_bitmap_data_object(
+        gc_context,
+        Some(bitmap_data_proto),
+        Some(function_proto),
+    );
+    flash.define_value(gc_context, "display", display.into(), EnumSet::empty());
+    display.define_value(
+        gc_context,
+        "BitmapData",
+        bitmap_data.into(),
+        EnumSet::empty(),
+    );
+
     let listeners = SystemListeners::new(gc_context, Some(array_proto));
 
     let mut globals = ScriptObject::bare_object(gc_context);
     globals.define_value(gc_context, "flash", flash.into(), EnumSet::empty());
     globals.define_value(gc_context, "Array", array.into(), EnumSet::empty());
+    globals.define_value(
+        gc_context,
+        "AsBroadcaster",
+        as_broadcaster::create(gc_context, Some(object_proto), Some(function_proto)).into(),
+        EnumSet::empty(),
+    );
     globals.define_value(gc_context, "Button", button.into(), EnumSet::empty());
     globals.define_value(gc_context, "Color", color.into(), EnumSet::empty());
     globals.define_value(gc_context, "Object", object.into(), EnumSet::empty());
@@ -310,6 +455,13 @@ pub fn create_globals<'gc>(
         movie_clip_loader.into(),
         EnumSet::empty(),
     );
+    globals.define_value(gc_context, "LoadVars", load_vars.into(), EnumSet::empty());
+    globals.define_value(
+        gc_context,
+        "LocalConnection",
+        local_connection.into(),
+        EnumSet::empty(),
+    );
     globals.define_value(gc_context, "Sound", sound.into(), EnumSet::empty());
     globals.define_value(gc_context, "TextField", text_field.into(), EnumSet::empty());
     globals.define_value(
@@ -320,9 +472,11 @@ pub fn create_globals<'gc>(
     );
     globals.define_value(gc_context, "XMLNode", xmlnode.into(), EnumSet::empty());
     globals.define_value(gc_context, "XML", xml.into(), EnumSet::empty());
+    globals.define_value(gc_context, "XMLSocket", xml_socket.into(), EnumSet::empty());
     globals.define_value(gc_context, "String", string.into(), EnumSet::empty());
     globals.define_value(gc_context, "Number", number.into(), EnumSet::empty());
     globals.define_value(gc_context, "Boolean", boolean.into(), EnumSet::empty());
+    globals.define_value(gc_context, "Date", date.into(), EnumSet::empty());
 
     let shared_object_proto = shared_object::create_proto(gc_context, object_proto, function_proto);
 
@@ -379,6 +533,17 @@ pub fn create_globals<'gc>(
         )),
         EnumSet::empty(),
     );
+    globals.define_value(
+        gc_context,
+        "Selection",
+        Value::Object(selection::create_selection_object(
+            gc_context,
+            Some(object_proto),
+            Some(function_proto),
+            &listeners.selection,
+        )),
+        EnumSet::empty(),
+    );
     globals.define_value(
         gc_context,
         "Key",
@@ -386,6 +551,7 @@ pub fn create_globals<'gc>(
             gc_context,
             Some(object_proto),
             Some(function_proto),
+            &listeners.key,
         )),
         EnumSet::empty(),
     );
@@ -395,7 +561,17 @@ pub fn create_globals<'gc>(
         Value::Object(stage::create_stage_object(
             gc_context,
             Some(object_proto),
-            Some(array_proto),
+            Some(function_proto),
+            &listeners.stage,
+        )),
+        EnumSet::empty(),
+    );
+    globals.define_value(
+        gc_context,
+        "ExternalInterface",
+        Value::Object(external_interface::create_external_interface_object(
+            gc_context,
+            Some(object_proto),
             Some(function_proto),
         )),
         EnumSet::empty(),
@@ -421,6 +597,34 @@ pub fn create_globals<'gc>(
         EnumSet::empty(),
         Some(function_proto),
     );
+    globals.force_set_function(
+        "setInterval",
+        set_interval,
+        gc_context,
+        EnumSet::empty(),
+        Some(function_proto),
+    );
+    globals.force_set_function(
+        "setTimeout",
+        set_timeout,
+        gc_context,
+        EnumSet::empty(),
+        Some(function_proto),
+    );
+    globals.force_set_function(
+        "clearInterval",
+        clear_interval,
+        gc_context,
+        EnumSet::empty(),
+        Some(function_proto),
+    );
+    globals.force_set_function(
+        "clearTimeout",
+        clear_interval,
+        gc_context,
+        EnumSet::empty(),
+        Some(function_proto),
+    );
     globals.force_set_function(
         "ASSetPropFlags",
         object::as_set_prop_flags,
@@ -454,14 +658,17 @@ pub fn create_globals<'gc>(
             text_format: text_format_proto,
             array: array_proto,
             xml_node: xmlnode_proto,
+            xml: xml_proto,
             string: string_proto,
             number: number_proto,
             boolean: boolean_proto,
+            date: date_proto,
             matrix: matrix_proto,
             point: point_proto,
             rectangle: rectangle_proto,
             rectangle_constructor: rectangle,
             shared_object: shared_object_proto,
+            bitmap_data: bitmap_data_proto,
         },
         globals.into(),
         listeners,
@@ -611,4 +818,60 @@ mod tests {
             [Value::Null] => std::f64::NAN
         }
     );
+
+    fn count_calls<'gc>(
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        this: Object<'gc>,
+        _args: &[Value<'gc>],
+    ) -> Result<ReturnValue<'gc>, Error<'gc>> {
+        let count = this
+            .get("count", avm, context)?
+            .coerce_to_f64(avm, context)?;
+        this.set("count", (count + 1.0).into(), avm, context)?;
+        Ok(Value::Undefined.into())
+    }
+
+    #[test]
+    fn timers() {
+        use crate::avm1::test_utils::with_avm;
+        use crate::timer::Timers;
+
+        with_avm(8, |avm, context, _root| -> Result<(), Error> {
+            let globals = avm.global_object_cell();
+            let mut object = ScriptObject::object(context.gc_context, None);
+            object.force_set_function(
+                "tick",
+                count_calls,
+                context.gc_context,
+                EnumSet::empty(),
+                None,
+            );
+            object.set("count", 0.into(), avm, context)?;
+            let object: Object = object.into();
+
+            let set_timeout = globals.get("setTimeout", avm, context)?;
+            let set_interval = globals.get("setInterval", avm, context)?;
+            let clear_interval = globals.get("clearInterval", avm, context)?;
+            let args = [object.into(), "tick".into(), 0.into()];
+            let timeout = set_timeout.call(avm, context, globals, None, &args)?;
+            let interval = set_interval.call(avm, context, globals, None, &args)?;
+            assert_eq!(timeout, 1.into());
+            assert_eq!(interval, 2.into());
+
+            // Both timers are due immediately, and fire once.
+            Timers::update_timers(avm, context);
+            assert_eq!(object.get("count", avm, context)?, 2.into());
+
+            // The timeout is gone, and the interval is not due again until time passes.
+            Timers::update_timers(avm, context);
+            assert_eq!(object.get("count", avm, context)?, 2.into());
+            assert!(!context.timers.remove(1));
+
+            clear_interval.call(avm, context, globals, None, &[interval])?;
+            assert!(!context.timers.remove(2));
+
+            Ok(())
+        });
+    }
 }
@@ -0,0 +1,212 @@
+//! `AsBroadcaster` global, which lets any object broadcast events to listeners.
+
+use crate::avm1::error::Error;
+use crate::avm1::listeners::Listeners;
+use crate::avm1::property::Attribute;
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::{Avm1, Object, ScriptObject, TObject, UpdateContext, Value};
+
+use gc_arena::MutationContext;
+
+/// The methods copied onto an object by `AsBroadcaster.initialize`.
+const BROADCASTER_METHODS: [&str; 3] = ["broadcastMessage", "addListener", "removeListener"];
+
+/// The listeners of a broadcaster, if it has been initialized.
+fn listeners<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+) -> Result<Option<Listeners<'gc>>, Error<'gc>> {
+    match this.get("_listeners", avm, context)? {
+        Value::Object(listeners) => Ok(Some(listeners.into())),
+        _ => Ok(None),
+    }
+}
+
+pub fn initialize<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let broadcaster = match args.get(0) {
+        Some(Value::Object(broadcaster)) => *broadcaster,
+        _ => return Ok(Value::Undefined.into()),
+    };
+
+    // The methods are looked up on `AsBroadcaster` itself, so that scripts
+    // can replace them.
+    for name in BROADCASTER_METHODS.iter() {
+        let method = this.get(name, avm, context)?;
+        broadcaster.define_value(context.gc_context, name, method, Attribute::DontEnum.into());
+    }
+
+    let listeners = Listeners::new(context.gc_context, Some(avm.prototypes.array));
+    broadcaster.define_value(
+        context.gc_context,
+        "_listeners",
+        listeners.object().into(),
+        Attribute::DontEnum.into(),
+    );
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn add_listener<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    match listeners(avm, context, this)? {
+        Some(listeners) => listeners.add_listener(context, args),
+        None => Ok(false.into()),
+    }
+}
+
+pub fn remove_listener<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    match listeners(avm, context, this)? {
+        Some(listeners) => listeners.remove_listener(avm, context, args),
+        None => Ok(false.into()),
+    }
+}
+
+/// Call the method named by the first argument on every listener, passing the
+/// remaining arguments.
+///
+/// Returns `true` if there were any listeners to notify.
+pub fn broadcast_message<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let method = match args.get(0) {
+        Some(method) => method.coerce_to_string(avm, context)?.to_string(),
+        None => return Ok(Value::Undefined.into()),
+    };
+
+    let listeners = match listeners(avm, context, this)? {
+        Some(listeners) => listeners,
+        None => return Ok(Value::Undefined.into()),
+    };
+
+    // Listeners added or removed by a handler don't affect this broadcast.
+    let handlers = listeners.prepare_handlers(avm, context, &method);
+    for (listener, handler) in handlers {
+        handler.call(avm, context, listener, None, &args[1..])?;
+    }
+
+    if listeners.object().length() > 0 {
+        Ok(true.into())
+    } else {
+        Ok(Value::Undefined.into())
+    }
+}
+
+pub fn create<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Option<Object<'gc>>,
+    fn_proto: Option<Object<'gc>>,
+) -> Object<'gc> {
+    let mut as_broadcaster = ScriptObject::object(gc_context, proto);
+
+    as_broadcaster.force_set_function(
+        "initialize",
+        initialize,
+        gc_context,
+        Attribute::DontEnum | Attribute::DontDelete,
+        fn_proto,
+    );
+
+    as_broadcaster.force_set_function(
+        "addListener",
+        add_listener,
+        gc_context,
+        Attribute::DontEnum | Attribute::DontDelete,
+        fn_proto,
+    );
+
+    as_broadcaster.force_set_function(
+        "removeListener",
+        remove_listener,
+        gc_context,
+        Attribute::DontEnum | Attribute::DontDelete,
+        fn_proto,
+    );
+
+    as_broadcaster.force_set_function(
+        "broadcastMessage",
+        broadcast_message,
+        gc_context,
+        Attribute::DontEnum | Attribute::DontDelete,
+        fn_proto,
+    );
+
+    as_broadcaster.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm1::function::{Executable, FunctionObject};
+    use crate::avm1::test_utils::with_avm;
+
+    fn on_test<'gc>(
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        this: Object<'gc>,
+        args: &[Value<'gc>],
+    ) -> Result<ReturnValue<'gc>, Error<'gc>> {
+        let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+        this.set("received", value, avm, context)?;
+        Ok(Value::Undefined.into())
+    }
+
+    #[test]
+    fn broadcast_to_listeners() {
+        with_avm(6, |avm, context, _this| -> Result<(), Error> {
+            let as_broadcaster = create(
+                context.gc_context,
+                Some(avm.prototypes.object),
+                Some(avm.prototypes.function),
+            );
+            let broadcaster: Object<'_> =
+                ScriptObject::object(context.gc_context, Some(avm.prototypes.object)).into();
+            initialize(avm, context, as_broadcaster, &[broadcaster.into()])?
+                .resolve(avm, context)?;
+
+            let result = broadcast_message(avm, context, broadcaster, &["onTest".into()])?;
+            assert_eq!(result.resolve(avm, context)?, Value::Undefined);
+
+            let listener: Object<'_> =
+                ScriptObject::object(context.gc_context, Some(avm.prototypes.object)).into();
+            let handler = FunctionObject::function(
+                context.gc_context,
+                Executable::Native(on_test),
+                Some(avm.prototypes.function),
+                None,
+            );
+            listener.set("onTest", handler.into(), avm, context)?;
+
+            let method = broadcaster.get("addListener", avm, context)?;
+            method.call(avm, context, broadcaster, None, &[listener.into()])?;
+            let result =
+                broadcast_message(avm, context, broadcaster, &["onTest".into(), 5.into()])?;
+            assert_eq!(result.resolve(avm, context)?, true.into());
+            assert_eq!(listener.get("received", avm, context)?, 5.into());
+
+            let result = remove_listener(avm, context, broadcaster, &[listener.into()])?;
+            assert_eq!(result.resolve(avm, context)?, true.into());
+            broadcast_message(avm, context, broadcaster, &["onTest".into(), 6.into()])?
+                .resolve(avm, context)?;
+            assert_eq!(listener.get("received", avm, context)?, 5.into());
+            Ok(())
+        })
+    }
+}
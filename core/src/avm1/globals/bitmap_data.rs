@@ -0,0 +1,756 @@
+//! flash.display.BitmapData
+
+use crate::avm1::bitmap_data_object::BitmapDataObject;
+use crate::avm1::error::Error;
+use crate::avm1::function::{Executable, FunctionObject, NativeFunction};
+use crate::avm1::globals::matrix::value_to_matrix;
+use crate::avm1::globals::point::value_to_point;
+use crate::avm1::globals::rectangle::construct_new_rectangle;
+use crate::avm1::property::Attribute::*;
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::{Avm1, Object, TObject, Value};
+use crate::bitmap_data::{self, BitmapData, PerlinNoise, PixelRect, ThresholdOperation, MAX_SIZE};
+use crate::character::Character;
+use crate::color_transform::ColorTransform;
+use crate::context::UpdateContext;
+use crate::display_object::TDisplayObject;
+use gc_arena::{GcCell, MutationContext};
+use swf::Matrix;
+
+/// Returns the pixels of a `BitmapData`, unless it has been disposed.
+fn bitmap_data_of(object: Object<'_>) -> Option<GcCell<'_, BitmapData>> {
+    object
+        .as_bitmap_data_object()
+        .map(|object| object.bitmap_data())
+        .filter(|bitmap_data| !bitmap_data.read().disposed())
+}
+
+/// Returns the pixels of a `BitmapData` argument, unless it has been disposed.
+fn arg_to_bitmap_data<'gc>(arg: Option<&Value<'gc>>) -> Option<GcCell<'gc, BitmapData>> {
+    match arg {
+        Some(Value::Object(object)) => bitmap_data_of(*object),
+        _ => None,
+    }
+}
+
+/// Calls `f` with the pixels of `target` and `source`, copying the source first if it is the
+/// same bitmap as the target.
+fn with_source<'gc, R>(
+    gc_context: MutationContext<'gc, '_>,
+    target: GcCell<'gc, BitmapData>,
+    source: GcCell<'gc, BitmapData>,
+    f: impl FnOnce(&mut BitmapData, &BitmapData) -> R,
+) -> R {
+    if GcCell::ptr_eq(target, source) {
+        let source = source.read().clone();
+        f(&mut target.write(gc_context), &source)
+    } else {
+        f(&mut target.write(gc_context), &source.read())
+    }
+}
+
+/// Reads a `Rectangle`, truncating it to whole pixels.
+fn value_to_rect<'gc>(
+    value: Value<'gc>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<PixelRect, Error<'gc>> {
+    let object = value.coerce_to_object(avm, context);
+    let x = object.get("x", avm, context)?.coerce_to_f64(avm, context)?;
+    let y = object.get("y", avm, context)?.coerce_to_f64(avm, context)?;
+    let width = object
+        .get("width", avm, context)?
+        .coerce_to_f64(avm, context)?;
+    let height = object
+        .get("height", avm, context)?
+        .coerce_to_f64(avm, context)?;
+    Ok(PixelRect::new(
+        x as i32,
+        y as i32,
+        width as i32,
+        height as i32,
+    ))
+}
+
+/// Reads a `ColorTransform`, using the identity transform for any missing property.
+fn value_to_color_transform<'gc>(
+    value: Value<'gc>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<ColorTransform, Error<'gc>> {
+    let object = value.coerce_to_object(avm, context);
+    let mut property = |name: &str, default: f64| -> Result<f32, Error<'gc>> {
+        Ok(match object.get(name, avm, context)? {
+            Value::Undefined => default,
+            value => value.coerce_to_f64(avm, context)?,
+        } as f32)
+    };
+    Ok(ColorTransform {
+        r_mult: property("redMultiplier", 1.0)?,
+        g_mult: property("greenMultiplier", 1.0)?,
+        b_mult: property("blueMultiplier", 1.0)?,
+        a_mult: property("alphaMultiplier", 1.0)?,
+        r_add: property("redOffset", 0.0)? / 255.0,
+        g_add: property("greenOffset", 0.0)? / 255.0,
+        b_add: property("blueOffset", 0.0)? / 255.0,
+        a_add: property("alphaOffset", 0.0)? / 255.0,
+    })
+}
+
+fn arg_to_u32<'gc>(
+    args: &[Value<'gc>],
+    index: usize,
+    default: u32,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<u32, Error<'gc>> {
+    match args.get(index) {
+        Some(value) => value.coerce_to_u32(avm, context),
+        None => Ok(default),
+    }
+}
+
+fn arg_to_bool(args: &[Value<'_>], index: usize, default: bool, swf_version: u8) -> bool {
+    args.get(index)
+        .map(|value| value.as_bool(swf_version))
+        .unwrap_or(default)
+}
+
+pub fn constructor<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let bitmap_data = match this.as_bitmap_data_object() {
+        Some(object) => object.bitmap_data(),
+        None => return Ok(Value::Undefined.into()),
+    };
+
+    let width = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_i32(avm, context)?;
+    let height = args
+        .get(1)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_i32(avm, context)?;
+    let transparent = arg_to_bool(args, 2, true, avm.current_swf_version());
+    let fill_color = arg_to_u32(args, 3, 0xFFFF_FFFF, avm, context)?;
+
+    let valid_size = |size: i32| size > 0 && size as u32 <= MAX_SIZE;
+    let new_bitmap_data = if valid_size(width) && valid_size(height) {
+        BitmapData::new(width as u32, height as u32, transparent, fill_color)
+    } else {
+        log::warn!("BitmapData: Invalid size {}x{}", width, height);
+        let mut bitmap_data = BitmapData::new(0, 0, transparent, 0);
+        bitmap_data.dispose();
+        bitmap_data
+    };
+    bitmap_data
+        .write(context.gc_context)
+        .replace_with(new_bitmap_data);
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn create_bitmap_data_object<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    bitmap_data_proto: Option<Object<'gc>>,
+    fn_proto: Option<Object<'gc>>,
+) -> Object<'gc> {
+    let bitmap_data = FunctionObject::function(
+        gc_context,
+        Executable::Native(constructor),
+        fn_proto,
+        bitmap_data_proto,
+    );
+    let mut object = bitmap_data.as_script_object().unwrap();
+
+    object.force_set_function(
+        "loadBitmap",
+        load_bitmap,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        fn_proto,
+    );
+
+    bitmap_data
+}
+
+/// Implements `BitmapData.loadBitmap`, which creates a `BitmapData` from a bitmap exported
+/// from the library.
+fn load_bitmap<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let export_name = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(avm, context)?;
+    let bitmap = avm
+        .base_clip()
+        .movie()
+        .and_then(|movie| context.library.library_for_movie(movie))
+        .and_then(|library| library.get_character_by_export_name(&export_name))
+        .and_then(|character| match character {
+            Character::Bitmap(bitmap) => Some(bitmap),
+            _ => None,
+        });
+
+    match bitmap.and_then(|bitmap| bitmap.pixels()) {
+        Some(pixels) => Ok(BitmapDataObject::with_bitmap_data(
+            context.gc_context,
+            Some(avm.prototypes().bitmap_data),
+            pixels,
+        )
+        .into()),
+        None => {
+            log::warn!(
+                "BitmapData.loadBitmap: No bitmap exported as '{}'",
+                export_name
+            );
+            Ok(Value::Undefined.into())
+        }
+    }
+}
+
+fn width<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    Ok(match bitmap_data_of(this) {
+        Some(bitmap_data) => bitmap_data.read().width().into(),
+        None => (-1).into(),
+    })
+}
+
+fn height<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    Ok(match bitmap_data_of(this) {
+        Some(bitmap_data) => bitmap_data.read().height().into(),
+        None => (-1).into(),
+    })
+}
+
+fn transparent<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    Ok(match bitmap_data_of(this) {
+        Some(bitmap_data) => bitmap_data.read().transparent().into(),
+        None => (-1).into(),
+    })
+}
+
+fn rectangle<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let (width, height) = match bitmap_data_of(this) {
+        Some(bitmap_data) => {
+            let bitmap_data = bitmap_data.read();
+            (bitmap_data.width(), bitmap_data.height())
+        }
+        None => return Ok((-1).into()),
+    };
+    let args = [0.into(), 0.into(), width.into(), height.into()];
+    Ok(construct_new_rectangle(&args, avm, context)?.into())
+}
+
+/// Reads the `x` and `y` arguments of the pixel methods.
+fn pixel_position<'gc>(
+    args: &[Value<'gc>],
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<(i32, i32), Error<'gc>> {
+    let x = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_i32(avm, context)?;
+    let y = args
+        .get(1)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_i32(avm, context)?;
+    Ok((x, y))
+}
+
+fn get_pixel<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let Some(bitmap_data) = bitmap_data_of(this) {
+        let (x, y) = pixel_position(args, avm, context)?;
+        return Ok(bitmap_data.read().get_pixel(x, y).into());
+    }
+    Ok((-1).into())
+}
+
+fn get_pixel32<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let Some(bitmap_data) = bitmap_data_of(this) {
+        let (x, y) = pixel_position(args, avm, context)?;
+        // Flash returns the ARGB value as a signed 32-bit integer.
+        return Ok((bitmap_data.read().get_pixel32(x, y) as i32).into());
+    }
+    Ok((-1).into())
+}
+
+fn set_pixel<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let Some(bitmap_data) = bitmap_data_of(this) {
+        let (x, y) = pixel_position(args, avm, context)?;
+        let color = arg_to_u32(args, 2, 0, avm, context)?;
+        bitmap_data.write(context.gc_context).set_pixel(x, y, color);
+    }
+    Ok(Value::Undefined.into())
+}
+
+fn set_pixel32<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let Some(bitmap_data) = bitmap_data_of(this) {
+        let (x, y) = pixel_position(args, avm, context)?;
+        let color = arg_to_u32(args, 2, 0, avm, context)?;
+        bitmap_data
+            .write(context.gc_context)
+            .set_pixel32(x, y, color);
+    }
+    Ok(Value::Undefined.into())
+}
+
+fn fill_rect<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let (Some(bitmap_data), Some(rect)) = (bitmap_data_of(this), args.get(0)) {
+        let rect = value_to_rect(rect.clone(), avm, context)?;
+        let color = arg_to_u32(args, 1, 0, avm, context)?;
+        bitmap_data.write(context.gc_context).fill_rect(rect, color);
+    }
+    Ok(Value::Undefined.into())
+}
+
+fn copy_pixels<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let (bitmap_data, source) = match (bitmap_data_of(this), arg_to_bitmap_data(args.get(0))) {
+        (Some(bitmap_data), Some(source)) => (bitmap_data, source),
+        _ => return Ok(Value::Undefined.into()),
+    };
+    let source_rect = value_to_rect(
+        args.get(1).unwrap_or(&Value::Undefined).clone(),
+        avm,
+        context,
+    )?;
+    let (dest_x, dest_y) = value_to_point(
+        args.get(2).unwrap_or(&Value::Undefined).clone(),
+        avm,
+        context,
+    )?;
+    // The alpha bitmap is copied, in case it is the bitmap being written to.
+    let alpha_source = arg_to_bitmap_data(args.get(3)).map(|alpha| alpha.read().clone());
+    let alpha_point = match args.get(4) {
+        Some(point @ Value::Object(_)) => value_to_point(point.clone(), avm, context)?,
+        _ => (0.0, 0.0),
+    };
+    let merge_alpha = arg_to_bool(args, 5, false, avm.current_swf_version());
+
+    with_source(context.gc_context, bitmap_data, source, |target, source| {
+        target.copy_pixels(
+            source,
+            source_rect,
+            (dest_x as i32, dest_y as i32),
+            alpha_source
+                .as_ref()
+                .map(|alpha| (alpha, (alpha_point.0 as i32, alpha_point.1 as i32))),
+            merge_alpha,
+        )
+    });
+    Ok(Value::Undefined.into())
+}
+
+fn draw<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let (bitmap_data, source) = match (bitmap_data_of(this), args.get(0)) {
+        (Some(bitmap_data), Some(Value::Object(source))) => (bitmap_data, *source),
+        _ => return Ok(Value::Undefined.into()),
+    };
+    let matrix = match args.get(1) {
+        Some(matrix @ Value::Object(_)) => value_to_matrix(matrix.clone(), avm, context)?,
+        _ => Matrix::identity(),
+    };
+    let color_transform = match args.get(2) {
+        Some(color_transform @ Value::Object(_)) => {
+            value_to_color_transform(color_transform.clone(), avm, context)?
+        }
+        _ => ColorTransform::default(),
+    };
+    // TODO: The blend mode (argument 3) is ignored.
+    if let Some(Value::String(blend_mode)) = args.get(3) {
+        if blend_mode.as_str() != "normal" {
+            log::warn!("BitmapData.draw: Blend mode {} is not applied", blend_mode);
+        }
+    }
+    let clip = match args.get(4) {
+        Some(rect @ Value::Object(_)) => Some(value_to_rect(rect.clone(), avm, context)?),
+        _ => None,
+    };
+    let smoothing = arg_to_bool(args, 5, false, avm.current_swf_version());
+
+    if let Some(source) = bitmap_data_of(source) {
+        with_source(context.gc_context, bitmap_data, source, |target, source| {
+            bitmap_data::draw_bitmap_data(target, source, &matrix, color_transform, clip, smoothing)
+        });
+    } else if let Some(display_object) = source.as_display_object() {
+        let requester = avm.base_clip().movie();
+        let movie = display_object.movie();
+        if !context.policies.can_access_movie(
+            requester.as_ref().and_then(|movie| movie.url()),
+            movie.as_ref().and_then(|movie| movie.url()),
+        ) {
+            log::warn!(
+                "BitmapData.draw: Access to {} is denied",
+                movie.as_ref().and_then(|movie| movie.url()).unwrap_or("")
+            );
+            return Ok(Value::Undefined.into());
+        }
+
+        // The display object may show this bitmap, so it is drawn into a copy.
+        let mut pixels = bitmap_data.read().clone();
+        bitmap_data::draw_display_object(
+            &mut pixels,
+            display_object,
+            &matrix,
+            color_transform,
+            clip,
+            context.library,
+        );
+        bitmap_data.write(context.gc_context).replace_with(pixels);
+    }
+    Ok(Value::Undefined.into())
+}
+
+fn color_transform<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let (Some(bitmap_data), Some(rect), Some(color_transform)) =
+        (bitmap_data_of(this), args.get(0), args.get(1))
+    {
+        let rect = value_to_rect(rect.clone(), avm, context)?;
+        let color_transform = value_to_color_transform(color_transform.clone(), avm, context)?;
+        bitmap_data
+            .write(context.gc_context)
+            .color_transform(rect, &color_transform);
+    }
+    Ok(Value::Undefined.into())
+}
+
+fn noise<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let Some(bitmap_data) = bitmap_data_of(this) {
+        let seed = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_i32(avm, context)?;
+        let low = arg_to_u32(args, 1, 0, avm, context)?.min(255) as u8;
+        let high = arg_to_u32(args, 2, 255, avm, context)?.min(255) as u8;
+        let channels = arg_to_u32(args, 3, 7, avm, context)? as u8;
+        let gray_scale = arg_to_bool(args, 4, false, avm.current_swf_version());
+        bitmap_data
+            .write(context.gc_context)
+            .noise(seed, low, high, channels, gray_scale);
+    }
+    Ok(Value::Undefined.into())
+}
+
+fn perlin_noise<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let bitmap_data = match bitmap_data_of(this) {
+        Some(bitmap_data) => bitmap_data,
+        None => return Ok(Value::Undefined.into()),
+    };
+    let mut numbers = [0.0; 4];
+    for (i, number) in numbers.iter_mut().enumerate() {
+        *number = args
+            .get(i)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_f64(avm, context)?;
+    }
+    let swf_version = avm.current_swf_version();
+    let mut offsets = Vec::new();
+    if let Some(Value::Object(array)) = args.get(8) {
+        for offset in array.array() {
+            offsets.push(value_to_point(offset, avm, context)?);
+        }
+    }
+
+    let options = PerlinNoise {
+        base_x: numbers[0],
+        base_y: numbers[1],
+        num_octaves: numbers[2].max(0.0) as usize,
+        seed: numbers[3] as i32,
+        stitch: arg_to_bool(args, 4, false, swf_version),
+        fractal_noise: arg_to_bool(args, 5, false, swf_version),
+        channels: arg_to_u32(args, 6, 7, avm, context)? as u8,
+        gray_scale: arg_to_bool(args, 7, false, swf_version),
+        offsets,
+    };
+    bitmap_data.write(context.gc_context).perlin_noise(&options);
+    Ok(Value::Undefined.into())
+}
+
+fn threshold<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let (bitmap_data, source) = match (bitmap_data_of(this), arg_to_bitmap_data(args.get(0))) {
+        (Some(bitmap_data), Some(source)) => (bitmap_data, source),
+        _ => return Ok(0.into()),
+    };
+    let source_rect = value_to_rect(
+        args.get(1).unwrap_or(&Value::Undefined).clone(),
+        avm,
+        context,
+    )?;
+    let (dest_x, dest_y) = value_to_point(
+        args.get(2).unwrap_or(&Value::Undefined).clone(),
+        avm,
+        context,
+    )?;
+    let operation = args
+        .get(3)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(avm, context)?;
+    let operation = match ThresholdOperation::from_str(&operation) {
+        Some(operation) => operation,
+        None => return Ok(0.into()),
+    };
+    let threshold = arg_to_u32(args, 4, 0, avm, context)?;
+    let color = arg_to_u32(args, 5, 0, avm, context)?;
+    let mask = arg_to_u32(args, 6, 0xFFFF_FFFF, avm, context)?;
+    let copy_source = arg_to_bool(args, 7, false, avm.current_swf_version());
+
+    let count = with_source(context.gc_context, bitmap_data, source, |target, source| {
+        target.threshold(
+            source,
+            source_rect,
+            (dest_x as i32, dest_y as i32),
+            operation,
+            threshold,
+            color,
+            mask,
+            copy_source,
+        )
+    });
+    Ok(count.into())
+}
+
+fn flood_fill<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let Some(bitmap_data) = bitmap_data_of(this) {
+        let (x, y) = pixel_position(args, avm, context)?;
+        let color = arg_to_u32(args, 2, 0, avm, context)?;
+        bitmap_data
+            .write(context.gc_context)
+            .flood_fill(x, y, color);
+    }
+    Ok(Value::Undefined.into())
+}
+
+fn clone<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let Some(bitmap_data) = bitmap_data_of(this) {
+        let pixels = bitmap_data.read().clone();
+        return Ok(BitmapDataObject::with_bitmap_data(
+            context.gc_context,
+            Some(avm.prototypes().bitmap_data),
+            pixels,
+        )
+        .into());
+    }
+    Ok(Value::Undefined.into())
+}
+
+fn dispose<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let Some(bitmap_data) = bitmap_data_of(this) {
+        bitmap_data.write(context.gc_context).dispose();
+    }
+    Ok(Value::Undefined.into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let bitmap_data_proto =
+        BitmapDataObject::with_bitmap_data(gc_context, Some(proto), BitmapData::new(0, 0, true, 0));
+    let mut object = bitmap_data_proto.as_script_object().unwrap();
+
+    let properties: &[(&str, NativeFunction<'gc>)] = &[
+        ("width", width),
+        ("height", height),
+        ("transparent", transparent),
+        ("rectangle", rectangle),
+    ];
+    for (name, getter) in properties {
+        object.add_property(
+            gc_context,
+            name,
+            Executable::Native(*getter),
+            None,
+            DontDelete | ReadOnly | DontEnum,
+        );
+    }
+
+    let methods: &[(&str, NativeFunction<'gc>)] = &[
+        ("getPixel", get_pixel),
+        ("getPixel32", get_pixel32),
+        ("setPixel", set_pixel),
+        ("setPixel32", set_pixel32),
+        ("fillRect", fill_rect),
+        ("copyPixels", copy_pixels),
+        ("draw", draw),
+        ("colorTransform", color_transform),
+        ("noise", noise),
+        ("perlinNoise", perlin_noise),
+        ("threshold", threshold),
+        ("floodFill", flood_fill),
+        ("clone", clone),
+        ("dispose", dispose),
+    ];
+    for (name, function) in methods {
+        object.force_set_function(
+            name,
+            *function,
+            gc_context,
+            DontDelete | ReadOnly | DontEnum,
+            Some(fn_proto),
+        );
+    }
+
+    bitmap_data_proto.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm1::test_utils::with_avm;
+
+    fn new_bitmap_data<'gc>(
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        args: &[Value<'gc>],
+    ) -> Result<Object<'gc>, Error<'gc>> {
+        let proto = avm.prototypes().bitmap_data;
+        let object = proto.new(avm, context, proto, args)?;
+        constructor(avm, context, object, args)?.resolve(avm, context)?;
+        Ok(object)
+    }
+
+    #[test]
+    fn pixels_and_dispose() {
+        with_avm(8, |avm, context, _root| -> Result<(), Error> {
+            let object = new_bitmap_data(avm, context, &[2.into(), 3.into(), false.into()])?;
+            assert_eq!(object.get("width", avm, context)?, 2.into());
+            assert_eq!(object.get("height", avm, context)?, 3.into());
+            assert_eq!(object.get("transparent", avm, context)?, false.into());
+
+            let args = [1.into(), 1.into(), 0x0012_3456.into()];
+            set_pixel32(avm, context, object, &args)?.resolve(avm, context)?;
+            let result = get_pixel32(avm, context, object, &[1.into(), 1.into()])?;
+            assert_eq!(result.resolve(avm, context)?, (-15_584_170).into());
+            let result = get_pixel(avm, context, object, &[0.into(), 0.into()])?;
+            assert_eq!(result.resolve(avm, context)?, 0x00FF_FFFF.into());
+
+            dispose(avm, context, object, &[])?.resolve(avm, context)?;
+            assert_eq!(object.get("width", avm, context)?, (-1).into());
+            let result = get_pixel32(avm, context, object, &[1.into(), 1.into()])?;
+            assert_eq!(result.resolve(avm, context)?, (-1).into());
+
+            let object = new_bitmap_data(avm, context, &[0.into(), 3.into()])?;
+            assert_eq!(object.get("width", avm, context)?, (-1).into());
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn draw_bitmap_data() {
+        with_avm(8, |avm, context, _root| -> Result<(), Error> {
+            let target =
+                new_bitmap_data(avm, context, &[4.into(), 4.into(), true.into(), 0.into()])?;
+            let source = new_bitmap_data(
+                avm,
+                context,
+                &[2.into(), 2.into(), true.into(), 0xFFFF_0000u32.into()],
+            )?;
+            draw(avm, context, target, &[source.into()])?.resolve(avm, context)?;
+            let result = get_pixel32(avm, context, target, &[1.into(), 1.into()])?;
+            assert_eq!(result.resolve(avm, context)?, (-65_536).into());
+            let result = get_pixel32(avm, context, target, &[2.into(), 2.into()])?;
+            assert_eq!(result.resolve(avm, context)?, 0.into());
+            Ok(())
+        })
+    }
+}
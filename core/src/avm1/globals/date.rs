@@ -0,0 +1,1072 @@
+//! `Date` class impl
+
+use crate::avm1::date_object::DateObject;
+use crate::avm1::error::Error;
+use crate::avm1::function::{Executable, FunctionObject, NativeFunction};
+use crate::avm1::property::Attribute::*;
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::{Avm1, Object, TObject, Value};
+use crate::context::UpdateContext;
+use gc_arena::MutationContext;
+
+const MS_PER_SECOND: f64 = 1000.0;
+const MS_PER_MINUTE: f64 = 60_000.0;
+const MS_PER_HOUR: f64 = 3_600_000.0;
+const MS_PER_DAY: f64 = 86_400_000.0;
+
+/// The largest time value that can be held by a `Date`, in milliseconds.
+const MAX_TIME: f64 = 8.64e15;
+
+/// The number of days before the first day of each month, in a non-leap year.
+const DAYS_BEFORE_MONTH: [f64; 12] = [
+    0.0, 31.0, 59.0, 90.0, 120.0, 151.0, 181.0, 212.0, 243.0, 273.0, 304.0, 334.0,
+];
+
+const DAY_NAMES: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+const MONTH_NAMES: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// The fields of a date, in the order they are accepted by the `Date` constructor
+/// and setters.
+#[derive(Clone, Copy)]
+enum DateField {
+    Year = 0,
+    Month = 1,
+    Date = 2,
+    Hours = 3,
+    Minutes = 4,
+    Seconds = 5,
+    Milliseconds = 6,
+}
+
+/// A time value broken down into its date fields.
+type DateFields = [f64; 7];
+
+fn day(time: f64) -> f64 {
+    (time / MS_PER_DAY).floor()
+}
+
+fn days_in_year(year: f64) -> f64 {
+    if year % 4.0 != 0.0 {
+        365.0
+    } else if year % 100.0 != 0.0 || year % 400.0 == 0.0 {
+        366.0
+    } else {
+        365.0
+    }
+}
+
+/// The day number of the first day of `year`.
+fn day_from_year(year: f64) -> f64 {
+    365.0 * (year - 1970.0) + ((year - 1969.0) / 4.0).floor() - ((year - 1901.0) / 100.0).floor()
+        + ((year - 1601.0) / 400.0).floor()
+}
+
+fn year_from_time(time: f64) -> f64 {
+    let day = day(time);
+    let mut year = (day / 365.2425).floor() + 1970.0;
+    while day_from_year(year) > day {
+        year -= 1.0;
+    }
+    while day_from_year(year + 1.0) <= day {
+        year += 1.0;
+    }
+    year
+}
+
+/// The number of days before the first day of `month` in `year`.
+fn days_before_month(year: f64, month: usize) -> f64 {
+    let leap_day = if month >= 2 && days_in_year(year) == 366.0 {
+        1.0
+    } else {
+        0.0
+    };
+    DAYS_BEFORE_MONTH[month] + leap_day
+}
+
+/// Splits a time value into its fields. The time value must be finite.
+fn split_time(time: f64) -> DateFields {
+    let year = year_from_time(time);
+    let day_in_year = day(time) - day_from_year(year);
+    let month = (1..12)
+        .take_while(|&month| days_before_month(year, month) <= day_in_year)
+        .last()
+        .unwrap_or(0);
+    let date = day_in_year - days_before_month(year, month) + 1.0;
+    let time_in_day = time.rem_euclid(MS_PER_DAY);
+
+    [
+        year,
+        month as f64,
+        date,
+        (time_in_day / MS_PER_HOUR).floor(),
+        (time_in_day / MS_PER_MINUTE).floor() % 60.0,
+        (time_in_day / MS_PER_SECOND).floor() % 60.0,
+        time_in_day % MS_PER_SECOND,
+    ]
+}
+
+/// Builds a time value from its fields, allowing any field to overflow into the next.
+fn make_time(fields: &DateFields) -> f64 {
+    if fields.iter().any(|field| !field.is_finite()) {
+        return f64::NAN;
+    }
+    let mut fields = *fields;
+    for field in fields.iter_mut() {
+        *field = field.trunc();
+    }
+    let [year, month, date, hours, minutes, seconds, milliseconds] = fields;
+
+    let year = year + (month / 12.0).floor();
+    let month = month.rem_euclid(12.0) as usize;
+    let day = day_from_year(year) + days_before_month(year, month) + date - 1.0;
+    let time =
+        hours * MS_PER_HOUR + minutes * MS_PER_MINUTE + seconds * MS_PER_SECOND + milliseconds;
+
+    day * MS_PER_DAY + time
+}
+
+/// Limits a time value to the range that can be held by a `Date`.
+fn time_clip(time: f64) -> f64 {
+    if time.is_finite() && time.abs() <= MAX_TIME {
+        time.trunc() + 0.0
+    } else {
+        f64::NAN
+    }
+}
+
+/// The offset of local time from UTC at the given UTC time, in milliseconds.
+fn local_offset(context: &mut UpdateContext<'_, '_, '_>, time: f64) -> f64 {
+    f64::from(context.navigator.local_timezone_offset(time)) * MS_PER_MINUTE
+}
+
+fn local_time(context: &mut UpdateContext<'_, '_, '_>, time: f64) -> f64 {
+    time + local_offset(context, time)
+}
+
+fn utc_time(context: &mut UpdateContext<'_, '_, '_>, time: f64) -> f64 {
+    // The offset is looked up at the UTC time, which we only know approximately until we
+    // know the offset. This guess is only wrong within an hour of a daylight saving change.
+    let guess = time - local_offset(context, time);
+    time - local_offset(context, guess)
+}
+
+/// Two digit years passed to the constructor and `Date.UTC` are taken to be in the 1900s.
+fn full_year(year: f64) -> f64 {
+    let integer_year = year.trunc();
+    if (0.0..=99.0).contains(&integer_year) {
+        1900.0 + integer_year
+    } else {
+        year
+    }
+}
+
+/// Reads the date fields passed as arguments to the constructor or `Date.UTC`.
+///
+/// Missing fields default to the first day of the month at midnight.
+fn fields_from_args<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<DateFields, Error<'gc>> {
+    let mut fields = [0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0];
+    for (field, arg) in fields.iter_mut().zip(args) {
+        *field = arg.coerce_to_f64(avm, context)?;
+    }
+    fields[DateField::Year as usize] = full_year(fields[DateField::Year as usize]);
+    Ok(fields)
+}
+
+/// `Date` constructor/function
+pub fn constructor<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let date = match this.as_date_object() {
+        Some(date) => date,
+        None => {
+            // Calling `Date` as a function returns the current time as a string.
+            let now = context.navigator.current_time();
+            return Ok(date_to_string(context, time_clip(now)).into());
+        }
+    };
+
+    let date_time = match args {
+        [] => context.navigator.current_time(),
+        [time] => time.coerce_to_f64(avm, context)?,
+        _ => {
+            let fields = fields_from_args(avm, context, args)?;
+            utc_time(context, make_time(&fields))
+        }
+    };
+    date.set_date_time(context.gc_context, time_clip(date_time));
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn create_date_object<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    date_proto: Option<Object<'gc>>,
+    fn_proto: Option<Object<'gc>>,
+) -> Object<'gc> {
+    let date = FunctionObject::function(
+        gc_context,
+        Executable::Native(constructor),
+        fn_proto,
+        date_proto,
+    );
+    let mut object = date.as_script_object().unwrap();
+
+    object.force_set_function(
+        "UTC",
+        utc,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        fn_proto,
+    );
+
+    date
+}
+
+/// Implements `Date.UTC`
+fn utc<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if args.len() < 2 {
+        return Ok(f64::NAN.into());
+    }
+    let fields = fields_from_args(avm, context, args)?;
+    Ok(time_clip(make_time(&fields)).into())
+}
+
+/// Formats a time value the way Flash does, e.g. `Tue Feb 1 00:00:00 GMT+0000 2000`.
+fn date_to_string(context: &mut UpdateContext<'_, '_, '_>, time: f64) -> String {
+    if time.is_nan() {
+        return "Invalid Date".to_string();
+    }
+
+    let offset = local_offset(context, time);
+    let [year, month, date, hours, minutes, seconds, _] = split_time(time + offset);
+    let week_day = (day(time + offset) + 4.0).rem_euclid(7.0) as usize;
+    let offset_minutes = (offset / MS_PER_MINUTE) as i32;
+
+    format!(
+        "{} {} {} {:02}:{:02}:{:02} GMT{}{:02}{:02} {}",
+        DAY_NAMES[week_day],
+        MONTH_NAMES[month as usize],
+        date,
+        hours,
+        minutes,
+        seconds,
+        if offset_minutes < 0 { '-' } else { '+' },
+        offset_minutes.abs() / 60,
+        offset_minutes.abs() % 60,
+        year
+    )
+}
+
+fn to_string<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let Some(date) = this.as_date_object() {
+        return Ok(date_to_string(context, date.date_time()).into());
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+fn get_time<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let Some(date) = this.as_date_object() {
+        return Ok(date.date_time().into());
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+fn set_time<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let Some(date) = this.as_date_object() {
+        let time = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_f64(avm, context)?;
+        let time = time_clip(time);
+        date.set_date_time(context.gc_context, time);
+        return Ok(time.into());
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+fn get_timezone_offset<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let Some(date) = this.as_date_object() {
+        let time = date.date_time();
+        if time.is_nan() {
+            return Ok(f64::NAN.into());
+        }
+        // The offset is reported as the difference of UTC from local time.
+        let offset = context.navigator.local_timezone_offset(time);
+        return Ok(f64::from(-offset).into());
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+/// Reads one field of a date, in either local time or UTC.
+fn get_field<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    field: DateField,
+    is_utc: bool,
+) -> Value<'gc> {
+    if let Some(date) = this.as_date_object() {
+        let mut time = date.date_time();
+        if time.is_nan() {
+            return f64::NAN.into();
+        }
+        if !is_utc {
+            time = local_time(context, time);
+        }
+        split_time(time)[field as usize].into()
+    } else {
+        Value::Undefined
+    }
+}
+
+/// Reads the day of the week of a date, in either local time or UTC.
+fn get_week_day<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    is_utc: bool,
+) -> Value<'gc> {
+    if let Some(date) = this.as_date_object() {
+        let mut time = date.date_time();
+        if time.is_nan() {
+            return f64::NAN.into();
+        }
+        if !is_utc {
+            time = local_time(context, time);
+        }
+        (day(time) + 4.0).rem_euclid(7.0).into()
+    } else {
+        Value::Undefined
+    }
+}
+
+/// Changes the fields of a date from the given arguments, starting with `first_field`.
+///
+/// At most `max_fields` fields are changed, and the remaining fields keep their values.
+/// Returns the new time value of the date.
+fn set_fields<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+    first_field: DateField,
+    max_fields: usize,
+    is_utc: bool,
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let date = match this.as_date_object() {
+        Some(date) => date,
+        None => return Ok(Value::Undefined.into()),
+    };
+
+    let mut time = date.date_time();
+    if time.is_nan() {
+        if let DateField::Year = first_field {
+            // Setting the year of an invalid date starts from the epoch.
+            time = 0.0;
+        } else {
+            return Ok(f64::NAN.into());
+        }
+    } else if !is_utc {
+        time = local_time(context, time);
+    }
+
+    let mut fields = split_time(time);
+    let first_field = first_field as usize;
+    for i in 0..max_fields {
+        fields[first_field + i] = match args.get(i) {
+            Some(arg) => arg.coerce_to_f64(avm, context)?,
+            None if i == 0 => f64::NAN,
+            None => break,
+        };
+    }
+
+    let mut time = make_time(&fields);
+    if !is_utc {
+        time = utc_time(context, time);
+    }
+    let time = time_clip(time);
+    date.set_date_time(context.gc_context, time);
+
+    Ok(time.into())
+}
+
+macro_rules! date_getters {
+    ($($name:ident => $field:ident, $is_utc:expr;)*) => {
+        $(
+            fn $name<'gc>(
+                _avm: &mut Avm1<'gc>,
+                context: &mut UpdateContext<'_, 'gc, '_>,
+                this: Object<'gc>,
+                _args: &[Value<'gc>],
+            ) -> Result<ReturnValue<'gc>, Error<'gc>> {
+                Ok(get_field(context, this, DateField::$field, $is_utc).into())
+            }
+        )*
+    };
+}
+
+date_getters! {
+    get_full_year => Year, false;
+    get_month => Month, false;
+    get_date => Date, false;
+    get_hours => Hours, false;
+    get_minutes => Minutes, false;
+    get_seconds => Seconds, false;
+    get_milliseconds => Milliseconds, false;
+    get_utc_full_year => Year, true;
+    get_utc_month => Month, true;
+    get_utc_date => Date, true;
+    get_utc_hours => Hours, true;
+    get_utc_minutes => Minutes, true;
+    get_utc_seconds => Seconds, true;
+    get_utc_milliseconds => Milliseconds, true;
+}
+
+macro_rules! date_setters {
+    ($($name:ident => $field:ident, $max_fields:expr, $is_utc:expr;)*) => {
+        $(
+            fn $name<'gc>(
+                avm: &mut Avm1<'gc>,
+                context: &mut UpdateContext<'_, 'gc, '_>,
+                this: Object<'gc>,
+                args: &[Value<'gc>],
+            ) -> Result<ReturnValue<'gc>, Error<'gc>> {
+                set_fields(avm, context, this, args, DateField::$field, $max_fields, $is_utc)
+            }
+        )*
+    };
+}
+
+date_setters! {
+    set_full_year => Year, 3, false;
+    set_month => Month, 2, false;
+    set_date => Date, 1, false;
+    set_hours => Hours, 4, false;
+    set_minutes => Minutes, 3, false;
+    set_seconds => Seconds, 2, false;
+    set_milliseconds => Milliseconds, 1, false;
+    set_utc_full_year => Year, 3, true;
+    set_utc_month => Month, 2, true;
+    set_utc_date => Date, 1, true;
+    set_utc_hours => Hours, 4, true;
+    set_utc_minutes => Minutes, 3, true;
+    set_utc_seconds => Seconds, 2, true;
+    set_utc_milliseconds => Milliseconds, 1, true;
+}
+
+fn get_day<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    Ok(get_week_day(context, this, false).into())
+}
+
+fn get_utc_day<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    Ok(get_week_day(context, this, true).into())
+}
+
+fn get_year<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let year = get_field(context, this, DateField::Year, false);
+    if let Value::Number(year) = year {
+        return Ok((year - 1900.0).into());
+    }
+
+    Ok(year.into())
+}
+
+fn get_utc_year<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let year = get_field(context, this, DateField::Year, true);
+    if let Value::Number(year) = year {
+        return Ok((year - 1900.0).into());
+    }
+
+    Ok(year.into())
+}
+
+/// Implements `Date.setYear`, which also takes two digit years to be in the 1900s.
+fn set_year<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let year = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_f64(avm, context)?;
+    let year = full_year(year).into();
+    set_fields(avm, context, this, &[year], DateField::Year, 1, false)
+}
+
+/// Creates `Date.prototype`.
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let date_proto = DateObject::with_date_time(gc_context, Some(proto), f64::NAN);
+    let mut object = date_proto.as_script_object().unwrap();
+
+    let methods: &[(&str, NativeFunction<'gc>)] = &[
+        ("toString", to_string),
+        ("valueOf", get_time),
+        ("getTime", get_time),
+        ("setTime", set_time),
+        ("getTimezoneOffset", get_timezone_offset),
+        ("getFullYear", get_full_year),
+        ("getYear", get_year),
+        ("getMonth", get_month),
+        ("getDate", get_date),
+        ("getDay", get_day),
+        ("getHours", get_hours),
+        ("getMinutes", get_minutes),
+        ("getSeconds", get_seconds),
+        ("getMilliseconds", get_milliseconds),
+        ("getUTCFullYear", get_utc_full_year),
+        ("getUTCYear", get_utc_year),
+        ("getUTCMonth", get_utc_month),
+        ("getUTCDate", get_utc_date),
+        ("getUTCDay", get_utc_day),
+        ("getUTCHours", get_utc_hours),
+        ("getUTCMinutes", get_utc_minutes),
+        ("getUTCSeconds", get_utc_seconds),
+        ("getUTCMilliseconds", get_utc_milliseconds),
+        ("setFullYear", set_full_year),
+        ("setYear", set_year),
+        ("setMonth", set_month),
+        ("setDate", set_date),
+        ("setHours", set_hours),
+        ("setMinutes", set_minutes),
+        ("setSeconds", set_seconds),
+        ("setMilliseconds", set_milliseconds),
+        ("setUTCFullYear", set_utc_full_year),
+        ("setUTCMonth", set_utc_month),
+        ("setUTCDate", set_utc_date),
+        ("setUTCHours", set_utc_hours),
+        ("setUTCMinutes", set_utc_minutes),
+        ("setUTCSeconds", set_utc_seconds),
+        ("setUTCMilliseconds", set_utc_milliseconds),
+    ];
+    for (name, function) in methods {
+        object.force_set_function(
+            name,
+            *function,
+            gc_context,
+            DontDelete | ReadOnly | DontEnum,
+            Some(fn_proto),
+        );
+    }
+
+    date_proto.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm1::test_utils::{with_avm, with_avm_and_navigator};
+    use crate::backend::navigator::{
+        NavigationMethod, NavigatorBackend, NullNavigatorBackend, OwnedFuture, RequestOptions,
+        Response,
+    };
+    use crate::loader;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    /// Tue Feb 1 03:04:05.006 2000 UTC
+    const TEST_TIME: f64 = 949374245006.0;
+
+    /// A navigator whose clock is stopped at `TEST_TIME`, in a timezone with a fixed offset
+    /// from UTC.
+    struct FixedTimeNavigator {
+        inner: NullNavigatorBackend,
+
+        /// The offset of local time from UTC, in minutes.
+        offset: i32,
+    }
+
+    impl FixedTimeNavigator {
+        fn new(offset: i32) -> Self {
+            Self {
+                inner: NullNavigatorBackend::new(),
+                offset,
+            }
+        }
+    }
+
+    impl NavigatorBackend for FixedTimeNavigator {
+        fn navigate_to_url(
+            &self,
+            url: String,
+            window: Option<String>,
+            vars_method: Option<(NavigationMethod, HashMap<String, String>)>,
+        ) {
+            self.inner.navigate_to_url(url, window, vars_method)
+        }
+
+        fn fetch(
+            &self,
+            url: &str,
+            options: RequestOptions,
+        ) -> OwnedFuture<Response, loader::Error> {
+            self.inner.fetch(url, options)
+        }
+
+        fn time_since_launch(&mut self) -> Duration {
+            self.inner.time_since_launch()
+        }
+
+        fn current_time(&self) -> f64 {
+            TEST_TIME
+        }
+
+        fn local_timezone_offset(&self, _time: f64) -> i32 {
+            self.offset
+        }
+
+        fn spawn_future(&mut self, future: OwnedFuture<(), loader::Error>) {
+            self.inner.spawn_future(future)
+        }
+    }
+
+    /// Calls methods of a date at `TEST_TIME`, with local time `offset` minutes ahead of
+    /// UTC, and checks their results. Each call is made on a new date.
+    fn check_methods(offset: i32, cases: &[(&str, &[f64], f64)]) {
+        let mut navigator = FixedTimeNavigator::new(offset);
+        with_avm_and_navigator(
+            7,
+            &mut navigator,
+            |avm, context, _root| -> Result<(), Error> {
+                for (name, args, expected) in cases {
+                    let date = setup(avm, context);
+                    let args: Vec<Value> = args.iter().map(|arg| (*arg).into()).collect();
+                    let function = date.get(name, avm, context)?;
+                    assert_eq!(
+                        function.call(avm, context, date, None, &args)?,
+                        (*expected).into(),
+                        "{}({:?}) at offset {}",
+                        name,
+                        args,
+                        offset
+                    );
+                }
+                Ok(())
+            },
+        );
+    }
+
+    fn setup<'gc>(avm: &mut Avm1<'gc>, context: &mut UpdateContext<'_, 'gc, '_>) -> Object<'gc> {
+        DateObject::with_date_time(context.gc_context, Some(avm.prototypes().date), TEST_TIME)
+            .into()
+    }
+
+    fn setup_invalid<'gc>(
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Object<'gc> {
+        DateObject::with_date_time(context.gc_context, Some(avm.prototypes().date), f64::NAN).into()
+    }
+
+    fn setup_class<'gc>(
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Object<'gc> {
+        create_date_object(
+            context.gc_context,
+            Some(avm.prototypes().date),
+            Some(avm.prototypes().function),
+        )
+    }
+
+    test_method!(test_get_time, "getTime", setup,
+        [6, 7] => {
+            [] => TEST_TIME
+        }
+    );
+
+    test_method!(test_get_full_year, "getFullYear", setup,
+        [6, 7] => {
+            [] => 2000.0
+        }
+    );
+
+    test_method!(test_get_year, "getYear", setup,
+        [6, 7] => {
+            [] => 100.0
+        }
+    );
+
+    test_method!(test_get_month, "getMonth", setup,
+        [6, 7] => {
+            [] => 1.0
+        }
+    );
+
+    test_method!(test_get_date, "getDate", setup,
+        [6, 7] => {
+            [] => 1.0
+        }
+    );
+
+    test_method!(test_get_day, "getDay", setup,
+        [6, 7] => {
+            [] => 2.0
+        }
+    );
+
+    test_method!(test_get_utc_hours, "getUTCHours", setup,
+        [6, 7] => {
+            [] => 3.0
+        }
+    );
+
+    test_method!(test_get_minutes, "getMinutes", setup,
+        [6, 7] => {
+            [] => 4.0
+        }
+    );
+
+    test_method!(test_get_seconds, "getSeconds", setup,
+        [6, 7] => {
+            [] => 5.0
+        }
+    );
+
+    test_method!(test_get_milliseconds, "getMilliseconds", setup,
+        [6, 7] => {
+            [] => 6.0
+        }
+    );
+
+    test_method!(test_get_timezone_offset, "getTimezoneOffset", setup,
+        [6, 7] => {
+            [] => 0.0
+        }
+    );
+
+    test_method!(test_to_string, "toString", setup,
+        [6, 7] => {
+            [] => "Tue Feb 1 03:04:05 GMT+0000 2000"
+        }
+    );
+
+    test_method!(test_invalid_date, "toString", setup_invalid,
+        [6, 7] => {
+            [] => "Invalid Date"
+        }
+    );
+
+    test_method!(test_invalid_date_getter, "getHours", setup_invalid,
+        [6, 7] => {
+            [] => f64::NAN
+        }
+    );
+
+    test_method!(test_set_full_year, "setFullYear", setup,
+        [6, 7] => {
+            [2004.0, 1.0, 29.0] => 1078023845006.0,
+            [1999.0] => 920257445006.0
+        }
+    );
+
+    test_method!(test_set_full_year_invalid_date, "setFullYear", setup_invalid,
+        [6, 7] => {
+            [2000.0] => 946684800000.0
+        }
+    );
+
+    test_method!(test_set_month, "setMonth", setup,
+        [6, 7] => {
+            [0.0] => 946695845006.0,
+            [] => f64::NAN
+        }
+    );
+
+    test_method!(test_set_date, "setDate", setup,
+        [6, 7] => {
+            [32.0] => 952052645006.0
+        }
+    );
+
+    test_method!(test_set_utc_hours, "setUTCHours", setup,
+        [6, 7] => {
+            [25.0] => 949453445006.0
+        }
+    );
+
+    test_method!(test_set_year, "setYear", setup,
+        [6, 7] => {
+            [99.0] => 917838245006.0
+        }
+    );
+
+    test_method!(test_utc, "UTC", setup_class,
+        [6, 7] => {
+            [2000.0, 1.0] => 949363200000.0,
+            [99.0, 11.0, 31.0] => 946598400000.0,
+            [2000.0, 0.0, 1.0, 0.0, 0.0, 0.0, -1.0] => 946684799999.0,
+            [2000.0] => f64::NAN
+        }
+    );
+
+    #[test]
+    fn test_behind_utc() {
+        // The local time is Mon Jan 31 22:04:05.006 2000.
+        check_methods(
+            -300,
+            &[
+                ("getTimezoneOffset", &[], 300.0),
+                ("getFullYear", &[], 2000.0),
+                ("getYear", &[], 100.0),
+                ("getMonth", &[], 0.0),
+                ("getDate", &[], 31.0),
+                ("getDay", &[], 1.0),
+                ("getHours", &[], 22.0),
+                ("getMinutes", &[], 4.0),
+                ("getSeconds", &[], 5.0),
+                ("getMilliseconds", &[], 6.0),
+                ("getUTCFullYear", &[], 2000.0),
+                ("getUTCYear", &[], 100.0),
+                ("getUTCMonth", &[], 1.0),
+                ("getUTCDate", &[], 1.0),
+                ("getUTCDay", &[], 2.0),
+                ("getUTCHours", &[], 3.0),
+                ("getUTCMinutes", &[], 4.0),
+                ("getUTCSeconds", &[], 5.0),
+                ("getUTCMilliseconds", &[], 6.0),
+                ("setFullYear", &[2001.0], 980996645006.0),
+                ("setYear", &[99.0], 917838245006.0),
+                ("setHours", &[24.0], 949381445006.0),
+                ("setMinutes", &[60.0, 0.0, 0.0], 949377600000.0),
+                ("setMilliseconds", &[1000.0], 949374246000.0),
+                ("setUTCFullYear", &[1999.0], 917838245006.0),
+                ("setUTCMonth", &[0.0, 15.0], 947905445006.0),
+                ("setUTCHours", &[-1.0], 949359845006.0),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_ahead_of_utc() {
+        // The local time is Tue Feb 1 08:34:05.006 2000.
+        check_methods(
+            330,
+            &[
+                ("getTimezoneOffset", &[], -330.0),
+                ("getDate", &[], 1.0),
+                ("getHours", &[], 8.0),
+                ("getMinutes", &[], 34.0),
+                ("getUTCHours", &[], 3.0),
+                ("getUTCMinutes", &[], 4.0),
+                ("setMinutes", &[0.0], 949372205006.0),
+                ("setUTCMinutes", &[0.0], 949374005006.0),
+                ("setHours", &[2.0], 949352645006.0),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_rollover() {
+        // Fields out of range carry over into the next larger field, in local time.
+        check_methods(
+            -300,
+            &[
+                // February 31st is March 2nd in a leap year.
+                ("setMonth", &[1.0], 952052645006.0),
+                ("setMonth", &[13.0], 983675045006.0),
+                ("setMonth", &[-1.0], 946695845006.0),
+                ("setDate", &[0.0], 946695845006.0),
+                ("setDate", &[32.0], 949460645006.0),
+                ("setUTCDate", &[31.0], 951966245006.0),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_to_string_in_timezone() {
+        let cases = [
+            (-300, "Mon Jan 31 22:04:05 GMT-0500 2000"),
+            (330, "Tue Feb 1 08:34:05 GMT+0530 2000"),
+            (-30, "Tue Feb 1 02:34:05 GMT-0030 2000"),
+        ];
+        for &(offset, expected) in &cases {
+            let mut navigator = FixedTimeNavigator::new(offset);
+            with_avm_and_navigator(
+                7,
+                &mut navigator,
+                |avm, context, _root| -> Result<(), Error> {
+                    let date = setup(avm, context);
+                    let result = to_string(avm, context, date, &[])?;
+                    assert_eq!(result.resolve(avm, context)?, expected.into());
+
+                    // Calling `Date` as a function formats the current time.
+                    let result = constructor(avm, context, avm.global_object_cell(), &[])?;
+                    assert_eq!(result.resolve(avm, context)?, expected.into());
+                    Ok(())
+                },
+            );
+        }
+    }
+
+    #[test]
+    fn test_constructor_in_timezone() {
+        let mut navigator = FixedTimeNavigator::new(-300);
+        with_avm_and_navigator(
+            7,
+            &mut navigator,
+            |avm, context, _root| -> Result<(), Error> {
+                let cases: &[(&[Value], f64)] = &[
+                    (&[], TEST_TIME),
+                    (&[TEST_TIME.into()], TEST_TIME),
+                    // The fields are in local time.
+                    (
+                        &[
+                            2000.into(),
+                            0.into(),
+                            31.into(),
+                            22.into(),
+                            4.into(),
+                            5.into(),
+                            6.into(),
+                        ],
+                        TEST_TIME,
+                    ),
+                    (&[2000.into(), 1.into()], 949381200000.0),
+                ];
+                for (args, expected) in cases {
+                    let date = setup_invalid(avm, context);
+                    let _ = constructor(avm, context, date, args)?;
+                    let date_time = date.as_date_object().unwrap().date_time();
+                    assert_eq!(date_time, *expected, "{:?}", args);
+                }
+
+                // `Date.UTC` ignores the timezone.
+                let class = setup_class(avm, context);
+                let cases: &[(&[Value], f64)] = &[
+                    (
+                        &[
+                            2000.into(),
+                            1.into(),
+                            1.into(),
+                            3.into(),
+                            4.into(),
+                            5.into(),
+                            6.into(),
+                        ],
+                        TEST_TIME,
+                    ),
+                    (&[2000.into(), 13.into(), 31.into()], 983577600000.0),
+                    (
+                        &[
+                            1999.into(),
+                            11.into(),
+                            31.into(),
+                            23.into(),
+                            59.into(),
+                            59.into(),
+                            1000.into(),
+                        ],
+                        946684800000.0,
+                    ),
+                ];
+                for (args, expected) in cases {
+                    let result = utc(avm, context, class, args)?;
+                    assert_eq!(
+                        result.resolve(avm, context)?,
+                        (*expected).into(),
+                        "{:?}",
+                        args
+                    );
+                }
+                Ok(())
+            },
+        );
+    }
+
+    #[test]
+    fn test_constructor() {
+        with_avm(7, |avm, context, _root| -> Result<(), Error> {
+            let cases: &[(&[Value], f64)] = &[
+                (&[], 0.0),
+                (&[1000.0.into()], 1000.0),
+                (&[2000.0.into(), 1.0.into()], 949363200000.0),
+                (&[0.0.into(), 0.0.into()], -2208988800000.0),
+                (&[2000.0.into(), 12.0.into(), 1.0.into()], 978307200000.0),
+                (&[f64::NAN.into(), 0.0.into()], f64::NAN),
+            ];
+            for (args, expected) in cases {
+                let date = setup_invalid(avm, context);
+                let _ = constructor(avm, context, date, args)?;
+                let date_time = date.as_date_object().unwrap().date_time();
+                assert!(
+                    date_time == *expected || (date_time.is_nan() && expected.is_nan()),
+                    "{:?} => {} (expected {})",
+                    args,
+                    date_time,
+                    expected
+                );
+            }
+
+            let result = constructor(avm, context, avm.global_object_cell(), &[])?;
+            assert_eq!(
+                result.resolve(avm, context)?,
+                "Thu Jan 1 00:00:00 GMT+0000 1970".into()
+            );
+
+            Ok(())
+        });
+    }
+}
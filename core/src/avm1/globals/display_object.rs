@@ -74,6 +74,22 @@ pub fn define_display_object_proto<'gc>(
         None,
         DontDelete | ReadOnly | DontEnum,
     );
+
+    object.add_property(
+        gc_context,
+        "tabIndex",
+        Executable::Native(get_tab_index),
+        Some(Executable::Native(set_tab_index)),
+        DontDelete | DontEnum,
+    );
+
+    object.add_property(
+        gc_context,
+        "tabEnabled",
+        Executable::Native(get_tab_enabled),
+        Some(Executable::Native(set_tab_enabled)),
+        DontDelete | DontEnum,
+    );
 }
 
 pub fn get_parent<'gc>(
@@ -91,6 +107,71 @@ pub fn get_parent<'gc>(
         .into())
 }
 
+pub fn get_tab_index<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    Ok(this
+        .as_display_object()
+        .and_then(|dobj| dobj.tab_index())
+        .map(Value::from)
+        .unwrap_or(Value::Undefined)
+        .into())
+}
+
+pub fn set_tab_index<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    // Unsetting `tabIndex` removes the object from any custom tab order.
+    let tab_index = match args.get(0) {
+        None | Some(Value::Undefined) | Some(Value::Null) => None,
+        Some(v) => Some(v.coerce_to_i32(avm, context)?),
+    };
+
+    if let Some(mut dobj) = this.as_display_object() {
+        dobj.set_tab_index(context.gc_context, tab_index);
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn get_tab_enabled<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    Ok(this
+        .as_display_object()
+        .and_then(|dobj| dobj.tab_enabled())
+        .map(Value::from)
+        .unwrap_or(Value::Undefined)
+        .into())
+}
+
+pub fn set_tab_enabled<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let tab_enabled = match args.get(0) {
+        None | Some(Value::Undefined) | Some(Value::Null) => None,
+        Some(v) => Some(v.as_bool(avm.current_swf_version())),
+    };
+
+    if let Some(mut dobj) = this.as_display_object() {
+        dobj.set_tab_enabled(context.gc_context, tab_enabled);
+    }
+
+    Ok(Value::Undefined.into())
+}
+
 pub fn get_depth<'gc>(
     display_object: DisplayObject<'gc>,
     avm: &mut Avm1<'gc>,
@@ -0,0 +1,232 @@
+//! `ExternalInterface` impl
+
+use crate::avm1::error::Error;
+use crate::avm1::function::Executable;
+use crate::avm1::property::Attribute;
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::{Avm1, Object, ScriptObject, TObject, UpdateContext, Value};
+use crate::external::{Callback, Value as ExternalValue};
+use gc_arena::MutationContext;
+
+pub fn create_external_interface_object<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Option<Object<'gc>>,
+    fn_proto: Option<Object<'gc>>,
+) -> Object<'gc> {
+    let mut object = ScriptObject::object(gc_context, proto);
+
+    object.add_property(
+        gc_context,
+        "available",
+        Executable::Native(available),
+        None,
+        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
+    );
+
+    object.force_set_function(
+        "addCallback",
+        add_callback,
+        gc_context,
+        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
+        fn_proto,
+    );
+
+    object.force_set_function(
+        "call",
+        call,
+        gc_context,
+        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
+        fn_proto,
+    );
+
+    object.into()
+}
+
+fn available<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    Ok(context.external_interface.available().into())
+}
+
+fn add_callback<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if args.len() < 3 || !context.external_interface.available() {
+        return Ok(false.into());
+    }
+
+    let name = args[0].coerce_to_string(avm, context)?.into_owned();
+    let method = match &args[2] {
+        Value::Object(method) if method.as_executable().is_some() => *method,
+        _ => return Ok(false.into()),
+    };
+    context
+        .external_interface
+        .add_callback(name, Callback::new(args[1].clone(), method));
+
+    Ok(true.into())
+}
+
+fn call<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let name = match args.get(0) {
+        Some(name) => name.coerce_to_string(avm, context)?,
+        None => return Ok(Value::Null.into()),
+    };
+    let method = match context.external_interface.get_method_for(&name) {
+        Some(method) => method,
+        None => return Ok(Value::Null.into()),
+    };
+
+    let mut external_args = Vec::with_capacity(args.len() - 1);
+    for arg in &args[1..] {
+        external_args.push(ExternalValue::from_avm1(avm, context, arg.clone())?);
+    }
+    let result = method.call(&external_args);
+
+    Ok(result.into_avm1(avm, context).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm1::test_utils::with_avm;
+    use crate::external::{ExternalInterfaceMethod, ExternalInterfaceProvider};
+    use enumset::EnumSet;
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+    use std::rc::Rc;
+
+    /// A host with a `sum` method, which records the callbacks and commands it receives.
+    #[derive(Default)]
+    struct TestProvider {
+        received: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl ExternalInterfaceProvider for TestProvider {
+        fn get_method(&self, name: &str) -> Option<Box<dyn ExternalInterfaceMethod>> {
+            match name {
+                "sum" => Some(Box::new(|args: &[ExternalValue]| {
+                    let sum = args
+                        .iter()
+                        .map(|arg| match arg {
+                            ExternalValue::Number(n) => *n,
+                            _ => 0.0,
+                        })
+                        .sum::<f64>();
+                    ExternalValue::Number(sum)
+                })),
+                _ => None,
+            }
+        }
+
+        fn on_callback_available(&self, name: &str) {
+            self.received
+                .borrow_mut()
+                .push(format!("callback {}", name));
+        }
+
+        fn on_fs_command(&self, command: &str, args: &str) -> bool {
+            self.received
+                .borrow_mut()
+                .push(format!("fscommand {} {}", command, args));
+            true
+        }
+    }
+
+    /// Returns its arguments as an array.
+    fn echo<'gc>(
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        _this: Object<'gc>,
+        args: &[Value<'gc>],
+    ) -> Result<ReturnValue<'gc>, Error<'gc>> {
+        let array = ScriptObject::array(context.gc_context, Some(avm.prototypes().array));
+        array.set_length(context.gc_context, args.len());
+        for (i, arg) in args.iter().enumerate() {
+            array.set_array_element(i, arg.clone(), context.gc_context);
+        }
+        Ok(Value::Object(array.into()).into())
+    }
+
+    #[test]
+    fn unavailable_without_provider() {
+        with_avm(8, |avm, context, _root| -> Result<(), Error> {
+            let object = create_external_interface_object(
+                context.gc_context,
+                Some(avm.prototypes().object),
+                Some(avm.prototypes().function),
+            );
+            assert_eq!(object.get("available", avm, context)?, false.into());
+            let result = call(avm, context, object, &["sum".into(), 1.into()])?;
+            assert_eq!(result.resolve(avm, context)?, Value::Null);
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn calls_between_movie_and_host() {
+        with_avm(8, |avm, context, _root| -> Result<(), Error> {
+            let provider = TestProvider::default();
+            let received = provider.received.clone();
+            context.external_interface.add_provider(Box::new(provider));
+
+            let object = create_external_interface_object(
+                context.gc_context,
+                Some(avm.prototypes().object),
+                Some(avm.prototypes().function),
+            );
+            assert_eq!(object.get("available", avm, context)?, true.into());
+
+            let result = call(avm, context, object, &["sum".into(), 1.into(), 2.into()])?;
+            assert_eq!(result.resolve(avm, context)?, 3.into());
+            let result = call(avm, context, object, &["missing".into()])?;
+            assert_eq!(result.resolve(avm, context)?, Value::Null);
+
+            let mut callbacks = ScriptObject::object(context.gc_context, None);
+            callbacks.force_set_function(
+                "echo",
+                echo,
+                context.gc_context,
+                EnumSet::empty(),
+                Some(avm.prototypes().function),
+            );
+            let method = callbacks.get("echo", avm, context)?;
+            let args = ["echo".into(), Value::Null, method];
+            let result = add_callback(avm, context, object, &args)?;
+            assert_eq!(result.resolve(avm, context)?, true.into());
+            crate::avm1::fscommand::handle("fullscreen", "true", avm, context)?;
+            assert_eq!(
+                *received.borrow(),
+                vec![
+                    "callback echo".to_string(),
+                    "fscommand fullscreen true".to_string()
+                ]
+            );
+
+            let mut values = BTreeMap::new();
+            values.insert("a".to_string(), ExternalValue::Bool(true));
+            let args = vec![
+                ExternalValue::String("text".to_string()),
+                ExternalValue::Object(values),
+            ];
+            let callback = context.external_interface.get_callback("echo").unwrap();
+            assert_eq!(
+                callback.call(avm, context, args.clone()),
+                ExternalValue::List(args)
+            );
+
+            Ok(())
+        })
+    }
+}
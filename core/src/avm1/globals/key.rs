@@ -1,4 +1,5 @@
 use crate::avm1::error::Error;
+use crate::avm1::listeners::Listeners;
 use crate::avm1::property::Attribute;
 use crate::avm1::return_value::ReturnValue;
 use crate::avm1::{Avm1, Object, ScriptObject, TObject, UpdateContext, Value};
@@ -34,13 +35,42 @@ pub fn get_code<'gc>(
     Ok(code.into())
 }
 
+pub fn get_ascii<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    Ok(context.keyboard.last_ascii().into())
+}
+
+pub fn is_toggled<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let Some(key) = args
+        .get(0)
+        .and_then(|v| v.coerce_to_f64(avm, context).ok())
+        .and_then(|k| KeyCode::try_from(k as u8).ok())
+    {
+        Ok(context.keyboard.is_toggled(key).into())
+    } else {
+        Ok(false.into())
+    }
+}
+
 pub fn create_key_object<'gc>(
     gc_context: MutationContext<'gc, '_>,
     proto: Option<Object<'gc>>,
     fn_proto: Option<Object<'gc>>,
+    listener: &Listeners<'gc>,
 ) -> Object<'gc> {
     let mut key = ScriptObject::object(gc_context, proto);
 
+    register_listener!(gc_context, key, listener, fn_proto, key);
+
     key.define_value(
         gc_context,
         "ALT",
@@ -172,5 +202,45 @@ pub fn create_key_object<'gc>(
         fn_proto,
     );
 
+    key.force_set_function(
+        "getAscii",
+        get_ascii,
+        gc_context,
+        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
+        fn_proto,
+    );
+
+    key.force_set_function(
+        "isToggled",
+        is_toggled,
+        gc_context,
+        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
+        fn_proto,
+    );
+
     key.into()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm1::test_utils::with_avm;
+
+    #[test]
+    fn ascii_and_toggled() {
+        with_avm(6, |avm, context, this| -> Result<(), Error> {
+            context.keyboard.key_down(KeyCode::CapsLock, false);
+            context.keyboard.key_down(KeyCode::Q, false);
+            let result = get_ascii(avm, context, this, &[])?;
+            assert_eq!(result.resolve(avm, context)?, u32::from(b'Q').into());
+
+            let result = is_toggled(avm, context, this, &[20.into()])?;
+            assert_eq!(result.resolve(avm, context)?, true.into());
+            let result = is_toggled(avm, context, this, &[145.into()])?;
+            assert_eq!(result.resolve(avm, context)?, false.into());
+            let result = is_toggled(avm, context, this, &[])?;
+            assert_eq!(result.resolve(avm, context)?, false.into());
+            Ok(())
+        })
+    }
+}
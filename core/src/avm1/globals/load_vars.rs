@@ -0,0 +1,455 @@
+//! `LoadVars` impl
+
+use crate::avm1::error::Error;
+use crate::avm1::property::Attribute::*;
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::script_object::ScriptObject;
+use crate::avm1::{Avm1, Object, TObject, UpdateContext, Value};
+use crate::backend::navigator::{NavigationMethod, RequestOptions};
+use crate::display_object::TDisplayObject;
+use crate::policy;
+use gc_arena::MutationContext;
+use std::borrow::Cow;
+use url::form_urlencoded;
+
+/// The MIME type sent with `send` and `sendAndLoad` unless `contentType` is changed.
+const DEFAULT_CONTENT_TYPE: &str = "application/x-www-form-urlencoded";
+
+/// Implements `LoadVars`
+pub fn constructor<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    Ok(Value::Undefined.into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let mut object = ScriptObject::object(gc_context, Some(proto));
+
+    object.force_set_function(
+        "load",
+        load,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "send",
+        send,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "sendAndLoad",
+        send_and_load,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "decode",
+        decode,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "toString",
+        to_string,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "getBytesLoaded",
+        get_bytes_loaded,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "getBytesTotal",
+        get_bytes_total,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "addRequestHeader",
+        add_request_header,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function("onData", on_data, gc_context, DontEnum, Some(fn_proto));
+    object.force_set_function("onLoad", on_load, gc_context, DontEnum, Some(fn_proto));
+    object.define_value(
+        gc_context,
+        "contentType",
+        DEFAULT_CONTENT_TYPE.into(),
+        DontEnum.into(),
+    );
+
+    object.into()
+}
+
+fn load<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let url = match args.get(0) {
+        Some(Value::Undefined) | Some(Value::Null) | None => return Ok(false.into()),
+        Some(url) => url.coerce_to_string(avm, context)?,
+    };
+
+    let request_options = RequestOptions::get().with_headers(request_headers(this, avm, context)?);
+    spawn_load(avm, context, this, &url, request_options)?;
+
+    Ok(true.into())
+}
+
+fn send<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let url = match args.get(0) {
+        Some(Value::Undefined) | Some(Value::Null) | None => return Ok(false.into()),
+        Some(url) => url.coerce_to_string(avm, context)?.to_string(),
+    };
+    let window = match args.get(1) {
+        Some(Value::Undefined) | Some(Value::Null) | None => None,
+        Some(window) => Some(window.coerce_to_string(avm, context)?.to_string()),
+    };
+    let method = navigation_method(avm, context, args.get(2))?;
+
+    let vars = form_values(this, avm, context)?.into_iter().collect();
+    context
+        .navigator
+        .navigate_to_url(url, window, Some((method, vars)));
+
+    Ok(true.into())
+}
+
+fn send_and_load<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let url = match args.get(0) {
+        Some(Value::Undefined) | Some(Value::Null) | None => return Ok(false.into()),
+        Some(url) => url.coerce_to_string(avm, context)?,
+    };
+    let target = match args.get(1) {
+        Some(Value::Object(target)) => *target,
+        _ => return Ok(false.into()),
+    };
+    let method = navigation_method(avm, context, args.get(2))?;
+
+    let qstring = form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(form_values(this, avm, context)?)
+        .finish();
+    let (url, request_options) = match method {
+        NavigationMethod::GET if url.find('?').is_none() => (
+            Cow::Owned(format!("{}?{}", url, qstring)),
+            RequestOptions::get(),
+        ),
+        NavigationMethod::GET => (
+            Cow::Owned(format!("{}&{}", url, qstring)),
+            RequestOptions::get(),
+        ),
+        NavigationMethod::POST => {
+            let content_type = this
+                .get("contentType", avm, context)?
+                .coerce_to_string(avm, context)?
+                .to_string();
+            (
+                url,
+                RequestOptions::post(Some((qstring.into_bytes(), content_type))),
+            )
+        }
+    };
+    let request_options = request_options.with_headers(request_headers(this, avm, context)?);
+    spawn_load(avm, context, target, &url, request_options)?;
+
+    Ok(true.into())
+}
+
+fn decode<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let Some(data) = args.get(0) {
+        let data = data.coerce_to_string(avm, context)?;
+        for (k, v) in form_urlencoded::parse(data.as_bytes()) {
+            this.set(&k, v.into_owned().into(), avm, context)?;
+        }
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+fn to_string<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let qstring = form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(form_values(this, avm, context)?)
+        .finish();
+
+    Ok(qstring.into())
+}
+
+fn get_bytes_loaded<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    Ok(this.get("_bytesLoaded", avm, context)?.into())
+}
+
+fn get_bytes_total<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    Ok(this.get("_bytesTotal", avm, context)?.into())
+}
+
+fn add_request_header<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    // Headers are either passed as a name and value, or as an array of alternating names and values.
+    let new_headers = match args {
+        [Value::Object(array), ..] => array.array(),
+        [name, value, ..] => vec![name.clone(), value.clone()],
+        _ => return Ok(Value::Undefined.into()),
+    };
+
+    let headers = match this.get("_customHeaders", avm, context)? {
+        Value::Object(headers) => headers,
+        _ => {
+            let headers: Object<'gc> =
+                ScriptObject::array(context.gc_context, Some(avm.prototypes().array)).into();
+            this.define_value(
+                context.gc_context,
+                "_customHeaders",
+                headers.into(),
+                DontEnum.into(),
+            );
+            headers
+        }
+    };
+
+    for pair in new_headers.chunks_exact(2) {
+        let length = headers.length();
+        headers.set_length(context.gc_context, length + 2);
+        headers.set_array_element(length, pair[0].clone(), context.gc_context);
+        headers.set_array_element(length + 1, pair[1].clone(), context.gc_context);
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+fn on_data<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let src = args.get(0).cloned().unwrap_or(Value::Undefined);
+
+    if let Value::Undefined = src {
+        this.set("loaded", false.into(), avm, context)?;
+        this.call_method("onLoad", &[false.into()], avm, context)?;
+    } else {
+        this.call_method("decode", &[src], avm, context)?;
+        this.set("loaded", true.into(), avm, context)?;
+        this.call_method("onLoad", &[true.into()], avm, context)?;
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+fn on_load<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    Ok(Value::Undefined.into())
+}
+
+/// Starts loading the given URL, delivering the response to `target`'s `onData` handler.
+fn spawn_load<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    target: Object<'gc>,
+    url: &str,
+    request_options: RequestOptions,
+) -> Result<(), Error<'gc>> {
+    target.set("loaded", false.into(), avm, context)?;
+
+    let target_clip = avm.target_clip_or_root();
+    let fetch = policy::fetch_data(
+        context,
+        target_clip.movie().as_deref(),
+        url,
+        request_options,
+    );
+    let process = context.load_manager.load_form_into_load_vars(
+        context.player.clone().unwrap(),
+        target,
+        target_clip,
+        fetch,
+    );
+
+    context.navigator.spawn_future(process);
+
+    Ok(())
+}
+
+/// Parses the HTTP method argument of `send` and `sendAndLoad`, which defaults to POST.
+fn navigation_method<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    method: Option<&Value<'gc>>,
+) -> Result<NavigationMethod, Error<'gc>> {
+    let method = match method {
+        Some(Value::Undefined) | None => return Ok(NavigationMethod::POST),
+        Some(method) => method.coerce_to_string(avm, context)?,
+    };
+
+    Ok(NavigationMethod::from_method_str(&method.to_uppercase()).unwrap_or(NavigationMethod::POST))
+}
+
+/// Collects the enumerable properties of a `LoadVars` object as form values.
+fn form_values<'gc>(
+    this: Object<'gc>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<Vec<(String, String)>, Error<'gc>> {
+    let mut values = Vec::new();
+    for key in this.get_keys(avm) {
+        let value = this.get(&key, avm, context)?;
+        if let Value::Object(object) = value {
+            if object.as_executable().is_some() {
+                continue;
+            }
+        }
+        values.push((key, value.coerce_to_string(avm, context)?.to_string()));
+    }
+
+    Ok(values)
+}
+
+/// Collects the headers added with `addRequestHeader`.
+fn request_headers<'gc>(
+    this: Object<'gc>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<Vec<(String, String)>, Error<'gc>> {
+    let headers = match this.get("_customHeaders", avm, context)? {
+        Value::Object(headers) => headers.array(),
+        _ => return Ok(Vec::new()),
+    };
+
+    let mut pairs = Vec::new();
+    for pair in headers.chunks_exact(2) {
+        let name = pair[0].coerce_to_string(avm, context)?.to_string();
+        let value = pair[1].coerce_to_string(avm, context)?.to_string();
+        pairs.push((name, value));
+    }
+
+    Ok(pairs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm1::test_utils::with_avm;
+
+    #[test]
+    fn decode_and_to_string() {
+        with_avm(8, |avm, context, _root| -> Result<(), Error> {
+            let proto = create_proto(
+                context.gc_context,
+                avm.prototypes().object,
+                avm.prototypes().function,
+            );
+            let object: Object<'_> = ScriptObject::object(context.gc_context, Some(proto)).into();
+
+            decode(avm, context, object, &["a=1&b=hello+world%21".into()])?
+                .resolve(avm, context)?;
+            assert_eq!(object.get("a", avm, context)?, "1".into());
+            assert_eq!(object.get("b", avm, context)?, "hello world!".into());
+
+            let mut keys = form_values(object, avm, context)?;
+            keys.sort();
+            assert_eq!(
+                keys,
+                vec![
+                    ("a".to_string(), "1".to_string()),
+                    ("b".to_string(), "hello world!".to_string())
+                ]
+            );
+
+            let string = to_string(avm, context, object, &[])?.resolve(avm, context)?;
+            let string = string.coerce_to_string(avm, context)?;
+            assert!(string == "a=1&b=hello+world%21" || string == "b=hello+world%21&a=1");
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn request_headers_are_collected() {
+        with_avm(8, |avm, context, _root| -> Result<(), Error> {
+            let proto = create_proto(
+                context.gc_context,
+                avm.prototypes().object,
+                avm.prototypes().function,
+            );
+            let object: Object<'_> = ScriptObject::object(context.gc_context, Some(proto)).into();
+
+            add_request_header(avm, context, object, &["X-One".into(), "1".into()])?
+                .resolve(avm, context)?;
+            let array = ScriptObject::array(context.gc_context, Some(avm.prototypes().array));
+            array.set_length(context.gc_context, 2);
+            array.set_array_element(0, "X-Two".into(), context.gc_context);
+            array.set_array_element(1, "2".into(), context.gc_context);
+            add_request_header(avm, context, object, &[array.into()])?.resolve(avm, context)?;
+
+            assert_eq!(
+                request_headers(object, avm, context)?,
+                vec![
+                    ("X-One".to_string(), "1".to_string()),
+                    ("X-Two".to_string(), "2".to_string())
+                ]
+            );
+            assert!(form_values(object, avm, context)?.is_empty());
+
+            Ok(())
+        })
+    }
+}
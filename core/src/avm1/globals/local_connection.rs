@@ -0,0 +1,253 @@
+//! `LocalConnection` impl
+
+use crate::avm1::error::Error;
+use crate::avm1::globals::shared_object::serialize_value;
+use crate::avm1::property::Attribute::*;
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::script_object::ScriptObject;
+use crate::avm1::{Avm1, Object, UpdateContext, Value};
+use crate::display_object::TDisplayObject;
+use crate::local_connection::{full_connection_name, movie_domain};
+use gc_arena::MutationContext;
+use swf::amf0::types::Value as AmfValue;
+
+/// The names of `LocalConnection` methods, which can't be called by `send`.
+const RESERVED_METHODS: &[&str] = &[
+    "send",
+    "connect",
+    "close",
+    "allowDomain",
+    "allowInsecureDomain",
+    "domain",
+];
+
+/// Implements `LocalConnection`
+pub fn constructor<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    Ok(Value::Undefined.into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let mut object = ScriptObject::object(gc_context, Some(proto));
+
+    object.force_set_function(
+        "connect",
+        connect,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "send",
+        send,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "close",
+        close,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "domain",
+        domain,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+
+    object.into()
+}
+
+/// Returns the domain of the movie running the current code.
+fn current_domain<'gc>(avm: &mut Avm1<'gc>) -> String {
+    movie_domain(avm.target_clip_or_root().movie().as_deref())
+}
+
+fn connect<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let name = match args.get(0) {
+        Some(Value::Undefined) | Some(Value::Null) | None => return Ok(false.into()),
+        Some(name) => name.coerce_to_string(avm, context)?,
+    };
+    // Only senders may name the domain of a connection.
+    if name.is_empty() || name.contains(':') {
+        return Ok(false.into());
+    }
+
+    let name = full_connection_name(&name, &current_domain(avm));
+    let connected = context
+        .local_connections
+        .connect(context.local_connection, this, name);
+
+    Ok(connected.into())
+}
+
+fn send<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let (name, method_name) = match (args.get(0), args.get(1)) {
+        (Some(name), Some(method_name)) => (
+            name.coerce_to_string(avm, context)?.into_owned(),
+            method_name.coerce_to_string(avm, context)?.into_owned(),
+        ),
+        _ => return Ok(false.into()),
+    };
+    if name.is_empty() || method_name.is_empty() || RESERVED_METHODS.contains(&&*method_name) {
+        return Ok(false.into());
+    }
+
+    // Arguments that can't be serialized, such as functions, arrive as `undefined`.
+    let mut objects = Vec::new();
+    let mut amf_args = Vec::with_capacity(args.len() - 2);
+    for arg in &args[2..] {
+        let arg = serialize_value(avm, context, arg.clone(), &mut objects)?;
+        amf_args.push(arg.unwrap_or(AmfValue::Undefined));
+    }
+
+    let domain = current_domain(avm);
+    let name = full_connection_name(&name, &domain);
+    let id = context
+        .local_connection
+        .send(&name, &method_name, &domain, amf_args);
+    context.local_connections.add_pending(id, this);
+
+    Ok(true.into())
+}
+
+fn close<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    context
+        .local_connections
+        .close(context.local_connection, this);
+
+    Ok(Value::Undefined.into())
+}
+
+fn domain<'gc>(
+    avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    Ok(current_domain(avm).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm1::test_utils::with_avm;
+    use crate::avm1::TObject;
+    use crate::local_connection::LocalConnections;
+    use enumset::EnumSet;
+
+    /// Stores the arguments in the `received` array property of `this`.
+    fn record<'gc>(
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        this: Object<'gc>,
+        args: &[Value<'gc>],
+    ) -> Result<ReturnValue<'gc>, Error<'gc>> {
+        let received = ScriptObject::array(context.gc_context, Some(avm.prototypes().array));
+        received.set_length(context.gc_context, args.len());
+        for (i, arg) in args.iter().enumerate() {
+            received.set_array_element(i, arg.clone(), context.gc_context);
+        }
+        this.set("received", received.into(), avm, context)?;
+        Ok(Value::Undefined.into())
+    }
+
+    #[test]
+    fn send_between_connections() {
+        with_avm(8, |avm, context, _root| -> Result<(), Error> {
+            let proto = create_proto(
+                context.gc_context,
+                avm.prototypes().object,
+                avm.prototypes().function,
+            );
+            let mut receiver = ScriptObject::object(context.gc_context, Some(proto));
+            let mut sender = ScriptObject::object(context.gc_context, Some(proto));
+            receiver.force_set_function(
+                "show",
+                record,
+                context.gc_context,
+                EnumSet::empty(),
+                Some(avm.prototypes().function),
+            );
+            sender.force_set_function(
+                "onStatus",
+                record,
+                context.gc_context,
+                EnumSet::empty(),
+                Some(avm.prototypes().function),
+            );
+            let receiver: Object<'_> = receiver.into();
+            let sender: Object<'_> = sender.into();
+
+            let result = connect(avm, context, receiver, &["Content".into()])?;
+            assert_eq!(result.resolve(avm, context)?, true.into());
+            let result = connect(avm, context, sender, &["content".into()])?;
+            assert_eq!(result.resolve(avm, context)?, false.into());
+            let result = connect(avm, context, sender, &["a:content".into()])?;
+            assert_eq!(result.resolve(avm, context)?, false.into());
+
+            let result = send(avm, context, sender, &["content".into(), "close".into()])?;
+            assert_eq!(result.resolve(avm, context)?, false.into());
+            let args = ["CONTENT".into(), "show".into(), 1.into(), "a".into()];
+            let result = send(avm, context, sender, &args)?;
+            assert_eq!(result.resolve(avm, context)?, true.into());
+
+            // The first update delivers the message, and the second its status.
+            LocalConnections::update_connections(avm, context);
+            let received = receiver
+                .get("received", avm, context)?
+                .coerce_to_object(avm, context);
+            assert_eq!(received.array(), vec![1.into(), "a".into()]);
+            LocalConnections::update_connections(avm, context);
+            let status = sender
+                .get("received", avm, context)?
+                .coerce_to_object(avm, context)
+                .array()[0]
+                .coerce_to_object(avm, context);
+            let level = status.get("level", avm, context)?;
+            assert_eq!(level, "status".into());
+
+            let result = close(avm, context, receiver, &[])?;
+            result.resolve(avm, context)?;
+            let args = ["content".into(), "show".into()];
+            send(avm, context, sender, &args)?.resolve(avm, context)?;
+            LocalConnections::update_connections(avm, context);
+            let status = sender
+                .get("received", avm, context)?
+                .coerce_to_object(avm, context)
+                .array()[0]
+                .coerce_to_object(avm, context);
+            let level = status.get("level", avm, context)?;
+            assert_eq!(level, "error".into());
+
+            Ok(())
+        })
+    }
+}
@@ -7,7 +7,10 @@ use crate::avm1::property::Attribute::*;
 use crate::avm1::return_value::ReturnValue;
 use crate::avm1::{Avm1, Object, ScriptObject, TObject, UpdateContext, Value};
 use crate::backend::navigator::NavigationMethod;
-use crate::display_object::{DisplayObject, EditText, MovieClip, TDisplayObject};
+use crate::display_object::{
+    Bitmap, DisplayObject, EditText, HitTestMode, MovieClip, TDisplayObject,
+};
+use crate::policy;
 use crate::prelude::*;
 use crate::shape_utils::DrawCommand;
 use crate::tag_utils::SwfSlice;
@@ -62,16 +65,18 @@ pub fn hit_test<'gc>(
             .get(2)
             .map(|v| v.as_bool(avm.current_swf_version()))
             .unwrap_or(false);
-        if shape {
-            log::warn!("Ignoring shape hittest and using bounding box instead. Shape based hit detection is not yet implemented. See https://github.com/ruffle-rs/ruffle/issues/177");
-        }
         if x.is_finite() && y.is_finite() {
             // The docs say the point is in "Stage coordinates", but actually they are in root coordinates.
             // root can be moved via _root._x etc., so we actually have to transform from root to world space.
             let point = movie_clip
                 .root()
                 .local_to_global((Twips::from_pixels(x), Twips::from_pixels(y)));
-            return Ok(movie_clip.hit_test(point).into());
+            let hit = if shape {
+                movie_clip.hit_test_shape(context, point, HitTestMode::AvmHitTest)
+            } else {
+                movie_clip.hit_test(point)
+            };
+            return Ok(hit.into());
         }
     } else if args.len() == 1 {
         let other = args
@@ -103,6 +108,7 @@ pub fn create_proto<'gc>(
         gc_context,
         object,
         Some(fn_proto),
+        "attachBitmap" => attach_bitmap,
         "attachMovie" => attach_movie,
         "createEmptyMovieClip" => create_empty_movie_clip,
         "createTextField" => create_text_field,
@@ -417,6 +423,43 @@ fn clear<'gc>(
     Ok(Value::Undefined.into())
 }
 
+fn attach_bitmap<'gc>(
+    mut movie_clip: MovieClip<'gc>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let bitmap_data = match args.get(0) {
+        Some(Value::Object(object)) => object.as_bitmap_data_object(),
+        _ => None,
+    };
+    let (bitmap_data, depth) = match (bitmap_data, args.get(1)) {
+        (Some(bitmap_data), Some(depth)) => (
+            bitmap_data.bitmap_data(),
+            depth
+                .coerce_to_i32(avm, context)?
+                .wrapping_add(AVM_DEPTH_BIAS),
+        ),
+        _ => {
+            log::error!("MovieClip.attachBitmap: Too few parameters");
+            return Ok(Value::Undefined.into());
+        }
+    };
+    // TODO: Pixel snapping (argument 2) is ignored.
+    let smoothing = args
+        .get(3)
+        .map(|v| v.as_bool(avm.current_swf_version()))
+        .unwrap_or(false);
+
+    if depth < 0 || depth > AVM_MAX_DEPTH {
+        return Ok(Value::Undefined.into());
+    }
+
+    let bitmap = Bitmap::with_bitmap_data(context, bitmap_data, smoothing);
+    movie_clip.add_child_from_avm(context, bitmap.into(), depth);
+    Ok(Value::Undefined.into())
+}
+
 fn attach_movie<'gc>(
     mut movie_clip: MovieClip<'gc>,
     avm: &mut Avm1<'gc>,
@@ -988,9 +1031,11 @@ fn load_movie<'gc>(
     let method = NavigationMethod::from_method_str(&method.coerce_to_string(avm, context)?);
     let (url, opts) = avm.locals_into_request_options(context, url, method);
     let fetch = context.navigator.fetch(&url, opts);
+    let url = policy::resolve_url(target.movie().as_deref(), &url);
     let process = context.load_manager.load_movie_into_clip(
         context.player.clone().unwrap(),
         DisplayObject::MovieClip(target),
+        url,
         fetch,
         None,
     );
@@ -1011,7 +1056,7 @@ fn load_variables<'gc>(
     let method = args.get(1).cloned().unwrap_or(Value::Undefined);
     let method = NavigationMethod::from_method_str(&method.coerce_to_string(avm, context)?);
     let (url, opts) = avm.locals_into_request_options(context, url, method);
-    let fetch = context.navigator.fetch(&url, opts);
+    let fetch = policy::fetch_data(context, target.movie().as_deref(), &url, opts);
     let target = target.object().coerce_to_object(avm, context);
     let process =
         context
@@ -8,6 +8,7 @@ use crate::avm1::script_object::ScriptObject;
 use crate::avm1::{Avm1, Object, UpdateContext, Value};
 use crate::backend::navigator::RequestOptions;
 use crate::display_object::{DisplayObject, TDisplayObject};
+use crate::policy;
 use enumset::EnumSet;
 use gc_arena::MutationContext;
 
@@ -130,9 +131,11 @@ pub fn load_clip<'gc>(
             .and_then(|dobj| dobj.as_movie_clip())
         {
             let fetch = context.navigator.fetch(&url, RequestOptions::get());
+            let url = policy::resolve_url(avm.target_clip_or_root().movie().as_deref(), &url);
             let process = context.load_manager.load_movie_into_clip(
                 context.player.clone().unwrap(),
                 DisplayObject::MovieClip(movieclip),
+                url,
                 fetch,
                 Some(this),
             );
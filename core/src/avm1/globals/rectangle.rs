@@ -52,6 +52,17 @@ fn constructor<'gc>(
     Ok(Value::Undefined.into())
 }
 
+pub fn construct_new_rectangle<'gc>(
+    args: &[Value<'gc>],
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<Object<'gc>, Error<'gc>> {
+    let proto = context.system_prototypes.rectangle;
+    let object = proto.new(avm, context, proto, args)?;
+    let _ = constructor(avm, context, object, args)?;
+    Ok(object)
+}
+
 fn to_string<'gc>(
     avm: &mut Avm1<'gc>,
     context: &mut UpdateContext<'_, 'gc, '_>,
@@ -0,0 +1,220 @@
+//! `Selection` global, which controls keyboard focus and text selection.
+
+use crate::avm1::error::Error;
+use crate::avm1::listeners::Listeners;
+use crate::avm1::property::Attribute;
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::{Avm1, Object, ScriptObject, TObject, UpdateContext, Value};
+use crate::display_object::{EditText, TDisplayObject, TextSelection};
+use gc_arena::MutationContext;
+
+/// The text field that currently has focus, if any.
+fn focused_text_field<'gc>(context: &UpdateContext<'_, 'gc, '_>) -> Option<EditText<'gc>> {
+    context
+        .focus_tracker
+        .get()
+        .and_then(|focused| focused.as_edit_text())
+}
+
+pub fn get_focus<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    Ok(context
+        .focus_tracker
+        .get()
+        .map(|focused| Value::from(focused.path()))
+        .unwrap_or(Value::Null)
+        .into())
+}
+
+pub fn set_focus<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let focus_tracker = context.focus_tracker;
+    match args.get(0).cloned().unwrap_or(Value::Undefined) {
+        // Focus is removed entirely with `null` or `undefined`.
+        Value::Undefined | Value::Null => {
+            focus_tracker.set(None, context);
+            Ok(true.into())
+        }
+        target => {
+            let start_clip = avm.target_clip_or_root();
+            let object = avm.resolve_target_display_object(context, start_clip, target)?;
+            match object {
+                Some(object) if object.is_focusable(avm, context) => {
+                    focus_tracker.set(Some(object), context);
+                    Ok(true.into())
+                }
+                _ => Ok(false.into()),
+            }
+        }
+    }
+}
+
+pub fn get_begin_index<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    Ok(focused_text_field(context)
+        .and_then(|text_field| text_field.selection())
+        .map(|selection| selection.start() as f64)
+        .unwrap_or(-1.0)
+        .into())
+}
+
+pub fn get_end_index<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    Ok(focused_text_field(context)
+        .and_then(|text_field| text_field.selection())
+        .map(|selection| selection.end() as f64)
+        .unwrap_or(-1.0)
+        .into())
+}
+
+pub fn get_caret_index<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    // The caret sits at the end of the selection that the user moves.
+    Ok(focused_text_field(context)
+        .and_then(|text_field| text_field.selection())
+        .map(|selection| selection.to() as f64)
+        .unwrap_or(-1.0)
+        .into())
+}
+
+pub fn set_selection<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let Some(text_field) = focused_text_field(context) {
+        let begin = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(avm, context)?;
+        let end = args
+            .get(1)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_i32(avm, context)?;
+        let selection = TextSelection::for_range(begin.max(0) as usize, end.max(0) as usize);
+        text_field.set_selection(context.gc_context, Some(selection));
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn create_selection_object<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Option<Object<'gc>>,
+    fn_proto: Option<Object<'gc>>,
+    listener: &Listeners<'gc>,
+) -> Object<'gc> {
+    let mut selection = ScriptObject::object(gc_context, proto);
+
+    register_listener!(gc_context, selection, listener, fn_proto, selection);
+
+    selection.force_set_function(
+        "getFocus",
+        get_focus,
+        gc_context,
+        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
+        fn_proto,
+    );
+
+    selection.force_set_function(
+        "setFocus",
+        set_focus,
+        gc_context,
+        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
+        fn_proto,
+    );
+
+    selection.force_set_function(
+        "getBeginIndex",
+        get_begin_index,
+        gc_context,
+        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
+        fn_proto,
+    );
+
+    selection.force_set_function(
+        "getEndIndex",
+        get_end_index,
+        gc_context,
+        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
+        fn_proto,
+    );
+
+    selection.force_set_function(
+        "getCaretIndex",
+        get_caret_index,
+        gc_context,
+        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
+        fn_proto,
+    );
+
+    selection.force_set_function(
+        "setSelection",
+        set_selection,
+        gc_context,
+        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
+        fn_proto,
+    );
+
+    selection.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm1::test_utils::with_avm;
+
+    #[test]
+    fn focus_and_selection() {
+        with_avm(8, |avm, context, this| -> Result<(), Error> {
+            let text_field = EditText::new(context, context.swf.clone(), 0.0, 0.0, 100.0, 20.0);
+            text_field.set_text("hello".to_string(), context).unwrap();
+            let focus_tracker = context.focus_tracker;
+
+            let result = get_begin_index(avm, context, this, &[])?;
+            assert_eq!(result.resolve(avm, context)?, (-1).into());
+
+            // Focusing a text field selects all of its text.
+            focus_tracker.set(Some(text_field.into()), context);
+            let result = get_begin_index(avm, context, this, &[])?;
+            assert_eq!(result.resolve(avm, context)?, 0.into());
+            let result = get_end_index(avm, context, this, &[])?;
+            assert_eq!(result.resolve(avm, context)?, 5.into());
+
+            set_selection(avm, context, this, &[4.into(), 1.into()])?.resolve(avm, context)?;
+            let result = get_begin_index(avm, context, this, &[])?;
+            assert_eq!(result.resolve(avm, context)?, 1.into());
+            let result = get_caret_index(avm, context, this, &[])?;
+            assert_eq!(result.resolve(avm, context)?, 1.into());
+
+            let result = set_focus(avm, context, this, &[Value::Null])?;
+            assert_eq!(result.resolve(avm, context)?, true.into());
+            let result = get_focus(avm, context, this, &[])?;
+            assert_eq!(result.resolve(avm, context)?, Value::Null);
+            Ok(())
+        })
+    }
+}
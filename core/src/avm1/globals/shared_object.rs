@@ -1,14 +1,18 @@
 use crate::avm1::error::Error;
 use crate::avm1::function::{Executable, FunctionObject};
 use crate::avm1::return_value::ReturnValue;
-use crate::avm1::{Avm1, Object, TObject, Value};
+use crate::avm1::{Avm1, Object, ScriptObject, TObject, Value};
 use crate::context::UpdateContext;
 use enumset::EnumSet;
 use gc_arena::MutationContext;
 
+use crate::avm1::date_object::DateObject;
+use crate::avm1::globals::xml;
 use crate::avm1::shared_object::SharedObject;
-
 use json::JsonValue;
+use swf::amf0::read::read_lso;
+use swf::amf0::types::{Lso, Value as AmfValue};
+use swf::amf0::write::write_lso;
 
 pub fn delete_all<'gc>(
     _avm: &mut Avm1<'gc>,
@@ -30,40 +34,168 @@ pub fn get_disk_usage<'gc>(
     Ok(Value::Undefined.into())
 }
 
-/// Serialize an Object and any children to a JSON object
-/// It would be best if this was implemented via serde but due to avm and context it can't
-/// Undefined fields aren't serialized
-fn recursive_serialize<'gc>(
+/// Converts the properties of an object to AMF0 values.
+///
+/// `objects` holds every object written so far, in the order they were written, so that
+/// objects that appear more than once (including cycles) can be written as references.
+fn serialize_properties<'gc>(
     avm: &mut Avm1<'gc>,
     action_context: &mut UpdateContext<'_, 'gc, '_>,
-    obj: Object<'gc>,
-    json_obj: &mut JsonValue,
-) {
-    for k in &obj.get_keys(avm) {
-        if let Ok(elem) = obj.get(k, avm, action_context) {
-            match elem {
-                Value::Undefined => {}
-                Value::Null => json_obj[k] = JsonValue::Null,
-                Value::Bool(b) => json_obj[k] = b.into(),
-                Value::Number(f) => json_obj[k] = f.into(),
-                Value::String(s) => json_obj[k] = s.into(),
-                Value::Object(o) => {
-                    // Don't attempt to serialize functions
-                    if !o
-                        .is_instance_of(avm, action_context, o, avm.prototypes.function)
-                        .unwrap_or_default()
-                    {
-                        let mut sub_data_json = JsonValue::new_object();
-                        recursive_serialize(avm, action_context, o, &mut sub_data_json);
-                        json_obj[k] = sub_data_json;
-                    }
-                }
+    object: Object<'gc>,
+    objects: &mut Vec<Object<'gc>>,
+) -> Result<Vec<(String, AmfValue)>, Error<'gc>> {
+    let mut properties = Vec::new();
+    for key in object.get_keys(avm) {
+        let value = object.get(&key, avm, action_context)?;
+        if let Some(value) = serialize_value(avm, action_context, value, objects)? {
+            properties.push((key, value));
+        }
+    }
+    Ok(properties)
+}
+
+/// Converts a value to AMF0, returning `None` for values that aren't saved.
+pub(crate) fn serialize_value<'gc>(
+    avm: &mut Avm1<'gc>,
+    action_context: &mut UpdateContext<'_, 'gc, '_>,
+    value: Value<'gc>,
+    objects: &mut Vec<Object<'gc>>,
+) -> Result<Option<AmfValue>, Error<'gc>> {
+    let object = match value {
+        Value::Undefined => return Ok(Some(AmfValue::Undefined)),
+        Value::Null => return Ok(Some(AmfValue::Null)),
+        Value::Bool(b) => return Ok(Some(AmfValue::Bool(b))),
+        Value::Number(f) => return Ok(Some(AmfValue::Number(f))),
+        Value::String(s) => return Ok(Some(AmfValue::String(s))),
+        Value::Object(object) => object,
+    };
+
+    // Functions and display objects can't be saved.
+    if object.as_executable().is_some() || object.as_display_object().is_some() {
+        return Ok(None);
+    }
+
+    if let Some(index) = objects.iter().position(|o| Object::ptr_eq(*o, object)) {
+        if index <= usize::from(u16::MAX) {
+            return Ok(Some(AmfValue::Reference(index as u16)));
+        }
+    }
+
+    if let Some(date) = object.as_date_object() {
+        return Ok(Some(AmfValue::Date(date.date_time(), 0)));
+    }
+
+    if let Some(node) = object.as_xml_node() {
+        if object.is_instance_of(avm, action_context, object, avm.prototypes.xml)? {
+            let xml = node
+                .into_string(&mut xml::is_as2_compatible)
+                .unwrap_or_default();
+            return Ok(Some(AmfValue::XMLDocument(xml)));
+        }
+    }
+
+    objects.push(object);
+
+    if object.is_instance_of(avm, action_context, object, avm.prototypes.array)? {
+        let mut properties = Vec::new();
+        for (i, element) in object.array().into_iter().enumerate() {
+            if let Value::Undefined = element {
+                continue;
+            }
+            if let Some(element) = serialize_value(avm, action_context, element, objects)? {
+                properties.push((i.to_string(), element));
+            }
+        }
+        properties.extend(serialize_properties(avm, action_context, object, objects)?);
+        return Ok(Some(AmfValue::ECMAArray {
+            length: object.length() as u32,
+            properties,
+        }));
+    }
+
+    let properties = serialize_properties(avm, action_context, object, objects)?;
+    Ok(Some(AmfValue::Object(properties)))
+}
+
+/// Creates the properties of an object from AMF0 values.
+fn deserialize_properties<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+    properties: &[(String, AmfValue)],
+    objects: &mut Vec<Object<'gc>>,
+) -> Result<(), Error<'gc>> {
+    for (key, value) in properties {
+        let value = deserialize_value(avm, context, value, objects)?;
+        match key.parse::<usize>() {
+            Ok(index) if object.length() > index => {
+                object.set_array_element(index, value, context.gc_context);
             }
+            _ => object.define_value(context.gc_context, key, value, EnumSet::empty()),
         }
     }
+    Ok(())
+}
+
+/// Converts an AMF0 value to an AVM1 value.
+///
+/// `objects` holds every object read so far, in the order they were read, to resolve references.
+pub(crate) fn deserialize_value<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    value: &AmfValue,
+    objects: &mut Vec<Object<'gc>>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = match value {
+        AmfValue::Number(f) => Value::Number(*f),
+        AmfValue::Bool(b) => Value::Bool(*b),
+        AmfValue::String(s) => Value::String(s.clone()),
+        AmfValue::Null => Value::Null,
+        AmfValue::Undefined | AmfValue::Unsupported => Value::Undefined,
+        AmfValue::Reference(index) => objects
+            .get(usize::from(*index))
+            .map_or(Value::Undefined, |object| Value::Object(*object)),
+        AmfValue::Object(properties) | AmfValue::TypedObject(_, properties) => {
+            let object: Object<'gc> =
+                ScriptObject::object(context.gc_context, Some(avm.prototypes.object)).into();
+            objects.push(object);
+            deserialize_properties(avm, context, object, properties, objects)?;
+            object.into()
+        }
+        AmfValue::ECMAArray { length, properties } => {
+            let array: Object<'gc> =
+                ScriptObject::array(context.gc_context, Some(avm.prototypes.array)).into();
+            objects.push(array);
+            array.set_length(context.gc_context, *length as usize);
+            deserialize_properties(avm, context, array, properties, objects)?;
+            array.into()
+        }
+        AmfValue::StrictArray(values) => {
+            let array: Object<'gc> =
+                ScriptObject::array(context.gc_context, Some(avm.prototypes.array)).into();
+            objects.push(array);
+            array.set_length(context.gc_context, values.len());
+            for (i, value) in values.iter().enumerate() {
+                let value = deserialize_value(avm, context, value, objects)?;
+                array.set_array_element(i, value, context.gc_context);
+            }
+            array.into()
+        }
+        AmfValue::Date(time, _) => {
+            DateObject::with_date_time(context.gc_context, Some(avm.prototypes.date), *time).into()
+        }
+        AmfValue::XMLDocument(xml) => {
+            let xml_proto = avm.prototypes.xml;
+            let object = xml_proto.new(avm, context, xml_proto, &[])?;
+            let _ = xml::xml_constructor(avm, context, object, &[xml.clone().into()])?;
+            object.into()
+        }
+    };
+    Ok(value)
 }
 
 /// Deserialize an Object and any children from a JSON object
+/// This is used to load shared objects saved by older versions, which used JSON rather than AMF0
 /// It would be best if this was implemented via serde but due to avm and context it can't
 /// Undefined fields aren't deserialized
 fn recursive_deserialize<'gc>(
@@ -166,9 +298,21 @@ pub fn get_local<'gc>(
     let _ = crate::avm1::globals::object::constructor(avm, action_context, data, &[])?;
 
     // Load the data object from storage if it existed prior
-    if let Some(saved) = action_context.storage.get_string(&name) {
-        if let Ok(json_data) = json::parse(&saved) {
-            recursive_deserialize(json_data, avm, data, action_context);
+    if let Some(saved) = action_context.storage.get(&name) {
+        match read_lso(&saved) {
+            Ok(lso) => {
+                deserialize_properties(avm, action_context, data, &lso.body, &mut vec![])?;
+            }
+            Err(e) => {
+                if let Some(json_data) = std::str::from_utf8(&saved)
+                    .ok()
+                    .and_then(|saved| json::parse(saved).ok())
+                {
+                    recursive_deserialize(json_data, avm, data, action_context);
+                } else {
+                    log::warn!("Unable to read shared object {}: {}", name, e);
+                }
+            }
         }
     }
 
@@ -348,16 +492,22 @@ pub fn flush<'gc>(
         .get("data", avm, action_context)?
         .coerce_to_object(avm, action_context);
 
-    let mut data_json = JsonValue::new_object();
-    recursive_serialize(avm, action_context, data, &mut data_json);
+    let body = serialize_properties(avm, action_context, data, &mut vec![])?;
 
     let this_obj = this.as_shared_object().unwrap();
     let name = this_obj.get_name();
 
-    Ok(action_context
-        .storage
-        .put_string(&name, data_json.dump())
-        .into())
+    let lso = Lso {
+        name: name.clone(),
+        body,
+    };
+    let mut bytes = Vec::new();
+    if let Err(e) = write_lso(&lso, &mut bytes) {
+        log::warn!("Unable to write shared object {}: {}", name, e);
+        return Ok(false.into());
+    }
+
+    Ok(action_context.storage.put(&name, &bytes).into())
 }
 
 pub fn get_size<'gc>(
@@ -477,3 +627,63 @@ pub fn constructor<'gc>(
 ) -> Result<ReturnValue<'gc>, Error<'gc>> {
     Ok(Value::Undefined.into())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm1::test_utils::with_avm;
+
+    #[test]
+    fn round_trip_data() {
+        with_avm(8, |avm, context, _root| -> Result<(), Error> {
+            let data: Object<'_> =
+                ScriptObject::object(context.gc_context, Some(avm.prototypes.object)).into();
+            let array: Object<'_> =
+                ScriptObject::array(context.gc_context, Some(avm.prototypes.array)).into();
+            array.set_array_element(0, "a".into(), context.gc_context);
+            array.set_array_element(2, 3.0.into(), context.gc_context);
+            let date = DateObject::with_date_time(
+                context.gc_context,
+                Some(avm.prototypes.date),
+                949363200000.0,
+            );
+            data.set("array", array.into(), avm, context)?;
+            data.set("date", Value::Object(date.into()), avm, context)?;
+            data.set("nothing", Value::Undefined, avm, context)?;
+            data.set("self", data.into(), avm, context)?;
+
+            let lso = Lso {
+                name: "test".to_string(),
+                body: serialize_properties(avm, context, data, &mut vec![])?,
+            };
+            let mut bytes = Vec::new();
+            write_lso(&lso, &mut bytes).unwrap();
+            let lso = read_lso(&bytes).unwrap();
+
+            let loaded: Object<'_> =
+                ScriptObject::object(context.gc_context, Some(avm.prototypes.object)).into();
+            deserialize_properties(avm, context, loaded, &lso.body, &mut vec![])?;
+
+            let array = loaded
+                .get("array", avm, context)?
+                .coerce_to_object(avm, context);
+            assert_eq!(array.length(), 3);
+            assert_eq!(
+                array.array(),
+                vec!["a".into(), Value::Undefined, 3.0.into()]
+            );
+            let date = loaded
+                .get("date", avm, context)?
+                .coerce_to_object(avm, context);
+            assert_eq!(date.as_date_object().unwrap().date_time(), 949363200000.0);
+            assert!(loaded.has_own_property(avm, context, "nothing"));
+            let copy = loaded
+                .get("self", avm, context)?
+                .coerce_to_object(avm, context);
+            assert_eq!(copy.get("self", avm, context)?, copy.into());
+            assert_eq!(copy.get("array", avm, context)?, array.into());
+
+            Ok(())
+        });
+    }
+}
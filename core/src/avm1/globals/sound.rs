@@ -1,11 +1,12 @@
 //! AVM1 Sound object
-//! TODO: Sound position, transform, loadSound
 
 use crate::avm1::error::Error;
 use crate::avm1::function::Executable;
 use crate::avm1::property::Attribute::*;
 use crate::avm1::return_value::ReturnValue;
-use crate::avm1::{Avm1, Object, SoundObject, TObject, UpdateContext, Value};
+use crate::avm1::{Avm1, Object, ScriptObject, SoundObject, TObject, UpdateContext, Value};
+use crate::backend::audio::SoundTransform;
+use crate::backend::navigator::RequestOptions;
 use crate::character::Character;
 use crate::display_object::TDisplayObject;
 use gc_arena::MutationContext;
@@ -86,7 +87,7 @@ pub fn create_proto<'gc>(
     );
 
     object.as_script_object().unwrap().force_set_function(
-        "get_transform",
+        "getTransform",
         get_transform,
         gc_context,
         DontDelete | ReadOnly | DontEnum,
@@ -94,7 +95,7 @@ pub fn create_proto<'gc>(
     );
 
     object.as_script_object().unwrap().force_set_function(
-        "get_volume",
+        "getVolume",
         get_volume,
         gc_context,
         DontDelete | ReadOnly | DontEnum,
@@ -102,7 +103,7 @@ pub fn create_proto<'gc>(
     );
 
     object.as_script_object().unwrap().force_set_function(
-        "load_sound",
+        "loadSound",
         load_sound,
         gc_context,
         DontDelete | ReadOnly | DontEnum,
@@ -118,7 +119,7 @@ pub fn create_proto<'gc>(
     );
 
     object.as_script_object().unwrap().force_set_function(
-        "set_pan",
+        "setPan",
         set_pan,
         gc_context,
         DontDelete | ReadOnly | DontEnum,
@@ -126,7 +127,7 @@ pub fn create_proto<'gc>(
     );
 
     object.as_script_object().unwrap().force_set_function(
-        "set_transform",
+        "setTransform",
         set_transform,
         gc_context,
         DontDelete | ReadOnly | DontEnum,
@@ -134,7 +135,7 @@ pub fn create_proto<'gc>(
     );
 
     object.as_script_object().unwrap().force_set_function(
-        "set_volume",
+        "setVolume",
         set_volume,
         gc_context,
         DontDelete | ReadOnly | DontEnum,
@@ -245,74 +246,150 @@ fn get_bytes_total<'gc>(
     }
 }
 
+/// The sound transform affected by this `Sound` object.
+///
+/// A `Sound` object with a target clip controls the transform of that clip; otherwise it controls
+/// the transform applied to every sound in the movie.
+fn sound_transform<'gc>(
+    context: &UpdateContext<'_, 'gc, '_>,
+    sound_object: SoundObject<'gc>,
+) -> SoundTransform {
+    if let Some(owner) = sound_object.owner() {
+        owner.sound_transform()
+    } else {
+        context.audio_manager.global_sound_transform()
+    }
+}
+
+fn set_sound_transform<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    sound_object: SoundObject<'gc>,
+    transform: SoundTransform,
+) {
+    if let Some(mut owner) = sound_object.owner() {
+        owner.set_sound_transform(context.gc_context, transform);
+    } else {
+        context.audio_manager.set_global_sound_transform(transform);
+    }
+}
+
 fn get_pan<'gc>(
     _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: Object<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error<'gc>> {
-    log::warn!("Sound.getPan: Unimplemented");
-    Ok(0.into())
+    if let Some(sound_object) = this.as_sound_object() {
+        Ok(sound_transform(context, sound_object).pan().into())
+    } else {
+        log::warn!("Sound.getPan: this is not a Sound");
+        Ok(Value::Undefined.into())
+    }
 }
 
 fn get_transform<'gc>(
-    _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: Object<'gc>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error<'gc>> {
-    log::warn!("Sound.getTransform: Unimplemented");
-    Ok(Value::Undefined.into())
+    if let Some(sound_object) = this.as_sound_object() {
+        let transform = sound_transform(context, sound_object);
+        let out = ScriptObject::object(context.gc_context, Some(avm.prototypes.object));
+        out.set("ll", transform.left_to_left.into(), avm, context)?;
+        out.set("lr", transform.left_to_right.into(), avm, context)?;
+        out.set("rl", transform.right_to_left.into(), avm, context)?;
+        out.set("rr", transform.right_to_right.into(), avm, context)?;
+        Ok(out.into())
+    } else {
+        log::warn!("Sound.getTransform: this is not a Sound");
+        Ok(Value::Undefined.into())
+    }
 }
 
 fn get_volume<'gc>(
     _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: Object<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error<'gc>> {
-    log::warn!("Sound.getVolume: Unimplemented");
-    Ok(100.into())
+    if let Some(sound_object) = this.as_sound_object() {
+        Ok(sound_transform(context, sound_object).volume.into())
+    } else {
+        log::warn!("Sound.getVolume: this is not a Sound");
+        Ok(Value::Undefined.into())
+    }
 }
 
 fn id3<'gc>(
     avm: &mut Avm1<'gc>,
     _context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: Object<'gc>,
+    this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error<'gc>> {
     if avm.current_swf_version() >= 6 {
-        log::warn!("Sound.id3: Unimplemented");
+        if let Some(sound_object) = this.as_sound_object() {
+            if let Some(id3) = sound_object.id3() {
+                return Ok(id3.into());
+            }
+        } else {
+            log::warn!("Sound.id3: this is not a Sound");
+        }
     }
     Ok(Value::Undefined.into())
 }
 
 fn load_sound<'gc>(
     avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error<'gc>> {
     if avm.current_swf_version() >= 6 {
-        log::warn!("Sound.loadSound: Unimplemented");
+        if let Some(sound_object) = this.as_sound_object() {
+            let url = args
+                .get(0)
+                .unwrap_or(&Value::Undefined)
+                .coerce_to_string(avm, context)?;
+            let is_streaming = args
+                .get(1)
+                .unwrap_or(&Value::Undefined)
+                .as_bool(avm.current_swf_version());
+
+            let fetch = context.navigator.fetch(&url, RequestOptions::get());
+            let active_clip = avm.target_clip_or_root();
+            let process = context.load_manager.load_sound_into_object(
+                context.player.clone().unwrap(),
+                sound_object,
+                active_clip,
+                is_streaming,
+                fetch,
+            );
+
+            context.navigator.spawn_future(process);
+        } else {
+            log::warn!("Sound.loadSound: this is not a Sound");
+        }
     }
     Ok(Value::Undefined.into())
 }
 
 fn position<'gc>(
     avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error<'gc>> {
     if avm.current_swf_version() >= 6 {
         if let Some(sound_object) = this.as_sound_object() {
-            // TODO: The position is "sticky"; even if the sound is no longer playing, it should return
-            // the previous valid position.
-            // Needs some audio backend work for this.
+            // The position is "sticky"; once the sound stops playing, the last valid position
+            // is returned.
             if sound_object.sound().is_some() {
-                if let Some(_sound_instance) = sound_object.sound_instance() {
-                    log::warn!("Sound.position: Unimplemented");
+                if let Some(position) = sound_object
+                    .sound_instance()
+                    .and_then(|instance| context.audio.get_sound_position(instance))
+                {
+                    sound_object.set_position(context.gc_context, position);
                 }
                 return Ok(sound_object.position().into());
             }
@@ -324,32 +401,81 @@ fn position<'gc>(
 }
 
 fn set_pan<'gc>(
-    _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error<'gc>> {
-    log::warn!("Sound.setPan: Unimplemented");
+    let pan = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_i32(avm, context)?;
+    if let Some(sound_object) = this.as_sound_object() {
+        let mut transform = sound_transform(context, sound_object);
+        transform.set_pan(pan);
+        set_sound_transform(context, sound_object, transform);
+    } else {
+        log::warn!("Sound.setPan: this is not a Sound");
+    }
     Ok(Value::Undefined.into())
 }
 
 fn set_transform<'gc>(
-    _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error<'gc>> {
-    log::warn!("Sound.setTransform: Unimplemented");
+    fn set_channel<'gc>(
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        object: Object<'gc>,
+        property: &str,
+        out: &mut i32,
+    ) -> Result<(), Error<'gc>> {
+        // The parameters are set only if the property exists on the object itself (prototype excluded).
+        if object.has_own_property(avm, context, property) {
+            *out = object
+                .get(property, avm, context)?
+                .coerce_to_i32(avm, context)?;
+        }
+        Ok(())
+    }
+
+    let object = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_object(avm, context);
+    if let Some(sound_object) = this.as_sound_object() {
+        let mut transform = sound_transform(context, sound_object);
+        set_channel(avm, context, object, "ll", &mut transform.left_to_left)?;
+        set_channel(avm, context, object, "lr", &mut transform.left_to_right)?;
+        set_channel(avm, context, object, "rl", &mut transform.right_to_left)?;
+        set_channel(avm, context, object, "rr", &mut transform.right_to_right)?;
+        set_sound_transform(context, sound_object, transform);
+    } else {
+        log::warn!("Sound.setTransform: this is not a Sound");
+    }
     Ok(Value::Undefined.into())
 }
 
 fn set_volume<'gc>(
-    _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error<'gc>> {
-    log::warn!("Sound.setVolume: Unimplemented");
+    let volume = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_i32(avm, context)?;
+    if let Some(sound_object) = this.as_sound_object() {
+        let mut transform = sound_transform(context, sound_object);
+        transform.volume = volume;
+        set_sound_transform(context, sound_object, transform);
+    } else {
+        log::warn!("Sound.setVolume: this is not a Sound");
+    }
     Ok(Value::Undefined.into())
 }
 
@@ -377,7 +503,8 @@ fn start<'gc>(
     use swf::{SoundEvent, SoundInfo};
     if let Some(sound_object) = this.as_sound_object() {
         if let Some(sound) = sound_object.sound() {
-            let sound_instance = context.audio.start_sound(
+            let sound_instance = context.audio_manager.start_sound(
+                context.audio,
                 sound,
                 &SoundInfo {
                     event: SoundEvent::Start,
@@ -390,9 +517,11 @@ fn start<'gc>(
                     num_loops: loops,
                     envelope: None,
                 },
+                sound_object.owner(),
             );
             if let Ok(sound_instance) = sound_instance {
                 sound_object.set_sound_instance(context.gc_context, Some(sound_instance));
+                sound_object.set_position(context.gc_context, 0);
             }
         } else {
             log::warn!("Sound.start: No sound is attached");
@@ -435,12 +564,11 @@ fn stop<'gc>(
                     name
                 )
             }
-        } else if let Some(_owner) = sound.owner() {
+        } else if let Some(owner) = sound.owner() {
             // Usage 2: Stop all sound running within a given clip.
-            // TODO: We just stop the last played sound for now.
-            if let Some(sound_instance) = sound.sound_instance() {
-                context.audio.stop_sound(sound_instance);
-            }
+            context
+                .audio_manager
+                .stop_sounds_with_display_object(context.audio, owner);
         } else {
             // Usage 3: If there is no owner and no name, this call acts like `stopAllSounds()`.
             context.audio.stop_all_sounds();
@@ -1,29 +1,23 @@
 //! Stage object
-//!
-//! TODO: This is a very rough stub with not much implementation.
 use crate::avm1::error::Error;
 use crate::avm1::function::Executable;
+use crate::avm1::listeners::Listeners;
 use crate::avm1::property::Attribute;
 use crate::avm1::return_value::ReturnValue;
 use crate::avm1::{Avm1, Object, ScriptObject, TObject, UpdateContext, Value};
+use crate::stage::StageAlign;
 
 use gc_arena::MutationContext;
 
 pub fn create_stage_object<'gc>(
     gc_context: MutationContext<'gc, '_>,
     proto: Option<Object<'gc>>,
-    _array_proto: Option<Object<'gc>>,
     fn_proto: Option<Object<'gc>>,
+    listener: &Listeners<'gc>,
 ) -> Object<'gc> {
     let mut stage = ScriptObject::object(gc_context, proto);
 
-    stage.force_set_function(
-        "addListener",
-        add_listener,
-        gc_context,
-        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
-        fn_proto,
-    );
+    register_listener!(gc_context, stage, listener, fn_proto, stage);
 
     stage.add_property(
         gc_context,
@@ -41,14 +35,6 @@ pub fn create_stage_object<'gc>(
         Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
     );
 
-    stage.force_set_function(
-        "removeListener",
-        remove_listener,
-        gc_context,
-        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
-        fn_proto,
-    );
-
     stage.add_property(
         gc_context,
         "scaleMode",
@@ -76,33 +62,25 @@ pub fn create_stage_object<'gc>(
     stage.into()
 }
 
-fn add_listener<'gc>(
-    _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: Object<'gc>,
-    _args: &[Value<'gc>],
-) -> Result<ReturnValue<'gc>, Error<'gc>> {
-    log::warn!("Stage.addListener: unimplemented");
-    Ok(Value::Undefined.into())
-}
-
 fn align<'gc>(
     _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error<'gc>> {
-    log::warn!("Stage.align: unimplemented");
-    Ok("".into())
+    Ok(context.stage.align.to_string().into())
 }
 
 fn set_align<'gc>(
-    _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error<'gc>> {
-    log::warn!("Stage.align: unimplemented");
+    if let Some(align) = args.get(0) {
+        let align = align.coerce_to_string(avm, context)?;
+        context.stage.align = StageAlign::from(&*align);
+    }
     Ok(Value::Undefined.into())
 }
 
@@ -112,56 +90,52 @@ fn height<'gc>(
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error<'gc>> {
-    Ok(context.stage_size.1.to_pixels().into())
-}
-
-fn remove_listener<'gc>(
-    _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: Object<'gc>,
-    _args: &[Value<'gc>],
-) -> Result<ReturnValue<'gc>, Error<'gc>> {
-    log::warn!("Stage.removeListener: unimplemented");
-    Ok("".into())
+    Ok(context.stage.stage_size().1.into())
 }
 
 fn scale_mode<'gc>(
     _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error<'gc>> {
-    log::warn!("Stage.scaleMode: unimplemented");
-    Ok("noScale".into())
+    Ok(context.stage.scale_mode.to_string().into())
 }
 
 fn set_scale_mode<'gc>(
-    _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error<'gc>> {
-    log::warn!("Stage.scaleMode: unimplemented");
+    if let Some(scale_mode) = args.get(0) {
+        // Unknown scale modes are ignored.
+        let scale_mode = scale_mode.coerce_to_string(avm, context)?;
+        if let Ok(scale_mode) = scale_mode.parse() {
+            context.stage.scale_mode = scale_mode;
+        }
+    }
     Ok(Value::Undefined.into())
 }
 
 fn show_menu<'gc>(
     _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error<'gc>> {
-    log::warn!("Stage.showMenu: unimplemented");
-    Ok(true.into())
+    Ok(context.stage.show_menu.into())
 }
 
 fn set_show_menu<'gc>(
-    _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error<'gc>> {
-    log::warn!("Stage.showMenu: unimplemented");
+    if let Some(show_menu) = args.get(0) {
+        context.stage.show_menu = show_menu.as_bool(avm.current_swf_version());
+    }
     Ok(Value::Undefined.into())
 }
 
@@ -171,5 +145,5 @@ fn width<'gc>(
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error<'gc>> {
-    Ok(context.stage_size.0.to_pixels().into())
+    Ok(context.stage.stage_size().0.into())
 }
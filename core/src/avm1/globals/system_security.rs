@@ -4,37 +4,61 @@ use crate::avm1::object::Object;
 use crate::avm1::return_value::ReturnValue;
 use crate::avm1::{Avm1, ScriptObject, TObject, Value};
 use crate::context::UpdateContext;
+use crate::display_object::TDisplayObject;
+use crate::policy;
 use enumset::EnumSet;
 use gc_arena::MutationContext;
 use std::convert::Into;
+use url::Url;
 
 fn allow_domain<'gc>(
-    _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error<'gc>> {
-    log::warn!("System.security.allowDomain() not implemented");
+    let movie_url = avm
+        .target_clip_or_root()
+        .movie()
+        .and_then(|movie| movie.url().and_then(|url| Url::parse(url).ok()));
+
+    if let Some(movie_url) = movie_url {
+        for domain in args {
+            let domain = domain.coerce_to_string(avm, context)?.to_string();
+            context.policies.allow_domain(&movie_url, domain);
+        }
+    }
+
     Ok(Value::Undefined.into())
 }
 
 fn allow_insecure_domain<'gc>(
-    _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error<'gc>> {
-    log::warn!("System.security.allowInsecureDomain() not implemented");
-    Ok(Value::Undefined.into())
+    // HTTP and HTTPS movies aren't told apart when checking access between movies.
+    allow_domain(avm, context, this, args)
 }
 
 fn load_policy_file<'gc>(
-    _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error<'gc>> {
-    log::warn!("System.security.allowInsecureDomain() not implemented");
+    let url = match args.get(0) {
+        Some(Value::Undefined) | Some(Value::Null) | None => return Ok(Value::Undefined.into()),
+        Some(url) => url.coerce_to_string(avm, context)?,
+    };
+    let url = policy::resolve_url(avm.target_clip_or_root().movie().as_deref(), &url);
+
+    match Url::parse(&url) {
+        Ok(url) => context.policies.load_policy_file(url),
+        Err(e) => log::warn!("System.security.loadPolicyFile: Invalid URL {}: {}", url, e),
+    }
+
     Ok(Value::Undefined.into())
 }
 
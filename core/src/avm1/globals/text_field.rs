@@ -415,6 +415,320 @@ pub fn set_auto_size<'gc>(
     Ok(Value::Undefined.into())
 }
 
+pub fn get_type<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let Some(etext) = this
+        .as_display_object()
+        .and_then(|dobj| dobj.as_edit_text())
+    {
+        return Ok(if etext.is_editable() {
+            "input".to_string().into()
+        } else {
+            "dynamic".to_string().into()
+        });
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn set_type<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let set_type = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(avm, context)?
+        .to_lowercase();
+
+    if let Some(etext) = this
+        .as_display_object()
+        .and_then(|dobj| dobj.as_edit_text())
+    {
+        // Unknown types leave the text field unchanged.
+        match set_type.as_str() {
+            "input" => etext.set_editable(context.gc_context, true),
+            "dynamic" => etext.set_editable(context.gc_context, false),
+            _ => {}
+        }
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn selectable<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let Some(etext) = this
+        .as_display_object()
+        .and_then(|dobj| dobj.as_edit_text())
+    {
+        return Ok(etext.is_selectable().into());
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn set_selectable<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let is_selectable = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .as_bool(avm.current_swf_version());
+
+    if let Some(etext) = this
+        .as_display_object()
+        .and_then(|dobj| dobj.as_edit_text())
+    {
+        etext.set_selectable(context.gc_context, is_selectable);
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn password<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let Some(etext) = this
+        .as_display_object()
+        .and_then(|dobj| dobj.as_edit_text())
+    {
+        return Ok(etext.is_password().into());
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn set_password<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let is_password = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .as_bool(avm.current_swf_version());
+
+    if let Some(etext) = this
+        .as_display_object()
+        .and_then(|dobj| dobj.as_edit_text())
+    {
+        etext.set_password(is_password, context);
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn max_chars<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let Some(etext) = this
+        .as_display_object()
+        .and_then(|dobj| dobj.as_edit_text())
+    {
+        // An unlimited `maxChars` is `null`.
+        return Ok(match etext.max_chars() {
+            0 => Value::Null.into(),
+            max_chars => max_chars.into(),
+        });
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn set_max_chars<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let max_chars = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_i32(avm, context)?;
+
+    if let Some(etext) = this
+        .as_display_object()
+        .and_then(|dobj| dobj.as_edit_text())
+    {
+        etext.set_max_chars(context.gc_context, max_chars.max(0));
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn restrict<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let Some(etext) = this
+        .as_display_object()
+        .and_then(|dobj| dobj.as_edit_text())
+    {
+        return Ok(match etext.restrict() {
+            Some(restrict) => restrict.into(),
+            None => Value::Null.into(),
+        });
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn set_restrict<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let restrict = match args.get(0) {
+        None | Some(Value::Undefined) | Some(Value::Null) => None,
+        Some(v) => Some(v.coerce_to_string(avm, context)?.into_owned()),
+    };
+
+    if let Some(etext) = this
+        .as_display_object()
+        .and_then(|dobj| dobj.as_edit_text())
+    {
+        etext.set_restrict(context.gc_context, restrict);
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn scroll<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let Some(etext) = this
+        .as_display_object()
+        .and_then(|dobj| dobj.as_edit_text())
+    {
+        return Ok((etext.scroll() as f64).into());
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn set_scroll<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let scroll = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_i32(avm, context)?;
+
+    if let Some(etext) = this
+        .as_display_object()
+        .and_then(|dobj| dobj.as_edit_text())
+    {
+        etext.set_scroll(context.gc_context, scroll.max(1) as usize);
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn max_scroll<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let Some(etext) = this
+        .as_display_object()
+        .and_then(|dobj| dobj.as_edit_text())
+    {
+        return Ok((etext.max_scroll() as f64).into());
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn bottom_scroll<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let Some(etext) = this
+        .as_display_object()
+        .and_then(|dobj| dobj.as_edit_text())
+    {
+        return Ok((etext.bottom_scroll() as f64).into());
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn mouse_wheel_enabled<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    if let Some(etext) = this
+        .as_display_object()
+        .and_then(|dobj| dobj.as_edit_text())
+    {
+        return Ok(etext.mouse_wheel_enabled().into());
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn set_mouse_wheel_enabled<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let is_enabled = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .as_bool(avm.current_swf_version());
+
+    if let Some(etext) = this
+        .as_display_object()
+        .and_then(|dobj| dobj.as_edit_text())
+    {
+        etext.set_mouse_wheel_enabled(context.gc_context, is_enabled);
+    }
+
+    Ok(Value::Undefined.into())
+}
+
 pub fn create_proto<'gc>(
     gc_context: MutationContext<'gc, '_>,
     proto: Object<'gc>,
@@ -523,6 +837,69 @@ pub fn attach_virtual_properties<'gc>(gc_context: MutationContext<'gc, '_>, obje
         Some(Executable::Native(set_embed_fonts)),
         ReadOnly.into(),
     );
+    object.add_property(
+        gc_context,
+        "type",
+        Executable::Native(get_type),
+        Some(Executable::Native(set_type)),
+        ReadOnly.into(),
+    );
+    object.add_property(
+        gc_context,
+        "selectable",
+        Executable::Native(selectable),
+        Some(Executable::Native(set_selectable)),
+        ReadOnly.into(),
+    );
+    object.add_property(
+        gc_context,
+        "password",
+        Executable::Native(password),
+        Some(Executable::Native(set_password)),
+        ReadOnly.into(),
+    );
+    object.add_property(
+        gc_context,
+        "maxChars",
+        Executable::Native(max_chars),
+        Some(Executable::Native(set_max_chars)),
+        ReadOnly.into(),
+    );
+    object.add_property(
+        gc_context,
+        "restrict",
+        Executable::Native(restrict),
+        Some(Executable::Native(set_restrict)),
+        ReadOnly.into(),
+    );
+    object.add_property(
+        gc_context,
+        "scroll",
+        Executable::Native(scroll),
+        Some(Executable::Native(set_scroll)),
+        ReadOnly.into(),
+    );
+    object.add_property(
+        gc_context,
+        "maxscroll",
+        Executable::Native(max_scroll),
+        None,
+        ReadOnly.into(),
+    );
+    object.add_property(
+        gc_context,
+        "bottomScroll",
+        Executable::Native(bottom_scroll),
+        None,
+        ReadOnly.into(),
+    );
+    object.add_property(
+        gc_context,
+        "mouseWheelEnabled",
+        Executable::Native(mouse_wheel_enabled),
+        Some(Executable::Native(set_mouse_wheel_enabled)),
+        ReadOnly.into(),
+    );
 }
 
 fn get_new_text_format<'gc>(
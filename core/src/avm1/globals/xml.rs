@@ -8,6 +8,8 @@ use crate::avm1::script_object::ScriptObject;
 use crate::avm1::xml_object::XMLObject;
 use crate::avm1::{Avm1, Object, TObject, UpdateContext, Value};
 use crate::backend::navigator::RequestOptions;
+use crate::display_object::TDisplayObject;
+use crate::policy;
 use crate::xml;
 use crate::xml::{XMLDocument, XMLNode};
 use enumset::EnumSet;
@@ -36,7 +38,7 @@ pub const XML_MISMATCHED_END: f64 = -10.0;
 /// not. Those nodes are filtered from all attributes that return XML nodes to
 /// act as if those nodes did not exist. For example, `prevSibling` skips
 /// past incompatible nodes, etc.
-fn is_as2_compatible(node: XMLNode<'_>) -> bool {
+pub fn is_as2_compatible(node: XMLNode<'_>) -> bool {
     node.is_document_root() || node.is_element() || node.is_text()
 }
 
@@ -814,8 +816,13 @@ pub fn xml_load<'gc>(
 
         this.set("loaded", false.into(), avm, ac)?;
 
-        let fetch = ac.navigator.fetch(&url, RequestOptions::get());
         let target_clip = avm.target_clip_or_root();
+        let fetch = policy::fetch_data(
+            ac,
+            target_clip.movie().as_deref(),
+            &url,
+            RequestOptions::get(),
+        );
         let process = ac.load_manager.load_xml_into_node(
             ac.player.clone().unwrap(),
             node,
@@ -0,0 +1,206 @@
+//! `XMLSocket` impl
+
+use crate::avm1::error::Error;
+use crate::avm1::globals::xml::xml_constructor;
+use crate::avm1::property::Attribute::*;
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::script_object::ScriptObject;
+use crate::avm1::xml_object::XMLObject;
+use crate::avm1::{Avm1, Object, TObject, UpdateContext, Value};
+use crate::display_object::TDisplayObject;
+use gc_arena::MutationContext;
+use url::Url;
+
+/// The lowest port that `XMLSocket` is allowed to connect to.
+const MIN_PORT: f64 = 1024.0;
+
+/// Implements `XMLSocket`
+pub fn constructor<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    Ok(Value::Undefined.into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let mut object = ScriptObject::object(gc_context, Some(proto));
+
+    object.force_set_function(
+        "connect",
+        connect,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "send",
+        send,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "close",
+        close,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function("onData", on_data, gc_context, DontEnum, Some(fn_proto));
+
+    object.into()
+}
+
+fn connect<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let host = match args.get(0) {
+        Some(Value::Undefined) | Some(Value::Null) | None => {
+            // A null host means the host the movie was loaded from.
+            let movie = avm.target_clip_or_root().movie();
+            movie
+                .as_ref()
+                .and_then(|movie| movie.url())
+                .and_then(|url| Url::parse(url).ok())
+                .and_then(|url| url.host_str().map(str::to_string))
+                .unwrap_or_else(|| "localhost".to_string())
+        }
+        Some(host) => host.coerce_to_string(avm, context)?.into_owned(),
+    };
+    let port = args
+        .get(1)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_f64(avm, context)?;
+
+    if host.is_empty() || !(MIN_PORT..=f64::from(u16::MAX)).contains(&port) {
+        return Ok(false.into());
+    }
+
+    context
+        .xml_sockets
+        .connect(context.sockets, this, &host, port as u16);
+
+    Ok(true.into())
+}
+
+fn send<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let message = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(avm, context)?
+        .into_owned();
+
+    context.xml_sockets.send(context.sockets, this, &message);
+
+    Ok(Value::Undefined.into())
+}
+
+fn close<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    context.xml_sockets.close(context.sockets, this);
+
+    Ok(Value::Undefined.into())
+}
+
+/// The default `onData` handler, which parses the message and passes it to `onXML`.
+fn on_data<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error<'gc>> {
+    let src = args.get(0).cloned().unwrap_or(Value::Undefined);
+
+    let xml = XMLObject::empty_node(context.gc_context, Some(avm.prototypes().xml));
+    xml_constructor(avm, context, xml, &[src])?.resolve(avm, context)?;
+    this.call_method("onXML", &[xml.into()], avm, context)?;
+
+    Ok(Value::Undefined.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm1::test_utils::with_avm;
+    use crate::xml_socket::XMLSockets;
+    use enumset::EnumSet;
+
+    /// Stores the first argument in the `received` property of `this`.
+    fn record<'gc>(
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        this: Object<'gc>,
+        args: &[Value<'gc>],
+    ) -> Result<ReturnValue<'gc>, Error<'gc>> {
+        let value = args.get(0).cloned().unwrap_or(Value::Undefined);
+        this.set("received", value, avm, context)?;
+        Ok(Value::Undefined.into())
+    }
+
+    #[test]
+    fn connect_send_and_receive() {
+        with_avm(8, |avm, context, _root| -> Result<(), Error> {
+            let proto = create_proto(
+                context.gc_context,
+                avm.prototypes().object,
+                avm.prototypes().function,
+            );
+            let mut script_object = ScriptObject::object(context.gc_context, Some(proto));
+            for name in &["onConnect", "onXML"] {
+                script_object.force_set_function(
+                    name,
+                    record,
+                    context.gc_context,
+                    EnumSet::empty(),
+                    Some(avm.prototypes().function),
+                );
+            }
+            let object: Object<'_> = script_object.into();
+
+            assert_eq!(
+                connect(avm, context, object, &["localhost".into(), 80.into()])?
+                    .resolve(avm, context)?,
+                false.into()
+            );
+            assert_eq!(
+                connect(avm, context, object, &[Value::Null, 8080.into()])?
+                    .resolve(avm, context)?,
+                true.into()
+            );
+            XMLSockets::update_sockets(avm, context);
+            assert_eq!(object.get("received", avm, context)?, true.into());
+
+            // The loopback backend echoes everything sent back to the socket.
+            send(avm, context, object, &["<a>1</a>".into()])?.resolve(avm, context)?;
+            XMLSockets::update_sockets(avm, context);
+            let received = object.get("received", avm, context)?;
+            assert_eq!(received.coerce_to_string(avm, context)?, "<a>1</a>");
+
+            close(avm, context, object, &[])?.resolve(avm, context)?;
+            send(avm, context, object, &["ignored".into()])?.resolve(avm, context)?;
+            assert!(context.sockets.poll().is_empty());
+
+            Ok(())
+        })
+    }
+}
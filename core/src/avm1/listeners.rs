@@ -133,31 +133,51 @@ impl<'gc> Listeners<'gc> {
     }
 }
 
+impl<'gc> From<Object<'gc>> for Listeners<'gc> {
+    /// Use an existing array as a list of listeners, such as the `_listeners`
+    /// of an `AsBroadcaster`.
+    fn from(object: Object<'gc>) -> Self {
+        Self(object)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum SystemListener {
     Mouse,
+    Key,
     Ime,
+    Selection,
+    Stage,
 }
 
 #[derive(Clone, Collect, Debug, Copy)]
 #[collect(no_drop)]
 pub struct SystemListeners<'gc> {
     pub mouse: Listeners<'gc>,
+    pub key: Listeners<'gc>,
     pub ime: Listeners<'gc>,
+    pub selection: Listeners<'gc>,
+    pub stage: Listeners<'gc>,
 }
 
 impl<'gc> SystemListeners<'gc> {
     pub fn new(gc_context: MutationContext<'gc, '_>, array_proto: Option<Object<'gc>>) -> Self {
         Self {
             mouse: Listeners::new(gc_context, array_proto),
+            key: Listeners::new(gc_context, array_proto),
             ime: Listeners::new(gc_context, array_proto),
+            selection: Listeners::new(gc_context, array_proto),
+            stage: Listeners::new(gc_context, array_proto),
         }
     }
 
     pub fn get(&self, listener: SystemListener) -> Listeners<'gc> {
         match listener {
             SystemListener::Mouse => self.mouse,
+            SystemListener::Key => self.key,
             SystemListener::Ime => self.ime,
+            SystemListener::Selection => self.selection,
+            SystemListener::Stage => self.stage,
         }
     }
 }
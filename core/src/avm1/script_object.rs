@@ -713,16 +713,28 @@ mod tests {
     use crate::avm1::activation::Activation;
     use crate::avm1::globals::system::SystemProperties;
     use crate::avm1::property::Attribute::*;
-    use crate::backend::audio::NullAudioBackend;
+    use crate::avm2::Avm2;
+    use crate::backend::audio::{AudioManager, NullAudioBackend};
     use crate::backend::input::NullInputBackend;
+    use crate::backend::local_connection::InProcessLocalConnectionBackend;
     use crate::backend::navigator::NullNavigatorBackend;
     use crate::backend::render::NullRenderer;
+    use crate::backend::socket::NullSocketBackend;
     use crate::backend::storage::MemoryStorageBackend;
+    use crate::backend::video::NullVideoBackend;
     use crate::display_object::MovieClip;
+    use crate::external::ExternalInterface;
+    use crate::focus_tracker::FocusTracker;
+    use crate::keyboard::KeyboardState;
     use crate::library::Library;
     use crate::loader::LoadManager;
+    use crate::local_connection::LocalConnections;
+    use crate::policy::PolicyManager;
     use crate::prelude::*;
+    use crate::stage::StageProperties;
     use crate::tag_utils::{SwfMovie, SwfSlice};
+    use crate::timer::Timers;
+    use crate::xml_socket::XMLSockets;
     use gc_arena::rootless_arena;
     use rand::{rngs::SmallRng, SeedableRng};
     use std::collections::{BTreeMap, HashMap};
@@ -751,6 +763,7 @@ mod tests {
                 action_queue: &mut crate::context::ActionQueue::new(),
                 audio: &mut NullAudioBackend::new(),
                 input: &mut NullInputBackend::new(),
+                keyboard: &mut KeyboardState::new(),
                 background_color: &mut Color {
                     r: 0,
                     g: 0,
@@ -764,14 +777,25 @@ mod tests {
                 mouse_hovered_object: None,
                 mouse_position: &(Twips::new(0), Twips::new(0)),
                 drag_object: &mut None,
-                stage_size: (Twips::from_pixels(550.0), Twips::from_pixels(400.0)),
+                stage: &mut StageProperties::new(550, 400),
                 player: None,
                 load_manager: &mut LoadManager::new(),
                 system: &mut SystemProperties::default(),
                 instance_counter: &mut 0,
                 storage: &mut MemoryStorageBackend::default(),
+                video: &mut NullVideoBackend::new(),
+                sockets: &mut NullSocketBackend::new(),
+                local_connection: &mut InProcessLocalConnectionBackend::new(),
                 shared_objects: &mut HashMap::new(),
                 unbound_text_fields: &mut Vec::new(),
+                avm2: &mut Avm2::new(gc_context),
+                focus_tracker: FocusTracker::new(gc_context),
+                audio_manager: &mut AudioManager::new(),
+                timers: &mut Timers::new(),
+                policies: &mut PolicyManager::new(),
+                xml_sockets: &mut XMLSockets::new(),
+                local_connections: &mut LocalConnections::new(),
+                external_interface: &mut ExternalInterface::new(),
             };
 
             root.post_instantiation(&mut avm, &mut context, root, None, false);
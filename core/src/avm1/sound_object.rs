@@ -39,12 +39,16 @@ pub struct SoundObjectData<'gc> {
 
     /// Duration of the currently attached sound in milliseconds.
     duration: u32,
+
+    /// The ID3 tags of a sound loaded via `loadSound`.
+    id3: Option<Object<'gc>>,
 }
 
 unsafe impl<'gc> Collect for SoundObjectData<'gc> {
     fn trace(&self, cc: gc_arena::CollectionContext) {
         self.base.trace(cc);
         self.owner.trace(cc);
+        self.id3.trace(cc);
     }
 }
 
@@ -73,6 +77,7 @@ impl<'gc> SoundObject<'gc> {
                 owner: None,
                 position: 0,
                 duration: 0,
+                id3: None,
             },
         ))
     }
@@ -125,6 +130,14 @@ impl<'gc> SoundObject<'gc> {
         self.0.write(gc_context).position = position;
     }
 
+    pub fn id3(self) -> Option<Object<'gc>> {
+        self.0.read().id3
+    }
+
+    pub fn set_id3(self, gc_context: MutationContext<'gc, '_>, id3: Option<Object<'gc>>) {
+        self.0.write(gc_context).id3 = id3;
+    }
+
     fn base(self) -> ScriptObject<'gc> {
         self.0.read().base
     }
@@ -13,6 +13,7 @@ use enumset::EnumSet;
 use gc_arena::{Collect, GcCell, MutationContext};
 use std::borrow::Cow;
 use std::fmt;
+use swf::BlendMode;
 
 /// The type string for MovieClip objects.
 pub const TYPE_OF_MOVIE_CLIP: &str = "movieclip";
@@ -562,6 +563,10 @@ impl<'gc> DisplayPropertyMap<'gc> {
         property_map.add_property("_xmouse", x_mouse, None);
         property_map.add_property("_ymouse", y_mouse, None);
 
+        // Flash 8 properties, which have no SWF4 index.
+        property_map.add_property("blendMode", blend_mode, Some(set_blend_mode));
+        property_map.add_property("_blendMode", blend_mode, Some(set_blend_mode));
+
         GcCell::allocate(gc_context, property_map)
     }
 
@@ -887,20 +892,23 @@ fn set_high_quality<'gc>(
 
 fn focus_rect<'gc>(
     _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: DisplayObject<'gc>,
 ) -> Result<Value<'gc>, Error<'gc>> {
-    log::warn!("Unimplemented property _focusrect");
-    Ok(Value::Null)
+    Ok(context.focus_tracker.show_focus_rect().into())
 }
 
 fn set_focus_rect<'gc>(
-    _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: DisplayObject<'gc>,
-    _val: Value<'gc>,
+    val: Value<'gc>,
 ) -> Result<(), Error<'gc>> {
-    log::warn!("Unimplemented property _focusrect");
+    // `_focusrect` is global to the player, regardless of the object it is set on.
+    let show_focus_rect = val.as_bool(avm.current_swf_version());
+    context
+        .focus_tracker
+        .set_show_focus_rect(context.gc_context, show_focus_rect);
     Ok(())
 }
 
@@ -960,6 +968,59 @@ fn y_mouse<'gc>(
     Ok(local.1.to_pixels().into())
 }
 
+/// The names of each blend mode, ordered by their SWF value.
+const BLEND_MODE_NAMES: [(BlendMode, &str); 14] = [
+    (BlendMode::Normal, "normal"),
+    (BlendMode::Layer, "layer"),
+    (BlendMode::Multiply, "multiply"),
+    (BlendMode::Screen, "screen"),
+    (BlendMode::Lighten, "lighten"),
+    (BlendMode::Darken, "darken"),
+    (BlendMode::Difference, "difference"),
+    (BlendMode::Add, "add"),
+    (BlendMode::Subtract, "subtract"),
+    (BlendMode::Invert, "invert"),
+    (BlendMode::Alpha, "alpha"),
+    (BlendMode::Erase, "erase"),
+    (BlendMode::Overlay, "overlay"),
+    (BlendMode::HardLight, "hardlight"),
+];
+
+fn blend_mode<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: DisplayObject<'gc>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let blend_mode = this.blend_mode();
+    let name = BLEND_MODE_NAMES
+        .iter()
+        .find(|(mode, _)| *mode == blend_mode)
+        .map(|(_, name)| *name)
+        .unwrap_or("normal");
+    Ok(name.into())
+}
+
+fn set_blend_mode<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    mut this: DisplayObject<'gc>,
+    val: Value<'gc>,
+) -> Result<(), Error<'gc>> {
+    // Blend modes can be set by name, or by their 1-based SWF value.
+    let blend_mode = match val {
+        Value::String(name) => BLEND_MODE_NAMES
+            .iter()
+            .find(|(_, mode_name)| *mode_name == name)
+            .map(|(mode, _)| *mode),
+        Value::Number(n) if n >= 1.0 => BLEND_MODE_NAMES.get(n as usize - 1).map(|(mode, _)| *mode),
+        _ => None,
+    };
+    if let Some(blend_mode) = blend_mode {
+        this.set_blend_mode(context.gc_context, blend_mode);
+    }
+    Ok(())
+}
+
 fn property_coerce_to_number<'gc>(
     avm: &mut Avm1<'gc>,
     context: &mut UpdateContext<'_, 'gc, '_>,
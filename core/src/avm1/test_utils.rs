@@ -2,17 +2,29 @@ use crate::avm1::activation::Activation;
 use crate::avm1::error::Error;
 use crate::avm1::globals::system::SystemProperties;
 use crate::avm1::{Avm1, Object, UpdateContext};
-use crate::backend::audio::NullAudioBackend;
+use crate::avm2::Avm2;
+use crate::backend::audio::{AudioManager, NullAudioBackend};
 use crate::backend::input::NullInputBackend;
-use crate::backend::navigator::NullNavigatorBackend;
+use crate::backend::local_connection::InProcessLocalConnectionBackend;
+use crate::backend::navigator::{NavigatorBackend, NullNavigatorBackend};
 use crate::backend::render::NullRenderer;
+use crate::backend::socket::LoopbackSocketBackend;
 use crate::backend::storage::MemoryStorageBackend;
+use crate::backend::video::NullVideoBackend;
 use crate::context::ActionQueue;
 use crate::display_object::{MovieClip, TDisplayObject};
+use crate::external::ExternalInterface;
+use crate::focus_tracker::FocusTracker;
+use crate::keyboard::KeyboardState;
 use crate::library::Library;
 use crate::loader::LoadManager;
+use crate::local_connection::LocalConnections;
+use crate::policy::PolicyManager;
 use crate::prelude::*;
+use crate::stage::StageProperties;
 use crate::tag_utils::{SwfMovie, SwfSlice};
+use crate::timer::Timers;
+use crate::xml_socket::XMLSockets;
 use gc_arena::{rootless_arena, GcCell, MutationContext};
 use rand::{rngs::SmallRng, SeedableRng};
 use std::collections::{BTreeMap, HashMap};
@@ -26,8 +38,25 @@ where
         Object<'gc>,
     ) -> Result<(), Error<'gc>>,
 {
-    fn in_the_arena<'gc, F>(swf_version: u8, test: F, gc_context: MutationContext<'gc, '_>)
-    where
+    with_avm_and_navigator(swf_version, &mut NullNavigatorBackend::new(), test)
+}
+
+/// Like `with_avm`, but the test runs with the given navigator backend, such as one with a
+/// fixed clock and timezone.
+pub fn with_avm_and_navigator<F>(swf_version: u8, navigator: &mut dyn NavigatorBackend, test: F)
+where
+    F: for<'a, 'gc> FnOnce(
+        &mut Avm1<'gc>,
+        &mut UpdateContext<'a, 'gc, '_>,
+        Object<'gc>,
+    ) -> Result<(), Error<'gc>>,
+{
+    fn in_the_arena<'gc, F>(
+        swf_version: u8,
+        navigator: &mut dyn NavigatorBackend,
+        test: F,
+        gc_context: MutationContext<'gc, '_>,
+    ) where
         F: for<'a> FnOnce(
             &mut Avm1<'gc>,
             &mut UpdateContext<'a, 'gc, '_>,
@@ -51,6 +80,7 @@ where
             rng: &mut SmallRng::from_seed([0u8; 16]),
             audio: &mut NullAudioBackend::new(),
             input: &mut NullInputBackend::new(),
+            keyboard: &mut KeyboardState::new(),
             action_queue: &mut ActionQueue::new(),
             background_color: &mut Color {
                 r: 0,
@@ -59,20 +89,31 @@ where
                 a: 0,
             },
             library: &mut Library::default(),
-            navigator: &mut NullNavigatorBackend::new(),
+            navigator,
             renderer: &mut NullRenderer::new(),
             system_prototypes: avm.prototypes().clone(),
             mouse_hovered_object: None,
             mouse_position: &(Twips::new(0), Twips::new(0)),
             drag_object: &mut None,
-            stage_size: (Twips::from_pixels(550.0), Twips::from_pixels(400.0)),
+            stage: &mut StageProperties::new(550, 400),
             player: None,
             load_manager: &mut LoadManager::new(),
             system: &mut SystemProperties::default(),
             instance_counter: &mut 0,
             storage: &mut MemoryStorageBackend::default(),
+            video: &mut NullVideoBackend::new(),
+            sockets: &mut LoopbackSocketBackend::new(),
+            local_connection: &mut InProcessLocalConnectionBackend::new(),
             shared_objects: &mut HashMap::new(),
             unbound_text_fields: &mut Vec::new(),
+            avm2: &mut Avm2::new(gc_context),
+            focus_tracker: FocusTracker::new(gc_context),
+            audio_manager: &mut AudioManager::new(),
+            timers: &mut Timers::new(),
+            policies: &mut PolicyManager::new(),
+            xml_sockets: &mut XMLSockets::new(),
+            local_connections: &mut LocalConnections::new(),
+            external_interface: &mut ExternalInterface::new(),
         };
         root.post_instantiation(&mut avm, &mut context, root, None, false);
         root.set_name(context.gc_context, "");
@@ -90,7 +131,7 @@ where
         }
     }
 
-    rootless_arena(|gc_context| in_the_arena(swf_version, test, gc_context))
+    rootless_arena(|gc_context| in_the_arena(swf_version, navigator, test, gc_context))
 }
 
 macro_rules! test_method {
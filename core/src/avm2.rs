@@ -0,0 +1,310 @@
+//! ActionScript Virtual Machine 2 (AS3) support
+
+use crate::avm2::function::Executable;
+use crate::avm2::method::BytecodeMethod;
+use crate::avm2::names::{Multiname, Namespace, QName};
+use crate::avm2::scope::{Scope, ScopeClass};
+use crate::avm2::script::{Script, TranslationUnit};
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::stage_object::StageObject;
+use crate::avm2::traits::install_trait;
+use crate::context::UpdateContext;
+use crate::display_object::DisplayObject;
+use gc_arena::{Collect, GcCell, MutationContext};
+use swf::avm2::read::Reader;
+
+#[macro_use]
+mod object;
+
+mod activation;
+mod array_object;
+mod class;
+mod error;
+mod function;
+mod globals;
+mod method;
+mod names;
+mod primitive_object;
+mod property;
+mod scope;
+mod script;
+mod script_object;
+mod stage_object;
+#[cfg(test)]
+mod test_utils;
+#[cfg(test)]
+mod tests;
+mod traits;
+mod value;
+
+pub use crate::avm2::error::Error;
+pub use crate::avm2::globals::SystemPrototypes;
+pub use crate::avm2::object::{Object, TObject};
+pub use crate::avm2::value::Value;
+
+/// The maximum depth of nested AVM2 calls before a script is aborted.
+const MAX_CALL_DEPTH: u16 = 256;
+
+/// The state of an AVM2 interpreter.
+#[derive(Collect)]
+#[collect(no_drop)]
+pub struct Avm2<'gc> {
+    /// The global object holding all builtin classes and functions.
+    globals: Object<'gc>,
+
+    /// System prototypes.
+    system_prototypes: SystemPrototypes<'gc>,
+
+    /// Every script loaded into the player, in load order.
+    scripts: Vec<GcCell<'gc, Script<'gc>>>,
+
+    /// The number of AVM2 calls currently on the Rust stack.
+    call_depth: u16,
+}
+
+impl<'gc> Avm2<'gc> {
+    /// Construct a new AVM interpreter.
+    pub fn new(mc: MutationContext<'gc, '_>) -> Self {
+        let (globals, system_prototypes) = globals::create_globals(mc);
+
+        Self {
+            globals,
+            system_prototypes,
+            scripts: Vec::new(),
+            call_depth: 0,
+        }
+    }
+
+    /// Return the builtin global object.
+    pub fn globals(&self) -> Object<'gc> {
+        self.globals
+    }
+
+    /// Return the system prototypes.
+    pub fn prototypes(&self) -> &SystemPrototypes<'gc> {
+        &self.system_prototypes
+    }
+
+    /// Note that a bytecode method is about to be entered.
+    ///
+    /// Fails if scripts have recursed too deeply.
+    pub fn enter_call(&mut self) -> Result<(), Error<'gc>> {
+        if self.call_depth >= MAX_CALL_DEPTH {
+            return Err(Error::RecursionLimit);
+        }
+
+        self.call_depth += 1;
+        Ok(())
+    }
+
+    /// Note that a bytecode method has returned.
+    pub fn exit_call(&mut self) {
+        self.call_depth = self.call_depth.saturating_sub(1);
+    }
+
+    /// Load an ABC file embedded in a `DoAbc` tag.
+    ///
+    /// All of the file's scripts have their traits installed immediately.
+    /// Unless `is_lazy_initialize` is set, the last script in the file (its
+    /// entry point) is then run.
+    pub fn load_abc(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        abc: &[u8],
+        is_lazy_initialize: bool,
+    ) -> Result<(), Error<'gc>> {
+        let mut reader = Reader::new(abc);
+        let abc_file = reader.read()?;
+        let translation_unit = TranslationUnit::from_abc(abc_file);
+        let num_scripts = translation_unit.abc().scripts.len() as u32;
+
+        let mut scripts = Vec::with_capacity(num_scripts as usize);
+        for script_index in 0..num_scripts {
+            let globals = ScriptObject::object(context.gc_context, context.avm2.globals);
+            let script = GcCell::allocate(
+                context.gc_context,
+                Script::new(globals, translation_unit.clone(), script_index),
+            );
+            let scope = Scope::push_scope(None, globals, ScopeClass::Ordinary, context.gc_context);
+
+            for abc_trait in translation_unit.abc().scripts[script_index as usize]
+                .traits
+                .iter()
+            {
+                install_trait(
+                    context,
+                    &translation_unit,
+                    abc_trait,
+                    Some(scope),
+                    globals,
+                    None,
+                )?;
+            }
+
+            context.avm2.scripts.push(script);
+            scripts.push(script);
+        }
+
+        if !is_lazy_initialize {
+            if let Some(entry_point) = scripts.last() {
+                Self::init_script(context, *entry_point)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Run a script's initializer, if it has not been run already.
+    fn init_script(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        script: GcCell<'gc, Script<'gc>>,
+    ) -> Result<(), Error<'gc>> {
+        if script.read().is_initialized() {
+            return Ok(());
+        }
+
+        script.write(context.gc_context).set_initialized();
+
+        let (globals, translation_unit, script_index) = {
+            let read = script.read();
+            (
+                read.globals(),
+                read.translation_unit().clone(),
+                read.script_index(),
+            )
+        };
+        let init_method = translation_unit.abc().scripts[script_index as usize]
+            .init_method
+            .clone();
+        let method = BytecodeMethod::from_method_index(translation_unit, init_method)
+            .ok_or_else(|| Error::InvalidConstant("Unknown script initializer".to_string()))?;
+        let scope = Scope::push_scope(None, globals, ScopeClass::Ordinary, context.gc_context);
+
+        Executable::from_method(method.into(), Some(scope), None, None).exec(
+            Some(globals),
+            &[],
+            context,
+        )?;
+
+        Ok(())
+    }
+
+    /// Find the global object of the script that defines a given name.
+    ///
+    /// The defining script is initialized if it has not been already. Names
+    /// not defined by any script are resolved against the builtin globals.
+    pub fn find_script_global(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        name: &Multiname,
+    ) -> Result<Option<Object<'gc>>, Error<'gc>> {
+        let local_name = match name.local_name() {
+            Some(local_name) => local_name,
+            None => return Ok(None),
+        };
+
+        let scripts = context.avm2.scripts.clone();
+        for script in scripts {
+            let globals = script.read().globals();
+            let is_defined = name.namespace_set().any(|ns| {
+                if ns.is_any() {
+                    globals.resolve_any(local_name).is_some()
+                } else {
+                    globals.has_own_property(&QName::new(ns.clone(), local_name))
+                }
+            });
+
+            if is_defined {
+                Self::init_script(context, script)?;
+                return Ok(Some(globals));
+            }
+        }
+
+        let globals = context.avm2.globals;
+        if globals.resolve_multiname(name).is_some() {
+            return Ok(Some(globals));
+        }
+
+        Ok(None)
+    }
+
+    /// Look up a class by its fully qualified name, such as
+    /// `flash.display.MovieClip`.
+    pub fn lookup_class(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        class_name: &str,
+    ) -> Result<Option<Object<'gc>>, Error<'gc>> {
+        let qname = match class_name.rfind('.') {
+            Some(split) => QName::new(
+                Namespace::package(&class_name[..split]),
+                &class_name[split + 1..],
+            ),
+            None => QName::new(Namespace::public_namespace(), class_name),
+        };
+        let globals = match Self::find_script_global(context, &qname.clone().into())? {
+            Some(globals) => globals,
+            None => return Ok(None),
+        };
+
+        Ok(globals.get_property(globals, &qname, context)?.as_object())
+    }
+
+    /// Construct the AVM2 side of a display object, as an instance of the
+    /// given class.
+    pub fn construct_display_object(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        display_object: DisplayObject<'gc>,
+        class_object: Object<'gc>,
+    ) -> Result<Object<'gc>, Error<'gc>> {
+        let proto = class_object
+            .get_property(class_object, &QName::dynamic_name("prototype"), context)?
+            .as_object()
+            .unwrap_or(context.avm2.system_prototypes.object);
+        let object: Object<'gc> =
+            StageObject::for_display_object(context.gc_context, display_object, proto).into();
+
+        class::install_instance_traits(
+            context,
+            class_object.as_class().ok_or(Error::NotConstructable)?,
+            object,
+        )?;
+
+        Ok(object)
+    }
+
+    /// Run the constructor of a class on an already-allocated instance.
+    pub fn run_constructor(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        class_object: Object<'gc>,
+        instance: Object<'gc>,
+    ) -> Result<(), Error<'gc>> {
+        let constructor = class_object
+            .as_executable()
+            .ok_or(Error::NotConstructable)?;
+        constructor.exec(Some(instance), &[], context)?;
+
+        Ok(())
+    }
+
+    /// Call a function object, such as a frame script or event handler.
+    pub fn run_callable(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        callable: Object<'gc>,
+        receiver: Option<Object<'gc>>,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        callable.call(receiver, args, context)
+    }
+
+    /// Dispatch a new event of the given type, such as `enterFrame`, to the
+    /// listeners of a target.
+    pub fn dispatch_event(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        target: Object<'gc>,
+        event_type: &str,
+    ) -> Result<bool, Error<'gc>> {
+        let event_class =
+            Self::lookup_class(context, "flash.events.Event")?.ok_or(Error::NotConstructable)?;
+        let event = event_class.construct(context, &[event_type.into()])?;
+
+        globals::flash::events::event_dispatcher::dispatch(context, target, event)
+    }
+}
@@ -0,0 +1,1272 @@
+//! Activation frames & bytecode interpretation
+
+use crate::avm1::value::{f64_to_wrapping_i32, f64_to_wrapping_u32};
+use crate::avm2::array_object::ArrayObject;
+use crate::avm2::function::FunctionObject;
+use crate::avm2::method::BytecodeMethod;
+use crate::avm2::names::{abc_string, Multiname, Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::scope::{Scope, ScopeClass};
+use crate::avm2::script::TranslationUnit;
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::traits::install_trait;
+use crate::avm2::value::{abc_default_value, abc_double, abc_int, abc_uint, Hint};
+use crate::avm2::{Avm2, Error, UpdateContext, Value};
+use gc_arena::GcCell;
+use std::f64::NAN;
+use swf::avm2::read::Reader;
+use swf::avm2::types::{
+    Class as AbcClass, Exception as AbcException, Index, Method as AbcMethod,
+    Multiname as AbcMultiname, Namespace as AbcNamespace, Op,
+};
+use swf::read::SwfRead;
+
+/// What the interpreter loop should do after an instruction runs.
+enum FrameControl<'gc> {
+    /// Proceed to the next instruction.
+    Continue,
+
+    /// Jump relative to the end of the current instruction.
+    Jump(i32),
+
+    /// Jump relative to the start of the current instruction.
+    JumpFromStart(i32),
+
+    /// Return from the method with a value.
+    Return(Value<'gc>),
+}
+
+/// Represents a single activation of a given AVM2 method.
+///
+/// Activations are not garbage collected: they only live on the Rust stack
+/// for the duration of a method call, which cannot span a collection.
+pub struct Activation<'gc> {
+    /// The method being executed.
+    method: BytecodeMethod,
+
+    /// Local registers.
+    ///
+    /// All activations have local registers; register zero holds `this`,
+    /// followed by the method's parameters.
+    local_registers: Vec<Value<'gc>>,
+
+    /// The operand stack.
+    stack: Vec<Value<'gc>>,
+
+    /// The local scope stack, which is captured by closures created in this
+    /// activation.
+    scope_stack: Vec<(Object<'gc>, ScopeClass)>,
+
+    /// The scope chain the method closed over when it was defined.
+    outer: Option<GcCell<'gc, Scope<'gc>>>,
+
+    /// The class that defined the currently running method, if any.
+    defining_class: Option<Object<'gc>>,
+}
+
+impl<'gc> Activation<'gc> {
+    /// Construct an activation for a bytecode method call.
+    pub fn from_method(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        method: BytecodeMethod,
+        outer: Option<GcCell<'gc, Scope<'gc>>>,
+        this: Option<Object<'gc>>,
+        arguments: &[Value<'gc>],
+        defining_class: Option<Object<'gc>>,
+    ) -> Result<Self, Error<'gc>> {
+        let translation_unit = method.translation_unit().clone();
+        let abc_method = method.method();
+        let body = method.body().ok_or_else(|| {
+            Error::InvalidConstant("Attempted to call a method with no body".to_string())
+        })?;
+
+        let num_params = abc_method.params.len();
+        let num_locals = (body.num_locals as usize).max(num_params + 2);
+        let mut local_registers = vec![Value::Undefined; num_locals];
+        local_registers[0] = this.map(Value::Object).unwrap_or(Value::Null);
+
+        for (i, param) in abc_method.params.iter().enumerate() {
+            local_registers[i + 1] = match (arguments.get(i), &param.default_value) {
+                (Some(arg), _) => arg.clone(),
+                (None, Some(default)) => abc_default_value(&translation_unit, default)?,
+                (None, None) => Value::Undefined,
+            };
+        }
+
+        if abc_method.needs_rest || abc_method.needs_arguments_object {
+            let array_proto = context.avm2.prototypes().array;
+            let extra = if abc_method.needs_rest {
+                arguments.get(num_params..).unwrap_or(&[]).to_vec()
+            } else {
+                arguments.to_vec()
+            };
+            let array = ArrayObject::from_vec(context.gc_context, array_proto, extra);
+            local_registers[num_params + 1] = array.into();
+        }
+
+        let stack = Vec::with_capacity(body.max_stack as usize);
+        let scope_stack = Vec::with_capacity(body.max_scope_depth as usize);
+
+        Ok(Self {
+            method,
+            local_registers,
+            stack,
+            scope_stack,
+            outer,
+            defining_class,
+        })
+    }
+
+    /// Run the method to completion, yielding its return value.
+    pub fn run(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let method = self.method.clone();
+        let body = method.body().ok_or_else(|| {
+            Error::InvalidConstant("Attempted to run a method with no body".to_string())
+        })?;
+        let code = &body.code[..];
+        let mut pos = 0;
+
+        while pos < code.len() {
+            let mut reader = Reader::new(&code[pos..]);
+            let op = match reader.read_op()? {
+                Some(op) => op,
+                None => break,
+            };
+            let end = code.len() - reader.get_inner().len();
+
+            let result = self.do_op(context, op);
+            let target = match result {
+                Ok(FrameControl::Continue) => end as i64,
+                Ok(FrameControl::Jump(offset)) => end as i64 + i64::from(offset),
+                Ok(FrameControl::JumpFromStart(offset)) => pos as i64 + i64::from(offset),
+                Ok(FrameControl::Return(value)) => return Ok(value),
+                Err(Error::ThrownValue(value)) => {
+                    match self.find_exception_handler(context, &body.exceptions, pos, &value)? {
+                        Some(target) => {
+                            self.stack.clear();
+                            self.scope_stack.clear();
+                            self.push(value);
+                            i64::from(target)
+                        }
+                        None => return Err(Error::ThrownValue(value)),
+                    }
+                }
+                Err(e) => return Err(e),
+            };
+
+            if target < 0 || target as usize > code.len() {
+                return Err(Error::InvalidConstant(format!(
+                    "Attempted to jump to invalid offset {}",
+                    target
+                )));
+            }
+
+            pos = target as usize;
+        }
+
+        Ok(Value::Undefined)
+    }
+
+    /// Find the target of an exception handler that covers the given
+    /// instruction and accepts the thrown value.
+    fn find_exception_handler(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        exceptions: &[AbcException],
+        pos: usize,
+        value: &Value<'gc>,
+    ) -> Result<Option<u32>, Error<'gc>> {
+        for exception in exceptions {
+            let pos = pos as u32;
+            if pos < exception.from_offset || pos >= exception.to_offset {
+                continue;
+            }
+
+            if exception.type_name.0 == 0 {
+                return Ok(Some(exception.target_offset));
+            }
+
+            let type_name = Multiname::from_abc_multiname(
+                self.method.translation_unit(),
+                exception.type_name.clone(),
+                || Err(Error::StackUnderflow),
+            )?;
+            let matches = match self.resolve_class(context, &type_name)? {
+                Some(class) => self.is_instance_of(context, value, class)?,
+                None => true,
+            };
+
+            if matches {
+                return Ok(Some(exception.target_offset));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn translation_unit(&self) -> TranslationUnit {
+        self.method.translation_unit().clone()
+    }
+
+    fn push(&mut self, value: impl Into<Value<'gc>>) {
+        self.stack.push(value.into());
+    }
+
+    fn pop(&mut self) -> Result<Value<'gc>, Error<'gc>> {
+        self.stack.pop().ok_or(Error::StackUnderflow)
+    }
+
+    /// Pop a number of function arguments off the stack, in call order.
+    fn pop_args(&mut self, num_args: u32) -> Result<Vec<Value<'gc>>, Error<'gc>> {
+        let num_args = num_args as usize;
+        if num_args > self.stack.len() {
+            return Err(Error::StackUnderflow);
+        }
+
+        Ok(self.stack.split_off(self.stack.len() - num_args))
+    }
+
+    fn local(&self, index: u32) -> Value<'gc> {
+        self.local_registers
+            .get(index as usize)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+    }
+
+    fn set_local(&mut self, index: u32, value: Value<'gc>) {
+        let index = index as usize;
+        if self.local_registers.len() <= index {
+            self.local_registers.resize(index + 1, Value::Undefined);
+        }
+
+        self.local_registers[index] = value;
+    }
+
+    fn pool_string(&self, index: Index<String>) -> Result<String, Error<'gc>> {
+        abc_string(self.method.translation_unit().abc(), index)
+    }
+
+    /// Resolve a multiname from the constant pool, popping any runtime
+    /// components off the stack.
+    fn pool_multiname(&mut self, index: Index<AbcMultiname>) -> Result<Multiname, Error<'gc>> {
+        let translation_unit = self.translation_unit();
+        let stack = &mut self.stack;
+        Multiname::from_abc_multiname(&translation_unit, index, || {
+            stack.pop().ok_or(Error::StackUnderflow)
+        })
+    }
+
+    /// Capture the current scope stack as a scope chain, for use by a
+    /// closure or class defined in this activation.
+    fn capture_scope(
+        &self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Option<GcCell<'gc, Scope<'gc>>> {
+        let mut scope = self.outer;
+        for (object, class) in self.scope_stack.iter() {
+            scope = Some(Scope::push_scope(
+                scope,
+                *object,
+                *class,
+                context.gc_context,
+            ));
+        }
+
+        scope
+    }
+
+    /// Find the object on the scope chain that holds a given name.
+    ///
+    /// Names that are not found in scope are resolved against every loaded
+    /// script, which may cause that script to be initialized.
+    fn find_property(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        name: &Multiname,
+    ) -> Result<Option<Object<'gc>>, Error<'gc>> {
+        for (object, _class) in self.scope_stack.iter().rev() {
+            if object.resolve_multiname(name).is_some() {
+                return Ok(Some(*object));
+            }
+        }
+
+        if let Some(outer) = self.outer {
+            if let Some(object) = outer.read().find(name) {
+                return Ok(Some(object));
+            }
+        }
+
+        Avm2::find_script_global(context, name)
+    }
+
+    /// The global object of the script this method was defined in.
+    fn global_scope(&self, context: &mut UpdateContext<'_, 'gc, '_>) -> Object<'gc> {
+        if let Some(outer) = self.outer {
+            return outer.read().global_object();
+        }
+
+        self.scope_stack
+            .first()
+            .map(|(object, _)| *object)
+            .unwrap_or_else(|| context.avm2.globals())
+    }
+
+    /// Resolve a type name into a class object.
+    fn resolve_class(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        name: &Multiname,
+    ) -> Result<Option<Object<'gc>>, Error<'gc>> {
+        match self.find_property(context, name)? {
+            Some(holder) => Ok(get_property_by_multiname(holder, name, context)?.as_object()),
+            None => Ok(None),
+        }
+    }
+
+    /// Determine if a value is an instance of a given class.
+    fn is_instance_of(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        value: &Value<'gc>,
+        class: Object<'gc>,
+    ) -> Result<bool, Error<'gc>> {
+        let object = match value {
+            Value::Undefined | Value::Null => return Ok(false),
+            _ => value.coerce_to_object(context)?,
+        };
+
+        let proto = class
+            .get_property(class, &QName::dynamic_name("prototype"), context)?
+            .as_object();
+        let proto = match proto {
+            Some(proto) => proto,
+            None => return Ok(false),
+        };
+
+        let mut my_proto = object.proto();
+        while let Some(p) = my_proto {
+            if Object::ptr_eq(p, proto) {
+                return Ok(true);
+            }
+
+            my_proto = p.proto();
+        }
+
+        // Integers are Numbers, but their boxes only carry the Number
+        // prototype.
+        if let Value::Number(n) = value {
+            let class_name = class.as_class().map(|c| c.read().name().clone());
+            if let Some(class_name) = class_name {
+                return Ok(match class_name.local_name() {
+                    "int" => n.fract() == 0.0 && f64_to_wrapping_i32(*n) as f64 == *n,
+                    "uint" => n.fract() == 0.0 && f64_to_wrapping_u32(*n) as f64 == *n,
+                    _ => false,
+                });
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Resolve the superclass of the method currently running.
+    fn super_class(&self) -> Result<Object<'gc>, Error<'gc>> {
+        self.defining_class
+            .and_then(|class| class.as_class())
+            .and_then(|class| class.read().super_class())
+            .ok_or_else(|| Error::ThrownValue("ReferenceError: No superclass".into()))
+    }
+
+    /// Resolve the prototype of the superclass of the method currently
+    /// running.
+    fn super_proto(
+        &self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Object<'gc>, Error<'gc>> {
+        let super_class = self.super_class()?;
+        super_class
+            .get_property(super_class, &QName::dynamic_name("prototype"), context)?
+            .as_object()
+            .ok_or_else(|| Error::ThrownValue("ReferenceError: No superclass".into()))
+    }
+
+    /// Coerce a value to the given type name, if that type is a primitive.
+    fn coerce_to_type(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        type_name: &Multiname,
+        value: Value<'gc>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        Ok(match type_name.local_name() {
+            Some("int") => value.coerce_to_i32(context)?.into(),
+            Some("uint") => value.coerce_to_u32(context)?.into(),
+            Some("Number") => value.coerce_to_number(context)?.into(),
+            Some("Boolean") => value.coerce_to_boolean().into(),
+            Some("String") => match value {
+                Value::Undefined | Value::Null => Value::Null,
+                value => value.coerce_to_string(context)?.into(),
+            },
+            _ => match value {
+                Value::Undefined => Value::Null,
+                value => value,
+            },
+        })
+    }
+
+    fn do_op(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        op: Op,
+    ) -> Result<FrameControl<'gc>, Error<'gc>> {
+        match op {
+            Op::PushByte { value } => self.push(f64::from(value as i8)),
+            Op::PushShort { value } => self.push(f64::from(value as i16)),
+            Op::PushInt { value } => {
+                let value = abc_int(self.method.translation_unit(), value)?;
+                self.push(value)
+            }
+            Op::PushUint { value } => {
+                let value = abc_uint(self.method.translation_unit(), value)?;
+                self.push(value)
+            }
+            Op::PushDouble { value } => {
+                let value = abc_double(self.method.translation_unit(), value)?;
+                self.push(value)
+            }
+            Op::PushString { value } => {
+                let value = self.pool_string(value)?;
+                self.push(value)
+            }
+            Op::PushNamespace { value } => self.op_push_namespace(value)?,
+            Op::PushTrue => self.push(true),
+            Op::PushFalse => self.push(false),
+            Op::PushNull => self.push(Value::Null),
+            Op::PushUndefined => self.push(Value::Undefined),
+            Op::PushNaN => self.push(NAN),
+            Op::Pop => {
+                self.pop()?;
+            }
+            Op::Dup => {
+                let value = self.pop()?;
+                self.push(value.clone());
+                self.push(value);
+            }
+            Op::Swap => {
+                let a = self.pop()?;
+                let b = self.pop()?;
+                self.push(a);
+                self.push(b);
+            }
+            Op::GetLocal { index } => self.push(self.local(index)),
+            Op::SetLocal { index } => {
+                let value = self.pop()?;
+                self.set_local(index, value);
+            }
+            Op::Kill { index } => self.set_local(index, Value::Undefined),
+            Op::IncLocal { index } => {
+                let value = self.local(index).coerce_to_number(context)?;
+                self.set_local(index, (value + 1.0).into());
+            }
+            Op::IncLocalI { index } => {
+                let value = self.local(index).coerce_to_i32(context)?;
+                self.set_local(index, value.wrapping_add(1).into());
+            }
+            Op::DecLocal { index } => {
+                let value = self.local(index).coerce_to_number(context)?;
+                self.set_local(index, (value - 1.0).into());
+            }
+            Op::DecLocalI { index } => {
+                let value = self.local(index).coerce_to_i32(context)?;
+                self.set_local(index, value.wrapping_sub(1).into());
+            }
+            Op::PushScope => {
+                let object = self.pop()?.coerce_to_object(context)?;
+                self.scope_stack.push((object, ScopeClass::Ordinary));
+            }
+            Op::PushWith => {
+                let object = self.pop()?.coerce_to_object(context)?;
+                self.scope_stack.push((object, ScopeClass::With));
+            }
+            Op::PopScope => {
+                self.scope_stack.pop();
+            }
+            Op::GetScopeObject { index } => {
+                let object = self
+                    .scope_stack
+                    .get(index as usize)
+                    .map(|(object, _)| *object)
+                    .ok_or(Error::StackUnderflow)?;
+                self.push(object);
+            }
+            Op::GetGlobalScope => {
+                let global = self.global_scope(context);
+                self.push(global);
+            }
+            Op::GetGlobalSlot { index } => {
+                let value = self.global_scope(context).get_slot(index)?;
+                self.push(value);
+            }
+            Op::SetGlobalSlot { index } => {
+                let value = self.pop()?;
+                self.global_scope(context)
+                    .set_slot(index, value, context.gc_context)?;
+            }
+            Op::GetSlot { index } => {
+                let object = self.pop()?.coerce_to_object(context)?;
+                self.push(object.get_slot(index)?);
+            }
+            Op::SetSlot { index } => {
+                let value = self.pop()?;
+                let object = self.pop()?.coerce_to_object(context)?;
+                object.set_slot(index, value, context.gc_context)?;
+            }
+            Op::GetProperty { index } => {
+                let name = self.pool_multiname(index)?;
+                let object = self.pop()?.coerce_to_object(context)?;
+                let value = get_property_by_multiname(object, &name, context)?;
+                self.push(value);
+            }
+            Op::SetProperty { index } => {
+                let value = self.pop()?;
+                let name = self.pool_multiname(index)?;
+                let object = self.pop()?.coerce_to_object(context)?;
+                let name = object
+                    .resolve_multiname(&name)
+                    .unwrap_or_else(|| dynamic_qname(&name));
+                object.set_property(object, &name, value, context)?;
+            }
+            Op::InitProperty { index } => {
+                let value = self.pop()?;
+                let name = self.pool_multiname(index)?;
+                let object = self.pop()?.coerce_to_object(context)?;
+                let name = object
+                    .resolve_multiname(&name)
+                    .unwrap_or_else(|| dynamic_qname(&name));
+                object.init_property(object, &name, value, context)?;
+            }
+            Op::DeleteProperty { index } => {
+                let name = self.pool_multiname(index)?;
+                let object = self.pop()?.coerce_to_object(context)?;
+                let deleted = match object.resolve_multiname(&name) {
+                    Some(name) => object.delete_property(context.gc_context, &name),
+                    None => true,
+                };
+                self.push(deleted);
+            }
+            Op::GetSuper { index } => {
+                let name = self.pool_multiname(index)?;
+                let object = self.pop()?.coerce_to_object(context)?;
+                let base_proto = self.super_proto(context)?;
+                let value = match base_proto.resolve_multiname(&name) {
+                    Some(name) => base_proto.get_property(object, &name, context)?,
+                    None => Value::Undefined,
+                };
+                self.push(value);
+            }
+            Op::SetSuper { index } => {
+                let value = self.pop()?;
+                let name = self.pool_multiname(index)?;
+                let object = self.pop()?.coerce_to_object(context)?;
+                let base_proto = self.super_proto(context)?;
+                let name = base_proto
+                    .resolve_multiname(&name)
+                    .unwrap_or_else(|| dynamic_qname(&name));
+                base_proto.set_property(object, &name, value, context)?;
+            }
+            Op::FindProperty { index } => {
+                let name = self.pool_multiname(index)?;
+                let object = match self.find_property(context, &name)? {
+                    Some(object) => object,
+                    None => self.global_scope(context),
+                };
+                self.push(object);
+            }
+            Op::FindPropStrict { index } => {
+                let name = self.pool_multiname(index)?;
+                let object = self
+                    .find_property(context, &name)?
+                    .ok_or_else(|| reference_error(&name))?;
+                self.push(object);
+            }
+            Op::GetLex { index } => {
+                let name = self.pool_multiname(index)?;
+                let object = self
+                    .find_property(context, &name)?
+                    .ok_or_else(|| reference_error(&name))?;
+                let value = get_property_by_multiname(object, &name, context)?;
+                self.push(value);
+            }
+            Op::In => {
+                let object = self.pop()?.coerce_to_object(context)?;
+                let name = self.pop()?.coerce_to_string(context)?;
+                let has_property = object.has_property(&QName::dynamic_name(&name))
+                    || object
+                        .resolve_multiname(&Multiname::from(QName::new(Namespace::Any, &name)))
+                        .is_some();
+                self.push(has_property);
+            }
+            Op::HasNext => {
+                let index = self.pop()?.coerce_to_u32(context)?;
+                let object = self.pop()?;
+                let next = match object {
+                    Value::Object(object) if object.get_enumerant_name(index).is_some() => {
+                        index + 1
+                    }
+                    _ => 0,
+                };
+                self.push(next);
+            }
+            Op::HasNext2 {
+                object_register,
+                index_register,
+            } => {
+                let index = self.local(index_register).coerce_to_u32(context)?;
+                let object = self.local(object_register);
+                let has_next = match object {
+                    Value::Object(object) => object.get_enumerant_name(index).is_some(),
+                    _ => false,
+                };
+
+                if has_next {
+                    self.set_local(index_register, (index + 1).into());
+                } else {
+                    self.set_local(object_register, Value::Null);
+                    self.set_local(index_register, 0.into());
+                }
+
+                self.push(has_next);
+            }
+            Op::NextName => {
+                let index = self.pop()?.coerce_to_u32(context)?;
+                let object = self.pop()?.coerce_to_object(context)?;
+                let name = index
+                    .checked_sub(1)
+                    .and_then(|index| object.get_enumerant_name(index))
+                    .map(|name| Value::from(name.local_name()))
+                    .unwrap_or(Value::Undefined);
+                self.push(name);
+            }
+            Op::NextValue => {
+                let index = self.pop()?.coerce_to_u32(context)?;
+                let object = self.pop()?.coerce_to_object(context)?;
+                let name = index
+                    .checked_sub(1)
+                    .and_then(|index| object.get_enumerant_name(index));
+                let value = match name {
+                    Some(name) => object.get_property(object, &name, context)?,
+                    None => Value::Undefined,
+                };
+                self.push(value);
+            }
+            Op::Call { num_args } => {
+                let args = self.pop_args(num_args)?;
+                let receiver = self.pop()?.as_object();
+                let function = self.pop()?;
+                let value = call_value(function, receiver, &args, context)?;
+                self.push(value);
+            }
+            Op::CallProperty { index, num_args } => {
+                let value = self.op_call_property(context, index, num_args, false)?;
+                self.push(value);
+            }
+            Op::CallPropLex { index, num_args } => {
+                let value = self.op_call_property(context, index, num_args, true)?;
+                self.push(value);
+            }
+            Op::CallPropVoid { index, num_args } => {
+                let args = self.pop_args(num_args)?;
+                let name = self.pool_multiname(index)?;
+                let object = self.pop()?.coerce_to_object(context)?;
+                let function = get_property_by_multiname(object, &name, context)?;
+                call_value(function, Some(object), &args, context)?;
+            }
+            Op::CallStatic { index, num_args } => {
+                let args = self.pop_args(num_args)?;
+                let receiver = self.pop()?.as_object();
+                let value = self.op_call_static(context, index, receiver, &args)?;
+                self.push(value);
+            }
+            Op::CallSuper { index, num_args } => {
+                let value = self.op_call_super(context, index, num_args)?;
+                self.push(value);
+            }
+            Op::CallSuperVoid { index, num_args } => {
+                self.op_call_super(context, index, num_args)?;
+            }
+            Op::Construct { num_args } => {
+                let args = self.pop_args(num_args)?;
+                let constructor = self.pop()?;
+                let object = construct_value(constructor, &args, context)?;
+                self.push(object);
+            }
+            Op::ConstructProp { index, num_args } => {
+                let args = self.pop_args(num_args)?;
+                let name = self.pool_multiname(index)?;
+                let source = self.pop()?.coerce_to_object(context)?;
+                let constructor = get_property_by_multiname(source, &name, context)?;
+                let object = construct_value(constructor, &args, context)?;
+                self.push(object);
+            }
+            Op::ConstructSuper { num_args } => {
+                let args = self.pop_args(num_args)?;
+                let receiver = self.pop()?.coerce_to_object(context)?;
+                let super_class = self.super_class()?;
+                if let Some(constructor) = super_class.as_executable() {
+                    constructor.exec(Some(receiver), &args, context)?;
+                }
+            }
+            Op::NewFunction { index } => {
+                let method = BytecodeMethod::from_method_index(self.translation_unit(), index)
+                    .ok_or_else(|| Error::InvalidConstant("Unknown method".to_string()))?;
+                let scope = self.capture_scope(context);
+                let fn_proto = context.avm2.prototypes().function;
+                let function = FunctionObject::from_method(
+                    context.gc_context,
+                    method.into(),
+                    scope,
+                    fn_proto,
+                    None,
+                );
+                self.push(function);
+            }
+            Op::NewClass { index } => {
+                let base = self.pop()?.as_object();
+                let scope = self.capture_scope(context);
+                let class = FunctionObject::from_abc_class(
+                    context,
+                    self.translation_unit(),
+                    Index::<AbcClass>::new(index.0),
+                    base,
+                    scope,
+                )?;
+                self.push(class);
+            }
+            Op::NewObject { num_args } => {
+                let object =
+                    ScriptObject::object(context.gc_context, context.avm2.prototypes().object);
+                let mut properties = Vec::with_capacity(num_args as usize);
+                for _ in 0..num_args {
+                    let value = self.pop()?;
+                    let name = self.pop()?.coerce_to_string(context)?;
+                    properties.push((name, value));
+                }
+
+                for (name, value) in properties.into_iter().rev() {
+                    object.install_dynamic_property(
+                        context.gc_context,
+                        QName::dynamic_name(&name),
+                        value,
+                    );
+                }
+
+                self.push(object);
+            }
+            Op::NewArray { num_args } => {
+                let args = self.pop_args(num_args)?;
+                let array_proto = context.avm2.prototypes().array;
+                let array = ArrayObject::from_vec(context.gc_context, array_proto, args);
+                self.push(array);
+            }
+            Op::NewActivation => {
+                let activation = ScriptObject::bare_object(context.gc_context);
+                let method = self.method.clone();
+                if let Some(body) = method.body() {
+                    for abc_trait in body.traits.iter() {
+                        install_trait(
+                            context,
+                            method.translation_unit(),
+                            abc_trait,
+                            None,
+                            activation,
+                            None,
+                        )?;
+                    }
+                }
+
+                self.push(activation);
+            }
+            Op::NewCatch { index } => {
+                let catch_scope = ScriptObject::bare_object(context.gc_context);
+                let method = self.method.clone();
+                let exception = method
+                    .body()
+                    .and_then(|body| body.exceptions.get(index.0 as usize));
+                if let Some(exception) = exception {
+                    // The variable name is a multiname index in the ABC file.
+                    let name = QName::from_abc_multiname(
+                        method.translation_unit().abc(),
+                        Index::new(exception.variable_name.0),
+                    )
+                    .unwrap_or_else(|_| QName::dynamic_name(""));
+                    catch_scope.install_slot(context.gc_context, name, 1, Value::Undefined);
+                }
+
+                self.push(catch_scope);
+            }
+            Op::ReturnValue => return Ok(FrameControl::Return(self.pop()?)),
+            Op::ReturnVoid => return Ok(FrameControl::Return(Value::Undefined)),
+            Op::Throw => return Err(Error::ThrownValue(self.pop()?)),
+            Op::Coerce { index } => {
+                let type_name = self.pool_multiname(index)?;
+                let value = self.pop()?;
+                let value = self.coerce_to_type(context, &type_name, value)?;
+                self.push(value);
+            }
+            Op::CoerceA => {}
+            Op::CoerceS => {
+                let value = match self.pop()? {
+                    Value::Undefined | Value::Null => Value::Null,
+                    value => value.coerce_to_string(context)?.into(),
+                };
+                self.push(value);
+            }
+            Op::ConvertB => {
+                let value = self.pop()?.coerce_to_boolean();
+                self.push(value);
+            }
+            Op::ConvertD => {
+                let value = self.pop()?.coerce_to_number(context)?;
+                self.push(value);
+            }
+            Op::ConvertI => {
+                let value = self.pop()?.coerce_to_i32(context)?;
+                self.push(value);
+            }
+            Op::ConvertU => {
+                let value = self.pop()?.coerce_to_u32(context)?;
+                self.push(value);
+            }
+            Op::ConvertS => {
+                let value = self.pop()?.coerce_to_string(context)?;
+                self.push(value);
+            }
+            Op::ConvertO => {
+                let value = self.pop()?;
+                value.coerce_to_object(context)?;
+                self.push(value);
+            }
+            Op::AsType { type_name } => {
+                let type_name = self.pool_multiname(type_name)?;
+                let value = self.pop()?;
+                let is_instance = match self.resolve_class(context, &type_name)? {
+                    Some(class) => self.is_instance_of(context, &value, class)?,
+                    None => false,
+                };
+                self.push(if is_instance { value } else { Value::Null });
+            }
+            Op::AsTypeLate => {
+                let class = self.pop()?.coerce_to_object(context)?;
+                let value = self.pop()?;
+                let is_instance = self.is_instance_of(context, &value, class)?;
+                self.push(if is_instance { value } else { Value::Null });
+            }
+            Op::IsType { index } => {
+                let type_name = self.pool_multiname(index)?;
+                let value = self.pop()?;
+                let is_instance = match self.resolve_class(context, &type_name)? {
+                    Some(class) => self.is_instance_of(context, &value, class)?,
+                    None => false,
+                };
+                self.push(is_instance);
+            }
+            Op::IsTypeLate | Op::InstanceOf => {
+                let class = self.pop()?.coerce_to_object(context)?;
+                let value = self.pop()?;
+                let is_instance = self.is_instance_of(context, &value, class)?;
+                self.push(is_instance);
+            }
+            Op::TypeOf => {
+                let value = self.pop()?;
+                self.push(value.type_of());
+            }
+            Op::CheckFilter => {}
+            Op::Add => {
+                let value2 = self.pop()?;
+                let value1 = self.pop()?;
+                let value = add(value1, value2, context)?;
+                self.push(value);
+            }
+            Op::AddI => {
+                let value2 = self.pop()?.coerce_to_i32(context)?;
+                let value1 = self.pop()?.coerce_to_i32(context)?;
+                self.push(value1.wrapping_add(value2));
+            }
+            Op::Subtract => {
+                let value2 = self.pop()?.coerce_to_number(context)?;
+                let value1 = self.pop()?.coerce_to_number(context)?;
+                self.push(value1 - value2);
+            }
+            Op::SubtractI => {
+                let value2 = self.pop()?.coerce_to_i32(context)?;
+                let value1 = self.pop()?.coerce_to_i32(context)?;
+                self.push(value1.wrapping_sub(value2));
+            }
+            Op::Multiply => {
+                let value2 = self.pop()?.coerce_to_number(context)?;
+                let value1 = self.pop()?.coerce_to_number(context)?;
+                self.push(value1 * value2);
+            }
+            Op::MultiplyI => {
+                let value2 = self.pop()?.coerce_to_i32(context)?;
+                let value1 = self.pop()?.coerce_to_i32(context)?;
+                self.push(value1.wrapping_mul(value2));
+            }
+            Op::Divide => {
+                let value2 = self.pop()?.coerce_to_number(context)?;
+                let value1 = self.pop()?.coerce_to_number(context)?;
+                self.push(value1 / value2);
+            }
+            Op::Modulo => {
+                let value2 = self.pop()?.coerce_to_number(context)?;
+                let value1 = self.pop()?.coerce_to_number(context)?;
+                self.push(value1 % value2);
+            }
+            Op::Negate => {
+                let value = self.pop()?.coerce_to_number(context)?;
+                self.push(-value);
+            }
+            Op::NegateI => {
+                let value = self.pop()?.coerce_to_i32(context)?;
+                self.push(value.wrapping_neg());
+            }
+            Op::Increment => {
+                let value = self.pop()?.coerce_to_number(context)?;
+                self.push(value + 1.0);
+            }
+            Op::IncrementI => {
+                let value = self.pop()?.coerce_to_i32(context)?;
+                self.push(value.wrapping_add(1));
+            }
+            Op::Decrement => {
+                let value = self.pop()?.coerce_to_number(context)?;
+                self.push(value - 1.0);
+            }
+            Op::DecrementI => {
+                let value = self.pop()?.coerce_to_i32(context)?;
+                self.push(value.wrapping_sub(1));
+            }
+            Op::BitAnd => {
+                let value2 = self.pop()?.coerce_to_i32(context)?;
+                let value1 = self.pop()?.coerce_to_i32(context)?;
+                self.push(value1 & value2);
+            }
+            Op::BitOr => {
+                let value2 = self.pop()?.coerce_to_i32(context)?;
+                let value1 = self.pop()?.coerce_to_i32(context)?;
+                self.push(value1 | value2);
+            }
+            Op::BitXor => {
+                let value2 = self.pop()?.coerce_to_i32(context)?;
+                let value1 = self.pop()?.coerce_to_i32(context)?;
+                self.push(value1 ^ value2);
+            }
+            Op::BitNot => {
+                let value = self.pop()?.coerce_to_i32(context)?;
+                self.push(!value);
+            }
+            Op::LShift => {
+                let value2 = self.pop()?.coerce_to_u32(context)?;
+                let value1 = self.pop()?.coerce_to_i32(context)?;
+                self.push(value1.wrapping_shl(value2 & 0x1F));
+            }
+            Op::RShift => {
+                let value2 = self.pop()?.coerce_to_u32(context)?;
+                let value1 = self.pop()?.coerce_to_i32(context)?;
+                self.push(value1.wrapping_shr(value2 & 0x1F));
+            }
+            Op::URShift => {
+                let value2 = self.pop()?.coerce_to_u32(context)?;
+                let value1 = self.pop()?.coerce_to_u32(context)?;
+                self.push(value1.wrapping_shr(value2 & 0x1F));
+            }
+            Op::Not => {
+                let value = self.pop()?.coerce_to_boolean();
+                self.push(!value);
+            }
+            Op::Equals => {
+                let value2 = self.pop()?;
+                let value1 = self.pop()?;
+                let result = value1.abstract_eq(&value2, context)?;
+                self.push(result);
+            }
+            Op::StrictEquals => {
+                let value2 = self.pop()?;
+                let value1 = self.pop()?;
+                self.push(value1 == value2);
+            }
+            Op::LessThan => {
+                let value2 = self.pop()?;
+                let value1 = self.pop()?;
+                let result = value1.abstract_lt(&value2, context)?.unwrap_or(false);
+                self.push(result);
+            }
+            Op::LessEquals => {
+                let value2 = self.pop()?;
+                let value1 = self.pop()?;
+                let result = value2.abstract_lt(&value1, context)?.map(|r| !r);
+                self.push(result.unwrap_or(false));
+            }
+            Op::GreaterThan => {
+                let value2 = self.pop()?;
+                let value1 = self.pop()?;
+                let result = value2.abstract_lt(&value1, context)?.unwrap_or(false);
+                self.push(result);
+            }
+            Op::GreaterEquals => {
+                let value2 = self.pop()?;
+                let value1 = self.pop()?;
+                let result = value1.abstract_lt(&value2, context)?.map(|r| !r);
+                self.push(result.unwrap_or(false));
+            }
+            Op::Jump { offset } => return Ok(FrameControl::Jump(offset)),
+            Op::IfTrue { offset } => {
+                if self.pop()?.coerce_to_boolean() {
+                    return Ok(FrameControl::Jump(offset));
+                }
+            }
+            Op::IfFalse { offset } => {
+                if !self.pop()?.coerce_to_boolean() {
+                    return Ok(FrameControl::Jump(offset));
+                }
+            }
+            Op::IfEq { offset } | Op::IfNe { offset } => {
+                let value2 = self.pop()?;
+                let value1 = self.pop()?;
+                let is_equal = value1.abstract_eq(&value2, context)?;
+                let jump_if = match op {
+                    Op::IfEq { .. } => true,
+                    _ => false,
+                };
+                if is_equal == jump_if {
+                    return Ok(FrameControl::Jump(offset));
+                }
+            }
+            Op::IfStrictEq { offset } => {
+                let value2 = self.pop()?;
+                let value1 = self.pop()?;
+                if value1 == value2 {
+                    return Ok(FrameControl::Jump(offset));
+                }
+            }
+            Op::IfStrictNe { offset } => {
+                let value2 = self.pop()?;
+                let value1 = self.pop()?;
+                if value1 != value2 {
+                    return Ok(FrameControl::Jump(offset));
+                }
+            }
+            Op::IfLt { offset }
+            | Op::IfLe { offset }
+            | Op::IfGt { offset }
+            | Op::IfGe { offset }
+            | Op::IfNlt { offset }
+            | Op::IfNle { offset }
+            | Op::IfNgt { offset }
+            | Op::IfNge { offset } => {
+                let value2 = self.pop()?;
+                let value1 = self.pop()?;
+                let result = match op {
+                    Op::IfLt { .. } | Op::IfNlt { .. } => value1.abstract_lt(&value2, context)?,
+                    Op::IfGt { .. } | Op::IfNgt { .. } => value2.abstract_lt(&value1, context)?,
+                    Op::IfLe { .. } | Op::IfNle { .. } => {
+                        value2.abstract_lt(&value1, context)?.map(|r| !r)
+                    }
+                    _ => value1.abstract_lt(&value2, context)?.map(|r| !r),
+                };
+                let should_jump = match op {
+                    Op::IfLt { .. } | Op::IfGt { .. } | Op::IfLe { .. } | Op::IfGe { .. } => {
+                        result.unwrap_or(false)
+                    }
+                    _ => !result.unwrap_or(false),
+                };
+                if should_jump {
+                    return Ok(FrameControl::Jump(offset));
+                }
+            }
+            Op::LookupSwitch {
+                default_offset,
+                case_offsets,
+            } => {
+                let index = self.pop()?.coerce_to_i32(context)?;
+                let offset = if index >= 0 {
+                    case_offsets
+                        .get(index as usize)
+                        .copied()
+                        .unwrap_or(default_offset)
+                } else {
+                    default_offset
+                };
+                return Ok(FrameControl::JumpFromStart(offset));
+            }
+            Op::Nop
+            | Op::Label
+            | Op::Debug { .. }
+            | Op::DebugFile { .. }
+            | Op::DebugLine { .. } => {}
+            _ => return Err(Error::UnimplementedOp(format!("{:?}", op))),
+        }
+
+        Ok(FrameControl::Continue)
+    }
+
+    fn op_push_namespace(&mut self, value: Index<AbcNamespace>) -> Result<(), Error<'gc>> {
+        let ns = Namespace::from_abc_namespace(self.method.translation_unit().abc(), value)?;
+        self.push(ns);
+        Ok(())
+    }
+
+    fn op_call_property(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        index: Index<AbcMultiname>,
+        num_args: u32,
+        is_lex: bool,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let args = self.pop_args(num_args)?;
+        let name = self.pool_multiname(index)?;
+        let object = self.pop()?.coerce_to_object(context)?;
+        let function = get_property_by_multiname(object, &name, context)?;
+        let receiver = if is_lex { None } else { Some(object) };
+
+        call_value(function, receiver, &args, context)
+    }
+
+    fn op_call_super(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        index: Index<AbcMultiname>,
+        num_args: u32,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let args = self.pop_args(num_args)?;
+        let name = self.pool_multiname(index)?;
+        let object = self.pop()?.coerce_to_object(context)?;
+        let base_proto = self.super_proto(context)?;
+        let function = match base_proto.resolve_multiname(&name) {
+            Some(name) => base_proto.get_property(object, &name, context)?,
+            None => return Err(reference_error(&name)),
+        };
+
+        call_value(function, Some(object), &args, context)
+    }
+
+    fn op_call_static(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        index: Index<AbcMethod>,
+        receiver: Option<Object<'gc>>,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let method = BytecodeMethod::from_method_index(self.translation_unit(), index)
+            .ok_or_else(|| Error::InvalidConstant("Unknown method".to_string()))?;
+        let scope = self.capture_scope(context);
+        let fn_proto = context.avm2.prototypes().function;
+        let function =
+            FunctionObject::from_method(context.gc_context, method.into(), scope, fn_proto, None);
+
+        function.call(receiver, args, context)
+    }
+}
+
+/// Retrieve a property from an object by multiname.
+///
+/// Names that do not resolve yield `undefined`.
+pub fn get_property_by_multiname<'gc>(
+    object: Object<'gc>,
+    name: &Multiname,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    match object.resolve_multiname(name) {
+        Some(name) => object.get_property(object, &name, context),
+        None => Ok(Value::Undefined),
+    }
+}
+
+/// The name a dynamic property would be created under if a multiname does
+/// not resolve to an existing property.
+fn dynamic_qname(name: &Multiname) -> QName {
+    let ns = name
+        .namespace_set()
+        .find(|ns| ns.is_public())
+        .or_else(|| name.namespace_set().next())
+        .cloned()
+        .filter(|ns| !ns.is_any())
+        .unwrap_or_else(Namespace::public_namespace);
+
+    QName::new(ns, name.local_name().unwrap_or(""))
+}
+
+/// Construct the error thrown when a name cannot be found.
+fn reference_error<'gc>(name: &Multiname) -> Error<'gc> {
+    Error::ThrownValue(
+        format!(
+            "ReferenceError: Error #1065: Variable {} is not defined.",
+            name.local_name().unwrap_or("*")
+        )
+        .into(),
+    )
+}
+
+/// Call a value as a function.
+fn call_value<'gc>(
+    function: Value<'gc>,
+    receiver: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    match function {
+        Value::Object(function) => match function.call(receiver, args, context) {
+            Err(Error::NotCallable) => Err(Error::ThrownValue(
+                "TypeError: Error #1006: value is not a function.".into(),
+            )),
+            result => result,
+        },
+        _ => Err(Error::ThrownValue(
+            "TypeError: Error #1006: value is not a function.".into(),
+        )),
+    }
+}
+
+/// Construct a value as a class or constructor function.
+fn construct_value<'gc>(
+    constructor: Value<'gc>,
+    args: &[Value<'gc>],
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    match constructor {
+        Value::Object(constructor) => match constructor.construct(context, args) {
+            Ok(object) => Ok(object.into()),
+            Err(Error::NotConstructable) => Err(Error::ThrownValue(
+                "TypeError: Error #1007: Instantiation attempted on a non-constructor.".into(),
+            )),
+            Err(e) => Err(e),
+        },
+        _ => Err(Error::ThrownValue(
+            "TypeError: Error #1007: Instantiation attempted on a non-constructor.".into(),
+        )),
+    }
+}
+
+/// ECMA-262 3rd edition s. 11.6.1 The Addition operator
+fn add<'gc>(
+    value1: Value<'gc>,
+    value2: Value<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let (Value::Number(n1), Value::Number(n2)) = (&value1, &value2) {
+        return Ok((n1 + n2).into());
+    }
+
+    let prim1 = value1.coerce_to_primitive(Hint::Number, context)?;
+    let prim2 = value2.coerce_to_primitive(Hint::Number, context)?;
+
+    match (&prim1, &prim2) {
+        (Value::String(_), _) | (_, Value::String(_)) => {
+            let mut s = prim1.coerce_to_string(context)?;
+            s.push_str(&prim2.coerce_to_string(context)?);
+            Ok(s.into())
+        }
+        _ => Ok((prim1.coerce_to_number(context)? + prim2.coerce_to_number(context)?).into()),
+    }
+}
@@ -0,0 +1,225 @@
+//! Array-structured objects
+
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, ObjectPtr, TObject};
+use crate::avm2::property::Property;
+use crate::avm2::script_object::ScriptObjectData;
+use crate::avm2::{Error, UpdateContext, Value};
+use gc_arena::{Collect, GcCell, MutationContext};
+
+/// A class instance allocator that allocates `ArrayObject`s.
+pub fn array_allocator<'gc>(proto: Object<'gc>, mc: MutationContext<'gc, '_>) -> Object<'gc> {
+    ArrayObject::from_vec(mc, proto, vec![]).into()
+}
+
+/// An Object which stores numerical properties in a dense array.
+#[derive(Collect, Debug, Clone, Copy)]
+#[collect(no_drop)]
+pub struct ArrayObject<'gc>(GcCell<'gc, ArrayObjectData<'gc>>);
+
+#[derive(Collect, Debug, Clone)]
+#[collect(no_drop)]
+pub struct ArrayObjectData<'gc> {
+    /// Base script object
+    base: ScriptObjectData<'gc>,
+
+    /// Array elements.
+    array: Vec<Value<'gc>>,
+}
+
+impl<'gc> ArrayObject<'gc> {
+    /// Construct an array with the given elements.
+    pub fn from_vec(
+        mc: MutationContext<'gc, '_>,
+        proto: Object<'gc>,
+        array: Vec<Value<'gc>>,
+    ) -> Self {
+        ArrayObject(GcCell::allocate(
+            mc,
+            ArrayObjectData {
+                base: ScriptObjectData::base_new(Some(proto)),
+                array,
+            },
+        ))
+    }
+
+    pub fn length(self) -> usize {
+        self.0.read().array.len()
+    }
+
+    pub fn set_length(self, mc: MutationContext<'gc, '_>, length: usize) {
+        self.0.write(mc).array.resize(length, Value::Undefined);
+    }
+
+    pub fn get(self, index: usize) -> Value<'gc> {
+        self.0
+            .read()
+            .array
+            .get(index)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+    }
+
+    pub fn set(self, mc: MutationContext<'gc, '_>, index: usize, value: Value<'gc>) {
+        let mut write = self.0.write(mc);
+        if write.array.len() <= index {
+            write.array.resize(index + 1, Value::Undefined);
+        }
+
+        write.array[index] = value;
+    }
+
+    /// Copy out the elements of this array.
+    pub fn to_vec(self) -> Vec<Value<'gc>> {
+        self.0.read().array.clone()
+    }
+
+    /// Replace the elements of this array.
+    pub fn replace(self, mc: MutationContext<'gc, '_>, array: Vec<Value<'gc>>) {
+        self.0.write(mc).array = array;
+    }
+
+    /// Determine if a name refers to an array index.
+    fn index_of(name: &QName) -> Option<usize> {
+        if name.namespace().is_public() {
+            name.local_name().parse().ok()
+        } else {
+            None
+        }
+    }
+
+    fn is_length(name: &QName) -> bool {
+        name.namespace().is_public() && name.local_name() == "length"
+    }
+}
+
+impl<'gc> TObject<'gc> for ArrayObject<'gc> {
+    impl_avm2_custom_object!(base);
+
+    fn get_property_local(
+        self,
+        receiver: Object<'gc>,
+        name: &QName,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        if let Some(index) = Self::index_of(name) {
+            return Ok(self.get(index));
+        }
+
+        if Self::is_length(name) {
+            return Ok(self.length().into());
+        }
+
+        let property = self.0.read().base.property(name);
+        match property {
+            Some(Property::Slot { slot_id, .. }) => self.0.read().base.get_slot(slot_id),
+            Some(property) => property.get(receiver, context),
+            None => Ok(Value::Undefined),
+        }
+    }
+
+    fn set_property_local(
+        self,
+        receiver: Object<'gc>,
+        name: &QName,
+        value: Value<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<(), Error<'gc>> {
+        if let Some(index) = Self::index_of(name) {
+            self.set(context.gc_context, index, value);
+            return Ok(());
+        }
+
+        if Self::is_length(name) {
+            let length = value.coerce_to_u32(context)? as usize;
+            self.set_length(context.gc_context, length);
+            return Ok(());
+        }
+
+        let property = self.0.read().base.property(name);
+        match property {
+            Some(Property::Virtual { set: Some(set), .. }) => {
+                set.call(Some(receiver), &[value], context)?;
+                Ok(())
+            }
+            Some(Property::Virtual { set: None, .. }) => Ok(()),
+            _ => self
+                .0
+                .write(context.gc_context)
+                .base
+                .set_property_value(name, value, false),
+        }
+    }
+
+    fn init_property_local(
+        self,
+        receiver: Object<'gc>,
+        name: &QName,
+        value: Value<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<(), Error<'gc>> {
+        if Self::index_of(name).is_some() || Self::is_length(name) {
+            return self.set_property_local(receiver, name, value, context);
+        }
+
+        self.0
+            .write(context.gc_context)
+            .base
+            .set_property_value(name, value, true)
+    }
+
+    fn has_own_property(self, name: &QName) -> bool {
+        if let Some(index) = Self::index_of(name) {
+            return index < self.length();
+        }
+
+        Self::is_length(name) || self.0.read().base.has_own_property(name)
+    }
+
+    fn resolve_any(self, local_name: &str) -> Option<Namespace> {
+        let is_index = local_name
+            .parse::<usize>()
+            .map(|index| index < self.length())
+            .unwrap_or(false);
+
+        if is_index || local_name == "length" {
+            return Some(Namespace::public_namespace());
+        }
+
+        self.0.read().base.resolve_any(local_name)
+    }
+
+    fn delete_property(self, gc_context: MutationContext<'gc, '_>, name: &QName) -> bool {
+        if let Some(index) = Self::index_of(name) {
+            let mut write = self.0.write(gc_context);
+            if let Some(element) = write.array.get_mut(index) {
+                *element = Value::Undefined;
+            }
+
+            return true;
+        }
+
+        self.0.write(gc_context).base.delete_property(name)
+    }
+
+    fn get_enumerant_name(self, index: u32) -> Option<QName> {
+        let length = self.length() as u32;
+        if index < length {
+            Some(QName::dynamic_name(&index.to_string()))
+        } else {
+            self.0.read().base.get_enumerant_name(index - length)
+        }
+    }
+
+    fn property_is_enumerable(self, name: &QName) -> bool {
+        if let Some(index) = Self::index_of(name) {
+            return index < self.length();
+        }
+
+        self.0.read().base.property_is_enumerable(name)
+    }
+
+    fn as_array_object(self) -> Option<ArrayObject<'gc>> {
+        Some(self)
+    }
+}
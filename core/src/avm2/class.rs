@@ -0,0 +1,178 @@
+//! AVM2 classes
+
+use crate::avm2::method::{Method, NativeMethod};
+use crate::avm2::names::QName;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script::TranslationUnit;
+use crate::avm2::traits::{install_trait, is_instance_slot};
+use crate::avm2::{Error, UpdateContext, Value};
+use gc_arena::{Collect, CollectionContext, GcCell, MutationContext};
+
+/// A function that allocates a new, uninitialized instance of a class given
+/// the class prototype.
+pub type AllocatorFn<'gc> = fn(Object<'gc>, MutationContext<'gc, '_>) -> Object<'gc>;
+
+/// A loaded class definition.
+///
+/// Each class object (a `FunctionObject`) carries one of these, which
+/// describes how instances of that class are created and initialized.
+#[derive(Clone)]
+pub struct Class<'gc> {
+    /// The name of the class.
+    name: QName,
+
+    /// The class object of this class's superclass, if any.
+    super_class: Option<Object<'gc>>,
+
+    /// The instance initializer (constructor body) of this class.
+    instance_init: Method<'gc>,
+
+    /// The ABC file and instance index this class was defined by, if it was
+    /// defined by an ABC file. Used to install per-instance traits.
+    abc_instance: Option<(TranslationUnit, u32)>,
+
+    /// How to allocate instances of this class.
+    ///
+    /// Classes without an allocator inherit their superclass's allocator.
+    allocator: Option<AllocatorFn<'gc>>,
+
+    /// What happens when the class object is called as a function, e.g.
+    /// `int(x)`. Classes without a call handler coerce their argument.
+    call_handler: Option<NativeMethod<'gc>>,
+}
+
+unsafe impl<'gc> Collect for Class<'gc> {
+    fn trace(&self, cc: CollectionContext) {
+        self.super_class.trace(cc);
+        self.instance_init.trace(cc);
+    }
+}
+
+impl<'gc> std::fmt::Debug for Class<'gc> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Class")
+            .field("name", &self.name)
+            .field("super_class", &self.super_class)
+            .field("instance_init", &self.instance_init)
+            .field("abc_instance", &self.abc_instance)
+            .finish()
+    }
+}
+
+impl<'gc> Class<'gc> {
+    /// Create a class definition for a natively implemented class.
+    pub fn native(
+        name: QName,
+        super_class: Option<Object<'gc>>,
+        instance_init: NativeMethod<'gc>,
+    ) -> Self {
+        Self {
+            name,
+            super_class,
+            instance_init: Method::Native(instance_init),
+            abc_instance: None,
+            allocator: None,
+            call_handler: None,
+        }
+    }
+
+    /// Create a class definition for a class defined in an ABC file.
+    pub fn from_abc(
+        name: QName,
+        super_class: Option<Object<'gc>>,
+        instance_init: Method<'gc>,
+        translation_unit: TranslationUnit,
+        instance_index: u32,
+    ) -> Self {
+        Self {
+            name,
+            super_class,
+            instance_init,
+            abc_instance: Some((translation_unit, instance_index)),
+            allocator: None,
+            call_handler: None,
+        }
+    }
+
+    pub fn with_allocator(mut self, allocator: AllocatorFn<'gc>) -> Self {
+        self.allocator = Some(allocator);
+        self
+    }
+
+    pub fn with_call_handler(mut self, call_handler: NativeMethod<'gc>) -> Self {
+        self.call_handler = Some(call_handler);
+        self
+    }
+
+    pub fn name(&self) -> &QName {
+        &self.name
+    }
+
+    pub fn super_class(&self) -> Option<Object<'gc>> {
+        self.super_class
+    }
+
+    pub fn instance_init(&self) -> &Method<'gc> {
+        &self.instance_init
+    }
+
+    pub fn call_handler(&self) -> Option<NativeMethod<'gc>> {
+        self.call_handler
+    }
+
+    /// Find the allocator for this class, searching superclasses if needed.
+    pub fn allocator(&self) -> Option<AllocatorFn<'gc>> {
+        if self.allocator.is_some() {
+            return self.allocator;
+        }
+
+        self.super_class
+            .and_then(|sc| sc.as_class())
+            .and_then(|sc| sc.read().allocator())
+    }
+}
+
+/// Install per-instance traits (slots and constants) for a class and all of
+/// its superclasses onto a freshly allocated instance.
+pub fn install_instance_traits<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    class: GcCell<'gc, Class<'gc>>,
+    instance: Object<'gc>,
+) -> Result<(), Error<'gc>> {
+    let (super_class, abc_instance) = {
+        let read = class.read();
+        (read.super_class, read.abc_instance.clone())
+    };
+
+    if let Some(super_class) = super_class.and_then(|sc| sc.as_class()) {
+        install_instance_traits(context, super_class, instance)?;
+    }
+
+    if let Some((translation_unit, index)) = abc_instance {
+        let abc_instance = &translation_unit.abc().instances[index as usize];
+        for abc_trait in abc_instance.traits.iter().filter(|t| is_instance_slot(t)) {
+            install_trait(context, &translation_unit, abc_trait, None, instance, None)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Install instance traits onto an already-allocated instance of a class and
+/// run its constructor.
+pub fn initialize_instance<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    class_object: Object<'gc>,
+    instance: Object<'gc>,
+    arguments: &[Value<'gc>],
+) -> Result<(), Error<'gc>> {
+    let class = class_object.as_class().ok_or(Error::NotConstructable)?;
+    install_instance_traits(context, class, instance)?;
+
+    let constructor = class_object
+        .as_executable()
+        .ok_or(Error::NotConstructable)?;
+    constructor.exec(Some(instance), arguments, context)?;
+
+    Ok(())
+}
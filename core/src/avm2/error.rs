@@ -0,0 +1,48 @@
+use crate::avm2::Value;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error<'gc> {
+    #[error("Couldn't parse ABC file. This may or may not be a bug in Ruffle, please help us by reporting it to https://github.com/ruffle-rs/ruffle/issues and include the swf that triggered it.")]
+    InvalidAbc(#[from] swf::error::Error),
+
+    #[error("Attempted to access an ABC constant pool entry that does not exist: {0}")]
+    InvalidConstant(String),
+
+    #[error("Unimplemented AVM2 opcode: {0}")]
+    UnimplementedOp(String),
+
+    #[error("Stack underflow. This is probably a bug in Ruffle, please report it to https://github.com/ruffle-rs/ruffle/issues and include the swf that triggered it.")]
+    StackUnderflow,
+
+    #[error("Call stack exceeded the recursion limit")]
+    RecursionLimit,
+
+    #[error("Cannot find property {0}")]
+    PropertyNotFound(String),
+
+    #[error("Value is not a function")]
+    NotCallable,
+
+    #[error("Value is not a constructor")]
+    NotConstructable,
+
+    #[error("A script has thrown a custom error.")]
+    ThrownValue(Value<'gc>),
+}
+
+impl Error<'_> {
+    pub fn is_halting(&self) -> bool {
+        match self {
+            Error::InvalidAbc(_) => true,
+            Error::InvalidConstant(_) => true,
+            Error::UnimplementedOp(_) => true,
+            Error::StackUnderflow => true,
+            Error::RecursionLimit => true,
+            Error::PropertyNotFound(_) => false,
+            Error::NotCallable => false,
+            Error::NotConstructable => false,
+            Error::ThrownValue(_) => false,
+        }
+    }
+}
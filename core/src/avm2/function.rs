@@ -0,0 +1,341 @@
+//! AVM2 executables.
+
+use crate::avm2::activation::Activation;
+use crate::avm2::class::{initialize_instance, Class};
+use crate::avm2::method::{BytecodeMethod, Method, NativeMethod};
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, ObjectPtr, TObject};
+use crate::avm2::property::Property;
+use crate::avm2::scope::{Scope, ScopeClass};
+use crate::avm2::script::TranslationUnit;
+use crate::avm2::script_object::{scriptobject_allocator, ScriptObject, ScriptObjectData};
+use crate::avm2::traits::{install_trait, is_instance_slot};
+use crate::avm2::{Error, UpdateContext, Value};
+use gc_arena::{Collect, GcCell, MutationContext};
+use swf::avm2::types::{Class as AbcClass, Index};
+
+/// Represents code that can be executed by some means.
+#[derive(Clone, Debug, Collect)]
+#[collect(no_drop)]
+pub struct Executable<'gc> {
+    /// The method to run.
+    method: Method<'gc>,
+
+    /// The scope the method was defined in, if it is a bytecode method.
+    scope: Option<GcCell<'gc, Scope<'gc>>>,
+
+    /// The receiver this method is bound to, if any. Unbound methods take
+    /// their receiver from the caller.
+    receiver: Option<Object<'gc>>,
+
+    /// The class object that defined this method, if it is a class or
+    /// instance method. Used to resolve `super`.
+    defining_class: Option<Object<'gc>>,
+}
+
+impl<'gc> Executable<'gc> {
+    pub fn from_method(
+        method: Method<'gc>,
+        scope: Option<GcCell<'gc, Scope<'gc>>>,
+        receiver: Option<Object<'gc>>,
+        defining_class: Option<Object<'gc>>,
+    ) -> Self {
+        Self {
+            method,
+            scope,
+            receiver,
+            defining_class,
+        }
+    }
+
+    /// Execute a method.
+    ///
+    /// The `unbound_receiver` is used as `this` unless the executable is
+    /// bound to a particular receiver.
+    pub fn exec(
+        &self,
+        unbound_receiver: Option<Object<'gc>>,
+        arguments: &[Value<'gc>],
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let receiver = self.receiver.or(unbound_receiver);
+
+        match &self.method {
+            Method::Native(nf) => nf(context, receiver, arguments),
+            Method::Entry(bm) => {
+                context.avm2.enter_call()?;
+                let result = Activation::from_method(
+                    context,
+                    bm.clone(),
+                    self.scope,
+                    receiver,
+                    arguments,
+                    self.defining_class,
+                )
+                .and_then(|mut activation| activation.run(context));
+                context.avm2.exit_call();
+
+                result
+            }
+        }
+    }
+}
+
+/// An Object which can be called to execute its function code.
+///
+/// Class objects are also `FunctionObject`s; calling them runs their
+/// instance initializer.
+#[derive(Collect, Debug, Clone, Copy)]
+#[collect(no_drop)]
+pub struct FunctionObject<'gc>(GcCell<'gc, FunctionObjectData<'gc>>);
+
+#[derive(Collect, Debug, Clone)]
+#[collect(no_drop)]
+pub struct FunctionObjectData<'gc> {
+    /// Base script object
+    base: ScriptObjectData<'gc>,
+
+    /// Executable code
+    exec: Option<Executable<'gc>>,
+
+    /// The class this object constructs, if it is a class object.
+    class: Option<GcCell<'gc, Class<'gc>>>,
+}
+
+impl<'gc> FunctionObject<'gc> {
+    fn allocate(
+        mc: MutationContext<'gc, '_>,
+        fn_proto: Object<'gc>,
+        exec: Option<Executable<'gc>>,
+        class: Option<GcCell<'gc, Class<'gc>>>,
+    ) -> Self {
+        FunctionObject(GcCell::allocate(
+            mc,
+            FunctionObjectData {
+                base: ScriptObjectData::base_new(Some(fn_proto)),
+                exec,
+                class,
+            },
+        ))
+    }
+
+    /// Construct a function from a method and the scope it closes over.
+    pub fn from_method(
+        mc: MutationContext<'gc, '_>,
+        method: Method<'gc>,
+        scope: Option<GcCell<'gc, Scope<'gc>>>,
+        fn_proto: Object<'gc>,
+        defining_class: Option<Object<'gc>>,
+    ) -> Object<'gc> {
+        let exec = Executable::from_method(method, scope, None, defining_class);
+
+        Self::allocate(mc, fn_proto, Some(exec), None).into()
+    }
+
+    /// Construct a builtin function object from a Rust function.
+    pub fn from_builtin(
+        mc: MutationContext<'gc, '_>,
+        nf: NativeMethod<'gc>,
+        fn_proto: Object<'gc>,
+    ) -> Object<'gc> {
+        Self::from_method(mc, nf.into(), None, fn_proto, None)
+    }
+
+    /// Construct a class object for a natively implemented class.
+    ///
+    /// The given prototype becomes the class's `prototype` property.
+    pub fn from_native_class(
+        mc: MutationContext<'gc, '_>,
+        class: Class<'gc>,
+        proto: Object<'gc>,
+        fn_proto: Object<'gc>,
+    ) -> Object<'gc> {
+        let instance_init = class.instance_init().clone();
+        let class_object: Object<'gc> =
+            Self::allocate(mc, fn_proto, None, Some(GcCell::allocate(mc, class))).into();
+
+        if let Object::FunctionObject(function) = class_object {
+            function.0.write(mc).exec = Some(Executable::from_method(
+                instance_init,
+                None,
+                None,
+                Some(class_object),
+            ));
+        }
+
+        class_object.install_method(mc, QName::dynamic_name("prototype"), proto);
+        proto.install_method(mc, QName::dynamic_name("constructor"), class_object);
+
+        class_object
+    }
+
+    /// Construct a class object from an ABC class definition.
+    ///
+    /// This is the implementation of the `newclass` opcode: it creates the
+    /// class's prototype, installs its instance methods and class traits,
+    /// and runs the class initializer.
+    pub fn from_abc_class(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        translation_unit: TranslationUnit,
+        class_index: Index<AbcClass>,
+        super_class: Option<Object<'gc>>,
+        scope: Option<GcCell<'gc, Scope<'gc>>>,
+    ) -> Result<Object<'gc>, Error<'gc>> {
+        let abc = translation_unit.abc();
+        let abc_instance = abc
+            .instances
+            .get(class_index.0 as usize)
+            .ok_or_else(|| Error::InvalidConstant(format!("Unknown class {}", class_index.0)))?;
+        let abc_class = abc
+            .classes
+            .get(class_index.0 as usize)
+            .ok_or_else(|| Error::InvalidConstant(format!("Unknown class {}", class_index.0)))?;
+        let name = QName::from_abc_multiname(abc, abc_instance.name.clone())?;
+
+        let super_proto = match super_class {
+            Some(super_class) => super_class
+                .get_property(super_class, &QName::dynamic_name("prototype"), context)?
+                .as_object(),
+            None => None,
+        };
+        let super_proto = super_proto.unwrap_or(context.avm2.prototypes().object);
+        let fn_proto = context.avm2.prototypes().function;
+        let mc = context.gc_context;
+
+        let instance_init = BytecodeMethod::from_method_index(
+            translation_unit.clone(),
+            abc_instance.init_method.clone(),
+        )
+        .ok_or_else(|| {
+            Error::InvalidConstant(format!(
+                "Unknown instance initializer {}",
+                abc_instance.init_method.0
+            ))
+        })?;
+        let class_init = BytecodeMethod::from_method_index(
+            translation_unit.clone(),
+            abc_class.init_method.clone(),
+        )
+        .ok_or_else(|| {
+            Error::InvalidConstant(format!(
+                "Unknown class initializer {}",
+                abc_class.init_method.0
+            ))
+        })?;
+
+        let class = Class::from_abc(
+            name,
+            super_class,
+            instance_init.clone().into(),
+            translation_unit.clone(),
+            class_index.0,
+        );
+        let function = Self::allocate(mc, fn_proto, None, Some(GcCell::allocate(mc, class)));
+        let class_object: Object<'gc> = function.into();
+        let class_scope = Scope::push_scope(scope, class_object, ScopeClass::Ordinary, mc);
+        function.0.write(mc).exec = Some(Executable::from_method(
+            instance_init.into(),
+            Some(class_scope),
+            None,
+            Some(class_object),
+        ));
+
+        let proto = ScriptObject::object(mc, super_proto);
+        class_object.install_method(mc, QName::dynamic_name("prototype"), proto);
+        proto.install_method(mc, QName::dynamic_name("constructor"), class_object);
+
+        for abc_trait in abc_instance.traits.iter() {
+            if !is_instance_slot(abc_trait) {
+                install_trait(
+                    context,
+                    &translation_unit,
+                    abc_trait,
+                    Some(class_scope),
+                    proto,
+                    Some(class_object),
+                )?;
+            }
+        }
+
+        for abc_trait in abc_class.traits.iter() {
+            install_trait(
+                context,
+                &translation_unit,
+                abc_trait,
+                Some(class_scope),
+                class_object,
+                None,
+            )?;
+        }
+
+        Executable::from_method(class_init.into(), Some(class_scope), None, None).exec(
+            Some(class_object),
+            &[],
+            context,
+        )?;
+
+        Ok(class_object)
+    }
+}
+
+impl<'gc> TObject<'gc> for FunctionObject<'gc> {
+    impl_avm2_custom_object!(base);
+    impl_avm2_custom_object_properties!(base);
+
+    fn as_executable(self) -> Option<Executable<'gc>> {
+        self.0.read().exec.clone()
+    }
+
+    fn as_class(self) -> Option<GcCell<'gc, Class<'gc>>> {
+        self.0.read().class
+    }
+
+    fn call(
+        self,
+        receiver: Option<Object<'gc>>,
+        arguments: &[Value<'gc>],
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let (exec, class) = {
+            let read = self.0.read();
+            (read.exec.clone(), read.class)
+        };
+
+        if let Some(class) = class {
+            let call_handler = class.read().call_handler();
+            return match call_handler {
+                Some(call_handler) => call_handler(context, receiver, arguments),
+                None => Ok(arguments.get(0).cloned().unwrap_or(Value::Undefined)),
+            };
+        }
+
+        exec.ok_or(Error::NotCallable)?
+            .exec(receiver, arguments, context)
+    }
+
+    fn construct(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        arguments: &[Value<'gc>],
+    ) -> Result<Object<'gc>, Error<'gc>> {
+        let class = self.0.read().class;
+        let this: Object<'gc> = self.into();
+        let proto = this
+            .get_property(this, &QName::dynamic_name("prototype"), context)?
+            .as_object()
+            .unwrap_or(context.avm2.prototypes().object);
+
+        if let Some(class) = class {
+            let allocator = class.read().allocator().unwrap_or(scriptobject_allocator);
+            let instance = allocator(proto, context.gc_context);
+            initialize_instance(context, this, instance, arguments)?;
+
+            Ok(instance)
+        } else {
+            let instance = ScriptObject::object(context.gc_context, proto);
+            self.call(Some(instance), arguments, context)?;
+
+            Ok(instance)
+        }
+    }
+}
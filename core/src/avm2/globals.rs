@@ -0,0 +1,287 @@
+//! Global scope built-ins
+
+use crate::avm2::class::Class;
+use crate::avm2::function::FunctionObject;
+use crate::avm2::method::NativeMethod;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::{Error, UpdateContext, Value};
+use gc_arena::{Collect, MutationContext};
+
+mod array;
+mod boolean;
+mod error;
+pub(crate) mod flash;
+mod function;
+mod math;
+mod number;
+mod object;
+mod string;
+
+/// A structure containing all system prototypes, which are used internally
+/// to construct new objects.
+#[derive(Clone, Collect)]
+#[collect(no_drop)]
+pub struct SystemPrototypes<'gc> {
+    pub object: Object<'gc>,
+    pub function: Object<'gc>,
+    pub string: Object<'gc>,
+    pub number: Object<'gc>,
+    pub boolean: Object<'gc>,
+    pub array: Object<'gc>,
+    pub error: Object<'gc>,
+    pub display_object: Object<'gc>,
+    pub sprite: Object<'gc>,
+    pub movie_clip: Object<'gc>,
+}
+
+/// Implements `trace`
+pub fn trace<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let mut message = Vec::with_capacity(args.len());
+    for arg in args {
+        message.push(arg.coerce_to_string(context)?);
+    }
+
+    log::info!(target: "avm_trace", "{}", message.join(" "));
+
+    Ok(Value::Undefined)
+}
+
+/// An instance initializer that does nothing.
+pub fn noop_init<'gc>(
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Install a native function on an object under a public name.
+pub fn install_function<'gc>(
+    mc: MutationContext<'gc, '_>,
+    target: Object<'gc>,
+    name: &str,
+    nf: NativeMethod<'gc>,
+    fn_proto: Object<'gc>,
+) {
+    target.install_method(
+        mc,
+        QName::dynamic_name(name),
+        FunctionObject::from_builtin(mc, nf, fn_proto),
+    );
+}
+
+/// Install a native getter, and optionally a setter, on an object under a
+/// public name.
+pub fn install_accessor<'gc>(
+    mc: MutationContext<'gc, '_>,
+    target: Object<'gc>,
+    name: &str,
+    getter: NativeMethod<'gc>,
+    setter: Option<NativeMethod<'gc>>,
+    fn_proto: Object<'gc>,
+) {
+    target.install_getter(
+        mc,
+        QName::dynamic_name(name),
+        FunctionObject::from_builtin(mc, getter, fn_proto),
+    );
+
+    if let Some(setter) = setter {
+        target.install_setter(
+            mc,
+            QName::dynamic_name(name),
+            FunctionObject::from_builtin(mc, setter, fn_proto),
+        );
+    }
+}
+
+/// Create a native class object and install it on the global object.
+fn install_class<'gc>(
+    mc: MutationContext<'gc, '_>,
+    globals: Object<'gc>,
+    class: Class<'gc>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let name = class.name().clone();
+    let class_object = FunctionObject::from_native_class(mc, class, proto, fn_proto);
+    globals.install_const(mc, name, 0, class_object.into());
+
+    class_object
+}
+
+/// Construct the global object and all builtin classes.
+pub fn create_globals<'gc>(mc: MutationContext<'gc, '_>) -> (Object<'gc>, SystemPrototypes<'gc>) {
+    let object_proto = ScriptObject::bare_object(mc);
+    let function_proto = function::create_proto(mc, object_proto);
+    object::fill_proto(mc, object_proto, function_proto);
+
+    let globals = ScriptObject::object(mc, object_proto);
+    let public = Namespace::public_namespace;
+
+    let object_class = install_class(
+        mc,
+        globals,
+        Class::native(QName::new(public(), "Object"), None, object::instance_init)
+            .with_call_handler(object::call_handler),
+        object_proto,
+        function_proto,
+    );
+    let _function_class = install_class(
+        mc,
+        globals,
+        Class::native(
+            QName::new(public(), "Function"),
+            Some(object_class),
+            noop_init,
+        ),
+        function_proto,
+        function_proto,
+    );
+
+    let string_proto = string::create_proto(mc, object_proto, function_proto);
+    install_class(
+        mc,
+        globals,
+        Class::native(
+            QName::new(public(), "String"),
+            Some(object_class),
+            noop_init,
+        )
+        .with_call_handler(string::call_handler),
+        string_proto,
+        function_proto,
+    );
+
+    let number_proto = number::create_proto(mc, object_proto, function_proto);
+    install_class(
+        mc,
+        globals,
+        Class::native(
+            QName::new(public(), "Number"),
+            Some(object_class),
+            noop_init,
+        )
+        .with_call_handler(number::number_call_handler),
+        number_proto,
+        function_proto,
+    );
+    let int_proto = number::create_proto(mc, object_proto, function_proto);
+    install_class(
+        mc,
+        globals,
+        Class::native(QName::new(public(), "int"), Some(object_class), noop_init)
+            .with_call_handler(number::int_call_handler),
+        int_proto,
+        function_proto,
+    );
+    let uint_proto = number::create_proto(mc, object_proto, function_proto);
+    install_class(
+        mc,
+        globals,
+        Class::native(QName::new(public(), "uint"), Some(object_class), noop_init)
+            .with_call_handler(number::uint_call_handler),
+        uint_proto,
+        function_proto,
+    );
+
+    let boolean_proto = boolean::create_proto(mc, object_proto, function_proto);
+    install_class(
+        mc,
+        globals,
+        Class::native(
+            QName::new(public(), "Boolean"),
+            Some(object_class),
+            noop_init,
+        )
+        .with_call_handler(boolean::call_handler),
+        boolean_proto,
+        function_proto,
+    );
+
+    let array_proto = array::create_proto(mc, object_proto, function_proto);
+    install_class(
+        mc,
+        globals,
+        Class::native(
+            QName::new(public(), "Array"),
+            Some(object_class),
+            array::instance_init,
+        )
+        .with_allocator(crate::avm2::array_object::array_allocator)
+        .with_call_handler(array::call_handler),
+        array_proto,
+        function_proto,
+    );
+
+    let error_proto = error::create_proto(mc, object_proto, function_proto);
+    let error_class = install_class(
+        mc,
+        globals,
+        Class::native(
+            QName::new(public(), "Error"),
+            Some(object_class),
+            error::instance_init,
+        ),
+        error_proto,
+        function_proto,
+    );
+    for error_name in &[
+        "ArgumentError",
+        "RangeError",
+        "ReferenceError",
+        "SyntaxError",
+        "TypeError",
+        "VerifyError",
+    ] {
+        let proto = error::create_subclass_proto(mc, error_proto, error_name);
+        install_class(
+            mc,
+            globals,
+            Class::native(
+                QName::new(public(), error_name),
+                Some(error_class),
+                error::instance_init,
+            ),
+            proto,
+            function_proto,
+        );
+    }
+
+    let math = math::create(mc, object_proto, function_proto);
+    globals.install_const(mc, QName::new(public(), "Math"), 0, math.into());
+
+    install_function(mc, globals, "trace", trace, function_proto);
+    globals.install_const(mc, QName::new(public(), "NaN"), 0, std::f64::NAN.into());
+    globals.install_const(
+        mc,
+        QName::new(public(), "Infinity"),
+        0,
+        std::f64::INFINITY.into(),
+    );
+    globals.install_const(mc, QName::new(public(), "undefined"), 0, Value::Undefined);
+
+    let flash = flash::create(mc, globals, object_class, object_proto, function_proto);
+
+    (
+        globals,
+        SystemPrototypes {
+            object: object_proto,
+            function: function_proto,
+            string: string_proto,
+            number: number_proto,
+            boolean: boolean_proto,
+            array: array_proto,
+            error: error_proto,
+            display_object: flash.display_object,
+            sprite: flash.sprite,
+            movie_clip: flash.movie_clip,
+        },
+    )
+}
@@ -0,0 +1,276 @@
+//! `Array` builtin and prototype
+
+use crate::avm2::array_object::ArrayObject;
+use crate::avm2::globals::install_function;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::{Error, UpdateContext, Value};
+use gc_arena::MutationContext;
+
+/// Fill a freshly allocated array with the arguments to its constructor.
+fn fill_array<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    array: ArrayObject<'gc>,
+    args: &[Value<'gc>],
+) -> Result<(), Error<'gc>> {
+    if let [Value::Number(length)] = args {
+        if length.fract() != 0.0 || *length < 0.0 || *length > f64::from(u32::MAX) {
+            return Err(Error::ThrownValue(
+                "RangeError: Error #1005: Array index is not a positive integer.".into(),
+            ));
+        }
+
+        array.set_length(context.gc_context, *length as usize);
+    } else {
+        array.replace(context.gc_context, args.to_vec());
+    }
+
+    Ok(())
+}
+
+/// Implements `Array`'s instance initializer.
+pub fn instance_init<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(array) = this.and_then(|this| this.as_array_object()) {
+        fill_array(context, array, args)?;
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Array` when called as a function.
+pub fn call_handler<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let array_proto = context.avm2.prototypes().array;
+    let array = ArrayObject::from_vec(context.gc_context, array_proto, vec![]);
+    fill_array(context, array, args)?;
+
+    Ok(Object::from(array).into())
+}
+
+/// Retrieve the array a method was called on.
+fn this_array<'gc>(this: Option<Object<'gc>>) -> Result<ArrayObject<'gc>, Error<'gc>> {
+    this.and_then(|this| this.as_array_object())
+        .ok_or_else(|| Error::ThrownValue("TypeError: Error #1034: Type Coercion failed.".into()))
+}
+
+/// Implements `Array.prototype.push`
+fn push<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let array = this_array(this)?;
+    let mut values = array.to_vec();
+    values.extend_from_slice(args);
+    let length = values.len();
+    array.replace(context.gc_context, values);
+
+    Ok((length as f64).into())
+}
+
+/// Implements `Array.prototype.pop`
+fn pop<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let array = this_array(this)?;
+    let mut values = array.to_vec();
+    let value = values.pop().unwrap_or(Value::Undefined);
+    array.replace(context.gc_context, values);
+
+    Ok(value)
+}
+
+/// Implements `Array.prototype.shift`
+fn shift<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let array = this_array(this)?;
+    let mut values = array.to_vec();
+    if values.is_empty() {
+        return Ok(Value::Undefined);
+    }
+
+    let value = values.remove(0);
+    array.replace(context.gc_context, values);
+
+    Ok(value)
+}
+
+/// Implements `Array.prototype.unshift`
+fn unshift<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let array = this_array(this)?;
+    let mut values = args.to_vec();
+    values.extend(array.to_vec());
+    let length = values.len();
+    array.replace(context.gc_context, values);
+
+    Ok((length as f64).into())
+}
+
+/// Implements `Array.prototype.join`
+fn join<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let array = this_array(this)?;
+    let separator = match args.get(0) {
+        Some(Value::Undefined) | None => ",".to_string(),
+        Some(separator) => separator.coerce_to_string(context)?,
+    };
+
+    let mut parts = Vec::with_capacity(array.length());
+    for value in array.to_vec() {
+        parts.push(match value {
+            Value::Undefined | Value::Null => "".to_string(),
+            value => value.coerce_to_string(context)?,
+        });
+    }
+
+    Ok(parts.join(&separator).into())
+}
+
+/// Implements `Array.prototype.toString`
+fn to_string<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    join(context, this, &[])
+}
+
+/// Resolve a possibly negative index into an array of a given length.
+fn resolve_index(index: f64, length: usize) -> usize {
+    if index < 0.0 {
+        (length as f64 + index.trunc()).max(0.0) as usize
+    } else if index.is_nan() {
+        0
+    } else {
+        (index.trunc() as usize).min(length)
+    }
+}
+
+/// Implements `Array.prototype.slice`
+fn slice<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let array = this_array(this)?;
+    let values = array.to_vec();
+    let start = match args.get(0) {
+        Some(start) => resolve_index(start.coerce_to_number(context)?, values.len()),
+        None => 0,
+    };
+    let end = match args.get(1) {
+        Some(Value::Undefined) | None => values.len(),
+        Some(end) => resolve_index(end.coerce_to_number(context)?, values.len()),
+    };
+    let sliced = values.get(start..end).unwrap_or(&[]).to_vec();
+    let array_proto = context.avm2.prototypes().array;
+
+    Ok(Object::from(ArrayObject::from_vec(
+        context.gc_context,
+        array_proto,
+        sliced,
+    ))
+    .into())
+}
+
+/// Implements `Array.prototype.concat`
+fn concat<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let mut values = this_array(this)?.to_vec();
+    for arg in args {
+        match arg.as_object().and_then(|arg| arg.as_array_object()) {
+            Some(array) => values.extend(array.to_vec()),
+            None => values.push(arg.clone()),
+        }
+    }
+
+    let array_proto = context.avm2.prototypes().array;
+
+    Ok(Object::from(ArrayObject::from_vec(
+        context.gc_context,
+        array_proto,
+        values,
+    ))
+    .into())
+}
+
+/// Implements `Array.prototype.reverse`
+fn reverse<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let array = this_array(this)?;
+    let mut values = array.to_vec();
+    values.reverse();
+    array.replace(context.gc_context, values);
+
+    Ok(Object::from(array).into())
+}
+
+/// Implements `Array.prototype.indexOf`
+fn index_of<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let values = this_array(this)?.to_vec();
+    let search = args.get(0).cloned().unwrap_or(Value::Undefined);
+    let start = match args.get(1) {
+        Some(start) => resolve_index(start.coerce_to_number(context)?, values.len()),
+        None => 0,
+    };
+
+    let found = values
+        .iter()
+        .enumerate()
+        .skip(start)
+        .find(|(_, value)| **value == search)
+        .map(|(index, _)| index as f64)
+        .unwrap_or(-1.0);
+
+    Ok(found.into())
+}
+
+/// Construct `Array.prototype`.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    object_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let proto: Object<'gc> = ArrayObject::from_vec(mc, object_proto, vec![]).into();
+
+    install_function(mc, proto, "push", push, fn_proto);
+    install_function(mc, proto, "pop", pop, fn_proto);
+    install_function(mc, proto, "shift", shift, fn_proto);
+    install_function(mc, proto, "unshift", unshift, fn_proto);
+    install_function(mc, proto, "join", join, fn_proto);
+    install_function(mc, proto, "toString", to_string, fn_proto);
+    install_function(mc, proto, "slice", slice, fn_proto);
+    install_function(mc, proto, "concat", concat, fn_proto);
+    install_function(mc, proto, "reverse", reverse, fn_proto);
+    install_function(mc, proto, "indexOf", index_of, fn_proto);
+
+    proto
+}
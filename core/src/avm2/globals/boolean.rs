@@ -0,0 +1,59 @@
+//! `Boolean` builtin and prototype
+
+use crate::avm2::globals::install_function;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::{Error, UpdateContext, Value};
+use gc_arena::MutationContext;
+
+/// Implements `Boolean` when called as a function.
+pub fn call_handler<'gc>(
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(args
+        .get(0)
+        .map(|value| value.coerce_to_boolean())
+        .unwrap_or(false)
+        .into())
+}
+
+/// Implements `Boolean.prototype.toString`
+fn to_string<'gc>(
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let value = this
+        .map(|this| this.value_of().coerce_to_boolean())
+        .unwrap_or(false);
+
+    Ok(if value { "true" } else { "false" }.into())
+}
+
+/// Implements `Boolean.prototype.valueOf`
+fn value_of<'gc>(
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this
+        .map(|this| this.value_of().coerce_to_boolean())
+        .unwrap_or(false)
+        .into())
+}
+
+/// Construct `Boolean.prototype`.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    object_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let proto = ScriptObject::object(mc, object_proto);
+
+    install_function(mc, proto, "toString", to_string, fn_proto);
+    install_function(mc, proto, "valueOf", value_of, fn_proto);
+
+    proto
+}
@@ -0,0 +1,81 @@
+//! `Error` builtin and its subclasses
+
+use crate::avm2::globals::install_function;
+use crate::avm2::names::QName;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::{Error, UpdateContext, Value};
+use gc_arena::MutationContext;
+
+/// Implements `Error`'s instance initializer.
+pub fn instance_init<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        let message = match args.get(0) {
+            Some(Value::Undefined) | None => "".to_string(),
+            Some(message) => message.coerce_to_string(context)?,
+        };
+        this.install_dynamic_property(
+            context.gc_context,
+            QName::dynamic_name("message"),
+            message.into(),
+        );
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Error.prototype.toString`
+fn to_string<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = match this {
+        Some(this) => this,
+        None => return Ok("Error".into()),
+    };
+    let name = this
+        .get_property(this, &QName::dynamic_name("name"), context)?
+        .coerce_to_string(context)?;
+    let message = this
+        .get_property(this, &QName::dynamic_name("message"), context)?
+        .coerce_to_string(context)?;
+
+    if message.is_empty() {
+        Ok(name.into())
+    } else {
+        Ok(format!("{}: {}", name, message).into())
+    }
+}
+
+/// Construct `Error.prototype`.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    object_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let proto = ScriptObject::object(mc, object_proto);
+
+    proto.install_dynamic_property(mc, QName::dynamic_name("name"), "Error".into());
+    proto.install_dynamic_property(mc, QName::dynamic_name("message"), "".into());
+    install_function(mc, proto, "toString", to_string, fn_proto);
+
+    proto
+}
+
+/// Construct the prototype of a builtin subclass of `Error`.
+pub fn create_subclass_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    error_proto: Object<'gc>,
+    name: &str,
+) -> Object<'gc> {
+    let proto = ScriptObject::object(mc, error_proto);
+
+    proto.install_dynamic_property(mc, QName::dynamic_name("name"), name.into());
+
+    proto
+}
@@ -0,0 +1,141 @@
+//! `flash` namespace
+
+use crate::avm2::class::Class;
+use crate::avm2::function::FunctionObject;
+use crate::avm2::globals::noop_init;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::ScriptObject;
+use gc_arena::MutationContext;
+
+pub mod display;
+pub mod events;
+
+/// Prototypes of `flash` classes that native code needs to construct.
+pub struct FlashPrototypes<'gc> {
+    pub display_object: Object<'gc>,
+    pub sprite: Object<'gc>,
+    pub movie_clip: Object<'gc>,
+}
+
+/// Define a class in the `flash` namespace and return its class object.
+fn define_class<'gc>(
+    mc: MutationContext<'gc, '_>,
+    globals: Object<'gc>,
+    class: Class<'gc>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let name = class.name().clone();
+    let class_object = FunctionObject::from_native_class(mc, class, proto, fn_proto);
+    globals.install_const(mc, name, 0, class_object.into());
+
+    class_object
+}
+
+/// Construct all of the classes in the `flash` namespace that we support.
+pub fn create<'gc>(
+    mc: MutationContext<'gc, '_>,
+    globals: Object<'gc>,
+    object_class: Object<'gc>,
+    object_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> FlashPrototypes<'gc> {
+    let events = Namespace::package("flash.events");
+    let display = Namespace::package("flash.display");
+
+    let event_dispatcher_proto = events::event_dispatcher::create_proto(mc, object_proto, fn_proto);
+    let event_dispatcher = define_class(
+        mc,
+        globals,
+        Class::native(
+            QName::new(events.clone(), "EventDispatcher"),
+            Some(object_class),
+            noop_init,
+        ),
+        event_dispatcher_proto,
+        fn_proto,
+    );
+
+    let event_proto = events::event::create_proto(mc, object_proto, fn_proto);
+    let event = define_class(
+        mc,
+        globals,
+        Class::native(
+            QName::new(events, "Event"),
+            Some(object_class),
+            events::event::instance_init,
+        ),
+        event_proto,
+        fn_proto,
+    );
+    events::event::install_constants(mc, event);
+
+    let display_object_proto =
+        display::display_object::create_proto(mc, event_dispatcher_proto, fn_proto);
+    let display_object = define_class(
+        mc,
+        globals,
+        Class::native(
+            QName::new(display.clone(), "DisplayObject"),
+            Some(event_dispatcher),
+            noop_init,
+        ),
+        display_object_proto,
+        fn_proto,
+    );
+
+    let interactive_object_proto = ScriptObject::object(mc, display_object_proto);
+    let interactive_object = define_class(
+        mc,
+        globals,
+        Class::native(
+            QName::new(display.clone(), "InteractiveObject"),
+            Some(display_object),
+            noop_init,
+        ),
+        interactive_object_proto,
+        fn_proto,
+    );
+
+    let container_proto = ScriptObject::object(mc, interactive_object_proto);
+    let container = define_class(
+        mc,
+        globals,
+        Class::native(
+            QName::new(display.clone(), "DisplayObjectContainer"),
+            Some(interactive_object),
+            noop_init,
+        ),
+        container_proto,
+        fn_proto,
+    );
+
+    let sprite_proto = ScriptObject::object(mc, container_proto);
+    let sprite = define_class(
+        mc,
+        globals,
+        Class::native(
+            QName::new(display.clone(), "Sprite"),
+            Some(container),
+            noop_init,
+        ),
+        sprite_proto,
+        fn_proto,
+    );
+
+    let movie_clip_proto = display::movie_clip::create_proto(mc, sprite_proto, fn_proto);
+    define_class(
+        mc,
+        globals,
+        Class::native(QName::new(display, "MovieClip"), Some(sprite), noop_init),
+        movie_clip_proto,
+        fn_proto,
+    );
+
+    FlashPrototypes {
+        display_object: display_object_proto,
+        sprite: sprite_proto,
+        movie_clip: movie_clip_proto,
+    }
+}
@@ -0,0 +1,4 @@
+//! `flash.display` namespace
+
+pub mod display_object;
+pub mod movie_clip;
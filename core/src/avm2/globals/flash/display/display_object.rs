@@ -0,0 +1,167 @@
+//! `flash.display.DisplayObject` builtin
+
+use crate::avm2::globals::install_accessor;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::{Error, UpdateContext, Value};
+use crate::display_object::TDisplayObject;
+use gc_arena::MutationContext;
+
+/// Implements `DisplayObject.x`'s getter.
+fn x<'gc>(
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this
+        .and_then(|this| this.as_display_object())
+        .map(|dobj| dobj.x().into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Implements `DisplayObject.x`'s setter.
+fn set_x<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mut dobj) = this.and_then(|this| this.as_display_object()) {
+        let value = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_number(context)?;
+        dobj.set_x(context.gc_context, value);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DisplayObject.y`'s getter.
+fn y<'gc>(
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this
+        .and_then(|this| this.as_display_object())
+        .map(|dobj| dobj.y().into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Implements `DisplayObject.y`'s setter.
+fn set_y<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mut dobj) = this.and_then(|this| this.as_display_object()) {
+        let value = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_number(context)?;
+        dobj.set_y(context.gc_context, value);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DisplayObject.alpha`'s getter.
+fn alpha<'gc>(
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this
+        .and_then(|this| this.as_display_object())
+        .map(|dobj| dobj.alpha().into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Implements `DisplayObject.alpha`'s setter.
+fn set_alpha<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(dobj) = this.and_then(|this| this.as_display_object()) {
+        let value = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_number(context)?;
+        dobj.set_alpha(context.gc_context, value);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DisplayObject.visible`'s getter.
+fn visible<'gc>(
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this
+        .and_then(|this| this.as_display_object())
+        .map(|dobj| dobj.visible().into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Implements `DisplayObject.visible`'s setter.
+fn set_visible<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mut dobj) = this.and_then(|this| this.as_display_object()) {
+        let value = args.get(0).unwrap_or(&Value::Undefined).coerce_to_boolean();
+        dobj.set_visible(context.gc_context, value);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `DisplayObject.name`'s getter.
+fn name<'gc>(
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this
+        .and_then(|this| this.as_display_object())
+        .map(|dobj| dobj.name().to_string().into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Implements `DisplayObject.name`'s setter.
+fn set_name<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(mut dobj) = this.and_then(|this| this.as_display_object()) {
+        let value = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .coerce_to_string(context)?;
+        dobj.set_name(context.gc_context, &value);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `DisplayObject.prototype`.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    event_dispatcher_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let proto = ScriptObject::object(mc, event_dispatcher_proto);
+
+    install_accessor(mc, proto, "x", x, Some(set_x), fn_proto);
+    install_accessor(mc, proto, "y", y, Some(set_y), fn_proto);
+    install_accessor(mc, proto, "alpha", alpha, Some(set_alpha), fn_proto);
+    install_accessor(mc, proto, "visible", visible, Some(set_visible), fn_proto);
+    install_accessor(mc, proto, "name", name, Some(set_name), fn_proto);
+
+    proto
+}
@@ -0,0 +1,199 @@
+//! `flash.display.MovieClip` builtin
+
+use crate::avm2::globals::{install_accessor, install_function};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::{Error, UpdateContext, Value};
+use crate::context::ActionType;
+use crate::display_object::{MovieClip, TDisplayObject};
+use gc_arena::MutationContext;
+
+/// Retrieve the movie clip a method was called on, if any.
+fn this_clip<'gc>(this: Option<Object<'gc>>) -> Option<MovieClip<'gc>> {
+    this.and_then(|this| this.as_display_object())
+        .and_then(|dobj| dobj.as_movie_clip())
+}
+
+/// Resolve a frame number or label to a frame number.
+fn frame_number<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    clip: MovieClip<'gc>,
+    frame: &Value<'gc>,
+) -> Result<Option<u16>, Error<'gc>> {
+    Ok(match frame {
+        Value::String(label) => clip.frame_label_to_number(label),
+        frame => {
+            let frame = frame.coerce_to_i32(context)?;
+            Some(frame.max(1).min(i32::from(u16::MAX)) as u16)
+        }
+    })
+}
+
+/// Queue a goto on a movie clip.
+fn queue_goto<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+    stop: bool,
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(clip) = this_clip(this) {
+        let frame = args.get(0).cloned().unwrap_or(Value::Undefined);
+        if let Some(frame) = frame_number(context, clip, &frame)? {
+            context.action_queue.queue_actions(
+                clip.into(),
+                ActionType::Avm2Goto { frame, stop },
+                false,
+            );
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `MovieClip.prototype.play`
+fn play<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(clip) = this_clip(this) {
+        clip.play(context);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `MovieClip.prototype.stop`
+fn stop<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(clip) = this_clip(this) {
+        clip.stop(context);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `MovieClip.prototype.gotoAndPlay`
+fn goto_and_play<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    queue_goto(context, this, args, false)
+}
+
+/// Implements `MovieClip.prototype.gotoAndStop`
+fn goto_and_stop<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    queue_goto(context, this, args, true)
+}
+
+/// Implements `MovieClip.prototype.nextFrame`
+fn next_frame<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(clip) = this_clip(this) {
+        if clip.current_frame() < clip.total_frames() {
+            let frame = clip.current_frame() + 1;
+            context.action_queue.queue_actions(
+                clip.into(),
+                ActionType::Avm2Goto { frame, stop: true },
+                false,
+            );
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `MovieClip.prototype.prevFrame`
+fn prev_frame<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(clip) = this_clip(this) {
+        if clip.current_frame() > 1 {
+            let frame = clip.current_frame() - 1;
+            context.action_queue.queue_actions(
+                clip.into(),
+                ActionType::Avm2Goto { frame, stop: true },
+                false,
+            );
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `MovieClip.currentFrame`'s getter.
+fn current_frame<'gc>(
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_clip(this)
+        .map(|clip| f64::from(clip.current_frame()).into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Implements `MovieClip.totalFrames`'s getter.
+fn total_frames<'gc>(
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this_clip(this)
+        .map(|clip| f64::from(clip.total_frames()).into())
+        .unwrap_or(Value::Undefined))
+}
+
+/// Implements `MovieClip.prototype.addFrameScript`
+///
+/// Arguments are pairs of zero-based frame numbers and functions to run when
+/// that frame is entered. A `null` function removes a frame script.
+fn add_frame_script<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(clip) = this_clip(this) {
+        for pair in args.chunks_exact(2) {
+            let frame = pair[0].coerce_to_u32(context)?;
+            if frame < u32::from(u16::MAX) {
+                clip.set_frame_script(context.gc_context, frame as u16 + 1, pair[1].as_object());
+            }
+        }
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Construct `MovieClip.prototype`.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    sprite_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let proto = ScriptObject::object(mc, sprite_proto);
+
+    install_function(mc, proto, "play", play, fn_proto);
+    install_function(mc, proto, "stop", stop, fn_proto);
+    install_function(mc, proto, "gotoAndPlay", goto_and_play, fn_proto);
+    install_function(mc, proto, "gotoAndStop", goto_and_stop, fn_proto);
+    install_function(mc, proto, "nextFrame", next_frame, fn_proto);
+    install_function(mc, proto, "prevFrame", prev_frame, fn_proto);
+    install_function(mc, proto, "addFrameScript", add_frame_script, fn_proto);
+    install_accessor(mc, proto, "currentFrame", current_frame, None, fn_proto);
+    install_accessor(mc, proto, "totalFrames", total_frames, None, fn_proto);
+
+    proto
+}
@@ -0,0 +1,4 @@
+//! `flash.events` namespace
+
+pub mod event;
+pub mod event_dispatcher;
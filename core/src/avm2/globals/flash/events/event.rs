@@ -0,0 +1,121 @@
+//! `flash.events.Event` builtin
+
+use crate::avm2::globals::install_function;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::{Error, UpdateContext, Value};
+use gc_arena::MutationContext;
+
+/// The event types that are constants on the `Event` class, and their names.
+const EVENT_TYPES: [(&str, &str); 1] = [("ENTER_FRAME", "enterFrame")];
+
+/// Implements `Event`'s instance initializer.
+pub fn instance_init<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(this) = this {
+        let event_type = args
+            .get(0)
+            .cloned()
+            .unwrap_or(Value::Undefined)
+            .coerce_to_string(context)?;
+        let bubbles = args.get(1).map(|v| v.coerce_to_boolean()).unwrap_or(false);
+        let cancelable = args.get(2).map(|v| v.coerce_to_boolean()).unwrap_or(false);
+
+        let mc = context.gc_context;
+        this.install_dynamic_property(mc, QName::dynamic_name("type"), event_type.into());
+        this.install_dynamic_property(mc, QName::dynamic_name("bubbles"), bubbles.into());
+        this.install_dynamic_property(mc, QName::dynamic_name("cancelable"), cancelable.into());
+        this.install_dynamic_property(mc, QName::dynamic_name("target"), Value::Null);
+        this.install_dynamic_property(mc, QName::dynamic_name("currentTarget"), Value::Null);
+        // Events are only ever dispatched at their target.
+        this.install_dynamic_property(mc, QName::dynamic_name("eventPhase"), 2.into());
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `Event.prototype.toString`
+fn to_string<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = match this {
+        Some(this) => this,
+        None => return Ok("[Event]".into()),
+    };
+    let event_type = this
+        .get_property(this, &QName::dynamic_name("type"), context)?
+        .coerce_to_string(context)?;
+    let bubbles = this
+        .get_property(this, &QName::dynamic_name("bubbles"), context)?
+        .coerce_to_boolean();
+    let cancelable = this
+        .get_property(this, &QName::dynamic_name("cancelable"), context)?
+        .coerce_to_boolean();
+    let event_phase = this
+        .get_property(this, &QName::dynamic_name("eventPhase"), context)?
+        .coerce_to_string(context)?;
+
+    Ok(format!(
+        "[Event type=\"{}\" bubbles={} cancelable={} eventPhase={}]",
+        event_type, bubbles, cancelable, event_phase
+    )
+    .into())
+}
+
+/// Construct `Event.prototype`.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    object_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let proto = ScriptObject::object(mc, object_proto);
+
+    install_function(mc, proto, "toString", to_string, fn_proto);
+
+    proto
+}
+
+/// Install the event type constants onto the `Event` class.
+pub fn install_constants<'gc>(mc: MutationContext<'gc, '_>, class_object: Object<'gc>) {
+    for (name, event_type) in EVENT_TYPES.iter() {
+        class_object.install_const(
+            mc,
+            QName::new(Namespace::public_namespace(), name),
+            0,
+            (*event_type).into(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm2::test_utils::with_avm2;
+    use crate::avm2::Avm2;
+
+    #[test]
+    fn construct_event() {
+        with_avm2(|context| -> Result<(), Error> {
+            let class = Avm2::lookup_class(context, "flash.events.Event")?.unwrap();
+            let enter_frame = class.get_property(
+                class,
+                &QName::new(Namespace::public_namespace(), "ENTER_FRAME"),
+                context,
+            )?;
+            assert_eq!(enter_frame, "enterFrame".into());
+
+            let event = class.construct(context, &[enter_frame, true.into()])?;
+            assert_eq!(
+                to_string(context, Some(event), &[])?,
+                "[Event type=\"enterFrame\" bubbles=true cancelable=false eventPhase=2]".into()
+            );
+            Ok(())
+        });
+    }
+}
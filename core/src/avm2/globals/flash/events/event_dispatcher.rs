@@ -0,0 +1,423 @@
+//! `flash.events.EventDispatcher` builtin
+
+use crate::avm2::array_object::ArrayObject;
+use crate::avm2::globals::install_function;
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::{Error, UpdateContext, Value};
+use gc_arena::MutationContext;
+
+/// The name of the slot that holds a dispatcher's listeners.
+///
+/// It is in a private namespace, so scripts can't see or replace it.
+fn listeners_name() -> QName {
+    QName::new(
+        Namespace::Private("flash.events:EventDispatcher".to_string()),
+        "listeners",
+    )
+}
+
+/// A registered listener, stored as an array of `[type, listener,
+/// useCapture, priority]` in the dispatcher's listener list.
+struct Listener<'gc> {
+    event_type: String,
+    listener: Object<'gc>,
+    use_capture: bool,
+    priority: i32,
+}
+
+impl<'gc> Listener<'gc> {
+    fn from_value(value: Value<'gc>) -> Option<Self> {
+        let entry = value.as_object()?.as_array_object()?;
+        let event_type = match entry.get(0) {
+            Value::String(event_type) => event_type,
+            _ => return None,
+        };
+        let priority = match entry.get(3) {
+            Value::Number(priority) => priority as i32,
+            _ => 0,
+        };
+
+        Some(Self {
+            event_type,
+            listener: entry.get(1).as_object()?,
+            use_capture: entry.get(2).coerce_to_boolean(),
+            priority,
+        })
+    }
+
+    fn into_value(self, context: &mut UpdateContext<'_, 'gc, '_>) -> Value<'gc> {
+        let array_proto = context.avm2.prototypes().array;
+        let entry = ArrayObject::from_vec(
+            context.gc_context,
+            array_proto,
+            vec![
+                self.event_type.into(),
+                self.listener.into(),
+                self.use_capture.into(),
+                self.priority.into(),
+            ],
+        );
+
+        Object::from(entry).into()
+    }
+
+    fn matches(&self, event_type: &str, listener: Object<'gc>, use_capture: bool) -> bool {
+        self.event_type == event_type
+            && Object::ptr_eq(self.listener, listener)
+            && self.use_capture == use_capture
+    }
+}
+
+/// The listener list of a dispatcher, creating it if asked to.
+fn listener_list<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    create: bool,
+) -> Result<Option<ArrayObject<'gc>>, Error<'gc>> {
+    let name = listeners_name();
+    if this.has_own_property(&name) {
+        let list = this.get_property(this, &name, context)?;
+        return Ok(list.as_object().and_then(|list| list.as_array_object()));
+    }
+
+    if !create {
+        return Ok(None);
+    }
+
+    let array_proto = context.avm2.prototypes().array;
+    let list = ArrayObject::from_vec(context.gc_context, array_proto, vec![]);
+    this.install_slot(context.gc_context, name, 0, Object::from(list).into());
+
+    Ok(Some(list))
+}
+
+/// The listeners registered on a dispatcher, in the order they are called.
+fn listeners<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+) -> Result<Vec<Listener<'gc>>, Error<'gc>> {
+    Ok(match listener_list(context, this, false)? {
+        Some(list) => list
+            .to_vec()
+            .into_iter()
+            .filter_map(Listener::from_value)
+            .collect(),
+        None => Vec::new(),
+    })
+}
+
+/// Read the `type`, `listener` and `useCapture` arguments shared by
+/// `addEventListener` and `removeEventListener`.
+fn listener_args<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<Option<(String, Object<'gc>, bool)>, Error<'gc>> {
+    let listener = match args.get(1).and_then(|listener| listener.as_object()) {
+        Some(listener) => listener,
+        None => return Ok(None),
+    };
+    let event_type = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(context)?;
+    let use_capture = args.get(2).map(|v| v.coerce_to_boolean()).unwrap_or(false);
+
+    Ok(Some((event_type, listener, use_capture)))
+}
+
+/// Implements `EventDispatcher.prototype.addEventListener`
+///
+/// Listeners are called in order of descending priority, and in the order
+/// they were added within the same priority. Adding a listener twice has no
+/// effect.
+fn add_event_listener<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = match this {
+        Some(this) => this,
+        None => return Ok(Value::Undefined),
+    };
+    let (event_type, listener, use_capture) = match listener_args(context, args)? {
+        Some(listener_args) => listener_args,
+        None => return Ok(Value::Undefined),
+    };
+    let priority = match args.get(3) {
+        Some(priority) => priority.coerce_to_i32(context)?,
+        None => 0,
+    };
+
+    let mut listeners = listeners(context, this)?;
+    if listeners
+        .iter()
+        .any(|l| l.matches(&event_type, listener, use_capture))
+    {
+        return Ok(Value::Undefined);
+    }
+
+    let index = listeners
+        .iter()
+        .position(|l| l.priority < priority)
+        .unwrap_or(listeners.len());
+    listeners.insert(
+        index,
+        Listener {
+            event_type,
+            listener,
+            use_capture,
+            priority,
+        },
+    );
+
+    let listeners = listeners
+        .into_iter()
+        .map(|l| l.into_value(context))
+        .collect();
+    if let Some(list) = listener_list(context, this, true)? {
+        list.replace(context.gc_context, listeners);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `EventDispatcher.prototype.removeEventListener`
+fn remove_event_listener<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = match this {
+        Some(this) => this,
+        None => return Ok(Value::Undefined),
+    };
+    let (event_type, listener, use_capture) = match listener_args(context, args)? {
+        Some(listener_args) => listener_args,
+        None => return Ok(Value::Undefined),
+    };
+
+    let listeners = listeners(context, this)?
+        .into_iter()
+        .filter(|l| !l.matches(&event_type, listener, use_capture))
+        .map(|l| l.into_value(context))
+        .collect();
+    if let Some(list) = listener_list(context, this, false)? {
+        list.replace(context.gc_context, listeners);
+    }
+
+    Ok(Value::Undefined)
+}
+
+/// Implements `EventDispatcher.prototype.hasEventListener` and
+/// `willTrigger`.
+///
+/// Events don't propagate through the display list yet, so `willTrigger`
+/// only considers this dispatcher's own listeners.
+fn has_event_listener<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = match this {
+        Some(this) => this,
+        None => return Ok(false.into()),
+    };
+    let event_type = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(context)?;
+
+    Ok(listeners(context, this)?
+        .iter()
+        .any(|l| l.event_type == event_type)
+        .into())
+}
+
+/// Implements `EventDispatcher.prototype.dispatchEvent`
+fn dispatch_event<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = match this {
+        Some(this) => this,
+        None => return Ok(false.into()),
+    };
+    let event = match args.get(0).and_then(|event| event.as_object()) {
+        Some(event) => event,
+        None => return Ok(false.into()),
+    };
+
+    Ok(dispatch(context, this, event)?.into())
+}
+
+/// Dispatch an event to the listeners of a target.
+///
+/// Events are only delivered to the target itself; there are no capture or
+/// bubbling phases yet, so capture listeners are never called. Listeners
+/// added or removed by a handler don't affect this dispatch.
+///
+/// Methods aren't bound to their instance yet, so, like frame scripts,
+/// listeners are called with the target as `this`.
+pub fn dispatch<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    target: Object<'gc>,
+    event: Object<'gc>,
+) -> Result<bool, Error<'gc>> {
+    let event_type = event
+        .get_property(event, &QName::dynamic_name("type"), context)?
+        .coerce_to_string(context)?;
+    event.set_property(
+        event,
+        &QName::dynamic_name("target"),
+        target.into(),
+        context,
+    )?;
+    event.set_property(
+        event,
+        &QName::dynamic_name("currentTarget"),
+        target.into(),
+        context,
+    )?;
+
+    let handlers: Vec<Object<'gc>> = listeners(context, target)?
+        .into_iter()
+        .filter(|l| l.event_type == event_type && !l.use_capture)
+        .map(|l| l.listener)
+        .collect();
+    for handler in handlers {
+        handler.call(Some(target), &[event.into()], context)?;
+    }
+
+    Ok(true)
+}
+
+/// Construct `EventDispatcher.prototype`.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    object_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let proto = ScriptObject::object(mc, object_proto);
+
+    install_function(mc, proto, "addEventListener", add_event_listener, fn_proto);
+    install_function(
+        mc,
+        proto,
+        "removeEventListener",
+        remove_event_listener,
+        fn_proto,
+    );
+    install_function(mc, proto, "hasEventListener", has_event_listener, fn_proto);
+    install_function(mc, proto, "willTrigger", has_event_listener, fn_proto);
+    install_function(mc, proto, "dispatchEvent", dispatch_event, fn_proto);
+
+    proto
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm2::function::FunctionObject;
+    use crate::avm2::test_utils::with_avm2;
+    use crate::avm2::Avm2;
+
+    /// Append a letter to the `log` property of the event's current target.
+    fn log_event<'gc>(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        letter: &str,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let event = args.get(0).and_then(|event| event.as_object()).unwrap();
+        let target = event
+            .get_property(event, &QName::dynamic_name("currentTarget"), context)?
+            .as_object()
+            .unwrap();
+        let log = target
+            .get_property(target, &QName::dynamic_name("log"), context)?
+            .coerce_to_string(context)?;
+        target.set_property(
+            target,
+            &QName::dynamic_name("log"),
+            format!("{}{}", log, letter).into(),
+            context,
+        )?;
+
+        Ok(Value::Undefined)
+    }
+
+    fn on_a<'gc>(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        _this: Option<Object<'gc>>,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        log_event(context, "a", args)
+    }
+
+    fn on_b<'gc>(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        _this: Option<Object<'gc>>,
+        args: &[Value<'gc>],
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        log_event(context, "b", args)
+    }
+
+    #[test]
+    fn dispatch_to_listeners() {
+        with_avm2(|context| -> Result<(), Error> {
+            let class = Avm2::lookup_class(context, "flash.events.EventDispatcher")?.unwrap();
+            let dispatcher = class.construct(context, &[])?;
+            let log = QName::dynamic_name("log");
+            dispatcher.set_property(dispatcher, &log, "".into(), context)?;
+
+            let fn_proto = context.avm2.prototypes().function;
+            let a = FunctionObject::from_builtin(context.gc_context, on_a, fn_proto);
+            let b = FunctionObject::from_builtin(context.gc_context, on_b, fn_proto);
+            let this = Some(dispatcher);
+
+            add_event_listener(context, this, &["enterFrame".into(), a.into()])?;
+            add_event_listener(context, this, &["enterFrame".into(), a.into()])?;
+            add_event_listener(
+                context,
+                this,
+                &["enterFrame".into(), b.into(), false.into(), 1.into()],
+            )?;
+            add_event_listener(context, this, &["other".into(), a.into()])?;
+            add_event_listener(context, this, &["enterFrame".into(), b.into(), true.into()])?;
+            assert_eq!(
+                has_event_listener(context, this, &["enterFrame".into()])?,
+                true.into()
+            );
+            assert_eq!(
+                has_event_listener(context, this, &["missing".into()])?,
+                false.into()
+            );
+
+            // Higher priorities run first, duplicates are ignored and capture
+            // listeners aren't called at the target.
+            assert!(Avm2::dispatch_event(context, dispatcher, "enterFrame")?);
+            assert_eq!(
+                dispatcher.get_property(dispatcher, &log, context)?,
+                "ba".into()
+            );
+
+            remove_event_listener(context, this, &["enterFrame".into(), b.into()])?;
+            Avm2::dispatch_event(context, dispatcher, "enterFrame")?;
+            assert_eq!(
+                dispatcher.get_property(dispatcher, &log, context)?,
+                "baa".into()
+            );
+
+            remove_event_listener(context, this, &["other".into(), a.into()])?;
+            assert_eq!(
+                has_event_listener(context, this, &["other".into()])?,
+                false.into()
+            );
+            Ok(())
+        });
+    }
+}
@@ -0,0 +1,50 @@
+//! Function builtin and prototype
+
+use crate::avm2::globals::install_function;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::{Error, UpdateContext, Value};
+use gc_arena::MutationContext;
+
+/// Implements `Function.prototype.call`
+fn call<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    func: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let func = func.ok_or(Error::NotCallable)?;
+    let this = args.get(0).and_then(|this| this.as_object());
+
+    func.call(this, args.get(1..).unwrap_or(&[]), context)
+}
+
+/// Implements `Function.prototype.apply`
+fn apply<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    func: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let func = func.ok_or(Error::NotCallable)?;
+    let this = args.get(0).and_then(|this| this.as_object());
+    let arguments = args
+        .get(1)
+        .and_then(|args| args.as_object())
+        .and_then(|args| args.as_array_object())
+        .map(|args| args.to_vec())
+        .unwrap_or_default();
+
+    func.call(this, &arguments, context)
+}
+
+/// Construct `Function.prototype`.
+///
+/// `Function.prototype` is itself an ordinary object; the methods on it are
+/// installed once it exists, since they need it as their own prototype.
+pub fn create_proto<'gc>(mc: MutationContext<'gc, '_>, object_proto: Object<'gc>) -> Object<'gc> {
+    let function_proto = ScriptObject::object(mc, object_proto);
+
+    install_function(mc, function_proto, "call", call, function_proto);
+    install_function(mc, function_proto, "apply", apply, function_proto);
+
+    function_proto
+}
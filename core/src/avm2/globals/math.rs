@@ -0,0 +1,167 @@
+//! `Math` builtin
+
+use crate::avm2::globals::install_function;
+use crate::avm2::names::QName;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::{Error, UpdateContext, Value};
+use gc_arena::MutationContext;
+use rand::Rng;
+use std::f64::{self, NAN};
+
+macro_rules! wrap_std {
+    ( $mc: ident, $object: ident, $proto: ident, $($name:expr => $std:path),* ) => {{
+        $(
+            install_function(
+                $mc,
+                $object,
+                $name,
+                |context, _this, args| -> Result<Value<'gc>, Error<'gc>> {
+                    if let Some(input) = args.get(0) {
+                        Ok($std(input.coerce_to_number(context)?).into())
+                    } else {
+                        Ok(NAN.into())
+                    }
+                },
+                $proto,
+            );
+        )*
+    }};
+}
+
+fn atan2<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let y = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_number(context)?;
+    let x = args
+        .get(1)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_number(context)?;
+
+    Ok(y.atan2(x).into())
+}
+
+fn pow<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let x = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_number(context)?;
+    let y = args
+        .get(1)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_number(context)?;
+
+    Ok(x.powf(y).into())
+}
+
+fn round<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let x = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_number(context)?;
+
+    Ok((x + 0.5).floor().into())
+}
+
+fn max<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let mut result = f64::NEG_INFINITY;
+    for arg in args {
+        let n = arg.coerce_to_number(context)?;
+        if n.is_nan() {
+            return Ok(NAN.into());
+        }
+
+        result = result.max(n);
+    }
+
+    Ok(result.into())
+}
+
+fn min<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let mut result = f64::INFINITY;
+    for arg in args {
+        let n = arg.coerce_to_number(context)?;
+        if n.is_nan() {
+            return Ok(NAN.into());
+        }
+
+        result = result.min(n);
+    }
+
+    Ok(result.into())
+}
+
+fn random<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(context.rng.gen_range(0.0f64, 1.0f64).into())
+}
+
+/// Construct the `Math` object.
+pub fn create<'gc>(
+    mc: MutationContext<'gc, '_>,
+    object_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let math = ScriptObject::object(mc, object_proto);
+
+    for (name, value) in &[
+        ("E", std::f64::consts::E),
+        ("LN10", std::f64::consts::LN_10),
+        ("LN2", std::f64::consts::LN_2),
+        ("LOG10E", std::f64::consts::LOG10_E),
+        ("LOG2E", std::f64::consts::LOG2_E),
+        ("PI", std::f64::consts::PI),
+        ("SQRT1_2", std::f64::consts::FRAC_1_SQRT_2),
+        ("SQRT2", std::f64::consts::SQRT_2),
+    ] {
+        math.install_const(mc, QName::dynamic_name(name), 0, (*value).into());
+    }
+
+    wrap_std!(mc, math, fn_proto,
+        "abs" => f64::abs,
+        "acos" => f64::acos,
+        "asin" => f64::asin,
+        "atan" => f64::atan,
+        "ceil" => f64::ceil,
+        "cos" => f64::cos,
+        "exp" => f64::exp,
+        "floor" => f64::floor,
+        "log" => f64::ln,
+        "sin" => f64::sin,
+        "sqrt" => f64::sqrt,
+        "tan" => f64::tan
+    );
+
+    install_function(mc, math, "atan2", atan2, fn_proto);
+    install_function(mc, math, "pow", pow, fn_proto);
+    install_function(mc, math, "round", round, fn_proto);
+    install_function(mc, math, "max", max, fn_proto);
+    install_function(mc, math, "min", min, fn_proto);
+    install_function(mc, math, "random", random, fn_proto);
+
+    math
+}
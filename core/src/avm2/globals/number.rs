@@ -0,0 +1,105 @@
+//! `Number`, `int`, and `uint` builtins and prototypes
+
+use crate::avm1::value::f64_to_string;
+use crate::avm2::globals::install_function;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::{Error, UpdateContext, Value};
+use gc_arena::MutationContext;
+
+/// Implements `Number` when called as a function.
+pub fn number_call_handler<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    match args.get(0) {
+        Some(value) => Ok(value.coerce_to_number(context)?.into()),
+        None => Ok(0.0.into()),
+    }
+}
+
+/// Implements `int` when called as a function.
+pub fn int_call_handler<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    match args.get(0) {
+        Some(value) => Ok(value.coerce_to_i32(context)?.into()),
+        None => Ok(0.into()),
+    }
+}
+
+/// Implements `uint` when called as a function.
+pub fn uint_call_handler<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    match args.get(0) {
+        Some(value) => Ok(value.coerce_to_u32(context)?.into()),
+        None => Ok(0.into()),
+    }
+}
+
+/// Implements `Number.prototype.toString`
+fn to_string<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let number = this
+        .map(|this| this.value_of())
+        .unwrap_or(Value::Undefined)
+        .coerce_to_number(context)?;
+    let radix = match args.get(0) {
+        Some(Value::Undefined) | None => 10,
+        Some(radix) => radix.coerce_to_i32(context)?,
+    };
+
+    if radix == 10 || !(2..=36).contains(&radix) || !number.is_finite() {
+        return Ok(f64_to_string(number).into());
+    }
+
+    let is_negative = number < 0.0;
+    let mut n = number.abs().trunc() as u64;
+    let mut digits = Vec::new();
+    loop {
+        let digit = (n % radix as u64) as u32;
+        digits.push(std::char::from_digit(digit, radix as u32).unwrap_or('0'));
+        n /= radix as u64;
+        if n == 0 {
+            break;
+        }
+    }
+
+    if is_negative {
+        digits.push('-');
+    }
+
+    Ok(digits.into_iter().rev().collect::<String>().into())
+}
+
+/// Implements `Number.prototype.valueOf`
+fn value_of<'gc>(
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.map(|this| this.value_of()).unwrap_or(Value::Undefined))
+}
+
+/// Construct the prototype of a numeric class.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    object_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let proto = ScriptObject::object(mc, object_proto);
+
+    install_function(mc, proto, "toString", to_string, fn_proto);
+    install_function(mc, proto, "valueOf", value_of, fn_proto);
+
+    proto
+}
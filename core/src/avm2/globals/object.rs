@@ -0,0 +1,132 @@
+//! Object builtin and prototype
+
+use crate::avm2::globals::install_function;
+use crate::avm2::names::QName;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::{Error, UpdateContext, Value};
+use gc_arena::MutationContext;
+
+/// Implements `Object`'s instance initializer.
+pub fn instance_init<'gc>(
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(Value::Undefined)
+}
+
+/// Implements `Object` when called as a function.
+pub fn call_handler<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    match args.get(0) {
+        None | Some(Value::Undefined) | Some(Value::Null) => {
+            let proto = context.avm2.prototypes().object;
+            Ok(ScriptObject::object(context.gc_context, proto).into())
+        }
+        Some(value) => Ok(value.coerce_to_object(context)?.into()),
+    }
+}
+
+/// Implements `Object.prototype.toString`
+fn to_string<'gc>(
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(match this {
+        Some(this) if this.as_executable().is_some() => "[object Function]",
+        Some(_) => "[object Object]",
+        None => "[object null]",
+    }
+    .into())
+}
+
+/// Implements `Object.prototype.valueOf`
+fn value_of<'gc>(
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(this.map(|this| this.value_of()).unwrap_or(Value::Undefined))
+}
+
+/// Implements `Object.prototype.hasOwnProperty`
+fn has_own_property<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = match this {
+        Some(this) => this,
+        None => return Ok(false.into()),
+    };
+    let name = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(context)?;
+
+    Ok(this.has_own_property(&QName::dynamic_name(&name)).into())
+}
+
+/// Implements `Object.prototype.isPrototypeOf`
+fn is_prototype_of<'gc>(
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let (Some(this), Some(Value::Object(object))) = (this, args.get(0)) {
+        let mut proto = object.proto();
+        while let Some(p) = proto {
+            if Object::ptr_eq(this, p) {
+                return Ok(true.into());
+            }
+
+            proto = p.proto();
+        }
+    }
+
+    Ok(false.into())
+}
+
+/// Implements `Object.prototype.propertyIsEnumerable`
+fn property_is_enumerable<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = match this {
+        Some(this) => this,
+        None => return Ok(false.into()),
+    };
+    let name = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(context)?;
+
+    Ok(this
+        .property_is_enumerable(&QName::dynamic_name(&name))
+        .into())
+}
+
+/// Partially construct `Object.prototype`.
+///
+/// `Object.prototype` has to be created before `Function.prototype`, but its
+/// methods need `Function.prototype` to exist, so they are filled in later.
+pub fn fill_proto<'gc>(mc: MutationContext<'gc, '_>, proto: Object<'gc>, fn_proto: Object<'gc>) {
+    install_function(mc, proto, "toString", to_string, fn_proto);
+    install_function(mc, proto, "toLocaleString", to_string, fn_proto);
+    install_function(mc, proto, "valueOf", value_of, fn_proto);
+    install_function(mc, proto, "hasOwnProperty", has_own_property, fn_proto);
+    install_function(mc, proto, "isPrototypeOf", is_prototype_of, fn_proto);
+    install_function(
+        mc,
+        proto,
+        "propertyIsEnumerable",
+        property_is_enumerable,
+        fn_proto,
+    );
+}
@@ -0,0 +1,245 @@
+//! `String` builtin and prototype
+
+use crate::avm2::array_object::ArrayObject;
+use crate::avm2::globals::install_function;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::{Error, UpdateContext, Value};
+use gc_arena::MutationContext;
+
+/// Implements `String` when called as a function.
+pub fn call_handler<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    match args.get(0) {
+        Some(value) => Ok(value.coerce_to_string(context)?.into()),
+        None => Ok("".into()),
+    }
+}
+
+/// Retrieve the string a method was called on.
+fn this_string<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+) -> Result<Vec<u16>, Error<'gc>> {
+    let value = this.map(|this| this.value_of()).unwrap_or(Value::Undefined);
+
+    Ok(value.coerce_to_string(context)?.encode_utf16().collect())
+}
+
+/// Resolve a possibly negative string index relative to a length.
+fn clamp_index(index: f64, length: usize, negative_from_end: bool) -> usize {
+    let index = if index.is_nan() { 0.0 } else { index.trunc() };
+    if index < 0.0 {
+        if negative_from_end {
+            (length as f64 + index).max(0.0) as usize
+        } else {
+            0
+        }
+    } else {
+        (index as usize).min(length)
+    }
+}
+
+/// Implements `String.prototype.toString` and `String.prototype.valueOf`
+fn to_string<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    Ok(String::from_utf16_lossy(&this_string(context, this)?).into())
+}
+
+/// Implements `String.prototype.charAt`
+fn char_at<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_string(context, this)?;
+    let index = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_number(context)?;
+    let index = if index.is_nan() { 0.0 } else { index.trunc() };
+
+    if index >= 0.0 && (index as usize) < this.len() {
+        let index = index as usize;
+        Ok(String::from_utf16_lossy(&this[index..=index]).into())
+    } else {
+        Ok("".into())
+    }
+}
+
+/// Implements `String.prototype.charCodeAt`
+fn char_code_at<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_string(context, this)?;
+    let index = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_number(context)?;
+    let index = if index.is_nan() { 0.0 } else { index.trunc() };
+
+    if index >= 0.0 && (index as usize) < this.len() {
+        Ok(f64::from(this[index as usize]).into())
+    } else {
+        Ok(std::f64::NAN.into())
+    }
+}
+
+/// Implements `String.prototype.indexOf`
+fn index_of<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_string(context, this)?;
+    let pattern: Vec<u16> = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_string(context)?
+        .encode_utf16()
+        .collect();
+    let start = match args.get(1) {
+        Some(start) => clamp_index(start.coerce_to_number(context)?, this.len(), false),
+        None => 0,
+    };
+
+    if pattern.is_empty() {
+        return Ok((start as f64).into());
+    }
+
+    let found = this
+        .get(start..)
+        .and_then(|haystack| {
+            haystack
+                .windows(pattern.len())
+                .position(|window| window == &pattern[..])
+        })
+        .map(|index| (index + start) as f64)
+        .unwrap_or(-1.0);
+
+    Ok(found.into())
+}
+
+/// Implements `String.prototype.substring`
+fn substring<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_string(context, this)?;
+    let start = match args.get(0) {
+        Some(start) => clamp_index(start.coerce_to_number(context)?, this.len(), false),
+        None => 0,
+    };
+    let end = match args.get(1) {
+        Some(Value::Undefined) | None => this.len(),
+        Some(end) => clamp_index(end.coerce_to_number(context)?, this.len(), false),
+    };
+    let (start, end) = if start > end {
+        (end, start)
+    } else {
+        (start, end)
+    };
+
+    Ok(String::from_utf16_lossy(&this[start..end]).into())
+}
+
+/// Implements `String.prototype.substr`
+fn substr<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = this_string(context, this)?;
+    let start = match args.get(0) {
+        Some(start) => clamp_index(start.coerce_to_number(context)?, this.len(), true),
+        None => 0,
+    };
+    let length = match args.get(1) {
+        Some(Value::Undefined) | None => this.len(),
+        Some(length) => clamp_index(length.coerce_to_number(context)?, this.len(), false),
+    };
+    let end = (start + length).min(this.len());
+
+    Ok(String::from_utf16_lossy(&this[start..end]).into())
+}
+
+/// Implements `String.prototype.toUpperCase`
+fn to_upper_case<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = String::from_utf16_lossy(&this_string(context, this)?);
+
+    Ok(this.to_uppercase().into())
+}
+
+/// Implements `String.prototype.toLowerCase`
+fn to_lower_case<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = String::from_utf16_lossy(&this_string(context, this)?);
+
+    Ok(this.to_lowercase().into())
+}
+
+/// Implements `String.prototype.split`
+fn split<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Option<Object<'gc>>,
+    args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let this = String::from_utf16_lossy(&this_string(context, this)?);
+    let array_proto = context.avm2.prototypes().array;
+    let parts: Vec<Value<'gc>> = match args.get(0) {
+        Some(Value::Undefined) | None => vec![this.into()],
+        Some(delimiter) => {
+            let delimiter = delimiter.coerce_to_string(context)?;
+            if delimiter.is_empty() {
+                this.chars().map(|c| c.to_string().into()).collect()
+            } else {
+                this.split(delimiter.as_str()).map(|s| s.into()).collect()
+            }
+        }
+    };
+
+    Ok(Object::from(ArrayObject::from_vec(
+        context.gc_context,
+        array_proto,
+        parts,
+    ))
+    .into())
+}
+
+/// Construct `String.prototype`.
+pub fn create_proto<'gc>(
+    mc: MutationContext<'gc, '_>,
+    object_proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let proto = ScriptObject::object(mc, object_proto);
+
+    install_function(mc, proto, "toString", to_string, fn_proto);
+    install_function(mc, proto, "valueOf", to_string, fn_proto);
+    install_function(mc, proto, "charAt", char_at, fn_proto);
+    install_function(mc, proto, "charCodeAt", char_code_at, fn_proto);
+    install_function(mc, proto, "indexOf", index_of, fn_proto);
+    install_function(mc, proto, "substring", substring, fn_proto);
+    install_function(mc, proto, "substr", substr, fn_proto);
+    install_function(mc, proto, "toUpperCase", to_upper_case, fn_proto);
+    install_function(mc, proto, "toLowerCase", to_lower_case, fn_proto);
+    install_function(mc, proto, "split", split, fn_proto);
+
+    proto
+}
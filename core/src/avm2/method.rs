@@ -0,0 +1,115 @@
+//! AVM2 methods
+
+use crate::avm2::object::Object;
+use crate::avm2::script::TranslationUnit;
+use crate::avm2::{Error, UpdateContext, Value};
+use gc_arena::{Collect, CollectionContext};
+use std::fmt;
+use swf::avm2::types::{Index, Method as AbcMethod, MethodBody as AbcMethodBody};
+
+/// Represents a function defined in Ruffle's code.
+///
+/// Parameters are as follows:
+///
+///  * The action context
+///  * The current `this` object
+///  * The arguments this function was called with
+pub type NativeMethod<'gc> = fn(
+    &mut UpdateContext<'_, 'gc, '_>,
+    Option<Object<'gc>>,
+    &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>>;
+
+/// Represents a reference to an AVM2 method and body.
+#[derive(Clone, Collect, Debug)]
+#[collect(require_static)]
+pub struct BytecodeMethod {
+    /// The translation unit this function was defined in.
+    translation_unit: TranslationUnit,
+
+    /// The index of the method in the ABC file.
+    abc_method: u32,
+
+    /// The index of the method body in the ABC file, if it has one.
+    abc_method_body: Option<u32>,
+}
+
+impl BytecodeMethod {
+    /// Construct an `BytecodeMethod` from an `AbcFile` and method index.
+    pub fn from_method_index(
+        translation_unit: TranslationUnit,
+        abc_method: Index<AbcMethod>,
+    ) -> Option<Self> {
+        let abc = translation_unit.abc();
+        abc.methods.get(abc_method.0 as usize)?;
+
+        let abc_method_body = abc
+            .method_bodies
+            .iter()
+            .position(|body| body.method.0 == abc_method.0)
+            .map(|index| index as u32);
+
+        Some(Self {
+            translation_unit,
+            abc_method: abc_method.0,
+            abc_method_body,
+        })
+    }
+
+    pub fn translation_unit(&self) -> &TranslationUnit {
+        &self.translation_unit
+    }
+
+    pub fn method(&self) -> &AbcMethod {
+        &self.translation_unit.abc().methods[self.abc_method as usize]
+    }
+
+    pub fn body(&self) -> Option<&AbcMethodBody> {
+        self.abc_method_body
+            .map(|index| &self.translation_unit.abc().method_bodies[index as usize])
+    }
+}
+
+/// An uninstantiated method that can either be natively implemented or sourced
+/// from an ABC file.
+#[derive(Clone)]
+pub enum Method<'gc> {
+    /// A native method.
+    Native(NativeMethod<'gc>),
+
+    /// An ABC-provided method entry.
+    Entry(BytecodeMethod),
+}
+
+unsafe impl<'gc> Collect for Method<'gc> {
+    fn trace(&self, cc: CollectionContext) {
+        match self {
+            Method::Native(_nf) => {}
+            Method::Entry(a2me) => a2me.trace(cc),
+        }
+    }
+}
+
+impl<'gc> fmt::Debug for Method<'gc> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Method::Native(_nf) => f
+                .debug_tuple("Method::Native")
+                .field(&"<native code>".to_string())
+                .finish(),
+            Method::Entry(a2me) => f.debug_tuple("Method::Entry").field(a2me).finish(),
+        }
+    }
+}
+
+impl<'gc> From<NativeMethod<'gc>> for Method<'gc> {
+    fn from(nf: NativeMethod<'gc>) -> Self {
+        Self::Native(nf)
+    }
+}
+
+impl<'gc> From<BytecodeMethod> for Method<'gc> {
+    fn from(a2me: BytecodeMethod) -> Self {
+        Self::Entry(a2me)
+    }
+}
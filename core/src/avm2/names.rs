@@ -0,0 +1,340 @@
+//! AVM2 names & namespacing
+
+use crate::avm2::error::Error;
+use crate::avm2::script::TranslationUnit;
+use crate::avm2::Value;
+use gc_arena::Collect;
+use swf::avm2::types::{
+    AbcFile, Index, Multiname as AbcMultiname, Namespace as AbcNamespace,
+    NamespaceSet as AbcNamespaceSet,
+};
+
+/// Represents the name of a namespace.
+#[derive(Clone, Collect, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[collect(require_static)]
+pub enum Namespace {
+    Namespace(String),
+    Package(String),
+    PackageInternal(String),
+    Protected(String),
+    Explicit(String),
+    StaticProtected(String),
+    Private(String),
+    Any,
+}
+
+impl Namespace {
+    /// Read a namespace declaration from the ABC constant pool and copy it to
+    /// a namespace value.
+    pub fn from_abc_namespace<'gc>(
+        file: &AbcFile,
+        namespace_index: Index<AbcNamespace>,
+    ) -> Result<Self, Error<'gc>> {
+        if namespace_index.0 == 0 {
+            return Ok(Self::Any);
+        }
+
+        let actual_index = namespace_index.0 as usize - 1;
+        let abc_namespace = file
+            .constant_pool
+            .namespaces
+            .get(actual_index)
+            .ok_or_else(|| {
+                Error::InvalidConstant(format!("Unknown namespace constant {}", namespace_index.0))
+            })?;
+
+        Ok(match abc_namespace {
+            AbcNamespace::Namespace(idx) => Self::Namespace(abc_string(file, idx.clone())?),
+            AbcNamespace::Package(idx) => Self::Package(abc_string(file, idx.clone())?),
+            AbcNamespace::PackageInternal(idx) => {
+                Self::PackageInternal(abc_string(file, idx.clone())?)
+            }
+            AbcNamespace::Protected(idx) => Self::Protected(abc_string(file, idx.clone())?),
+            AbcNamespace::Explicit(idx) => Self::Explicit(abc_string(file, idx.clone())?),
+            AbcNamespace::StaticProtected(idx) => {
+                Self::StaticProtected(abc_string(file, idx.clone())?)
+            }
+            AbcNamespace::Private(idx) => Self::Private(abc_string(file, idx.clone())?),
+        })
+    }
+
+    /// The public namespace, which all unqualified dynamic properties live in.
+    pub fn public_namespace() -> Self {
+        Namespace::Package("".to_string())
+    }
+
+    pub fn package(package_name: &str) -> Self {
+        Namespace::Package(package_name.to_string())
+    }
+
+    pub fn is_public(&self) -> bool {
+        if let Self::Package(name) = self {
+            name.is_empty()
+        } else {
+            false
+        }
+    }
+
+    pub fn is_any(&self) -> bool {
+        match self {
+            Self::Any => true,
+            _ => false,
+        }
+    }
+
+    /// Check if this namespace would match `other` when used as part of a
+    /// name lookup.
+    pub fn matches(&self, other: &Self) -> bool {
+        self.is_any() || other.is_any() || self == other
+    }
+}
+
+/// A `QName`, likely "qualified name", consists of a namespace and name string.
+///
+/// A property cannot be retrieved or set without first being resolved into a
+/// `QName`. All other forms of names and multinames are either versions of
+/// `QName` with unspecified parameters, or multiple names to be checked in
+/// order.
+#[derive(Clone, Collect, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[collect(require_static)]
+pub struct QName {
+    ns: Namespace,
+    name: String,
+}
+
+impl QName {
+    pub fn new(ns: Namespace, name: &str) -> Self {
+        Self {
+            ns,
+            name: name.to_string(),
+        }
+    }
+
+    pub fn dynamic_name(local_part: &str) -> Self {
+        Self {
+            ns: Namespace::public_namespace(),
+            name: local_part.to_string(),
+        }
+    }
+
+    /// Pull a `QName` from the multiname pool.
+    ///
+    /// This function returns an Err if the multiname does not exist or is not
+    /// a `QName`.
+    pub fn from_abc_multiname<'gc>(
+        file: &AbcFile,
+        multiname_index: Index<AbcMultiname>,
+    ) -> Result<Self, Error<'gc>> {
+        let actual_index = (multiname_index.0 as usize).checked_sub(1).ok_or_else(|| {
+            Error::InvalidConstant("Attempted to load a trait name of index zero".to_string())
+        })?;
+        let abc_multiname = file
+            .constant_pool
+            .multinames
+            .get(actual_index)
+            .ok_or_else(|| {
+                Error::InvalidConstant(format!("Unknown multiname constant {}", multiname_index.0))
+            })?;
+
+        Ok(match abc_multiname {
+            AbcMultiname::QName { namespace, name } | AbcMultiname::QNameA { namespace, name } => {
+                Self {
+                    ns: Namespace::from_abc_namespace(file, namespace.clone())?,
+                    name: abc_string(file, name.clone())?,
+                }
+            }
+            _ => {
+                return Err(Error::InvalidConstant(format!(
+                    "Attempted to pull QName from non-QName multiname {}",
+                    multiname_index.0
+                )))
+            }
+        })
+    }
+
+    pub fn local_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn namespace(&self) -> &Namespace {
+        &self.ns
+    }
+}
+
+/// A `Multiname` consists of a name which could be resolved in one or more
+/// potential namespaces.
+///
+/// All unresolved names are of type `Multiname`, and they can be used to
+/// resolve a `QName` against an object's properties. A `None` name matches
+/// any local name.
+#[derive(Clone, Debug, Collect)]
+#[collect(require_static)]
+pub struct Multiname {
+    /// The list of namespaces that satisfy this multiname.
+    ns: Vec<Namespace>,
+
+    /// The local name that satisfies this multiname. If `None`, then this
+    /// multiname is satisfied by any name in the namespace.
+    name: Option<String>,
+}
+
+impl Multiname {
+    /// Read a namespace set from the ABC constant pool, and return a list of
+    /// copied namespaces.
+    fn abc_namespace_set<'gc>(
+        file: &AbcFile,
+        namespace_set_index: Index<AbcNamespaceSet>,
+    ) -> Result<Vec<Namespace>, Error<'gc>> {
+        if namespace_set_index.0 == 0 {
+            //TODO: What is namespace set zero?
+            return Ok(vec![]);
+        }
+
+        let actual_index = namespace_set_index.0 as usize - 1;
+        let ns_set = file
+            .constant_pool
+            .namespace_sets
+            .get(actual_index)
+            .ok_or_else(|| {
+                Error::InvalidConstant(format!(
+                    "Unknown namespace set constant {}",
+                    namespace_set_index.0
+                ))
+            })?;
+        let mut result = vec![];
+
+        for ns in ns_set {
+            result.push(Namespace::from_abc_namespace(file, ns.clone())?)
+        }
+
+        Ok(result)
+    }
+
+    /// Read a multiname from the ABC constant pool.
+    ///
+    /// Runtime components of the name (namespace and/or local name) are
+    /// popped from the operand stack via `pop`; the name is always popped
+    /// before the namespace.
+    pub fn from_abc_multiname<'gc>(
+        translation_unit: &TranslationUnit,
+        multiname_index: Index<AbcMultiname>,
+        mut pop: impl FnMut() -> Result<Value<'gc>, Error<'gc>>,
+    ) -> Result<Self, Error<'gc>> {
+        let file = translation_unit.abc();
+        let actual_index = (multiname_index.0 as usize).checked_sub(1).ok_or_else(|| {
+            Error::InvalidConstant("Attempted to load a multiname of index zero".to_string())
+        })?;
+        let abc_multiname = file
+            .constant_pool
+            .multinames
+            .get(actual_index)
+            .ok_or_else(|| {
+                Error::InvalidConstant(format!("Unknown multiname constant {}", multiname_index.0))
+            })?;
+
+        Ok(match abc_multiname {
+            AbcMultiname::QName { namespace, name } | AbcMultiname::QNameA { namespace, name } => {
+                Self {
+                    ns: vec![Namespace::from_abc_namespace(file, namespace.clone())?],
+                    name: abc_string_option(file, name.clone())?,
+                }
+            }
+            AbcMultiname::RTQName { name } | AbcMultiname::RTQNameA { name } => {
+                let ns = pop()?.as_namespace()?;
+                Self {
+                    ns: vec![ns],
+                    name: abc_string_option(file, name.clone())?,
+                }
+            }
+            AbcMultiname::RTQNameL | AbcMultiname::RTQNameLA => {
+                let name = pop()?.into_name_string();
+                let ns = pop()?.as_namespace()?;
+                Self {
+                    ns: vec![ns],
+                    name: Some(name),
+                }
+            }
+            AbcMultiname::Multiname {
+                namespace_set,
+                name,
+            }
+            | AbcMultiname::MultinameA {
+                namespace_set,
+                name,
+            } => Self {
+                ns: Self::abc_namespace_set(file, namespace_set.clone())?,
+                name: abc_string_option(file, name.clone())?,
+            },
+            AbcMultiname::MultinameL { namespace_set }
+            | AbcMultiname::MultinameLA { namespace_set } => {
+                let name = pop()?.into_name_string();
+                Self {
+                    ns: Self::abc_namespace_set(file, namespace_set.clone())?,
+                    name: Some(name),
+                }
+            }
+        })
+    }
+
+    /// Indicates the any type (any name in any namespace).
+    pub fn any() -> Self {
+        Self {
+            ns: vec![Namespace::Any],
+            name: None,
+        }
+    }
+
+    pub fn namespace_set(&self) -> impl Iterator<Item = &Namespace> {
+        self.ns.iter()
+    }
+
+    pub fn local_name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Check if a given `QName` would be matched by this multiname.
+    pub fn contains_name(&self, name: &QName) -> bool {
+        let ns_match = self.ns.iter().any(|ns| ns.matches(name.namespace()));
+        let name_match = self
+            .name
+            .as_ref()
+            .map(|n| n == name.local_name())
+            .unwrap_or(true);
+
+        ns_match && name_match
+    }
+}
+
+impl From<QName> for Multiname {
+    fn from(q: QName) -> Self {
+        Self {
+            ns: vec![q.ns],
+            name: Some(q.name),
+        }
+    }
+}
+
+/// Retrieve a string from the ABC constant pool.
+///
+/// Index zero is the empty string.
+pub fn abc_string<'gc>(file: &AbcFile, index: Index<String>) -> Result<String, Error<'gc>> {
+    Ok(abc_string_option(file, index)?.unwrap_or_default())
+}
+
+/// Retrieve a string from the ABC constant pool, yielding `None` for index
+/// zero.
+pub fn abc_string_option<'gc>(
+    file: &AbcFile,
+    index: Index<String>,
+) -> Result<Option<String>, Error<'gc>> {
+    if index.0 == 0 {
+        return Ok(None);
+    }
+
+    file.constant_pool
+        .strings
+        .get(index.0 as usize - 1)
+        .cloned()
+        .map(Some)
+        .ok_or_else(|| Error::InvalidConstant(format!("Unknown string constant {}", index.0)))
+}
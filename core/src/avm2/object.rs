@@ -0,0 +1,461 @@
+//! AVM2 objects.
+
+use crate::avm2::array_object::ArrayObject;
+use crate::avm2::class::Class;
+use crate::avm2::function::{Executable, FunctionObject};
+use crate::avm2::names::{Multiname, Namespace, QName};
+use crate::avm2::primitive_object::PrimitiveObject;
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::stage_object::StageObject;
+use crate::avm2::{Error, UpdateContext, Value};
+use crate::display_object::DisplayObject;
+use gc_arena::{Collect, GcCell, MutationContext};
+use ruffle_macros::enum_trait_object;
+use std::fmt::Debug;
+
+/// Represents an object that can be directly interacted with by the AVM2
+/// runtime.
+#[enum_trait_object(
+    #[derive(Clone, Collect, Debug, Copy)]
+    #[collect(no_drop)]
+    pub enum Object<'gc> {
+        ScriptObject(ScriptObject<'gc>),
+        FunctionObject(FunctionObject<'gc>),
+        PrimitiveObject(PrimitiveObject<'gc>),
+        ArrayObject(ArrayObject<'gc>),
+        StageObject(StageObject<'gc>)
+    }
+)]
+pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy {
+    /// Retrieve a property by its QName, without taking prototype lookups
+    /// into account.
+    fn get_property_local(
+        self,
+        receiver: Object<'gc>,
+        name: &QName,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>>;
+
+    /// Retrieve a property by its QName.
+    fn get_property(
+        self,
+        receiver: Object<'gc>,
+        name: &QName,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let mut proto: Option<Object<'gc>> = Some(self.into());
+        while let Some(this_proto) = proto {
+            if this_proto.has_own_property(name) {
+                return this_proto.get_property_local(receiver, name, context);
+            }
+
+            proto = this_proto.proto();
+        }
+
+        Ok(Value::Undefined)
+    }
+
+    /// Set a property on this specific object.
+    fn set_property_local(
+        self,
+        receiver: Object<'gc>,
+        name: &QName,
+        value: Value<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<(), Error<'gc>>;
+
+    /// Set a property by its QName.
+    ///
+    /// Virtual properties on the prototype chain will have their setters
+    /// called; otherwise, the property is created on this object.
+    fn set_property(
+        self,
+        receiver: Object<'gc>,
+        name: &QName,
+        value: Value<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<(), Error<'gc>> {
+        if self.has_own_property(name) {
+            return self.set_property_local(receiver, name, value, context);
+        }
+
+        let mut proto = self.proto();
+        while let Some(this_proto) = proto {
+            if this_proto.has_own_property(name) {
+                if this_proto.has_own_virtual_property(name) {
+                    if let Some(setter) = this_proto.get_setter(name) {
+                        setter.call(Some(receiver), &[value], context)?;
+                    }
+
+                    return Ok(());
+                }
+
+                break;
+            }
+
+            proto = this_proto.proto();
+        }
+
+        self.set_property_local(receiver, name, value, context)
+    }
+
+    /// Initialize a property on this specific object.
+    ///
+    /// Unlike setting a property, initialization is allowed to write to
+    /// constants.
+    fn init_property_local(
+        self,
+        receiver: Object<'gc>,
+        name: &QName,
+        value: Value<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<(), Error<'gc>>;
+
+    /// Initialize a property by its QName.
+    fn init_property(
+        self,
+        receiver: Object<'gc>,
+        name: &QName,
+        value: Value<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<(), Error<'gc>> {
+        if !self.has_own_property(name) {
+            let mut proto = self.proto();
+            while let Some(this_proto) = proto {
+                if this_proto.has_own_virtual_property(name) {
+                    if let Some(setter) = this_proto.get_setter(name) {
+                        setter.call(Some(receiver), &[value], context)?;
+                    }
+
+                    return Ok(());
+                }
+
+                proto = this_proto.proto();
+            }
+        }
+
+        self.init_property_local(receiver, name, value, context)
+    }
+
+    /// Retrieve a slot by its index.
+    fn get_slot(self, id: u32) -> Result<Value<'gc>, Error<'gc>>;
+
+    /// Set a slot by its index.
+    fn set_slot(
+        self,
+        id: u32,
+        value: Value<'gc>,
+        mc: MutationContext<'gc, '_>,
+    ) -> Result<(), Error<'gc>>;
+
+    /// Indicates whether or not a property exists on this object itself.
+    fn has_own_property(self, name: &QName) -> bool;
+
+    /// Indicates whether or not a property on this object is a getter or
+    /// setter pair.
+    fn has_own_virtual_property(self, name: &QName) -> bool;
+
+    /// Retrieve the setter of a virtual property on this object, if any.
+    fn get_setter(self, name: &QName) -> Option<Object<'gc>>;
+
+    /// Indicates whether or not a property exists on this object or its
+    /// prototype chain.
+    fn has_property(self, name: &QName) -> bool {
+        let mut proto: Option<Object<'gc>> = Some(self.into());
+        while let Some(this_proto) = proto {
+            if this_proto.has_own_property(name) {
+                return true;
+            }
+
+            proto = this_proto.proto();
+        }
+
+        false
+    }
+
+    /// Find a namespace in which this object has a property of the given
+    /// local name.
+    fn resolve_any(self, local_name: &str) -> Option<Namespace>;
+
+    /// Resolve a multiname into a single QName, if any of the namespaces
+    /// match a property on this object or its prototype chain.
+    fn resolve_multiname(self, multiname: &Multiname) -> Option<QName> {
+        let local_name = multiname.local_name()?;
+
+        for ns in multiname.namespace_set() {
+            if ns.is_any() {
+                let mut proto: Option<Object<'gc>> = Some(self.into());
+                while let Some(this_proto) = proto {
+                    if let Some(ns) = this_proto.resolve_any(local_name) {
+                        return Some(QName::new(ns, local_name));
+                    }
+
+                    proto = this_proto.proto();
+                }
+            } else {
+                let qname = QName::new(ns.clone(), local_name);
+                if self.has_property(&qname) {
+                    return Some(qname);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Delete a named property from the object.
+    ///
+    /// Returns false if the property cannot be deleted.
+    fn delete_property(self, gc_context: MutationContext<'gc, '_>, name: &QName) -> bool;
+
+    /// Retrieve the name of the enumerable property at a given index, if
+    /// any. Indices start at zero.
+    fn get_enumerant_name(self, index: u32) -> Option<QName>;
+
+    /// Determine if a property is currently enumerable.
+    fn property_is_enumerable(self, name: &QName) -> bool;
+
+    /// Install a method (or any other non-slot value) on an object.
+    fn install_method(self, mc: MutationContext<'gc, '_>, name: QName, function: Object<'gc>);
+
+    /// Install a getter method on an object property.
+    fn install_getter(self, mc: MutationContext<'gc, '_>, name: QName, function: Object<'gc>);
+
+    /// Install a setter method on an object property.
+    fn install_setter(self, mc: MutationContext<'gc, '_>, name: QName, function: Object<'gc>);
+
+    /// Install a dynamic or built-in value property on an object.
+    fn install_dynamic_property(self, mc: MutationContext<'gc, '_>, name: QName, value: Value<'gc>);
+
+    /// Install a slot on an object property.
+    ///
+    /// A slot ID of zero allocates the next available slot.
+    fn install_slot(self, mc: MutationContext<'gc, '_>, name: QName, id: u32, value: Value<'gc>);
+
+    /// Install a const on an object property.
+    fn install_const(self, mc: MutationContext<'gc, '_>, name: QName, id: u32, value: Value<'gc>);
+
+    /// Call the object.
+    fn call(
+        self,
+        _receiver: Option<Object<'gc>>,
+        _arguments: &[Value<'gc>],
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        Err(Error::NotCallable)
+    }
+
+    /// Construct a new instance of this object, if it is a class.
+    fn construct(
+        self,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+        _arguments: &[Value<'gc>],
+    ) -> Result<Object<'gc>, Error<'gc>> {
+        Err(Error::NotConstructable)
+    }
+
+    /// Get this object's prototype.
+    fn proto(self) -> Option<Object<'gc>>;
+
+    /// Get a raw pointer value for this object.
+    fn as_ptr(self) -> *const ObjectPtr;
+
+    /// Get this object's `Executable`, if it has one.
+    fn as_executable(self) -> Option<Executable<'gc>> {
+        None
+    }
+
+    /// Get this object's class definition, if it is a class.
+    fn as_class(self) -> Option<GcCell<'gc, Class<'gc>>> {
+        None
+    }
+
+    /// Get this object's array storage, if it is an array.
+    fn as_array_object(self) -> Option<ArrayObject<'gc>> {
+        None
+    }
+
+    /// Get the display object this object is bound to, if any.
+    fn as_display_object(self) -> Option<DisplayObject<'gc>> {
+        None
+    }
+
+    /// Get the primitive value of this object.
+    ///
+    /// Ordinary objects are their own value; boxed primitives yield the
+    /// primitive they wrap.
+    fn value_of(self) -> Value<'gc> {
+        Value::Object(self.into())
+    }
+}
+
+pub enum ObjectPtr {}
+
+impl<'gc> Object<'gc> {
+    pub fn ptr_eq(a: Object<'gc>, b: Object<'gc>) -> bool {
+        a.as_ptr() == b.as_ptr()
+    }
+}
+
+/// Implements the portions of `TObject` that deal with property storage by
+/// delegating to a `ScriptObjectData` field.
+macro_rules! impl_avm2_custom_object_properties {
+    ($field:ident) => {
+        fn get_property_local(
+            self,
+            receiver: Object<'gc>,
+            name: &QName,
+            context: &mut UpdateContext<'_, 'gc, '_>,
+        ) -> Result<Value<'gc>, Error<'gc>> {
+            let property = self.0.read().$field.property(name);
+            match property {
+                Some(Property::Slot { slot_id, .. }) => self.0.read().$field.get_slot(slot_id),
+                Some(property) => property.get(receiver, context),
+                None => Ok(Value::Undefined),
+            }
+        }
+
+        fn set_property_local(
+            self,
+            receiver: Object<'gc>,
+            name: &QName,
+            value: Value<'gc>,
+            context: &mut UpdateContext<'_, 'gc, '_>,
+        ) -> Result<(), Error<'gc>> {
+            let property = self.0.read().$field.property(name);
+            match property {
+                Some(Property::Virtual { set: Some(set), .. }) => {
+                    set.call(Some(receiver), &[value], context)?;
+                    Ok(())
+                }
+                Some(Property::Virtual { set: None, .. }) => Ok(()),
+                _ => self
+                    .0
+                    .write(context.gc_context)
+                    .$field
+                    .set_property_value(name, value, false),
+            }
+        }
+
+        fn init_property_local(
+            self,
+            receiver: Object<'gc>,
+            name: &QName,
+            value: Value<'gc>,
+            context: &mut UpdateContext<'_, 'gc, '_>,
+        ) -> Result<(), Error<'gc>> {
+            let property = self.0.read().$field.property(name);
+            match property {
+                Some(Property::Virtual { set: Some(set), .. }) => {
+                    set.call(Some(receiver), &[value], context)?;
+                    Ok(())
+                }
+                Some(Property::Virtual { set: None, .. }) => Ok(()),
+                _ => self
+                    .0
+                    .write(context.gc_context)
+                    .$field
+                    .set_property_value(name, value, true),
+            }
+        }
+
+        fn has_own_property(self, name: &QName) -> bool {
+            self.0.read().$field.has_own_property(name)
+        }
+
+        fn resolve_any(self, local_name: &str) -> Option<Namespace> {
+            self.0.read().$field.resolve_any(local_name)
+        }
+
+        fn delete_property(self, gc_context: MutationContext<'gc, '_>, name: &QName) -> bool {
+            self.0.write(gc_context).$field.delete_property(name)
+        }
+
+        fn get_enumerant_name(self, index: u32) -> Option<QName> {
+            self.0.read().$field.get_enumerant_name(index)
+        }
+
+        fn property_is_enumerable(self, name: &QName) -> bool {
+            self.0.read().$field.property_is_enumerable(name)
+        }
+    };
+}
+
+/// Implements the remainder of `TObject` (slots, trait installation, and
+/// prototypes) by delegating to a `ScriptObjectData` field.
+macro_rules! impl_avm2_custom_object {
+    ($field:ident) => {
+        fn get_slot(self, id: u32) -> Result<Value<'gc>, Error<'gc>> {
+            self.0.read().$field.get_slot(id)
+        }
+
+        fn set_slot(
+            self,
+            id: u32,
+            value: Value<'gc>,
+            mc: MutationContext<'gc, '_>,
+        ) -> Result<(), Error<'gc>> {
+            self.0.write(mc).$field.set_slot(id, value)
+        }
+
+        fn has_own_virtual_property(self, name: &QName) -> bool {
+            self.0.read().$field.has_own_virtual_property(name)
+        }
+
+        fn get_setter(self, name: &QName) -> Option<Object<'gc>> {
+            self.0.read().$field.get_setter(name)
+        }
+
+        fn install_method(self, mc: MutationContext<'gc, '_>, name: QName, function: Object<'gc>) {
+            self.0.write(mc).$field.install_method(name, function)
+        }
+
+        fn install_getter(self, mc: MutationContext<'gc, '_>, name: QName, function: Object<'gc>) {
+            self.0.write(mc).$field.install_getter(name, function)
+        }
+
+        fn install_setter(self, mc: MutationContext<'gc, '_>, name: QName, function: Object<'gc>) {
+            self.0.write(mc).$field.install_setter(name, function)
+        }
+
+        fn install_dynamic_property(
+            self,
+            mc: MutationContext<'gc, '_>,
+            name: QName,
+            value: Value<'gc>,
+        ) {
+            self.0
+                .write(mc)
+                .$field
+                .install_dynamic_property(name, value)
+        }
+
+        fn install_slot(
+            self,
+            mc: MutationContext<'gc, '_>,
+            name: QName,
+            id: u32,
+            value: Value<'gc>,
+        ) {
+            self.0.write(mc).$field.install_slot(name, id, value)
+        }
+
+        fn install_const(
+            self,
+            mc: MutationContext<'gc, '_>,
+            name: QName,
+            id: u32,
+            value: Value<'gc>,
+        ) {
+            self.0.write(mc).$field.install_const(name, id, value)
+        }
+
+        fn proto(self) -> Option<Object<'gc>> {
+            self.0.read().$field.proto()
+        }
+
+        fn as_ptr(self) -> *const ObjectPtr {
+            self.0.as_ptr() as *const ObjectPtr
+        }
+    };
+}
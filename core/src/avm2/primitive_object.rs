@@ -0,0 +1,117 @@
+//! Boxed primitives
+
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, ObjectPtr, TObject};
+use crate::avm2::property::Property;
+use crate::avm2::script_object::ScriptObjectData;
+use crate::avm2::{Error, UpdateContext, Value};
+use gc_arena::{Collect, GcCell, MutationContext};
+
+/// An Object which represents a primitive value of some other kind.
+///
+/// Boxed primitives are created whenever a property is accessed on a
+/// `String`, `Number`, or `Boolean` value.
+#[derive(Collect, Debug, Clone, Copy)]
+#[collect(no_drop)]
+pub struct PrimitiveObject<'gc>(GcCell<'gc, PrimitiveObjectData<'gc>>);
+
+#[derive(Collect, Debug, Clone)]
+#[collect(no_drop)]
+pub struct PrimitiveObjectData<'gc> {
+    /// All normal script data.
+    base: ScriptObjectData<'gc>,
+
+    /// The primitive value this object represents.
+    primitive: Value<'gc>,
+}
+
+impl<'gc> PrimitiveObject<'gc> {
+    /// Box a primitive into an object.
+    pub fn from_primitive(
+        primitive: Value<'gc>,
+        proto: Object<'gc>,
+        mc: MutationContext<'gc, '_>,
+    ) -> Self {
+        PrimitiveObject(GcCell::allocate(
+            mc,
+            PrimitiveObjectData {
+                base: ScriptObjectData::base_new(Some(proto)),
+                primitive,
+            },
+        ))
+    }
+}
+
+impl<'gc> TObject<'gc> for PrimitiveObject<'gc> {
+    impl_avm2_custom_object!(base);
+
+    fn get_property_local(
+        self,
+        receiver: Object<'gc>,
+        name: &QName,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        if name.namespace().is_public() && name.local_name() == "length" {
+            if let Value::String(s) = &self.0.read().primitive {
+                return Ok(s.encode_utf16().count().into());
+            }
+        }
+
+        let property = self.0.read().base.property(name);
+        match property {
+            Some(Property::Slot { slot_id, .. }) => self.0.read().base.get_slot(slot_id),
+            Some(property) => property.get(receiver, context),
+            None => Ok(Value::Undefined),
+        }
+    }
+
+    fn set_property_local(
+        self,
+        _receiver: Object<'gc>,
+        _name: &QName,
+        _value: Value<'gc>,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<(), Error<'gc>> {
+        // Properties set on boxed primitives are discarded along with the box.
+        Ok(())
+    }
+
+    fn init_property_local(
+        self,
+        _receiver: Object<'gc>,
+        _name: &QName,
+        _value: Value<'gc>,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<(), Error<'gc>> {
+        Ok(())
+    }
+
+    fn has_own_property(self, name: &QName) -> bool {
+        let read = self.0.read();
+        let is_length = name.namespace().is_public()
+            && name.local_name() == "length"
+            && matches!(read.primitive, Value::String(_));
+
+        is_length || read.base.has_own_property(name)
+    }
+
+    fn resolve_any(self, local_name: &str) -> Option<Namespace> {
+        self.0.read().base.resolve_any(local_name)
+    }
+
+    fn delete_property(self, gc_context: MutationContext<'gc, '_>, name: &QName) -> bool {
+        self.0.write(gc_context).base.delete_property(name)
+    }
+
+    fn get_enumerant_name(self, index: u32) -> Option<QName> {
+        self.0.read().base.get_enumerant_name(index)
+    }
+
+    fn property_is_enumerable(self, name: &QName) -> bool {
+        self.0.read().base.property_is_enumerable(name)
+    }
+
+    fn value_of(self) -> Value<'gc> {
+        self.0.read().primitive.clone()
+    }
+}
@@ -0,0 +1,170 @@
+//! Property data structures
+
+use self::Attribute::*;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::{Error, UpdateContext, Value};
+use enumset::{EnumSet, EnumSetType};
+use gc_arena::{Collect, CollectionContext};
+
+/// Attributes of properties in the AVM2 runtime.
+#[derive(EnumSetType, Debug)]
+pub enum Attribute {
+    DontEnum,
+    DontDelete,
+    ReadOnly,
+}
+
+#[derive(Clone, Debug)]
+pub enum Property<'gc> {
+    Virtual {
+        get: Option<Object<'gc>>,
+        set: Option<Object<'gc>>,
+        attributes: EnumSet<Attribute>,
+    },
+    Stored {
+        value: Value<'gc>,
+        attributes: EnumSet<Attribute>,
+    },
+    Slot {
+        slot_id: u32,
+        attributes: EnumSet<Attribute>,
+    },
+}
+
+unsafe impl<'gc> Collect for Property<'gc> {
+    fn trace(&self, cc: CollectionContext) {
+        match self {
+            Property::Virtual { get, set, .. } => {
+                get.trace(cc);
+                set.trace(cc);
+            }
+            Property::Stored { value, .. } => value.trace(cc),
+            Property::Slot { .. } => {}
+        }
+    }
+}
+
+impl<'gc> Property<'gc> {
+    /// Create a new stored property.
+    pub fn new_stored(value: impl Into<Value<'gc>>) -> Self {
+        Property::Stored {
+            value: value.into(),
+            attributes: DontEnum | DontDelete,
+        }
+    }
+
+    /// Create a new, dynamically created property.
+    pub fn new_dynamic_property(value: impl Into<Value<'gc>>) -> Self {
+        Property::Stored {
+            value: value.into(),
+            attributes: EnumSet::empty(),
+        }
+    }
+
+    /// Convert a value into a slot-backed property.
+    pub fn new_slot(slot_id: u32) -> Self {
+        Property::Slot {
+            slot_id,
+            attributes: DontEnum | DontDelete,
+        }
+    }
+
+    /// Convert a value into a slot-backed constant.
+    pub fn new_const_slot(slot_id: u32) -> Self {
+        Property::Slot {
+            slot_id,
+            attributes: DontEnum | DontDelete | ReadOnly,
+        }
+    }
+
+    /// Create a new virtual property with no getter or setter installed.
+    pub fn new_virtual() -> Self {
+        Property::Virtual {
+            get: None,
+            set: None,
+            attributes: DontEnum | DontDelete,
+        }
+    }
+
+    /// Install a getter into this property.
+    ///
+    /// Non-virtual properties are replaced with a getter-only virtual one.
+    pub fn install_virtual_getter(&mut self, getter_impl: Object<'gc>) {
+        match self {
+            Property::Virtual { get, .. } => *get = Some(getter_impl),
+            _ => {
+                *self = Property::Virtual {
+                    get: Some(getter_impl),
+                    set: None,
+                    attributes: DontEnum | DontDelete,
+                }
+            }
+        }
+    }
+
+    /// Install a setter into this property.
+    ///
+    /// Non-virtual properties are replaced with a setter-only virtual one.
+    pub fn install_virtual_setter(&mut self, setter_impl: Object<'gc>) {
+        match self {
+            Property::Virtual { set, .. } => *set = Some(setter_impl),
+            _ => {
+                *self = Property::Virtual {
+                    get: None,
+                    set: Some(setter_impl),
+                    attributes: DontEnum | DontDelete,
+                }
+            }
+        }
+    }
+
+    /// Retrieve the value of this property, invoking the getter if
+    /// necessary.
+    ///
+    /// Slot properties must be resolved by the caller; this yields
+    /// `undefined` for them.
+    pub fn get(
+        &self,
+        receiver: Object<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        match self {
+            Property::Virtual { get: Some(get), .. } => get.call(Some(receiver), &[], context),
+            Property::Virtual { get: None, .. } => Ok(Value::Undefined),
+            Property::Stored { value, .. } => Ok(value.to_owned()),
+            Property::Slot { .. } => Ok(Value::Undefined),
+        }
+    }
+
+    /// Retrieve the setter of a virtual property, if any.
+    pub fn setter(&self) -> Option<Object<'gc>> {
+        match self {
+            Property::Virtual { set, .. } => *set,
+            _ => None,
+        }
+    }
+
+    /// List this property's attributes.
+    pub fn attributes(&self) -> EnumSet<Attribute> {
+        match self {
+            Property::Virtual { attributes, .. } => *attributes,
+            Property::Stored { attributes, .. } => *attributes,
+            Property::Slot { attributes, .. } => *attributes,
+        }
+    }
+
+    pub fn can_delete(&self) -> bool {
+        !self.attributes().contains(DontDelete)
+    }
+
+    pub fn is_enumerable(&self) -> bool {
+        !self.attributes().contains(DontEnum)
+    }
+
+    pub fn is_virtual(&self) -> bool {
+        match self {
+            Property::Virtual { .. } => true,
+            _ => false,
+        }
+    }
+}
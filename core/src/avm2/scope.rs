@@ -0,0 +1,80 @@
+//! Represents AVM2 scope chain resolution.
+
+use crate::avm2::names::Multiname;
+use crate::avm2::object::{Object, TObject};
+use gc_arena::{Collect, GcCell, MutationContext};
+
+/// Indicates what kind of scope a scope is.
+#[derive(Copy, Clone, Debug, PartialEq, Collect)]
+#[collect(require_static)]
+pub enum ScopeClass {
+    /// Scope represents an ordinary object pushed with `pushscope`.
+    Ordinary,
+
+    /// Scope represents an object added to the scope chain with `with`.
+    With,
+}
+
+/// Represents a scope chain for an AVM2 activation.
+#[derive(Debug, Collect)]
+#[collect(no_drop)]
+pub struct Scope<'gc> {
+    parent: Option<GcCell<'gc, Scope<'gc>>>,
+    class: ScopeClass,
+    values: Object<'gc>,
+}
+
+impl<'gc> Scope<'gc> {
+    /// Push a scope onto an existing scope chain.
+    pub fn push_scope(
+        parent: Option<GcCell<'gc, Scope<'gc>>>,
+        values: Object<'gc>,
+        class: ScopeClass,
+        mc: MutationContext<'gc, '_>,
+    ) -> GcCell<'gc, Self> {
+        GcCell::allocate(
+            mc,
+            Scope {
+                parent,
+                class,
+                values,
+            },
+        )
+    }
+
+    /// Returns a reference to the current local scope object.
+    pub fn locals(&self) -> &Object<'gc> {
+        &self.values
+    }
+
+    /// Returns a reference to the parent scope object.
+    pub fn parent_cell(&self) -> Option<GcCell<'gc, Scope<'gc>>> {
+        self.parent
+    }
+
+    /// Returns the class of this scope.
+    pub fn class(&self) -> ScopeClass {
+        self.class
+    }
+
+    /// Find an object that contains a given property in the scope stack.
+    pub fn find(&self, name: &Multiname) -> Option<Object<'gc>> {
+        if self.values.resolve_multiname(name).is_some() {
+            return Some(self.values);
+        }
+
+        if let Some(parent) = self.parent {
+            return parent.read().find(name);
+        }
+
+        None
+    }
+
+    /// Find the bottom-most (global) object of this scope chain.
+    pub fn global_object(&self) -> Object<'gc> {
+        match self.parent {
+            Some(parent) => parent.read().global_object(),
+            None => self.values,
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! Whole script representation
+
+use crate::avm2::object::Object;
+use gc_arena::{Collect, CollectionContext};
+use std::rc::Rc;
+use swf::avm2::types::AbcFile;
+
+/// A loaded ABC file, shared by every function, class, and script it
+/// defines.
+///
+/// The underlying `AbcFile` holds no garbage-collected pointers, so this type
+/// never needs to be traced.
+#[derive(Clone, Debug)]
+pub struct TranslationUnit(Rc<AbcFile>);
+
+unsafe impl Collect for TranslationUnit {
+    #[inline]
+    fn trace(&self, _cc: CollectionContext) {}
+
+    #[inline]
+    fn needs_trace() -> bool {
+        false
+    }
+}
+
+impl TranslationUnit {
+    pub fn from_abc(abc: AbcFile) -> Self {
+        Self(Rc::new(abc))
+    }
+
+    /// Retrieve the underlying `AbcFile` for this translation unit.
+    pub fn abc(&self) -> &AbcFile {
+        &self.0
+    }
+}
+
+/// A single script loaded from an ABC file.
+///
+/// Scripts own the global object their traits are installed on. A script's
+/// initializer runs at most once: either when its ABC file is loaded, or
+/// lazily, the first time one of its names is looked up.
+#[derive(Clone, Debug, Collect)]
+#[collect(no_drop)]
+pub struct Script<'gc> {
+    /// The global object for this script.
+    globals: Object<'gc>,
+
+    /// The ABC file this script was loaded from.
+    translation_unit: TranslationUnit,
+
+    /// The index of this script within its ABC file.
+    script_index: u32,
+
+    /// Whether or not the script initializer has been run.
+    initialized: bool,
+}
+
+impl<'gc> Script<'gc> {
+    pub fn new(globals: Object<'gc>, translation_unit: TranslationUnit, script_index: u32) -> Self {
+        Self {
+            globals,
+            translation_unit,
+            script_index,
+            initialized: false,
+        }
+    }
+
+    pub fn globals(&self) -> Object<'gc> {
+        self.globals
+    }
+
+    pub fn translation_unit(&self) -> &TranslationUnit {
+        &self.translation_unit
+    }
+
+    pub fn script_index(&self) -> u32 {
+        self.script_index
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+
+    pub fn set_initialized(&mut self) {
+        self.initialized = true;
+    }
+}
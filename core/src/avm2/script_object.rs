@@ -0,0 +1,264 @@
+//! Default AVM2 object impl
+
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, ObjectPtr, TObject};
+use crate::avm2::property::Property;
+use crate::avm2::{Error, UpdateContext, Value};
+use gc_arena::{Collect, CollectionContext, GcCell, MutationContext};
+use indexmap::IndexMap;
+use std::fmt::Debug;
+
+/// A class instance allocator that allocates `ScriptObject`s.
+pub fn scriptobject_allocator<'gc>(
+    proto: Object<'gc>,
+    mc: MutationContext<'gc, '_>,
+) -> Object<'gc> {
+    ScriptObject::object(mc, proto)
+}
+
+/// Default implementation of `avm2::Object`.
+#[derive(Clone, Collect, Debug, Copy)]
+#[collect(no_drop)]
+pub struct ScriptObject<'gc>(GcCell<'gc, ScriptObjectClass<'gc>>);
+
+/// `ScriptObject` carries its data in a single-field struct so that the
+/// shared property macros can address it the same way as every other
+/// object type.
+#[derive(Clone, Collect, Debug)]
+#[collect(no_drop)]
+pub struct ScriptObjectClass<'gc> {
+    base: ScriptObjectData<'gc>,
+}
+
+/// Base data common to all `TObject` implementations.
+///
+/// Host implementations of `TObject` should embed `ScriptObjectData` and
+/// forward any trait method implementations it does not overwrite to this
+/// struct.
+#[derive(Clone, Debug)]
+pub struct ScriptObjectData<'gc> {
+    /// Properties stored on this object, in insertion order.
+    values: IndexMap<QName, Property<'gc>>,
+
+    /// Slots stored on this object.
+    slots: Vec<Value<'gc>>,
+
+    /// Implicit prototype (or declared base class) of this script object.
+    proto: Option<Object<'gc>>,
+}
+
+unsafe impl<'gc> Collect for ScriptObjectData<'gc> {
+    fn trace(&self, cc: CollectionContext) {
+        for value in self.values.values() {
+            value.trace(cc);
+        }
+
+        self.slots.trace(cc);
+        self.proto.trace(cc);
+    }
+}
+
+impl<'gc> TObject<'gc> for ScriptObject<'gc> {
+    impl_avm2_custom_object!(base);
+    impl_avm2_custom_object_properties!(base);
+}
+
+impl<'gc> ScriptObject<'gc> {
+    /// Construct a bare object with no prototype.
+    pub fn bare_object(mc: MutationContext<'gc, '_>) -> Object<'gc> {
+        ScriptObject(GcCell::allocate(
+            mc,
+            ScriptObjectClass {
+                base: ScriptObjectData::base_new(None),
+            },
+        ))
+        .into()
+    }
+
+    /// Construct an object with a prototype.
+    pub fn object(mc: MutationContext<'gc, '_>, proto: Object<'gc>) -> Object<'gc> {
+        ScriptObject(GcCell::allocate(
+            mc,
+            ScriptObjectClass {
+                base: ScriptObjectData::base_new(Some(proto)),
+            },
+        ))
+        .into()
+    }
+}
+
+impl<'gc> ScriptObjectData<'gc> {
+    pub fn base_new(proto: Option<Object<'gc>>) -> Self {
+        ScriptObjectData {
+            values: IndexMap::new(),
+            slots: Vec::new(),
+            proto,
+        }
+    }
+
+    /// Retrieve a copy of an own property.
+    pub fn property(&self, name: &QName) -> Option<Property<'gc>> {
+        self.values.get(name).cloned()
+    }
+
+    /// Set the value of a stored or slotted property, creating a dynamic
+    /// property if none exists.
+    ///
+    /// Virtual properties must be handled by the caller.
+    pub fn set_property_value(
+        &mut self,
+        name: &QName,
+        value: Value<'gc>,
+        is_init: bool,
+    ) -> Result<(), Error<'gc>> {
+        match self.values.get_mut(name) {
+            Some(Property::Slot { slot_id, .. }) => {
+                let slot_id = *slot_id;
+                if is_init {
+                    self.init_slot(slot_id, value)
+                } else {
+                    self.set_slot(slot_id, value)
+                }
+            }
+            Some(Property::Stored {
+                value: stored,
+                attributes,
+            }) => {
+                if is_init || !attributes.contains(crate::avm2::property::Attribute::ReadOnly) {
+                    *stored = value;
+                }
+
+                Ok(())
+            }
+            Some(Property::Virtual { .. }) => Ok(()),
+            None => {
+                self.values
+                    .insert(name.clone(), Property::new_dynamic_property(value));
+                Ok(())
+            }
+        }
+    }
+
+    pub fn delete_property(&mut self, name: &QName) -> bool {
+        let can_delete = self
+            .values
+            .get(name)
+            .map(|p| p.can_delete())
+            .unwrap_or(false);
+
+        if can_delete {
+            self.values.shift_remove(name);
+        }
+
+        can_delete
+    }
+
+    pub fn get_slot(&self, id: u32) -> Result<Value<'gc>, Error<'gc>> {
+        // Slot zero is reserved; ABC slot IDs begin at one.
+        id.checked_sub(1)
+            .and_then(|id| self.slots.get(id as usize))
+            .cloned()
+            .ok_or_else(|| Error::PropertyNotFound(format!("slot {}", id)))
+    }
+
+    pub fn set_slot(&mut self, id: u32, value: Value<'gc>) -> Result<(), Error<'gc>> {
+        self.init_slot(id, value)
+    }
+
+    pub fn init_slot(&mut self, id: u32, value: Value<'gc>) -> Result<(), Error<'gc>> {
+        let index =
+            id.checked_sub(1)
+                .ok_or_else(|| Error::PropertyNotFound("slot 0".to_string()))? as usize;
+        if self.slots.len() <= index {
+            self.slots.resize(index + 1, Value::Undefined);
+        }
+
+        self.slots[index] = value;
+        Ok(())
+    }
+
+    pub fn has_own_property(&self, name: &QName) -> bool {
+        self.values.contains_key(name)
+    }
+
+    pub fn has_own_virtual_property(&self, name: &QName) -> bool {
+        self.values
+            .get(name)
+            .map(|p| p.is_virtual())
+            .unwrap_or(false)
+    }
+
+    pub fn get_setter(&self, name: &QName) -> Option<Object<'gc>> {
+        self.values.get(name).and_then(|p| p.setter())
+    }
+
+    pub fn resolve_any(&self, local_name: &str) -> Option<Namespace> {
+        self.values
+            .keys()
+            .find(|key| key.local_name() == local_name)
+            .map(|key| key.namespace().clone())
+    }
+
+    pub fn get_enumerant_name(&self, index: u32) -> Option<QName> {
+        self.values
+            .iter()
+            .filter(|(_, property)| property.is_enumerable())
+            .nth(index as usize)
+            .map(|(name, _)| name.clone())
+    }
+
+    pub fn property_is_enumerable(&self, name: &QName) -> bool {
+        self.values
+            .get(name)
+            .map(|p| p.is_enumerable())
+            .unwrap_or(false)
+    }
+
+    pub fn install_method(&mut self, name: QName, function: Object<'gc>) {
+        self.values.insert(name, Property::new_stored(function));
+    }
+
+    pub fn install_getter(&mut self, name: QName, function: Object<'gc>) {
+        self.values
+            .entry(name)
+            .or_insert_with(Property::new_virtual)
+            .install_virtual_getter(function);
+    }
+
+    pub fn install_setter(&mut self, name: QName, function: Object<'gc>) {
+        self.values
+            .entry(name)
+            .or_insert_with(Property::new_virtual)
+            .install_virtual_setter(function);
+    }
+
+    pub fn install_dynamic_property(&mut self, name: QName, value: Value<'gc>) {
+        self.values
+            .insert(name, Property::new_dynamic_property(value));
+    }
+
+    pub fn install_slot(&mut self, name: QName, id: u32, value: Value<'gc>) {
+        let id = self.allocate_slot(id);
+        self.values.insert(name, Property::new_slot(id));
+        let _ = self.init_slot(id, value);
+    }
+
+    pub fn install_const(&mut self, name: QName, id: u32, value: Value<'gc>) {
+        let id = self.allocate_slot(id);
+        self.values.insert(name, Property::new_const_slot(id));
+        let _ = self.init_slot(id, value);
+    }
+
+    /// Pick a slot ID for a new slot; zero means "the next free one".
+    fn allocate_slot(&self, id: u32) -> u32 {
+        if id == 0 {
+            self.slots.len() as u32 + 1
+        } else {
+            id
+        }
+    }
+
+    pub fn proto(&self) -> Option<Object<'gc>> {
+        self.proto
+    }
+}
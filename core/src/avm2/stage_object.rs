@@ -0,0 +1,50 @@
+//! AVM2 object impl for the display hierarchy.
+
+use crate::avm2::names::{Namespace, QName};
+use crate::avm2::object::{Object, ObjectPtr, TObject};
+use crate::avm2::property::Property;
+use crate::avm2::script_object::ScriptObjectData;
+use crate::avm2::{Error, UpdateContext, Value};
+use crate::display_object::DisplayObject;
+use gc_arena::{Collect, GcCell, MutationContext};
+
+/// An AVM2 object bound to a display object on the stage.
+#[derive(Clone, Collect, Debug, Copy)]
+#[collect(no_drop)]
+pub struct StageObject<'gc>(GcCell<'gc, StageObjectData<'gc>>);
+
+#[derive(Clone, Collect, Debug)]
+#[collect(no_drop)]
+pub struct StageObjectData<'gc> {
+    /// The base data common to all AVM2 objects.
+    base: ScriptObjectData<'gc>,
+
+    /// The associated display object.
+    display_object: DisplayObject<'gc>,
+}
+
+impl<'gc> StageObject<'gc> {
+    /// Create a stage object for a given display object.
+    pub fn for_display_object(
+        mc: MutationContext<'gc, '_>,
+        display_object: DisplayObject<'gc>,
+        proto: Object<'gc>,
+    ) -> Self {
+        Self(GcCell::allocate(
+            mc,
+            StageObjectData {
+                base: ScriptObjectData::base_new(Some(proto)),
+                display_object,
+            },
+        ))
+    }
+}
+
+impl<'gc> TObject<'gc> for StageObject<'gc> {
+    impl_avm2_custom_object!(base);
+    impl_avm2_custom_object_properties!(base);
+
+    fn as_display_object(self) -> Option<DisplayObject<'gc>> {
+        Some(self.0.read().display_object)
+    }
+}
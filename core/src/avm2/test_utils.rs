@@ -0,0 +1,94 @@
+use crate::avm1::test_utils::with_avm;
+use crate::avm2::function::Executable;
+use crate::avm2::method::BytecodeMethod;
+use crate::avm2::scope::{Scope, ScopeClass};
+use crate::avm2::script::TranslationUnit;
+use crate::avm2::script_object::ScriptObject;
+use crate::avm2::{Error, Object, UpdateContext, Value};
+use swf::avm2::types::{
+    AbcFile, ConstantPool, Index, Method, MethodBody, Multiname, Namespace, Script,
+};
+
+pub fn with_avm2<F>(test: F)
+where
+    F: for<'a, 'gc> FnOnce(&mut UpdateContext<'a, 'gc, '_>) -> Result<(), Error<'gc>>,
+{
+    with_avm(9, |_avm, context, _root| {
+        if let Err(e) = test(context) {
+            panic!("Encountered exception during test: {}", e);
+        }
+
+        Ok(())
+    })
+}
+
+/// Run a method body on a new script global object, and return its result.
+///
+/// The body is given as a list of instructions, each an opcode followed by
+/// its operands.
+///
+/// Each string in `strings` is also a public `QName` multiname, so that
+/// string constant `n` and multiname constant `n` (both 1-based) refer to the
+/// same name.
+pub fn run_code<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    strings: &[&str],
+    code: &[&[u8]],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let multinames = (1..=strings.len() as u32)
+        .map(|name| Multiname::QName {
+            namespace: Index::new(1),
+            name: Index::new(name),
+        })
+        .collect();
+    let abc = AbcFile {
+        major_version: 46,
+        minor_version: 16,
+        constant_pool: ConstantPool {
+            ints: vec![],
+            uints: vec![],
+            doubles: vec![],
+            strings: strings.iter().map(|s| s.to_string()).collect(),
+            namespaces: vec![Namespace::Package(Index::new(0))],
+            namespace_sets: vec![],
+            multinames,
+        },
+        methods: vec![Method {
+            name: Index::new(0),
+            params: vec![],
+            return_type: Index::new(0),
+            needs_arguments_object: false,
+            needs_activation: false,
+            needs_rest: false,
+            needs_dxns: false,
+        }],
+        metadata: vec![],
+        instances: vec![],
+        classes: vec![],
+        scripts: vec![Script {
+            init_method: Index::new(0),
+            traits: vec![],
+        }],
+        method_bodies: vec![MethodBody {
+            method: Index::new(0),
+            max_stack: 16,
+            num_locals: 4,
+            init_scope_depth: 0,
+            max_scope_depth: 4,
+            code: code.concat(),
+            exceptions: vec![],
+            traits: vec![],
+        }],
+    };
+
+    let method = BytecodeMethod::from_method_index(TranslationUnit::from_abc(abc), Index::new(0))
+        .ok_or_else(|| Error::InvalidConstant("Unknown test method".to_string()))?;
+    let globals: Object<'gc> = ScriptObject::object(context.gc_context, context.avm2.globals());
+    let scope = Scope::push_scope(None, globals, ScopeClass::Ordinary, context.gc_context);
+
+    Executable::from_method(method.into(), Some(scope), None, None).exec(
+        Some(globals),
+        &[],
+        context,
+    )
+}
@@ -0,0 +1,152 @@
+use crate::avm2::error::Error;
+use crate::avm2::test_utils::{run_code, with_avm2};
+use crate::avm2::Value;
+
+// Opcodes used by the tests below.
+const PUSH_BYTE: u8 = 0x24;
+const PUSH_STRING: u8 = 0x2c;
+const PUSH_TRUE: u8 = 0x26;
+const DUP: u8 = 0x2a;
+const ADD: u8 = 0xa0;
+const SUBTRACT: u8 = 0xa1;
+const MULTIPLY: u8 = 0xa2;
+const LESS_THAN: u8 = 0xad;
+const IF_FALSE: u8 = 0x12;
+const IF_LT: u8 = 0x15;
+const JUMP: u8 = 0x10;
+const GET_LOCAL_1: u8 = 0xd1;
+const SET_LOCAL_1: u8 = 0xd5;
+const INC_LOCAL_I: u8 = 0xc2;
+const NEW_OBJECT: u8 = 0x55;
+const NEW_ARRAY: u8 = 0x56;
+const GET_LEX: u8 = 0x60;
+const GET_PROPERTY: u8 = 0x66;
+const SET_PROPERTY: u8 = 0x61;
+const CALL_PROPERTY: u8 = 0x46;
+const TYPE_OF: u8 = 0x95;
+const RETURN_VALUE: u8 = 0x48;
+const RETURN_VOID: u8 = 0x47;
+
+#[test]
+fn arithmetic() {
+    with_avm2(|context| -> Result<(), Error> {
+        let code: &[&[u8]] = &[
+            &[PUSH_BYTE, 6],
+            &[PUSH_BYTE, 4],
+            &[SUBTRACT],
+            &[PUSH_BYTE, 3],
+            &[MULTIPLY],
+            &[RETURN_VALUE],
+        ];
+        assert_eq!(run_code(context, &[], code)?, 6.0.into());
+
+        // `pushbyte` sign-extends its operand.
+        let code: &[&[u8]] = &[&[PUSH_BYTE, 0xfe], &[PUSH_BYTE, 1], &[ADD], &[RETURN_VALUE]];
+        assert_eq!(run_code(context, &[], code)?, (-1.0).into());
+        Ok(())
+    });
+}
+
+#[test]
+fn string_concatenation() {
+    with_avm2(|context| -> Result<(), Error> {
+        let code: &[&[u8]] = &[&[PUSH_STRING, 1], &[PUSH_BYTE, 2], &[ADD], &[RETURN_VALUE]];
+        assert_eq!(run_code(context, &["a"], code)?, "a2".into());
+        Ok(())
+    });
+}
+
+#[test]
+fn branches() {
+    with_avm2(|context| -> Result<(), Error> {
+        // Branch offsets are relative to the end of the branch instruction.
+        let code: &[&[u8]] = &[
+            &[PUSH_BYTE, 1],
+            &[PUSH_BYTE, 2],
+            &[IF_LT, 3, 0, 0],
+            &[PUSH_BYTE, 10],
+            &[RETURN_VALUE],
+            &[PUSH_BYTE, 20],
+            &[RETURN_VALUE],
+        ];
+        assert_eq!(run_code(context, &[], code)?, 20.0.into());
+
+        let code: &[&[u8]] = &[
+            &[PUSH_BYTE, 2],
+            &[PUSH_BYTE, 1],
+            &[LESS_THAN],
+            &[IF_FALSE, 3, 0, 0],
+            &[PUSH_BYTE, 10],
+            &[RETURN_VALUE],
+            &[PUSH_BYTE, 20],
+            &[RETURN_VALUE],
+        ];
+        assert_eq!(run_code(context, &[], code)?, 20.0.into());
+
+        let code: &[&[u8]] = &[
+            &[PUSH_TRUE],
+            &[JUMP, 1, 0, 0],
+            &[RETURN_VALUE],
+            &[RETURN_VOID],
+        ];
+        assert_eq!(run_code(context, &[], code)?, Value::Undefined);
+        Ok(())
+    });
+}
+
+#[test]
+fn locals() {
+    with_avm2(|context| -> Result<(), Error> {
+        let code: &[&[u8]] = &[
+            &[PUSH_BYTE, 7],
+            &[SET_LOCAL_1],
+            &[INC_LOCAL_I, 1],
+            &[GET_LOCAL_1],
+            &[RETURN_VALUE],
+        ];
+        assert_eq!(run_code(context, &[], code)?, 8.0.into());
+        Ok(())
+    });
+}
+
+#[test]
+fn object_properties() {
+    with_avm2(|context| -> Result<(), Error> {
+        let code: &[&[u8]] = &[
+            &[NEW_OBJECT, 0],
+            &[DUP],
+            &[PUSH_BYTE, 5],
+            &[SET_PROPERTY, 1],
+            &[GET_PROPERTY, 1],
+            &[RETURN_VALUE],
+        ];
+        assert_eq!(run_code(context, &["x"], code)?, 5.0.into());
+
+        let code: &[&[u8]] = &[
+            &[PUSH_BYTE, 1],
+            &[PUSH_BYTE, 2],
+            &[NEW_ARRAY, 2],
+            &[GET_PROPERTY, 1],
+            &[RETURN_VALUE],
+        ];
+        assert_eq!(run_code(context, &["length"], code)?, 2.0.into());
+
+        let code: &[&[u8]] = &[&[NEW_OBJECT, 0], &[TYPE_OF], &[RETURN_VALUE]];
+        assert_eq!(run_code(context, &[], code)?, "object".into());
+        Ok(())
+    });
+}
+
+#[test]
+fn builtin_calls() {
+    with_avm2(|context| -> Result<(), Error> {
+        let code: &[&[u8]] = &[
+            &[GET_LEX, 1],
+            &[PUSH_BYTE, 0xfb],
+            &[CALL_PROPERTY, 2, 1],
+            &[RETURN_VALUE],
+        ];
+        assert_eq!(run_code(context, &["Math", "abs"], code)?, 5.0.into());
+        Ok(())
+    });
+}
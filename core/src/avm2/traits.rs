@@ -0,0 +1,138 @@
+//! Installation of ABC traits onto objects.
+
+use crate::avm2::function::FunctionObject;
+use crate::avm2::method::BytecodeMethod;
+use crate::avm2::names::QName;
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::scope::Scope;
+use crate::avm2::script::TranslationUnit;
+use crate::avm2::value::abc_default_value;
+use crate::avm2::{Error, UpdateContext, Value};
+use gc_arena::GcCell;
+use std::f64::NAN;
+use swf::avm2::types::{
+    DefaultValue as AbcDefaultValue, Index, Method as AbcMethod, Multiname as AbcMultiname,
+    Trait as AbcTrait, TraitKind as AbcTraitKind,
+};
+
+/// Install a single ABC trait onto an object.
+///
+/// Methods, getters, setters, and functions are instantiated as closures over
+/// `scope`. `defining_class` is the class object that declared the trait,
+/// which is used to resolve `super` accesses within the trait's methods.
+pub fn install_trait<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    translation_unit: &TranslationUnit,
+    abc_trait: &AbcTrait,
+    scope: Option<GcCell<'gc, Scope<'gc>>>,
+    target: Object<'gc>,
+    defining_class: Option<Object<'gc>>,
+) -> Result<(), Error<'gc>> {
+    let name = QName::from_abc_multiname(translation_unit.abc(), abc_trait.name.clone())?;
+    let mc = context.gc_context;
+
+    match &abc_trait.kind {
+        AbcTraitKind::Slot {
+            slot_id,
+            type_name,
+            value,
+        } => {
+            let value = default_slot_value(translation_unit, type_name, value.as_ref())?;
+            target.install_slot(mc, name, *slot_id, value);
+        }
+        AbcTraitKind::Const {
+            slot_id,
+            type_name,
+            value,
+        } => {
+            let value = default_slot_value(translation_unit, type_name, value.as_ref())?;
+            target.install_const(mc, name, *slot_id, value);
+        }
+        AbcTraitKind::Method { method, .. } => {
+            let function =
+                method_closure(context, translation_unit, method, scope, defining_class)?;
+            target.install_method(mc, name, function);
+        }
+        AbcTraitKind::Getter { method, .. } => {
+            let function =
+                method_closure(context, translation_unit, method, scope, defining_class)?;
+            target.install_getter(mc, name, function);
+        }
+        AbcTraitKind::Setter { method, .. } => {
+            let function =
+                method_closure(context, translation_unit, method, scope, defining_class)?;
+            target.install_setter(mc, name, function);
+        }
+        AbcTraitKind::Class { slot_id, .. } => {
+            // The class object itself is created when the owning script's
+            // initializer executes `newclass`.
+            target.install_slot(mc, name, *slot_id, Value::Undefined);
+        }
+        AbcTraitKind::Function { slot_id, function } => {
+            let function = method_closure(context, translation_unit, function, scope, None)?;
+            target.install_slot(mc, name, *slot_id, function.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Indicates if a trait is stored per-instance (as opposed to being shared on
+/// the class prototype).
+pub fn is_instance_slot(abc_trait: &AbcTrait) -> bool {
+    match abc_trait.kind {
+        AbcTraitKind::Slot { .. } | AbcTraitKind::Const { .. } => true,
+        _ => false,
+    }
+}
+
+/// Create a function object for a method in an ABC file.
+fn method_closure<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    translation_unit: &TranslationUnit,
+    method: &Index<AbcMethod>,
+    scope: Option<GcCell<'gc, Scope<'gc>>>,
+    defining_class: Option<Object<'gc>>,
+) -> Result<Object<'gc>, Error<'gc>> {
+    let method = BytecodeMethod::from_method_index(translation_unit.clone(), method.clone())
+        .ok_or_else(|| Error::InvalidConstant(format!("Unknown method {}", method.0)))?;
+    let fn_proto = context.avm2.prototypes().function;
+
+    Ok(FunctionObject::from_method(
+        context.gc_context,
+        method.into(),
+        scope,
+        fn_proto,
+        defining_class,
+    ))
+}
+
+/// Determine the initial value of a slot.
+///
+/// Slots without an explicit default take the default value of their
+/// declared type.
+fn default_slot_value<'gc>(
+    translation_unit: &TranslationUnit,
+    type_name: &Index<AbcMultiname>,
+    value: Option<&AbcDefaultValue>,
+) -> Result<Value<'gc>, Error<'gc>> {
+    if let Some(value) = value {
+        return abc_default_value(translation_unit, value);
+    }
+
+    if type_name.0 == 0 {
+        return Ok(Value::Undefined);
+    }
+
+    Ok(
+        match QName::from_abc_multiname(translation_unit.abc(), type_name.clone()) {
+            Ok(type_name) => match type_name.local_name() {
+                "int" | "uint" => 0.into(),
+                "Number" => NAN.into(),
+                "Boolean" => false.into(),
+                _ => Value::Null,
+            },
+            Err(_) => Value::Null,
+        },
+    )
+}
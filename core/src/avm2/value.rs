@@ -0,0 +1,538 @@
+//! AVM2 values
+
+use crate::avm1::value::{f64_to_string, f64_to_wrapping_i32, f64_to_wrapping_u32};
+use crate::avm2::error::Error;
+use crate::avm2::names::{abc_string, Namespace, QName};
+use crate::avm2::object::{Object, TObject};
+use crate::avm2::primitive_object::PrimitiveObject;
+use crate::avm2::script::TranslationUnit;
+use crate::avm2::UpdateContext;
+use std::f64::NAN;
+use swf::avm2::types::{DefaultValue as AbcDefaultValue, Index};
+
+/// An AVM2 value.
+///
+/// TODO: AVM2 also needs Scope, Namespace, and XML values.
+#[derive(Clone, Debug)]
+pub enum Value<'gc> {
+    Undefined,
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Namespace(Namespace),
+    Object(Object<'gc>),
+}
+
+impl<'gc> From<String> for Value<'gc> {
+    fn from(string: String) -> Self {
+        Value::String(string)
+    }
+}
+
+impl<'gc> From<&str> for Value<'gc> {
+    fn from(string: &str) -> Self {
+        Value::String(string.to_owned())
+    }
+}
+
+impl<'gc> From<bool> for Value<'gc> {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl<'gc, T> From<T> for Value<'gc>
+where
+    Object<'gc>: From<T>,
+{
+    fn from(value: T) -> Self {
+        Value::Object(Object::from(value))
+    }
+}
+
+impl<'gc> From<f64> for Value<'gc> {
+    fn from(value: f64) -> Self {
+        Value::Number(value)
+    }
+}
+
+impl<'gc> From<i32> for Value<'gc> {
+    fn from(value: i32) -> Self {
+        Value::Number(f64::from(value))
+    }
+}
+
+impl<'gc> From<u32> for Value<'gc> {
+    fn from(value: u32) -> Self {
+        Value::Number(f64::from(value))
+    }
+}
+
+impl<'gc> From<u16> for Value<'gc> {
+    fn from(value: u16) -> Self {
+        Value::Number(f64::from(value))
+    }
+}
+
+impl<'gc> From<usize> for Value<'gc> {
+    fn from(value: usize) -> Self {
+        Value::Number(value as f64)
+    }
+}
+
+impl<'gc> From<Namespace> for Value<'gc> {
+    fn from(value: Namespace) -> Self {
+        Value::Namespace(value)
+    }
+}
+
+unsafe impl<'gc> gc_arena::Collect for Value<'gc> {
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        if let Value::Object(object) = self {
+            object.trace(cc);
+        }
+    }
+}
+
+impl PartialEq for Value<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Undefined, Value::Undefined) => true,
+            (Value::Null, Value::Null) => true,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Number(a), Value::Number(b)) => a == b,
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Namespace(a), Value::Namespace(b)) => a == b,
+            (Value::Object(a), Value::Object(b)) => Object::ptr_eq(*a, *b),
+            _ => false,
+        }
+    }
+}
+
+/// Retrieve an integer from the ABC constant pool.
+pub fn abc_int<'gc>(
+    translation_unit: &TranslationUnit,
+    index: Index<i32>,
+) -> Result<i32, Error<'gc>> {
+    if index.0 == 0 {
+        return Ok(0);
+    }
+
+    translation_unit
+        .abc()
+        .constant_pool
+        .ints
+        .get(index.0 as usize - 1)
+        .cloned()
+        .ok_or_else(|| Error::InvalidConstant(format!("Unknown int constant {}", index.0)))
+}
+
+/// Retrieve an unsigned integer from the ABC constant pool.
+pub fn abc_uint<'gc>(
+    translation_unit: &TranslationUnit,
+    index: Index<u32>,
+) -> Result<u32, Error<'gc>> {
+    if index.0 == 0 {
+        return Ok(0);
+    }
+
+    translation_unit
+        .abc()
+        .constant_pool
+        .uints
+        .get(index.0 as usize - 1)
+        .cloned()
+        .ok_or_else(|| Error::InvalidConstant(format!("Unknown uint constant {}", index.0)))
+}
+
+/// Retrieve a double from the ABC constant pool.
+pub fn abc_double<'gc>(
+    translation_unit: &TranslationUnit,
+    index: Index<f64>,
+) -> Result<f64, Error<'gc>> {
+    if index.0 == 0 {
+        return Ok(NAN);
+    }
+
+    translation_unit
+        .abc()
+        .constant_pool
+        .doubles
+        .get(index.0 as usize - 1)
+        .cloned()
+        .ok_or_else(|| Error::InvalidConstant(format!("Unknown double constant {}", index.0)))
+}
+
+/// Retrieve a default value as an AVM2 `Value`.
+pub fn abc_default_value<'gc>(
+    translation_unit: &TranslationUnit,
+    default: &AbcDefaultValue,
+) -> Result<Value<'gc>, Error<'gc>> {
+    let file = translation_unit.abc();
+    Ok(match default {
+        AbcDefaultValue::Int(i) => abc_int(translation_unit, *i)?.into(),
+        AbcDefaultValue::Uint(u) => abc_uint(translation_unit, *u)?.into(),
+        AbcDefaultValue::Double(d) => abc_double(translation_unit, *d)?.into(),
+        AbcDefaultValue::String(s) => abc_string(file, s.clone())?.into(),
+        AbcDefaultValue::True => true.into(),
+        AbcDefaultValue::False => false.into(),
+        AbcDefaultValue::Null => Value::Null,
+        AbcDefaultValue::Undefined => Value::Undefined,
+        AbcDefaultValue::Namespace(ns)
+        | AbcDefaultValue::Package(ns)
+        | AbcDefaultValue::PackageInternal(ns)
+        | AbcDefaultValue::Protected(ns)
+        | AbcDefaultValue::Explicit(ns)
+        | AbcDefaultValue::StaticProtected(ns)
+        | AbcDefaultValue::Private(ns) => Namespace::from_abc_namespace(file, ns.clone())?.into(),
+    })
+}
+
+/// The preferred type of a primitive conversion.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hint {
+    String,
+    Number,
+}
+
+impl<'gc> Value<'gc> {
+    pub fn as_namespace(&self) -> Result<Namespace, Error<'gc>> {
+        match self {
+            Value::Namespace(ns) => Ok(ns.clone()),
+            _ => Err(Error::ThrownValue(
+                format!("TypeError: Expected Namespace, found {:?}", self).into(),
+            )),
+        }
+    }
+
+    pub fn as_object(&self) -> Option<Object<'gc>> {
+        match self {
+            Value::Object(object) => Some(*object),
+            _ => None,
+        }
+    }
+
+    pub fn is_primitive(&self) -> bool {
+        match self {
+            Value::Object(_) => false,
+            _ => true,
+        }
+    }
+
+    /// Convert a runtime name component (from a late-bound multiname) into
+    /// a string.
+    ///
+    /// Objects used as names are not run through `toString`.
+    pub fn into_name_string(self) -> String {
+        match self {
+            Value::Undefined => "undefined".to_string(),
+            Value::Null => "null".to_string(),
+            Value::Bool(b) => b.to_string(),
+            Value::Number(n) => f64_to_string(n),
+            Value::String(s) => s,
+            Value::Namespace(_) => "[namespace]".to_string(),
+            Value::Object(_) => "[object Object]".to_string(),
+        }
+    }
+
+    /// ECMA-262 2nd edition s. 9.2 ToBoolean
+    pub fn coerce_to_boolean(&self) -> bool {
+        match self {
+            Value::Undefined | Value::Null => false,
+            Value::Bool(b) => *b,
+            Value::Number(n) => !n.is_nan() && *n != 0.0,
+            Value::String(s) => !s.is_empty(),
+            Value::Namespace(_) | Value::Object(_) => true,
+        }
+    }
+
+    /// ECMA-262 2nd edition s. 9.1 ToPrimitive
+    ///
+    /// Objects are converted by calling `valueOf` and `toString`, in an order
+    /// determined by `hint`.
+    pub fn coerce_to_primitive(
+        &self,
+        hint: Hint,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Value<'gc>, Error<'gc>> {
+        let object = match self {
+            Value::Object(object) => *object,
+            _ => return Ok(self.clone()),
+        };
+
+        let methods = match hint {
+            Hint::Number => ["valueOf", "toString"],
+            Hint::String => ["toString", "valueOf"],
+        };
+
+        for method in methods.iter() {
+            let function = object.get_property(object, &QName::dynamic_name(method), context)?;
+            if let Value::Object(function) = function {
+                let result = function.call(Some(object), &[], context)?;
+                if result.is_primitive() {
+                    return Ok(result);
+                }
+            }
+        }
+
+        Err(Error::ThrownValue(
+            "TypeError: Cannot convert object to primitive".into(),
+        ))
+    }
+
+    /// ECMA-262 2nd edition s. 9.3 ToNumber
+    pub fn coerce_to_number(
+        &self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<f64, Error<'gc>> {
+        Ok(match self {
+            Value::Undefined => NAN,
+            Value::Null => 0.0,
+            Value::Bool(true) => 1.0,
+            Value::Bool(false) => 0.0,
+            Value::Number(n) => *n,
+            Value::String(s) => string_to_f64(s),
+            Value::Namespace(_) => NAN,
+            Value::Object(_) => self
+                .coerce_to_primitive(Hint::Number, context)?
+                .coerce_to_number(context)?,
+        })
+    }
+
+    /// ECMA-262 2nd edition s. 9.5 ToInt32
+    pub fn coerce_to_i32(
+        &self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<i32, Error<'gc>> {
+        Ok(f64_to_wrapping_i32(self.coerce_to_number(context)?))
+    }
+
+    /// ECMA-262 2nd edition s. 9.6 ToUint32
+    pub fn coerce_to_u32(
+        &self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<u32, Error<'gc>> {
+        Ok(f64_to_wrapping_u32(self.coerce_to_number(context)?))
+    }
+
+    /// ECMA-262 2nd edition s. 9.8 ToString
+    pub fn coerce_to_string(
+        &self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<String, Error<'gc>> {
+        Ok(match self {
+            Value::Object(_) => self
+                .coerce_to_primitive(Hint::String, context)?
+                .coerce_to_string(context)?,
+            _ => self.clone().into_name_string(),
+        })
+    }
+
+    /// ECMA-262 2nd edition s. 9.9 ToObject
+    ///
+    /// Primitive values are boxed into an object whose prototype is the
+    /// matching builtin class prototype.
+    pub fn coerce_to_object(
+        &self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Object<'gc>, Error<'gc>> {
+        let protos = context.avm2.prototypes();
+        let proto = match self {
+            Value::Undefined | Value::Null => {
+                return Err(Error::ThrownValue(
+                    "TypeError: Cannot convert null or undefined to an object".into(),
+                ))
+            }
+            Value::Object(object) => return Ok(*object),
+            Value::Bool(_) => protos.boolean,
+            Value::Number(_) => protos.number,
+            Value::String(_) => protos.string,
+            Value::Namespace(_) => protos.object,
+        };
+
+        Ok(PrimitiveObject::from_primitive(self.clone(), proto, context.gc_context).into())
+    }
+
+    /// Determine if two values are abstractly equal to each other.
+    ///
+    /// This abstract equality algorithm is intended to match ECMA-262 3rd
+    /// edition, section 11.9.3.
+    pub fn abstract_eq(
+        &self,
+        other: &Value<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<bool, Error<'gc>> {
+        match (self, other) {
+            (Value::Undefined, Value::Null) | (Value::Null, Value::Undefined) => Ok(true),
+            (Value::Number(_), Value::String(_)) | (Value::String(_), Value::Number(_)) => {
+                Ok(self.coerce_to_number(context)? == other.coerce_to_number(context)?)
+            }
+            (Value::Bool(_), _) => {
+                Value::Number(self.coerce_to_number(context)?).abstract_eq(other, context)
+            }
+            (_, Value::Bool(_)) => {
+                self.abstract_eq(&Value::Number(other.coerce_to_number(context)?), context)
+            }
+            (Value::Number(_), Value::Object(_)) | (Value::String(_), Value::Object(_)) => {
+                let other = other.coerce_to_primitive(Hint::Number, context)?;
+                self.abstract_eq(&other, context)
+            }
+            (Value::Object(_), Value::Number(_)) | (Value::Object(_), Value::String(_)) => {
+                let primitive = self.coerce_to_primitive(Hint::Number, context)?;
+                primitive.abstract_eq(other, context)
+            }
+            _ => Ok(self == other),
+        }
+    }
+
+    /// Determine if this value is abstractly less than another.
+    ///
+    /// This abstract relational comparison algorithm is intended to match
+    /// ECMA-262 3rd edition, section 11.8.5. It returns `None` if either value
+    /// is `NaN`.
+    pub fn abstract_lt(
+        &self,
+        other: &Value<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Result<Option<bool>, Error<'gc>> {
+        let prim_self = self.coerce_to_primitive(Hint::Number, context)?;
+        let prim_other = other.coerce_to_primitive(Hint::Number, context)?;
+
+        if let (Value::String(s), Value::String(o)) = (&prim_self, &prim_other) {
+            return Ok(Some(s < o));
+        }
+
+        let num_self = prim_self.coerce_to_number(context)?;
+        let num_other = prim_other.coerce_to_number(context)?;
+
+        if num_self.is_nan() || num_other.is_nan() {
+            return Ok(None);
+        }
+
+        Ok(Some(num_self < num_other))
+    }
+
+    /// Determine the `typeof` string of this value.
+    pub fn type_of(&self) -> &'static str {
+        match self {
+            Value::Undefined => "undefined",
+            Value::Null => "object",
+            Value::Bool(_) => "boolean",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Namespace(_) => "object",
+            Value::Object(object) => {
+                if object.as_executable().is_some() {
+                    "function"
+                } else {
+                    match object.value_of() {
+                        Value::Bool(_) => "boolean",
+                        Value::Number(_) => "number",
+                        Value::String(_) => "string",
+                        _ => "object",
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// ECMA-262 2nd edition s. 9.3.1 ToNumber applied to the String type.
+fn string_to_f64(s: &str) -> f64 {
+    let trimmed = s.trim();
+    if trimmed.is_empty() {
+        return 0.0;
+    }
+
+    if let Some(hex) = trimmed
+        .strip_prefix("0x")
+        .or_else(|| trimmed.strip_prefix("0X"))
+    {
+        return i64::from_str_radix(hex, 16)
+            .map(|n| n as f64)
+            .unwrap_or(NAN);
+    }
+
+    match trimmed {
+        "Infinity" | "+Infinity" => std::f64::INFINITY,
+        "-Infinity" => std::f64::NEG_INFINITY,
+        _ => trimmed.parse().unwrap_or(NAN),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::avm2::error::Error;
+    use crate::avm2::object::TObject;
+    use crate::avm2::test_utils::with_avm2;
+    use crate::avm2::Value;
+
+    #[test]
+    fn coerce_to_number() {
+        with_avm2(|context| -> Result<(), Error> {
+            assert!(Value::Undefined.coerce_to_number(context)?.is_nan());
+            assert_eq!(Value::Null.coerce_to_number(context)?, 0.0);
+            assert_eq!(Value::from(true).coerce_to_number(context)?, 1.0);
+            assert_eq!(Value::from(" 12.5 ").coerce_to_number(context)?, 12.5);
+            assert_eq!(Value::from("").coerce_to_number(context)?, 0.0);
+            assert_eq!(Value::from("0x1F").coerce_to_number(context)?, 31.0);
+            assert_eq!(
+                Value::from("-Infinity").coerce_to_number(context)?,
+                std::f64::NEG_INFINITY
+            );
+            assert!(Value::from("12px").coerce_to_number(context)?.is_nan());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn coerce_to_string_and_boolean() {
+        with_avm2(|context| -> Result<(), Error> {
+            assert_eq!(Value::Undefined.coerce_to_string(context)?, "undefined");
+            assert_eq!(Value::Null.coerce_to_string(context)?, "null");
+            assert_eq!(Value::from(1.5).coerce_to_string(context)?, "1.5");
+            assert_eq!(Value::from(false).coerce_to_string(context)?, "false");
+
+            assert!(!Value::from("").coerce_to_boolean());
+            assert!(Value::from("0").coerce_to_boolean());
+            assert!(!Value::from(std::f64::NAN).coerce_to_boolean());
+            assert!(!Value::Null.coerce_to_boolean());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn coerce_to_object() {
+        with_avm2(|context| -> Result<(), Error> {
+            let boxed = Value::from(5).coerce_to_object(context)?;
+            assert_eq!(boxed.value_of(), 5.into());
+            assert_eq!(Value::from(boxed).coerce_to_number(context)?, 5.0);
+            assert!(Value::Undefined.coerce_to_object(context).is_err());
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn abstract_eq_and_lt() {
+        with_avm2(|context| -> Result<(), Error> {
+            assert!(Value::Null.abstract_eq(&Value::Undefined, context)?);
+            assert!(Value::from("1").abstract_eq(&1.into(), context)?);
+            assert!(Value::from(true).abstract_eq(&"1".into(), context)?);
+            assert!(!Value::Null.abstract_eq(&0.into(), context)?);
+            assert!(!Value::from(std::f64::NAN).abstract_eq(&std::f64::NAN.into(), context)?);
+
+            assert_eq!(Value::from(1).abstract_lt(&2.into(), context)?, Some(true));
+            assert_eq!(
+                Value::from("b").abstract_lt(&"a".into(), context)?,
+                Some(false)
+            );
+            assert_eq!(
+                Value::from("10").abstract_lt(&9.into(), context)?,
+                Some(false)
+            );
+            assert_eq!(Value::Undefined.abstract_lt(&1.into(), context)?, None);
+            Ok(())
+        });
+    }
+}
@@ -1,5 +1,8 @@
 pub mod audio;
 pub mod input;
+pub mod local_connection;
 pub mod navigator;
 pub mod render;
+pub mod socket;
 pub mod storage;
+pub mod video;
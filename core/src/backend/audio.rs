@@ -1,6 +1,9 @@
+use crate::display_object::{DisplayObject, TDisplayObject};
+use gc_arena::{Collect, CollectionContext};
 use generational_arena::{Arena, Index};
 
 pub mod decoders;
+pub mod mp3_file;
 pub mod swf {
     pub use swf::{
         read, AudioCompression, CharacterId, Sound, SoundEnvelope, SoundEnvelopePoint, SoundEvent,
@@ -69,10 +72,29 @@ pub trait AudioBackend {
     /// which only plays a sound if that sound is not already playing.
     fn is_sound_playing_with_handle(&mut self, handle: SoundHandle) -> bool;
 
+    /// Returns whether a sound instance or stream is still playing.
+    fn is_sound_playing(&mut self, instance: SoundInstanceHandle) -> bool;
+
     /// Get the duration of a sound in milliseconds.
     /// Returns `None` if sound is not registered.
     fn get_sound_duration(&self, sound: SoundHandle) -> Option<u32>;
 
+    /// Get the current playback position of a sound instance in milliseconds.
+    /// Returns `None` if the sound instance is not playing.
+    fn get_sound_position(&self, _instance: SoundInstanceHandle) -> Option<u32> {
+        None
+    }
+
+    /// Sets the volume and channel mixing of a sound instance or stream.
+    /// Used by the AVM1 `Sound` object's `setVolume`, `setPan` and `setTransform`.
+    fn set_sound_transform(&mut self, _instance: SoundInstanceHandle, _transform: SoundTransform) {}
+
+    /// Registers a complete MP3 file, such as one loaded by `Sound.loadSound`.
+    fn register_mp3(&mut self, data: &[u8]) -> Result<SoundHandle, Error> {
+        let sound = mp3_file::sound_from_mp3(data)?;
+        self.register_sound(&sound)
+    }
+
     // TODO: Eventually remove this/move it to library.
     fn is_loading_complete(&self) -> bool {
         true
@@ -135,6 +157,9 @@ impl AudioBackend for NullAudioBackend {
     fn is_sound_playing_with_handle(&mut self, _handle: SoundHandle) -> bool {
         false
     }
+    fn is_sound_playing(&mut self, _instance: SoundInstanceHandle) -> bool {
+        false
+    }
 
     fn get_sound_duration(&self, _sound: SoundHandle) -> Option<u32> {
         None
@@ -146,3 +171,420 @@ impl Default for NullAudioBackend {
         NullAudioBackend::new()
     }
 }
+
+/// The volume and channel mixing applied to a sound.
+///
+/// All values are percentages, matching the AVM1 `Sound` object. The `*_to_*` values form a
+/// matrix mixing the left and right input channels into the left and right output channels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SoundTransform {
+    pub volume: i32,
+    pub left_to_left: i32,
+    pub left_to_right: i32,
+    pub right_to_left: i32,
+    pub right_to_right: i32,
+}
+
+impl SoundTransform {
+    pub const MAX_VOLUME: i32 = 100;
+
+    /// Applies another transform on top of this one, such as the transform of a parent clip.
+    pub fn concat(&mut self, other: &SoundTransform) {
+        // Integer math, as Flash truncates each step.
+        const MAX_VOLUME: i64 = SoundTransform::MAX_VOLUME as i64;
+        let (ll0, lr0, rl0, rr0) = (
+            i64::from(self.left_to_left),
+            i64::from(self.left_to_right),
+            i64::from(self.right_to_left),
+            i64::from(self.right_to_right),
+        );
+        let (ll1, lr1, rl1, rr1) = (
+            i64::from(other.left_to_left),
+            i64::from(other.left_to_right),
+            i64::from(other.right_to_left),
+            i64::from(other.right_to_right),
+        );
+        self.volume = (i64::from(self.volume) * i64::from(other.volume) / MAX_VOLUME) as i32;
+        self.left_to_left = ((ll0 * ll1 + lr0 * rl1) / MAX_VOLUME) as i32;
+        self.left_to_right = ((ll0 * lr1 + lr0 * rr1) / MAX_VOLUME) as i32;
+        self.right_to_left = ((rl0 * ll1 + rr0 * rl1) / MAX_VOLUME) as i32;
+        self.right_to_right = ((rl0 * lr1 + rr0 * rr1) / MAX_VOLUME) as i32;
+    }
+
+    /// The balance between the left and right channels, from -100 (left) to 100 (right).
+    pub fn pan(&self) -> i32 {
+        if self.left_to_left != Self::MAX_VOLUME {
+            Self::MAX_VOLUME - self.left_to_left.abs()
+        } else {
+            self.right_to_right.abs() - Self::MAX_VOLUME
+        }
+    }
+
+    /// Sets the balance between the left and right channels by attenuating the other channel.
+    pub fn set_pan(&mut self, pan: i32) {
+        if pan >= 0 {
+            self.left_to_left = Self::MAX_VOLUME - pan;
+            self.right_to_right = Self::MAX_VOLUME;
+        } else {
+            self.left_to_left = Self::MAX_VOLUME;
+            self.right_to_right = Self::MAX_VOLUME + pan;
+        }
+        self.left_to_right = 0;
+        self.right_to_left = 0;
+    }
+
+    /// The gains to mix a stereo sample frame with, including the volume, in the order
+    /// `[left_to_left, left_to_right, right_to_left, right_to_right]`.
+    pub fn mix_gains(&self) -> [f32; 4] {
+        let volume = self.volume as f32 / Self::MAX_VOLUME as f32;
+        let gain = |value: i32| value as f32 / Self::MAX_VOLUME as f32 * volume;
+        [
+            gain(self.left_to_left),
+            gain(self.left_to_right),
+            gain(self.right_to_left),
+            gain(self.right_to_right),
+        ]
+    }
+}
+
+impl Default for SoundTransform {
+    fn default() -> Self {
+        Self {
+            volume: Self::MAX_VOLUME,
+            left_to_left: Self::MAX_VOLUME,
+            left_to_right: 0,
+            right_to_left: 0,
+            right_to_right: Self::MAX_VOLUME,
+        }
+    }
+}
+
+/// Keeps track of the sounds started by display objects, so that the sound transforms of the
+/// display objects and their parents apply to them.
+pub struct AudioManager<'gc> {
+    /// The sounds that were playing as of the last update.
+    sounds: Vec<ActiveSound<'gc>>,
+
+    /// The transform applied to every sound, set by a `Sound` object without a target clip.
+    global_sound_transform: SoundTransform,
+}
+
+/// A sound instance or stream, and the display object that started it.
+struct ActiveSound<'gc> {
+    instance: SoundInstanceHandle,
+    display_object: Option<DisplayObject<'gc>>,
+
+    /// The transform last sent to the audio backend.
+    transform: SoundTransform,
+}
+
+unsafe impl<'gc> Collect for AudioManager<'gc> {
+    fn trace(&self, cc: CollectionContext) {
+        for sound in &self.sounds {
+            sound.display_object.trace(cc);
+        }
+    }
+}
+
+impl<'gc> AudioManager<'gc> {
+    pub fn new() -> Self {
+        Self {
+            sounds: vec![],
+            global_sound_transform: Default::default(),
+        }
+    }
+
+    /// Starts an event sound on behalf of a display object.
+    pub fn start_sound(
+        &mut self,
+        audio: &mut dyn AudioBackend,
+        sound: SoundHandle,
+        settings: &swf::SoundInfo,
+        display_object: Option<DisplayObject<'gc>>,
+    ) -> Result<SoundInstanceHandle, Error> {
+        let instance = audio.start_sound(sound, settings)?;
+        self.add_sound(audio, instance, display_object);
+        Ok(instance)
+    }
+
+    /// Performs a timeline sound event on behalf of a display object, honoring the sync
+    /// setting of the sound.
+    pub fn perform_sound_event(
+        &mut self,
+        audio: &mut dyn AudioBackend,
+        sound: SoundHandle,
+        settings: &swf::SoundInfo,
+        display_object: Option<DisplayObject<'gc>>,
+    ) {
+        use swf::SoundEvent;
+        // The sound event type is controlled by the "Sync" setting in the Flash IDE.
+        match settings.event {
+            // "Event" sounds always play, independent of the timeline.
+            SoundEvent::Event => {
+                let _ = self.start_sound(audio, sound, settings, display_object);
+            }
+
+            // "Start" sounds only play if an instance of the same sound is not already playing.
+            SoundEvent::Start => {
+                if !audio.is_sound_playing_with_handle(sound) {
+                    let _ = self.start_sound(audio, sound, settings, display_object);
+                }
+            }
+
+            // "Stop" stops any active instances of a given sound.
+            SoundEvent::Stop => audio.stop_sounds_with_handle(sound),
+        }
+    }
+
+    /// Starts the stream sound of a movie clip.
+    pub fn start_stream(
+        &mut self,
+        audio: &mut dyn AudioBackend,
+        clip_frame: u16,
+        clip_data: crate::tag_utils::SwfSlice,
+        stream_info: &swf::SoundStreamHead,
+        clip: DisplayObject<'gc>,
+    ) -> Result<AudioStreamHandle, Error> {
+        let stream = audio.start_stream(clip.id(), clip_frame, clip_data, stream_info)?;
+        self.add_sound(audio, stream, Some(clip));
+        Ok(stream)
+    }
+
+    fn add_sound(
+        &mut self,
+        audio: &mut dyn AudioBackend,
+        instance: SoundInstanceHandle,
+        display_object: Option<DisplayObject<'gc>>,
+    ) {
+        let transform = self.transform_for_display_object(display_object);
+        if transform != SoundTransform::default() {
+            audio.set_sound_transform(instance, transform);
+        }
+        self.sounds.push(ActiveSound {
+            instance,
+            display_object,
+            transform,
+        });
+    }
+
+    /// Stops every sound started by the given display object or its children.
+    pub fn stop_sounds_with_display_object(
+        &mut self,
+        audio: &mut dyn AudioBackend,
+        display_object: DisplayObject<'gc>,
+    ) {
+        self.sounds.retain(|sound| {
+            let mut parent = sound.display_object;
+            while let Some(object) = parent {
+                if DisplayObject::ptr_eq(object, display_object) {
+                    audio.stop_sound(sound.instance);
+                    return false;
+                }
+                parent = object.parent();
+            }
+            true
+        });
+    }
+
+    pub fn global_sound_transform(&self) -> SoundTransform {
+        self.global_sound_transform
+    }
+
+    pub fn set_global_sound_transform(&mut self, transform: SoundTransform) {
+        self.global_sound_transform = transform;
+    }
+
+    /// Forgets sounds that have finished, and sends the current sound transforms of the
+    /// remaining sounds to the audio backend.
+    pub fn update_sound_transforms(&mut self, audio: &mut dyn AudioBackend) {
+        let mut sounds = std::mem::take(&mut self.sounds);
+        sounds.retain(|sound| audio.is_sound_playing(sound.instance));
+        for sound in &mut sounds {
+            let transform = self.transform_for_display_object(sound.display_object);
+            if transform != sound.transform {
+                audio.set_sound_transform(sound.instance, transform);
+                sound.transform = transform;
+            }
+        }
+        self.sounds = sounds;
+    }
+
+    /// The combined transform of a display object, its parents, and the global transform.
+    fn transform_for_display_object(
+        &self,
+        display_object: Option<DisplayObject<'gc>>,
+    ) -> SoundTransform {
+        let mut transform = SoundTransform::default();
+        let mut parent = display_object;
+        while let Some(object) = parent {
+            transform.concat(&object.sound_transform());
+            parent = object.parent();
+        }
+        transform.concat(&self.global_sound_transform);
+        transform
+    }
+}
+
+impl<'gc> Default for AudioManager<'gc> {
+    fn default() -> Self {
+        AudioManager::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An audio backend that keeps track of the sounds that are playing.
+    #[derive(Default)]
+    struct TestAudioBackend {
+        sounds: Arena<()>,
+
+        /// The playing sound instances, with the sound and settings they were started with.
+        instances: Arena<(SoundHandle, swf::SoundInfo)>,
+    }
+
+    impl TestAudioBackend {
+        fn playing(&self, sound: SoundHandle) -> Vec<&swf::SoundInfo> {
+            self.instances
+                .iter()
+                .filter(|(_, (handle, _))| *handle == sound)
+                .map(|(_, (_, settings))| settings)
+                .collect()
+        }
+    }
+
+    impl AudioBackend for TestAudioBackend {
+        fn register_sound(&mut self, _sound: &swf::Sound) -> Result<SoundHandle, Error> {
+            Ok(self.sounds.insert(()))
+        }
+
+        fn start_sound(
+            &mut self,
+            sound: SoundHandle,
+            settings: &swf::SoundInfo,
+        ) -> Result<SoundInstanceHandle, Error> {
+            Ok(self.instances.insert((sound, settings.clone())))
+        }
+
+        fn start_stream(
+            &mut self,
+            _clip_id: crate::prelude::CharacterId,
+            _stream_start_frame: u16,
+            _clip_data: crate::tag_utils::SwfSlice,
+            _handle: &swf::SoundStreamHead,
+        ) -> Result<AudioStreamHandle, Error> {
+            Err("Streams are not supported".into())
+        }
+
+        fn stop_sound(&mut self, sound: SoundInstanceHandle) {
+            self.instances.remove(sound);
+        }
+
+        fn stop_stream(&mut self, _stream: AudioStreamHandle) {}
+
+        fn stop_all_sounds(&mut self) {
+            self.instances.clear();
+        }
+
+        fn stop_sounds_with_handle(&mut self, sound: SoundHandle) {
+            self.instances.retain(|_, (handle, _)| *handle != sound);
+        }
+
+        fn is_sound_playing_with_handle(&mut self, sound: SoundHandle) -> bool {
+            !self.playing(sound).is_empty()
+        }
+
+        fn is_sound_playing(&mut self, instance: SoundInstanceHandle) -> bool {
+            self.instances.contains(instance)
+        }
+
+        fn get_sound_duration(&self, _sound: SoundHandle) -> Option<u32> {
+            None
+        }
+    }
+
+    fn sound_info(event: swf::SoundEvent) -> swf::SoundInfo {
+        swf::SoundInfo {
+            event,
+            in_sample: None,
+            out_sample: None,
+            num_loops: 1,
+            envelope: None,
+        }
+    }
+
+    fn register_sound(audio: &mut TestAudioBackend) -> SoundHandle {
+        audio
+            .register_sound(&swf::Sound {
+                id: 1,
+                format: swf::SoundFormat {
+                    compression: swf::AudioCompression::Uncompressed,
+                    sample_rate: 44100,
+                    is_stereo: false,
+                    is_16_bit: true,
+                },
+                num_samples: 0,
+                data: vec![],
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn sound_events() {
+        let mut audio = TestAudioBackend::default();
+        let mut manager = AudioManager::new();
+        let sound = register_sound(&mut audio);
+        let other_sound = register_sound(&mut audio);
+
+        // "Event" sounds start a new instance every time.
+        let event = sound_info(swf::SoundEvent::Event);
+        manager.perform_sound_event(&mut audio, sound, &event, None);
+        manager.perform_sound_event(&mut audio, sound, &event, None);
+        assert_eq!(audio.playing(sound).len(), 2);
+
+        // "Start" sounds don't start while the sound is already playing.
+        let start = sound_info(swf::SoundEvent::Start);
+        manager.perform_sound_event(&mut audio, sound, &start, None);
+        assert_eq!(audio.playing(sound).len(), 2);
+        manager.perform_sound_event(&mut audio, other_sound, &start, None);
+        manager.perform_sound_event(&mut audio, other_sound, &start, None);
+        assert_eq!(audio.playing(other_sound).len(), 1);
+
+        // "Stop" stops every instance of the sound, and only of that sound.
+        manager.perform_sound_event(&mut audio, sound, &sound_info(swf::SoundEvent::Stop), None);
+        assert!(audio.playing(sound).is_empty());
+        assert_eq!(audio.playing(other_sound).len(), 1);
+
+        // Once stopped, "Start" sounds play again.
+        manager.perform_sound_event(&mut audio, sound, &start, None);
+        assert_eq!(audio.playing(sound).len(), 1);
+
+        // Finished sounds are forgotten by the manager.
+        audio.stop_all_sounds();
+        manager.update_sound_transforms(&mut audio);
+        assert!(manager.sounds.is_empty());
+    }
+
+    #[test]
+    fn sound_event_settings() {
+        let mut audio = TestAudioBackend::default();
+        let mut manager = AudioManager::new();
+        let sound = register_sound(&mut audio);
+
+        // The loop count, in and out points and envelope are passed on to the backend.
+        let settings = swf::SoundInfo {
+            event: swf::SoundEvent::Start,
+            in_sample: Some(4410),
+            out_sample: Some(22050),
+            num_loops: 3,
+            envelope: Some(vec![swf::SoundEnvelopePoint {
+                sample: 0,
+                left_volume: 0.5,
+                right_volume: 1.0,
+            }]),
+        };
+        manager.perform_sound_event(&mut audio, sound, &settings, None);
+        assert_eq!(audio.playing(sound), [&settings]);
+    }
+}
@@ -0,0 +1,368 @@
+//! Helpers for standalone MP3 files, as loaded by `Sound.loadSound`.
+//!
+//! Sounds embedded in a SWF come with their format in the `DefineSound` tag. Loaded MP3 files
+//! only have their frame headers, so we scan the frames to build an equivalent `swf::Sound`.
+//! The ID3 tags of the file are exposed to ActionScript through `Sound.id3`.
+
+use std::collections::BTreeMap;
+use swf::{AudioCompression, Sound, SoundFormat};
+
+type Error = Box<dyn std::error::Error>;
+
+/// Builds a sound from the contents of an MP3 file, with any ID3 tags removed.
+pub fn sound_from_mp3(data: &[u8]) -> Result<Sound, Error> {
+    let data = strip_id3(data);
+
+    let mut pos = 0;
+    let mut format = None;
+    let mut num_samples = 0u32;
+    while pos + 4 <= data.len() {
+        match FrameHeader::parse(&data[pos..pos + 4]) {
+            Some(header) => {
+                if format.is_none() {
+                    format = Some(SoundFormat {
+                        compression: AudioCompression::Mp3,
+                        sample_rate: header.sample_rate,
+                        is_stereo: header.is_stereo,
+                        is_16_bit: true,
+                    });
+                }
+                num_samples = num_samples.saturating_add(header.samples_per_frame);
+                pos += header.frame_len.max(4);
+            }
+            // Skip garbage between frames until the next frame sync.
+            None => pos += 1,
+        }
+    }
+    let format = format.ok_or("No MP3 frames found")?;
+
+    // Sounds registered with the audio backends start with the MP3 latency seek.
+    let mut sound_data = Vec::with_capacity(data.len() + 2);
+    sound_data.extend_from_slice(&[0, 0]);
+    sound_data.extend_from_slice(data);
+    Ok(Sound {
+        id: 0,
+        format,
+        num_samples,
+        data: sound_data,
+    })
+}
+
+/// The fields of an MP3 frame header needed to describe the sound.
+struct FrameHeader {
+    sample_rate: u16,
+    is_stereo: bool,
+    samples_per_frame: u32,
+    frame_len: usize,
+}
+
+impl FrameHeader {
+    fn parse(header: &[u8]) -> Option<Self> {
+        // Frame sync
+        if header[0] != 0xFF || header[1] & 0xE0 != 0xE0 {
+            return None;
+        }
+        // 0 = MPEG 2.5, 2 = MPEG 2, 3 = MPEG 1
+        let version = (header[1] >> 3) & 0b11;
+        let layer = (header[1] >> 1) & 0b11;
+        let bitrate_index = usize::from(header[2] >> 4);
+        let sample_rate_index = usize::from((header[2] >> 2) & 0b11);
+        let padding = usize::from((header[2] >> 1) & 0b1);
+        let channel_mode = header[3] >> 6;
+        // Only layer III is supported by Flash.
+        if version == 1 || layer != 1 || bitrate_index == 0 || bitrate_index == 15 {
+            return None;
+        }
+
+        const MPEG1_BITRATES: [u32; 15] = [
+            0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+        ];
+        const MPEG2_BITRATES: [u32; 15] =
+            [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+        const SAMPLE_RATES: [u16; 3] = [44100, 48000, 32000];
+
+        let sample_rate = *SAMPLE_RATES.get(sample_rate_index)?;
+        let (bitrate, sample_rate, samples_per_frame) = match version {
+            3 => (MPEG1_BITRATES[bitrate_index], sample_rate, 1152),
+            2 => (MPEG2_BITRATES[bitrate_index], sample_rate / 2, 576),
+            _ => (MPEG2_BITRATES[bitrate_index], sample_rate / 4, 576),
+        };
+        let frame_len =
+            (samples_per_frame / 8 * bitrate * 1000 / u32::from(sample_rate)) as usize + padding;
+        Some(Self {
+            sample_rate,
+            is_stereo: channel_mode != 0b11,
+            samples_per_frame,
+            frame_len,
+        })
+    }
+}
+
+/// Returns the audio data of an MP3 file without its ID3v2 header and ID3v1 footer.
+fn strip_id3(mut data: &[u8]) -> &[u8] {
+    if let Some(len) = id3v2_len(data) {
+        data = &data[len.min(data.len())..];
+    }
+    if data.len() >= 128 && &data[data.len() - 128..data.len() - 125] == b"TAG" {
+        data = &data[..data.len() - 128];
+    }
+    data
+}
+
+/// The total length of the ID3v2 tag at the start of the data, if there is one.
+fn id3v2_len(data: &[u8]) -> Option<usize> {
+    if data.len() < 10 || &data[0..3] != b"ID3" {
+        return None;
+    }
+    let has_footer = data[5] & 0x10 != 0;
+    Some(10 + synchsafe(&data[6..10]) + if has_footer { 10 } else { 0 })
+}
+
+fn synchsafe(bytes: &[u8]) -> usize {
+    bytes
+        .iter()
+        .fold(0, |value, &byte| (value << 7) | usize::from(byte & 0x7F))
+}
+
+/// The ID3 metadata of an MP3 file, as properties of the `Sound.id3` object.
+///
+/// Text frames are available under their frame ID (such as `TIT2`), and the common ones also
+/// under the names Flash used for ID3v1 fields (such as `songname`).
+pub fn read_id3(data: &[u8]) -> BTreeMap<String, String> {
+    let mut tags = BTreeMap::new();
+
+    // ID3v1 comes first so that the richer ID3v2 tags take precedence.
+    if data.len() >= 128 {
+        let tag = &data[data.len() - 128..];
+        if &tag[0..3] == b"TAG" {
+            let fields = [
+                ("songname", &tag[3..33]),
+                ("artist", &tag[33..63]),
+                ("album", &tag[63..93]),
+                ("year", &tag[93..97]),
+                ("comment", &tag[97..127]),
+            ];
+            for (name, value) in fields.iter() {
+                let value = latin1_to_string(value);
+                let value = value.trim_end_matches('\0').trim_end();
+                if !value.is_empty() {
+                    tags.insert(name.to_string(), value.to_string());
+                }
+            }
+            // ID3v1.1 stores the track number in the last byte of the comment.
+            if tag[125] == 0 && tag[126] != 0 {
+                tags.insert("track".to_string(), tag[126].to_string());
+            }
+            if tag[127] != 0xFF {
+                tags.insert("genre".to_string(), tag[127].to_string());
+            }
+        }
+    }
+
+    if let Some(len) = id3v2_len(data) {
+        let major_version = data[3];
+        let flags = data[5];
+        let end = len.min(data.len());
+        let mut pos = 10;
+        // Skip the extended header.
+        if flags & 0x40 != 0 && pos + 4 <= end {
+            let ext_len = if major_version >= 4 {
+                synchsafe(&data[pos..pos + 4])
+            } else {
+                synchsafe_or_plain(&data[pos..pos + 4], false) + 4
+            };
+            pos += ext_len;
+        }
+
+        while pos + 10 <= end {
+            let id = &data[pos..pos + 4];
+            if id[0] == 0 {
+                // Padding
+                break;
+            }
+            let size = synchsafe_or_plain(&data[pos + 4..pos + 8], major_version >= 4);
+            let start = pos + 10;
+            let frame_end = match start.checked_add(size) {
+                Some(frame_end) if frame_end <= end => frame_end,
+                _ => break,
+            };
+            let frame = &data[start..frame_end];
+            let id = String::from_utf8_lossy(id).into_owned();
+            let value = if id == "COMM" && frame.len() > 4 {
+                // Encoding, language, description and then the comment itself.
+                let encoding = frame[0];
+                let text = decode_id3_text(encoding, &frame[4..]);
+                text.split_once('\0')
+                    .map(|(_, comment)| comment.to_string())
+            } else if id.starts_with('T') && !frame.is_empty() {
+                Some(decode_id3_text(frame[0], &frame[1..]))
+            } else {
+                None
+            };
+            if let Some(value) = value {
+                let value = value.trim_end_matches('\0').to_string();
+                if let Some(name) = id3v1_name(&id) {
+                    tags.insert(name.to_string(), value.clone());
+                }
+                tags.insert(id, value);
+            }
+            pos = frame_end;
+        }
+    }
+
+    tags
+}
+
+/// The ID3v1 field name Flash exposes for an ID3v2 frame.
+fn id3v1_name(frame_id: &str) -> Option<&'static str> {
+    Some(match frame_id {
+        "TIT2" => "songname",
+        "TPE1" => "artist",
+        "TALB" => "album",
+        "TYER" | "TDRC" => "year",
+        "COMM" => "comment",
+        "TCON" => "genre",
+        "TRCK" => "track",
+        _ => return None,
+    })
+}
+
+/// ID3v2.4 frame sizes are synchsafe integers, while ID3v2.3 sizes are plain.
+fn synchsafe_or_plain(bytes: &[u8], is_synchsafe: bool) -> usize {
+    if is_synchsafe {
+        synchsafe(bytes)
+    } else {
+        bytes
+            .iter()
+            .fold(0, |value, &byte| (value << 8) | usize::from(byte))
+    }
+}
+
+fn latin1_to_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|&byte| char::from(byte)).collect()
+}
+
+fn decode_id3_text(encoding: u8, bytes: &[u8]) -> String {
+    match encoding {
+        // UTF-16 with a byte order mark
+        1 => {
+            let (bytes, big_endian) = match bytes {
+                [0xFE, 0xFF, rest @ ..] => (rest, true),
+                [0xFF, 0xFE, rest @ ..] => (rest, false),
+                _ => (bytes, false),
+            };
+            utf16_to_string(bytes, big_endian)
+        }
+        // UTF-16BE
+        2 => utf16_to_string(bytes, true),
+        // UTF-8
+        3 => String::from_utf8_lossy(bytes).into_owned(),
+        // ISO-8859-1
+        _ => latin1_to_string(bytes),
+    }
+}
+
+fn utf16_to_string(bytes: &[u8], big_endian: bool) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| {
+            if big_endian {
+                u16::from_be_bytes([pair[0], pair[1]])
+            } else {
+                u16::from_le_bytes([pair[0], pair[1]])
+            }
+        })
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A silent MPEG-1 layer III frame: 128kbps, 44.1kHz, joint stereo.
+    fn silent_frame() -> Vec<u8> {
+        let mut frame = vec![0xFF, 0xFB, 0x90, 0x44];
+        frame.resize(417, 0);
+        frame
+    }
+
+    fn id3v2_tag(frames: &[(&[u8; 4], &[u8])]) -> Vec<u8> {
+        let mut body = vec![];
+        for (id, content) in frames {
+            body.extend_from_slice(&id[..]);
+            body.extend_from_slice(&(content.len() as u32).to_be_bytes());
+            body.extend_from_slice(&[0, 0]);
+            body.extend_from_slice(content);
+        }
+        let len = body.len();
+        let mut tag = b"ID3\x03\x00\x00".to_vec();
+        tag.extend_from_slice(&[
+            (len >> 21) as u8 & 0x7F,
+            (len >> 14) as u8 & 0x7F,
+            (len >> 7) as u8 & 0x7F,
+            len as u8 & 0x7F,
+        ]);
+        tag.extend(body);
+        tag
+    }
+
+    #[test]
+    fn scan_mp3_frames() {
+        let mut data = id3v2_tag(&[(b"TIT2", b"\x00Title")]);
+        for _ in 0..10 {
+            data.extend(silent_frame());
+        }
+        let sound = sound_from_mp3(&data).unwrap();
+        assert_eq!(sound.format.compression, AudioCompression::Mp3);
+        assert_eq!(sound.format.sample_rate, 44100);
+        assert!(sound.format.is_stereo);
+        assert_eq!(sound.num_samples, 11520);
+        // The ID3 tag is removed, and the latency seek is added.
+        assert_eq!(&sound.data[..6], &[0, 0, 0xFF, 0xFB, 0x90, 0x44]);
+    }
+
+    #[test]
+    fn no_mp3_frames() {
+        assert!(sound_from_mp3(b"not an mp3 file").is_err());
+    }
+
+    #[test]
+    fn read_id3v2_tags() {
+        let data = id3v2_tag(&[
+            (b"TIT2", b"\x00Title"),
+            (b"TPE1", b"\x01\xFF\xFEA\x00r\x00t\x00"),
+            (b"COMM", b"\x00engdesc\x00A comment"),
+        ]);
+        let tags = read_id3(&data);
+        assert_eq!(tags.get("songname").map(String::as_str), Some("Title"));
+        assert_eq!(tags.get("TIT2").map(String::as_str), Some("Title"));
+        assert_eq!(tags.get("artist").map(String::as_str), Some("Art"));
+        assert_eq!(tags.get("comment").map(String::as_str), Some("A comment"));
+    }
+
+    #[test]
+    fn read_id3v1_tags() {
+        let mut data = silent_frame();
+        let mut tag = b"TAG".to_vec();
+        let mut field = |value: &[u8], len: usize| {
+            let mut value = value.to_vec();
+            value.resize(len, 0);
+            tag.extend(value);
+        };
+        field(b"Song", 30);
+        field(b"Artist", 30);
+        field(b"Album", 30);
+        field(b"1999", 4);
+        field(b"Comment", 28);
+        tag.extend_from_slice(&[0, 7, 12]);
+        data.extend(tag);
+
+        let tags = read_id3(&data);
+        assert_eq!(tags.get("songname").map(String::as_str), Some("Song"));
+        assert_eq!(tags.get("year").map(String::as_str), Some("1999"));
+        assert_eq!(tags.get("track").map(String::as_str), Some("7"));
+        assert_eq!(tags.get("genre").map(String::as_str), Some("12"));
+        assert_eq!(strip_id3(&data).len(), 417);
+    }
+}
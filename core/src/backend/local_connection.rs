@@ -0,0 +1,273 @@
+//! Local connection backends, used by `LocalConnection` to pass messages between movies.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use swf::amf0::types::Value as AmfValue;
+
+/// Identifies a message, both for the player that sent it and the one receiving it.
+pub type MessageId = u64;
+
+/// A method call sent to a `LocalConnection` listening in another movie.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LocalConnectionMessage {
+    pub id: MessageId,
+
+    /// The full name of the connection, including any domain prefix.
+    pub connection_name: String,
+
+    /// The method to call on the receiving `LocalConnection`.
+    pub method_name: String,
+
+    /// The superdomain of the movie that sent the message.
+    pub sender_domain: String,
+
+    /// The AMF0-encoded arguments of the call.
+    pub args: Vec<AmfValue>,
+}
+
+/// Something that happened since the last time the backend was polled.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LocalConnectionEvent {
+    /// A message arrived on a connection this player is listening on.
+    ///
+    /// The player must answer it with `LocalConnectionBackend::respond`.
+    Message(LocalConnectionMessage),
+
+    /// A message sent by this player was either handled by the receiver (`true`), or there
+    /// was nothing listening to it or the receiver refused it (`false`).
+    Status(MessageId, bool),
+}
+
+pub trait LocalConnectionBackend {
+    /// Starts listening on a connection name.
+    ///
+    /// Returns `false` if the name is already in use, by this player or another one.
+    fn connect(&mut self, name: &str) -> bool;
+
+    /// Stops listening on a connection name.
+    fn close(&mut self, name: &str);
+
+    /// Sends a message to whoever is listening on `connection_name`.
+    ///
+    /// This must not block; whether the message was handled is reported by `poll` as a
+    /// `LocalConnectionEvent::Status`.
+    fn send(
+        &mut self,
+        connection_name: &str,
+        method_name: &str,
+        sender_domain: &str,
+        args: Vec<AmfValue>,
+    ) -> MessageId;
+
+    /// Reports back to the sender of a message whether it was handled.
+    fn respond(&mut self, id: MessageId, success: bool);
+
+    /// Returns every event that happened since the last poll, in order.
+    fn poll(&mut self) -> Vec<LocalConnectionEvent>;
+}
+
+/// The connections of every player in the process that shares a registry.
+///
+/// Cloning a registry gives another handle to the same connections.
+#[derive(Clone, Default)]
+pub struct LocalConnectionRegistry(Arc<Mutex<RegistryData>>);
+
+#[derive(Default)]
+struct RegistryData {
+    next_backend_id: u64,
+    next_message_id: MessageId,
+
+    /// The backend listening on each connection name.
+    listeners: HashMap<String, u64>,
+
+    /// The events waiting to be polled by each backend.
+    queues: HashMap<u64, VecDeque<LocalConnectionEvent>>,
+
+    /// The backend that sent each message that is waiting for a response.
+    pending: HashMap<MessageId, u64>,
+}
+
+impl LocalConnectionRegistry {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Creates a backend for a player that talks to every other backend of this registry.
+    pub fn backend(&self) -> InProcessLocalConnectionBackend {
+        let mut data = self.0.lock().unwrap();
+        let id = data.next_backend_id;
+        data.next_backend_id += 1;
+        data.queues.insert(id, VecDeque::new());
+        InProcessLocalConnectionBackend {
+            registry: self.clone(),
+            id,
+        }
+    }
+}
+
+/// A local connection backend that passes messages between players in the same process.
+pub struct InProcessLocalConnectionBackend {
+    registry: LocalConnectionRegistry,
+    id: u64,
+}
+
+impl InProcessLocalConnectionBackend {
+    /// Creates a backend with a registry of its own, so it can only talk to itself.
+    pub fn new() -> Self {
+        LocalConnectionRegistry::new().backend()
+    }
+}
+
+impl Default for InProcessLocalConnectionBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocalConnectionBackend for InProcessLocalConnectionBackend {
+    fn connect(&mut self, name: &str) -> bool {
+        let mut data = self.registry.0.lock().unwrap();
+        if data.listeners.contains_key(name) {
+            return false;
+        }
+        data.listeners.insert(name.to_string(), self.id);
+        true
+    }
+
+    fn close(&mut self, name: &str) {
+        let mut data = self.registry.0.lock().unwrap();
+        if data.listeners.get(name) == Some(&self.id) {
+            data.listeners.remove(name);
+        }
+    }
+
+    fn send(
+        &mut self,
+        connection_name: &str,
+        method_name: &str,
+        sender_domain: &str,
+        args: Vec<AmfValue>,
+    ) -> MessageId {
+        let mut data = self.registry.0.lock().unwrap();
+        let id = data.next_message_id;
+        data.next_message_id += 1;
+
+        let (receiver, event) = match data.listeners.get(connection_name) {
+            Some(&receiver) => {
+                data.pending.insert(id, self.id);
+                let message = LocalConnectionMessage {
+                    id,
+                    connection_name: connection_name.to_string(),
+                    method_name: method_name.to_string(),
+                    sender_domain: sender_domain.to_string(),
+                    args,
+                };
+                (receiver, LocalConnectionEvent::Message(message))
+            }
+            None => (self.id, LocalConnectionEvent::Status(id, false)),
+        };
+        if let Some(queue) = data.queues.get_mut(&receiver) {
+            queue.push_back(event);
+        }
+        id
+    }
+
+    fn respond(&mut self, id: MessageId, success: bool) {
+        let mut data = self.registry.0.lock().unwrap();
+        if let Some(sender) = data.pending.remove(&id) {
+            if let Some(queue) = data.queues.get_mut(&sender) {
+                queue.push_back(LocalConnectionEvent::Status(id, success));
+            }
+        }
+    }
+
+    fn poll(&mut self) -> Vec<LocalConnectionEvent> {
+        let mut data = self.registry.0.lock().unwrap();
+        match data.queues.get_mut(&self.id) {
+            Some(queue) => queue.drain(..).collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl Drop for InProcessLocalConnectionBackend {
+    fn drop(&mut self) {
+        let mut data = self.registry.0.lock().unwrap();
+        let id = self.id;
+        data.listeners.retain(|_, listener| *listener != id);
+        data.pending.retain(|_, sender| *sender != id);
+
+        // Messages that will never be handled are answered on behalf of this backend.
+        let unanswered: Vec<MessageId> = data
+            .queues
+            .remove(&id)
+            .into_iter()
+            .flatten()
+            .filter_map(|event| match event {
+                LocalConnectionEvent::Message(message) => Some(message.id),
+                LocalConnectionEvent::Status(..) => None,
+            })
+            .collect();
+        for message_id in unanswered {
+            if let Some(sender) = data.pending.remove(&message_id) {
+                if let Some(queue) = data.queues.get_mut(&sender) {
+                    queue.push_back(LocalConnectionEvent::Status(message_id, false));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_between_backends() {
+        let registry = LocalConnectionRegistry::new();
+        let mut sender = registry.backend();
+        let mut receiver = registry.backend();
+
+        assert!(receiver.connect("localhost:content"));
+        assert!(!sender.connect("localhost:content"));
+
+        let args = vec![AmfValue::Number(1.0), AmfValue::String("a".to_string())];
+        let id = sender.send("localhost:content", "show", "localhost", args.clone());
+        let missing = sender.send("localhost:other", "show", "localhost", vec![]);
+        assert_eq!(
+            receiver.poll(),
+            vec![LocalConnectionEvent::Message(LocalConnectionMessage {
+                id,
+                connection_name: "localhost:content".to_string(),
+                method_name: "show".to_string(),
+                sender_domain: "localhost".to_string(),
+                args,
+            })]
+        );
+        assert_eq!(
+            sender.poll(),
+            vec![LocalConnectionEvent::Status(missing, false)]
+        );
+
+        receiver.respond(id, true);
+        assert_eq!(sender.poll(), vec![LocalConnectionEvent::Status(id, true)]);
+        assert!(receiver.poll().is_empty());
+
+        receiver.close("localhost:content");
+        assert!(sender.connect("localhost:content"));
+    }
+
+    #[test]
+    fn dropped_backends_release_names_and_messages() {
+        let registry = LocalConnectionRegistry::new();
+        let mut sender = registry.backend();
+        let mut receiver = registry.backend();
+
+        assert!(receiver.connect("_status"));
+        let id = sender.send("_status", "update", "localhost", vec![]);
+        drop(receiver);
+
+        assert_eq!(sender.poll(), vec![LocalConnectionEvent::Status(id, false)]);
+        assert!(sender.connect("_status"));
+    }
+}
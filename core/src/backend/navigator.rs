@@ -51,6 +51,9 @@ pub struct RequestOptions {
     ///
     /// The body consists of data and a mime type.
     body: Option<(Vec<u8>, String)>,
+
+    /// Additional HTTP headers to be sent with the request.
+    headers: Vec<(String, String)>,
 }
 
 impl RequestOptions {
@@ -59,6 +62,7 @@ impl RequestOptions {
         Self {
             method: NavigationMethod::GET,
             body: None,
+            headers: Vec::new(),
         }
     }
 
@@ -67,6 +71,7 @@ impl RequestOptions {
         Self {
             method: NavigationMethod::POST,
             body,
+            headers: Vec::new(),
         }
     }
 
@@ -79,6 +84,36 @@ impl RequestOptions {
     pub fn body(&self) -> &Option<(Vec<u8>, String)> {
         &self.body
     }
+
+    /// Retrieve the additional HTTP headers of this request.
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// Add HTTP headers to be sent with this request.
+    pub fn with_headers(mut self, headers: Vec<(String, String)>) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+}
+
+/// A successful response to a fetch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Response {
+    /// The HTTP status code of the response.
+    ///
+    /// Loads that aren't made over HTTP, such as of local files, report `200`.
+    pub status: u16,
+
+    /// The body of the response.
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// Construct the response to a load that wasn't made over HTTP.
+    pub fn ok(body: Vec<u8>) -> Self {
+        Self { status: 200, body }
+    }
 }
 
 /// Type alias for pinned, boxed, and owned futures that output a falliable
@@ -117,12 +152,26 @@ pub trait NavigatorBackend {
     );
 
     /// Fetch data at a given URL and return it some time in the future.
-    fn fetch(&self, url: &str, request_options: RequestOptions) -> OwnedFuture<Vec<u8>, Error>;
+    ///
+    /// HTTP responses with an unsuccessful status code should fail with
+    /// `Error::HttpNotOk`.
+    fn fetch(&self, url: &str, request_options: RequestOptions) -> OwnedFuture<Response, Error>;
 
     /// Get the amount of time since the SWF was launched.
     /// Used by the `getTimer` ActionScript call.
     fn time_since_launch(&mut self) -> Duration;
 
+    /// Get the current time in milliseconds since the Unix epoch.
+    /// Used by the ActionScript `Date` class.
+    fn current_time(&self) -> f64;
+
+    /// Get the offset of local time from UTC in minutes, at the given time in
+    /// milliseconds since the Unix epoch.
+    ///
+    /// The offset is positive for timezones ahead of UTC, and includes any
+    /// daylight saving time in effect at that time.
+    fn local_timezone_offset(&self, time: f64) -> i32;
+
     /// Arrange for a future to be run at some point in the... well, future.
     ///
     /// This function must be called to ensure a future is actually computed.
@@ -287,17 +336,29 @@ impl NavigatorBackend for NullNavigatorBackend {
     ) {
     }
 
-    fn fetch(&self, url: &str, _opts: RequestOptions) -> OwnedFuture<Vec<u8>, Error> {
+    fn fetch(&self, url: &str, _opts: RequestOptions) -> OwnedFuture<Response, Error> {
         let mut path = self.relative_base_path.clone();
         path.push(url);
 
-        Box::pin(async move { fs::read(path).map_err(Error::NetworkError) })
+        Box::pin(async move {
+            fs::read(path)
+                .map(Response::ok)
+                .map_err(Error::NetworkError)
+        })
     }
 
     fn time_since_launch(&mut self) -> Duration {
         Duration::from_millis(0)
     }
 
+    fn current_time(&self) -> f64 {
+        0.0
+    }
+
+    fn local_timezone_offset(&self, _time: f64) -> i32 {
+        0
+    }
+
     fn spawn_future(&mut self, future: OwnedFuture<(), Error>) {
         if let Some(channel) = self.channel.as_ref() {
             channel.send(future).unwrap();
@@ -4,6 +4,9 @@ use downcast_rs::Downcast;
 use std::io::Read;
 pub use swf;
 
+pub mod blend;
+pub mod filters;
+
 pub trait RenderBackend: Downcast {
     fn set_viewport_dimensions(&mut self, width: u32, height: u32);
     fn register_shape(&mut self, shape: DistilledShape) -> ShapeHandle;
@@ -30,6 +33,21 @@ pub trait RenderBackend: Downcast {
         &mut self,
         swf_tag: &swf::DefineBitsLossless,
     ) -> Result<BitmapInfo, Error>;
+    /// Registers a bitmap from premultiplied RGBA pixels, such as a decoded video frame.
+    fn register_bitmap_raw(
+        &mut self,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> Result<BitmapHandle, Error>;
+    /// Replaces the pixels of a registered bitmap, returning the handle to draw it with.
+    fn update_texture(
+        &mut self,
+        bitmap: BitmapHandle,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> Result<BitmapHandle, Error>;
 
     fn begin_frame(&mut self, clear: Color);
     fn render_bitmap(&mut self, bitmap: BitmapHandle, transform: &Transform);
@@ -39,6 +57,12 @@ pub trait RenderBackend: Downcast {
     fn push_mask(&mut self);
     fn activate_mask(&mut self);
     fn pop_mask(&mut self);
+
+    /// Redirects all following draws to a new, transparent offscreen target.
+    fn push_offscreen(&mut self);
+    /// Applies `filters` to the current offscreen target and composites it onto
+    /// the previous render target using `blend_mode`.
+    fn pop_offscreen(&mut self, filters: &[swf::Filter], blend_mode: swf::BlendMode);
 }
 impl_downcast!(RenderBackend);
 
@@ -133,6 +157,23 @@ impl RenderBackend for NullRenderer {
             height: 0,
         })
     }
+    fn register_bitmap_raw(
+        &mut self,
+        _width: u32,
+        _height: u32,
+        _rgba: Vec<u8>,
+    ) -> Result<BitmapHandle, Error> {
+        Ok(BitmapHandle(0))
+    }
+    fn update_texture(
+        &mut self,
+        bitmap: BitmapHandle,
+        _width: u32,
+        _height: u32,
+        _rgba: Vec<u8>,
+    ) -> Result<BitmapHandle, Error> {
+        Ok(bitmap)
+    }
     fn begin_frame(&mut self, _clear: Color) {}
     fn end_frame(&mut self) {}
     fn render_bitmap(&mut self, _bitmap: BitmapHandle, _transform: &Transform) {}
@@ -141,6 +182,8 @@ impl RenderBackend for NullRenderer {
     fn push_mask(&mut self) {}
     fn activate_mask(&mut self) {}
     fn pop_mask(&mut self) {}
+    fn push_offscreen(&mut self) {}
+    fn pop_offscreen(&mut self, _filters: &[swf::Filter], _blend_mode: swf::BlendMode) {}
 }
 
 /// The format of image data in a DefineBitsJpeg2/3 tag.
@@ -496,6 +539,16 @@ pub fn unmultiply_alpha_rgba(rgba: &mut [u8]) {
     })
 }
 
+/// Converts standard RGBA to RGBA premultiplied alpha.
+pub fn premultiply_alpha_rgba(rgba: &mut [u8]) {
+    rgba.chunks_exact_mut(4).for_each(|rgba| {
+        let a = f32::from(rgba[3]) / 255.0;
+        rgba[0] = (f32::from(rgba[0]) * a) as u8;
+        rgba[1] = (f32::from(rgba[1]) * a) as u8;
+        rgba[2] = (f32::from(rgba[2]) * a) as u8;
+    })
+}
+
 /// Converts an RGBA color from sRGB space to linear color space.
 pub fn srgb_to_linear(color: [f32; 4]) -> [f32; 4] {
     fn to_linear_channel(n: f32) -> f32 {
@@ -0,0 +1,127 @@
+//! Software implementation of display object blend modes.
+//!
+//! Backends use this for blend modes that can't be expressed as a fixed-function blend,
+//! by reading back both the layer and the content beneath it.
+
+use swf::BlendMode;
+
+/// Blends `layer` onto `backdrop` in place.
+///
+/// Both images are RGBA with premultiplied alpha, and must be the same size.
+pub fn blend_layer(layer: &[u8], backdrop: &mut [u8], blend_mode: BlendMode) {
+    for (src, dst) in layer.chunks_exact(4).zip(backdrop.chunks_exact_mut(4)) {
+        let s = to_floats(src);
+        let d = to_floats(dst);
+        let (sa, da) = (s[3], d[3]);
+
+        let out = match blend_mode {
+            BlendMode::Normal | BlendMode::Layer => per_channel(|c| s[c] + d[c] * (1.0 - sa)),
+            BlendMode::Alpha => per_channel(|c| d[c] * sa),
+            BlendMode::Erase => per_channel(|c| d[c] * (1.0 - sa)),
+            _ => {
+                let mut out = [0.0; 4];
+                for c in 0..3 {
+                    let sc = if sa > 0.0 { s[c] / sa } else { 0.0 };
+                    let dc = if da > 0.0 { d[c] / da } else { 0.0 };
+                    let blended = blend_channel(blend_mode, sc, dc);
+                    out[c] = s[c] * (1.0 - da) + d[c] * (1.0 - sa) + sa * da * blended;
+                }
+                out[3] = sa + da - sa * da;
+                out
+            }
+        };
+
+        let alpha = out[3].clamp(0.0, 1.0);
+        for (c, value) in dst.iter_mut().enumerate() {
+            *value = (out[c].max(0.0).min(alpha) * 255.0).round() as u8;
+        }
+    }
+}
+
+/// The separable blend function for a single unpremultiplied color channel.
+fn blend_channel(blend_mode: BlendMode, s: f32, d: f32) -> f32 {
+    match blend_mode {
+        BlendMode::Multiply => s * d,
+        BlendMode::Screen => s + d - s * d,
+        BlendMode::Lighten => s.max(d),
+        BlendMode::Darken => s.min(d),
+        BlendMode::Difference => (s - d).abs(),
+        BlendMode::Add => (s + d).min(1.0),
+        BlendMode::Subtract => (d - s).max(0.0),
+        BlendMode::Invert => 1.0 - d,
+        BlendMode::Overlay => hard_light(d, s),
+        BlendMode::HardLight => hard_light(s, d),
+        _ => s,
+    }
+}
+
+fn hard_light(s: f32, d: f32) -> f32 {
+    if s <= 0.5 {
+        2.0 * s * d
+    } else {
+        1.0 - 2.0 * (1.0 - s) * (1.0 - d)
+    }
+}
+
+fn to_floats(rgba: &[u8]) -> [f32; 4] {
+    [
+        f32::from(rgba[0]) / 255.0,
+        f32::from(rgba[1]) / 255.0,
+        f32::from(rgba[2]) / 255.0,
+        f32::from(rgba[3]) / 255.0,
+    ]
+}
+
+fn per_channel(f: impl Fn(usize) -> f32) -> [f32; 4] {
+    [f(0), f(1), f(2), f(3)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blend(src: [u8; 4], dst: [u8; 4], blend_mode: BlendMode) -> [u8; 4] {
+        let mut backdrop = dst;
+        blend_layer(&src, &mut backdrop, blend_mode);
+        backdrop
+    }
+
+    #[test]
+    fn transparent_layer_keeps_backdrop() {
+        for &mode in &[
+            BlendMode::Normal,
+            BlendMode::Multiply,
+            BlendMode::Difference,
+            BlendMode::Overlay,
+            BlendMode::HardLight,
+            BlendMode::Darken,
+        ] {
+            assert_eq!(
+                blend([0, 0, 0, 0], [10, 200, 30, 255], mode),
+                [10, 200, 30, 255]
+            );
+        }
+    }
+
+    #[test]
+    fn opaque_blend_modes() {
+        let src = [255, 128, 0, 255];
+        let dst = [128, 128, 128, 255];
+        assert_eq!(blend(src, dst, BlendMode::Normal), src);
+        assert_eq!(blend(src, dst, BlendMode::Multiply), [128, 64, 0, 255]);
+        assert_eq!(blend(src, dst, BlendMode::Screen), [255, 192, 128, 255]);
+        assert_eq!(blend(src, dst, BlendMode::Difference), [127, 0, 128, 255]);
+        assert_eq!(blend(src, dst, BlendMode::Darken), [128, 128, 0, 255]);
+        assert_eq!(blend(src, dst, BlendMode::Subtract), [0, 0, 128, 255]);
+        assert_eq!(blend(src, dst, BlendMode::Invert), [127, 127, 127, 255]);
+    }
+
+    #[test]
+    fn erase_and_alpha() {
+        let dst = [100, 100, 100, 255];
+        assert_eq!(blend([0, 0, 0, 255], dst, BlendMode::Erase), [0, 0, 0, 0]);
+        assert_eq!(blend([0, 0, 0, 0], dst, BlendMode::Erase), dst);
+        assert_eq!(blend([0, 0, 0, 0], dst, BlendMode::Alpha), [0, 0, 0, 0]);
+        assert_eq!(blend([0, 0, 0, 255], dst, BlendMode::Alpha), dst);
+    }
+}
@@ -0,0 +1,629 @@
+//! Software implementations of the display object filters from `PlaceObject3`.
+//!
+//! Backends render a filtered display object to an offscreen target, read the pixels back
+//! and run them through `apply_filters` before compositing the result.
+
+use swf::{Color, Filter, GradientRecord};
+
+/// Applies a list of filters, in order, to an RGBA image.
+///
+/// `rgba` must contain `width * height` pixels with premultiplied alpha,
+/// and is overwritten with the filtered result.
+pub fn apply_filters(rgba: &mut [u8], width: u32, height: u32, filters: &[Filter]) {
+    if filters.is_empty() || width == 0 || height == 0 {
+        return;
+    }
+
+    let mut image = Image::from_rgba(rgba, width as usize, height as usize);
+    for filter in filters {
+        image = match filter {
+            Filter::BlurFilter(filter) => {
+                blur_image(&image, filter.blur_x, filter.blur_y, filter.num_passes)
+            }
+            Filter::DropShadowFilter(filter) => {
+                let (dx, dy) = offset_for(filter.angle, filter.distance);
+                let shadow = Shadow {
+                    blur_x: filter.blur_x,
+                    blur_y: filter.blur_y,
+                    num_passes: filter.num_passes,
+                    strength: filter.strength,
+                    dx,
+                    dy,
+                    is_inner: filter.is_inner,
+                    is_knockout: filter.is_knockout,
+                };
+                shadow.apply(&image, |v| solid_color(&filter.color, v))
+            }
+            Filter::GlowFilter(filter) => {
+                let glow = Shadow {
+                    blur_x: filter.blur_x,
+                    blur_y: filter.blur_y,
+                    num_passes: filter.num_passes,
+                    strength: filter.strength,
+                    dx: 0,
+                    dy: 0,
+                    is_inner: filter.is_inner,
+                    is_knockout: filter.is_knockout,
+                };
+                glow.apply(&image, |v| solid_color(&filter.color, v))
+            }
+            Filter::GradientGlowFilter(filter) => {
+                let (dx, dy) = offset_for(filter.angle, filter.distance);
+                let glow = Shadow {
+                    blur_x: filter.blur_x,
+                    blur_y: filter.blur_y,
+                    num_passes: filter.num_passes,
+                    strength: filter.strength,
+                    dx,
+                    dy,
+                    is_inner: filter.is_inner,
+                    is_knockout: filter.is_knockout,
+                };
+                glow.apply(&image, |v| gradient_color(&filter.colors, v))
+            }
+            Filter::BevelFilter(filter) => {
+                let (dx, dy) = offset_for(filter.angle, filter.distance);
+                let bevel = Bevel {
+                    blur_x: filter.blur_x,
+                    blur_y: filter.blur_y,
+                    num_passes: filter.num_passes,
+                    strength: filter.strength,
+                    dx,
+                    dy,
+                    is_inner: filter.is_inner,
+                    is_knockout: filter.is_knockout,
+                    is_on_top: filter.is_on_top,
+                };
+                bevel.apply(&image, |v| {
+                    if v >= 0.0 {
+                        solid_color(&filter.highlight_color, v)
+                    } else {
+                        solid_color(&filter.shadow_color, -v)
+                    }
+                })
+            }
+            Filter::GradientBevelFilter(filter) => {
+                let (dx, dy) = offset_for(filter.angle, filter.distance);
+                let bevel = Bevel {
+                    blur_x: filter.blur_x,
+                    blur_y: filter.blur_y,
+                    num_passes: filter.num_passes,
+                    strength: filter.strength,
+                    dx,
+                    dy,
+                    is_inner: filter.is_inner,
+                    is_knockout: filter.is_knockout,
+                    is_on_top: filter.is_on_top,
+                };
+                // The middle of the gradient is the flat, unlit area; the shadow
+                // sits at the start and the highlight at the end.
+                bevel.apply(&image, |v| gradient_color(&filter.colors, (v + 1.0) / 2.0))
+            }
+            Filter::ColorMatrixFilter(filter) => color_matrix(&image, &filter.matrix),
+            Filter::ConvolutionFilter(filter) => convolution(&image, filter),
+        };
+    }
+    image.write_rgba(rgba);
+}
+
+/// A floating point image with premultiplied alpha, stored as separate channel planes.
+#[derive(Clone)]
+struct Image {
+    width: usize,
+    height: usize,
+    channels: [Vec<f32>; 4],
+}
+
+impl Image {
+    fn new(width: usize, height: usize) -> Self {
+        let len = width * height;
+        Self {
+            width,
+            height,
+            channels: [
+                vec![0.0; len],
+                vec![0.0; len],
+                vec![0.0; len],
+                vec![0.0; len],
+            ],
+        }
+    }
+
+    fn from_rgba(rgba: &[u8], width: usize, height: usize) -> Self {
+        let mut image = Self::new(width, height);
+        for (i, pixel) in rgba.chunks_exact(4).take(width * height).enumerate() {
+            for (channel, &value) in image.channels.iter_mut().zip(pixel) {
+                channel[i] = f32::from(value) / 255.0;
+            }
+        }
+        image
+    }
+
+    fn write_rgba(&self, rgba: &mut [u8]) {
+        for (i, pixel) in rgba
+            .chunks_exact_mut(4)
+            .take(self.width * self.height)
+            .enumerate()
+        {
+            let alpha = self.channels[3][i].clamp(0.0, 1.0);
+            for (c, value) in pixel.iter_mut().enumerate() {
+                // Keep the colors valid for premultiplied alpha.
+                let v = self.channels[c][i].max(0.0).min(alpha);
+                *value = (v * 255.0).round() as u8;
+            }
+        }
+    }
+
+    fn alpha(&self) -> &[f32] {
+        &self.channels[3]
+    }
+
+    /// Returns the unpremultiplied color of a pixel.
+    fn unmultiplied(&self, i: usize) -> [f32; 4] {
+        let a = self.channels[3][i];
+        if a > 0.0 {
+            [
+                self.channels[0][i] / a,
+                self.channels[1][i] / a,
+                self.channels[2][i] / a,
+                a,
+            ]
+        } else {
+            [0.0; 4]
+        }
+    }
+
+    /// Stores an unpremultiplied color into a pixel.
+    fn set_unmultiplied(&mut self, i: usize, color: [f32; 4]) {
+        let a = color[3].clamp(0.0, 1.0);
+        for (channel, value) in self.channels.iter_mut().zip(&color[..3]) {
+            channel[i] = value.clamp(0.0, 1.0) * a;
+        }
+        self.channels[3][i] = a;
+    }
+}
+
+/// The pixel offset of a shadow or bevel, given its angle (in radians) and distance.
+fn offset_for(angle: f64, distance: f64) -> (isize, isize) {
+    (
+        (angle.cos() * distance).round() as isize,
+        (angle.sin() * distance).round() as isize,
+    )
+}
+
+/// A premultiplied color with coverage `amount`.
+fn solid_color(color: &Color, amount: f32) -> [f32; 4] {
+    let a = f32::from(color.a) / 255.0 * amount;
+    [
+        f32::from(color.r) / 255.0 * a,
+        f32::from(color.g) / 255.0 * a,
+        f32::from(color.b) / 255.0 * a,
+        a,
+    ]
+}
+
+/// Samples a gradient at `position` (0.0 to 1.0), returning a premultiplied color.
+fn gradient_color(records: &[GradientRecord], position: f32) -> [f32; 4] {
+    let ratio = position.clamp(0.0, 1.0) * 255.0;
+    let (first, last) = match (records.first(), records.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return [0.0; 4],
+    };
+
+    let color = if ratio <= f32::from(first.ratio) {
+        first.color.clone()
+    } else if ratio >= f32::from(last.ratio) {
+        last.color.clone()
+    } else {
+        let (a, b) = records
+            .windows(2)
+            .map(|pair| (&pair[0], &pair[1]))
+            .find(|(_, b)| ratio <= f32::from(b.ratio))
+            .unwrap_or((first, last));
+        let span = f32::from(b.ratio) - f32::from(a.ratio);
+        let t = if span > 0.0 {
+            (ratio - f32::from(a.ratio)) / span
+        } else {
+            0.0
+        };
+        let lerp = |x: u8, y: u8| (f32::from(x) + (f32::from(y) - f32::from(x)) * t) as u8;
+        Color {
+            r: lerp(a.color.r, b.color.r),
+            g: lerp(a.color.g, b.color.g),
+            b: lerp(a.color.b, b.color.b),
+            a: lerp(a.color.a, b.color.a),
+        }
+    };
+    solid_color(&color, 1.0)
+}
+
+/// Blurs every channel of an image.
+fn blur_image(image: &Image, blur_x: f64, blur_y: f64, num_passes: u8) -> Image {
+    let mut out = image.clone();
+    for channel in &mut out.channels {
+        blur(
+            channel,
+            image.width,
+            image.height,
+            blur_x,
+            blur_y,
+            num_passes,
+        );
+    }
+    out
+}
+
+/// Blurs a single channel using repeated box blurs.
+///
+/// `blur_x` and `blur_y` are the width and height of the box in pixels, as in Flash.
+/// Pixels outside of the image are treated as zero.
+fn blur(plane: &mut [f32], width: usize, height: usize, blur_x: f64, blur_y: f64, passes: u8) {
+    let radius_x = ((blur_x - 1.0) / 2.0).round().max(0.0) as usize;
+    let radius_y = ((blur_y - 1.0) / 2.0).round().max(0.0) as usize;
+    if radius_x == 0 && radius_y == 0 {
+        return;
+    }
+
+    let mut line = Vec::with_capacity(width.max(height));
+    for _ in 0..passes {
+        if radius_x > 0 {
+            for y in 0..height {
+                let row = &mut plane[y * width..(y + 1) * width];
+                line.clear();
+                line.extend_from_slice(row);
+                box_blur_line(&line, row.iter_mut(), radius_x);
+            }
+        }
+        if radius_y > 0 {
+            for x in 0..width {
+                line.clear();
+                line.extend((0..height).map(|y| plane[y * width + x]));
+                box_blur_line(&line, plane.iter_mut().skip(x).step_by(width), radius_y);
+            }
+        }
+    }
+}
+
+/// Writes the box blur of `src` with the given radius to `dst`.
+fn box_blur_line<'a>(src: &[f32], dst: impl Iterator<Item = &'a mut f32>, radius: usize) {
+    let len = src.len();
+    let scale = 1.0 / (radius * 2 + 1) as f32;
+    let mut sum: f32 = src.iter().take(radius).sum();
+    for (i, out) in dst.enumerate() {
+        if i + radius < len {
+            sum += src[i + radius];
+        }
+        *out = sum * scale;
+        if i >= radius {
+            sum -= src[i - radius];
+        }
+    }
+}
+
+/// Returns a copy of a plane moved by (`dx`, `dy`) pixels, filling uncovered areas with `fill`.
+fn shift(plane: &[f32], width: usize, height: usize, dx: isize, dy: isize, fill: f32) -> Vec<f32> {
+    if dx == 0 && dy == 0 {
+        return plane.to_vec();
+    }
+    let mut out = vec![fill; plane.len()];
+    for y in 0..height {
+        let src_y = y as isize - dy;
+        if src_y < 0 || src_y >= height as isize {
+            continue;
+        }
+        for x in 0..width {
+            let src_x = x as isize - dx;
+            if src_x >= 0 && src_x < width as isize {
+                out[y * width + x] = plane[src_y as usize * width + src_x as usize];
+            }
+        }
+    }
+    out
+}
+
+/// Shared implementation of the drop shadow, glow and gradient glow filters.
+struct Shadow {
+    blur_x: f64,
+    blur_y: f64,
+    num_passes: u8,
+    strength: f32,
+    dx: isize,
+    dy: isize,
+    is_inner: bool,
+    is_knockout: bool,
+}
+
+impl Shadow {
+    /// `colorize` maps the shadow coverage (0.0 to 1.0) to a premultiplied color.
+    fn apply(&self, image: &Image, colorize: impl Fn(f32) -> [f32; 4]) -> Image {
+        let (width, height) = (image.width, image.height);
+        let alpha = image.alpha();
+
+        // Inner shadows are cast by the transparent area around the object.
+        let mut coverage: Vec<f32> = if self.is_inner {
+            alpha.iter().map(|a| 1.0 - a).collect()
+        } else {
+            alpha.to_vec()
+        };
+        let fill = if self.is_inner { 1.0 } else { 0.0 };
+        coverage = shift(&coverage, width, height, self.dx, self.dy, fill);
+        blur(
+            &mut coverage,
+            width,
+            height,
+            self.blur_x,
+            self.blur_y,
+            self.num_passes,
+        );
+
+        let mut out = Image::new(width, height);
+        for i in 0..width * height {
+            let mut amount = (coverage[i] * self.strength).clamp(0.0, 1.0);
+            let src_a = alpha[i];
+            if self.is_inner {
+                amount *= src_a;
+            } else if self.is_knockout {
+                amount *= 1.0 - src_a;
+            }
+            let shadow = colorize(amount);
+
+            for c in 0..4 {
+                let src = image.channels[c][i];
+                out.channels[c][i] = match (self.is_knockout, self.is_inner) {
+                    (true, _) => shadow[c],
+                    // Inner shadows are drawn on top of the object.
+                    (false, true) => shadow[c] + src * (1.0 - shadow[3]),
+                    // Outer shadows are drawn underneath the object.
+                    (false, false) => src + shadow[c] * (1.0 - src_a),
+                };
+            }
+        }
+        out
+    }
+}
+
+/// Shared implementation of the bevel and gradient bevel filters.
+struct Bevel {
+    blur_x: f64,
+    blur_y: f64,
+    num_passes: u8,
+    strength: f32,
+    dx: isize,
+    dy: isize,
+    is_inner: bool,
+    is_knockout: bool,
+    is_on_top: bool,
+}
+
+impl Bevel {
+    /// `colorize` maps the bevel amount to a premultiplied color: positive amounts are lit,
+    /// negative amounts are in shadow.
+    fn apply(&self, image: &Image, colorize: impl Fn(f32) -> [f32; 4]) -> Image {
+        let (width, height) = (image.width, image.height);
+        let alpha = image.alpha();
+
+        let mut blurred = alpha.to_vec();
+        blur(
+            &mut blurred,
+            width,
+            height,
+            self.blur_x,
+            self.blur_y,
+            self.num_passes,
+        );
+        // Edges facing the light see more of the object behind them along the light direction.
+        let ahead = shift(&blurred, width, height, -self.dx, -self.dy, 0.0);
+        let behind = shift(&blurred, width, height, self.dx, self.dy, 0.0);
+
+        let mut out = Image::new(width, height);
+        for i in 0..width * height {
+            let mut amount = ((ahead[i] - behind[i]) * self.strength).clamp(-1.0, 1.0);
+            let src_a = alpha[i];
+            if self.is_inner {
+                amount *= src_a;
+            } else if !self.is_on_top && self.is_knockout {
+                amount *= 1.0 - src_a;
+            }
+            let bevel = if amount != 0.0 {
+                colorize(amount)
+            } else {
+                [0.0; 4]
+            };
+
+            for c in 0..4 {
+                let src = image.channels[c][i];
+                out.channels[c][i] = if self.is_knockout {
+                    bevel[c]
+                } else if self.is_inner || self.is_on_top {
+                    bevel[c] + src * (1.0 - bevel[3])
+                } else {
+                    src + bevel[c] * (1.0 - src_a)
+                };
+            }
+        }
+        out
+    }
+}
+
+/// Applies a 4x5 color matrix to the unpremultiplied colors of an image.
+///
+/// The offsets in the last column are in the 0-255 range, as in Flash.
+fn color_matrix(image: &Image, matrix: &[f64; 20]) -> Image {
+    let m: Vec<f32> = matrix.iter().map(|&v| v as f32).collect();
+    let mut out = Image::new(image.width, image.height);
+    for i in 0..image.width * image.height {
+        let [r, g, b, a] = image.unmultiplied(i);
+        let mut color = [0.0; 4];
+        for (row, value) in color.iter_mut().enumerate() {
+            let m = &m[row * 5..row * 5 + 5];
+            *value = m[0] * r + m[1] * g + m[2] * b + m[3] * a + m[4] / 255.0;
+        }
+        out.set_unmultiplied(i, color);
+    }
+    out
+}
+
+/// Applies a convolution matrix to the unpremultiplied colors of an image.
+fn convolution(image: &Image, filter: &swf::ConvolutionFilter) -> Image {
+    let (width, height) = (image.width as isize, image.height as isize);
+    let cols = isize::from(filter.num_matrix_cols);
+    let rows = isize::from(filter.num_matrix_rows);
+    let divisor = if filter.divisor != 0.0 {
+        filter.divisor as f32
+    } else {
+        1.0
+    };
+    let bias = filter.bias as f32 / 255.0;
+    let default_color = [
+        f32::from(filter.default_color.r) / 255.0,
+        f32::from(filter.default_color.g) / 255.0,
+        f32::from(filter.default_color.b) / 255.0,
+        f32::from(filter.default_color.a) / 255.0,
+    ];
+
+    let mut out = Image::new(image.width, image.height);
+    for y in 0..height {
+        for x in 0..width {
+            let mut sum = [0.0; 4];
+            for row in 0..rows {
+                for col in 0..cols {
+                    let weight = match filter.matrix.get((row * cols + col) as usize) {
+                        Some(&weight) => weight as f32,
+                        None => continue,
+                    };
+                    let sx = x + col - cols / 2;
+                    let sy = y + row - rows / 2;
+                    let sample = if sx >= 0 && sx < width && sy >= 0 && sy < height {
+                        image.unmultiplied((sy * width + sx) as usize)
+                    } else if filter.is_clamped {
+                        let sx = sx.max(0).min(width - 1);
+                        let sy = sy.max(0).min(height - 1);
+                        image.unmultiplied((sy * width + sx) as usize)
+                    } else {
+                        default_color
+                    };
+                    for c in 0..4 {
+                        sum[c] += sample[c] * weight;
+                    }
+                }
+            }
+
+            let i = (y * width + x) as usize;
+            let mut color = [0.0; 4];
+            for c in 0..4 {
+                color[c] = sum[c] / divisor + bias;
+            }
+            if filter.is_preserve_alpha {
+                color[3] = image.channels[3][i];
+            }
+            out.set_unmultiplied(i, color);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 5x5 transparent image with an opaque white pixel in the middle.
+    fn dot() -> Vec<u8> {
+        let mut rgba = vec![0; 5 * 5 * 4];
+        rgba[12 * 4..12 * 4 + 4].copy_from_slice(&[255, 255, 255, 255]);
+        rgba
+    }
+
+    fn alpha_at(rgba: &[u8], x: usize, y: usize) -> u8 {
+        rgba[(y * 5 + x) * 4 + 3]
+    }
+
+    #[test]
+    fn blur_spreads_pixels() {
+        let mut rgba = dot();
+        let filter = Filter::BlurFilter(Box::new(swf::BlurFilter {
+            blur_x: 3.0,
+            blur_y: 3.0,
+            num_passes: 1,
+        }));
+        apply_filters(&mut rgba, 5, 5, &[filter]);
+        for y in 1..=3 {
+            for x in 1..=3 {
+                assert_eq!(alpha_at(&rgba, x, y), 28);
+            }
+        }
+        assert_eq!(alpha_at(&rgba, 0, 0), 0);
+        assert_eq!(alpha_at(&rgba, 4, 2), 0);
+    }
+
+    #[test]
+    fn drop_shadow_is_offset() {
+        let mut rgba = dot();
+        let filter = Filter::DropShadowFilter(Box::new(swf::DropShadowFilter {
+            color: Color {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: 255,
+            },
+            blur_x: 0.0,
+            blur_y: 0.0,
+            angle: 0.0,
+            distance: 2.0,
+            strength: 1.0,
+            is_inner: false,
+            is_knockout: false,
+            num_passes: 1,
+        }));
+        apply_filters(&mut rgba, 5, 5, &[filter]);
+        assert_eq!(&rgba[12 * 4..12 * 4 + 4], &[255, 255, 255, 255]);
+        assert_eq!(&rgba[14 * 4..14 * 4 + 4], &[0, 0, 0, 255]);
+        assert_eq!(alpha_at(&rgba, 3, 2), 0);
+    }
+
+    #[test]
+    fn identity_color_matrix() {
+        let mut rgba = vec![10, 20, 30, 128, 255, 0, 0, 255];
+        let expected = rgba.clone();
+        let mut matrix = [0.0; 20];
+        for i in 0..4 {
+            matrix[i * 6] = 1.0;
+        }
+        let filter = Filter::ColorMatrixFilter(Box::new(swf::ColorMatrixFilter { matrix }));
+        apply_filters(&mut rgba, 2, 1, &[filter]);
+        assert_eq!(rgba, expected);
+    }
+
+    #[test]
+    fn color_matrix_offsets() {
+        let mut rgba = vec![0, 0, 0, 255];
+        let mut matrix = [0.0; 20];
+        matrix[4] = 255.0;
+        matrix[18] = 1.0;
+        let filter = Filter::ColorMatrixFilter(Box::new(swf::ColorMatrixFilter { matrix }));
+        apply_filters(&mut rgba, 1, 1, &[filter]);
+        assert_eq!(rgba, vec![255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn convolution_identity() {
+        let mut rgba = dot();
+        let expected = rgba.clone();
+        let filter = Filter::ConvolutionFilter(Box::new(swf::ConvolutionFilter {
+            num_matrix_rows: 3,
+            num_matrix_cols: 3,
+            matrix: vec![0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0],
+            divisor: 1.0,
+            bias: 0.0,
+            default_color: Color {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: 0,
+            },
+            is_clamped: true,
+            is_preserve_alpha: false,
+        }));
+        apply_filters(&mut rgba, 5, 5, &[filter]);
+        assert_eq!(rgba, expected);
+    }
+}
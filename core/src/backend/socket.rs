@@ -0,0 +1,130 @@
+//! Socket backends, used by `XMLSocket` to talk to servers.
+
+use generational_arena::{Arena, Index};
+use std::collections::VecDeque;
+
+pub type SocketHandle = Index;
+
+/// Something that happened to a socket since the last time the backend was polled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SocketEvent {
+    /// A connection attempt finished, either successfully or not.
+    Connect(SocketHandle, bool),
+
+    /// Data was received from the server.
+    Data(SocketHandle, Vec<u8>),
+
+    /// The connection was closed by the server or dropped.
+    Close(SocketHandle),
+}
+
+pub trait SocketBackend {
+    /// Starts connecting to the given host and port.
+    ///
+    /// This must not block; the outcome is reported by `poll` as a `SocketEvent::Connect`.
+    fn connect(&mut self, host: &str, port: u16) -> SocketHandle;
+
+    /// Sends data over an open connection.
+    fn send(&mut self, handle: SocketHandle, data: Vec<u8>);
+
+    /// Closes a connection. No further events will be reported for it.
+    fn close(&mut self, handle: SocketHandle);
+
+    /// Returns every event that happened since the last poll, in order.
+    fn poll(&mut self) -> Vec<SocketEvent>;
+}
+
+/// A socket backend that fails every connection attempt.
+pub struct NullSocketBackend {
+    sockets: Arena<()>,
+    events: Vec<SocketEvent>,
+}
+
+impl NullSocketBackend {
+    pub fn new() -> Self {
+        Self {
+            sockets: Arena::new(),
+            events: Vec::new(),
+        }
+    }
+}
+
+impl Default for NullSocketBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SocketBackend for NullSocketBackend {
+    fn connect(&mut self, _host: &str, _port: u16) -> SocketHandle {
+        let handle = self.sockets.insert(());
+        self.events.push(SocketEvent::Connect(handle, false));
+        handle
+    }
+
+    fn send(&mut self, _handle: SocketHandle, _data: Vec<u8>) {}
+
+    fn close(&mut self, handle: SocketHandle) {
+        self.sockets.remove(handle);
+    }
+
+    fn poll(&mut self) -> Vec<SocketEvent> {
+        std::mem::take(&mut self.events)
+    }
+}
+
+/// An in-memory socket backend where every connection succeeds and echoes back whatever is
+/// sent over it.
+pub struct LoopbackSocketBackend {
+    sockets: Arena<()>,
+    events: VecDeque<SocketEvent>,
+}
+
+impl LoopbackSocketBackend {
+    pub fn new() -> Self {
+        Self {
+            sockets: Arena::new(),
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Simulates the server closing a connection.
+    pub fn close_from_server(&mut self, handle: SocketHandle) {
+        if self.sockets.remove(handle).is_some() {
+            self.events.push_back(SocketEvent::Close(handle));
+        }
+    }
+}
+
+impl Default for LoopbackSocketBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SocketBackend for LoopbackSocketBackend {
+    fn connect(&mut self, _host: &str, _port: u16) -> SocketHandle {
+        let handle = self.sockets.insert(());
+        self.events.push_back(SocketEvent::Connect(handle, true));
+        handle
+    }
+
+    fn send(&mut self, handle: SocketHandle, data: Vec<u8>) {
+        if self.sockets.contains(handle) {
+            self.events.push_back(SocketEvent::Data(handle, data));
+        }
+    }
+
+    fn close(&mut self, handle: SocketHandle) {
+        self.sockets.remove(handle);
+        self.events.retain(|event| match event {
+            SocketEvent::Connect(h, _) | SocketEvent::Data(h, _) | SocketEvent::Close(h) => {
+                *h != handle
+            }
+        });
+    }
+
+    fn poll(&mut self) -> Vec<SocketEvent> {
+        self.events.drain(..).collect()
+    }
+}
@@ -0,0 +1,118 @@
+//! Video decoding backends.
+
+use crate::backend::render::{BitmapInfo, RenderBackend};
+use generational_arena::{Arena, Index};
+use swf::{VideoCodec, VideoDeblocking};
+
+pub mod decoders;
+pub mod software;
+
+pub type VideoStreamHandle = Index;
+
+type Error = Box<dyn std::error::Error>;
+
+/// An encoded video frame, as stored in a `VideoFrame` tag.
+#[derive(Copy, Clone, Debug)]
+pub struct EncodedFrame<'a> {
+    /// The codec the frame was encoded with.
+    pub codec: VideoCodec,
+
+    /// The encoded frame data.
+    pub data: &'a [u8],
+
+    /// The number of this frame within its stream.
+    pub frame_id: u32,
+}
+
+/// Whether a video frame depends on frames that came before it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FrameDependency {
+    /// The frame can be decoded on its own, so the stream can be seeked to it.
+    None,
+
+    /// The frame can only be decoded after the frames before it.
+    Past,
+}
+
+impl FrameDependency {
+    pub fn is_keyframe(self) -> bool {
+        self == FrameDependency::None
+    }
+}
+
+pub trait VideoBackend {
+    /// Registers a new video stream, returning a handle to decode its frames with.
+    fn register_video_stream(
+        &mut self,
+        num_frames: u32,
+        size: (u16, u16),
+        codec: VideoCodec,
+        deblocking: VideoDeblocking,
+    ) -> Result<VideoStreamHandle, Error>;
+
+    /// Inspects a frame of a video stream as it is preloaded, without decoding it.
+    fn preload_video_stream_frame(
+        &mut self,
+        stream: VideoStreamHandle,
+        encoded_frame: EncodedFrame<'_>,
+    ) -> Result<FrameDependency, Error>;
+
+    /// Decodes the next frame of a video stream into a bitmap.
+    ///
+    /// Frames must be decoded in order, starting from a keyframe. The same bitmap is reused
+    /// for every frame of the stream.
+    fn decode_video_stream_frame(
+        &mut self,
+        stream: VideoStreamHandle,
+        encoded_frame: EncodedFrame<'_>,
+        renderer: &mut dyn RenderBackend,
+    ) -> Result<BitmapInfo, Error>;
+}
+
+/// A video backend that can't decode anything.
+pub struct NullVideoBackend {
+    streams: Arena<()>,
+}
+
+impl NullVideoBackend {
+    pub fn new() -> Self {
+        Self {
+            streams: Arena::new(),
+        }
+    }
+}
+
+impl Default for NullVideoBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VideoBackend for NullVideoBackend {
+    fn register_video_stream(
+        &mut self,
+        _num_frames: u32,
+        _size: (u16, u16),
+        _codec: VideoCodec,
+        _deblocking: VideoDeblocking,
+    ) -> Result<VideoStreamHandle, Error> {
+        Ok(self.streams.insert(()))
+    }
+
+    fn preload_video_stream_frame(
+        &mut self,
+        _stream: VideoStreamHandle,
+        _encoded_frame: EncodedFrame<'_>,
+    ) -> Result<FrameDependency, Error> {
+        Ok(FrameDependency::None)
+    }
+
+    fn decode_video_stream_frame(
+        &mut self,
+        _stream: VideoStreamHandle,
+        _encoded_frame: EncodedFrame<'_>,
+        _renderer: &mut dyn RenderBackend,
+    ) -> Result<BitmapInfo, Error> {
+        Err("Video decoding is not supported by this backend".into())
+    }
+}
@@ -0,0 +1,161 @@
+//! Software decoders for the video codecs used in SWF files.
+
+mod h263;
+mod screen;
+
+pub use h263::H263Decoder;
+pub use screen::ScreenVideoDecoder;
+
+use crate::backend::video::{EncodedFrame, FrameDependency};
+use swf::VideoCodec;
+
+type Error = Box<dyn std::error::Error>;
+
+/// A decoded video frame.
+pub struct DecodedFrame {
+    pub width: u16,
+    pub height: u16,
+
+    /// The frame's pixels, as RGBA.
+    pub rgba: Vec<u8>,
+}
+
+/// Decodes the frames of a single video stream.
+pub trait VideoDecoder {
+    /// Determines whether a frame depends on the frames before it, without decoding it.
+    fn preload_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<FrameDependency, Error>;
+
+    /// Decodes a frame. Frames that aren't keyframes must follow the frame they depend on.
+    fn decode_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<DecodedFrame, Error>;
+}
+
+/// Creates a decoder for the given codec, if it is supported.
+pub fn make_decoder(codec: VideoCodec) -> Result<Box<dyn VideoDecoder>, Error> {
+    match codec {
+        VideoCodec::H263 => Ok(Box::new(H263Decoder::new())),
+        VideoCodec::ScreenVideo => Ok(Box::new(ScreenVideoDecoder::new())),
+        VideoCodec::VP6 | VideoCodec::VP6WithAlpha => {
+            Err(format!("Unsupported video codec {:?}", codec).into())
+        }
+    }
+}
+
+/// Reads a bitstream most significant bit first.
+struct BitReader<'a> {
+    data: &'a [u8],
+    bit_pos: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, bit_pos: 0 }
+    }
+
+    /// Returns the next `num_bits` bits without consuming them.
+    /// Bits past the end of the data read as zeroes.
+    fn peek_bits(&self, num_bits: u32) -> u32 {
+        let mut value = 0;
+        for i in 0..num_bits as usize {
+            let pos = self.bit_pos + i;
+            let bit = self
+                .data
+                .get(pos / 8)
+                .map(|byte| (byte >> (7 - pos % 8)) & 1)
+                .unwrap_or(0);
+            value = (value << 1) | u32::from(bit);
+        }
+        value
+    }
+
+    fn skip_bits(&mut self, num_bits: u32) -> Result<(), Error> {
+        self.bit_pos += num_bits as usize;
+        if self.bit_pos > self.data.len() * 8 {
+            Err("Unexpected end of video data".into())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn read_bits(&mut self, num_bits: u32) -> Result<u32, Error> {
+        let value = self.peek_bits(num_bits);
+        self.skip_bits(num_bits)?;
+        Ok(value)
+    }
+
+    fn read_bit(&mut self) -> Result<bool, Error> {
+        Ok(self.read_bits(1)? == 1)
+    }
+
+    /// Reads a two's complement signed value.
+    fn read_signed_bits(&mut self, num_bits: u32) -> Result<i32, Error> {
+        let value = self.read_bits(num_bits)?;
+        let shift = 32 - num_bits;
+        Ok(((value << shift) as i32) >> shift)
+    }
+
+    /// Reads a variable length code from a table of `(code, length, value)` entries.
+    fn read_vlc<T: Copy>(&mut self, table: &[(u16, u8, T)]) -> Result<T, Error> {
+        for &(code, len, value) in table {
+            if self.peek_bits(len.into()) == u32::from(code) {
+                self.skip_bits(len.into())?;
+                return Ok(value);
+            }
+        }
+        Err("Invalid variable length code in video data".into())
+    }
+}
+
+/// Converts planar YUV 4:2:0 with BT.601 video range to RGBA.
+///
+/// The chroma planes are half the resolution of the luma plane in each dimension.
+fn yuv420_to_rgba(
+    width: usize,
+    height: usize,
+    y_plane: &[u8],
+    y_stride: usize,
+    cb_plane: &[u8],
+    cr_plane: &[u8],
+    chroma_stride: usize,
+) -> Vec<u8> {
+    let mut rgba = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        for x in 0..width {
+            let luma = i32::from(y_plane[y * y_stride + x]) - 16;
+            let chroma_index = (y / 2) * chroma_stride + x / 2;
+            let cb = i32::from(cb_plane[chroma_index]) - 128;
+            let cr = i32::from(cr_plane[chroma_index]) - 128;
+
+            let r = (298 * luma + 409 * cr + 128) >> 8;
+            let g = (298 * luma - 100 * cb - 208 * cr + 128) >> 8;
+            let b = (298 * luma + 516 * cb + 128) >> 8;
+            rgba.push(r.clamp(0, 255) as u8);
+            rgba.push(g.clamp(0, 255) as u8);
+            rgba.push(b.clamp(0, 255) as u8);
+            rgba.push(255);
+        }
+    }
+    rgba
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bit_reader() {
+        let mut reader = BitReader::new(&[0b1110_0000, 0b1111_0000]);
+        assert_eq!(reader.read_signed_bits(3).unwrap(), -1);
+        assert_eq!(reader.read_bits(4).unwrap(), 0);
+        assert_eq!(reader.read_signed_bits(3).unwrap(), 3);
+        assert_eq!(reader.peek_bits(8), 0b1100_0000);
+        assert!(reader.read_bits(7).is_err());
+    }
+
+    #[test]
+    fn yuv_conversion() {
+        let white = yuv420_to_rgba(1, 1, &[235], 1, &[128], &[128], 1);
+        assert_eq!(white, [255, 255, 255, 255]);
+        let black = yuv420_to_rgba(1, 1, &[16], 1, &[128], &[128], 1);
+        assert_eq!(black, [0, 0, 0, 255]);
+    }
+}
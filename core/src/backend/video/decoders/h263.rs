@@ -0,0 +1,906 @@
+//! Sorenson H.263 decoder.
+//!
+//! Sorenson Spark is baseline H.263 with a simpler picture header, disposable inter frames
+//! that are never used as a reference, and an 11-bit escape for large coefficients.
+
+use super::{yuv420_to_rgba, BitReader, DecodedFrame, Error, VideoDecoder};
+use crate::backend::video::{EncodedFrame, FrameDependency};
+
+/// Decodes a Sorenson H.263 stream.
+pub struct H263Decoder {
+    /// The last decoded frame that later inter frames predict from.
+    reference: Option<Picture>,
+
+    idct: Idct,
+}
+
+impl H263Decoder {
+    pub fn new() -> Self {
+        Self {
+            reference: None,
+            idct: Idct::new(),
+        }
+    }
+}
+
+impl Default for H263Decoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VideoDecoder for H263Decoder {
+    fn preload_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<FrameDependency, Error> {
+        let header = PictureHeader::read(&mut BitReader::new(encoded_frame.data))?;
+        Ok(if header.picture_type == PictureType::Intra {
+            FrameDependency::None
+        } else {
+            FrameDependency::Past
+        })
+    }
+
+    fn decode_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<DecodedFrame, Error> {
+        let mut reader = BitReader::new(encoded_frame.data);
+        let header = PictureHeader::read(&mut reader)?;
+        if header.width == 0 || header.height == 0 {
+            return Err("Invalid H.263 picture size".into());
+        }
+
+        let reference = match (&self.reference, header.picture_type) {
+            (_, PictureType::Intra) => None,
+            (Some(reference), _)
+                if reference.width == header.width && reference.height == header.height =>
+            {
+                Some(reference)
+            }
+            _ => return Err("H.263 inter frame without a reference frame".into()),
+        };
+
+        let picture = Picture::new(header.width, header.height);
+        let vectors = MotionVectors::new(picture.mb_width * 2, picture.mb_height * 2);
+        let mut decoder = PictureDecoder {
+            reader,
+            version: header.version,
+            quantizer: header.quantizer.into(),
+            reference,
+            picture,
+            vectors,
+            idct: &self.idct,
+        };
+        for mb_y in 0..decoder.picture.mb_height {
+            for mb_x in 0..decoder.picture.mb_width {
+                decoder.decode_macroblock(mb_x, mb_y)?;
+            }
+        }
+
+        let picture = decoder.picture;
+        let frame = DecodedFrame {
+            width: picture.width,
+            height: picture.height,
+            rgba: picture.to_rgba(),
+        };
+        if header.picture_type != PictureType::DisposableInter {
+            self.reference = Some(picture);
+        }
+        Ok(frame)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum PictureType {
+    Intra,
+    Inter,
+
+    /// An inter frame that no later frame depends on.
+    DisposableInter,
+}
+
+struct PictureHeader {
+    version: u32,
+    width: u16,
+    height: u16,
+    picture_type: PictureType,
+    quantizer: u8,
+}
+
+impl PictureHeader {
+    fn read(reader: &mut BitReader<'_>) -> Result<Self, Error> {
+        if reader.read_bits(17)? != 1 {
+            return Err("Invalid H.263 picture start code".into());
+        }
+        let version = reader.read_bits(5)?;
+        if version > 1 {
+            return Err(format!("Unsupported H.263 version {}", version).into());
+        }
+        let _temporal_reference = reader.read_bits(8)?;
+        let (width, height) = match reader.read_bits(3)? {
+            0 => (reader.read_bits(8)? as u16, reader.read_bits(8)? as u16),
+            1 => (reader.read_bits(16)? as u16, reader.read_bits(16)? as u16),
+            2 => (352, 288),
+            3 => (176, 144),
+            4 => (128, 96),
+            5 => (320, 240),
+            6 => (160, 120),
+            _ => return Err("Invalid H.263 picture size".into()),
+        };
+        let picture_type = match reader.read_bits(2)? {
+            0 => PictureType::Intra,
+            1 => PictureType::Inter,
+            2 => PictureType::DisposableInter,
+            _ => return Err("Invalid H.263 picture type".into()),
+        };
+        let _deblocking = reader.read_bit()?;
+        let quantizer = reader.read_bits(5)? as u8;
+        if quantizer == 0 {
+            return Err("Invalid H.263 quantizer".into());
+        }
+
+        // Extra information, which is never used.
+        while reader.read_bit()? {
+            reader.skip_bits(8)?;
+        }
+
+        Ok(Self {
+            version,
+            width,
+            height,
+            picture_type,
+            quantizer,
+        })
+    }
+}
+
+/// A YUV 4:2:0 picture, with planes padded to a whole number of macroblocks.
+struct Picture {
+    width: u16,
+    height: u16,
+    mb_width: usize,
+    mb_height: usize,
+    planes: [Vec<u8>; 3],
+}
+
+impl Picture {
+    fn new(width: u16, height: u16) -> Self {
+        let mb_width = usize::from(width).div_ceil(16);
+        let mb_height = usize::from(height).div_ceil(16);
+        let luma_size = mb_width * mb_height * 256;
+        Self {
+            width,
+            height,
+            mb_width,
+            mb_height,
+            planes: [
+                vec![0; luma_size],
+                vec![0; luma_size / 4],
+                vec![0; luma_size / 4],
+            ],
+        }
+    }
+
+    /// The stride and height of a plane, in pixels.
+    fn plane_size(&self, plane: usize) -> (usize, usize) {
+        if plane == 0 {
+            (self.mb_width * 16, self.mb_height * 16)
+        } else {
+            (self.mb_width * 8, self.mb_height * 8)
+        }
+    }
+
+    fn to_rgba(&self) -> Vec<u8> {
+        yuv420_to_rgba(
+            self.width.into(),
+            self.height.into(),
+            &self.planes[0],
+            self.mb_width * 16,
+            &self.planes[1],
+            &self.planes[2],
+            self.mb_width * 8,
+        )
+    }
+}
+
+/// A motion vector in half pixels.
+type MotionVector = (i32, i32);
+
+/// The motion vectors of each 8x8 luma block of a picture, used to predict later vectors.
+struct MotionVectors {
+    width: usize,
+    height: usize,
+    vectors: Vec<MotionVector>,
+}
+
+impl MotionVectors {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            vectors: vec![(0, 0); width * height],
+        }
+    }
+
+    /// Returns the vector of a block, or zero if the block is outside the picture.
+    fn get(&self, x: isize, y: isize) -> MotionVector {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            (0, 0)
+        } else {
+            self.vectors[y as usize * self.width + x as usize]
+        }
+    }
+
+    fn set(&mut self, x: usize, y: usize, vector: MotionVector) {
+        self.vectors[y * self.width + x] = vector;
+    }
+
+    /// Predicts the vector of a block from the median of its left, above and above-right
+    /// neighbours. `above_right` is the horizontal offset of the third candidate, which
+    /// depends on where the block sits within its macroblock.
+    fn predict(&self, x: usize, y: usize, above_right: isize) -> MotionVector {
+        let (x, y) = (x as isize, y as isize);
+        let left = self.get(x - 1, y);
+        if y == 0 {
+            return left;
+        }
+        let above = self.get(x, y - 1);
+        let above_right = self.get(x + above_right, y - 1);
+        (
+            median(left.0, above.0, above_right.0),
+            median(left.1, above.1, above_right.1),
+        )
+    }
+}
+
+fn median(a: i32, b: i32, c: i32) -> i32 {
+    a.max(b).min(a.min(b).max(c))
+}
+
+/// The state of a picture while its macroblocks are decoded.
+struct PictureDecoder<'a> {
+    reader: BitReader<'a>,
+    version: u32,
+    quantizer: i32,
+    reference: Option<&'a Picture>,
+    picture: Picture,
+    vectors: MotionVectors,
+    idct: &'a Idct,
+}
+
+impl<'a> PictureDecoder<'a> {
+    fn decode_macroblock(&mut self, mb_x: usize, mb_y: usize) -> Result<(), Error> {
+        let (is_intra, has_quant, has_four_vectors, cbpc) = if self.reference.is_some() {
+            // The coded bit is set when the macroblock is copied unchanged from the reference.
+            if self.reader.read_bit()? {
+                for block in 0..6 {
+                    self.write_block(mb_x, mb_y, block, (0, 0), None)?;
+                }
+                return Ok(());
+            }
+            let mcbpc = loop {
+                let mcbpc = self.reader.read_vlc(INTER_MCBPC)?;
+                if mcbpc != MCBPC_STUFFING {
+                    break mcbpc;
+                }
+            };
+            (mcbpc & 4 != 0, mcbpc & 8 != 0, mcbpc & 16 != 0, mcbpc & 3)
+        } else {
+            let mcbpc = loop {
+                let mcbpc = self.reader.read_vlc(INTRA_MCBPC)?;
+                if mcbpc != MCBPC_STUFFING {
+                    break mcbpc;
+                }
+            };
+            (true, mcbpc & 4 != 0, false, mcbpc & 3)
+        };
+
+        let mut cbpy = self.reader.read_vlc(CBPY)?;
+        if !is_intra {
+            cbpy ^= 0xf;
+        }
+        let cbp = (cbpy << 2) | cbpc;
+
+        if has_quant {
+            let dquant = DQUANT[self.reader.read_bits(2)? as usize];
+            self.quantizer = (self.quantizer + dquant).clamp(1, 31);
+        }
+
+        let mut luma_vectors = [(0, 0); 4];
+        let mut chroma_vector = (0, 0);
+        if !is_intra {
+            let (x, y) = (mb_x * 2, mb_y * 2);
+            if has_four_vectors {
+                for (block, vector) in luma_vectors.iter_mut().enumerate() {
+                    let (bx, by) = (x + (block & 1), y + (block >> 1));
+                    let predictor = self.vectors.predict(bx, by, ABOVE_RIGHT_OFFSETS[block]);
+                    *vector = self.read_motion_vector(predictor)?;
+                    self.vectors.set(bx, by, *vector);
+                }
+                let sum_x = luma_vectors.iter().map(|v| v.0).sum();
+                let sum_y = luma_vectors.iter().map(|v| v.1).sum();
+                chroma_vector = (chroma_from_sum(sum_x), chroma_from_sum(sum_y));
+            } else {
+                let predictor = self.vectors.predict(x, y, ABOVE_RIGHT_OFFSETS[0]);
+                let vector = self.read_motion_vector(predictor)?;
+                for block in 0..4 {
+                    self.vectors.set(x + (block & 1), y + (block >> 1), vector);
+                }
+                luma_vectors = [vector; 4];
+                // Chroma vectors are halved, rounding towards the half pixel.
+                chroma_vector = (
+                    (vector.0 >> 1) | (vector.0 & 1),
+                    (vector.1 >> 1) | (vector.1 & 1),
+                );
+            }
+        }
+
+        for block in 0..6 {
+            let coded = cbp & (32 >> block) != 0;
+            let residual = if is_intra || coded {
+                let coefficients = self.read_block(is_intra, coded)?;
+                Some(self.idct.transform(&coefficients))
+            } else {
+                None
+            };
+            let vector = luma_vectors.get(block).copied().unwrap_or(chroma_vector);
+            if is_intra {
+                self.write_intra_block(mb_x, mb_y, block, &residual.unwrap_or([0; 64]));
+            } else {
+                self.write_block(mb_x, mb_y, block, vector, residual.as_ref())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn read_motion_vector(&mut self, predictor: MotionVector) -> Result<MotionVector, Error> {
+        Ok((
+            self.read_vector_component(predictor.0)?,
+            self.read_vector_component(predictor.1)?,
+        ))
+    }
+
+    fn read_vector_component(&mut self, predictor: i32) -> Result<i32, Error> {
+        let magnitude = self.reader.read_vlc(MVD)?;
+        if magnitude == 0 {
+            return Ok(predictor);
+        }
+        let difference = if self.reader.read_bit()? {
+            -magnitude
+        } else {
+            magnitude
+        };
+        // Vectors wrap around to stay within -16 to 15.5 pixels.
+        Ok(((predictor + difference + 32) & 63) - 32)
+    }
+
+    /// Reads the dequantized coefficients of a block, in raster order.
+    fn read_block(&mut self, is_intra: bool, coded: bool) -> Result<[i32; 64], Error> {
+        let mut block = [0; 64];
+        let mut index = 0;
+        if is_intra {
+            let dc = self.reader.read_bits(8)?;
+            if dc == 0 || dc == 128 {
+                return Err("Invalid H.263 intra DC coefficient".into());
+            }
+            let dc = if dc == 255 { 128 } else { dc as i32 };
+            block[0] = dc * 8;
+            index = 1;
+        }
+
+        if coded {
+            loop {
+                let (last, run, level) = self.read_coefficient()?;
+                index += run;
+                if index >= 64 {
+                    return Err("H.263 coefficient out of range".into());
+                }
+                block[ZIGZAG[index]] = dequantize(level, self.quantizer);
+                index += 1;
+                if last {
+                    break;
+                }
+            }
+        }
+
+        Ok(block)
+    }
+
+    /// Reads a run-length coded coefficient as `(last, run, level)`.
+    fn read_coefficient(&mut self) -> Result<(bool, usize, i32), Error> {
+        if let Some((last, run, level)) = self.reader.read_vlc(TCOEF)? {
+            let level = if self.reader.read_bit()? {
+                -i32::from(level)
+            } else {
+                level.into()
+            };
+            return Ok((last, run.into(), level));
+        }
+
+        let last = if self.version == 0 {
+            let last = self.reader.read_bit()?;
+            let run = self.reader.read_bits(6)? as usize;
+            let level = self.reader.read_signed_bits(8)?;
+            (last, run, level)
+        } else {
+            let is_long = self.reader.read_bit()?;
+            let last = self.reader.read_bit()?;
+            let run = self.reader.read_bits(6)? as usize;
+            let level = self.reader.read_signed_bits(if is_long { 11 } else { 7 })?;
+            (last, run, level)
+        };
+        if last.2 == 0 {
+            return Err("Invalid H.263 escaped coefficient".into());
+        }
+        Ok(last)
+    }
+
+    /// Returns the plane and top-left pixel of a block within a macroblock.
+    fn block_position(mb_x: usize, mb_y: usize, block: usize) -> (usize, usize, usize) {
+        if block < 4 {
+            (0, mb_x * 16 + (block & 1) * 8, mb_y * 16 + (block >> 1) * 8)
+        } else {
+            (block - 3, mb_x * 8, mb_y * 8)
+        }
+    }
+
+    fn write_intra_block(&mut self, mb_x: usize, mb_y: usize, block: usize, pixels: &[i32; 64]) {
+        let (plane, x, y) = Self::block_position(mb_x, mb_y, block);
+        let (stride, _) = self.picture.plane_size(plane);
+        let output = &mut self.picture.planes[plane];
+        for row in 0..8 {
+            for col in 0..8 {
+                output[(y + row) * stride + x + col] = pixels[row * 8 + col].clamp(0, 255) as u8;
+            }
+        }
+    }
+
+    /// Writes a block predicted from the reference picture, plus an optional residual.
+    fn write_block(
+        &mut self,
+        mb_x: usize,
+        mb_y: usize,
+        block: usize,
+        vector: MotionVector,
+        residual: Option<&[i32; 64]>,
+    ) -> Result<(), Error> {
+        let reference = self
+            .reference
+            .ok_or("H.263 inter macroblock without a reference frame")?;
+        let (plane, x, y) = Self::block_position(mb_x, mb_y, block);
+        let (stride, height) = self.picture.plane_size(plane);
+        let source = &reference.planes[plane];
+        let sample = |px: i32, py: i32| {
+            let px = px.clamp(0, stride as i32 - 1) as usize;
+            let py = py.clamp(0, height as i32 - 1) as usize;
+            i32::from(source[py * stride + px])
+        };
+
+        let (full_x, half_x) = (x as i32 + (vector.0 >> 1), vector.0 & 1);
+        let (full_y, half_y) = (y as i32 + (vector.1 >> 1), vector.1 & 1);
+        let output = &mut self.picture.planes[plane];
+        for row in 0..8 {
+            for col in 0..8 {
+                let (px, py) = (full_x + col as i32, full_y + row as i32);
+                let predicted = match (half_x, half_y) {
+                    (0, 0) => sample(px, py),
+                    (1, 0) => (sample(px, py) + sample(px + 1, py) + 1) >> 1,
+                    (0, _) => (sample(px, py) + sample(px, py + 1) + 1) >> 1,
+                    _ => {
+                        (sample(px, py)
+                            + sample(px + 1, py)
+                            + sample(px, py + 1)
+                            + sample(px + 1, py + 1)
+                            + 2)
+                            >> 2
+                    }
+                };
+                let value = predicted + residual.map(|r| r[row * 8 + col]).unwrap_or(0);
+                output[(y + row) * stride + x + col] = value.clamp(0, 255) as u8;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Derives a chroma vector component from the sum of four luma vector components.
+fn chroma_from_sum(sum: i32) -> i32 {
+    const ROUNDING: [i32; 16] = [0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2];
+    ROUNDING[(sum & 15) as usize] + ((sum >> 3) & !1)
+}
+
+fn dequantize(level: i32, quantizer: i32) -> i32 {
+    let magnitude = quantizer * (2 * level.abs() + 1) - (1 - quantizer % 2);
+    (level.signum() * magnitude).clamp(-2048, 2047)
+}
+
+/// A separable 8x8 inverse DCT.
+struct Idct {
+    /// `basis[x][u]` is the contribution of frequency `u` to sample `x`.
+    basis: [[f32; 8]; 8],
+}
+
+impl Idct {
+    fn new() -> Self {
+        let mut basis = [[0.0; 8]; 8];
+        for (x, row) in basis.iter_mut().enumerate() {
+            for (u, value) in row.iter_mut().enumerate() {
+                let scale = if u == 0 {
+                    std::f32::consts::FRAC_1_SQRT_2
+                } else {
+                    1.0
+                };
+                let angle = (2 * x + 1) as f32 * u as f32 * std::f32::consts::PI / 16.0;
+                *value = scale * angle.cos() / 2.0;
+            }
+        }
+        Self { basis }
+    }
+
+    fn transform(&self, coefficients: &[i32; 64]) -> [i32; 64] {
+        let mut rows = [0.0f32; 64];
+        for y in 0..8 {
+            for x in 0..8 {
+                rows[y * 8 + x] = (0..8)
+                    .map(|u| self.basis[x][u] * coefficients[y * 8 + u] as f32)
+                    .sum();
+            }
+        }
+
+        let mut output = [0; 64];
+        for y in 0..8 {
+            for x in 0..8 {
+                let value: f32 = (0..8).map(|v| self.basis[y][v] * rows[v * 8 + x]).sum();
+                output[y * 8 + x] = value.round() as i32;
+            }
+        }
+        output
+    }
+}
+
+#[rustfmt::skip]
+const ZIGZAG: [usize; 64] = [
+     0,  1,  8, 16,  9,  2,  3, 10,
+    17, 24, 32, 25, 18, 11,  4,  5,
+    12, 19, 26, 33, 40, 48, 41, 34,
+    27, 20, 13,  6,  7, 14, 21, 28,
+    35, 42, 49, 56, 57, 50, 43, 36,
+    29, 22, 15, 23, 30, 37, 44, 51,
+    58, 59, 52, 45, 38, 31, 39, 46,
+    53, 60, 61, 54, 47, 55, 62, 63,
+];
+
+const DQUANT: [i32; 4] = [-1, -2, 1, 2];
+
+/// The offsets of the above-right motion vector candidate for each luma block.
+const ABOVE_RIGHT_OFFSETS: [isize; 4] = [2, 1, 1, -1];
+
+const MCBPC_STUFFING: u32 = 0xff;
+
+/// Intra macroblock types. Bit 2 is set when a quantizer change follows, and the low bits
+/// are the chroma coded block pattern.
+const INTRA_MCBPC: &[(u16, u8, u32)] = &[
+    (0x1, 1, 0),
+    (0x1, 3, 1),
+    (0x2, 3, 2),
+    (0x3, 3, 3),
+    (0x1, 4, 4),
+    (0x1, 6, 5),
+    (0x2, 6, 6),
+    (0x3, 6, 7),
+    (0x1, 9, MCBPC_STUFFING),
+];
+
+/// Inter macroblock types. Bit 2 marks intra macroblocks, bit 3 a quantizer change, and bit 4
+/// four motion vectors. The low bits are the chroma coded block pattern.
+const INTER_MCBPC: &[(u16, u8, u32)] = &[
+    (0x1, 1, 0),
+    (0x3, 4, 1),
+    (0x2, 4, 2),
+    (0x5, 6, 3),
+    (0x3, 5, 8),
+    (0x4, 8, 9),
+    (0x3, 8, 10),
+    (0x3, 7, 11),
+    (0x3, 3, 16),
+    (0x7, 7, 17),
+    (0x6, 7, 18),
+    (0x5, 9, 19),
+    (0x4, 6, 4),
+    (0x4, 9, 5),
+    (0x3, 9, 6),
+    (0x2, 9, 7),
+    (0x2, 3, 12),
+    (0x5, 7, 13),
+    (0x4, 7, 14),
+    (0x5, 8, 15),
+    (0x1, 9, MCBPC_STUFFING),
+];
+
+/// Luma coded block patterns for intra macroblocks.
+const CBPY: &[(u16, u8, u32)] = &[
+    (0x3, 4, 0),
+    (0x5, 5, 1),
+    (0x4, 5, 2),
+    (0x9, 4, 3),
+    (0x3, 5, 4),
+    (0x7, 4, 5),
+    (0x2, 6, 6),
+    (0xb, 4, 7),
+    (0x2, 5, 8),
+    (0x3, 6, 9),
+    (0x5, 4, 10),
+    (0xa, 4, 11),
+    (0x4, 4, 12),
+    (0x8, 4, 13),
+    (0x6, 4, 14),
+    (0x3, 2, 15),
+];
+
+/// Motion vector difference magnitudes, in half pixels.
+const MVD: &[(u16, u8, i32)] = &[
+    (0x1, 1, 0),
+    (0x1, 2, 1),
+    (0x1, 3, 2),
+    (0x1, 4, 3),
+    (0x3, 6, 4),
+    (0x5, 7, 5),
+    (0x4, 7, 6),
+    (0x3, 7, 7),
+    (0xb, 9, 8),
+    (0xa, 9, 9),
+    (0x9, 9, 10),
+    (0x11, 10, 11),
+    (0x10, 10, 12),
+    (0xf, 10, 13),
+    (0xe, 10, 14),
+    (0xd, 10, 15),
+    (0xc, 10, 16),
+    (0xb, 10, 17),
+    (0xa, 10, 18),
+    (0x9, 10, 19),
+    (0x8, 10, 20),
+    (0x7, 10, 21),
+    (0x6, 10, 22),
+    (0x5, 10, 23),
+    (0x4, 10, 24),
+    (0x7, 11, 25),
+    (0x6, 11, 26),
+    (0x5, 11, 27),
+    (0x4, 11, 28),
+    (0x3, 11, 29),
+    (0x2, 11, 30),
+    (0x3, 12, 31),
+    (0x2, 12, 32),
+];
+
+/// A run-length coded coefficient as `(last, run, level)`.
+type Coefficient = (bool, u8, i16);
+
+/// Transform coefficients, followed by a sign bit.
+/// `None` is the escape code for coefficients that aren't in the table.
+const TCOEF: &[(u16, u8, Option<Coefficient>)] = &[
+    (0x2, 2, Some((false, 0, 1))),
+    (0xf, 4, Some((false, 0, 2))),
+    (0x15, 6, Some((false, 0, 3))),
+    (0x17, 7, Some((false, 0, 4))),
+    (0x1f, 8, Some((false, 0, 5))),
+    (0x25, 9, Some((false, 0, 6))),
+    (0x24, 9, Some((false, 0, 7))),
+    (0x21, 10, Some((false, 0, 8))),
+    (0x20, 10, Some((false, 0, 9))),
+    (0x7, 11, Some((false, 0, 10))),
+    (0x6, 11, Some((false, 0, 11))),
+    (0x20, 11, Some((false, 0, 12))),
+    (0x6, 3, Some((false, 1, 1))),
+    (0x14, 6, Some((false, 1, 2))),
+    (0x1e, 8, Some((false, 1, 3))),
+    (0xf, 10, Some((false, 1, 4))),
+    (0x21, 11, Some((false, 1, 5))),
+    (0x50, 12, Some((false, 1, 6))),
+    (0xe, 4, Some((false, 2, 1))),
+    (0x1d, 8, Some((false, 2, 2))),
+    (0xe, 10, Some((false, 2, 3))),
+    (0x51, 12, Some((false, 2, 4))),
+    (0xd, 5, Some((false, 3, 1))),
+    (0x23, 9, Some((false, 3, 2))),
+    (0xd, 10, Some((false, 3, 3))),
+    (0xc, 5, Some((false, 4, 1))),
+    (0x22, 9, Some((false, 4, 2))),
+    (0x52, 12, Some((false, 4, 3))),
+    (0xb, 5, Some((false, 5, 1))),
+    (0xc, 10, Some((false, 5, 2))),
+    (0x53, 12, Some((false, 5, 3))),
+    (0x13, 6, Some((false, 6, 1))),
+    (0xb, 10, Some((false, 6, 2))),
+    (0x54, 12, Some((false, 6, 3))),
+    (0x12, 6, Some((false, 7, 1))),
+    (0xa, 10, Some((false, 7, 2))),
+    (0x11, 6, Some((false, 8, 1))),
+    (0x9, 10, Some((false, 8, 2))),
+    (0x10, 6, Some((false, 9, 1))),
+    (0x8, 10, Some((false, 9, 2))),
+    (0x16, 7, Some((false, 10, 1))),
+    (0x55, 12, Some((false, 10, 2))),
+    (0x15, 7, Some((false, 11, 1))),
+    (0x14, 7, Some((false, 12, 1))),
+    (0x1c, 8, Some((false, 13, 1))),
+    (0x1b, 8, Some((false, 14, 1))),
+    (0x21, 9, Some((false, 15, 1))),
+    (0x20, 9, Some((false, 16, 1))),
+    (0x1f, 9, Some((false, 17, 1))),
+    (0x1e, 9, Some((false, 18, 1))),
+    (0x1d, 9, Some((false, 19, 1))),
+    (0x1c, 9, Some((false, 20, 1))),
+    (0x1b, 9, Some((false, 21, 1))),
+    (0x1a, 9, Some((false, 22, 1))),
+    (0x22, 11, Some((false, 23, 1))),
+    (0x23, 11, Some((false, 24, 1))),
+    (0x56, 12, Some((false, 25, 1))),
+    (0x57, 12, Some((false, 26, 1))),
+    (0x7, 4, Some((true, 0, 1))),
+    (0x19, 9, Some((true, 0, 2))),
+    (0x5, 11, Some((true, 0, 3))),
+    (0xf, 6, Some((true, 1, 1))),
+    (0x4, 11, Some((true, 1, 2))),
+    (0xe, 6, Some((true, 2, 1))),
+    (0xd, 6, Some((true, 3, 1))),
+    (0xc, 6, Some((true, 4, 1))),
+    (0x13, 7, Some((true, 5, 1))),
+    (0x12, 7, Some((true, 6, 1))),
+    (0x11, 7, Some((true, 7, 1))),
+    (0x10, 7, Some((true, 8, 1))),
+    (0x1a, 8, Some((true, 9, 1))),
+    (0x19, 8, Some((true, 10, 1))),
+    (0x18, 8, Some((true, 11, 1))),
+    (0x17, 8, Some((true, 12, 1))),
+    (0x16, 8, Some((true, 13, 1))),
+    (0x15, 8, Some((true, 14, 1))),
+    (0x14, 8, Some((true, 15, 1))),
+    (0x13, 8, Some((true, 16, 1))),
+    (0x18, 9, Some((true, 17, 1))),
+    (0x17, 9, Some((true, 18, 1))),
+    (0x16, 9, Some((true, 19, 1))),
+    (0x15, 9, Some((true, 20, 1))),
+    (0x14, 9, Some((true, 21, 1))),
+    (0x13, 9, Some((true, 22, 1))),
+    (0x12, 9, Some((true, 23, 1))),
+    (0x11, 9, Some((true, 24, 1))),
+    (0x7, 10, Some((true, 25, 1))),
+    (0x6, 10, Some((true, 26, 1))),
+    (0x5, 10, Some((true, 27, 1))),
+    (0x4, 10, Some((true, 28, 1))),
+    (0x24, 11, Some((true, 29, 1))),
+    (0x25, 11, Some((true, 30, 1))),
+    (0x26, 11, Some((true, 31, 1))),
+    (0x27, 11, Some((true, 32, 1))),
+    (0x58, 12, Some((true, 33, 1))),
+    (0x59, 12, Some((true, 34, 1))),
+    (0x5a, 12, Some((true, 35, 1))),
+    (0x5b, 12, Some((true, 36, 1))),
+    (0x5c, 12, Some((true, 37, 1))),
+    (0x5d, 12, Some((true, 38, 1))),
+    (0x5e, 12, Some((true, 39, 1))),
+    (0x5f, 12, Some((true, 40, 1))),
+    (0x3, 7, None),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swf::VideoCodec;
+
+    /// Packs `(value, num_bits)` fields into bytes, most significant bit first.
+    fn pack_bits(fields: &[(u32, u32)]) -> Vec<u8> {
+        let mut bytes = vec![];
+        let mut bit_pos = 0;
+        for &(value, num_bits) in fields {
+            for i in (0..num_bits).rev() {
+                if bit_pos % 8 == 0 {
+                    bytes.push(0);
+                }
+                let bit = ((value >> i) & 1) as u8;
+                *bytes.last_mut().unwrap() |= bit << (7 - bit_pos % 8);
+                bit_pos += 1;
+            }
+        }
+        bytes
+    }
+
+    fn picture_header(picture_type: u32) -> Vec<(u32, u32)> {
+        vec![
+            (1, 17),
+            (0, 5),
+            (0, 8),
+            (0, 3),
+            (16, 8),
+            (16, 8),
+            (picture_type, 2),
+            (0, 1),
+            (1, 5),
+            (0, 1),
+        ]
+    }
+
+    fn frame(data: &[u8]) -> EncodedFrame<'_> {
+        EncodedFrame {
+            codec: VideoCodec::H263,
+            data,
+            frame_id: 0,
+        }
+    }
+
+    #[test]
+    fn tables_are_prefix_free() {
+        fn check<T>(table: &[(u16, u8, T)]) {
+            for (i, &(code_a, len_a, _)) in table.iter().enumerate() {
+                for &(code_b, len_b, _) in &table[i + 1..] {
+                    let len = len_a.min(len_b);
+                    assert_ne!(code_a >> (len_a - len), code_b >> (len_b - len));
+                }
+            }
+        }
+        check(INTRA_MCBPC);
+        check(INTER_MCBPC);
+        check(CBPY);
+        check(MVD);
+        check(TCOEF);
+    }
+
+    #[test]
+    fn decode_flat_frames() {
+        // A single intra macroblock with only DC coefficients, which paint it white.
+        let mut intra = picture_header(0);
+        intra.extend_from_slice(&[(1, 1), (0b0011, 4)]);
+        intra.extend_from_slice(&[(235, 8); 4]);
+        intra.extend_from_slice(&[(255, 8); 2]);
+        let intra = pack_bits(&intra);
+
+        // A disposable inter frame that skips its only macroblock.
+        let mut inter = picture_header(2);
+        inter.push((1, 1));
+        let inter = pack_bits(&inter);
+
+        let mut decoder = H263Decoder::new();
+        assert_eq!(
+            decoder.preload_frame(frame(&intra)).unwrap(),
+            FrameDependency::None
+        );
+        assert_eq!(
+            decoder.preload_frame(frame(&inter)).unwrap(),
+            FrameDependency::Past
+        );
+        assert!(decoder.decode_frame(frame(&inter)).is_err());
+
+        let decoded = decoder.decode_frame(frame(&intra)).unwrap();
+        assert_eq!((decoded.width, decoded.height), (16, 16));
+        assert!(decoded.rgba.iter().all(|&c| c == 255));
+
+        let decoded = decoder.decode_frame(frame(&inter)).unwrap();
+        assert!(decoded.rgba.iter().all(|&c| c == 255));
+    }
+
+    #[test]
+    fn decode_swf_frames() {
+        // The frames of `swf/tests/swfs/DefineVideoStream.swf`, a solid red square.
+        let frames: [&[u8]; 4] = [
+            &[
+                0x00, 0x00, 0x84, 0x00, 0x04, 0x04, 0x11, 0x26, 0xbe, 0xbe, 0xbe, 0xbe, 0xc9, 0xb6,
+            ],
+            &[0x00, 0x00, 0x84, 0x04, 0x04, 0x04, 0x31, 0x20],
+            &[0x00, 0x00, 0x84, 0x08, 0x04, 0x04, 0x31, 0x20],
+            &[0x00, 0x00, 0x84, 0x0c, 0x04, 0x04, 0x31, 0x20],
+        ];
+        let mut decoder = H263Decoder::new();
+        for data in &frames {
+            let decoded = decoder.decode_frame(frame(data)).unwrap();
+            assert_eq!((decoded.width, decoded.height), (8, 8));
+            assert!(decoded.rgba.chunks(4).all(|c| c == [237, 29, 36, 255]));
+        }
+    }
+
+    #[test]
+    fn inverse_dct() {
+        let idct = Idct::new();
+        let mut coefficients = [0; 64];
+        coefficients[0] = 800;
+        assert_eq!(idct.transform(&coefficients), [100; 64]);
+    }
+}
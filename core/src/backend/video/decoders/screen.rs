@@ -0,0 +1,208 @@
+//! Screen Video decoder.
+//!
+//! Screen Video splits each frame into a grid of blocks. Each block is either a zlib-compressed
+//! image, or empty when it is unchanged from the previous frame.
+
+use super::{DecodedFrame, Error, VideoDecoder};
+use crate::backend::video::{EncodedFrame, FrameDependency};
+use flate2::read::ZlibDecoder;
+use std::io::Read;
+
+/// Decodes a Screen Video stream.
+pub struct ScreenVideoDecoder {
+    /// The previously decoded frame as RGBA, which unchanged blocks are kept from.
+    last_frame: Option<DecodedFrame>,
+}
+
+impl ScreenVideoDecoder {
+    pub fn new() -> Self {
+        Self { last_frame: None }
+    }
+}
+
+impl Default for ScreenVideoDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VideoDecoder for ScreenVideoDecoder {
+    fn preload_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<FrameDependency, Error> {
+        let (header, mut data) = FrameHeader::read(encoded_frame.data)?;
+        for _ in 0..header.num_blocks() {
+            let block = read_block_data(&mut data)?;
+            if block.is_empty() {
+                return Ok(FrameDependency::Past);
+            }
+        }
+        Ok(FrameDependency::None)
+    }
+
+    fn decode_frame(&mut self, encoded_frame: EncodedFrame<'_>) -> Result<DecodedFrame, Error> {
+        let (header, mut data) = FrameHeader::read(encoded_frame.data)?;
+        let (width, height) = (usize::from(header.width), usize::from(header.height));
+
+        let last_frame = self
+            .last_frame
+            .take()
+            .filter(|frame| frame.width == header.width && frame.height == header.height);
+        let has_last_frame = last_frame.is_some();
+        let mut rgba = last_frame
+            .map(|frame| frame.rgba)
+            .unwrap_or_else(|| vec![0; width * height * 4]);
+
+        // Blocks are stored from the bottom of the image up, and so are the rows within them.
+        let mut block_pixels = vec![];
+        for block_y in 0..header.blocks_high() {
+            for block_x in 0..header.blocks_wide() {
+                let block = read_block_data(&mut data)?;
+                if block.is_empty() {
+                    if !has_last_frame {
+                        return Err("Screen Video frame without a previous frame".into());
+                    }
+                    continue;
+                }
+
+                let x = block_x * header.block_width;
+                let bottom = block_y * header.block_height;
+                let block_width = header.block_width.min(width - x);
+                let block_height = header.block_height.min(height - bottom);
+
+                block_pixels.clear();
+                ZlibDecoder::new(block).read_to_end(&mut block_pixels)?;
+                if block_pixels.len() < block_width * block_height * 3 {
+                    return Err("Screen Video block is too short".into());
+                }
+
+                for (row, bgr_row) in block_pixels
+                    .chunks_exact(block_width * 3)
+                    .take(block_height)
+                    .enumerate()
+                {
+                    let y = height - 1 - (bottom + row);
+                    let start = (y * width + x) * 4;
+                    let output = &mut rgba[start..start + block_width * 4];
+                    for (pixel, bgr) in output.chunks_exact_mut(4).zip(bgr_row.chunks_exact(3)) {
+                        pixel.copy_from_slice(&[bgr[2], bgr[1], bgr[0], 255]);
+                    }
+                }
+            }
+        }
+
+        let frame = DecodedFrame {
+            width: header.width,
+            height: header.height,
+            rgba: rgba.clone(),
+        };
+        self.last_frame = Some(DecodedFrame {
+            width: header.width,
+            height: header.height,
+            rgba,
+        });
+        Ok(frame)
+    }
+}
+
+struct FrameHeader {
+    block_width: usize,
+    block_height: usize,
+    width: u16,
+    height: u16,
+}
+
+impl FrameHeader {
+    /// Reads the header of a frame, returning it with the block data that follows.
+    fn read(data: &[u8]) -> Result<(Self, &[u8]), Error> {
+        if data.len() < 4 {
+            return Err("Screen Video frame is too short".into());
+        }
+        let horizontal = u16::from_be_bytes([data[0], data[1]]);
+        let vertical = u16::from_be_bytes([data[2], data[3]]);
+        let header = Self {
+            block_width: (usize::from(horizontal >> 12) + 1) * 16,
+            width: horizontal & 0xfff,
+            block_height: (usize::from(vertical >> 12) + 1) * 16,
+            height: vertical & 0xfff,
+        };
+        Ok((header, &data[4..]))
+    }
+
+    fn blocks_wide(&self) -> usize {
+        usize::from(self.width).div_ceil(self.block_width)
+    }
+
+    fn blocks_high(&self) -> usize {
+        usize::from(self.height).div_ceil(self.block_height)
+    }
+
+    fn num_blocks(&self) -> usize {
+        self.blocks_wide() * self.blocks_high()
+    }
+}
+
+/// Reads the size-prefixed data of the next block. Unchanged blocks have no data.
+fn read_block_data<'a>(data: &mut &'a [u8]) -> Result<&'a [u8], Error> {
+    if data.len() < 2 {
+        return Err("Unexpected end of Screen Video data".into());
+    }
+    let size = usize::from(u16::from_be_bytes([data[0], data[1]]));
+    let block = data
+        .get(2..2 + size)
+        .ok_or("Unexpected end of Screen Video data")?;
+    *data = &data[2 + size..];
+    Ok(block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+    use swf::VideoCodec;
+
+    fn compress(data: &[u8]) -> Vec<u8> {
+        let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn frame(data: &[u8]) -> EncodedFrame<'_> {
+        EncodedFrame {
+            codec: VideoCodec::ScreenVideo,
+            data,
+            frame_id: 0,
+        }
+    }
+
+    #[test]
+    fn decode_frames() {
+        // A 2x2 image in a single 16x16 block, with its bottom row stored first.
+        let block = compress(&[255, 0, 0, 0, 255, 0, 0, 0, 255, 255, 255, 255]);
+        let mut keyframe = vec![0x00, 0x02, 0x00, 0x02];
+        keyframe.extend_from_slice(&(block.len() as u16).to_be_bytes());
+        keyframe.extend_from_slice(&block);
+        let unchanged = [0x00, 0x02, 0x00, 0x02, 0x00, 0x00];
+
+        let mut decoder = ScreenVideoDecoder::new();
+        assert_eq!(
+            decoder.preload_frame(frame(&keyframe)).unwrap(),
+            FrameDependency::None
+        );
+        assert_eq!(
+            decoder.preload_frame(frame(&unchanged)).unwrap(),
+            FrameDependency::Past
+        );
+        assert!(decoder.decode_frame(frame(&unchanged)).is_err());
+
+        let expected = [
+            255, 0, 0, 255, 255, 255, 255, 255, //
+            0, 0, 255, 255, 0, 255, 0, 255,
+        ];
+        let decoded = decoder.decode_frame(frame(&keyframe)).unwrap();
+        assert_eq!((decoded.width, decoded.height), (2, 2));
+        assert_eq!(decoded.rgba, expected);
+        let decoded = decoder.decode_frame(frame(&unchanged)).unwrap();
+        assert_eq!(decoded.rgba, expected);
+    }
+}
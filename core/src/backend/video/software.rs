@@ -0,0 +1,89 @@
+//! A video backend that decodes frames on the CPU.
+
+use crate::backend::render::{BitmapHandle, BitmapInfo, RenderBackend};
+use crate::backend::video::decoders::{self, VideoDecoder};
+use crate::backend::video::{EncodedFrame, FrameDependency, VideoBackend, VideoStreamHandle};
+use generational_arena::Arena;
+use swf::{VideoCodec, VideoDeblocking};
+
+type Error = Box<dyn std::error::Error>;
+
+/// Decodes Sorenson H.263 and Screen Video streams in software.
+///
+/// Decoded frames are uploaded to the renderer as bitmaps.
+pub struct SoftwareVideoBackend {
+    streams: Arena<VideoStream>,
+}
+
+struct VideoStream {
+    decoder: Box<dyn VideoDecoder>,
+    bitmap: Option<BitmapHandle>,
+}
+
+impl SoftwareVideoBackend {
+    pub fn new() -> Self {
+        Self {
+            streams: Arena::new(),
+        }
+    }
+}
+
+impl Default for SoftwareVideoBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VideoBackend for SoftwareVideoBackend {
+    fn register_video_stream(
+        &mut self,
+        _num_frames: u32,
+        _size: (u16, u16),
+        codec: VideoCodec,
+        _deblocking: VideoDeblocking,
+    ) -> Result<VideoStreamHandle, Error> {
+        let decoder = decoders::make_decoder(codec)?;
+        Ok(self.streams.insert(VideoStream {
+            decoder,
+            bitmap: None,
+        }))
+    }
+
+    fn preload_video_stream_frame(
+        &mut self,
+        stream: VideoStreamHandle,
+        encoded_frame: EncodedFrame<'_>,
+    ) -> Result<FrameDependency, Error> {
+        let stream = self
+            .streams
+            .get_mut(stream)
+            .ok_or("Unregistered video stream")?;
+        stream.decoder.preload_frame(encoded_frame)
+    }
+
+    fn decode_video_stream_frame(
+        &mut self,
+        stream: VideoStreamHandle,
+        encoded_frame: EncodedFrame<'_>,
+        renderer: &mut dyn RenderBackend,
+    ) -> Result<BitmapInfo, Error> {
+        let stream = self
+            .streams
+            .get_mut(stream)
+            .ok_or("Unregistered video stream")?;
+        let frame = stream.decoder.decode_frame(encoded_frame)?;
+
+        let handle = if let Some(bitmap) = stream.bitmap {
+            renderer.update_texture(bitmap, frame.width.into(), frame.height.into(), frame.rgba)?
+        } else {
+            renderer.register_bitmap_raw(frame.width.into(), frame.height.into(), frame.rgba)?
+        };
+        stream.bitmap = Some(handle);
+
+        Ok(BitmapInfo {
+            handle,
+            width: frame.width,
+            height: frame.height,
+        })
+    }
+}
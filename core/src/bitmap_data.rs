@@ -0,0 +1,890 @@
+//! Bitmaps that are kept in memory, so that movies can read and modify their pixels with
+//! the `BitmapData` class.
+
+use crate::backend::render::{self, BitmapFormat, BitmapHandle, RenderBackend};
+use crate::color_transform::ColorTransform;
+use gc_arena::Collect;
+use std::cell::Cell;
+use std::convert::TryFrom;
+use std::fmt;
+
+mod draw;
+mod rasterizer;
+
+pub use draw::{draw_bitmap_data, draw_display_object};
+
+/// The largest width or height of a `BitmapData`, in pixels.
+pub const MAX_SIZE: u32 = 2880;
+
+/// A rectangle of pixels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PixelRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl PixelRect {
+    pub fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+/// The channels of a pixel that `noise` and `perlinNoise` generate, as used by the
+/// `BitmapDataChannel` constants.
+pub mod channel {
+    pub const RED: u8 = 1;
+    pub const GREEN: u8 = 2;
+    pub const BLUE: u8 = 4;
+    pub const ALPHA: u8 = 8;
+}
+
+/// The comparison used by `threshold`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThresholdOperation {
+    LessThan,
+    LessThanOrEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl ThresholdOperation {
+    pub fn from_str(operation: &str) -> Option<Self> {
+        Some(match operation {
+            "<" => ThresholdOperation::LessThan,
+            "<=" => ThresholdOperation::LessThanOrEqual,
+            ">" => ThresholdOperation::GreaterThan,
+            ">=" => ThresholdOperation::GreaterThanOrEqual,
+            "==" => ThresholdOperation::Equal,
+            "!=" => ThresholdOperation::NotEqual,
+            _ => return None,
+        })
+    }
+
+    fn matches(self, value: u32, threshold: u32) -> bool {
+        match self {
+            ThresholdOperation::LessThan => value < threshold,
+            ThresholdOperation::LessThanOrEqual => value <= threshold,
+            ThresholdOperation::GreaterThan => value > threshold,
+            ThresholdOperation::GreaterThanOrEqual => value >= threshold,
+            ThresholdOperation::Equal => value == threshold,
+            ThresholdOperation::NotEqual => value != threshold,
+        }
+    }
+}
+
+/// The parameters of `perlin_noise`.
+#[derive(Clone, Debug)]
+pub struct PerlinNoise {
+    /// The size of the first octave, in pixels.
+    pub base_x: f64,
+    pub base_y: f64,
+    pub num_octaves: usize,
+    pub seed: i32,
+    /// Whether the noise tiles seamlessly across the edges of the bitmap.
+    pub stitch: bool,
+    /// Whether to generate fractal noise, rather than turbulence.
+    pub fractal_noise: bool,
+    pub channels: u8,
+    pub gray_scale: bool,
+    /// The offset of each octave, in pixels.
+    pub offsets: Vec<(f64, f64)>,
+}
+
+/// A bitmap whose pixels are kept in memory, and uploaded to the renderer when they change.
+#[derive(Collect)]
+#[collect(require_static)]
+pub struct BitmapData {
+    width: u32,
+    height: u32,
+    transparent: bool,
+
+    /// The pixels as ARGB without premultiplied alpha, row by row.
+    pixels: Vec<u32>,
+
+    disposed: bool,
+
+    /// The texture the pixels were last uploaded to.
+    bitmap_handle: Cell<Option<BitmapHandle>>,
+
+    /// Whether the pixels changed since they were last uploaded.
+    dirty: Cell<bool>,
+}
+
+impl fmt::Debug for BitmapData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BitmapData")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("transparent", &self.transparent)
+            .field("disposed", &self.disposed)
+            .finish()
+    }
+}
+
+impl Clone for BitmapData {
+    /// Copies the pixels. The copy is uploaded to a texture of its own.
+    fn clone(&self) -> Self {
+        Self {
+            width: self.width,
+            height: self.height,
+            transparent: self.transparent,
+            pixels: self.pixels.clone(),
+            disposed: self.disposed,
+            bitmap_handle: Cell::new(None),
+            dirty: Cell::new(true),
+        }
+    }
+}
+
+impl BitmapData {
+    /// Creates a bitmap filled with a single color.
+    ///
+    /// Opaque bitmaps ignore the alpha of `fill_color`.
+    pub fn new(width: u32, height: u32, transparent: bool, fill_color: u32) -> Self {
+        let fill_color = if transparent {
+            fill_color
+        } else {
+            fill_color | 0xFF00_0000
+        };
+        Self {
+            width,
+            height,
+            transparent,
+            pixels: vec![fill_color; (width * height) as usize],
+            disposed: false,
+            bitmap_handle: Cell::new(None),
+            dirty: Cell::new(true),
+        }
+    }
+
+    /// Creates a bitmap from one decoded from an SWF tag.
+    pub fn from_decoded(bitmap: render::Bitmap) -> Self {
+        let (transparent, pixels) = match bitmap.data {
+            BitmapFormat::Rgb(data) => (
+                false,
+                data.chunks_exact(3)
+                    .map(|rgb| argb(0xFF, rgb[0], rgb[1], rgb[2]))
+                    .collect(),
+            ),
+            BitmapFormat::Rgba(mut data) => {
+                render::unmultiply_alpha_rgba(&mut data);
+                (
+                    true,
+                    data.chunks_exact(4)
+                        .map(|rgba| argb(rgba[3], rgba[0], rgba[1], rgba[2]))
+                        .collect(),
+                )
+            }
+        };
+        Self {
+            width: bitmap.width,
+            height: bitmap.height,
+            transparent,
+            pixels,
+            disposed: false,
+            bitmap_handle: Cell::new(None),
+            dirty: Cell::new(true),
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn transparent(&self) -> bool {
+        self.transparent
+    }
+
+    pub fn disposed(&self) -> bool {
+        self.disposed
+    }
+
+    /// Replaces the pixels with those of another bitmap, keeping the texture they are
+    /// uploaded to.
+    pub fn replace_with(&mut self, other: BitmapData) {
+        self.width = other.width;
+        self.height = other.height;
+        self.transparent = other.transparent;
+        self.pixels = other.pixels;
+        self.disposed = other.disposed;
+        self.dirty.set(true);
+    }
+
+    /// Frees the pixels. The bitmap is then empty, and can no longer be used.
+    pub fn dispose(&mut self) {
+        self.width = 0;
+        self.height = 0;
+        self.pixels = Vec::new();
+        self.disposed = true;
+        self.dirty.set(true);
+    }
+
+    /// Returns the pixel at the given position as ARGB, or 0 if it is outside the bitmap.
+    pub fn get_pixel32(&self, x: i32, y: i32) -> u32 {
+        self.index(x, y).map(|i| self.pixels[i]).unwrap_or(0)
+    }
+
+    /// Returns the pixel at the given position as RGB, or 0 if it is outside the bitmap.
+    pub fn get_pixel(&self, x: i32, y: i32) -> u32 {
+        self.get_pixel32(x, y) & 0x00FF_FFFF
+    }
+
+    pub fn set_pixel32(&mut self, x: i32, y: i32, color: u32) {
+        if let Some(i) = self.index(x, y) {
+            self.pixels[i] = self.opaque_if_needed(color);
+            self.dirty.set(true);
+        }
+    }
+
+    /// Sets the RGB of the pixel at the given position, keeping its alpha.
+    pub fn set_pixel(&mut self, x: i32, y: i32, color: u32) {
+        if let Some(i) = self.index(x, y) {
+            self.pixels[i] = (self.pixels[i] & 0xFF00_0000) | (color & 0x00FF_FFFF);
+            self.dirty.set(true);
+        }
+    }
+
+    pub fn fill_rect(&mut self, rect: PixelRect, color: u32) {
+        let color = self.opaque_if_needed(color);
+        if let Some((x_min, y_min, x_max, y_max)) = self.clip(rect) {
+            for y in y_min..y_max {
+                let row = (y * self.width as i32) as usize;
+                for pixel in &mut self.pixels[row + x_min as usize..row + x_max as usize] {
+                    *pixel = color;
+                }
+            }
+            self.dirty.set(true);
+        }
+    }
+
+    /// Copies a rectangle of `source` to `dest` in this bitmap.
+    ///
+    /// The alpha of the copied pixels is multiplied by the alpha of `alpha_source`, starting
+    /// from the given position in it. The copied pixels replace the existing ones, unless
+    /// `merge_alpha` is set, in which case they are blended over them.
+    pub fn copy_pixels(
+        &mut self,
+        source: &BitmapData,
+        source_rect: PixelRect,
+        dest: (i32, i32),
+        alpha_source: Option<(&BitmapData, (i32, i32))>,
+        merge_alpha: bool,
+    ) {
+        let (x_min, y_min, x_max, y_max) = match source.clip(source_rect) {
+            Some(bounds) => bounds,
+            None => return,
+        };
+        for y in y_min..y_max {
+            for x in x_min..x_max {
+                let i = match offset_point(dest, (x, y), source_rect)
+                    .and_then(|(dest_x, dest_y)| self.index(dest_x, dest_y))
+                {
+                    Some(i) => i,
+                    None => continue,
+                };
+
+                let mut color = source.get_pixel32(x, y);
+                if let Some((alpha_source, alpha_point)) = alpha_source {
+                    let (alpha_x, alpha_y) = match offset_point(alpha_point, (x, y), source_rect) {
+                        Some(point) => point,
+                        None => continue,
+                    };
+                    let alpha = alpha_source.get_pixel32(alpha_x, alpha_y) >> 24;
+                    color = with_alpha(color, (alpha_of(color) * alpha / 255) as u8);
+                }
+                self.pixels[i] = if merge_alpha {
+                    self.opaque_if_needed(blend(self.pixels[i], color))
+                } else {
+                    self.opaque_if_needed(color)
+                };
+            }
+        }
+        self.dirty.set(true);
+    }
+
+    pub fn color_transform(&mut self, rect: PixelRect, color_transform: &ColorTransform) {
+        if let Some((x_min, y_min, x_max, y_max)) = self.clip(rect) {
+            for y in y_min..y_max {
+                for x in x_min..x_max {
+                    let i = (y * self.width as i32 + x) as usize;
+                    self.pixels[i] =
+                        self.opaque_if_needed(transform_color(self.pixels[i], color_transform));
+                }
+            }
+            self.dirty.set(true);
+        }
+    }
+
+    /// Fills the bitmap with random values between `low` and `high` in the given channels.
+    ///
+    /// The same seed always generates the same noise. Other color channels are set to 0, and
+    /// alpha to 255.
+    pub fn noise(&mut self, seed: i32, low: u8, high: u8, channels: u8, gray_scale: bool) {
+        let (low, high) = (u32::from(low), u32::from(high.max(low)));
+        let mut random = ParkMiller::new(seed);
+        let mut next = || (low + random.next() % (high - low + 1)) as u8;
+        for i in 0..self.pixels.len() {
+            let (r, g, b) = if gray_scale {
+                let value = next();
+                (value, value, value)
+            } else {
+                let r = if channels & channel::RED != 0 {
+                    next()
+                } else {
+                    0
+                };
+                let g = if channels & channel::GREEN != 0 {
+                    next()
+                } else {
+                    0
+                };
+                let b = if channels & channel::BLUE != 0 {
+                    next()
+                } else {
+                    0
+                };
+                (r, g, b)
+            };
+            let a = if channels & channel::ALPHA != 0 {
+                next()
+            } else {
+                0xFF
+            };
+            self.pixels[i] = self.opaque_if_needed(argb(a, r, g, b));
+        }
+        self.dirty.set(true);
+    }
+
+    /// Fills the bitmap with Perlin noise in the given channels.
+    ///
+    /// Other color channels are set to 0, and alpha to 255.
+    pub fn perlin_noise(&mut self, options: &PerlinNoise) {
+        let noise = PerlinGenerator::new(options.seed);
+        let mut channels = vec![];
+        if options.gray_scale {
+            channels.push(0);
+        } else {
+            for (i, flag) in [channel::RED, channel::GREEN, channel::BLUE]
+                .iter()
+                .enumerate()
+            {
+                if options.channels & flag != 0 {
+                    channels.push(i);
+                }
+            }
+        }
+        if options.channels & channel::ALPHA != 0 {
+            channels.push(3);
+        }
+
+        let stitch_size = if options.stitch {
+            Some((self.width as f64, self.height as f64))
+        } else {
+            None
+        };
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let mut components = [0, 0, 0, 0xFF];
+                for &channel in &channels {
+                    let value = noise.sample(options, stitch_size, x, y, channel);
+                    components[channel] = (value * 255.0).max(0.0).min(255.0) as u8;
+                }
+                if options.gray_scale {
+                    components[1] = components[0];
+                    components[2] = components[0];
+                }
+                let [r, g, b, a] = components;
+                let i = (y * self.width + x) as usize;
+                self.pixels[i] = self.opaque_if_needed(argb(a, r, g, b));
+            }
+        }
+        self.dirty.set(true);
+    }
+
+    /// Tests the pixels of a rectangle of `source` against a threshold, setting the matching
+    /// ones to `color` at `dest` in this bitmap. Pixels that don't match are copied from
+    /// `source` if `copy_source` is set.
+    ///
+    /// Returns the number of matching pixels.
+    #[allow(clippy::too_many_arguments)]
+    pub fn threshold(
+        &mut self,
+        source: &BitmapData,
+        source_rect: PixelRect,
+        dest: (i32, i32),
+        operation: ThresholdOperation,
+        threshold: u32,
+        color: u32,
+        mask: u32,
+        copy_source: bool,
+    ) -> u32 {
+        let (x_min, y_min, x_max, y_max) = match source.clip(source_rect) {
+            Some(bounds) => bounds,
+            None => return 0,
+        };
+        let mut count = 0;
+        for y in y_min..y_max {
+            for x in x_min..x_max {
+                let i = match offset_point(dest, (x, y), source_rect)
+                    .and_then(|(dest_x, dest_y)| self.index(dest_x, dest_y))
+                {
+                    Some(i) => i,
+                    None => continue,
+                };
+                let source_color = source.get_pixel32(x, y);
+                if operation.matches(source_color & mask, threshold & mask) {
+                    self.pixels[i] = self.opaque_if_needed(color);
+                    count += 1;
+                } else if copy_source {
+                    self.pixels[i] = self.opaque_if_needed(source_color);
+                }
+            }
+        }
+        self.dirty.set(true);
+        count
+    }
+
+    /// Sets the pixel at the given position, and the pixels connected to it that have the
+    /// same color, to `color`.
+    pub fn flood_fill(&mut self, x: i32, y: i32, color: u32) {
+        let color = self.opaque_if_needed(color);
+        let target = match self.index(x, y) {
+            Some(i) => self.pixels[i],
+            None => return,
+        };
+        if target == color {
+            return;
+        }
+
+        let mut pending = vec![(x, y)];
+        while let Some((x, y)) = pending.pop() {
+            match self.index(x, y) {
+                Some(i) if self.pixels[i] == target => self.pixels[i] = color,
+                _ => continue,
+            }
+            pending.push((x - 1, y));
+            pending.push((x + 1, y));
+            pending.push((x, y - 1));
+            pending.push((x, y + 1));
+        }
+        self.dirty.set(true);
+    }
+
+    /// Returns the texture to draw this bitmap with, uploading the pixels to the renderer if
+    /// they changed.
+    pub fn bitmap_handle(&self, renderer: &mut dyn RenderBackend) -> Option<BitmapHandle> {
+        if self.disposed || self.pixels.is_empty() {
+            return None;
+        }
+        if !self.dirty.get() {
+            return self.bitmap_handle.get();
+        }
+
+        let mut rgba = Vec::with_capacity(self.pixels.len() * 4);
+        for &color in &self.pixels {
+            let [a, r, g, b] = color.to_be_bytes();
+            rgba.extend_from_slice(&[r, g, b, a]);
+        }
+        render::premultiply_alpha_rgba(&mut rgba);
+
+        let handle = match self.bitmap_handle.get() {
+            Some(handle) => renderer.update_texture(handle, self.width, self.height, rgba),
+            None => renderer.register_bitmap_raw(self.width, self.height, rgba),
+        };
+        match handle {
+            Ok(handle) => {
+                self.bitmap_handle.set(Some(handle));
+                self.dirty.set(false);
+            }
+            Err(e) => log::error!("Unable to upload BitmapData: {}", e),
+        }
+        self.bitmap_handle.get()
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if x >= 0 && y >= 0 && (x as u32) < self.width && (y as u32) < self.height {
+            Some((y as u32 * self.width + x as u32) as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the part of a rectangle inside the bitmap, as `(x_min, y_min, x_max, y_max)`.
+    fn clip(&self, rect: PixelRect) -> Option<(i32, i32, i32, i32)> {
+        let x_min = rect.x.max(0);
+        let y_min = rect.y.max(0);
+        let x_max = rect.x.saturating_add(rect.width).min(self.width as i32);
+        let y_max = rect.y.saturating_add(rect.height).min(self.height as i32);
+        if x_min < x_max && y_min < y_max {
+            Some((x_min, y_min, x_max, y_max))
+        } else {
+            None
+        }
+    }
+
+    fn opaque_if_needed(&self, color: u32) -> u32 {
+        if self.transparent {
+            color
+        } else {
+            color | 0xFF00_0000
+        }
+    }
+
+    /// Blends a color over the pixel at the given position.
+    fn blend_pixel(&mut self, x: i32, y: i32, color: u32) {
+        if let Some(i) = self.index(x, y) {
+            self.pixels[i] = self.opaque_if_needed(blend(self.pixels[i], color));
+        }
+    }
+}
+
+/// Returns the position of the source pixel `(x, y)` relative to `point`, where `point` is
+/// where the top left of `source_rect` goes. Returns `None` if the position doesn't fit in an
+/// `i32`, as it then can't be inside any bitmap.
+fn offset_point(
+    point: (i32, i32),
+    (x, y): (i32, i32),
+    source_rect: PixelRect,
+) -> Option<(i32, i32)> {
+    let offset = |point: i32, value: i32, origin: i32| {
+        i32::try_from(i64::from(point) + i64::from(value) - i64::from(origin)).ok()
+    };
+    Some((
+        offset(point.0, x, source_rect.x)?,
+        offset(point.1, y, source_rect.y)?,
+    ))
+}
+
+fn argb(a: u8, r: u8, g: u8, b: u8) -> u32 {
+    u32::from_be_bytes([a, r, g, b])
+}
+
+fn alpha_of(color: u32) -> u32 {
+    color >> 24
+}
+
+fn with_alpha(color: u32, alpha: u8) -> u32 {
+    (color & 0x00FF_FFFF) | (u32::from(alpha) << 24)
+}
+
+/// Blends `src` over `dest`.
+fn blend(dest: u32, src: u32) -> u32 {
+    let [sa, sr, sg, sb] = src.to_be_bytes();
+    let [da, dr, dg, db] = dest.to_be_bytes();
+    if sa == 0xFF || da == 0 {
+        return src;
+    }
+    if sa == 0 {
+        return dest;
+    }
+
+    let sa = f32::from(sa) / 255.0;
+    let da = f32::from(da) / 255.0;
+    let a = sa + da * (1.0 - sa);
+    let channel =
+        |s: u8, d: u8| ((f32::from(s) * sa + f32::from(d) * da * (1.0 - sa)) / a).round() as u8;
+    argb(
+        (a * 255.0).round() as u8,
+        channel(sr, dr),
+        channel(sg, dg),
+        channel(sb, db),
+    )
+}
+
+fn transform_color(color: u32, color_transform: &ColorTransform) -> u32 {
+    let [a, r, g, b] = color.to_be_bytes();
+    let channel = |value: u8, mult: f32, add: f32| {
+        (f32::from(value) * mult + add * 255.0).max(0.0).min(255.0) as u8
+    };
+    argb(
+        channel(a, color_transform.a_mult, color_transform.a_add),
+        channel(r, color_transform.r_mult, color_transform.r_add),
+        channel(g, color_transform.g_mult, color_transform.g_add),
+        channel(b, color_transform.b_mult, color_transform.b_add),
+    )
+}
+
+/// The Park-Miller random number generator, which Flash Player seeds its bitmap noise with.
+struct ParkMiller(u32);
+
+impl ParkMiller {
+    const MODULUS: i64 = 2_147_483_647;
+
+    fn new(seed: i32) -> Self {
+        let seed = i64::from(seed).rem_euclid(Self::MODULUS);
+        ParkMiller(if seed == 0 { 1 } else { seed as u32 })
+    }
+
+    fn next(&mut self) -> u32 {
+        self.0 = (i64::from(self.0) * 16807 % Self::MODULUS) as u32;
+        self.0
+    }
+}
+
+/// Ken Perlin's improved noise, with the permutation shuffled by a seed.
+struct PerlinGenerator {
+    permutation: Vec<u8>,
+}
+
+impl PerlinGenerator {
+    fn new(seed: i32) -> Self {
+        let mut random = ParkMiller::new(seed);
+        let mut permutation: Vec<u8> = (0..=255).collect();
+        for i in (1..permutation.len()).rev() {
+            let j = random.next() as usize % (i + 1);
+            permutation.swap(i, j);
+        }
+        Self { permutation }
+    }
+
+    /// Returns the value of a channel of the noise at a pixel, between 0 and 1.
+    fn sample(
+        &self,
+        options: &PerlinNoise,
+        stitch_size: Option<(f64, f64)>,
+        x: u32,
+        y: u32,
+        channel: usize,
+    ) -> f64 {
+        let mut frequency_x = 1.0 / options.base_x.abs().max(1.0);
+        let mut frequency_y = 1.0 / options.base_y.abs().max(1.0);
+        let mut amplitude = 1.0;
+        let mut total = 0.0;
+        let mut total_amplitude = 0.0;
+        for octave in 0..options.num_octaves {
+            let (offset_x, offset_y) = options.offsets.get(octave).copied().unwrap_or_default();
+            let period = stitch_size.map(|(width, height)| {
+                (
+                    ((width * frequency_x).round() as i64).max(1),
+                    ((height * frequency_y).round() as i64).max(1),
+                )
+            });
+            let value = self.noise(
+                (f64::from(x) + offset_x) * frequency_x,
+                (f64::from(y) + offset_y) * frequency_y,
+                channel as f64 + 0.5,
+                period,
+            );
+            total += if options.fractal_noise {
+                value
+            } else {
+                value.abs()
+            } * amplitude;
+            total_amplitude += amplitude;
+            amplitude *= 0.5;
+            frequency_x *= 2.0;
+            frequency_y *= 2.0;
+        }
+
+        if total_amplitude == 0.0 {
+            return 0.0;
+        }
+        let value = total / total_amplitude;
+        if options.fractal_noise {
+            (value + 1.0) / 2.0
+        } else {
+            value
+        }
+    }
+
+    /// Returns the noise at a point, between -1 and 1. The lattice wraps around with the
+    /// given period, if any.
+    fn noise(&self, x: f64, y: f64, z: f64, period: Option<(i64, i64)>) -> f64 {
+        fn fade(t: f64) -> f64 {
+            t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+        }
+        fn lerp(t: f64, a: f64, b: f64) -> f64 {
+            a + t * (b - a)
+        }
+        fn grad(hash: u8, x: f64, y: f64, z: f64) -> f64 {
+            let h = hash & 15;
+            let u = if h < 8 { x } else { y };
+            let v = if h < 4 {
+                y
+            } else if h == 12 || h == 14 {
+                x
+            } else {
+                z
+            };
+            (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+        }
+
+        let (x_floor, y_floor, z_floor) = (x.floor(), y.floor(), z.floor());
+        let (x, y, z) = (x - x_floor, y - y_floor, z - z_floor);
+        let wrap = |value: i64, period: Option<i64>| match period {
+            Some(period) => value.rem_euclid(period),
+            None => value,
+        };
+        let (period_x, period_y) = match period {
+            Some((x, y)) => (Some(x), Some(y)),
+            None => (None, None),
+        };
+        let x0 = wrap(x_floor as i64, period_x);
+        let x1 = wrap(x_floor as i64 + 1, period_x);
+        let y0 = wrap(y_floor as i64, period_y);
+        let y1 = wrap(y_floor as i64 + 1, period_y);
+        let z0 = z_floor as i64;
+        let hash = |x: i64, y: i64, z: i64| {
+            let p = &self.permutation;
+            let h = p[(x & 255) as usize] as i64;
+            let h = p[((h + y) & 255) as usize] as i64;
+            p[((h + z) & 255) as usize]
+        };
+
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+        let corner = |cx: i64, cy: i64, cz: i64, dx: f64, dy: f64, dz: f64| {
+            grad(hash(cx, cy, cz), x - dx, y - dy, z - dz)
+        };
+        lerp(
+            w,
+            lerp(
+                v,
+                lerp(
+                    u,
+                    corner(x0, y0, z0, 0.0, 0.0, 0.0),
+                    corner(x1, y0, z0, 1.0, 0.0, 0.0),
+                ),
+                lerp(
+                    u,
+                    corner(x0, y1, z0, 0.0, 1.0, 0.0),
+                    corner(x1, y1, z0, 1.0, 1.0, 0.0),
+                ),
+            ),
+            lerp(
+                v,
+                lerp(
+                    u,
+                    corner(x0, y0, z0 + 1, 0.0, 0.0, 1.0),
+                    corner(x1, y0, z0 + 1, 1.0, 0.0, 1.0),
+                ),
+                lerp(
+                    u,
+                    corner(x0, y1, z0 + 1, 0.0, 1.0, 1.0),
+                    corner(x1, y1, z0 + 1, 1.0, 1.0, 1.0),
+                ),
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opaque_bitmaps_ignore_alpha() {
+        let mut bitmap = BitmapData::new(2, 2, false, 0x0012_3456);
+        assert_eq!(bitmap.get_pixel32(0, 0), 0xFF12_3456);
+        bitmap.set_pixel32(1, 1, 0x8000_FF00);
+        assert_eq!(bitmap.get_pixel32(1, 1), 0xFF00_FF00);
+        assert_eq!(bitmap.get_pixel32(2, 0), 0);
+
+        let mut bitmap = BitmapData::new(2, 2, true, 0x8012_3456);
+        bitmap.set_pixel(0, 0, 0x00AB_CDEF);
+        assert_eq!(bitmap.get_pixel32(0, 0), 0x80AB_CDEF);
+        assert_eq!(bitmap.get_pixel(0, 0), 0x00AB_CDEF);
+    }
+
+    #[test]
+    fn fill_and_copy_pixels() {
+        let mut source = BitmapData::new(4, 4, true, 0);
+        source.fill_rect(PixelRect::new(1, 1, 10, 10), 0xFFFF_0000);
+        assert_eq!(source.get_pixel32(0, 0), 0);
+        assert_eq!(source.get_pixel32(3, 3), 0xFFFF_0000);
+
+        let mut dest = BitmapData::new(4, 4, true, 0xFF00_00FF);
+        dest.copy_pixels(&source, PixelRect::new(0, 0, 2, 2), (2, 2), None, false);
+        assert_eq!(dest.get_pixel32(2, 2), 0);
+        assert_eq!(dest.get_pixel32(3, 3), 0xFFFF_0000);
+        assert_eq!(dest.get_pixel32(1, 1), 0xFF00_00FF);
+
+        let mut dest = BitmapData::new(4, 4, true, 0xFF00_00FF);
+        dest.copy_pixels(&source, PixelRect::new(0, 0, 2, 2), (2, 2), None, true);
+        assert_eq!(dest.get_pixel32(2, 2), 0xFF00_00FF);
+        assert_eq!(dest.get_pixel32(3, 3), 0xFFFF_0000);
+    }
+
+    #[test]
+    fn copy_pixels_far_away() {
+        let source = BitmapData::new(4, 4, true, 0xFFFF_0000);
+        let mut dest = BitmapData::new(4, 4, true, 0xFF00_00FF);
+        let rect = PixelRect::new(0, 0, 4, 4);
+        dest.copy_pixels(&source, rect, (i32::MAX, i32::MAX), None, false);
+        dest.copy_pixels(&source, rect, (i32::MIN, 0), None, false);
+        dest.copy_pixels(
+            &source,
+            rect,
+            (0, 0),
+            Some((&source, (i32::MAX, i32::MAX))),
+            true,
+        );
+        assert_eq!(
+            dest.threshold(
+                &source,
+                rect,
+                (0, i32::MIN),
+                ThresholdOperation::Equal,
+                0xFFFF_0000,
+                0,
+                0xFFFF_FFFF,
+                true,
+            ),
+            0
+        );
+        assert!(dest.pixels.iter().all(|&pixel| pixel == 0xFF00_00FF));
+    }
+
+    #[test]
+    fn threshold_and_flood_fill() {
+        let mut bitmap = BitmapData::new(3, 3, true, 0xFF00_0000);
+        bitmap.set_pixel32(1, 0, 0xFFFF_FFFF);
+        bitmap.set_pixel32(1, 1, 0xFFFF_FFFF);
+        bitmap.set_pixel32(1, 2, 0xFFFF_FFFF);
+
+        let source = bitmap.clone();
+        let count = bitmap.threshold(
+            &source,
+            PixelRect::new(0, 0, 3, 3),
+            (0, 0),
+            ThresholdOperation::Equal,
+            0x00FF_0000,
+            0xFF00_FF00,
+            0x00FF_0000,
+            false,
+        );
+        assert_eq!(count, 3);
+        assert_eq!(bitmap.get_pixel32(1, 1), 0xFF00_FF00);
+        assert_eq!(bitmap.get_pixel32(0, 1), 0xFF00_0000);
+
+        bitmap.flood_fill(0, 0, 0xFF00_00FF);
+        assert_eq!(bitmap.get_pixel32(0, 2), 0xFF00_00FF);
+        assert_eq!(bitmap.get_pixel32(2, 0), 0xFF00_0000);
+    }
+
+    #[test]
+    fn noise_is_seeded() {
+        let mut a = BitmapData::new(8, 8, true, 0);
+        let mut b = BitmapData::new(8, 8, true, 0);
+        a.noise(42, 10, 20, channel::RED | channel::BLUE, false);
+        b.noise(42, 10, 20, channel::RED | channel::BLUE, false);
+        assert_eq!(a.pixels, b.pixels);
+        for &color in &a.pixels {
+            let [alpha, r, g, b] = color.to_be_bytes();
+            assert_eq!((alpha, g), (0xFF, 0));
+            assert!(r >= 10 && r <= 20 && b >= 10 && b <= 20);
+        }
+    }
+}
@@ -0,0 +1,229 @@
+//! Drawing display objects and bitmaps into a `BitmapData`, for `BitmapData.draw`.
+
+use super::rasterizer::{flatten, stroke, Affine, Paint, Rasterizer};
+use super::{BitmapData, PixelRect};
+use crate::character::Character;
+use crate::color_transform::ColorTransform;
+use crate::display_object::{DisplayObject, TDisplayObject};
+use crate::library::{Library, MovieLibrary};
+use crate::shape_utils::{DistilledShape, DrawPath, FillRule};
+use swf::{FillStyle, Matrix};
+
+/// Draws a display object and its children into a bitmap.
+///
+/// `matrix` maps the coordinates of the display object to the pixels of the bitmap; the
+/// display object's own transform is ignored.
+pub fn draw_display_object<'gc>(
+    target: &mut BitmapData,
+    object: DisplayObject<'gc>,
+    matrix: &Matrix,
+    color_transform: ColorTransform,
+    clip: Option<PixelRect>,
+    library: &Library<'gc>,
+) {
+    let transform = Affine::scale(1.0 / 20.0).then(&Affine::from_matrix(matrix));
+    draw_object(target, object, &transform, color_transform, clip, library);
+}
+
+/// Draws a bitmap into another.
+///
+/// `matrix` maps the pixels of `source` to the pixels of `target`.
+pub fn draw_bitmap_data(
+    target: &mut BitmapData,
+    source: &BitmapData,
+    matrix: &Matrix,
+    color_transform: ColorTransform,
+    clip: Option<PixelRect>,
+    smoothing: bool,
+) {
+    let transform = Affine::scale(1.0 / 20.0)
+        .then(&Affine::from_matrix(matrix))
+        .then(&Affine::scale(20.0));
+    let mut rasterizer = Rasterizer::new(target, clip, color_transform);
+    draw_bitmap(&mut rasterizer, source, &transform, smoothing);
+}
+
+/// Draws a display object, given the transform from its coordinates in twips to pixels.
+fn draw_object<'gc>(
+    target: &mut BitmapData,
+    object: DisplayObject<'gc>,
+    transform: &Affine,
+    color_transform: ColorTransform,
+    clip: Option<PixelRect>,
+    library: &Library<'gc>,
+) {
+    let movie_library = object
+        .movie()
+        .and_then(|movie| library.library_for_movie(movie));
+    {
+        let mut rasterizer = Rasterizer::new(target, clip, color_transform.clone());
+        match object {
+            DisplayObject::Graphic(graphic) => {
+                let shape = graphic.shape();
+                let fill_rule = FillRule::of_shape(&shape);
+                let shape = DistilledShape::from(&*shape);
+                draw_paths(
+                    &mut rasterizer,
+                    &shape.paths,
+                    fill_rule,
+                    transform,
+                    movie_library,
+                );
+            }
+            DisplayObject::MovieClip(movie_clip) => {
+                let drawing = movie_clip.drawing();
+                draw_paths(
+                    &mut rasterizer,
+                    &drawing.paths(),
+                    FillRule::EvenOdd,
+                    transform,
+                    movie_library,
+                );
+            }
+            DisplayObject::Bitmap(bitmap) => {
+                if let Some(pixels) = bitmap.pixels() {
+                    let transform = transform.then(&Affine::scale(20.0));
+                    draw_bitmap(&mut rasterizer, &pixels, &transform, bitmap.smoothing());
+                }
+            }
+            DisplayObject::Button(_) => (),
+            // TODO: Text, morph shapes and video aren't drawn yet.
+            DisplayObject::EditText(_) | DisplayObject::Text(_) => {
+                log::warn!("BitmapData.draw: Text is not drawn");
+            }
+            DisplayObject::MorphShape(_) => {
+                log::warn!("BitmapData.draw: Morph shapes are not drawn");
+            }
+            DisplayObject::Video(_) => {
+                log::warn!("BitmapData.draw: Video is not drawn");
+            }
+        }
+    }
+
+    for child in object.children() {
+        if !child.visible() {
+            continue;
+        }
+        // TODO: Masks aren't applied; the masks themselves are skipped.
+        if child.clip_depth() > 0 {
+            log::warn!("BitmapData.draw: Masks are not applied");
+            continue;
+        }
+        let child_transform = transform.then(&Affine::from_matrix(&child.matrix()));
+        let child_color_transform = color_transform.clone() * child.color_transform().clone();
+        draw_object(
+            target,
+            child,
+            &child_transform,
+            child_color_transform,
+            clip,
+            library,
+        );
+    }
+}
+
+fn draw_paths(
+    rasterizer: &mut Rasterizer,
+    paths: &[DrawPath],
+    fill_rule: FillRule,
+    transform: &Affine,
+    library: Option<&MovieLibrary>,
+) {
+    for path in paths {
+        match path {
+            DrawPath::Fill { style, commands } => {
+                let contours = flatten(commands, transform);
+                fill_path(rasterizer, &contours, style, fill_rule, transform, library);
+            }
+            DrawPath::Stroke {
+                style, commands, ..
+            } => {
+                let contours = flatten(commands, transform);
+                let width = style.width.get() as f64 * transform.scale_factor();
+                let color = &style.color;
+                rasterizer.fill(
+                    &stroke(&contours, width),
+                    FillRule::NonZero,
+                    &Paint::Color(u32::from_be_bytes([color.a, color.r, color.g, color.b])),
+                );
+            }
+        }
+    }
+}
+
+fn fill_path(
+    rasterizer: &mut Rasterizer,
+    contours: &[Vec<(f64, f64)>],
+    style: &FillStyle,
+    fill_rule: FillRule,
+    transform: &Affine,
+    library: Option<&MovieLibrary>,
+) {
+    let inverse = |matrix: &Matrix| transform.then(&Affine::from_matrix(matrix)).invert();
+    match style {
+        FillStyle::Color(color) => {
+            let color = u32::from_be_bytes([color.a, color.r, color.g, color.b]);
+            rasterizer.fill(contours, fill_rule, &Paint::Color(color));
+        }
+        FillStyle::LinearGradient(gradient) => {
+            if let Some(inverse) = inverse(&gradient.matrix) {
+                let paint = Paint::LinearGradient { gradient, inverse };
+                rasterizer.fill(contours, fill_rule, &paint);
+            }
+        }
+        // TODO: The focal point of focal gradients is ignored.
+        FillStyle::RadialGradient(gradient) | FillStyle::FocalGradient { gradient, .. } => {
+            if let Some(inverse) = inverse(&gradient.matrix) {
+                let paint = Paint::RadialGradient { gradient, inverse };
+                rasterizer.fill(contours, fill_rule, &paint);
+            }
+        }
+        FillStyle::Bitmap {
+            id,
+            matrix,
+            is_smoothed,
+            is_repeating,
+        } => {
+            let pixels = match library.and_then(|l| l.get_character_by_id(*id)) {
+                Some(Character::Bitmap(bitmap)) => bitmap.pixels(),
+                _ => None,
+            };
+            if let (Some(pixels), Some(inverse)) = (pixels, inverse(matrix)) {
+                let paint = Paint::Bitmap {
+                    bitmap: &pixels,
+                    inverse,
+                    repeating: *is_repeating,
+                    smoothed: *is_smoothed,
+                };
+                rasterizer.fill(contours, fill_rule, &paint);
+            }
+        }
+    }
+}
+
+/// Draws a bitmap, given the transform from its pixels to the pixels of the target.
+fn draw_bitmap(
+    rasterizer: &mut Rasterizer,
+    bitmap: &BitmapData,
+    transform: &Affine,
+    smoothing: bool,
+) {
+    let inverse = match transform.invert() {
+        Some(inverse) => inverse,
+        None => return,
+    };
+    let (width, height) = (f64::from(bitmap.width()), f64::from(bitmap.height()));
+    let outline = vec![
+        transform.apply(0.0, 0.0),
+        transform.apply(width, 0.0),
+        transform.apply(width, height),
+        transform.apply(0.0, height),
+    ];
+    let paint = Paint::Bitmap {
+        bitmap,
+        inverse,
+        repeating: false,
+        smoothed: smoothing,
+    };
+    rasterizer.fill(&[outline], FillRule::NonZero, &paint);
+}
@@ -0,0 +1,519 @@
+//! Software rendering of paths into a `BitmapData`.
+
+use super::{transform_color, BitmapData, PixelRect};
+use crate::color_transform::ColorTransform;
+use crate::shape_utils::{DrawCommand, FillRule};
+use std::cmp::Ordering;
+use swf::{Gradient, GradientSpread, Matrix};
+
+/// The number of rows sampled in each row of pixels, for anti-aliasing.
+const SUBSAMPLES: usize = 4;
+
+/// An affine transform, mapping `(x, y)` to `(a * x + c * y + tx, b * x + d * y + ty)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Affine {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub tx: f64,
+    pub ty: f64,
+}
+
+impl Affine {
+    pub fn scale(scale: f64) -> Self {
+        Self {
+            a: scale,
+            b: 0.0,
+            c: 0.0,
+            d: scale,
+            tx: 0.0,
+            ty: 0.0,
+        }
+    }
+
+    /// Converts a matrix, keeping its translation in twips.
+    pub fn from_matrix(matrix: &Matrix) -> Self {
+        Self {
+            a: f64::from(matrix.a),
+            b: f64::from(matrix.b),
+            c: f64::from(matrix.c),
+            d: f64::from(matrix.d),
+            tx: matrix.tx.get() as f64,
+            ty: matrix.ty.get() as f64,
+        }
+    }
+
+    /// Returns the transform that applies `other`, then this one.
+    pub fn then(&self, other: &Affine) -> Self {
+        Self {
+            a: self.a * other.a + self.c * other.b,
+            b: self.b * other.a + self.d * other.b,
+            c: self.a * other.c + self.c * other.d,
+            d: self.b * other.c + self.d * other.d,
+            tx: self.a * other.tx + self.c * other.ty + self.tx,
+            ty: self.b * other.tx + self.d * other.ty + self.ty,
+        }
+    }
+
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            self.a * x + self.c * y + self.tx,
+            self.b * x + self.d * y + self.ty,
+        )
+    }
+
+    /// Returns the inverse transform, or `None` if this one has no inverse.
+    pub fn invert(&self) -> Option<Self> {
+        let det = self.a * self.d - self.b * self.c;
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        Some(Self {
+            a: self.d / det,
+            b: -self.b / det,
+            c: -self.c / det,
+            d: self.a / det,
+            tx: (self.c * self.ty - self.d * self.tx) / det,
+            ty: (self.b * self.tx - self.a * self.ty) / det,
+        })
+    }
+
+    /// The factor this transform scales lengths by, on average.
+    pub fn scale_factor(&self) -> f64 {
+        (self.a * self.d - self.b * self.c).abs().sqrt()
+    }
+}
+
+/// What an area is filled with. The transforms map pixels of the target to the space of the
+/// gradient or bitmap.
+pub enum Paint<'a> {
+    Color(u32),
+    LinearGradient {
+        gradient: &'a Gradient,
+        inverse: Affine,
+    },
+    RadialGradient {
+        gradient: &'a Gradient,
+        inverse: Affine,
+    },
+    Bitmap {
+        bitmap: &'a BitmapData,
+        inverse: Affine,
+        repeating: bool,
+        smoothed: bool,
+    },
+}
+
+impl Paint<'_> {
+    /// Returns the color at the center of a pixel, as ARGB.
+    fn sample(&self, x: i32, y: i32) -> u32 {
+        let (x, y) = (f64::from(x) + 0.5, f64::from(y) + 0.5);
+        match self {
+            Paint::Color(color) => *color,
+            Paint::LinearGradient { gradient, inverse } => {
+                let (x, _) = inverse.apply(x, y);
+                sample_gradient(gradient, (x + 16384.0) / 32768.0)
+            }
+            Paint::RadialGradient { gradient, inverse } => {
+                let (x, y) = inverse.apply(x, y);
+                sample_gradient(gradient, x.hypot(y) / 16384.0)
+            }
+            Paint::Bitmap {
+                bitmap,
+                inverse,
+                repeating,
+                smoothed,
+            } => {
+                let (x, y) = inverse.apply(x, y);
+                sample_bitmap(bitmap, x, y, *repeating, *smoothed)
+            }
+        }
+    }
+}
+
+/// Fills paths into a bitmap.
+pub struct Rasterizer<'a> {
+    target: &'a mut BitmapData,
+
+    /// The area that can be drawn to, as `(x_min, y_min, x_max, y_max)`.
+    clip: (i32, i32, i32, i32),
+
+    color_transform: ColorTransform,
+}
+
+impl<'a> Rasterizer<'a> {
+    pub fn new(
+        target: &'a mut BitmapData,
+        clip: Option<PixelRect>,
+        color_transform: ColorTransform,
+    ) -> Self {
+        let bounds = PixelRect::new(0, 0, target.width() as i32, target.height() as i32);
+        let clip = target.clip(clip.unwrap_or(bounds)).unwrap_or((0, 0, 0, 0));
+        Self {
+            target,
+            clip,
+            color_transform,
+        }
+    }
+
+    /// Fills the area inside closed contours, given in pixels.
+    pub fn fill(&mut self, contours: &[Vec<(f64, f64)>], rule: FillRule, paint: &Paint) {
+        let (clip_x_min, clip_y_min, clip_x_max, clip_y_max) = self.clip;
+        if clip_x_min >= clip_x_max || clip_y_min >= clip_y_max {
+            return;
+        }
+
+        // Each edge is stored as `(x0, y0, x1, y1, winding)`, with `y0 < y1`.
+        let mut edges = Vec::new();
+        let (mut y_min, mut y_max) = (f64::INFINITY, f64::NEG_INFINITY);
+        for contour in contours {
+            for i in 0..contour.len() {
+                let (x0, y0) = contour[i];
+                let (x1, y1) = contour[(i + 1) % contour.len()];
+                if y0 == y1
+                    || !(x0.is_finite() && y0.is_finite() && x1.is_finite() && y1.is_finite())
+                {
+                    continue;
+                }
+                if y0 < y1 {
+                    edges.push((x0, y0, x1, y1, 1));
+                } else {
+                    edges.push((x1, y1, x0, y0, -1));
+                }
+                y_min = y_min.min(y0.min(y1));
+                y_max = y_max.max(y0.max(y1));
+            }
+        }
+        if edges.is_empty() {
+            return;
+        }
+
+        let row_start = (y_min.floor() as i32).max(clip_y_min);
+        let row_end = (y_max.ceil() as i32).min(clip_y_max);
+        let mut coverage = vec![0.0f32; (clip_x_max - clip_x_min) as usize];
+        let mut crossings: Vec<(f64, i32)> = Vec::new();
+        for row in row_start..row_end {
+            for value in coverage.iter_mut() {
+                *value = 0.0;
+            }
+
+            for sample in 0..SUBSAMPLES {
+                let y = f64::from(row) + (sample as f64 + 0.5) / SUBSAMPLES as f64;
+                crossings.clear();
+                for &(x0, y0, x1, y1, winding) in &edges {
+                    if y0 <= y && y < y1 {
+                        crossings.push((x0 + (y - y0) * (x1 - x0) / (y1 - y0), winding));
+                    }
+                }
+                crossings.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+
+                let mut winding = 0;
+                for pair in crossings.windows(2) {
+                    winding += pair[0].1;
+                    let inside = match rule {
+                        FillRule::EvenOdd => winding % 2 != 0,
+                        FillRule::NonZero => winding != 0,
+                    };
+                    if inside {
+                        add_span(
+                            &mut coverage,
+                            pair[0].0 - f64::from(clip_x_min),
+                            pair[1].0 - f64::from(clip_x_min),
+                            1.0 / SUBSAMPLES as f32,
+                        );
+                    }
+                }
+            }
+
+            for (i, &value) in coverage.iter().enumerate() {
+                if value <= 0.0 {
+                    continue;
+                }
+                let x = clip_x_min + i as i32;
+                let color = transform_color(paint.sample(x, row), &self.color_transform);
+                let alpha = (f32::from((color >> 24) as u8) * value.min(1.0)).round() as u32;
+                self.target
+                    .blend_pixel(x, row, (color & 0x00FF_FFFF) | (alpha << 24));
+            }
+        }
+    }
+}
+
+/// Adds the coverage of a horizontal span to a row of pixels.
+fn add_span(coverage: &mut [f32], x0: f64, x1: f64, weight: f32) {
+    let width = coverage.len() as f64;
+    let x0 = x0.max(0.0).min(width);
+    let x1 = x1.max(0.0).min(width);
+    if x1 <= x0 {
+        return;
+    }
+
+    let (i0, i1) = (x0.floor() as usize, x1.floor() as usize);
+    if i0 == i1 {
+        coverage[i0] += (x1 - x0) as f32 * weight;
+        return;
+    }
+    coverage[i0] += ((i0 + 1) as f64 - x0) as f32 * weight;
+    for value in &mut coverage[i0 + 1..i1] {
+        *value += weight;
+    }
+    if i1 < coverage.len() {
+        coverage[i1] += (x1 - i1 as f64) as f32 * weight;
+    }
+}
+
+/// Converts draw commands in twips to contours of points, transformed to pixels. Curves
+/// are split into lines.
+pub fn flatten(commands: &[DrawCommand], transform: &Affine) -> Vec<Vec<(f64, f64)>> {
+    let point = |x: swf::Twips, y: swf::Twips| transform.apply(x.get() as f64, y.get() as f64);
+
+    let mut contours = Vec::new();
+    let mut contour = vec![transform.apply(0.0, 0.0)];
+    for command in commands {
+        match *command {
+            DrawCommand::MoveTo { x, y } => {
+                if contour.len() > 1 {
+                    contours.push(contour);
+                }
+                contour = vec![point(x, y)];
+            }
+            DrawCommand::LineTo { x, y } => contour.push(point(x, y)),
+            DrawCommand::CurveTo { x1, y1, x2, y2 } => {
+                let start = *contour.last().unwrap();
+                let control = point(x1, y1);
+                let end = point(x2, y2);
+                let length = distance(start, control) + distance(control, end);
+                let steps = ((length / 2.0).ceil() as usize).max(1).min(64);
+                for step in 1..=steps {
+                    let t = step as f64 / steps as f64;
+                    let u = 1.0 - t;
+                    contour.push((
+                        u * u * start.0 + 2.0 * u * t * control.0 + t * t * end.0,
+                        u * u * start.1 + 2.0 * u * t * control.1 + t * t * end.1,
+                    ));
+                }
+            }
+        }
+    }
+    if contour.len() > 1 {
+        contours.push(contour);
+    }
+    contours
+}
+
+/// Returns the outline of lines along contours, as polygons to fill with the non-zero
+/// rule. Joins and caps are round.
+pub fn stroke(contours: &[Vec<(f64, f64)>], width: f64) -> Vec<Vec<(f64, f64)>> {
+    // Hairlines and thinner lines are drawn one pixel wide.
+    let radius = width.max(1.0) / 2.0;
+    let mut polygons = Vec::new();
+    for contour in contours {
+        for segment in contour.windows(2) {
+            let (start, end) = (segment[0], segment[1]);
+            let length = distance(start, end);
+            if length == 0.0 {
+                continue;
+            }
+            let normal_x = -(end.1 - start.1) / length * radius;
+            let normal_y = (end.0 - start.0) / length * radius;
+            polygons.push(clockwise(vec![
+                (start.0 + normal_x, start.1 + normal_y),
+                (end.0 + normal_x, end.1 + normal_y),
+                (end.0 - normal_x, end.1 - normal_y),
+                (start.0 - normal_x, start.1 - normal_y),
+            ]));
+        }
+
+        let steps = ((std::f64::consts::PI * radius).ceil() as usize)
+            .max(8)
+            .min(64);
+        for &(x, y) in contour {
+            let circle = (0..steps)
+                .map(|i| {
+                    let angle = i as f64 / steps as f64 * std::f64::consts::PI * 2.0;
+                    (x + angle.cos() * radius, y + angle.sin() * radius)
+                })
+                .collect();
+            polygons.push(clockwise(circle));
+        }
+    }
+    polygons
+}
+
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (b.0 - a.0).hypot(b.1 - a.1)
+}
+
+/// Orders the points of a polygon clockwise, so that overlapping polygons are merged when
+/// filled with the non-zero rule.
+fn clockwise(mut polygon: Vec<(f64, f64)>) -> Vec<(f64, f64)> {
+    let mut area = 0.0;
+    for i in 0..polygon.len() {
+        let (x0, y0) = polygon[i];
+        let (x1, y1) = polygon[(i + 1) % polygon.len()];
+        area += x0 * y1 - x1 * y0;
+    }
+    if area < 0.0 {
+        polygon.reverse();
+    }
+    polygon
+}
+
+/// Returns the color of a gradient at a position between 0 and 1, before spreading.
+fn sample_gradient(gradient: &Gradient, t: f64) -> u32 {
+    let t = match gradient.spread {
+        GradientSpread::Pad => t.max(0.0).min(1.0),
+        GradientSpread::Repeat => t.rem_euclid(1.0),
+        GradientSpread::Reflect => {
+            let t = t.rem_euclid(2.0);
+            if t > 1.0 {
+                2.0 - t
+            } else {
+                t
+            }
+        }
+    };
+    let ratio = t * 255.0;
+
+    let records = &gradient.records;
+    let (first, last) = match (records.first(), records.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return 0,
+    };
+    let color_of = |color: &swf::Color| u32::from_be_bytes([color.a, color.r, color.g, color.b]);
+    if ratio <= f64::from(first.ratio) {
+        return color_of(&first.color);
+    }
+    if ratio >= f64::from(last.ratio) {
+        return color_of(&last.color);
+    }
+    for pair in records.windows(2) {
+        let (start, end) = (&pair[0], &pair[1]);
+        if ratio <= f64::from(end.ratio) {
+            let span = f64::from(end.ratio) - f64::from(start.ratio);
+            let t = if span > 0.0 {
+                (ratio - f64::from(start.ratio)) / span
+            } else {
+                1.0
+            };
+            let mix = |a: u8, b: u8| (f64::from(a) + (f64::from(b) - f64::from(a)) * t) as u8;
+            return u32::from_be_bytes([
+                mix(start.color.a, end.color.a),
+                mix(start.color.r, end.color.r),
+                mix(start.color.g, end.color.g),
+                mix(start.color.b, end.color.b),
+            ]);
+        }
+    }
+    color_of(&last.color)
+}
+
+/// Returns the color of a bitmap at a position in its pixels.
+fn sample_bitmap(bitmap: &BitmapData, x: f64, y: f64, repeating: bool, smoothed: bool) -> u32 {
+    let (width, height) = (bitmap.width() as i32, bitmap.height() as i32);
+    if width == 0 || height == 0 {
+        return 0;
+    }
+    let pixel = |x: i32, y: i32| {
+        if repeating {
+            bitmap.get_pixel32(x.rem_euclid(width), y.rem_euclid(height))
+        } else {
+            bitmap.get_pixel32(x.max(0).min(width - 1), y.max(0).min(height - 1))
+        }
+    };
+    if !smoothed {
+        return pixel(x.floor() as i32, y.floor() as i32);
+    }
+
+    // Bilinear filtering, weighting colors by their alpha.
+    let (x, y) = (x - 0.5, y - 0.5);
+    let (x0, y0) = (x.floor(), y.floor());
+    let (fx, fy) = (x - x0, y - y0);
+    let (x0, y0) = (x0 as i32, y0 as i32);
+    let mut channels = [0.0; 4];
+    for &(dx, dy, weight) in &[
+        (0, 0, (1.0 - fx) * (1.0 - fy)),
+        (1, 0, fx * (1.0 - fy)),
+        (0, 1, (1.0 - fx) * fy),
+        (1, 1, fx * fy),
+    ] {
+        let [a, r, g, b] = pixel(x0 + dx, y0 + dy).to_be_bytes();
+        let alpha = f64::from(a) * weight;
+        channels[0] += alpha;
+        channels[1] += f64::from(r) * alpha;
+        channels[2] += f64::from(g) * alpha;
+        channels[3] += f64::from(b) * alpha;
+    }
+    if channels[0] <= 0.0 {
+        return 0;
+    }
+    u32::from_be_bytes([
+        channels[0].round() as u8,
+        (channels[1] / channels[0]).round() as u8,
+        (channels[2] / channels[0]).round() as u8,
+        (channels[3] / channels[0]).round() as u8,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swf::Twips;
+
+    #[test]
+    fn fills_and_strokes_paths() {
+        let mut bitmap = BitmapData::new(10, 10, true, 0);
+        let square = vec![
+            DrawCommand::MoveTo {
+                x: Twips::from_pixels(2.0),
+                y: Twips::from_pixels(2.0),
+            },
+            DrawCommand::LineTo {
+                x: Twips::from_pixels(6.0),
+                y: Twips::from_pixels(2.0),
+            },
+            DrawCommand::LineTo {
+                x: Twips::from_pixels(6.0),
+                y: Twips::from_pixels(6.0),
+            },
+            DrawCommand::LineTo {
+                x: Twips::from_pixels(2.0),
+                y: Twips::from_pixels(6.0),
+            },
+            DrawCommand::LineTo {
+                x: Twips::from_pixels(2.0),
+                y: Twips::from_pixels(2.0),
+            },
+        ];
+        let contours = flatten(&square, &Affine::scale(1.0 / 20.0));
+        let mut rasterizer = Rasterizer::new(&mut bitmap, None, ColorTransform::default());
+        rasterizer.fill(&contours, FillRule::EvenOdd, &Paint::Color(0xFFFF_0000));
+        assert_eq!(bitmap.get_pixel32(2, 2), 0xFFFF_0000);
+        assert_eq!(bitmap.get_pixel32(5, 5), 0xFFFF_0000);
+        assert_eq!(bitmap.get_pixel32(6, 6), 0);
+        assert_eq!(bitmap.get_pixel32(1, 4), 0);
+
+        let mut rasterizer = Rasterizer::new(&mut bitmap, None, ColorTransform::default());
+        rasterizer.fill(
+            &stroke(&contours, 2.0),
+            FillRule::NonZero,
+            &Paint::Color(0xFF00_00FF),
+        );
+        assert_eq!(bitmap.get_pixel32(1, 4), 0xFF00_00FF);
+        assert_eq!(bitmap.get_pixel32(4, 4), 0xFFFF_0000);
+        assert_eq!(bitmap.get_pixel32(8, 4), 0);
+    }
+
+    #[test]
+    fn ignores_non_finite_points() {
+        let mut bitmap = BitmapData::new(4, 4, true, 0);
+        let mut rasterizer = Rasterizer::new(&mut bitmap, None, ColorTransform::default());
+        let contours = vec![
+            vec![(0.0, 0.0), (std::f64::NAN, 3.0), (3.0, std::f64::INFINITY)],
+            // The first sample row crosses this edge at `0.0 * -inf`, which is NaN.
+            vec![(std::f64::MAX, 0.125), (-std::f64::MAX, 1.0), (0.0, 2.0)],
+        ];
+        rasterizer.fill(&contours, FillRule::NonZero, &Paint::Color(0xFFFF_0000));
+    }
+}
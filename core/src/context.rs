@@ -4,22 +4,39 @@ use crate::avm1;
 use crate::avm1::globals::system::SystemProperties;
 use crate::avm1::listeners::SystemListener;
 use crate::avm1::{Object, Value};
+use crate::avm2::{Avm2, Object as Avm2Object, Value as Avm2Value};
 use crate::backend::input::InputBackend;
+use crate::backend::local_connection::LocalConnectionBackend;
+use crate::backend::socket::SocketBackend;
 use crate::backend::storage::StorageBackend;
-use crate::backend::{audio::AudioBackend, navigator::NavigatorBackend, render::RenderBackend};
+use crate::backend::video::VideoBackend;
+use crate::backend::{
+    audio::{AudioBackend, AudioManager},
+    navigator::NavigatorBackend,
+    render::RenderBackend,
+};
 use crate::display_object::EditText;
+use crate::external::ExternalInterface;
+use crate::focus_tracker::FocusTracker;
+use crate::keyboard::KeyboardState;
 use crate::library::Library;
 use crate::loader::LoadManager;
+use crate::local_connection::LocalConnections;
 use crate::player::Player;
+use crate::policy::PolicyManager;
 use crate::prelude::*;
+use crate::stage::StageProperties;
 use crate::tag_utils::{SwfMovie, SwfSlice};
+use crate::timer::Timers;
 use crate::transform::TransformStack;
+use crate::xml_socket::XMLSockets;
 use core::fmt;
 use gc_arena::{Collect, MutationContext};
 use rand::rngs::SmallRng;
 use std::collections::VecDeque;
 use std::collections::{BTreeMap, HashMap};
 use std::sync::{Arc, Mutex, Weak};
+use swf::BlendMode;
 
 /// `UpdateContext` holds shared data that is used by the various subsystems of Ruffle.
 /// `Player` crates this when it begins a tick and passes it through the call stack to
@@ -66,9 +83,21 @@ pub struct UpdateContext<'a, 'gc, 'gc_context> {
     /// The input backend, used to detect user interactions.
     pub input: &'a mut dyn InputBackend,
 
+    /// The keyboard state tracked by the player, such as the last character typed.
+    pub keyboard: &'a mut KeyboardState,
+
     /// The storage backend, used for storing persistent state
     pub storage: &'a mut dyn StorageBackend,
 
+    /// The video backend, used to decode embedded video streams.
+    pub video: &'a mut dyn VideoBackend,
+
+    /// The socket backend, used by `XMLSocket` to connect to servers.
+    pub sockets: &'a mut dyn SocketBackend,
+
+    /// The local connection backend, used by `LocalConnection` to talk to other movies.
+    pub local_connection: &'a mut dyn LocalConnectionBackend,
+
     /// The RNG, used by the AVM `RandomNumber` opcode,  `Math.random(),` and `random()`.
     pub rng: &'a mut SmallRng,
 
@@ -88,8 +117,8 @@ pub struct UpdateContext<'a, 'gc, 'gc_context> {
     /// The object being dragged via a `startDrag` action.
     pub drag_object: &'a mut Option<crate::player::DragObject<'gc>>,
 
-    /// The dimensions of the stage.
-    pub stage_size: (Twips, Twips),
+    /// The sizes of the movie and viewport, and how the movie is scaled.
+    pub stage: &'a mut StageProperties,
 
     /// Weak reference to the player.
     ///
@@ -114,6 +143,30 @@ pub struct UpdateContext<'a, 'gc, 'gc_context> {
 
     /// Text fields with unbound variable bindings.
     pub unbound_text_fields: &'a mut Vec<EditText<'gc>>,
+
+    /// The AVM2 interpreter, which runs ActionScript 3 code.
+    pub avm2: &'a mut Avm2<'gc>,
+
+    /// The display object that currently has keyboard focus.
+    pub focus_tracker: FocusTracker<'gc>,
+
+    /// Manages the sounds playing in the movie, along with their sound transforms.
+    pub audio_manager: &'a mut AudioManager<'gc>,
+
+    /// Timers created by `setInterval` and `setTimeout`.
+    pub timers: &'a mut Timers<'gc>,
+
+    /// The cross-domain policy files and domain permissions known to the player.
+    pub policies: &'a mut PolicyManager,
+
+    /// The connections of `XMLSocket` objects.
+    pub xml_sockets: &'a mut XMLSockets<'gc>,
+
+    /// The `LocalConnection` objects that are listening or waiting for a status.
+    pub local_connections: &'a mut LocalConnections<'gc>,
+
+    /// The callbacks registered with `ExternalInterface`, and the hosts they are exposed to.
+    pub external_interface: &'a mut ExternalInterface<'gc>,
 }
 
 /// A queued ActionScript call.
@@ -218,6 +271,9 @@ pub struct RenderContext<'a, 'gc> {
 
     /// The stack of clip depths, used in masking.
     pub clip_depth_stack: Vec<Depth>,
+
+    /// The blend mode of the offscreen layer currently being rendered into.
+    pub blend_mode: BlendMode,
 }
 
 /// The type of action being run.
@@ -245,6 +301,26 @@ pub enum ActionType<'gc> {
         method: &'static str,
         args: Vec<Value<'gc>>,
     },
+
+    /// An AVM2 function, such as a frame script added with `addFrameScript`.
+    Avm2Callable {
+        callable: Avm2Object<'gc>,
+        receiver: Option<Avm2Object<'gc>>,
+        args: Vec<Avm2Value<'gc>>,
+    },
+
+    /// An AVM2 event, such as `enterFrame`, dispatched to a display object.
+    Avm2Event {
+        target: Avm2Object<'gc>,
+        event_type: &'static str,
+    },
+
+    /// A goto requested by AVM2 code.
+    ///
+    /// Gotos need the AVM1 interpreter to run the target frame, which AVM2
+    /// natives do not have access to, so they are deferred until the queue
+    /// is run.
+    Avm2Goto { frame: u16, stop: bool },
 }
 
 impl fmt::Debug for ActionType<'_> {
@@ -278,6 +354,26 @@ impl fmt::Debug for ActionType<'_> {
                 .field("method", method)
                 .field("args", args)
                 .finish(),
+            ActionType::Avm2Callable {
+                callable,
+                receiver,
+                args,
+            } => f
+                .debug_struct("ActionType::Avm2Callable")
+                .field("callable", callable)
+                .field("receiver", receiver)
+                .field("args", args)
+                .finish(),
+            ActionType::Avm2Event { target, event_type } => f
+                .debug_struct("ActionType::Avm2Event")
+                .field("target", target)
+                .field("event_type", event_type)
+                .finish(),
+            ActionType::Avm2Goto { frame, stop } => f
+                .debug_struct("ActionType::Avm2Goto")
+                .field("frame", frame)
+                .field("stop", stop)
+                .finish(),
         }
     }
 }
@@ -296,6 +392,18 @@ unsafe impl<'gc> Collect for ActionType<'gc> {
             ActionType::NotifyListeners { args, .. } => {
                 args.trace(cc);
             }
+            ActionType::Avm2Callable {
+                callable,
+                receiver,
+                args,
+            } => {
+                callable.trace(cc);
+                receiver.trace(cc);
+                args.trace(cc);
+            }
+            ActionType::Avm2Event { target, .. } => {
+                target.trace(cc);
+            }
             _ => {}
         }
     }
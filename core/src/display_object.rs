@@ -1,4 +1,5 @@
 use crate::avm1::{Avm1, Object, TObject, Value};
+use crate::backend::audio::SoundTransform;
 use crate::context::{RenderContext, UpdateContext};
 use crate::player::NEWEST_PLAYER_VERSION;
 use crate::prelude::*;
@@ -11,6 +12,7 @@ use std::cell::{Ref, RefMut};
 use std::cmp::min;
 use std::fmt::Debug;
 use std::sync::Arc;
+use swf::BlendMode;
 
 mod bitmap;
 mod button;
@@ -19,15 +21,17 @@ mod graphic;
 mod morph_shape;
 mod movie_clip;
 mod text;
+mod video;
 
 use crate::events::{ClipEvent, ClipEventResult};
-pub use bitmap::Bitmap;
+pub use bitmap::{Bitmap, BitmapSource};
 pub use button::Button;
-pub use edit_text::{AutoSizeMode, EditText};
+pub use edit_text::{AutoSizeMode, EditText, TextSelection};
 pub use graphic::Graphic;
 pub use morph_shape::{MorphShape, MorphShapeStatic};
 pub use movie_clip::MovieClip;
 pub use text::Text;
+pub use video::Video;
 
 #[derive(Clone, Debug)]
 pub struct DisplayObjectBase<'gc> {
@@ -38,6 +42,23 @@ pub struct DisplayObjectBase<'gc> {
     name: String,
     clip_depth: Depth,
 
+    /// The filters applied to this display object when it is rendered.
+    filters: Vec<swf::Filter>,
+
+    /// How this display object is blended onto the content beneath it.
+    blend_mode: BlendMode,
+
+    /// The volume and panning applied to sounds played by this display object and its children.
+    sound_transform: SoundTransform,
+
+    /// The position of this display object in the tab order, if it has been
+    /// set by a `SetTabIndex` tag or by `tabIndex`.
+    tab_index: Option<i32>,
+
+    /// Whether Tab may move focus to this display object, if `tabEnabled`
+    /// has been set. Otherwise, this depends on the kind of object.
+    tab_enabled: Option<bool>,
+
     // Cached transform properties `_xscale`, `_yscale`, `_rotation`.
     // These are expensive to calculate, so they will be calculated and cached when AS requests
     // one of these properties.
@@ -69,6 +90,11 @@ impl<'gc> Default for DisplayObjectBase<'gc> {
             transform: Default::default(),
             name: Default::default(),
             clip_depth: Default::default(),
+            filters: Vec::new(),
+            blend_mode: BlendMode::Normal,
+            sound_transform: Default::default(),
+            tab_index: None,
+            tab_enabled: None,
             rotation: 0.0,
             scale_x: 1.0,
             scale_y: 1.0,
@@ -274,6 +300,40 @@ impl<'gc> DisplayObjectBase<'gc> {
     fn set_clip_depth(&mut self, _context: MutationContext<'gc, '_>, depth: Depth) {
         self.clip_depth = depth;
     }
+    fn filters(&self) -> &[swf::Filter] {
+        &self.filters
+    }
+    fn set_filters(&mut self, _context: MutationContext<'gc, '_>, filters: Vec<swf::Filter>) {
+        self.filters = filters;
+    }
+    fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+    fn set_blend_mode(&mut self, _context: MutationContext<'gc, '_>, blend_mode: BlendMode) {
+        self.blend_mode = blend_mode;
+    }
+    fn sound_transform(&self) -> SoundTransform {
+        self.sound_transform
+    }
+    fn set_sound_transform(
+        &mut self,
+        _context: MutationContext<'gc, '_>,
+        sound_transform: SoundTransform,
+    ) {
+        self.sound_transform = sound_transform;
+    }
+    fn tab_index(&self) -> Option<i32> {
+        self.tab_index
+    }
+    fn set_tab_index(&mut self, _context: MutationContext<'gc, '_>, tab_index: Option<i32>) {
+        self.tab_index = tab_index;
+    }
+    fn tab_enabled(&self) -> Option<bool> {
+        self.tab_enabled
+    }
+    fn set_tab_enabled(&mut self, _context: MutationContext<'gc, '_>, tab_enabled: Option<bool>) {
+        self.tab_enabled = tab_enabled;
+    }
     fn parent(&self) -> Option<DisplayObject<'gc>> {
         self.parent
     }
@@ -371,6 +431,7 @@ impl<'gc> DisplayObjectBase<'gc> {
         MorphShape(MorphShape<'gc>),
         MovieClip(MovieClip<'gc>),
         Text(Text<'gc>),
+        Video(Video<'gc>),
     }
 )]
 pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>> {
@@ -630,6 +691,52 @@ pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>>
 
     fn clip_depth(&self) -> Depth;
     fn set_clip_depth(&mut self, context: MutationContext<'gc, '_>, depth: Depth);
+
+    /// The filters applied to this display object when it is rendered.
+    fn filters(&self) -> Ref<[swf::Filter]>;
+    fn set_filters(&mut self, context: MutationContext<'gc, '_>, filters: Vec<swf::Filter>);
+
+    /// How this display object is blended onto the content beneath it.
+    fn blend_mode(&self) -> BlendMode;
+    fn set_blend_mode(&mut self, context: MutationContext<'gc, '_>, blend_mode: BlendMode);
+
+    /// The volume and panning applied to sounds played by this display object and its children.
+    fn sound_transform(&self) -> SoundTransform;
+    fn set_sound_transform(
+        &mut self,
+        context: MutationContext<'gc, '_>,
+        sound_transform: SoundTransform,
+    );
+
+    /// The position of this display object in the tab order, if any.
+    fn tab_index(&self) -> Option<i32>;
+    fn set_tab_index(&mut self, context: MutationContext<'gc, '_>, tab_index: Option<i32>);
+
+    /// The value of `tabEnabled`, if it has been set.
+    fn tab_enabled(&self) -> Option<bool>;
+    fn set_tab_enabled(&mut self, context: MutationContext<'gc, '_>, tab_enabled: Option<bool>);
+
+    /// Whether this display object may receive keyboard focus at all.
+    fn is_focusable(
+        &self,
+        _avm: &mut Avm1<'gc>,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> bool {
+        false
+    }
+
+    /// Whether pressing Tab may move keyboard focus to this display object.
+    ///
+    /// Objects are only tab-enabled by default if they respond to the user,
+    /// which `tabEnabled` overrides.
+    fn is_tab_enabled(
+        &self,
+        _avm: &mut Avm1<'gc>,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> bool {
+        self.tab_enabled().unwrap_or(false)
+    }
+
     fn parent(&self) -> Option<DisplayObject<'gc>>;
     fn set_parent(&mut self, context: MutationContext<'gc, '_>, parent: Option<DisplayObject<'gc>>);
     fn first_child(&self) -> Option<DisplayObject<'gc>>;
@@ -729,6 +836,9 @@ pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>>
         ClipEventResult::NotHandled
     }
 
+    /// Called when this display object gains or loses keyboard focus.
+    fn on_focus_changed(&self, _gc_context: MutationContext<'gc, '_>, _focused: bool) {}
+
     fn run_frame(&mut self, _avm: &mut Avm1<'gc>, _context: &mut UpdateContext<'_, 'gc, '_>) {}
     fn render(&self, _context: &mut RenderContext<'_, 'gc>) {}
 
@@ -760,11 +870,15 @@ pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>>
     fn as_morph_shape(&self) -> Option<MorphShape<'gc>> {
         None
     }
+    fn as_video(&self) -> Option<Video<'gc>> {
+        None
+    }
     fn apply_place_object(
         &mut self,
-        gc_context: MutationContext<'gc, '_>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
         place_object: &swf::PlaceObject,
     ) {
+        let gc_context = context.gc_context;
         // PlaceObject tags only apply if this onject has not been dynamically moved by AS code.
         if !self.transformed_by_script() {
             if let Some(matrix) = &place_object.matrix {
@@ -779,9 +893,17 @@ pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>>
             if let Some(clip_depth) = place_object.clip_depth {
                 self.set_clip_depth(gc_context, clip_depth.into());
             }
+            if let Some(filters) = &place_object.filters {
+                self.set_filters(gc_context, filters.clone());
+            }
+            if let Some(blend_mode) = place_object.blend_mode {
+                self.set_blend_mode(gc_context, blend_mode);
+            }
             if let Some(ratio) = place_object.ratio {
                 if let Some(mut morph_shape) = self.as_morph_shape() {
                     morph_shape.set_ratio(gc_context, ratio);
+                } else if let Some(video) = self.as_video() {
+                    video.seek(context, ratio.into());
                 }
             }
             // Clip events only apply to movie clips.
@@ -811,6 +933,8 @@ pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>>
         self.set_matrix(gc_context, &*other.matrix());
         self.set_color_transform(gc_context, &*other.color_transform());
         self.set_clip_depth(gc_context, other.clip_depth());
+        self.set_filters(gc_context, other.filters().to_vec());
+        self.set_blend_mode(gc_context, other.blend_mode());
         self.set_name(gc_context, &*other.name());
         if let (Some(mut me), Some(other)) = (self.as_morph_shape(), other.as_morph_shape()) {
             me.set_ratio(gc_context, other.ratio());
@@ -831,6 +955,17 @@ pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>>
         false
     }
 
+    /// Tests if a given stage position point is inside the shape of this object or one of its
+    /// children. Objects without a shape are hit anywhere inside their world bounds.
+    fn hit_test_shape(
+        &self,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+        pos: (Twips, Twips),
+        _mode: HitTestMode,
+    ) -> bool {
+        self.world_bounds().contains(pos)
+    }
+
     fn mouse_pick(
         &self,
         _avm: &mut Avm1<'gc>,
@@ -1020,6 +1155,68 @@ macro_rules! impl_display_object_sansbounds {
         ) {
             self.0.write(context).$field.set_clip_depth(context, depth)
         }
+        fn filters(&self) -> std::cell::Ref<[swf::Filter]> {
+            std::cell::Ref::map(self.0.read(), |o| o.$field.filters())
+        }
+        fn set_filters(
+            &mut self,
+            context: gc_arena::MutationContext<'gc, '_>,
+            filters: Vec<swf::Filter>,
+        ) {
+            self.0.write(context).$field.set_filters(context, filters)
+        }
+        fn blend_mode(&self) -> swf::BlendMode {
+            self.0.read().$field.blend_mode()
+        }
+        fn set_blend_mode(
+            &mut self,
+            context: gc_arena::MutationContext<'gc, '_>,
+            blend_mode: swf::BlendMode,
+        ) {
+            self.0
+                .write(context)
+                .$field
+                .set_blend_mode(context, blend_mode)
+        }
+        fn sound_transform(&self) -> crate::backend::audio::SoundTransform {
+            self.0.read().$field.sound_transform()
+        }
+        fn set_sound_transform(
+            &mut self,
+            context: gc_arena::MutationContext<'gc, '_>,
+            sound_transform: crate::backend::audio::SoundTransform,
+        ) {
+            self.0
+                .write(context)
+                .$field
+                .set_sound_transform(context, sound_transform)
+        }
+        fn tab_index(&self) -> Option<i32> {
+            self.0.read().$field.tab_index()
+        }
+        fn set_tab_index(
+            &mut self,
+            context: gc_arena::MutationContext<'gc, '_>,
+            tab_index: Option<i32>,
+        ) {
+            self.0
+                .write(context)
+                .$field
+                .set_tab_index(context, tab_index)
+        }
+        fn tab_enabled(&self) -> Option<bool> {
+            self.0.read().$field.tab_enabled()
+        }
+        fn set_tab_enabled(
+            &mut self,
+            context: gc_arena::MutationContext<'gc, '_>,
+            tab_enabled: Option<bool>,
+        ) {
+            self.0
+                .write(context)
+                .$field
+                .set_tab_enabled(context, tab_enabled)
+        }
         fn parent(&self) -> Option<crate::display_object::DisplayObject<'gc>> {
             self.0.read().$field.parent()
         }
@@ -1158,7 +1355,7 @@ pub fn render_children<'gc>(
             context.renderer.activate_mask();
         } else if child.visible() {
             // Normal child.
-            child.render(context);
+            render_layered(context, child);
         }
     }
 
@@ -1168,6 +1365,73 @@ pub fn render_children<'gc>(
     }
 }
 
+/// Renders a display object, drawing it to an offscreen layer first if it has filters or a
+/// blend mode.
+fn render_layered<'gc>(context: &mut RenderContext<'_, 'gc>, object: DisplayObject<'gc>) {
+    let blend_mode = match object.blend_mode() {
+        // The alpha and erase modes only affect a parent layer, and otherwise draw normally.
+        BlendMode::Alpha | BlendMode::Erase if context.blend_mode != BlendMode::Layer => {
+            BlendMode::Normal
+        }
+        blend_mode => blend_mode,
+    };
+
+    if blend_mode == BlendMode::Normal && object.filters().is_empty() {
+        object.render(context);
+    } else {
+        let filters = object.filters().to_vec();
+        let parent_blend_mode = std::mem::replace(&mut context.blend_mode, blend_mode);
+        context.renderer.push_offscreen();
+        object.render(context);
+        context.renderer.pop_offscreen(&filters, blend_mode);
+        context.blend_mode = parent_blend_mode;
+    }
+}
+
+/// Tests if a stage position point is inside the shape of any of the children of a display
+/// object, taking masking into account.
+pub fn hit_test_children<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    children: &std::collections::BTreeMap<Depth, DisplayObject<'gc>>,
+    point: (Twips, Twips),
+    mode: HitTestMode,
+) -> bool {
+    unmasked_children(context, children, point)
+        .into_iter()
+        .any(|child| mode.includes(child) && child.hit_test_shape(context, point, mode))
+}
+
+/// Returns the children of a display object that aren't masks and that aren't masked out at a
+/// stage position point, from the lowest depth to the highest.
+pub fn unmasked_children<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    children: &std::collections::BTreeMap<Depth, DisplayObject<'gc>>,
+    point: (Twips, Twips),
+) -> Vec<DisplayObject<'gc>> {
+    let mut result = vec![];
+    let mut clip_depth = 0;
+    let mut inside_masks = true;
+    let mut clip_depth_stack = vec![];
+    for (&depth, &child) in children {
+        // Masks end in the same order as in `render_children`.
+        while clip_depth > 0 && depth >= clip_depth {
+            let (parent_clip_depth, parent_inside_masks) = clip_depth_stack.pop().unwrap();
+            clip_depth = parent_clip_depth;
+            inside_masks = parent_inside_masks;
+        }
+        if child.clip_depth() > 0 && child.allow_as_mask() {
+            // Masks apply whether or not they are visible.
+            clip_depth_stack.push((clip_depth, inside_masks));
+            clip_depth = child.clip_depth();
+            inside_masks =
+                inside_masks && child.hit_test_shape(context, point, HitTestMode::AvmHitTest);
+        } else if inside_masks {
+            result.push(child);
+        }
+    }
+    result
+}
+
 impl<'gc> DisplayObject<'gc> {
     pub fn ptr_eq(a: DisplayObject<'gc>, b: DisplayObject<'gc>) -> bool {
         a.as_ptr() == b.as_ptr()
@@ -1193,6 +1457,27 @@ enum DisplayObjectFlags {
     TransformedByScript,
 }
 
+/// What is tested by `hit_test_shape`. Masks are never hit themselves, but hide the parts of
+/// the objects they mask that are outside of them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HitTestMode {
+    /// `MovieClip.hitTest` with the shape flag, which also hits invisible objects.
+    AvmHitTest,
+
+    /// Finding the object under the mouse cursor, which skips invisible objects.
+    MousePick,
+}
+
+impl HitTestMode {
+    /// Whether a child object is tested in this mode.
+    pub fn includes<'gc>(self, object: DisplayObject<'gc>) -> bool {
+        match self {
+            HitTestMode::AvmHitTest => true,
+            HitTestMode::MousePick => object.visible(),
+        }
+    }
+}
+
 pub struct ChildIter<'gc> {
     cur_child: Option<DisplayObject<'gc>>,
 }
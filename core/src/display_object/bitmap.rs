@@ -1,7 +1,7 @@
 //! Bitmap display object
 
 use crate::avm1::Avm1;
-use crate::backend::render::BitmapHandle;
+use crate::backend::render::{self, BitmapHandle};
 use crate::context::{RenderContext, UpdateContext};
 use crate::display_object::{DisplayObjectBase, TDisplayObject};
 use crate::prelude::*;
@@ -9,7 +9,8 @@ use gc_arena::{Collect, Gc, GcCell};
 
 /// A Bitmap display object is a raw bitamp on the stage.
 /// This can only be instanitated on the display list in SWFv9 AVM2 files.
-/// In AVM1, this is only a library symbol that is referenced by `Graphic`.
+/// In AVM1, this is only a library symbol that is referenced by `Graphic`,
+/// or a `BitmapData` attached to a clip with `MovieClip.attachBitmap`.
 /// Normally bitmaps are drawn in Flash as part of a Shape tag (`Graphic`),
 /// but starting in AVM2, a raw `Bitmap` display object can be crated
 /// with the `PlaceObject3` tag.
@@ -21,7 +22,21 @@ pub struct Bitmap<'gc>(GcCell<'gc, BitmapData<'gc>>);
 #[derive(Clone, Debug)]
 pub struct BitmapData<'gc> {
     base: DisplayObjectBase<'gc>,
-    static_data: Gc<'gc, BitmapStatic>,
+    content: BitmapContent<'gc>,
+
+    /// Whether the bitmap is smoothed when it is scaled or rotated.
+    smoothing: bool,
+}
+
+/// Where the pixels of a bitmap come from.
+#[derive(Clone, Debug)]
+enum BitmapContent<'gc> {
+    /// A bitmap character defined in an SWF file.
+    Static(Gc<'gc, BitmapStatic>),
+
+    /// A `BitmapData` created in ActionScript. Changes to its pixels are shown the next time
+    /// the bitmap is rendered.
+    Dynamic(GcCell<'gc, crate::bitmap_data::BitmapData>),
 }
 
 impl<'gc> Bitmap<'gc> {
@@ -31,35 +46,74 @@ impl<'gc> Bitmap<'gc> {
         bitmap_handle: BitmapHandle,
         width: u16,
         height: u16,
+        source: BitmapSource,
     ) -> Self {
         Bitmap(GcCell::allocate(
             context.gc_context,
             BitmapData {
                 base: Default::default(),
-                static_data: Gc::allocate(
+                content: BitmapContent::Static(Gc::allocate(
                     context.gc_context,
                     BitmapStatic {
                         id,
                         bitmap_handle,
                         width,
                         height,
+                        source,
                     },
-                ),
+                )),
+                smoothing: false,
             },
         ))
     }
 
-    #[allow(dead_code)]
-    pub fn bitmap_handle(self) -> BitmapHandle {
-        self.0.read().static_data.bitmap_handle
+    /// Creates a bitmap that shows the pixels of a `BitmapData`.
+    pub fn with_bitmap_data(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        bitmap_data: GcCell<'gc, crate::bitmap_data::BitmapData>,
+        smoothing: bool,
+    ) -> Self {
+        Bitmap(GcCell::allocate(
+            context.gc_context,
+            BitmapData {
+                base: Default::default(),
+                content: BitmapContent::Dynamic(bitmap_data),
+                smoothing,
+            },
+        ))
     }
 
-    pub fn width(self) -> u16 {
-        self.0.read().static_data.width
+    pub fn width(self) -> u32 {
+        match &self.0.read().content {
+            BitmapContent::Static(static_data) => u32::from(static_data.width),
+            BitmapContent::Dynamic(bitmap_data) => bitmap_data.read().width(),
+        }
+    }
+
+    pub fn height(self) -> u32 {
+        match &self.0.read().content {
+            BitmapContent::Static(static_data) => u32::from(static_data.height),
+            BitmapContent::Dynamic(bitmap_data) => bitmap_data.read().height(),
+        }
     }
 
-    pub fn height(self) -> u16 {
-        self.0.read().static_data.height
+    pub fn smoothing(self) -> bool {
+        self.0.read().smoothing
+    }
+
+    /// Returns a copy of the pixels of this bitmap, decoding them if they come from an SWF
+    /// file.
+    pub fn pixels(self) -> Option<crate::bitmap_data::BitmapData> {
+        match &self.0.read().content {
+            BitmapContent::Static(static_data) => match static_data.source.decode() {
+                Ok(bitmap) => Some(crate::bitmap_data::BitmapData::from_decoded(bitmap)),
+                Err(e) => {
+                    log::error!("Unable to decode bitmap {}: {}", static_data.id, e);
+                    None
+                }
+            },
+            BitmapContent::Dynamic(bitmap_data) => Some(bitmap_data.read().clone()),
+        }
     }
 }
 
@@ -67,15 +121,18 @@ impl<'gc> TDisplayObject<'gc> for Bitmap<'gc> {
     impl_display_object!(base);
 
     fn id(&self) -> CharacterId {
-        self.0.read().static_data.id
+        match &self.0.read().content {
+            BitmapContent::Static(static_data) => static_data.id,
+            BitmapContent::Dynamic(_) => 0,
+        }
     }
 
     fn self_bounds(&self) -> BoundingBox {
         BoundingBox {
             x_min: Twips::new(0),
             y_min: Twips::new(0),
-            x_max: Twips::new(Bitmap::width(*self)),
-            y_max: Twips::new(Bitmap::height(*self)),
+            x_max: Twips::from_pixels(Bitmap::width(*self).into()),
+            y_max: Twips::from_pixels(Bitmap::height(*self).into()),
             valid: true,
         }
     }
@@ -90,21 +147,30 @@ impl<'gc> TDisplayObject<'gc> for Bitmap<'gc> {
             return;
         }
 
-        context.transform_stack.push(&*self.transform());
-
-        context.renderer.render_bitmap(
-            self.0.read().static_data.bitmap_handle,
-            context.transform_stack.transform(),
-        );
-
-        context.transform_stack.pop();
+        let bitmap_handle = match &self.0.read().content {
+            BitmapContent::Static(static_data) => Some(static_data.bitmap_handle),
+            BitmapContent::Dynamic(bitmap_data) => {
+                bitmap_data.read().bitmap_handle(context.renderer)
+            }
+        };
+
+        if let Some(bitmap_handle) = bitmap_handle {
+            context.transform_stack.push(&*self.transform());
+            context
+                .renderer
+                .render_bitmap(bitmap_handle, context.transform_stack.transform());
+            context.transform_stack.pop();
+        }
     }
 }
 
 unsafe impl<'gc> gc_arena::Collect for BitmapData<'gc> {
     fn trace(&self, cc: gc_arena::CollectionContext) {
         self.base.trace(cc);
-        self.static_data.trace(cc);
+        match &self.content {
+            BitmapContent::Static(static_data) => static_data.trace(cc),
+            BitmapContent::Dynamic(bitmap_data) => bitmap_data.trace(cc),
+        }
     }
 }
 
@@ -115,6 +181,7 @@ struct BitmapStatic {
     bitmap_handle: BitmapHandle,
     width: u16,
     height: u16,
+    source: BitmapSource,
 }
 
 unsafe impl<'gc> gc_arena::Collect for BitmapStatic {
@@ -123,3 +190,30 @@ unsafe impl<'gc> gc_arena::Collect for BitmapStatic {
         true
     }
 }
+
+/// The encoded pixels of a bitmap character, kept so that `BitmapData.loadBitmap` can decode
+/// them.
+#[derive(Clone, Debug)]
+pub enum BitmapSource {
+    /// JPEG, PNG or GIF data, with any JPEG tables already applied, and the alpha channel of
+    /// a `DefineBitsJPEG3` tag.
+    Jpeg {
+        data: Vec<u8>,
+        alpha_data: Option<Vec<u8>>,
+    },
+
+    Lossless(swf::DefineBitsLossless),
+}
+
+impl BitmapSource {
+    fn decode(&self) -> Result<render::Bitmap, Box<dyn std::error::Error>> {
+        match self {
+            BitmapSource::Jpeg { data, alpha_data } => {
+                render::decode_define_bits_jpeg(data, alpha_data.as_deref())
+            }
+            BitmapSource::Lossless(define_bits_lossless) => {
+                render::decode_define_bits_lossless(define_bits_lossless)
+            }
+        }
+    }
+}
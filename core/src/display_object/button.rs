@@ -1,6 +1,6 @@
 use crate::avm1::{Avm1, Object, StageObject, Value};
 use crate::context::{ActionType, RenderContext, UpdateContext};
-use crate::display_object::{DisplayObjectBase, TDisplayObject};
+use crate::display_object::{hit_test_children, DisplayObjectBase, HitTestMode, TDisplayObject};
 use crate::events::{ButtonKeyCode, ClipEvent, ClipEventResult};
 use crate::prelude::*;
 use crate::tag_utils::{SwfMovie, SwfSlice};
@@ -166,21 +166,52 @@ impl<'gc> TDisplayObject<'gc> for Button<'gc> {
         false
     }
 
+    fn hit_test_shape(
+        &self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        point: (Twips, Twips),
+        mode: HitTestMode,
+    ) -> bool {
+        // The mouse uses the hit area, while scripts test the state that is shown.
+        let button = self.0.read();
+        let children = match mode {
+            HitTestMode::AvmHitTest => &button.children,
+            HitTestMode::MousePick => &button.hit_area,
+        };
+        hit_test_children(context, children, point, mode)
+    }
+
     fn mouse_pick(
         &self,
         _avm: &mut Avm1<'gc>,
-        _context: &mut UpdateContext<'_, 'gc, '_>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
         self_node: DisplayObject<'gc>,
         point: (Twips, Twips),
     ) -> Option<DisplayObject<'gc>> {
         // The button is hovered if the mouse is over any child nodes.
-        if self.hit_test(point) {
+        if self.visible() && self.hit_test_shape(context, point, HitTestMode::MousePick) {
             Some(self_node)
         } else {
             None
         }
     }
 
+    fn is_focusable(
+        &self,
+        _avm: &mut Avm1<'gc>,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> bool {
+        true
+    }
+
+    fn is_tab_enabled(
+        &self,
+        _avm: &mut Avm1<'gc>,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> bool {
+        self.tab_enabled().unwrap_or(true)
+    }
+
     fn object(&self) -> Value<'gc> {
         self.0
             .read()
@@ -248,6 +279,8 @@ impl<'gc> ButtonData<'gc> {
                         context.gc_context,
                         &record.color_transform.clone().into(),
                     );
+                    child.set_filters(context.gc_context, record.filters.clone());
+                    child.set_blend_mode(context.gc_context, record.blend_mode);
                     child.set_depth(context.gc_context, record.depth.into());
                     child.post_instantiation(avm, context, child, None, false);
                     child.run_frame(avm, context);
@@ -381,7 +414,13 @@ impl<'gc> ButtonData<'gc> {
                 .library_for_movie_mut(self.movie())
                 .get_sound(*id)
             {
-                let _ = context.audio.start_sound(sound_handle, sound_info);
+                // The button itself is borrowed here, so its sounds are owned by its parent clip.
+                context.audio_manager.perform_sound_event(
+                    context.audio,
+                    sound_handle,
+                    sound_info,
+                    self.base.parent(),
+                );
             }
         }
     }
@@ -1,10 +1,11 @@
 //! `EditText` display object and support code.
 use crate::avm1::globals::text_field::attach_virtual_properties;
 use crate::avm1::{Avm1, Object, StageObject, TObject, Value};
-use crate::context::{RenderContext, UpdateContext};
+use crate::context::{ActionType, RenderContext, UpdateContext};
 use crate::display_object::{DisplayObjectBase, TDisplayObject};
 use crate::drawing::Drawing;
-use crate::font::{round_down_to_pixel, Glyph};
+use crate::events::KeyCode;
+use crate::font::{round_down_to_pixel, EvalParameters, Font, Glyph};
 use crate::html::{BoxBounds, FormatSpans, LayoutBox, TextFormat};
 use crate::prelude::*;
 use crate::shape_utils::DrawCommand;
@@ -12,6 +13,7 @@ use crate::tag_utils::SwfMovie;
 use crate::transform::Transform;
 use crate::xml::XMLDocument;
 use gc_arena::{Collect, Gc, GcCell, MutationContext};
+use std::borrow::Cow;
 use std::{cell::Ref, sync::Arc};
 use swf::Twips;
 
@@ -83,9 +85,46 @@ pub struct EditTextData<'gc> {
     /// If the text field renders as HTML.
     is_html: bool,
 
+    /// If the text field can be edited by the user.
+    is_editable: bool,
+
+    /// If the text in the text field can be selected by the user.
+    is_selectable: bool,
+
+    /// If the text field displays each character as an asterisk.
+    is_password: bool,
+
+    /// The maximum number of characters the user may enter, or zero for no
+    /// limit. Only applies to user input.
+    max_chars: i32,
+
+    /// The set of characters the user may enter, in `restrict` syntax.
+    ///
+    /// `None` allows all characters. Only applies to user input.
+    restrict: Option<String>,
+
+    /// The current selection, if any. A selection with no length is the
+    /// position of the caret.
+    selection: Option<TextSelection>,
+
+    /// Whether this text field currently has keyboard focus.
+    has_focus: bool,
+
+    /// Whether the caret is currently shown, as it blinks.
+    is_caret_visible: bool,
+
+    /// The line shown at the top of the text field, starting from 1.
+    scroll: usize,
+
+    /// Whether the mouse wheel scrolls this text field while it has focus.
+    mouse_wheel_enabled: bool,
+
     /// The current border drawing.
     drawing: Drawing,
 
+    /// The current selection highlight or caret drawing.
+    selection_drawing: Drawing,
+
     /// Whether or not the width of the field should change in response to text
     /// changes, and in what direction should added or removed width should
     /// apply.
@@ -123,6 +162,10 @@ impl<'gc> EditText<'gc> {
         let is_multiline = swf_tag.is_multiline;
         let is_word_wrap = swf_tag.is_word_wrap;
         let is_html = swf_tag.is_html;
+        let is_editable = !swf_tag.is_read_only;
+        let is_selectable = swf_tag.is_selectable;
+        let is_password = swf_tag.is_password;
+        let max_chars = swf_tag.max_length.map(i32::from).unwrap_or(0);
         let document = XMLDocument::new(context.gc_context);
         let text = swf_tag.initial_text.clone().unwrap_or_default();
         let default_format = TextFormat::from_swf_tag(swf_tag.clone(), swf_movie.clone(), context);
@@ -143,7 +186,7 @@ impl<'gc> EditText<'gc> {
         let bounds: BoundingBox = swf_tag.bounds.clone().into();
 
         let (layout, intrinsic_bounds) = LayoutBox::lower_from_text_spans(
-            &text_spans,
+            &Self::layout_spans(&text_spans, is_password),
             context,
             swf_movie.clone(),
             bounds.width() - Twips::from_pixels(Self::INTERNAL_PADDING * 2.0),
@@ -183,7 +226,18 @@ impl<'gc> EditText<'gc> {
                 has_border,
                 is_device_font,
                 is_html,
+                is_editable,
+                is_selectable,
+                is_password,
+                max_chars,
+                restrict: None,
+                selection: None,
+                has_focus: false,
+                is_caret_visible: true,
+                scroll: 1,
+                mouse_wheel_enabled: true,
                 drawing: Drawing::new(),
+                selection_drawing: Drawing::new(),
                 object: None,
                 layout,
                 intrinsic_bounds,
@@ -226,7 +280,7 @@ impl<'gc> EditText<'gc> {
                 b: 0,
                 a: 0xFF,
             }),
-            max_length: Some(width as u16),
+            max_length: None,
             layout: Some(swf::TextLayout {
                 align: swf::TextAlign::Left,
                 left_margin: Twips::from_pixels(0.0),
@@ -453,6 +507,315 @@ impl<'gc> EditText<'gc> {
         self.relayout(context);
     }
 
+    pub fn is_editable(self) -> bool {
+        self.0.read().is_editable
+    }
+
+    pub fn set_editable(self, context: MutationContext<'gc, '_>, is_editable: bool) {
+        self.0.write(context).is_editable = is_editable;
+        self.redraw_selection(context);
+    }
+
+    pub fn is_selectable(self) -> bool {
+        self.0.read().is_selectable
+    }
+
+    pub fn set_selectable(self, context: MutationContext<'gc, '_>, is_selectable: bool) {
+        self.0.write(context).is_selectable = is_selectable;
+    }
+
+    pub fn is_password(self) -> bool {
+        self.0.read().is_password
+    }
+
+    pub fn set_password(self, is_password: bool, context: &mut UpdateContext<'_, 'gc, '_>) {
+        self.0.write(context.gc_context).is_password = is_password;
+        self.relayout(context);
+    }
+
+    pub fn max_chars(self) -> i32 {
+        self.0.read().max_chars
+    }
+
+    pub fn set_max_chars(self, context: MutationContext<'gc, '_>, max_chars: i32) {
+        self.0.write(context).max_chars = max_chars;
+    }
+
+    pub fn restrict(self) -> Option<String> {
+        self.0.read().restrict.clone()
+    }
+
+    pub fn set_restrict(self, context: MutationContext<'gc, '_>, restrict: Option<String>) {
+        self.0.write(context).restrict = restrict;
+    }
+
+    pub fn has_focus(self) -> bool {
+        self.0.read().has_focus
+    }
+
+    pub fn selection(self) -> Option<TextSelection> {
+        self.0.read().selection
+    }
+
+    /// Change the current selection, clamped to the current text.
+    ///
+    /// The caret is always shown immediately after the selection changes.
+    pub fn set_selection(
+        self,
+        context: MutationContext<'gc, '_>,
+        selection: Option<TextSelection>,
+    ) {
+        let mut edit_text = self.0.write(context);
+        let selection = selection.map(|selection| selection.clamped(edit_text.text_spans.text()));
+        edit_text.selection = selection;
+        edit_text.is_caret_visible = true;
+        drop(edit_text);
+
+        self.redraw_selection(context);
+    }
+
+    /// Toggle the visibility of the caret, causing it to blink.
+    pub fn blink_caret(self, context: MutationContext<'gc, '_>) {
+        let mut edit_text = self.0.write(context);
+        edit_text.is_caret_visible = !edit_text.is_caret_visible;
+        drop(edit_text);
+
+        self.redraw_selection(context);
+    }
+
+    /// The line shown at the top of the text field, starting from 1.
+    pub fn scroll(self) -> usize {
+        self.0.read().scroll
+    }
+
+    /// Scroll the text field so that the given line is shown at the top.
+    ///
+    /// The line is clamped between the first line and `maxscroll`.
+    pub fn set_scroll(self, context: MutationContext<'gc, '_>, scroll: usize) {
+        let max_scroll = self.max_scroll();
+        self.0.write(context).scroll = scroll.max(1).min(max_scroll);
+    }
+
+    /// The largest value of `scroll` that still shows text in every line of
+    /// the text field.
+    pub fn max_scroll(self) -> usize {
+        let edit_text = self.0.read();
+        max_scroll(
+            &Self::line_extents(&edit_text.layout),
+            edit_text.text_height(),
+        )
+    }
+
+    /// The last line that is fully visible in the text field, starting from 1.
+    pub fn bottom_scroll(self) -> usize {
+        let edit_text = self.0.read();
+        bottom_scroll(
+            &Self::line_extents(&edit_text.layout),
+            edit_text.scroll,
+            edit_text.text_height(),
+        )
+    }
+
+    pub fn mouse_wheel_enabled(self) -> bool {
+        self.0.read().mouse_wheel_enabled
+    }
+
+    pub fn set_mouse_wheel_enabled(self, context: MutationContext<'gc, '_>, is_enabled: bool) {
+        self.0.write(context).mouse_wheel_enabled = is_enabled;
+    }
+
+    /// Find the vertical extent of each line of the laid-out text.
+    ///
+    /// Text boxes whose extents overlap are considered to share a line.
+    fn line_extents(layout: &[LayoutBox<'gc>]) -> Vec<(Twips, Twips)> {
+        let mut lines: Vec<(Twips, Twips)> = Vec::new();
+        for layout_box in layout.iter().filter(|layout_box| layout_box.is_text_box()) {
+            let bounds = layout_box.bounds();
+            match lines.last_mut() {
+                Some((_, bottom)) if bounds.offset_y() < *bottom => {
+                    *bottom = (*bottom).max(bounds.extent_y());
+                }
+                _ => lines.push((bounds.offset_y(), bounds.extent_y())),
+            }
+        }
+        lines
+    }
+
+    /// The vertical offset of the text caused by scrolling.
+    fn scroll_offset(edit_text: &EditTextData<'gc>) -> Twips {
+        Self::line_extents(&edit_text.layout)
+            .get(edit_text.scroll - 1)
+            .map(|(top, _)| *top)
+            .unwrap_or_default()
+    }
+
+    /// Find the position in the text nearest to a given point on the stage.
+    ///
+    /// Points above or below the text snap to the nearest line, and points
+    /// to either side of a line snap to its nearest end.
+    pub fn screen_position_to_index(self, position: (Twips, Twips)) -> usize {
+        let local_position = self.global_to_local(position);
+        let edit_text = self.0.read();
+        let padding = Twips::from_pixels(Self::INTERNAL_PADDING);
+        let x = local_position.0 - edit_text.bounds.x_min - padding;
+        let y =
+            local_position.1 - edit_text.bounds.y_min - padding + Self::scroll_offset(&edit_text);
+        let text = edit_text.text_spans.text();
+        let layout_text = Self::layout_text(text, edit_text.is_password);
+
+        let mut closest: Option<((Twips, Twips), &LayoutBox<'gc>)> = None;
+        for layout_box in edit_text.layout.iter() {
+            if layout_box.text_range().is_none() {
+                continue;
+            }
+
+            let bounds = layout_box.bounds();
+            let distance = (
+                distance_outside(y, bounds.offset_y(), bounds.extent_y()),
+                distance_outside(x, bounds.offset_x(), bounds.extent_x()),
+            );
+            if closest
+                .map(|(closest, _)| distance < closest)
+                .unwrap_or(true)
+            {
+                closest = Some((distance, layout_box));
+            }
+        }
+
+        let layout_position = match closest {
+            Some((_, layout_box)) => Self::layout_box_position_at(layout_box, &layout_text, x),
+            None => 0,
+        };
+
+        Self::layout_to_text_position(text, edit_text.is_password, layout_position)
+    }
+
+    /// Place the caret at the position nearest to a point on the stage.
+    ///
+    /// If `is_extending` is set, the current selection is instead extended
+    /// up to that position, as when dragging or shift-clicking.
+    pub fn select_at_position(
+        self,
+        context: MutationContext<'gc, '_>,
+        position: (Twips, Twips),
+        is_extending: bool,
+    ) {
+        let index = self.screen_position_to_index(position);
+        let selection = match self.selection() {
+            Some(selection) if is_extending => TextSelection::for_range(selection.from(), index),
+            _ => TextSelection::for_position(index),
+        };
+
+        self.set_selection(context, Some(selection));
+    }
+
+    /// Handle a character typed by the user.
+    ///
+    /// The character replaces the current selection, unless the text field
+    /// is not editable or the character is disallowed by `restrict`.
+    pub fn text_input(
+        self,
+        character: char,
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) {
+        if !self.is_editable() || character.is_control() {
+            return;
+        }
+
+        if let Some(restrict) = &self.0.read().restrict {
+            if !restrict_allows(restrict, character) {
+                return;
+            }
+        }
+
+        if let Some(selection) = self.selection() {
+            let mut buffer = [0; 4];
+            self.replace_text_as_user(
+                selection.start(),
+                selection.end(),
+                character.encode_utf8(&mut buffer),
+                avm,
+                context,
+            );
+        }
+    }
+
+    /// Handle a key pressed by the user, for caret movement, selection,
+    /// deletion and clipboard shortcuts.
+    pub fn key_down(
+        self,
+        key_code: KeyCode,
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) {
+        let selection = match self.selection() {
+            Some(selection) => selection,
+            None => return,
+        };
+        let text = self.text();
+        let is_shift_down = context.input.is_key_down(KeyCode::Shift);
+        let is_control_down = context.input.is_key_down(KeyCode::Control);
+        let is_editable = self.is_editable();
+
+        match key_code {
+            KeyCode::Left | KeyCode::Right | KeyCode::Home | KeyCode::End => {
+                let to = match key_code {
+                    KeyCode::Left if !is_shift_down && !selection.is_caret() => selection.start(),
+                    KeyCode::Right if !is_shift_down && !selection.is_caret() => selection.end(),
+                    KeyCode::Left => previous_char_boundary(&text, selection.to()),
+                    KeyCode::Right => next_char_boundary(&text, selection.to()),
+                    KeyCode::Home => 0,
+                    _ => text.len(),
+                };
+                let new_selection = if is_shift_down {
+                    TextSelection::for_range(selection.from(), to)
+                } else {
+                    TextSelection::for_position(to)
+                };
+
+                self.set_selection(context.gc_context, Some(new_selection));
+            }
+            KeyCode::Backspace | KeyCode::Delete if is_editable => {
+                let (start, end) = if !selection.is_caret() {
+                    (selection.start(), selection.end())
+                } else if key_code == KeyCode::Backspace {
+                    (
+                        previous_char_boundary(&text, selection.to()),
+                        selection.to(),
+                    )
+                } else {
+                    (selection.to(), next_char_boundary(&text, selection.to()))
+                };
+
+                if start < end {
+                    self.replace_text_as_user(start, end, "", avm, context);
+                }
+            }
+            KeyCode::Return if is_editable && self.is_multiline() => {
+                self.replace_text_as_user(selection.start(), selection.end(), "\n", avm, context);
+            }
+            KeyCode::A if is_control_down => {
+                self.set_selection(
+                    context.gc_context,
+                    Some(TextSelection::for_range(0, text.len())),
+                );
+            }
+            KeyCode::C | KeyCode::X
+                if is_control_down && !selection.is_caret() && !self.is_password() =>
+            {
+                context
+                    .input
+                    .set_clipboard_content(text[selection.start()..selection.end()].to_string());
+
+                if key_code == KeyCode::X && is_editable {
+                    self.replace_text_as_user(selection.start(), selection.end(), "", avm, context);
+                }
+            }
+            _ => {}
+        }
+    }
+
     /// Construct a base text transform for a particular `EditText` span.
     ///
     /// This `text_transform` is separate from and relative to the base
@@ -571,6 +934,267 @@ impl<'gc> EditText<'gc> {
         }
     }
 
+    /// Redraw the selection highlight or caret of this `EditText`.
+    ///
+    /// Nothing is drawn unless the text field has focus.
+    fn redraw_selection(self, context: MutationContext<'gc, '_>) {
+        let edit_text = self.0.read();
+        let mut rects = Vec::new();
+
+        if let Some(selection) = edit_text.selection.filter(|_| edit_text.has_focus) {
+            let text = edit_text.text_spans.text();
+            let is_password = edit_text.is_password;
+            let layout_text = Self::layout_text(text, is_password);
+            let start = Self::text_to_layout_position(text, is_password, selection.start());
+            let end = Self::text_to_layout_position(text, is_password, selection.end());
+
+            if !selection.is_caret() {
+                for layout_box in edit_text.layout.iter() {
+                    if let Some((box_start, box_end)) = layout_box.text_range() {
+                        if start < box_end && end > box_start {
+                            let bounds = layout_box.bounds();
+                            rects.push((
+                                Self::layout_box_x_at(
+                                    layout_box,
+                                    &layout_text,
+                                    start.max(box_start),
+                                ),
+                                bounds.offset_y(),
+                                Self::layout_box_x_at(layout_box, &layout_text, end.min(box_end)),
+                                bounds.extent_y(),
+                            ));
+                        }
+                    }
+                }
+            } else if edit_text.is_editable && edit_text.is_caret_visible {
+                let (x, y, height) = Self::caret_position(&edit_text.layout, &layout_text, start)
+                    .unwrap_or_else(|| {
+                        let size = edit_text.text_spans.default_format().size.unwrap_or(12.0);
+                        (Twips::zero(), Twips::zero(), Twips::from_pixels(size))
+                    });
+                rects.push((x, y, x + Twips::from_pixels(1.0), y + height));
+            }
+        }
+
+        drop(edit_text);
+
+        let mut write = self.0.write(context);
+        write.selection_drawing.clear();
+
+        for (x_min, y_min, x_max, y_max) in rects {
+            write
+                .selection_drawing
+                .set_fill_style(Some(swf::FillStyle::Color(swf::Color::from_rgb(0, 0xFF))));
+            write
+                .selection_drawing
+                .draw_command(DrawCommand::MoveTo { x: x_min, y: y_min });
+            write
+                .selection_drawing
+                .draw_command(DrawCommand::LineTo { x: x_max, y: y_min });
+            write
+                .selection_drawing
+                .draw_command(DrawCommand::LineTo { x: x_max, y: y_max });
+            write
+                .selection_drawing
+                .draw_command(DrawCommand::LineTo { x: x_min, y: y_max });
+            write
+                .selection_drawing
+                .draw_command(DrawCommand::LineTo { x: x_min, y: y_min });
+        }
+
+        write.selection_drawing.set_fill_style(None);
+    }
+
+    /// Replace a range of text on behalf of the user, respecting `maxChars`,
+    /// and leave the caret after the inserted text.
+    fn replace_text_as_user(
+        self,
+        from: usize,
+        to: usize,
+        text: &str,
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) {
+        let max_chars = self.max_chars();
+        let text = if max_chars > 0 {
+            let current_text = self.text();
+            let remaining_chars = current_text.chars().count()
+                - current_text
+                    .get(from..to)
+                    .map(|replaced| replaced.chars().count())
+                    .unwrap_or(0);
+            let available_chars = (max_chars as usize).saturating_sub(remaining_chars);
+
+            Cow::Owned(text.chars().take(available_chars).collect::<String>())
+        } else {
+            Cow::Borrowed(text)
+        };
+
+        if text.is_empty() && from == to {
+            return;
+        }
+
+        self.0
+            .write(context.gc_context)
+            .text_spans
+            .replace_text(from, to, &text, None);
+        self.relayout(context);
+        self.set_selection(
+            context.gc_context,
+            Some(TextSelection::for_position(from + text.len())),
+        );
+
+        self.propagate_text_binding(avm, context);
+
+        if let Some(object) = self.0.read().object {
+            context.action_queue.queue_actions(
+                self.into(),
+                ActionType::Method {
+                    object,
+                    name: "onChanged",
+                    args: vec![object.into()],
+                },
+                false,
+            );
+        }
+    }
+
+    /// The format spans that are actually laid out, which are masked in
+    /// password fields.
+    fn layout_spans(text_spans: &FormatSpans, is_password: bool) -> Cow<'_, FormatSpans> {
+        if is_password {
+            Cow::Owned(text_spans.hide_text())
+        } else {
+            Cow::Borrowed(text_spans)
+        }
+    }
+
+    /// The text that is actually laid out, which is masked in password
+    /// fields.
+    fn layout_text(text: &str, is_password: bool) -> Cow<'_, str> {
+        if is_password {
+            Cow::Owned("*".repeat(text.chars().count()))
+        } else {
+            Cow::Borrowed(text)
+        }
+    }
+
+    /// Convert a position in the text into the equivalent position in the
+    /// laid-out text.
+    fn text_to_layout_position(text: &str, is_password: bool, position: usize) -> usize {
+        if is_password {
+            text.get(..position)
+                .map(|text| text.chars().count())
+                .unwrap_or(position)
+        } else {
+            position
+        }
+    }
+
+    /// Convert a position in the laid-out text into the equivalent position
+    /// in the text.
+    fn layout_to_text_position(text: &str, is_password: bool, position: usize) -> usize {
+        if is_password {
+            text.char_indices()
+                .nth(position)
+                .map(|(index, _)| index)
+                .unwrap_or_else(|| text.len())
+        } else {
+            position
+        }
+    }
+
+    /// Find the position within a text box nearest to a horizontal offset.
+    fn layout_box_position_at(layout_box: &LayoutBox<'gc>, layout_text: &str, x: Twips) -> usize {
+        let (start, end) = match layout_box.text_range() {
+            Some(range) => range,
+            None => return 0,
+        };
+        let (text, _text_format, font, params, _color) =
+            match layout_box.as_renderable_text(layout_text) {
+                Some(renderable) => renderable,
+                None => return start,
+            };
+
+        let x = x - layout_box.bounds().offset_x();
+        let mut last_width = Twips::zero();
+        for (index, character) in text.char_indices() {
+            let width = measure_prefix(font, params, text, index + character.len_utf8());
+
+            // Snap to whichever side of the character is nearer.
+            if x < (last_width + width) / 2 {
+                return start + index;
+            }
+
+            last_width = width;
+        }
+
+        end
+    }
+
+    /// Find the horizontal offset of a position within a text box.
+    fn layout_box_x_at(layout_box: &LayoutBox<'gc>, layout_text: &str, position: usize) -> Twips {
+        let bounds = layout_box.bounds();
+
+        match (
+            layout_box.text_range(),
+            layout_box.as_renderable_text(layout_text),
+        ) {
+            (Some((start, _end)), Some((text, _text_format, font, params, _color))) => {
+                bounds.offset_x()
+                    + measure_prefix(font, params, text, position.saturating_sub(start))
+            }
+            _ => bounds.offset_x(),
+        }
+    }
+
+    /// Find the location of the caret for a position in the laid-out text.
+    ///
+    /// Returns the horizontal offset, top and height of the caret, or `None`
+    /// if no text has been laid out.
+    fn caret_position(
+        layout: &[LayoutBox<'gc>],
+        layout_text: &str,
+        position: usize,
+    ) -> Option<(Twips, Twips, Twips)> {
+        let mut preceding_box = None;
+
+        for layout_box in layout.iter() {
+            if let Some((start, end)) = layout_box.text_range() {
+                if start <= position && position < end {
+                    let bounds = layout_box.bounds();
+                    return Some((
+                        Self::layout_box_x_at(layout_box, layout_text, position),
+                        bounds.offset_y(),
+                        bounds.height(),
+                    ));
+                }
+
+                if end <= position {
+                    preceding_box = Some((layout_box, end));
+                }
+            }
+        }
+
+        let (layout_box, end) = preceding_box?;
+        let bounds = layout_box.bounds();
+
+        // A caret after a line break sits at the start of the next line.
+        if layout_text
+            .get(end..position)
+            .map(|skipped| skipped.contains('\n'))
+            .unwrap_or(false)
+        {
+            Some((Twips::zero(), bounds.extent_y(), bounds.height()))
+        } else {
+            Some((
+                Self::layout_box_x_at(layout_box, layout_text, end),
+                bounds.offset_y(),
+                bounds.height(),
+            ))
+        }
+    }
+
     /// Internal padding between the bounds of the EditText and the text.
     /// Applies to each side.
     const INTERNAL_PADDING: f64 = 2.0;
@@ -589,7 +1213,7 @@ impl<'gc> EditText<'gc> {
         let width = edit_text.bounds.width() - Twips::from_pixels(Self::INTERNAL_PADDING * 2.0);
 
         let (new_layout, intrinsic_bounds) = LayoutBox::lower_from_text_spans(
-            &edit_text.text_spans,
+            &Self::layout_spans(&edit_text.text_spans, edit_text.is_password),
             context,
             movie,
             width,
@@ -600,6 +1224,12 @@ impl<'gc> EditText<'gc> {
         edit_text.layout = new_layout;
         edit_text.intrinsic_bounds = intrinsic_bounds;
 
+        // The text may have changed out from under the selection.
+        let selection = edit_text
+            .selection
+            .map(|selection| selection.clamped(edit_text.text_spans.text()));
+        edit_text.selection = selection;
+
         match autosize {
             AutoSizeMode::None => {}
             AutoSizeMode::Left => {
@@ -638,6 +1268,16 @@ impl<'gc> EditText<'gc> {
                 edit_text.base.set_transformed_by_script(true);
             }
         }
+
+        // The text may also have become too short to remain scrolled.
+        let max_scroll = max_scroll(
+            &Self::line_extents(&edit_text.layout),
+            edit_text.text_height(),
+        );
+        edit_text.scroll = edit_text.scroll.min(max_scroll);
+
+        drop(edit_text);
+        self.redraw_selection(context.gc_context);
     }
 
     /// Measure the width and height of the `EditText`'s current text load.
@@ -653,34 +1293,62 @@ impl<'gc> EditText<'gc> {
     }
 
     /// Render a layout box, plus it's children.
-    fn render_layout_box(self, context: &mut RenderContext<'_, 'gc>, lbox: &LayoutBox<'gc>) {
+    ///
+    /// Any part of the box that falls within `selection` (a range of the
+    /// laid-out text) is rendered in white, over the selection highlight.
+    fn render_layout_box(
+        self,
+        context: &mut RenderContext<'_, 'gc>,
+        lbox: &LayoutBox<'gc>,
+        layout_text: &str,
+        selection: Option<(usize, usize)>,
+    ) {
         let box_transform: Transform = lbox.bounds().origin().into();
         context.transform_stack.push(&box_transform);
 
-        let edit_text = self.0.read();
-
         // If the font can't be found or has no glyph information, use the "device font" instead.
         // We're cheating a bit and not actually rendering text using the OS/web.
         // Instead, we embed an SWF version of Noto Sans to use as the "device font", and render
         // it the same as any other SWF outline text.
-        if let Some((text, _tf, font, params, color)) =
-            lbox.as_renderable_text(edit_text.text_spans.text())
-        {
+        if let Some((text, _tf, font, params, color)) = lbox.as_renderable_text(layout_text) {
             let baseline_adjustmnet =
                 font.get_baseline_for_height(params.height()) - params.height();
-            font.evaluate(
-                text,
-                self.text_transform(color, baseline_adjustmnet),
-                params,
-                |transform, glyph: &Glyph, _advance| {
-                    // Render glyph.
-                    context.transform_stack.push(transform);
-                    context
-                        .renderer
-                        .render_shape(glyph.shape, context.transform_stack.transform());
-                    context.transform_stack.pop();
-                },
-            );
+
+            let (selection_start, selection_end) = match (lbox.text_range(), selection) {
+                (Some((start, _end)), Some((selection_start, selection_end))) => (
+                    selection_start.saturating_sub(start).min(text.len()),
+                    selection_end.saturating_sub(start).min(text.len()),
+                ),
+                _ => (0, 0),
+            };
+            let selected_color = swf::Color::from_rgb(0xFFFFFF, color.a);
+            let segments = [
+                (0, selection_start, color.clone()),
+                (selection_start, selection_end, selected_color),
+                (selection_end, text.len(), color),
+            ];
+
+            for (segment_start, segment_end, color) in segments.iter() {
+                if segment_start >= segment_end {
+                    continue;
+                }
+
+                let mut transform = self.text_transform(color.clone(), baseline_adjustmnet);
+                transform.matrix.tx += measure_prefix(font, params, text, *segment_start);
+                font.evaluate(
+                    &text[*segment_start..*segment_end],
+                    transform,
+                    params,
+                    |transform, glyph: &Glyph, _advance| {
+                        // Render glyph.
+                        context.transform_stack.push(transform);
+                        context
+                            .renderer
+                            .render_shape(glyph.shape, context.transform_stack.transform());
+                        context.transform_stack.pop();
+                    },
+                );
+            }
         }
 
         if let Some(drawing) = lbox.as_renderable_drawing() {
@@ -976,20 +1644,107 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
             ..Default::default()
         });
 
-        for layout_box in self.0.read().layout.iter() {
-            self.render_layout_box(context, layout_box);
+        let edit_text = self.0.read();
+        let scroll_offset = Self::scroll_offset(&edit_text);
+        context.transform_stack.push(&Transform {
+            matrix: Matrix {
+                ty: Twips::zero() - scroll_offset,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        let text = edit_text.text_spans.text();
+        let is_password = edit_text.is_password;
+        let layout_text = Self::layout_text(text, is_password);
+        let selection = edit_text
+            .selection
+            .filter(|selection| edit_text.has_focus && !selection.is_caret())
+            .map(|selection| {
+                (
+                    Self::text_to_layout_position(text, is_password, selection.start()),
+                    Self::text_to_layout_position(text, is_password, selection.end()),
+                )
+            });
+
+        edit_text.selection_drawing.render(context);
+
+        // Lines scrolled out of the top of the text field are not drawn.
+        for layout_box in edit_text.layout.iter() {
+            let is_scrolled_out = (layout_box.is_text_box() || layout_box.is_bullet())
+                && layout_box.bounds().offset_y() < scroll_offset;
+            if !is_scrolled_out {
+                self.render_layout_box(context, layout_box, &layout_text, selection);
+            }
         }
+        drop(edit_text);
 
         context.transform_stack.pop();
         context.transform_stack.pop();
         context.transform_stack.pop();
+        context.transform_stack.pop();
     }
 
     fn allow_as_mask(&self) -> bool {
         false
     }
 
+    fn is_focusable(
+        &self,
+        _avm: &mut Avm1<'gc>,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> bool {
+        self.is_editable() || self.is_selectable()
+    }
+
+    /// Only input text fields are in the tab order by default.
+    fn is_tab_enabled(
+        &self,
+        _avm: &mut Avm1<'gc>,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> bool {
+        self.tab_enabled().unwrap_or_else(|| self.is_editable())
+    }
+
+    fn mouse_pick(
+        &self,
+        _avm: &mut Avm1<'gc>,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+        self_node: DisplayObject<'gc>,
+        point: (Twips, Twips),
+    ) -> Option<DisplayObject<'gc>> {
+        let is_interactive = self.is_editable() || self.is_selectable();
+        if self.visible() && is_interactive && self.world_bounds().contains(point) {
+            Some(self_node)
+        } else {
+            None
+        }
+    }
+
+    fn on_focus_changed(&self, gc_context: MutationContext<'gc, '_>, focused: bool) {
+        let mut edit_text = self.0.write(gc_context);
+        edit_text.has_focus = focused;
+        edit_text.is_caret_visible = true;
+
+        // Text fields that gain focus without being clicked select everything.
+        if focused && edit_text.selection.is_none() {
+            let length = edit_text.text_spans.text().len();
+            edit_text.selection = Some(TextSelection::for_range(0, length));
+        }
+        drop(edit_text);
+
+        self.redraw_selection(gc_context);
+    }
+
     fn unload(&mut self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        // Removed text fields lose focus.
+        let focus_tracker = context.focus_tracker;
+        if let Some(focused) = focus_tracker.get() {
+            if DisplayObject::ptr_eq(focused, (*self).into()) {
+                focus_tracker.set(None, context);
+            }
+        }
+
         // Unbind any display objects bound to this text.
         if let Some(stage_object) = self.0.write(context.gc_context).bound_stage_object.take() {
             stage_object.clear_text_field_binding(context.gc_context, *self);
@@ -1011,6 +1766,203 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
     }
 }
 
+/// A range of selected text within an `EditText`, as byte positions into
+/// its text.
+///
+/// `from` is where the selection was started and `to` is where the caret
+/// currently is, so `from` may come after `to`. A selection where both are
+/// equal is just the position of the caret.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Collect)]
+#[collect(require_static)]
+pub struct TextSelection {
+    from: usize,
+    to: usize,
+}
+
+impl TextSelection {
+    /// Construct a selection that is just a caret position.
+    pub fn for_position(position: usize) -> Self {
+        Self {
+            from: position,
+            to: position,
+        }
+    }
+
+    /// Construct a selection from where it was started to the caret.
+    pub fn for_range(from: usize, to: usize) -> Self {
+        Self { from, to }
+    }
+
+    /// The position the selection was started from.
+    pub fn from(&self) -> usize {
+        self.from
+    }
+
+    /// The position of the caret.
+    pub fn to(&self) -> usize {
+        self.to
+    }
+
+    /// The first selected position.
+    pub fn start(&self) -> usize {
+        self.from.min(self.to)
+    }
+
+    /// The position after the last selected character.
+    pub fn end(&self) -> usize {
+        self.from.max(self.to)
+    }
+
+    /// Whether this selection is empty and only positions the caret.
+    pub fn is_caret(&self) -> bool {
+        self.from == self.to
+    }
+
+    /// Fit this selection within a given text, moving each end back onto a
+    /// character boundary if necessary.
+    pub fn clamped(self, text: &str) -> Self {
+        let clamp = |mut position: usize| {
+            position = position.min(text.len());
+            while !text.is_char_boundary(position) {
+                position -= 1;
+            }
+            position
+        };
+
+        Self {
+            from: clamp(self.from),
+            to: clamp(self.to),
+        }
+    }
+}
+
+impl<'gc> EditTextData<'gc> {
+    /// The height available to text inside the text field.
+    fn text_height(&self) -> Twips {
+        self.bounds.height() - Twips::from_pixels(EditText::INTERNAL_PADDING * 2.0)
+    }
+}
+
+/// The largest line that may be scrolled to the top of a text field of the
+/// given height, starting from 1.
+///
+/// This is the first line from which every following line fits into the
+/// text field.
+fn max_scroll(lines: &[(Twips, Twips)], height: Twips) -> usize {
+    let bottom = match lines.last() {
+        Some((_, bottom)) => *bottom,
+        None => return 1,
+    };
+
+    lines
+        .iter()
+        .position(|(top, _)| bottom - *top <= height)
+        .unwrap_or(lines.len() - 1)
+        + 1
+}
+
+/// The last line that is fully visible in a text field of the given height
+/// when scrolled to `scroll`, starting from 1.
+///
+/// The top line always counts as visible, even if it does not fit.
+fn bottom_scroll(lines: &[(Twips, Twips)], scroll: usize, height: Twips) -> usize {
+    let top = match lines.get(scroll.max(1) - 1) {
+        Some((top, _)) => *top,
+        None => return scroll.max(1),
+    };
+
+    lines
+        .iter()
+        .skip(scroll.max(1))
+        .take_while(|(_, bottom)| *bottom - top <= height)
+        .count()
+        + scroll.max(1)
+}
+
+/// Measure the width of the first `length` bytes of a string.
+fn measure_prefix(font: Font<'_>, params: EvalParameters, text: &str, length: usize) -> Twips {
+    text.get(..length)
+        .map(|prefix| font.measure(prefix, params, false).0)
+        .unwrap_or_default()
+}
+
+/// How far a value lies outside of a range, or zero if it is inside.
+fn distance_outside(value: Twips, min: Twips, max: Twips) -> Twips {
+    if value < min {
+        min - value
+    } else if value > max {
+        value - max
+    } else {
+        Twips::zero()
+    }
+}
+
+/// The position of the character before a given position.
+fn previous_char_boundary(text: &str, position: usize) -> usize {
+    text.get(..position)
+        .and_then(|text| text.char_indices().next_back())
+        .map(|(index, _)| index)
+        .unwrap_or(0)
+}
+
+/// The position of the character after a given position.
+fn next_char_boundary(text: &str, position: usize) -> usize {
+    text.get(position..)
+        .and_then(|text| text.chars().next())
+        .map(|character| position + character.len_utf8())
+        .unwrap_or_else(|| text.len())
+}
+
+/// Check if a character may be entered into a text field with the given
+/// `restrict` pattern.
+///
+/// The pattern lists allowed characters, with `-` denoting a range. A `^`
+/// toggles between allowing and disallowing the characters that follow it,
+/// and `\` escapes the next character. Later parts of the pattern take
+/// precedence over earlier ones, so `A-Z^Q` allows every capital letter other
+/// than `Q`. Patterns that begin with `^` allow anything not disallowed.
+fn restrict_allows(restrict: &str, character: char) -> bool {
+    let mut is_allowed = restrict.starts_with('^');
+    let mut is_excluding = false;
+    let mut chars = restrict.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let start = match c {
+            '^' => {
+                is_excluding = !is_excluding;
+                continue;
+            }
+            '\\' => match chars.next() {
+                Some(escaped) => escaped,
+                None => break,
+            },
+            c => c,
+        };
+
+        let mut end = start;
+        if chars.peek() == Some(&'-') {
+            chars.next();
+            end = match chars.next() {
+                Some('\\') => chars.next().unwrap_or(start),
+                Some(c) => c,
+                // A trailing `-` is a literal.
+                None => {
+                    if character == '-' {
+                        is_allowed = !is_excluding;
+                    }
+                    start
+                }
+            };
+        }
+
+        if (start..=end).contains(&character) {
+            is_allowed = !is_excluding;
+        }
+    }
+
+    is_allowed
+}
+
 /// Static data shared between all instances of a text object.
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
@@ -1025,3 +1977,218 @@ unsafe impl<'gc> gc_arena::Collect for EditTextStatic {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{bottom_scroll, max_scroll, restrict_allows, EditText, TextSelection};
+    use crate::avm1::test_utils::with_avm;
+    use crate::avm1::error::Error;
+    use crate::avm1::Avm1;
+    use crate::context::{ActionType, UpdateContext};
+    use crate::display_object::TDisplayObject;
+    use crate::events::KeyCode;
+    use swf::Twips;
+
+    /// Creates a focused, editable text field with the caret at the given position, like one
+    /// the user has clicked into.
+    fn focused_text_field<'gc>(
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        text: &str,
+        caret: usize,
+    ) -> EditText<'gc> {
+        let mut text_field = EditText::new(context, context.swf.clone(), 0.0, 0.0, 100.0, 20.0);
+        text_field.post_instantiation(avm, context, text_field.into(), None, true);
+        text_field.set_editable(context.gc_context, true);
+        text_field.set_text(text.to_string(), context).unwrap();
+        let focus_tracker = context.focus_tracker;
+        focus_tracker.set(Some(text_field.into()), context);
+        text_field.set_selection(context.gc_context, Some(TextSelection::for_position(caret)));
+        changed_count(context);
+        text_field
+    }
+
+    /// Drains the action queue, returning how many `onChanged` events were queued.
+    fn changed_count(context: &mut UpdateContext<'_, '_, '_>) -> usize {
+        let mut count = 0;
+        while let Some(actions) = context.action_queue.pop_action() {
+            if let ActionType::Method {
+                name: "onChanged", ..
+            } = actions.action_type
+            {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    fn caret(text_field: EditText<'_>) -> Option<usize> {
+        text_field
+            .selection()
+            .filter(|selection| selection.is_caret())
+            .map(|selection| selection.to())
+    }
+
+    fn lines(count: usize) -> Vec<(Twips, Twips)> {
+        (0..count)
+            .map(|line| {
+                (
+                    Twips::new(line as i32 * 200),
+                    Twips::new(line as i32 * 200 + 200),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn restrict_characters_and_ranges() {
+        assert!(restrict_allows("abc", 'b'));
+        assert!(!restrict_allows("abc", 'd'));
+        assert!(restrict_allows("A-Z0-9", 'Q'));
+        assert!(restrict_allows("A-Z0-9", '5'));
+        assert!(!restrict_allows("A-Z0-9", 'q'));
+        assert!(!restrict_allows("", 'a'));
+    }
+
+    #[test]
+    fn restrict_exclusions() {
+        assert!(restrict_allows("A-Z^Q", 'P'));
+        assert!(!restrict_allows("A-Z^Q", 'Q'));
+        assert!(restrict_allows("^0-9", 'a'));
+        assert!(!restrict_allows("^0-9", '7'));
+        assert!(restrict_allows("^a-z^m", 'm'));
+    }
+
+    #[test]
+    fn restrict_escapes() {
+        assert!(restrict_allows("0-9\\-", '-'));
+        assert!(restrict_allows("\\^", '^'));
+        assert!(restrict_allows("0-9-", '-'));
+        assert!(!restrict_allows("0-9\\-", 'a'));
+    }
+
+    #[test]
+    fn selection_clamped() {
+        let selection = TextSelection::for_range(10, 1).clamped("aé");
+        assert_eq!(selection.from(), 3);
+        assert_eq!(selection.to(), 1);
+        assert_eq!(selection.start(), 1);
+        assert_eq!(selection.end(), 3);
+
+        let selection = TextSelection::for_position(2).clamped("aé");
+        assert_eq!(selection.to(), 1);
+        assert!(selection.is_caret());
+    }
+
+    #[test]
+    fn scroll_limits() {
+        assert_eq!(max_scroll(&[], Twips::new(1000)), 1);
+        assert_eq!(max_scroll(&lines(3), Twips::new(1000)), 1);
+        assert_eq!(max_scroll(&lines(10), Twips::new(1000)), 6);
+        assert_eq!(max_scroll(&lines(10), Twips::new(100)), 10);
+
+        assert_eq!(bottom_scroll(&lines(3), 1, Twips::new(1000)), 3);
+        assert_eq!(bottom_scroll(&lines(10), 1, Twips::new(1000)), 5);
+        assert_eq!(bottom_scroll(&lines(10), 4, Twips::new(1000)), 8);
+        assert_eq!(bottom_scroll(&lines(10), 2, Twips::new(100)), 2);
+    }
+
+    #[test]
+    fn type_at_caret() {
+        with_avm(8, |avm, context, _this| -> Result<(), Error> {
+            let text_field = focused_text_field(avm, context, "helo", 3);
+            text_field.text_input('l', avm, context);
+            assert_eq!(text_field.text(), "hello");
+            assert_eq!(caret(text_field), Some(4));
+            assert_eq!(changed_count(context), 1);
+
+            text_field.set_selection(context.gc_context, Some(TextSelection::for_range(1, 4)));
+            text_field.text_input('é', avm, context);
+            assert_eq!(text_field.text(), "héo");
+            assert_eq!(caret(text_field), Some(3));
+            assert_eq!(changed_count(context), 1);
+
+            // Control characters are handled by `key_down` instead.
+            text_field.text_input('\u{8}', avm, context);
+            assert_eq!(text_field.text(), "héo");
+            assert_eq!(changed_count(context), 0);
+
+            text_field.set_editable(context.gc_context, false);
+            text_field.text_input('x', avm, context);
+            assert_eq!(text_field.text(), "héo");
+            assert_eq!(changed_count(context), 0);
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn backspace_and_delete() {
+        with_avm(8, |avm, context, _this| -> Result<(), Error> {
+            let text_field = focused_text_field(avm, context, "hello world", 5);
+            text_field.key_down(KeyCode::Backspace, avm, context);
+            assert_eq!(text_field.text(), "hell world");
+            assert_eq!(caret(text_field), Some(4));
+            assert_eq!(changed_count(context), 1);
+
+            text_field.key_down(KeyCode::Delete, avm, context);
+            assert_eq!(text_field.text(), "hellworld");
+            assert_eq!(caret(text_field), Some(4));
+            assert_eq!(changed_count(context), 1);
+
+            // A selection is deleted as a whole, by either key.
+            text_field.set_selection(context.gc_context, Some(TextSelection::for_range(6, 2)));
+            text_field.key_down(KeyCode::Backspace, avm, context);
+            assert_eq!(text_field.text(), "herld");
+            assert_eq!(caret(text_field), Some(2));
+            assert_eq!(changed_count(context), 1);
+
+            text_field.set_selection(context.gc_context, Some(TextSelection::for_range(2, 5)));
+            text_field.key_down(KeyCode::Delete, avm, context);
+            assert_eq!(text_field.text(), "he");
+            assert_eq!(changed_count(context), 1);
+
+            // Nothing to delete at the ends of the text.
+            text_field.key_down(KeyCode::Delete, avm, context);
+            text_field.set_selection(context.gc_context, Some(TextSelection::for_position(0)));
+            text_field.key_down(KeyCode::Backspace, avm, context);
+            assert_eq!(text_field.text(), "he");
+            assert_eq!(changed_count(context), 0);
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn max_chars_truncates_input() {
+        with_avm(8, |avm, context, _this| -> Result<(), Error> {
+            let text_field = focused_text_field(avm, context, "abc", 3);
+            text_field.set_max_chars(context.gc_context, 5);
+            for character in "def".chars() {
+                text_field.text_input(character, avm, context);
+            }
+            assert_eq!(text_field.text(), "abcde");
+            assert_eq!(caret(text_field), Some(5));
+            assert_eq!(changed_count(context), 2);
+
+            // Replacing a selection frees up its characters.
+            text_field.set_selection(context.gc_context, Some(TextSelection::for_range(0, 1)));
+            text_field.text_input('x', avm, context);
+            assert_eq!(text_field.text(), "xbcde");
+            assert_eq!(changed_count(context), 1);
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn restrict_filters_input() {
+        with_avm(8, |avm, context, _this| -> Result<(), Error> {
+            let text_field = focused_text_field(avm, context, "", 0);
+            text_field.set_restrict(context.gc_context, Some("0-9".to_string()));
+            for character in "a1b2".chars() {
+                text_field.text_input(character, avm, context);
+            }
+            assert_eq!(text_field.text(), "12");
+            assert_eq!(changed_count(context), 2);
+            Ok(())
+        })
+    }
+}
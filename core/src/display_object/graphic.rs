@@ -1,9 +1,11 @@
 use crate::avm1::Avm1;
 use crate::backend::render::ShapeHandle;
 use crate::context::{RenderContext, UpdateContext};
-use crate::display_object::{DisplayObjectBase, TDisplayObject};
+use crate::display_object::{DisplayObjectBase, HitTestMode, TDisplayObject};
 use crate::prelude::*;
+use crate::shape_utils;
 use gc_arena::{Collect, GcCell};
+use std::cell::Ref;
 
 #[derive(Clone, Debug, Collect, Copy)]
 #[collect(no_drop)]
@@ -21,6 +23,7 @@ impl<'gc> Graphic<'gc> {
             id: swf_shape.id,
             render_handle: context.renderer.register_shape(swf_shape.into()),
             bounds: swf_shape.shape_bounds.clone().into(),
+            shape: swf_shape.clone(),
         };
         Graphic(GcCell::allocate(
             context.gc_context,
@@ -30,6 +33,11 @@ impl<'gc> Graphic<'gc> {
             },
         ))
     }
+
+    /// The shape this graphic was defined with.
+    pub fn shape(&self) -> Ref<swf::Shape> {
+        Ref::map(self.0.read(), |graphic| &graphic.static_data.shape)
+    }
 }
 
 impl<'gc> TDisplayObject<'gc> for Graphic<'gc> {
@@ -58,6 +66,20 @@ impl<'gc> TDisplayObject<'gc> for Graphic<'gc> {
         // Noop
     }
 
+    fn hit_test_shape(
+        &self,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+        point: (Twips, Twips),
+        _mode: HitTestMode,
+    ) -> bool {
+        if !self.world_bounds().contains(point) {
+            return false;
+        }
+        let local_matrix = self.local_to_global_matrix();
+        let point = self.global_to_local(point);
+        shape_utils::shape_hit_test(&self.shape(), point, &local_matrix)
+    }
+
     fn render(&self, context: &mut RenderContext) {
         if !self.world_bounds().intersects(&context.view_bounds) {
             // Off-screen; culled
@@ -88,6 +110,7 @@ struct GraphicStatic {
     id: CharacterId,
     render_handle: ShapeHandle,
     bounds: BoundingBox,
+    shape: swf::Shape,
 }
 
 unsafe impl<'gc> gc_arena::Collect for GraphicStatic {
@@ -1,8 +1,9 @@
 use crate::avm1::Avm1;
 use crate::backend::render::{RenderBackend, ShapeHandle};
 use crate::context::{RenderContext, UpdateContext};
-use crate::display_object::{DisplayObjectBase, TDisplayObject};
+use crate::display_object::{DisplayObjectBase, HitTestMode, TDisplayObject};
 use crate::prelude::*;
+use crate::shape_utils;
 use gc_arena::{Collect, Gc, GcCell, MutationContext};
 use swf::Twips;
 
@@ -70,6 +71,24 @@ impl<'gc> TDisplayObject<'gc> for MorphShape<'gc> {
         context.transform_stack.pop();
     }
 
+    fn hit_test_shape(
+        &self,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+        point: (Twips, Twips),
+        _mode: HitTestMode,
+    ) -> bool {
+        if !self.world_bounds().contains(point) {
+            return false;
+        }
+        let local_matrix = self.local_to_global_matrix();
+        let point = self.global_to_local(point);
+        if let Some(frame) = self.0.read().static_data.frames.get(&self.ratio()) {
+            shape_utils::shape_hit_test(&frame.swf_shape, point, &local_matrix)
+        } else {
+            false
+        }
+    }
+
     fn self_bounds(&self) -> BoundingBox {
         // TODO: Use the bounds of the current ratio.
         if let Some(frame) = self.0.read().static_data.frames.get(&self.ratio()) {
@@ -92,6 +111,9 @@ unsafe impl<'gc> gc_arena::Collect for MorphShapeData<'gc> {
 struct Frame {
     shape: ShapeHandle,
     bounds: BoundingBox,
+
+    /// The interpolated shape, used for hit testing.
+    swf_shape: swf::Shape,
 }
 
 /// Static data shared between all instances of a morph shape.
@@ -300,6 +322,7 @@ impl MorphShapeStatic {
         let frame = Frame {
             shape: renderer.register_shape((&shape).into()),
             bounds: bounds.into(),
+            swf_shape: shape,
         };
         self.frames.insert(ratio, frame);
     }
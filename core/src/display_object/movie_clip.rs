@@ -1,11 +1,14 @@
 //! `MovieClip` display object and support code.
 use crate::avm1::{Avm1, Object, StageObject, TObject, Value};
+use crate::avm2::{Avm2, Object as Avm2Object};
 use crate::backend::audio::AudioStreamHandle;
+use crate::backend::render::glue_tables_to_jpeg;
 
 use crate::character::Character;
 use crate::context::{ActionType, RenderContext, UpdateContext};
 use crate::display_object::{
-    Bitmap, Button, DisplayObjectBase, EditText, Graphic, MorphShapeStatic, TDisplayObject, Text,
+    hit_test_children, unmasked_children, Bitmap, BitmapSource, Button, DisplayObjectBase,
+    EditText, Graphic, HitTestMode, MorphShapeStatic, TDisplayObject, Text, Video,
 };
 use crate::drawing::Drawing;
 use crate::events::{ButtonKeyCode, ClipEvent, ClipEventResult};
@@ -47,6 +50,8 @@ pub struct MovieClipData<'gc> {
     has_button_clip_event: bool,
     flags: EnumSet<MovieClipFlags>,
     avm1_constructor: Option<Object<'gc>>,
+    avm2_object: Option<Avm2Object<'gc>>,
+    frame_scripts: Vec<(FrameNumber, Avm2Object<'gc>)>,
     drawing: Drawing,
 }
 
@@ -67,6 +72,8 @@ impl<'gc> MovieClip<'gc> {
                 has_button_clip_event: false,
                 flags: EnumSet::empty(),
                 avm1_constructor: None,
+                avm2_object: None,
+                frame_scripts: Vec::new(),
                 drawing: Drawing::new(),
             },
         ))
@@ -101,6 +108,8 @@ impl<'gc> MovieClip<'gc> {
                 has_button_clip_event: false,
                 flags: MovieClipFlags::Playing.into(),
                 avm1_constructor: None,
+                avm2_object: None,
+                frame_scripts: Vec::new(),
                 drawing: Drawing::new(),
             },
         ))
@@ -149,10 +158,12 @@ impl<'gc> MovieClip<'gc> {
             TagCode::FileAttributes => {
                 let attributes = reader.read_file_attributes()?;
                 if attributes.is_action_script_3 {
-                    log::warn!("This SWF contains ActionScript 3 which is not yet supported by Ruffle. The movie may not work as intended.");
+                    log::warn!("This SWF contains ActionScript 3, which is only partially supported by Ruffle. The movie may not work as intended.");
                 }
                 Ok(())
             }
+            TagCode::DoAbc => self.preload_do_abc(context, reader, tag_len),
+            TagCode::SymbolClass => self.preload_symbol_class(context, reader),
             TagCode::DefineBits => self
                 .0
                 .write(context.gc_context)
@@ -209,7 +220,10 @@ impl<'gc> MovieClip<'gc> {
                 .0
                 .write(context.gc_context)
                 .define_font_3(context, reader),
-            TagCode::DefineFont4 => unimplemented!(),
+            TagCode::DefineFont4 => self
+                .0
+                .write(context.gc_context)
+                .define_font_4(context, reader, tag_len),
             TagCode::DefineMorphShape => self.0.write(context.gc_context).define_morph_shape(
                 context,
                 reader,
@@ -333,6 +347,14 @@ impl<'gc> MovieClip<'gc> {
                 .0
                 .write(context.gc_context)
                 .preload_sound_stream_block(context, reader, cur_frame, &mut static_data, tag_len),
+            TagCode::DefineVideoStream => self
+                .0
+                .write(context.gc_context)
+                .define_video_stream(context, reader),
+            TagCode::VideoFrame => self
+                .0
+                .write(context.gc_context)
+                .preload_video_frame(context, reader, tag_len),
             _ => Ok(()),
         };
         let _ = tag_utils::decode_tags(&mut reader, tag_callback, TagCode::End);
@@ -385,6 +407,36 @@ impl<'gc> MovieClip<'gc> {
         Ok(())
     }
 
+    #[inline]
+    fn preload_do_abc(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<&[u8]>,
+        tag_len: usize,
+    ) -> DecodeResult {
+        let do_abc = reader.read_do_abc(tag_len)?;
+        if let Err(e) = Avm2::load_abc(context, &do_abc.data, do_abc.is_lazy_initialize) {
+            log::error!("Error loading ABC file {}: {}", do_abc.name, e);
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn preload_symbol_class(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<&[u8]>,
+    ) -> DecodeResult {
+        let links = reader.read_symbol_class()?;
+        let library = context.library.library_for_movie_mut(self.0.read().movie());
+        for link in links {
+            library.register_avm2_class(link.id, &link.class_name);
+        }
+
+        Ok(())
+    }
+
     #[allow(dead_code)]
     pub fn playing(self) -> bool {
         self.0.read().playing()
@@ -457,6 +509,65 @@ impl<'gc> MovieClip<'gc> {
         self.0.write(gc_context).avm1_constructor = prototype;
     }
 
+    /// The AVM2 object for this clip, if its symbol is linked to an AVM2
+    /// class.
+    pub fn avm2_object(self) -> Option<Avm2Object<'gc>> {
+        self.0.read().avm2_object
+    }
+
+    /// Set or remove the AVM2 function that runs when a given frame is
+    /// entered. `frame` is 1-based.
+    pub fn set_frame_script(
+        self,
+        gc_context: MutationContext<'gc, '_>,
+        frame: FrameNumber,
+        callable: Option<Avm2Object<'gc>>,
+    ) {
+        let mut mc = self.0.write(gc_context);
+        mc.frame_scripts
+            .retain(|(script_frame, _)| *script_frame != frame);
+        if let Some(callable) = callable {
+            mc.frame_scripts.push((frame, callable));
+        }
+    }
+
+    /// Construct the AVM2 object for this clip if its symbol is linked to a
+    /// class by a `SymbolClass` tag, and run the class's constructor.
+    pub fn construct_avm2_object(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        display_object: DisplayObject<'gc>,
+    ) {
+        let movie = self.0.read().movie();
+        let class_name = context
+            .library
+            .library_for_movie(movie)
+            .and_then(|library| library.avm2_class_name(self.id()))
+            .map(|class_name| class_name.to_string());
+        let class_name = match class_name {
+            Some(class_name) => class_name,
+            None => return,
+        };
+
+        let result = Avm2::lookup_class(context, &class_name).and_then(|class_object| {
+            let class_object = match class_object {
+                Some(class_object) => class_object,
+                None => {
+                    log::warn!("AVM2 class {} does not exist", class_name);
+                    return Ok(());
+                }
+            };
+
+            let object = Avm2::construct_display_object(context, display_object, class_object)?;
+            self.0.write(context.gc_context).avm2_object = Some(object);
+            Avm2::run_constructor(context, class_object, object)
+        });
+
+        if let Err(e) = result {
+            log::error!("Error constructing AVM2 class {}: {}", class_name, e);
+        }
+    }
+
     pub fn frame_label_to_number(self, frame_label: &str) -> Option<FrameNumber> {
         // Frame labels are case insensitive.
         let label = frame_label.to_ascii_lowercase();
@@ -606,6 +717,26 @@ impl<'gc> MovieClip<'gc> {
         mc.drawing.draw_command(command);
     }
 
+    /// The shapes drawn into this clip with the drawing API.
+    pub fn drawing(&self) -> Ref<Drawing> {
+        Ref::map(self.0.read(), |mc| &mc.drawing)
+    }
+
+    /// Whether this clip acts as a button, as it does when it has any button
+    /// event handlers.
+    pub fn is_button_mode(
+        self,
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> bool {
+        self.0.read().has_button_clip_event || {
+            let object = self.object().coerce_to_object(avm, context);
+            ClipEvent::BUTTON_EVENT_METHODS
+                .iter()
+                .any(|handler| object.has_property(avm, context, handler))
+        }
+    }
+
     pub fn run_clip_event(
         self,
         context: &mut crate::context::UpdateContext<'_, 'gc, '_>,
@@ -662,6 +793,7 @@ impl<'gc> MovieClip<'gc> {
             TagCode::RemoveObject if run_display_actions => self.remove_object(context, reader, 1),
             TagCode::RemoveObject2 if run_display_actions => self.remove_object(context, reader, 2),
             TagCode::SetBackgroundColor => self.set_background_color(context, reader),
+            TagCode::SetTabIndex => self.set_tab_index(context, reader),
             TagCode::StartSound => self.start_sound_1(context, reader),
             TagCode::SoundStreamBlock => {
                 has_stream_block = true;
@@ -673,6 +805,25 @@ impl<'gc> MovieClip<'gc> {
 
         self.0.write(context.gc_context).tag_stream_pos = reader.get_ref().position();
 
+        let frame_script = {
+            let mc = self.0.read();
+            mc.frame_scripts
+                .iter()
+                .find(|(frame, _)| *frame == mc.current_frame)
+                .map(|(_, callable)| (*callable, mc.avm2_object))
+        };
+        if let Some((callable, receiver)) = frame_script {
+            context.action_queue.queue_actions(
+                self_display_object,
+                ActionType::Avm2Callable {
+                    callable,
+                    receiver,
+                    args: Vec::new(),
+                },
+                false,
+            );
+        }
+
         // If we are playing a streaming sound, there should(?) be a `SoundStreamBlock` on each frame.
         if !has_stream_block {
             self.0.write(context.gc_context).stop_audio_stream(context);
@@ -717,7 +868,7 @@ impl<'gc> MovieClip<'gc> {
                     }
                 }
                 // Run first frame.
-                child.apply_place_object(context.gc_context, place_object);
+                child.apply_place_object(context, place_object);
                 child.post_instantiation(avm, context, child, None, false);
                 child.run_frame(avm, context);
             }
@@ -853,7 +1004,7 @@ impl<'gc> MovieClip<'gc> {
                 // If it's a rewind, we removed any dead children above, so we always
                 // modify the previous child.
                 Some(mut prev_child) if params.id() == 0 || is_rewind => {
-                    prev_child.apply_place_object(context.gc_context, &params.place_object);
+                    prev_child.apply_place_object(context, &params.place_object);
                 }
                 _ => {
                     if let Some(mut child) = clip.instantiate_child(
@@ -932,8 +1083,21 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
         } else {
             mc.run_clip_event((*self).into(), context, ClipEvent::EnterFrame);
         }
+        let avm2_object = mc.avm2_object;
         drop(mc);
 
+        // AVM2 `enterFrame` listeners run before this frame's script.
+        if let Some(target) = avm2_object {
+            context.action_queue.queue_actions(
+                (*self).into(),
+                ActionType::Avm2Event {
+                    target,
+                    event_type: "enterFrame",
+                },
+                false,
+            );
+        }
+
         // Run my SWF tags.
         if self.playing() {
             self.run_frame_internal((*self).into(), avm, context, true);
@@ -963,6 +1127,20 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
         self.world_bounds().contains(point)
     }
 
+    fn hit_test_shape(
+        &self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        point: (Twips, Twips),
+        mode: HitTestMode,
+    ) -> bool {
+        if hit_test_children(context, &self.0.read().children, point, mode) {
+            return true;
+        }
+        let local_matrix = self.local_to_global_matrix();
+        let point = self.global_to_local(point);
+        self.0.read().drawing.hit_test(point, &local_matrix)
+    }
+
     fn mouse_pick(
         &self,
         avm: &mut Avm1<'gc>,
@@ -971,23 +1149,17 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
         point: (Twips, Twips),
     ) -> Option<DisplayObject<'gc>> {
         if self.visible() {
-            if self.world_bounds().contains(point) {
-                if self.0.read().has_button_clip_event {
-                    return Some(self_node);
-                }
-
-                let object = self.object().coerce_to_object(avm, context);
-                if ClipEvent::BUTTON_EVENT_METHODS
-                    .iter()
-                    .any(|handler| object.has_property(avm, context, handler))
-                {
-                    return Some(self_node);
-                }
+            if self.world_bounds().contains(point)
+                && self.is_button_mode(avm, context)
+                && self.hit_test_shape(context, point, HitTestMode::MousePick)
+            {
+                return Some(self_node);
             }
 
             // Maybe we could skip recursing down at all if !world_bounds.contains(point),
             // but a child button can have an invisible hit area outside the parent's bounds.
-            for child in self.0.read().children.values().rev() {
+            let children = unmasked_children(context, &self.0.read().children, point);
+            for child in children.iter().rev() {
                 let result = child.mouse_pick(avm, context, *child, point);
                 if result.is_some() {
                     return result;
@@ -1029,6 +1201,10 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
     ) {
         self.set_default_instance_name(context);
 
+        if self.0.read().avm2_object.is_none() {
+            self.construct_avm2_object(context, display_object);
+        }
+
         if self.0.read().object.is_none() {
             // If we are running within the AVM, this must be an immediate action.
             // If we are not, then this must be queued to be ran first-thing
@@ -1127,6 +1303,28 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
     fn allow_as_mask(&self) -> bool {
         !self.0.read().children.is_empty()
     }
+
+    /// Clips may be focused if they act as buttons, or if `focusEnabled` is
+    /// set.
+    fn is_focusable(&self, avm: &mut Avm1<'gc>, context: &mut UpdateContext<'_, 'gc, '_>) -> bool {
+        let object = self.object().coerce_to_object(avm, context);
+        let focus_enabled = object
+            .get("focusEnabled", avm, context)
+            .map(|value| value.as_bool(avm.current_swf_version()))
+            .unwrap_or(false);
+        focus_enabled || self.is_button_mode(avm, context)
+    }
+
+    fn is_tab_enabled(
+        &self,
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> bool {
+        match self.tab_enabled() {
+            Some(tab_enabled) => tab_enabled,
+            None => self.is_button_mode(avm, context),
+        }
+    }
 }
 
 unsafe impl<'gc> Collect for MovieClipData<'gc> {
@@ -1139,6 +1337,8 @@ unsafe impl<'gc> Collect for MovieClipData<'gc> {
         self.static_data.trace(cc);
         self.object.trace(cc);
         self.avm1_constructor.trace(cc);
+        self.avm2_object.trace(cc);
+        self.frame_scripts.trace(cc);
     }
 }
 
@@ -1448,17 +1648,19 @@ impl<'gc, 'a> MovieClipData<'gc> {
         let bitmap_info = context
             .renderer
             .register_bitmap_png(&define_bits_lossless)?;
+        let id = define_bits_lossless.id;
         let bitmap = crate::display_object::Bitmap::new(
             context,
-            define_bits_lossless.id,
+            id,
             bitmap_info.handle,
             bitmap_info.width,
             bitmap_info.height,
+            BitmapSource::Lossless(define_bits_lossless),
         );
         context
             .library
             .library_for_movie_mut(self.movie())
-            .register_character(define_bits_lossless.id, Character::Bitmap(bitmap));
+            .register_character(id, Character::Bitmap(bitmap));
         Ok(())
     }
 
@@ -1564,6 +1766,60 @@ impl<'gc, 'a> MovieClipData<'gc> {
         Ok(())
     }
 
+    #[inline]
+    fn define_video_stream(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<&'a [u8]>,
+    ) -> DecodeResult {
+        let streamdef = reader.read_define_video_stream()?;
+        let id = streamdef.id;
+        let stream = context.video.register_video_stream(
+            streamdef.num_frames.into(),
+            (streamdef.width, streamdef.height),
+            streamdef.codec,
+            streamdef.deblocking,
+        )?;
+        let video = Video::from_swf_tag(context.gc_context, streamdef, stream);
+        context
+            .library
+            .library_for_movie_mut(self.movie())
+            .register_character(id, Character::Video(video));
+        Ok(())
+    }
+
+    #[inline]
+    fn preload_video_frame(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<&'a [u8]>,
+        tag_len: usize,
+    ) -> DecodeResult {
+        let stream_id = reader.read_u16()?;
+        let frame_num = reader.read_u16()?;
+        let data = self
+            .static_data
+            .swf
+            .resize_to_reader(reader, tag_len.saturating_sub(4))
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    "Invalid source or tag length when preloading video frame",
+                )
+            })?;
+        match context
+            .library
+            .library_for_movie_mut(self.movie())
+            .get_character_by_id(stream_id)
+        {
+            Some(&Character::Video(video)) => {
+                video.preload_swf_frame(context, frame_num.into(), data)
+            }
+            _ => log::warn!("VideoFrame: Character {} is not a video stream", stream_id),
+        }
+        Ok(())
+    }
+
     #[inline]
     fn preload_sound_stream_head(
         &mut self,
@@ -1596,20 +1852,24 @@ impl<'gc, 'a> MovieClipData<'gc> {
             .get_mut()
             .take(data_len as u64)
             .read_to_end(&mut jpeg_data)?;
-        let bitmap_info = context.renderer.register_bitmap_jpeg(
-            id,
-            &jpeg_data,
-            context
-                .library
-                .library_for_movie_mut(self.movie())
-                .jpeg_tables(),
-        )?;
+        let jpeg_tables = context
+            .library
+            .library_for_movie_mut(self.movie())
+            .jpeg_tables();
+        let bitmap_info = context
+            .renderer
+            .register_bitmap_jpeg(id, &jpeg_data, jpeg_tables)?;
+        let source = BitmapSource::Jpeg {
+            data: glue_tables_to_jpeg(&jpeg_data, jpeg_tables).into_owned(),
+            alpha_data: None,
+        };
         let bitmap = crate::display_object::Bitmap::new(
             context,
             id,
             bitmap_info.handle,
             bitmap_info.width,
             bitmap_info.height,
+            source,
         );
         context
             .library
@@ -1634,12 +1894,17 @@ impl<'gc, 'a> MovieClipData<'gc> {
             .take(data_len as u64)
             .read_to_end(&mut jpeg_data)?;
         let bitmap_info = context.renderer.register_bitmap_jpeg_2(id, &jpeg_data)?;
+        let source = BitmapSource::Jpeg {
+            data: jpeg_data,
+            alpha_data: None,
+        };
         let bitmap = crate::display_object::Bitmap::new(
             context,
             id,
             bitmap_info.handle,
             bitmap_info.width,
             bitmap_info.height,
+            source,
         );
         context
             .library
@@ -1672,12 +1937,17 @@ impl<'gc, 'a> MovieClipData<'gc> {
         let bitmap_info = context
             .renderer
             .register_bitmap_jpeg_3(id, &jpeg_data, &alpha_data)?;
+        let source = BitmapSource::Jpeg {
+            data: jpeg_data,
+            alpha_data: Some(alpha_data),
+        };
         let bitmap = Bitmap::new(
             context,
             id,
             bitmap_info.handle,
             bitmap_info.width,
             bitmap_info.height,
+            source,
         );
         context
             .library
@@ -1711,12 +1981,17 @@ impl<'gc, 'a> MovieClipData<'gc> {
         let bitmap_info = context
             .renderer
             .register_bitmap_jpeg_3(id, &jpeg_data, &alpha_data)?;
+        let source = BitmapSource::Jpeg {
+            data: jpeg_data,
+            alpha_data: Some(alpha_data),
+        };
         let bitmap = Bitmap::new(
             context,
             id,
             bitmap_info.handle,
             bitmap_info.width,
             bitmap_info.height,
+            source,
         );
         context
             .library
@@ -1910,6 +2185,34 @@ impl<'gc, 'a> MovieClipData<'gc> {
         Ok(())
     }
 
+    #[inline]
+    fn define_font_4(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<&'a [u8]>,
+        tag_len: usize,
+    ) -> DecodeResult {
+        // The font data runs to the end of the tag, so limit the reader to this tag.
+        use std::io::Read;
+        let mut reader = swf::read::Reader::new(
+            reader.get_mut().take(tag_len as u64),
+            self.static_data.swf.version(),
+        );
+        let font = reader.read_define_font_4()?;
+        match Font::from_font4_tag(context.gc_context, context.renderer, &font) {
+            Ok(font_object) => context
+                .library
+                .library_for_movie_mut(self.movie())
+                .register_character(font.id, Character::Font(font_object)),
+            Err(e) => log::error!(
+                "MovieClip::define_font_4: Unable to read font ID {}: {}",
+                font.id,
+                e
+            ),
+        }
+        Ok(())
+    }
+
     #[inline]
     fn define_sound(
         &mut self,
@@ -2151,7 +2454,7 @@ impl<'gc, 'a> MovieClip<'gc> {
                     .get(&place_object.depth.into())
                     .copied()
                 {
-                    child.apply_place_object(context.gc_context, &place_object);
+                    child.apply_place_object(context, &place_object);
                     child
                 } else {
                     return Ok(());
@@ -2192,14 +2495,31 @@ impl<'gc, 'a> MovieClip<'gc> {
         Ok(())
     }
 
+    #[inline]
+    fn set_tab_index(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<&'a [u8]>,
+    ) -> DecodeResult {
+        let depth: Depth = reader.read_u16()?.into();
+        let tab_index = reader.read_u16()?;
+        let child = self.0.read().children.get(&depth).copied();
+        if let Some(mut child) = child {
+            child.set_tab_index(context.gc_context, Some(tab_index.into()));
+        }
+        Ok(())
+    }
+
     #[inline]
     fn sound_stream_block(
         self,
         context: &mut UpdateContext<'_, 'gc, '_>,
         _reader: &mut SwfStream<&'a [u8]>,
     ) -> DecodeResult {
-        let mut mc = self.0.write(context.gc_context);
+        let mc = self.0.read();
         if let (Some(stream_info), None) = (&mc.static_data.audio_stream_info, mc.audio_stream) {
+            let stream_info = stream_info.clone();
+            let clip_frame = mc.current_frame() + 1;
             let slice = mc
                 .static_data
                 .swf
@@ -2210,11 +2530,15 @@ impl<'gc, 'a> MovieClip<'gc> {
                         "Invalid slice generated when constructing sound stream block",
                     )
                 })?;
-            let audio_stream =
-                context
-                    .audio
-                    .start_stream(mc.id(), mc.current_frame() + 1, slice, &stream_info);
-            mc.audio_stream = audio_stream.ok();
+            drop(mc);
+            let audio_stream = context.audio_manager.start_stream(
+                context.audio,
+                clip_frame,
+                slice,
+                &stream_info,
+                self.into(),
+            );
+            self.0.write(context.gc_context).audio_stream = audio_stream.ok();
         }
 
         Ok(())
@@ -2232,24 +2556,12 @@ impl<'gc, 'a> MovieClip<'gc> {
             .library_for_movie_mut(self.movie().unwrap()) // TODO
             .get_sound(start_sound.id)
         {
-            use swf::SoundEvent;
-            // The sound event type is controlled by the "Sync" setting in the Flash IDE.
-            match start_sound.sound_info.event {
-                // "Event" sounds always play, independent of the timeline.
-                SoundEvent::Event => {
-                    let _ = context.audio.start_sound(handle, &start_sound.sound_info);
-                }
-
-                // "Start" sounds only play if an instance of the same sound is not already playing.
-                SoundEvent::Start => {
-                    if !context.audio.is_sound_playing_with_handle(handle) {
-                        let _ = context.audio.start_sound(handle, &start_sound.sound_info);
-                    }
-                }
-
-                // "Stop" stops any active instances of a given sound.
-                SoundEvent::Stop => context.audio.stop_sounds_with_handle(handle),
-            }
+            context.audio_manager.perform_sound_event(
+                context.audio,
+                handle,
+                &start_sound.sound_info,
+                Some(self.into()),
+            );
         }
         Ok(())
     }
@@ -2324,6 +2636,12 @@ impl GotoPlaceObject {
                 if place_object.class_name.is_none() {
                     place_object.class_name = Some(Default::default());
                 }
+                if place_object.filters.is_none() {
+                    place_object.filters = Some(Default::default());
+                }
+                if place_object.blend_mode.is_none() {
+                    place_object.blend_mode = Some(swf::BlendMode::Normal);
+                }
             }
         }
 
@@ -2390,6 +2708,12 @@ impl GotoPlaceObject {
         if next_place.background_color.is_some() {
             cur_place.background_color = next_place.background_color.take();
         }
+        if next_place.filters.is_some() {
+            cur_place.filters = next_place.filters.take();
+        }
+        if next_place.blend_mode.is_some() {
+            cur_place.blend_mode = next_place.blend_mode.take();
+        }
         // TODO: Other stuff.
     }
 }
@@ -1,7 +1,8 @@
 use crate::avm1::Avm1;
 use crate::context::{RenderContext, UpdateContext};
-use crate::display_object::{DisplayObjectBase, TDisplayObject};
+use crate::display_object::{DisplayObjectBase, HitTestMode, TDisplayObject};
 use crate::prelude::*;
+use crate::shape_utils;
 use crate::tag_utils::SwfMovie;
 use crate::transform::Transform;
 use gc_arena::{Collect, GcCell};
@@ -116,6 +117,58 @@ impl<'gc> TDisplayObject<'gc> for Text<'gc> {
     fn self_bounds(&self) -> BoundingBox {
         self.0.read().static_data.bounds.clone()
     }
+
+    fn hit_test_shape(
+        &self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        point: (Twips, Twips),
+        _mode: HitTestMode,
+    ) -> bool {
+        if !self.world_bounds().contains(point) {
+            return false;
+        }
+
+        // Test each glyph, placed the same way as when rendering.
+        let tf = self.0.read();
+        let text_matrix = self.local_to_global_matrix() * tf.static_data.text_transform;
+        let mut font_id = 0;
+        let mut height = Twips::new(0);
+        let mut glyph_matrix = Matrix::identity();
+        for block in &tf.static_data.text_blocks {
+            if let Some(x) = block.x_offset {
+                glyph_matrix.tx = x;
+            }
+            if let Some(y) = block.y_offset {
+                glyph_matrix.ty = y;
+            }
+            font_id = block.font_id.unwrap_or(font_id);
+            height = block.height.unwrap_or(height);
+            if let Some(font) = context
+                .library
+                .library_for_movie(self.movie().unwrap())
+                .unwrap()
+                .get_font(font_id)
+            {
+                let scale = (height.get() as f32) / font.scale();
+                glyph_matrix.a = scale;
+                glyph_matrix.d = scale;
+                for c in &block.glyphs {
+                    if let Some(glyph) = font.get_glyph(c.index as usize) {
+                        let local_matrix = text_matrix * glyph_matrix;
+                        let mut global_to_glyph = local_matrix;
+                        global_to_glyph.invert();
+                        let glyph_point = global_to_glyph * point;
+                        if shape_utils::shape_hit_test(&glyph.swf_shape, glyph_point, &local_matrix)
+                        {
+                            return true;
+                        }
+                        glyph_matrix.tx += Twips::new(c.advance);
+                    }
+                }
+            }
+        }
+        false
+    }
 }
 
 unsafe impl<'gc> gc_arena::Collect for TextData<'gc> {
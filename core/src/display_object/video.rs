@@ -0,0 +1,228 @@
+//! Video display object
+
+use crate::avm1::{Avm1, Object};
+use crate::backend::render::BitmapInfo;
+use crate::backend::video::{EncodedFrame, VideoStreamHandle};
+use crate::context::{RenderContext, UpdateContext};
+use crate::display_object::{DisplayObjectBase, TDisplayObject};
+use crate::prelude::*;
+use crate::tag_utils::SwfSlice;
+use crate::transform::Transform;
+use gc_arena::{Collect, GcCell, MutationContext};
+use std::collections::{BTreeMap, BTreeSet};
+use swf::DefineVideoStream;
+
+/// A Video display object plays a video stream embedded in the SWF.
+///
+/// The frames of the stream are stored in `VideoFrame` tags on the timeline. Which frame is
+/// shown is chosen by the ratio of the `PlaceObject` tags that place and move the video.
+#[derive(Clone, Debug, Collect, Copy)]
+#[collect(no_drop)]
+pub struct Video<'gc>(GcCell<'gc, VideoData<'gc>>);
+
+#[derive(Clone, Debug)]
+pub struct VideoData<'gc> {
+    base: DisplayObjectBase<'gc>,
+
+    /// The stream, which is shared between every instance of this video.
+    source: GcCell<'gc, VideoSource>,
+}
+
+#[derive(Debug, Collect)]
+#[collect(require_static)]
+struct VideoSource {
+    streamdef: DefineVideoStream,
+
+    /// The stream registered with the video backend.
+    stream: VideoStreamHandle,
+
+    /// The encoded data of each frame, by frame number.
+    frames: BTreeMap<u32, SwfSlice>,
+
+    /// The frames that can be decoded without the frames before them.
+    keyframes: BTreeSet<u32>,
+
+    /// The last frame the backend decoded, and the bitmap holding it.
+    decoded_frame: Option<(u32, BitmapInfo)>,
+}
+
+impl<'gc> Video<'gc> {
+    pub fn from_swf_tag(
+        gc_context: MutationContext<'gc, '_>,
+        streamdef: DefineVideoStream,
+        stream: VideoStreamHandle,
+    ) -> Self {
+        let source = GcCell::allocate(
+            gc_context,
+            VideoSource {
+                streamdef,
+                stream,
+                frames: BTreeMap::new(),
+                keyframes: BTreeSet::new(),
+                decoded_frame: None,
+            },
+        );
+        Video(GcCell::allocate(
+            gc_context,
+            VideoData {
+                base: Default::default(),
+                source,
+            },
+        ))
+    }
+
+    /// Records the encoded data of a frame of this video's stream.
+    pub fn preload_swf_frame(
+        self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        frame_id: u32,
+        data: SwfSlice,
+    ) {
+        let source = self.0.read().source;
+        let mut source = source.write(context.gc_context);
+        let encoded_frame = EncodedFrame {
+            codec: source.streamdef.codec,
+            data: data.data(),
+            frame_id,
+        };
+        match context
+            .video
+            .preload_video_stream_frame(source.stream, encoded_frame)
+        {
+            Ok(dependency) => {
+                if dependency.is_keyframe() {
+                    source.keyframes.insert(frame_id);
+                }
+                source.frames.insert(frame_id, data);
+            }
+            Err(e) => log::error!("Unable to preload video frame {}: {}", frame_id, e),
+        }
+    }
+
+    /// Shows the given frame of the stream.
+    ///
+    /// Frames are decoded forward from the last decoded frame when possible, and otherwise
+    /// from the closest keyframe before the target frame.
+    pub fn seek(self, context: &mut UpdateContext<'_, 'gc, '_>, frame_id: u32) {
+        let source = self.0.read().source;
+        let mut source = source.write(context.gc_context);
+
+        // Ratios past the last frame keep showing the last frame.
+        let frame_id = match source.frames.range(..=frame_id).next_back() {
+            Some((&frame_id, _)) => frame_id,
+            None => return,
+        };
+        let keyframe = source.keyframes.range(..=frame_id).next_back().copied();
+        let start = match source.decoded_frame {
+            Some((decoded, _)) if decoded == frame_id => return,
+            Some((decoded, _))
+                if decoded < frame_id && !matches!(keyframe, Some(k) if decoded < k) =>
+            {
+                decoded + 1
+            }
+            _ => keyframe.unwrap_or(0),
+        };
+
+        let codec = source.streamdef.codec;
+        let stream = source.stream;
+        let frames: Vec<(u32, SwfSlice)> = source
+            .frames
+            .range(start..=frame_id)
+            .map(|(&id, data)| (id, data.clone()))
+            .collect();
+        for (id, data) in frames {
+            let encoded_frame = EncodedFrame {
+                codec,
+                data: data.data(),
+                frame_id: id,
+            };
+            match context
+                .video
+                .decode_video_stream_frame(stream, encoded_frame, context.renderer)
+            {
+                Ok(bitmap) => source.decoded_frame = Some((id, bitmap)),
+                Err(e) => {
+                    log::error!("Unable to decode video frame {}: {}", id, e);
+                    return;
+                }
+            }
+        }
+    }
+}
+
+impl<'gc> TDisplayObject<'gc> for Video<'gc> {
+    impl_display_object!(base);
+
+    fn id(&self) -> CharacterId {
+        self.0.read().source.read().streamdef.id
+    }
+
+    fn as_video(&self) -> Option<Video<'gc>> {
+        Some(*self)
+    }
+
+    fn self_bounds(&self) -> BoundingBox {
+        let source = self.0.read().source;
+        let streamdef = &source.read().streamdef;
+        BoundingBox {
+            x_min: Twips::new(0),
+            y_min: Twips::new(0),
+            x_max: Twips::from_pixels(streamdef.width.into()),
+            y_max: Twips::from_pixels(streamdef.height.into()),
+            valid: true,
+        }
+    }
+
+    fn post_instantiation(
+        &mut self,
+        _avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        _display_object: DisplayObject<'gc>,
+        _init_object: Option<Object<'gc>>,
+        _instantiated_from_avm: bool,
+    ) {
+        let source = self.0.read().source;
+        if source.read().decoded_frame.is_none() {
+            self.seek(context, 0);
+        }
+    }
+
+    fn render(&self, context: &mut RenderContext) {
+        if !self.world_bounds().intersects(&context.view_bounds) {
+            // Off-screen; culled
+            return;
+        }
+
+        let source = self.0.read().source;
+        let source = source.read();
+        let bitmap = if let Some((_, bitmap)) = source.decoded_frame {
+            bitmap
+        } else {
+            return;
+        };
+
+        context.transform_stack.push(&*self.transform());
+        // Stretch the decoded frame to the size declared by the stream.
+        context.transform_stack.push(&Transform {
+            matrix: Matrix::scale(
+                f32::from(source.streamdef.width) / f32::from(bitmap.width.max(1)),
+                f32::from(source.streamdef.height) / f32::from(bitmap.height.max(1)),
+            ),
+            color_transform: Default::default(),
+        });
+
+        context
+            .renderer
+            .render_bitmap(bitmap.handle, context.transform_stack.transform());
+
+        context.transform_stack.pop();
+        context.transform_stack.pop();
+    }
+}
+
+unsafe impl<'gc> gc_arena::Collect for VideoData<'gc> {
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        self.base.trace(cc);
+        self.source.trace(cc);
+    }
+}
@@ -1,10 +1,10 @@
 use crate::backend::render::ShapeHandle;
 use crate::bounding_box::BoundingBox;
 use crate::context::RenderContext;
-use crate::shape_utils::{DistilledShape, DrawCommand, DrawPath};
+use crate::shape_utils::{self, DistilledShape, DrawCommand, DrawPath, FillRule};
 use gc_arena::Collect;
 use std::cell::Cell;
-use swf::{FillStyle, LineStyle, Twips};
+use swf::{FillStyle, LineStyle, Matrix, Twips};
 
 #[derive(Clone, Debug, Collect)]
 #[collect(require_static)]
@@ -139,42 +139,8 @@ impl Drawing {
     pub fn render(&self, context: &mut RenderContext) {
         if self.dirty.get() {
             self.dirty.set(false);
-            let mut paths = Vec::new();
-
-            for (style, commands) in &self.fills {
-                paths.push(DrawPath::Fill {
-                    style,
-                    commands: commands.to_owned(),
-                })
-            }
-
-            // TODO: If the current_fill is not closed, we should automatically close current_line
-
-            if let Some((style, commands)) = &self.current_fill {
-                paths.push(DrawPath::Fill {
-                    style,
-                    commands: commands.to_owned(),
-                })
-            }
-
-            for (style, commands) in &self.lines {
-                paths.push(DrawPath::Stroke {
-                    style,
-                    commands: commands.to_owned(),
-                    is_closed: false, // TODO: Determine this
-                })
-            }
-
-            if let Some((style, commands)) = &self.current_line {
-                paths.push(DrawPath::Stroke {
-                    style,
-                    commands: commands.to_owned(),
-                    is_closed: false, // TODO: Determine this
-                })
-            }
-
             let shape = DistilledShape {
-                paths,
+                paths: self.paths(),
                 shape_bounds: self.shape_bounds.clone(),
                 edge_bounds: self.edge_bounds.clone(),
                 id: 0,
@@ -195,9 +161,55 @@ impl Drawing {
         }
     }
 
+    /// Returns the fills and lines that have been drawn, in the order they are rendered.
+    pub fn paths(&self) -> Vec<DrawPath> {
+        let mut paths = Vec::new();
+
+        for (style, commands) in &self.fills {
+            paths.push(DrawPath::Fill {
+                style,
+                commands: commands.to_owned(),
+            })
+        }
+
+        // TODO: If the current_fill is not closed, we should automatically close current_line
+
+        if let Some((style, commands)) = &self.current_fill {
+            paths.push(DrawPath::Fill {
+                style,
+                commands: commands.to_owned(),
+            })
+        }
+
+        for (style, commands) in &self.lines {
+            paths.push(DrawPath::Stroke {
+                style,
+                commands: commands.to_owned(),
+                is_closed: false, // TODO: Determine this
+            })
+        }
+
+        if let Some((style, commands)) = &self.current_line {
+            paths.push(DrawPath::Stroke {
+                style,
+                commands: commands.to_owned(),
+                is_closed: false, // TODO: Determine this
+            })
+        }
+
+        paths
+    }
+
     pub fn self_bounds(&self) -> BoundingBox {
         self.shape_bounds.clone()
     }
+
+    /// Tests if a point, in the coordinates of the drawing, is inside one of its fills or on
+    /// one of its lines.
+    pub fn hit_test(&self, point: (Twips, Twips), local_matrix: &Matrix) -> bool {
+        self.shape_bounds.contains(point)
+            && shape_utils::paths_hit_test(&self.paths(), point, FillRule::EvenOdd, local_matrix)
+    }
 }
 
 fn stretch_bounding_box(
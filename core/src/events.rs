@@ -9,9 +9,36 @@ pub enum PlayerEvent {
     MouseUp { x: f64, y: f64 },
     MouseDown { x: f64, y: f64 },
     MouseLeft,
+    MouseWheel { delta: MouseWheelDelta },
     TextInput { codepoint: char },
 }
 
+/// The distance scrolled by the mouse wheel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MouseWheelDelta {
+    /// The number of lines scrolled, positive when scrolling up.
+    Lines(f64),
+
+    /// The number of pixels scrolled, positive when scrolling up.
+    Pixels(f64),
+}
+
+impl MouseWheelDelta {
+    /// The number of pixels scrolled per line.
+    ///
+    /// A notch of the wheel is usually reported as 100 pixels, which Flash
+    /// treats as three lines.
+    const PIXELS_PER_LINE: f64 = 100.0 / 3.0;
+
+    /// Returns the number of lines scrolled, as reported to ActionScript.
+    pub fn lines(self) -> f64 {
+        match self {
+            MouseWheelDelta::Lines(lines) => lines,
+            MouseWheelDelta::Pixels(pixels) => pixels / Self::PIXELS_PER_LINE,
+        }
+    }
+}
+
 /// Whether this button event was handled by some child.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum ClipEventResult {
@@ -102,6 +129,7 @@ impl ClipEvent {
 pub enum KeyCode {
     Unknown = 0,
     Backspace = 8,
+    Tab = 9,
     Return = 13,
     Shift = 16,
     Control = 17,
@@ -182,6 +210,7 @@ pub enum KeyCode {
     Insert = 45,
     Delete = 46,
     Pause = 19,
+    NumLock = 144,
     ScrollLock = 145,
     F1 = 112,
     F2 = 113,
@@ -331,6 +360,7 @@ pub fn key_code_to_button_key_code(key_code: KeyCode) -> Option<ButtonKeyCode> {
         KeyCode::Down => ButtonKeyCode::Down,
         KeyCode::PgUp => ButtonKeyCode::PgUp,
         KeyCode::PgDown => ButtonKeyCode::PgDown,
+        KeyCode::Tab => ButtonKeyCode::Tab,
         KeyCode::Escape => ButtonKeyCode::Escape,
         _ => return None,
     };
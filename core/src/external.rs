@@ -0,0 +1,290 @@
+//! Communication between movies and the application hosting the player, through
+//! `ExternalInterface` and `fscommand`.
+
+use crate::avm1::error::Error;
+use crate::avm1::{Avm1, Object as Avm1Object, ScriptObject, TObject, Value as Avm1Value};
+use crate::context::UpdateContext;
+use enumset::EnumSet;
+use gc_arena::{Collect, CollectionContext};
+use std::collections::BTreeMap;
+
+/// A value passed between a movie and the host.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Object(BTreeMap<String, Value>),
+    List(Vec<Value>),
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Number(value)
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_string())
+    }
+}
+
+impl From<Vec<Value>> for Value {
+    fn from(value: Vec<Value>) -> Self {
+        Value::List(value)
+    }
+}
+
+impl From<BTreeMap<String, Value>> for Value {
+    fn from(value: BTreeMap<String, Value>) -> Self {
+        Value::Object(value)
+    }
+}
+
+impl Value {
+    /// Converts an AVM1 value to pass it to the host.
+    ///
+    /// `undefined`, functions, display objects and objects that contain themselves become
+    /// `Null`. Arrays become lists, and other objects keep their enumerable properties.
+    pub fn from_avm1<'gc>(
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        value: Avm1Value<'gc>,
+    ) -> Result<Value, Error<'gc>> {
+        Self::from_avm1_object(avm, context, value, &mut Vec::new())
+    }
+
+    fn from_avm1_object<'gc>(
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        value: Avm1Value<'gc>,
+        parents: &mut Vec<Avm1Object<'gc>>,
+    ) -> Result<Value, Error<'gc>> {
+        let object = match value {
+            Avm1Value::Undefined | Avm1Value::Null => return Ok(Value::Null),
+            Avm1Value::Bool(value) => return Ok(Value::Bool(value)),
+            Avm1Value::Number(value) => return Ok(Value::Number(value)),
+            Avm1Value::String(value) => return Ok(Value::String(value)),
+            Avm1Value::Object(object) => object,
+        };
+
+        if object.as_executable().is_some()
+            || object.as_display_object().is_some()
+            || parents.iter().any(|o| Avm1Object::ptr_eq(*o, object))
+        {
+            return Ok(Value::Null);
+        }
+
+        parents.push(object);
+        let value = if object.is_instance_of(avm, context, object, avm.prototypes().array)? {
+            let mut values = Vec::new();
+            for element in object.array() {
+                values.push(Self::from_avm1_object(avm, context, element, parents)?);
+            }
+            Value::List(values)
+        } else {
+            let mut values = BTreeMap::new();
+            for key in object.get_keys(avm) {
+                let value = object.get(&key, avm, context)?;
+                values.insert(key, Self::from_avm1_object(avm, context, value, parents)?);
+            }
+            Value::Object(values)
+        };
+        parents.pop();
+
+        Ok(value)
+    }
+
+    /// Converts a value from the host to pass it to a movie.
+    pub fn into_avm1<'gc>(
+        self,
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) -> Avm1Value<'gc> {
+        match self {
+            Value::Null => Avm1Value::Null,
+            Value::Bool(value) => Avm1Value::Bool(value),
+            Value::Number(value) => Avm1Value::Number(value),
+            Value::String(value) => Avm1Value::String(value),
+            Value::Object(values) => {
+                let object =
+                    ScriptObject::object(context.gc_context, Some(avm.prototypes().object));
+                for (key, value) in values {
+                    let value = value.into_avm1(avm, context);
+                    object.define_value(context.gc_context, &key, value, EnumSet::empty());
+                }
+                object.into()
+            }
+            Value::List(values) => {
+                let array = ScriptObject::array(context.gc_context, Some(avm.prototypes().array));
+                array.set_length(context.gc_context, values.len());
+                for (i, value) in values.into_iter().enumerate() {
+                    let value = value.into_avm1(avm, context);
+                    array.set_array_element(i, value, context.gc_context);
+                }
+                array.into()
+            }
+        }
+    }
+}
+
+/// An ActionScript function that a movie lets the host call, through
+/// `ExternalInterface.addCallback`.
+#[derive(Clone, Collect)]
+#[collect(no_drop)]
+pub struct Callback<'gc> {
+    /// The object the function is called on. The global object is used if this isn't one.
+    this: Avm1Value<'gc>,
+
+    method: Avm1Object<'gc>,
+}
+
+impl<'gc> Callback<'gc> {
+    pub fn new(this: Avm1Value<'gc>, method: Avm1Object<'gc>) -> Self {
+        Self { this, method }
+    }
+
+    /// Calls the function, returning its result.
+    ///
+    /// Returns `Null` if the function throws, or there is no movie to run it in.
+    pub fn call(
+        &self,
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        args: Vec<Value>,
+    ) -> Value {
+        let this = match self.this {
+            Avm1Value::Object(this) => this,
+            _ => avm.global_object_cell(),
+        };
+        let method = self.method;
+        let result = avm.run_in_root_frame(context, |avm, context| {
+            let args: Vec<Avm1Value<'gc>> = args
+                .into_iter()
+                .map(|arg| arg.into_avm1(avm, context))
+                .collect();
+            let result = method.call(avm, context, this, None, &args)?;
+            Value::from_avm1(avm, context, result)
+        });
+        match result {
+            Some(Ok(value)) => value,
+            Some(Err(e)) => {
+                log::warn!("Error in ExternalInterface callback: {}", e);
+                Value::Null
+            }
+            None => Value::Null,
+        }
+    }
+}
+
+/// A host application that movies can communicate with.
+pub trait ExternalInterfaceProvider {
+    /// Returns the host method that `ExternalInterface.call` calls for the given name, if
+    /// there is one.
+    fn get_method(&self, name: &str) -> Option<Box<dyn ExternalInterfaceMethod>>;
+
+    /// Called when a movie registers a callback with `ExternalInterface.addCallback`, so that
+    /// the host can expose it. Callbacks are called with `Player::call_internal_interface`.
+    fn on_callback_available(&self, name: &str);
+
+    /// Called when a movie runs an `fscommand`.
+    ///
+    /// Returns whether the host handled the command.
+    fn on_fs_command(&self, command: &str, args: &str) -> bool;
+}
+
+/// A host method that movies can call with `ExternalInterface.call`.
+pub trait ExternalInterfaceMethod {
+    fn call(&self, args: &[Value]) -> Value;
+}
+
+impl<F> ExternalInterfaceMethod for F
+where
+    F: Fn(&[Value]) -> Value,
+{
+    fn call(&self, args: &[Value]) -> Value {
+        self(args)
+    }
+}
+
+/// The callbacks registered by movies, and the hosts they are exposed to.
+pub struct ExternalInterface<'gc> {
+    callbacks: BTreeMap<String, Callback<'gc>>,
+    providers: Vec<Box<dyn ExternalInterfaceProvider>>,
+}
+
+unsafe impl<'gc> Collect for ExternalInterface<'gc> {
+    fn trace(&self, cc: CollectionContext) {
+        for callback in self.callbacks.values() {
+            callback.trace(cc);
+        }
+    }
+}
+
+impl<'gc> ExternalInterface<'gc> {
+    pub fn new() -> Self {
+        Self {
+            callbacks: BTreeMap::new(),
+            providers: Vec::new(),
+        }
+    }
+
+    /// Adds a host, exposing every callback registered so far to it.
+    pub fn add_provider(&mut self, provider: Box<dyn ExternalInterfaceProvider>) {
+        for name in self.callbacks.keys() {
+            provider.on_callback_available(name);
+        }
+        self.providers.push(provider);
+    }
+
+    /// Registers a callback, replacing any other with the same name.
+    pub fn add_callback(&mut self, name: String, callback: Callback<'gc>) {
+        for provider in &self.providers {
+            provider.on_callback_available(&name);
+        }
+        self.callbacks.insert(name, callback);
+    }
+
+    pub fn get_callback(&self, name: &str) -> Option<Callback<'gc>> {
+        self.callbacks.get(name).cloned()
+    }
+
+    /// Returns the method of the first host that has one with the given name.
+    pub fn get_method_for(&self, name: &str) -> Option<Box<dyn ExternalInterfaceMethod>> {
+        self.providers
+            .iter()
+            .find_map(|provider| provider.get_method(name))
+    }
+
+    /// Whether there is a host to communicate with.
+    pub fn available(&self) -> bool {
+        !self.providers.is_empty()
+    }
+
+    /// Passes an `fscommand` to the hosts, returning whether any of them handled it.
+    pub fn on_fs_command(&self, command: &str, args: &str) -> bool {
+        self.providers
+            .iter()
+            .any(|provider| provider.on_fs_command(command, args))
+    }
+}
+
+impl<'gc> Default for ExternalInterface<'gc> {
+    fn default() -> Self {
+        ExternalInterface::new()
+    }
+}
@@ -0,0 +1,312 @@
+//! Keyboard focus tracking.
+
+use crate::avm1::listeners::SystemListener;
+use crate::avm1::{Avm1, Value};
+use crate::bounding_box::BoundingBox;
+use crate::context::{ActionType, RenderContext, UpdateContext};
+use crate::display_object::{DisplayObject, TDisplayObject};
+use crate::drawing::Drawing;
+use crate::shape_utils::DrawCommand;
+use gc_arena::{Collect, GcCell, MutationContext};
+use swf::Twips;
+
+/// Tracks which display object, if any, currently has keyboard focus.
+///
+/// The focused object receives text input and editing keys. Only one object
+/// can have focus at a time.
+#[derive(Clone, Copy, Collect, Debug)]
+#[collect(no_drop)]
+pub struct FocusTracker<'gc>(GcCell<'gc, FocusTrackerData<'gc>>);
+
+#[derive(Clone, Collect, Debug)]
+#[collect(no_drop)]
+struct FocusTrackerData<'gc> {
+    /// The display object that currently has focus.
+    focus: Option<DisplayObject<'gc>>,
+
+    /// Whether the focused object is highlighted, as it is when focus was
+    /// moved with the keyboard.
+    is_highlighted: bool,
+
+    /// Whether highlighted objects are drawn with a focus rectangle, as set by
+    /// `_focusrect`.
+    show_focus_rect: bool,
+
+    /// The focus rectangle drawing.
+    highlight: Drawing,
+
+    /// The bounds that the focus rectangle was last drawn around.
+    highlight_bounds: BoundingBox,
+}
+
+impl<'gc> FocusTracker<'gc> {
+    /// The color of the focus rectangle.
+    const HIGHLIGHT_COLOR: swf::Color = swf::Color {
+        r: 0xFF,
+        g: 0xFF,
+        b: 0,
+        a: 0xFF,
+    };
+
+    /// The width of the focus rectangle, in pixels.
+    const HIGHLIGHT_WIDTH: f64 = 2.0;
+
+    pub fn new(gc_context: MutationContext<'gc, '_>) -> Self {
+        Self(GcCell::allocate(
+            gc_context,
+            FocusTrackerData {
+                focus: None,
+                is_highlighted: false,
+                show_focus_rect: true,
+                highlight: Drawing::new(),
+                highlight_bounds: BoundingBox::default(),
+            },
+        ))
+    }
+
+    /// The display object that currently has focus.
+    pub fn get(&self) -> Option<DisplayObject<'gc>> {
+        self.0.read().focus
+    }
+
+    /// Move focus to a new display object, or remove focus entirely.
+    ///
+    /// Both the previously and newly focused objects are notified of the
+    /// change, and receive `onKillFocus` and `onSetFocus` respectively.
+    /// `Selection` listeners then receive `onSetFocus`. The new focus is not
+    /// highlighted.
+    pub fn set(
+        &self,
+        focused_element: Option<DisplayObject<'gc>>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+    ) {
+        let mut write = self.0.write(context.gc_context);
+        let old = std::mem::replace(&mut write.focus, focused_element);
+        write.is_highlighted = false;
+        drop(write);
+
+        let is_unchanged = match (old, focused_element) {
+            (Some(old), Some(new)) => DisplayObject::ptr_eq(old, new),
+            (None, None) => true,
+            _ => false,
+        };
+        if is_unchanged {
+            return;
+        }
+
+        if let Some(old) = old {
+            old.on_focus_changed(context.gc_context, false);
+            Self::queue_focus_event(context, old, "onKillFocus", focused_element);
+        }
+        if let Some(new) = focused_element {
+            new.on_focus_changed(context.gc_context, true);
+            Self::queue_focus_event(context, new, "onSetFocus", old);
+        }
+
+        if let Some(root) = context.levels.get(&0).copied() {
+            let old = old.map(|old| old.object()).unwrap_or(Value::Null);
+            let new = focused_element
+                .map(|new| new.object())
+                .unwrap_or(Value::Null);
+            context.action_queue.queue_actions(
+                root,
+                ActionType::NotifyListeners {
+                    listener: SystemListener::Selection,
+                    method: "onSetFocus",
+                    args: vec![old, new],
+                },
+                false,
+            );
+        }
+    }
+
+    /// Move focus to the next object in the tab order, or the previous one if
+    /// `reverse` is set, and highlight it.
+    ///
+    /// If any tab-enabled object has a `tabIndex`, the order is given by
+    /// `tabIndex` and objects without one are skipped. Otherwise, objects are
+    /// ordered from top to bottom, then left to right.
+    pub fn cycle(
+        &self,
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reverse: bool,
+    ) {
+        let mut tab_order = Vec::new();
+        let levels: Vec<DisplayObject<'gc>> = context.levels.values().copied().collect();
+        for level in levels {
+            Self::fill_tab_order(avm, context, level, &mut tab_order);
+        }
+
+        if tab_order.iter().any(|object| object.tab_index().is_some()) {
+            tab_order.retain(|object| object.tab_index().is_some());
+            tab_order.sort_by_key(|object| object.tab_index());
+        } else {
+            tab_order.sort_by_key(|object| {
+                let bounds = object.world_bounds();
+                (bounds.y_min, bounds.x_min)
+            });
+        }
+
+        let current = self.get().and_then(|focus| {
+            tab_order
+                .iter()
+                .position(|object| DisplayObject::ptr_eq(*object, focus))
+        });
+        if let Some(next) = next_in_cycle(tab_order.len(), current, reverse) {
+            self.set(Some(tab_order[next]), context);
+            self.0.write(context.gc_context).is_highlighted = true;
+        }
+    }
+
+    /// Collect the tab-enabled descendants of a display object, in display
+    /// order.
+    fn fill_tab_order(
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        object: DisplayObject<'gc>,
+        tab_order: &mut Vec<DisplayObject<'gc>>,
+    ) {
+        if !object.visible() {
+            return;
+        }
+
+        if object.is_tab_enabled(avm, context) {
+            tab_order.push(object);
+        }
+
+        // Buttons are focused as a whole.
+        if object.as_button().is_none() {
+            for child in object.children() {
+                Self::fill_tab_order(avm, context, child, tab_order);
+            }
+        }
+    }
+
+    /// Queue a focus event handler on a display object, passing the other
+    /// object involved in the change.
+    fn queue_focus_event(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        object: DisplayObject<'gc>,
+        name: &'static str,
+        other: Option<DisplayObject<'gc>>,
+    ) {
+        if context.swf.version() < 6 {
+            return;
+        }
+
+        if let Value::Object(handler_object) = object.object() {
+            let other = other.map(|other| other.object()).unwrap_or(Value::Null);
+            context.action_queue.queue_actions(
+                object,
+                ActionType::Method {
+                    object: handler_object,
+                    name,
+                    args: vec![other],
+                },
+                false,
+            );
+        }
+    }
+
+    /// Whether highlighted objects are drawn with a focus rectangle.
+    pub fn show_focus_rect(&self) -> bool {
+        self.0.read().show_focus_rect
+    }
+
+    pub fn set_show_focus_rect(&self, gc_context: MutationContext<'gc, '_>, show_focus_rect: bool) {
+        self.0.write(gc_context).show_focus_rect = show_focus_rect;
+    }
+
+    /// Draw the focus rectangle around the focused object, if it is
+    /// highlighted.
+    pub fn render_highlight(
+        &self,
+        gc_context: MutationContext<'gc, '_>,
+        context: &mut RenderContext<'_, 'gc>,
+    ) {
+        let data = self.0.read();
+        let focus = match data.focus {
+            Some(focus) if data.is_highlighted && data.show_focus_rect => focus,
+            _ => return,
+        };
+        if focus.removed() || !focus.visible() {
+            return;
+        }
+
+        let bounds = focus.world_bounds();
+        if bounds != data.highlight_bounds {
+            drop(data);
+            let mut write = self.0.write(gc_context);
+            Self::redraw_highlight(&mut write.highlight, &bounds);
+            write.highlight_bounds = bounds;
+            drop(write);
+            self.0.read().highlight.render(context);
+        } else {
+            data.highlight.render(context);
+        }
+    }
+
+    /// Redraw the focus rectangle around the given bounds.
+    fn redraw_highlight(highlight: &mut Drawing, bounds: &BoundingBox) {
+        highlight.clear();
+        highlight.set_line_style(Some(swf::LineStyle::new_v1(
+            Twips::from_pixels(Self::HIGHLIGHT_WIDTH),
+            Self::HIGHLIGHT_COLOR,
+        )));
+        highlight.draw_command(DrawCommand::MoveTo {
+            x: bounds.x_min,
+            y: bounds.y_min,
+        });
+        highlight.draw_command(DrawCommand::LineTo {
+            x: bounds.x_max,
+            y: bounds.y_min,
+        });
+        highlight.draw_command(DrawCommand::LineTo {
+            x: bounds.x_max,
+            y: bounds.y_max,
+        });
+        highlight.draw_command(DrawCommand::LineTo {
+            x: bounds.x_min,
+            y: bounds.y_max,
+        });
+        highlight.draw_command(DrawCommand::LineTo {
+            x: bounds.x_min,
+            y: bounds.y_min,
+        });
+        highlight.set_line_style(None);
+    }
+}
+
+/// Find the index of the next entry in a cycle of `len` entries, wrapping
+/// around at either end.
+///
+/// With no current entry, the cycle starts from the first entry, or the last
+/// if `reverse` is set.
+fn next_in_cycle(len: usize, current: Option<usize>, reverse: bool) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+
+    Some(match (current, reverse) {
+        (Some(current), false) => (current + 1) % len,
+        (Some(current), true) => (current + len - 1) % len,
+        (None, false) => 0,
+        (None, true) => len - 1,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::next_in_cycle;
+
+    #[test]
+    fn cycle_wraps() {
+        assert_eq!(next_in_cycle(0, None, false), None);
+        assert_eq!(next_in_cycle(3, None, false), Some(0));
+        assert_eq!(next_in_cycle(3, None, true), Some(2));
+        assert_eq!(next_in_cycle(3, Some(1), false), Some(2));
+        assert_eq!(next_in_cycle(3, Some(2), false), Some(0));
+        assert_eq!(next_in_cycle(3, Some(0), true), Some(2));
+    }
+}
@@ -3,6 +3,9 @@ use crate::html::TextSpan;
 use crate::prelude::*;
 use crate::transform::Transform;
 use gc_arena::{Collect, Gc, MutationContext};
+use std::sync::Arc;
+
+mod opentype;
 
 /// Certain Flash routines measure text by rounding down to the nearest whole pixel.
 pub fn round_down_to_pixel(t: Twips) -> Twips {
@@ -107,6 +110,7 @@ impl<'gc> Font<'gc> {
         for swf_glyph in &tag.glyphs {
             let glyph = Glyph {
                 shape: renderer.register_glyph_shape(swf_glyph),
+                swf_shape: Arc::new(glyph_to_shape(swf_glyph)),
                 advance: swf_glyph.advance.unwrap_or(0),
             };
             let index = glyphs.len();
@@ -148,6 +152,17 @@ impl<'gc> Font<'gc> {
         )))
     }
 
+    /// Constructs a font from a `DefineFont4` tag by reading the outlines of its embedded
+    /// OpenType font.
+    pub fn from_font4_tag(
+        gc_context: MutationContext<'gc, '_>,
+        renderer: &mut dyn RenderBackend,
+        tag: &swf::Font4,
+    ) -> Result<Font<'gc>, Error> {
+        let font = opentype::font4_to_swf_font(tag)?;
+        Self::from_swf_tag(gc_context, renderer, &font)
+    }
+
     /// Returns whether this font contains glyph shapes.
     /// If not, this font should be rendered as a device font.
     pub fn has_glyphs(self) -> bool {
@@ -359,9 +374,37 @@ impl<'gc> Font<'gc> {
 #[derive(Debug, Clone)]
 pub struct Glyph {
     pub shape: ShapeHandle,
+
+    /// The outline of the glyph as a shape, used for hit testing.
+    pub swf_shape: Arc<swf::Shape>,
+
     pub advance: i16,
 }
 
+/// Converts the outline of a glyph to a shape with a single fill.
+fn glyph_to_shape(glyph: &swf::Glyph) -> swf::Shape {
+    let bounds = crate::shape_utils::calculate_shape_bounds(&glyph.shape_records);
+    swf::Shape {
+        version: 2,
+        id: 0,
+        shape_bounds: bounds.clone(),
+        edge_bounds: bounds,
+        has_fill_winding_rule: false,
+        has_non_scaling_strokes: false,
+        has_scaling_strokes: true,
+        styles: swf::ShapeStyles {
+            fill_styles: vec![swf::FillStyle::Color(Color {
+                r: 255,
+                g: 255,
+                b: 255,
+                a: 255,
+            })],
+            line_styles: vec![],
+        },
+        shape: glyph.shape_records.clone(),
+    }
+}
+
 /// Structure which identifies a particular font by name and properties.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Collect)]
 #[collect(require_static)]
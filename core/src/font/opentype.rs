@@ -0,0 +1,1432 @@
+//! Reader for the OpenType font data embedded in `DefineFont4` tags.
+//!
+//! `DefineFont4` stores a complete OpenType font file instead of SWF shape records. We read
+//! the glyph outlines out of its `glyf` (TrueType) or `CFF ` (PostScript) table and convert
+//! them into the same shapes a `DefineFont3` tag would contain, so that the rest of the
+//! player can treat both kinds of fonts alike.
+
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use swf::{FontLayout, Glyph, KerningRecord, ShapeRecord, StyleChangeData, Twips};
+
+type Error = Box<dyn std::error::Error>;
+
+/// The size of the EM square of a `DefineFont3` glyph.
+const EM_SQUARE: f64 = 20480.0;
+
+/// The deepest subroutine or composite glyph nesting we follow before giving up.
+const MAX_NESTING: u32 = 10;
+
+/// Converts the OpenType data of a `DefineFont4` tag into an equivalent `DefineFont3` font.
+///
+/// Fonts without embedded data produce an empty font, which is rendered as a device font.
+pub fn font4_to_swf_font(tag: &swf::Font4) -> Result<swf::Font, Error> {
+    let (glyphs, layout) = if let Some(data) = &tag.data {
+        let font = OpenTypeFont::parse(data)?;
+        (font.glyphs()?, Some(font.layout()))
+    } else {
+        (vec![], None)
+    };
+
+    Ok(swf::Font {
+        version: 3,
+        id: tag.id,
+        name: tag.name.clone(),
+        language: swf::Language::Unknown,
+        layout,
+        glyphs,
+        is_small_text: false,
+        is_shift_jis: false,
+        is_ansi: false,
+        is_bold: tag.is_bold,
+        is_italic: tag.is_italic,
+    })
+}
+
+/// A big-endian cursor over a table of the font.
+#[derive(Clone)]
+struct Stream<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Stream<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn at(data: &'a [u8], pos: usize) -> Result<Self, Error> {
+        if pos > data.len() {
+            return Err("Offset out of bounds".into());
+        }
+        Ok(Self { data, pos })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    fn skip(&mut self, len: usize) -> Result<(), Error> {
+        self.read_bytes(len).map(|_| ())
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.data.len())
+            .ok_or("Unexpected end of font data")?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, Error> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16, Error> {
+        let bytes = self.read_bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_i16(&mut self) -> Result<i16, Error> {
+        Ok(self.read_u16()? as i16)
+    }
+
+    fn read_u24(&mut self) -> Result<u32, Error> {
+        let bytes = self.read_bytes(3)?;
+        Ok(u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]))
+    }
+
+    fn read_u32(&mut self) -> Result<u32, Error> {
+        let bytes = self.read_bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn read_i32(&mut self) -> Result<i32, Error> {
+        Ok(self.read_u32()? as i32)
+    }
+
+    /// Reads an unsigned offset of `size` bytes, as used by CFF INDEXes.
+    fn read_offset(&mut self, size: u8) -> Result<usize, Error> {
+        Ok(match size {
+            1 => self.read_u8()?.into(),
+            2 => self.read_u16()?.into(),
+            3 => self.read_u24()? as usize,
+            4 => self.read_u32()? as usize,
+            _ => return Err("Invalid CFF offset size".into()),
+        })
+    }
+}
+
+/// The parts of an OpenType font needed to build SWF glyphs.
+struct OpenTypeFont<'a> {
+    units_per_em: u16,
+    ascender: i16,
+    descender: i16,
+    line_gap: i16,
+
+    /// The advance width of each glyph, by glyph ID.
+    advances: Vec<u16>,
+
+    /// Maps each Unicode code point to a glyph ID.
+    code_points: BTreeMap<u16, u16>,
+
+    /// Kerning adjustments between pairs of glyph IDs.
+    kerning: Vec<(u16, u16, i16)>,
+
+    outlines: Outlines<'a>,
+}
+
+enum Outlines<'a> {
+    TrueType { glyf: &'a [u8], loca: Vec<usize> },
+    Cff(Cff<'a>),
+}
+
+impl<'a> OpenTypeFont<'a> {
+    fn parse(data: &'a [u8]) -> Result<Self, Error> {
+        let mut tables = BTreeMap::new();
+        let mut stream = Stream::new(data);
+        let version = stream.read_u32()?;
+        if version != 0x0001_0000
+            && &version.to_be_bytes() != b"OTTO"
+            && &version.to_be_bytes() != b"true"
+        {
+            return Err("Font data is not an OpenType font".into());
+        }
+        let num_tables = stream.read_u16()?;
+        stream.skip(6)?;
+        for _ in 0..num_tables {
+            let tag = stream.read_bytes(4)?;
+            let _checksum = stream.read_u32()?;
+            let offset = stream.read_u32()? as usize;
+            let length = stream.read_u32()? as usize;
+            let table = offset
+                .checked_add(length)
+                .and_then(|end| data.get(offset..end))
+                .ok_or("Font table out of bounds")?;
+            tables.insert(tag, table);
+        }
+        let table = |tag: &[u8]| -> Result<&'a [u8], Error> {
+            tables.get(tag).copied().ok_or_else(|| {
+                format!("Font is missing '{}' table", String::from_utf8_lossy(tag)).into()
+            })
+        };
+
+        let mut head = Stream::at(table(b"head")?, 18)?;
+        let units_per_em = head.read_u16()?;
+        if units_per_em == 0 {
+            return Err("Font has an empty EM square".into());
+        }
+        let mut head = Stream::at(table(b"head")?, 50)?;
+        let index_to_loc_format = head.read_i16()?;
+
+        let num_glyphs = Stream::at(table(b"maxp")?, 4)?.read_u16()?;
+
+        let mut hhea = Stream::at(table(b"hhea")?, 4)?;
+        let ascender = hhea.read_i16()?;
+        let descender = hhea.read_i16()?;
+        let line_gap = hhea.read_i16()?;
+        let num_h_metrics = Stream::at(table(b"hhea")?, 34)?.read_u16()?;
+
+        let mut hmtx = Stream::new(table(b"hmtx")?);
+        let mut advances = Vec::with_capacity(num_glyphs.into());
+        for _ in 0..num_h_metrics.min(num_glyphs) {
+            advances.push(hmtx.read_u16()?);
+            let _left_side_bearing = hmtx.read_i16()?;
+        }
+        let last_advance = advances.last().copied().unwrap_or(0);
+        advances.resize(num_glyphs.into(), last_advance);
+
+        let code_points = parse_cmap(table(b"cmap")?)?;
+        let kerning = match tables.get(&b"kern"[..]) {
+            Some(kern) => parse_kern(kern)?,
+            None => vec![],
+        };
+
+        let outlines = if let Some(cff) = tables.get(&b"CFF "[..]) {
+            Outlines::Cff(Cff::parse(cff)?)
+        } else {
+            let mut stream = Stream::new(table(b"loca")?);
+            let mut loca = Vec::with_capacity(usize::from(num_glyphs) + 1);
+            for _ in 0..=num_glyphs {
+                loca.push(if index_to_loc_format == 0 {
+                    usize::from(stream.read_u16()?) * 2
+                } else {
+                    stream.read_u32()? as usize
+                });
+            }
+            Outlines::TrueType {
+                glyf: table(b"glyf")?,
+                loca,
+            }
+        };
+
+        Ok(Self {
+            units_per_em,
+            ascender,
+            descender,
+            line_gap,
+            advances,
+            code_points,
+            kerning,
+            outlines,
+        })
+    }
+
+    /// The scale from font units to the EM square of a `DefineFont3` glyph.
+    fn scale(&self) -> f64 {
+        EM_SQUARE / f64::from(self.units_per_em)
+    }
+
+    fn scale_value(&self, value: f64) -> f64 {
+        (value * self.scale()).round()
+    }
+
+    fn layout(&self) -> FontLayout {
+        let mut glyph_codes: BTreeMap<u16, Vec<u16>> = BTreeMap::new();
+        for (&code, &glyph_id) in &self.code_points {
+            glyph_codes.entry(glyph_id).or_default().push(code);
+        }
+
+        let mut kerning = vec![];
+        for &(left, right, value) in &self.kerning {
+            if let (Some(left_codes), Some(right_codes)) =
+                (glyph_codes.get(&left), glyph_codes.get(&right))
+            {
+                let adjustment = Twips::new(self.scale_value(value.into()) as i32);
+                for &left_code in left_codes {
+                    for &right_code in right_codes {
+                        kerning.push(KerningRecord {
+                            left_code,
+                            right_code,
+                            adjustment,
+                        });
+                    }
+                }
+            }
+        }
+
+        let clamp_u16 = |value: f64| value.clamp(0.0, f64::from(u16::MAX)) as u16;
+        FontLayout {
+            ascent: clamp_u16(self.scale_value(self.ascender.into())),
+            descent: clamp_u16(-self.scale_value(self.descender.into())),
+            leading: self
+                .scale_value(self.line_gap.into())
+                .clamp(i16::MIN.into(), i16::MAX.into()) as i16,
+            kerning,
+        }
+    }
+
+    /// Converts the outline of a glyph into SWF shape records.
+    fn glyph_shape(&self, glyph_id: u16) -> Result<Vec<ShapeRecord>, Error> {
+        let mut builder = ShapeBuilder::new(self.scale());
+        match &self.outlines {
+            Outlines::TrueType { glyf, loca } => {
+                for contour in truetype_contours(glyf, loca, glyph_id, 0)? {
+                    builder.add_truetype_contour(&contour);
+                }
+            }
+            Outlines::Cff(cff) => cff.outline(glyph_id, &mut builder)?,
+        }
+        Ok(builder.finish())
+    }
+
+    /// Builds a SWF glyph for every code point mapped by the font.
+    fn glyphs(&self) -> Result<Vec<Glyph>, Error> {
+        let mut shapes: BTreeMap<u16, Vec<ShapeRecord>> = BTreeMap::new();
+        let mut glyphs = Vec::with_capacity(self.code_points.len());
+        for (&code, &glyph_id) in &self.code_points {
+            let shape_records = match shapes.entry(glyph_id) {
+                Entry::Occupied(entry) => entry.get().clone(),
+                Entry::Vacant(entry) => entry.insert(self.glyph_shape(glyph_id)?).clone(),
+            };
+
+            let advance = self
+                .advances
+                .get(usize::from(glyph_id))
+                .copied()
+                .unwrap_or(0);
+            glyphs.push(Glyph {
+                shape_records,
+                code,
+                advance: Some(self.scale_value(advance.into()).min(i16::MAX.into()) as i16),
+                bounds: None,
+            });
+        }
+        Ok(glyphs)
+    }
+}
+
+/// Reads the Unicode code points of the Basic Multilingual Plane out of the `cmap` table.
+fn parse_cmap(cmap: &[u8]) -> Result<BTreeMap<u16, u16>, Error> {
+    let mut stream = Stream::at(cmap, 2)?;
+    let num_tables = stream.read_u16()?;
+    let mut best: Option<(u8, usize)> = None;
+    for _ in 0..num_tables {
+        let platform_id = stream.read_u16()?;
+        let encoding_id = stream.read_u16()?;
+        let offset = stream.read_u32()? as usize;
+        let priority = match (platform_id, encoding_id) {
+            (3, 10) => 3,
+            (0, _) => 2,
+            (3, 1) => 1,
+            _ => continue,
+        };
+        if best.is_none_or(|(best_priority, _)| priority > best_priority) {
+            best = Some((priority, offset));
+        }
+    }
+    let offset = best.ok_or("Font has no Unicode character map")?.1;
+
+    let mut code_points = BTreeMap::new();
+    let mut stream = Stream::at(cmap, offset)?;
+    match stream.read_u16()? {
+        4 => {
+            stream.skip(4)?;
+            let seg_count = usize::from(stream.read_u16()? / 2);
+            stream.skip(6)?;
+            let end_codes = stream.pos;
+            let start_codes = end_codes + seg_count * 2 + 2;
+            let id_deltas = start_codes + seg_count * 2;
+            let id_range_offsets = id_deltas + seg_count * 2;
+            for segment in 0..seg_count {
+                let end = Stream::at(cmap, end_codes + segment * 2)?.read_u16()?;
+                let start = Stream::at(cmap, start_codes + segment * 2)?.read_u16()?;
+                let delta = Stream::at(cmap, id_deltas + segment * 2)?.read_u16()?;
+                let range_offset_pos = id_range_offsets + segment * 2;
+                let range_offset = Stream::at(cmap, range_offset_pos)?.read_u16()?;
+                for code in start..=end {
+                    if code == 0xFFFF {
+                        break;
+                    }
+                    let glyph_id = if range_offset == 0 {
+                        code.wrapping_add(delta)
+                    } else {
+                        let pos = range_offset_pos
+                            + usize::from(range_offset)
+                            + usize::from(code - start) * 2;
+                        match Stream::at(cmap, pos)?.read_u16()? {
+                            0 => 0,
+                            glyph_id => glyph_id.wrapping_add(delta),
+                        }
+                    };
+                    if glyph_id != 0 {
+                        code_points.insert(code, glyph_id);
+                    }
+                }
+            }
+        }
+        6 => {
+            stream.skip(4)?;
+            let first_code = stream.read_u16()?;
+            let entry_count = stream.read_u16()?;
+            for i in 0..entry_count {
+                let glyph_id = stream.read_u16()?;
+                if let Some(code) = first_code.checked_add(i) {
+                    if glyph_id != 0 {
+                        code_points.insert(code, glyph_id);
+                    }
+                }
+            }
+        }
+        12 => {
+            stream.skip(10)?;
+            let num_groups = stream.read_u32()?;
+            for _ in 0..num_groups {
+                let start = stream.read_u32()?;
+                let end = stream.read_u32()?.min(0xFFFF);
+                let start_glyph_id = stream.read_u32()?;
+                for code in start..=end {
+                    let glyph_id = start_glyph_id + (code - start);
+                    if glyph_id != 0 && glyph_id <= 0xFFFF {
+                        code_points.insert(code as u16, glyph_id as u16);
+                    }
+                }
+            }
+        }
+        format => return Err(format!("Unsupported character map format {}", format).into()),
+    }
+    Ok(code_points)
+}
+
+/// Reads the horizontal pairs of a version 0 `kern` table.
+fn parse_kern(kern: &[u8]) -> Result<Vec<(u16, u16, i16)>, Error> {
+    let mut stream = Stream::new(kern);
+    let mut pairs = vec![];
+    if stream.read_u16()? != 0 {
+        // Apple's `kern` table format is not used by Flash.
+        return Ok(pairs);
+    }
+    let num_subtables = stream.read_u16()?;
+    for _ in 0..num_subtables {
+        let start = stream.pos;
+        let _version = stream.read_u16()?;
+        let length = usize::from(stream.read_u16()?);
+        let coverage = stream.read_u16()?;
+        // Only horizontal kerning in format 0 is supported.
+        if coverage & 0b1 != 0 && coverage >> 8 == 0 {
+            let num_pairs = stream.read_u16()?;
+            stream.skip(6)?;
+            for _ in 0..num_pairs {
+                let left = stream.read_u16()?;
+                let right = stream.read_u16()?;
+                let value = stream.read_i16()?;
+                pairs.push((left, right, value));
+            }
+        }
+        stream = Stream::at(kern, start + length.max(6))?;
+    }
+    Ok(pairs)
+}
+
+/// A point of a TrueType contour, with whether it lies on the curve.
+type ContourPoint = (f64, f64, bool);
+
+/// Reads the contours of a glyph from the `glyf` table, resolving composite glyphs.
+fn truetype_contours(
+    glyf: &[u8],
+    loca: &[usize],
+    glyph_id: u16,
+    depth: u32,
+) -> Result<Vec<Vec<ContourPoint>>, Error> {
+    let glyph_id = usize::from(glyph_id);
+    let (start, end) = match (loca.get(glyph_id), loca.get(glyph_id + 1)) {
+        (Some(&start), Some(&end)) if start < end => (start, end),
+        // Glyphs without outlines, such as spaces, have no data.
+        _ => return Ok(vec![]),
+    };
+    let data = glyf.get(start..end).ok_or("Glyph out of bounds")?;
+    let mut stream = Stream::new(data);
+    let num_contours = stream.read_i16()?;
+    stream.skip(8)?;
+
+    if num_contours >= 0 {
+        let mut end_points = Vec::with_capacity(num_contours as usize);
+        for _ in 0..num_contours {
+            end_points.push(usize::from(stream.read_u16()?));
+        }
+        let num_points = end_points.last().map_or(0, |&end| end + 1);
+        let instructions_len = stream.read_u16()?;
+        stream.skip(instructions_len.into())?;
+
+        let mut flags = Vec::with_capacity(num_points);
+        while flags.len() < num_points {
+            let flag = stream.read_u8()?;
+            flags.push(flag);
+            if flag & 0b1000 != 0 {
+                for _ in 0..stream.read_u8()? {
+                    flags.push(flag);
+                }
+            }
+        }
+        flags.truncate(num_points);
+
+        let mut read_coordinates = |short_flag: u8, same_flag: u8| -> Result<Vec<f64>, Error> {
+            let mut value = 0i32;
+            let mut coordinates = Vec::with_capacity(num_points);
+            for &flag in &flags {
+                if flag & short_flag != 0 {
+                    let delta = i32::from(stream.read_u8()?);
+                    value += if flag & same_flag != 0 { delta } else { -delta };
+                } else if flag & same_flag == 0 {
+                    value += i32::from(stream.read_i16()?);
+                }
+                coordinates.push(f64::from(value));
+            }
+            Ok(coordinates)
+        };
+        let xs = read_coordinates(0b10, 0b1_0000)?;
+        let ys = read_coordinates(0b100, 0b10_0000)?;
+
+        let mut contours = Vec::with_capacity(end_points.len());
+        let mut start = 0;
+        for end in end_points {
+            if end < start || end >= num_points {
+                return Err("Invalid glyph contour".into());
+            }
+            contours.push(
+                (start..=end)
+                    .map(|i| (xs[i], ys[i], flags[i] & 0b1 != 0))
+                    .collect(),
+            );
+            start = end + 1;
+        }
+        Ok(contours)
+    } else {
+        if depth >= MAX_NESTING {
+            return Err("Composite glyphs are nested too deeply".into());
+        }
+
+        const ARGS_ARE_WORDS: u16 = 0x1;
+        const ARGS_ARE_XY_VALUES: u16 = 0x2;
+        const HAS_SCALE: u16 = 0x8;
+        const MORE_COMPONENTS: u16 = 0x20;
+        const HAS_XY_SCALE: u16 = 0x40;
+        const HAS_2X2: u16 = 0x80;
+
+        let mut contours = vec![];
+        loop {
+            let flags = stream.read_u16()?;
+            let component = stream.read_u16()?;
+            let (dx, dy) = if flags & ARGS_ARE_WORDS != 0 {
+                (f64::from(stream.read_i16()?), f64::from(stream.read_i16()?))
+            } else {
+                (
+                    f64::from(stream.read_u8()? as i8),
+                    f64::from(stream.read_u8()? as i8),
+                )
+            };
+            let mut read_f2dot14 =
+                || -> Result<f64, Error> { Ok(f64::from(stream.read_i16()?) / 16384.0) };
+            let (a, b, c, d) = if flags & HAS_SCALE != 0 {
+                let scale = read_f2dot14()?;
+                (scale, 0.0, 0.0, scale)
+            } else if flags & HAS_XY_SCALE != 0 {
+                (read_f2dot14()?, 0.0, 0.0, read_f2dot14()?)
+            } else if flags & HAS_2X2 != 0 {
+                (
+                    read_f2dot14()?,
+                    read_f2dot14()?,
+                    read_f2dot14()?,
+                    read_f2dot14()?,
+                )
+            } else {
+                (1.0, 0.0, 0.0, 1.0)
+            };
+            // Aligning components by matching points is rare, so those components are
+            // placed without an offset.
+            let (dx, dy) = if flags & ARGS_ARE_XY_VALUES != 0 {
+                (dx, dy)
+            } else {
+                (0.0, 0.0)
+            };
+
+            for contour in truetype_contours(glyf, loca, component, depth + 1)? {
+                contours.push(
+                    contour
+                        .into_iter()
+                        .map(|(x, y, on_curve)| (a * x + c * y + dx, b * x + d * y + dy, on_curve))
+                        .collect(),
+                );
+            }
+
+            if flags & MORE_COMPONENTS == 0 {
+                break;
+            }
+        }
+        Ok(contours)
+    }
+}
+
+/// A CFF INDEX, an array of variable length byte strings.
+struct Index<'a> {
+    data: &'a [u8],
+    offsets: Vec<usize>,
+}
+
+impl<'a> Index<'a> {
+    fn parse(stream: &mut Stream<'a>) -> Result<Self, Error> {
+        let count = stream.read_u16()?;
+        if count == 0 {
+            return Ok(Self {
+                data: &[],
+                offsets: vec![],
+            });
+        }
+        let offset_size = stream.read_u8()?;
+        let mut offsets = Vec::with_capacity(usize::from(count) + 1);
+        for _ in 0..=count {
+            // Offsets are relative to the byte before the data.
+            let offset = stream.read_offset(offset_size)?;
+            offsets.push(offset.checked_sub(1).ok_or("Invalid CFF INDEX offset")?);
+        }
+        let data = stream.read_bytes(*offsets.last().unwrap())?;
+        Ok(Self { data, offsets })
+    }
+
+    fn len(&self) -> usize {
+        self.offsets.len().saturating_sub(1)
+    }
+
+    fn get(&self, i: usize) -> Option<&'a [u8]> {
+        let start = *self.offsets.get(i)?;
+        let end = *self.offsets.get(i + 1)?;
+        self.data.get(start..end)
+    }
+
+    /// The number added to subroutine numbers by the charstrings using this INDEX.
+    fn subroutine_bias(&self) -> i32 {
+        match self.len() {
+            0..=1239 => 107,
+            1240..=33899 => 1131,
+            _ => 32768,
+        }
+    }
+}
+
+/// A CFF DICT, mapping operators to their operands.
+///
+/// Two-byte operators are stored as `1200 + second byte`.
+struct Dict(Vec<(u16, Vec<f64>)>);
+
+impl Dict {
+    fn parse(data: &[u8]) -> Result<Self, Error> {
+        let mut entries = vec![];
+        let mut operands = vec![];
+        let mut stream = Stream::new(data);
+        while !stream.is_empty() {
+            let b0 = stream.read_u8()?;
+            match b0 {
+                0..=21 => {
+                    let operator = if b0 == 12 {
+                        1200 + u16::from(stream.read_u8()?)
+                    } else {
+                        b0.into()
+                    };
+                    entries.push((operator, std::mem::take(&mut operands)));
+                }
+                28 => operands.push(f64::from(stream.read_i16()?)),
+                29 => operands.push(f64::from(stream.read_i32()?)),
+                30 => {
+                    // Real numbers are only used by entries we don't need, so skip the nibbles.
+                    while stream.read_u8()? & 0xF != 0xF {}
+                    operands.push(0.0);
+                }
+                32..=246 => operands.push(f64::from(i32::from(b0) - 139)),
+                247..=250 => {
+                    let b1 = i32::from(stream.read_u8()?);
+                    operands.push(f64::from((i32::from(b0) - 247) * 256 + b1 + 108));
+                }
+                251..=254 => {
+                    let b1 = i32::from(stream.read_u8()?);
+                    operands.push(f64::from(-(i32::from(b0) - 251) * 256 - b1 - 108));
+                }
+                _ => return Err("Invalid CFF DICT data".into()),
+            }
+        }
+        Ok(Self(entries))
+    }
+
+    fn get(&self, operator: u16) -> Option<&[f64]> {
+        self.0
+            .iter()
+            .find(|(op, _)| *op == operator)
+            .map(|(_, operands)| &operands[..])
+    }
+
+    fn get_offset(&self, operator: u16) -> Option<usize> {
+        self.get(operator)
+            .and_then(|operands| operands.first())
+            .map(|&offset| offset as usize)
+    }
+
+    /// Reads the local subroutines from the Private DICT referenced by this DICT.
+    fn private_subroutines<'a>(&self, cff: &'a [u8]) -> Result<Index<'a>, Error> {
+        const PRIVATE: u16 = 18;
+        const SUBRS: u16 = 19;
+        if let Some(&[size, offset]) = self.get(PRIVATE) {
+            let (size, offset) = (size as usize, offset as usize);
+            let data = offset
+                .checked_add(size)
+                .and_then(|end| cff.get(offset..end))
+                .ok_or("CFF Private DICT out of bounds")?;
+            if let Some(subrs) = Dict::parse(data)?.get_offset(SUBRS) {
+                return Index::parse(&mut Stream::at(cff, offset + subrs)?);
+            }
+        }
+        Ok(Index {
+            data: &[],
+            offsets: vec![],
+        })
+    }
+}
+
+/// The `CFF ` table of an OpenType font, containing Type 2 charstrings.
+struct Cff<'a> {
+    char_strings: Index<'a>,
+    global_subrs: Index<'a>,
+
+    /// The local subroutines of each Font DICT; non-CID fonts have a single one.
+    local_subrs: Vec<Index<'a>>,
+
+    /// Maps glyph IDs to Font DICTs in CID-keyed fonts.
+    fd_select: Option<Vec<u8>>,
+}
+
+impl<'a> Cff<'a> {
+    fn parse(data: &'a [u8]) -> Result<Self, Error> {
+        const CHAR_STRINGS: u16 = 17;
+        const CHARSTRING_TYPE: u16 = 1206;
+        const FD_ARRAY: u16 = 1236;
+        const FD_SELECT: u16 = 1237;
+
+        let mut stream = Stream::at(data, 2)?;
+        let header_size = stream.read_u8()?;
+        let mut stream = Stream::at(data, header_size.into())?;
+        let _names = Index::parse(&mut stream)?;
+        let top_dicts = Index::parse(&mut stream)?;
+        let _strings = Index::parse(&mut stream)?;
+        let global_subrs = Index::parse(&mut stream)?;
+
+        let top_dict = Dict::parse(top_dicts.get(0).ok_or("CFF has no Top DICT")?)?;
+        if let Some(&[charstring_type]) = top_dict.get(CHARSTRING_TYPE) {
+            if charstring_type as i32 != 2 {
+                return Err("Unsupported CFF charstring type".into());
+            }
+        }
+        let char_strings_offset = top_dict
+            .get_offset(CHAR_STRINGS)
+            .ok_or("CFF has no CharStrings")?;
+        let char_strings = Index::parse(&mut Stream::at(data, char_strings_offset)?)?;
+
+        let (local_subrs, fd_select) = if let Some(fd_array) = top_dict.get_offset(FD_ARRAY) {
+            let font_dicts = Index::parse(&mut Stream::at(data, fd_array)?)?;
+            let mut local_subrs = Vec::with_capacity(font_dicts.len());
+            for i in 0..font_dicts.len() {
+                let font_dict = Dict::parse(font_dicts.get(i).unwrap_or_default())?;
+                local_subrs.push(font_dict.private_subroutines(data)?);
+            }
+            let fd_select = top_dict
+                .get_offset(FD_SELECT)
+                .ok_or("CID-keyed CFF has no FDSelect")?;
+            let fd_select = parse_fd_select(data, fd_select, char_strings.len())?;
+            (local_subrs, Some(fd_select))
+        } else {
+            (vec![top_dict.private_subroutines(data)?], None)
+        };
+
+        Ok(Self {
+            char_strings,
+            global_subrs,
+            local_subrs,
+            fd_select,
+        })
+    }
+
+    fn outline(&self, glyph_id: u16, builder: &mut ShapeBuilder) -> Result<(), Error> {
+        let glyph_id = usize::from(glyph_id);
+        let char_string = self
+            .char_strings
+            .get(glyph_id)
+            .ok_or("Glyph out of bounds")?;
+        let font_dict = match &self.fd_select {
+            Some(fd_select) => fd_select.get(glyph_id).copied().unwrap_or(0).into(),
+            None => 0,
+        };
+        let empty = Index {
+            data: &[],
+            offsets: vec![],
+        };
+        let mut interpreter = CharStringInterpreter {
+            global_subrs: &self.global_subrs,
+            local_subrs: self.local_subrs.get(font_dict).unwrap_or(&empty),
+            builder,
+            stack: vec![],
+            x: 0.0,
+            y: 0.0,
+            num_stems: 0,
+            width_parsed: false,
+            finished: false,
+        };
+        interpreter.execute(char_string, 0)?;
+        Ok(())
+    }
+}
+
+/// Reads the Font DICT index of every glyph from a CFF FDSelect.
+fn parse_fd_select(data: &[u8], offset: usize, num_glyphs: usize) -> Result<Vec<u8>, Error> {
+    let mut stream = Stream::at(data, offset)?;
+    let mut fd_select = vec![0; num_glyphs];
+    match stream.read_u8()? {
+        0 => {
+            for fd in fd_select.iter_mut() {
+                *fd = stream.read_u8()?;
+            }
+        }
+        3 => {
+            let num_ranges = stream.read_u16()?;
+            let mut first = usize::from(stream.read_u16()?);
+            for _ in 0..num_ranges {
+                let fd = stream.read_u8()?;
+                let next = usize::from(stream.read_u16()?);
+                for glyph in fd_select.iter_mut().take(next).skip(first) {
+                    *glyph = fd;
+                }
+                first = next;
+            }
+        }
+        _ => return Err("Unsupported CFF FDSelect format".into()),
+    }
+    Ok(fd_select)
+}
+
+/// Runs Type 2 charstrings, drawing the glyph outline into a `ShapeBuilder`.
+///
+/// Hints have no effect on the outline, so they are only parsed far enough to skip them.
+struct CharStringInterpreter<'a, 'b> {
+    global_subrs: &'a Index<'a>,
+    local_subrs: &'a Index<'a>,
+    builder: &'b mut ShapeBuilder,
+    stack: Vec<f64>,
+    x: f64,
+    y: f64,
+    num_stems: usize,
+
+    /// Whether the optional advance width at the start of the charstring has been handled.
+    width_parsed: bool,
+
+    /// Set by `endchar`, which may appear inside a subroutine.
+    finished: bool,
+}
+
+impl<'a, 'b> CharStringInterpreter<'a, 'b> {
+    fn execute(&mut self, code: &[u8], depth: u32) -> Result<(), Error> {
+        if depth > MAX_NESTING {
+            return Err("CFF subroutines are nested too deeply".into());
+        }
+
+        let mut stream = Stream::new(code);
+        while !stream.is_empty() && !self.finished {
+            let b0 = stream.read_u8()?;
+            match b0 {
+                // hstem, vstem, hstemhm, vstemhm
+                1 | 3 | 18 | 23 => {
+                    self.parse_width(self.stack.len() % 2 == 1);
+                    self.num_stems += self.stack.len() / 2;
+                    self.stack.clear();
+                }
+                // hintmask, cntrmask
+                19 | 20 => {
+                    // Arguments before a mask are implicit vstem hints.
+                    self.parse_width(self.stack.len() % 2 == 1);
+                    self.num_stems += self.stack.len() / 2;
+                    self.stack.clear();
+                    stream.skip(self.num_stems.div_ceil(8))?;
+                }
+                // rmoveto
+                21 => {
+                    self.parse_width(self.stack.len() > 2);
+                    let (dx, dy) = (self.arg(0)?, self.arg(1)?);
+                    self.move_to(self.x + dx, self.y + dy);
+                }
+                // hmoveto
+                22 => {
+                    self.parse_width(self.stack.len() > 1);
+                    let dx = self.arg(0)?;
+                    self.move_to(self.x + dx, self.y);
+                }
+                // vmoveto
+                4 => {
+                    self.parse_width(self.stack.len() > 1);
+                    let dy = self.arg(0)?;
+                    self.move_to(self.x, self.y + dy);
+                }
+                // rlineto
+                5 => {
+                    for pair in self.stack.chunks_exact(2) {
+                        self.x += pair[0];
+                        self.y += pair[1];
+                        self.builder.line_to(self.x, self.y);
+                    }
+                    self.stack.clear();
+                }
+                // hlineto, vlineto
+                6 | 7 => {
+                    let mut horizontal = b0 == 6;
+                    for &delta in &self.stack {
+                        if horizontal {
+                            self.x += delta;
+                        } else {
+                            self.y += delta;
+                        }
+                        self.builder.line_to(self.x, self.y);
+                        horizontal = !horizontal;
+                    }
+                    self.stack.clear();
+                }
+                // rrcurveto
+                8 => {
+                    let stack = std::mem::take(&mut self.stack);
+                    for args in stack.chunks_exact(6) {
+                        self.curve(args[0], args[1], args[2], args[3], args[4], args[5]);
+                    }
+                }
+                // rcurveline
+                24 => {
+                    let stack = std::mem::take(&mut self.stack);
+                    let curves = stack.len().saturating_sub(2) / 6;
+                    for args in stack.chunks_exact(6).take(curves) {
+                        self.curve(args[0], args[1], args[2], args[3], args[4], args[5]);
+                    }
+                    if let &[dx, dy] = &stack[curves * 6..] {
+                        self.x += dx;
+                        self.y += dy;
+                        self.builder.line_to(self.x, self.y);
+                    }
+                }
+                // rlinecurve
+                25 => {
+                    let stack = std::mem::take(&mut self.stack);
+                    let lines = stack.len().saturating_sub(6) / 2;
+                    for pair in stack.chunks_exact(2).take(lines) {
+                        self.x += pair[0];
+                        self.y += pair[1];
+                        self.builder.line_to(self.x, self.y);
+                    }
+                    if let &[a, b, c, d, e, f] = &stack[lines * 2..] {
+                        self.curve(a, b, c, d, e, f);
+                    }
+                }
+                // vvcurveto
+                26 => {
+                    let stack = std::mem::take(&mut self.stack);
+                    let (mut dx1, args) = if stack.len() % 2 == 1 {
+                        (stack[0], &stack[1..])
+                    } else {
+                        (0.0, &stack[..])
+                    };
+                    for args in args.chunks_exact(4) {
+                        self.curve(dx1, args[0], args[1], args[2], 0.0, args[3]);
+                        dx1 = 0.0;
+                    }
+                }
+                // hhcurveto
+                27 => {
+                    let stack = std::mem::take(&mut self.stack);
+                    let (mut dy1, args) = if stack.len() % 2 == 1 {
+                        (stack[0], &stack[1..])
+                    } else {
+                        (0.0, &stack[..])
+                    };
+                    for args in args.chunks_exact(4) {
+                        self.curve(args[0], dy1, args[1], args[2], args[3], 0.0);
+                        dy1 = 0.0;
+                    }
+                }
+                // vhcurveto, hvcurveto
+                30 | 31 => {
+                    let stack = std::mem::take(&mut self.stack);
+                    let mut horizontal = b0 == 31;
+                    let mut i = 0;
+                    while i + 4 <= stack.len() {
+                        let last = if stack.len() - i == 5 {
+                            stack[i + 4]
+                        } else {
+                            0.0
+                        };
+                        let args = &stack[i..i + 4];
+                        if horizontal {
+                            self.curve(args[0], 0.0, args[1], args[2], last, args[3]);
+                        } else {
+                            self.curve(0.0, args[0], args[1], args[2], args[3], last);
+                        }
+                        horizontal = !horizontal;
+                        i += 4;
+                    }
+                }
+                // callsubr, callgsubr
+                10 | 29 => {
+                    let subrs = if b0 == 10 {
+                        self.local_subrs
+                    } else {
+                        self.global_subrs
+                    };
+                    let index = self.stack.pop().ok_or("CFF stack underflow")? as i32
+                        + subrs.subroutine_bias();
+                    let subr = usize::try_from(index)
+                        .ok()
+                        .and_then(|index| subrs.get(index))
+                        .ok_or("Invalid CFF subroutine")?;
+                    self.execute(subr, depth + 1)?;
+                }
+                // return
+                11 => return Ok(()),
+                // endchar
+                14 => {
+                    self.parse_width(self.stack.len() == 1 || self.stack.len() == 5);
+                    self.builder.close();
+                    self.stack.clear();
+                    self.finished = true;
+                }
+                12 => {
+                    let b1 = stream.read_u8()?;
+                    let stack = std::mem::take(&mut self.stack);
+                    match (b1, &stack[..]) {
+                        // hflex
+                        (34, &[dx1, dx2, dy2, dx3, dx4, dx5, dx6]) => {
+                            self.curve(dx1, 0.0, dx2, dy2, dx3, 0.0);
+                            self.curve(dx4, 0.0, dx5, -dy2, dx6, 0.0);
+                        }
+                        // flex
+                        (35, args) if args.len() == 13 => {
+                            self.curve(args[0], args[1], args[2], args[3], args[4], args[5]);
+                            self.curve(args[6], args[7], args[8], args[9], args[10], args[11]);
+                        }
+                        // hflex1
+                        (36, &[dx1, dy1, dx2, dy2, dx3, dx4, dx5, dy5, dx6]) => {
+                            self.curve(dx1, dy1, dx2, dy2, dx3, 0.0);
+                            self.curve(dx4, 0.0, dx5, dy5, dx6, -(dy1 + dy2 + dy5));
+                        }
+                        // flex1
+                        (37, args) if args.len() == 11 => {
+                            let dx: f64 = args.iter().take(10).step_by(2).sum();
+                            let dy: f64 = args.iter().skip(1).take(10).step_by(2).sum();
+                            let (dx6, dy6) = if dx.abs() > dy.abs() {
+                                (args[10], -dy)
+                            } else {
+                                (-dx, args[10])
+                            };
+                            self.curve(args[0], args[1], args[2], args[3], args[4], args[5]);
+                            self.curve(args[6], args[7], args[8], args[9], dx6, dy6);
+                        }
+                        _ => log::warn!("Unsupported CFF charstring operator 12 {}", b1),
+                    }
+                }
+                28 => self.stack.push(f64::from(stream.read_i16()?)),
+                32..=246 => self.stack.push(f64::from(i32::from(b0) - 139)),
+                247..=250 => {
+                    let b1 = i32::from(stream.read_u8()?);
+                    self.stack
+                        .push(f64::from((i32::from(b0) - 247) * 256 + b1 + 108));
+                }
+                251..=254 => {
+                    let b1 = i32::from(stream.read_u8()?);
+                    self.stack
+                        .push(f64::from(-(i32::from(b0) - 251) * 256 - b1 - 108));
+                }
+                255 => self.stack.push(f64::from(stream.read_i32()?) / 65536.0),
+                _ => return Err(format!("Invalid CFF charstring operator {}", b0).into()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Drops the advance width that may precede the arguments of the first stack-clearing
+    /// operator. Advances are read from the `hmtx` table instead.
+    fn parse_width(&mut self, has_width: bool) {
+        if !self.width_parsed {
+            self.width_parsed = true;
+            if has_width && !self.stack.is_empty() {
+                self.stack.remove(0);
+            }
+        }
+    }
+
+    fn arg(&self, i: usize) -> Result<f64, Error> {
+        Ok(*self.stack.get(i).ok_or("CFF stack underflow")?)
+    }
+
+    fn move_to(&mut self, x: f64, y: f64) {
+        self.x = x;
+        self.y = y;
+        self.builder.move_to(x, y);
+        self.stack.clear();
+    }
+
+    /// Draws a cubic curve, with each point relative to the one before it.
+    fn curve(&mut self, dxa: f64, dya: f64, dxb: f64, dyb: f64, dxc: f64, dyc: f64) {
+        let c1 = (self.x + dxa, self.y + dya);
+        let c2 = (c1.0 + dxb, c1.1 + dyb);
+        self.x = c2.0 + dxc;
+        self.y = c2.1 + dyc;
+        self.builder.cubic_to(c1, c2, (self.x, self.y));
+    }
+}
+
+/// Builds the shape records of a glyph from an outline in font units.
+///
+/// Every contour is filled with fill style 1; overlapping contours cut holes using the
+/// even-odd rule, as with the glyphs of `DefineFont` tags.
+struct ShapeBuilder {
+    scale: f64,
+    records: Vec<ShapeRecord>,
+
+    /// The position of the pen, in twips of the EM square.
+    pen: (i32, i32),
+
+    /// The start of the current contour in font units, if a contour is open.
+    contour_start: Option<(f64, f64)>,
+
+    /// The last point drawn to, in font units.
+    last: (f64, f64),
+}
+
+impl ShapeBuilder {
+    fn new(scale: f64) -> Self {
+        Self {
+            scale,
+            records: vec![],
+            pen: (0, 0),
+            contour_start: None,
+            last: (0.0, 0.0),
+        }
+    }
+
+    /// Converts a point in font units to twips. The Y axis points down in SWF shapes.
+    fn to_twips(&self, (x, y): (f64, f64)) -> (i32, i32) {
+        (
+            (x * self.scale).round() as i32,
+            (-y * self.scale).round() as i32,
+        )
+    }
+
+    fn move_to(&mut self, x: f64, y: f64) {
+        self.close();
+        let pen = self.to_twips((x, y));
+        let is_first = self.records.is_empty();
+        self.records.push(ShapeRecord::StyleChange(StyleChangeData {
+            move_to: Some((Twips::new(pen.0), Twips::new(pen.1))),
+            fill_style_0: None,
+            fill_style_1: if is_first { Some(1) } else { None },
+            line_style: None,
+            new_styles: None,
+        }));
+        self.pen = pen;
+        self.contour_start = Some((x, y));
+        self.last = (x, y);
+    }
+
+    fn line_to(&mut self, x: f64, y: f64) {
+        if self.contour_start.is_none() {
+            // Outlines must start with a move; treat a stray line as starting at the pen.
+            let (x0, y0) = self.last;
+            self.move_to(x0, y0);
+        }
+        let end = self.to_twips((x, y));
+        if end != self.pen {
+            self.records.push(ShapeRecord::StraightEdge {
+                delta_x: Twips::new(end.0 - self.pen.0),
+                delta_y: Twips::new(end.1 - self.pen.1),
+            });
+            self.pen = end;
+        }
+        self.last = (x, y);
+    }
+
+    fn quad_to(&mut self, control: (f64, f64), anchor: (f64, f64)) {
+        if self.contour_start.is_none() {
+            let (x0, y0) = self.last;
+            self.move_to(x0, y0);
+        }
+        let control_twips = self.to_twips(control);
+        let anchor_twips = self.to_twips(anchor);
+        if control_twips == self.pen || control_twips == anchor_twips {
+            self.line_to(anchor.0, anchor.1);
+            return;
+        }
+        self.records.push(ShapeRecord::CurvedEdge {
+            control_delta_x: Twips::new(control_twips.0 - self.pen.0),
+            control_delta_y: Twips::new(control_twips.1 - self.pen.1),
+            anchor_delta_x: Twips::new(anchor_twips.0 - control_twips.0),
+            anchor_delta_y: Twips::new(anchor_twips.1 - control_twips.1),
+        });
+        self.pen = anchor_twips;
+        self.last = anchor;
+    }
+
+    /// Approximates a cubic curve with quadratic curves, as SWF shapes have no cubics.
+    fn cubic_to(&mut self, c1: (f64, f64), c2: (f64, f64), end: (f64, f64)) {
+        let start = self.last;
+        // The error of approximating a cubic with a single quadratic is proportional to
+        // this distance, and shrinks with the cube of the number of pieces.
+        let dx = end.0 - 3.0 * c2.0 + 3.0 * c1.0 - start.0;
+        let dy = end.1 - 3.0 * c2.1 + 3.0 * c1.1 - start.1;
+        let error = (dx * dx + dy * dy).sqrt() * 3f64.sqrt() / 36.0 * self.scale;
+        let tolerance = 2.0;
+        let pieces = (error / tolerance).cbrt().ceil().clamp(1.0, 16.0) as u32;
+
+        let point = |t: f64| {
+            let mt = 1.0 - t;
+            let a = mt * mt * mt;
+            let b = 3.0 * mt * mt * t;
+            let c = 3.0 * mt * t * t;
+            let d = t * t * t;
+            (
+                a * start.0 + b * c1.0 + c * c2.0 + d * end.0,
+                a * start.1 + b * c1.1 + c * c2.1 + d * end.1,
+            )
+        };
+        let derivative = |t: f64| {
+            let mt = 1.0 - t;
+            let a = 3.0 * mt * mt;
+            let b = 6.0 * mt * t;
+            let c = 3.0 * t * t;
+            (
+                a * (c1.0 - start.0) + b * (c2.0 - c1.0) + c * (end.0 - c2.0),
+                a * (c1.1 - start.1) + b * (c2.1 - c1.1) + c * (end.1 - c2.1),
+            )
+        };
+
+        let step = 1.0 / f64::from(pieces);
+        for i in 0..pieces {
+            let t0 = f64::from(i) * step;
+            let t1 = t0 + step;
+            let p0 = point(t0);
+            let p1 = if i + 1 == pieces { end } else { point(t1) };
+            let d0 = derivative(t0);
+            let d1 = derivative(t1);
+            // The control point of the quadratic is the midpoint of the two control points
+            // the piece's tangents would give it.
+            let control = (
+                (p0.0 + d0.0 * step / 2.0 + p1.0 - d1.0 * step / 2.0) / 2.0,
+                (p0.1 + d0.1 * step / 2.0 + p1.1 - d1.1 * step / 2.0) / 2.0,
+            );
+            self.quad_to(control, p1);
+        }
+    }
+
+    /// Closes the current contour with a line back to its start.
+    fn close(&mut self) {
+        if let Some((x, y)) = self.contour_start {
+            self.line_to(x, y);
+            self.contour_start = None;
+        }
+    }
+
+    /// Draws a TrueType contour, where consecutive off-curve points imply an on-curve
+    /// point between them.
+    fn add_truetype_contour(&mut self, points: &[ContourPoint]) {
+        let midpoint = |a: &ContourPoint, b: &ContourPoint| ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+        let (first, last) = match (points.first(), points.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return,
+        };
+        let start = if first.2 {
+            (first.0, first.1)
+        } else if last.2 {
+            (last.0, last.1)
+        } else {
+            midpoint(last, first)
+        };
+        self.move_to(start.0, start.1);
+
+        let mut control: Option<(f64, f64)> = None;
+        for point in points.iter().skip(if first.2 { 1 } else { 0 }) {
+            if point.2 {
+                match control.take() {
+                    Some(control) => self.quad_to(control, (point.0, point.1)),
+                    None => self.line_to(point.0, point.1),
+                }
+            } else {
+                if let Some(previous) = control {
+                    let implied = midpoint(&(previous.0, previous.1, false), point);
+                    self.quad_to(previous, implied);
+                }
+                control = Some((point.0, point.1));
+            }
+        }
+        match control {
+            Some(control) => self.quad_to(control, start),
+            None => self.line_to(start.0, start.1),
+        }
+        self.contour_start = None;
+    }
+
+    fn finish(mut self) -> Vec<ShapeRecord> {
+        self.close();
+        self.records
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds the outline of a CFF charstring in font units of a 1000 unit EM square.
+    fn charstring_outline(code: &[u8]) -> Vec<ShapeRecord> {
+        let empty = Index {
+            data: &[],
+            offsets: vec![],
+        };
+        let mut builder = ShapeBuilder::new(1.0);
+        let mut interpreter = CharStringInterpreter {
+            global_subrs: &empty,
+            local_subrs: &empty,
+            builder: &mut builder,
+            stack: vec![],
+            x: 0.0,
+            y: 0.0,
+            num_stems: 0,
+            width_parsed: false,
+            finished: false,
+        };
+        interpreter.execute(code, 0).unwrap();
+        builder.finish()
+    }
+
+    fn number(value: i16) -> Vec<u8> {
+        let bytes = value.to_be_bytes();
+        vec![28, bytes[0], bytes[1]]
+    }
+
+    #[test]
+    fn cff_square_with_width() {
+        // 500 (width) 100 100 rmoveto 200 hlineto 200 vlineto -200 hlineto endchar
+        let mut code = vec![];
+        code.extend(number(500));
+        code.extend(number(100));
+        code.extend(number(100));
+        code.push(21);
+        code.extend(number(200));
+        code.push(6);
+        code.extend(number(200));
+        code.push(7);
+        code.extend(number(-200));
+        code.push(6);
+        code.push(14);
+
+        let records = charstring_outline(&code);
+        assert_eq!(records.len(), 5);
+        match &records[0] {
+            ShapeRecord::StyleChange(style_change) => {
+                assert_eq!(
+                    style_change.move_to,
+                    Some((Twips::new(100), Twips::new(-100)))
+                );
+                assert_eq!(style_change.fill_style_1, Some(1));
+            }
+            record => panic!("Expected a move, got {:?}", record),
+        }
+        let edges: Vec<(i32, i32)> = records[1..]
+            .iter()
+            .map(|record| match record {
+                ShapeRecord::StraightEdge { delta_x, delta_y } => (delta_x.get(), delta_y.get()),
+                record => panic!("Expected a straight edge, got {:?}", record),
+            })
+            .collect();
+        assert_eq!(edges, [(200, 0), (0, -200), (-200, 0), (0, 200)]);
+    }
+
+    #[test]
+    fn cff_curve_ends_at_anchor() {
+        // 0 0 rmoveto 0 100 100 0 100 -100 rrcurveto endchar
+        let mut code = vec![];
+        code.extend(number(0));
+        code.extend(number(0));
+        code.push(21);
+        for &value in &[0, 100, 100, 0, 100, -100] {
+            code.extend(number(value));
+        }
+        code.push(8);
+        code.push(14);
+
+        let records = charstring_outline(&code);
+        let mut end = (0, 0);
+        let mut has_curve = false;
+        for record in &records[1..] {
+            match record {
+                ShapeRecord::CurvedEdge {
+                    control_delta_x,
+                    control_delta_y,
+                    anchor_delta_x,
+                    anchor_delta_y,
+                } => {
+                    has_curve = true;
+                    end.0 += control_delta_x.get() + anchor_delta_x.get();
+                    end.1 += control_delta_y.get() + anchor_delta_y.get();
+                    if end == (200, 0) {
+                        break;
+                    }
+                }
+                record => panic!("Expected a curved edge, got {:?}", record),
+            }
+        }
+        assert!(has_curve);
+        assert_eq!(end, (200, 0));
+    }
+
+    #[test]
+    fn truetype_implied_points() {
+        let mut builder = ShapeBuilder::new(1.0);
+        builder.add_truetype_contour(&[
+            (0.0, 0.0, true),
+            (0.0, 100.0, false),
+            (100.0, 100.0, false),
+            (100.0, 0.0, true),
+        ]);
+        let records = builder.finish();
+        // Move, two quadratics meeting at the implied point (50, 100), and the closing line.
+        assert_eq!(records.len(), 4);
+        match records[1] {
+            ShapeRecord::CurvedEdge {
+                control_delta_x,
+                control_delta_y,
+                anchor_delta_x,
+                anchor_delta_y,
+            } => {
+                assert_eq!((control_delta_x.get(), control_delta_y.get()), (0, -100));
+                assert_eq!((anchor_delta_x.get(), anchor_delta_y.get()), (50, 0));
+            }
+            ref record => panic!("Expected a curved edge, got {:?}", record),
+        }
+        match records[3] {
+            ShapeRecord::StraightEdge { delta_x, delta_y } => {
+                assert_eq!((delta_x.get(), delta_y.get()), (-100, 0));
+            }
+            ref record => panic!("Expected a straight edge, got {:?}", record),
+        }
+    }
+}
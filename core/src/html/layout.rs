@@ -796,6 +796,16 @@ impl<'gc> LayoutBox<'gc> {
         }
     }
 
+    /// Returns the start and end positions of the text this box contains, if
+    /// it is a text box.
+    pub fn text_range(&self) -> Option<(usize, usize)> {
+        match &self.content {
+            LayoutContent::Text { start, end, .. } => Some((*start, *end)),
+            LayoutContent::Bullet { .. } => None,
+            LayoutContent::Drawing(..) => None,
+        }
+    }
+
     /// Returns a reference to the drawing this box contains, if it has one.
     pub fn as_renderable_drawing(&self) -> Option<&Drawing> {
         match &self.content {
@@ -793,3 +793,27 @@ fn formatspans_replace_text_degenerate() {
     assert_eq!((0, 1), fs.get_span_boundaries(0, 5));
     assert_eq!((1, 2), fs.get_span_boundaries(5, 9));
 }
+
+#[test]
+fn formatspans_hide_text() {
+    let mut tf1 = TextFormat::default();
+    tf1.font = Some("Same!".to_string());
+
+    let mut tf2 = TextFormat::default();
+    tf2.font = Some("Difference!".to_string());
+
+    let fs = FormatSpans::from_str_and_spans(
+        "abcé€fgh",
+        &[
+            TextSpan::with_length_and_format(5, tf1),
+            TextSpan::with_length_and_format(6, tf2),
+        ],
+    );
+
+    let hidden = fs.hide_text();
+
+    assert_eq!("********", hidden.text());
+
+    assert_eq!((0, 1), hidden.get_span_boundaries(0, 4));
+    assert_eq!((1, 2), hidden.get_span_boundaries(4, 8));
+}
@@ -1157,6 +1157,34 @@ impl FormatSpans {
         self.normalize();
     }
 
+    /// Construct a copy of these format spans with every character replaced
+    /// by an asterisk, as displayed by password fields.
+    ///
+    /// Each span of the copy covers the same characters as the original, so
+    /// the Nth character of the hidden text corresponds to the Nth character
+    /// of the original text.
+    pub fn hide_text(&self) -> Self {
+        let mut hidden = self.clone();
+        let mut hidden_text = String::with_capacity(self.text.len());
+        let mut position = 0;
+
+        for span in hidden.spans.iter_mut() {
+            let end = min(position + span.span_length, self.text.len());
+            let char_count = self
+                .text
+                .get(position..end)
+                .map(|text| text.chars().count())
+                .unwrap_or(0);
+
+            hidden_text.extend(std::iter::repeat('*').take(char_count));
+            position = end;
+            span.span_length = char_count;
+        }
+
+        hidden.text = hidden_text;
+        hidden
+    }
+
     /// Iterate over all text spans in the current list of format spans.
     ///
     /// The iterator returned by this function yields a tuple for each span,
@@ -0,0 +1,120 @@
+//! Keyboard state that is tracked by the player, rather than the input backend.
+
+use crate::events::KeyCode;
+
+/// The keyboard state queried by the `Key` global, other than which keys are
+/// held down.
+#[derive(Debug, Default)]
+pub struct KeyboardState {
+    /// The character code of the last key pressed.
+    last_ascii: u32,
+
+    /// Whether Caps Lock is toggled on.
+    is_caps_lock_on: bool,
+
+    /// Whether Num Lock is toggled on.
+    is_num_lock_on: bool,
+
+    /// Whether Scroll Lock is toggled on.
+    is_scroll_lock_on: bool,
+}
+
+impl KeyboardState {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Record a key being pressed.
+    ///
+    /// The character code is guessed from the key, as the character it
+    /// types may only arrive afterwards with `text_input`.
+    pub fn key_down(&mut self, key_code: KeyCode, is_shift_down: bool) {
+        match key_code {
+            KeyCode::CapsLock => self.is_caps_lock_on = !self.is_caps_lock_on,
+            KeyCode::NumLock => self.is_num_lock_on = !self.is_num_lock_on,
+            KeyCode::ScrollLock => self.is_scroll_lock_on = !self.is_scroll_lock_on,
+            _ => (),
+        }
+
+        let is_upper_case = is_shift_down != self.is_caps_lock_on;
+        self.last_ascii = key_code_to_ascii(key_code, is_upper_case);
+    }
+
+    /// Record the character typed by the last key press.
+    pub fn text_input(&mut self, codepoint: char) {
+        self.last_ascii = codepoint as u32;
+    }
+
+    /// The character code of the last key pressed, or zero if it does not
+    /// type a character.
+    pub fn last_ascii(&self) -> u32 {
+        self.last_ascii
+    }
+
+    /// Whether a lock key is toggled on. Other keys are never toggled.
+    pub fn is_toggled(&self, key_code: KeyCode) -> bool {
+        match key_code {
+            KeyCode::CapsLock => self.is_caps_lock_on,
+            KeyCode::NumLock => self.is_num_lock_on,
+            KeyCode::ScrollLock => self.is_scroll_lock_on,
+            _ => false,
+        }
+    }
+}
+
+/// The character code most likely typed by a key, or zero if it does not
+/// type a character.
+fn key_code_to_ascii(key_code: KeyCode, is_upper_case: bool) -> u32 {
+    let code = u32::from(u8::from(key_code));
+    match key_code {
+        KeyCode::Backspace | KeyCode::Tab | KeyCode::Return | KeyCode::Escape | KeyCode::Space => {
+            code
+        }
+        KeyCode::Delete => 127,
+        // Letter and digit key codes match their upper case ASCII characters.
+        _ if (u32::from(b'A')..=u32::from(b'Z')).contains(&code) && !is_upper_case => {
+            code + u32::from(b'a' - b'A')
+        }
+        _ if (u32::from(b'A')..=u32::from(b'Z')).contains(&code) => code,
+        _ if (u32::from(b'0')..=u32::from(b'9')).contains(&code) => code,
+        // Numpad key codes follow on from 96.
+        _ if (96..=105).contains(&code) => code - 96 + u32::from(b'0'),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KeyboardState;
+    use crate::events::KeyCode;
+
+    #[test]
+    fn ascii_from_keys() {
+        let mut keyboard = KeyboardState::new();
+        keyboard.key_down(KeyCode::A, false);
+        assert_eq!(keyboard.last_ascii(), u32::from(b'a'));
+        keyboard.key_down(KeyCode::A, true);
+        assert_eq!(keyboard.last_ascii(), u32::from(b'A'));
+        keyboard.key_down(KeyCode::Numpad7, false);
+        assert_eq!(keyboard.last_ascii(), u32::from(b'7'));
+        keyboard.key_down(KeyCode::Left, false);
+        assert_eq!(keyboard.last_ascii(), 0);
+
+        keyboard.key_down(KeyCode::Key1, true);
+        keyboard.text_input('!');
+        assert_eq!(keyboard.last_ascii(), u32::from(b'!'));
+    }
+
+    #[test]
+    fn lock_keys_toggle() {
+        let mut keyboard = KeyboardState::new();
+        assert!(!keyboard.is_toggled(KeyCode::CapsLock));
+        keyboard.key_down(KeyCode::CapsLock, false);
+        assert!(keyboard.is_toggled(KeyCode::CapsLock));
+        keyboard.key_down(KeyCode::B, false);
+        assert_eq!(keyboard.last_ascii(), u32::from(b'B'));
+        keyboard.key_down(KeyCode::CapsLock, false);
+        assert!(!keyboard.is_toggled(KeyCode::CapsLock));
+        assert!(!keyboard.is_toggled(KeyCode::A));
+    }
+}
@@ -10,28 +10,39 @@ extern crate smallvec;
 extern crate downcast_rs;
 
 mod avm1;
+mod avm2;
+mod bitmap_data;
 mod bounding_box;
 mod character;
 pub mod color_transform;
 mod context;
 mod drawing;
 pub mod events;
+pub mod external;
+mod focus_tracker;
 mod font;
 mod html;
+mod keyboard;
 mod library;
 pub mod loader;
+mod local_connection;
 mod player;
+mod policy;
 mod prelude;
 mod property_map;
 pub mod shape_utils;
+mod stage;
 pub mod string_utils;
 pub mod tag_utils;
+mod timer;
 mod transform;
 mod xml;
+mod xml_socket;
 
 pub mod backend;
 
-pub use events::PlayerEvent;
+pub use events::{MouseWheelDelta, PlayerEvent};
 pub use player::Player;
+pub use stage::{StageAlign, StageScaleMode};
 pub use swf;
 pub use swf::Color;
@@ -16,6 +16,7 @@ use weak_table::PtrWeakKeyHashMap;
 pub struct MovieLibrary<'gc> {
     characters: HashMap<CharacterId, Character<'gc>>,
     export_characters: HashMap<String, Character<'gc>>,
+    avm2_class_names: HashMap<CharacterId, String>,
     jpeg_tables: Option<Vec<u8>>,
     device_font: Option<Font<'gc>>,
     fonts: HashMap<FontDescriptor, Font<'gc>>,
@@ -26,6 +27,7 @@ impl<'gc> MovieLibrary<'gc> {
         MovieLibrary {
             characters: HashMap::new(),
             export_characters: HashMap::new(),
+            avm2_class_names: HashMap::new(),
             jpeg_tables: None,
             device_font: None,
             fonts: HashMap::new(),
@@ -70,6 +72,17 @@ impl<'gc> MovieLibrary<'gc> {
         }
     }
 
+    /// Links a character ID to an AVM2 class, as specified by a
+    /// `SymbolClass` tag. Character ID 0 refers to the main timeline.
+    pub fn register_avm2_class(&mut self, id: CharacterId, class_name: &str) {
+        self.avm2_class_names.insert(id, class_name.to_string());
+    }
+
+    /// Returns the name of the AVM2 class linked to a given character ID.
+    pub fn avm2_class_name(&self, id: CharacterId) -> Option<&str> {
+        self.avm2_class_names.get(&id).map(|name| name.as_str())
+    }
+
     pub fn contains_character(&self, id: CharacterId) -> bool {
         self.characters.contains_key(&id)
     }
@@ -132,6 +145,7 @@ impl<'gc> MovieLibrary<'gc> {
             Character::MovieClip(movie_clip) => Ok(movie_clip.instantiate(gc_context)),
             Character::Button(button) => Ok(button.instantiate(gc_context)),
             Character::Text(text) => Ok(text.instantiate(gc_context)),
+            Character::Video(video) => Ok(video.instantiate(gc_context)),
             _ => Err("Not a DisplayObject".into()),
         }
     }
@@ -1,7 +1,9 @@
 //! Management of async loaders
 
-use crate::avm1::{Object, TObject, Value};
-use crate::backend::navigator::OwnedFuture;
+use crate::avm1::property::Attribute;
+use crate::avm1::{Object, ScriptObject, SoundObject, TObject, Value};
+use crate::backend::audio::mp3_file;
+use crate::backend::navigator::{OwnedFuture, Response};
 use crate::context::{ActionQueue, ActionType};
 use crate::display_object::{DisplayObject, MorphShape, TDisplayObject};
 use crate::player::{Player, NEWEST_PLAYER_VERSION};
@@ -30,6 +32,12 @@ pub enum Error {
     #[error("Non-XML loader spawned as XML loader")]
     NotXmlLoader,
 
+    #[error("Non-sound loader spawned as sound loader")]
+    NotSoundLoader,
+
+    #[error("Non-LoadVars loader spawned as LoadVars loader")]
+    NotLoadVarsLoader,
+
     #[error("Invalid SWF")]
     InvalidSwf(#[from] crate::tag_utils::Error),
 
@@ -39,12 +47,28 @@ pub enum Error {
     #[error("Network error")]
     NetworkError(#[from] std::io::Error),
 
+    #[error("HTTP status {1} when loading {0}")]
+    HttpNotOk(String, u16),
+
+    #[error("Loading {0} is not permitted by the security sandbox")]
+    SandboxViolation(String),
+
     // TODO: We can't support lifetimes on this error object yet (or we'll need some backends inside
     // the GC arena). We're losing info here. How do we fix that?
     #[error("Error running avm1 script: {0}")]
     Avm1Error(String),
 }
 
+impl Error {
+    /// The HTTP status code to report to `onHTTPStatus` handlers for a failed load.
+    pub fn http_status(&self) -> u16 {
+        match self {
+            Error::HttpNotOk(_, status) => *status,
+            _ => 404,
+        }
+    }
+}
+
 impl From<crate::avm1::error::Error<'_>> for Error {
     fn from(error: crate::avm1::error::Error<'_>) -> Self {
         Error::Avm1Error(error.to_string())
@@ -96,12 +120,15 @@ impl<'gc> LoadManager<'gc> {
 
     /// Kick off a movie clip load.
     ///
+    /// The `url` is recorded as the location of the loaded movie.
+    ///
     /// Returns the loader's async process, which you will need to spawn.
     pub fn load_movie_into_clip(
         &mut self,
         player: Weak<Mutex<Player>>,
         target_clip: DisplayObject<'gc>,
-        fetch: OwnedFuture<Vec<u8>, Error>,
+        url: String,
+        fetch: OwnedFuture<Response, Error>,
         target_broadcaster: Option<Object<'gc>>,
     ) -> OwnedFuture<(), Error> {
         let loader = Loader::Movie {
@@ -115,7 +142,7 @@ impl<'gc> LoadManager<'gc> {
         let loader = self.get_loader_mut(handle).unwrap();
         loader.introduce_loader_handle(handle);
 
-        loader.movie_loader(player, fetch)
+        loader.movie_loader(player, url, fetch)
     }
 
     /// Indicates that a movie clip has initialized (ran it's first frame).
@@ -147,7 +174,7 @@ impl<'gc> LoadManager<'gc> {
         &mut self,
         player: Weak<Mutex<Player>>,
         target_object: Object<'gc>,
-        fetch: OwnedFuture<Vec<u8>, Error>,
+        fetch: OwnedFuture<Response, Error>,
     ) -> OwnedFuture<(), Error> {
         let loader = Loader::Form {
             self_handle: None,
@@ -169,7 +196,7 @@ impl<'gc> LoadManager<'gc> {
         player: Weak<Mutex<Player>>,
         target_node: XMLNode<'gc>,
         active_clip: DisplayObject<'gc>,
-        fetch: OwnedFuture<Vec<u8>, Error>,
+        fetch: OwnedFuture<Response, Error>,
     ) -> OwnedFuture<(), Error> {
         let loader = Loader::XML {
             self_handle: None,
@@ -183,6 +210,54 @@ impl<'gc> LoadManager<'gc> {
 
         loader.xml_loader(player, fetch)
     }
+
+    /// Kick off a form data load into an AVM1 `LoadVars` object.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_form_into_load_vars(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_object: Object<'gc>,
+        active_clip: DisplayObject<'gc>,
+        fetch: OwnedFuture<Response, Error>,
+    ) -> OwnedFuture<(), Error> {
+        let loader = Loader::LoadVars {
+            self_handle: None,
+            active_clip,
+            target_object,
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.load_vars_loader(player, fetch)
+    }
+
+    /// Kick off an MP3 load into an AVM1 `Sound` object.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_sound_into_object(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_object: SoundObject<'gc>,
+        active_clip: DisplayObject<'gc>,
+        is_streaming: bool,
+        fetch: OwnedFuture<Response, Error>,
+    ) -> OwnedFuture<(), Error> {
+        let loader = Loader::Sound {
+            self_handle: None,
+            active_clip,
+            target_object,
+            is_streaming,
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.sound_loader(player, fetch)
+    }
 }
 
 impl<'gc> Default for LoadManager<'gc> {
@@ -241,6 +316,34 @@ pub enum Loader<'gc> {
         /// The target node whose contents will be replaced with the parsed XML.
         target_node: XMLNode<'gc>,
     },
+
+    /// Loader that is loading URL-encoded variables into an AVM1 `LoadVars`
+    /// object.
+    LoadVars {
+        /// The handle to refer to this loader instance.
+        self_handle: Option<Handle>,
+
+        /// The active movie clip at the time of load invocation.
+        active_clip: DisplayObject<'gc>,
+
+        /// The `LoadVars` object whose handlers will receive the loaded data.
+        target_object: Object<'gc>,
+    },
+
+    /// Loader that is loading an MP3 file into an AVM1 `Sound` object.
+    Sound {
+        /// The handle to refer to this loader instance.
+        self_handle: Option<Handle>,
+
+        /// The active movie clip at the time of load invocation.
+        active_clip: DisplayObject<'gc>,
+
+        /// The `Sound` object that will receive the loaded sound.
+        target_object: SoundObject<'gc>,
+
+        /// Whether the sound should start playing as soon as it loads.
+        is_streaming: bool,
+    },
 }
 
 unsafe impl<'gc> Collect for Loader<'gc> {
@@ -256,6 +359,22 @@ unsafe impl<'gc> Collect for Loader<'gc> {
             }
             Loader::Form { target_object, .. } => target_object.trace(cc),
             Loader::XML { target_node, .. } => target_node.trace(cc),
+            Loader::LoadVars {
+                active_clip,
+                target_object,
+                ..
+            } => {
+                active_clip.trace(cc);
+                target_object.trace(cc);
+            }
+            Loader::Sound {
+                active_clip,
+                target_object,
+                ..
+            } => {
+                active_clip.trace(cc);
+                target_object.trace(cc);
+            }
         }
     }
 }
@@ -270,6 +389,8 @@ impl<'gc> Loader<'gc> {
             Loader::Movie { self_handle, .. } => *self_handle = Some(handle),
             Loader::Form { self_handle, .. } => *self_handle = Some(handle),
             Loader::XML { self_handle, .. } => *self_handle = Some(handle),
+            Loader::LoadVars { self_handle, .. } => *self_handle = Some(handle),
+            Loader::Sound { self_handle, .. } => *self_handle = Some(handle),
         }
     }
 
@@ -283,7 +404,8 @@ impl<'gc> Loader<'gc> {
     pub fn movie_loader(
         &mut self,
         player: Weak<Mutex<Player>>,
-        fetch: OwnedFuture<Vec<u8>, Error>,
+        url: String,
+        fetch: OwnedFuture<Response, Error>,
     ) -> OwnedFuture<(), Error> {
         let handle = match self {
             Loader::Movie { self_handle, .. } => self_handle.expect("Loader not self-introduced"),
@@ -329,7 +451,10 @@ impl<'gc> Loader<'gc> {
                 },
             )?;
 
-            let data = (fetch.await).and_then(|data| Ok((data.len(), SwfMovie::from_data(&data)?)));
+            let data = (fetch.await).and_then(|response| {
+                let length = response.body.len();
+                Ok((length, SwfMovie::from_data(&response.body, Some(url))?))
+            });
             if let Ok((length, movie)) = data {
                 let movie = Arc::new(movie);
 
@@ -455,7 +580,7 @@ impl<'gc> Loader<'gc> {
     pub fn form_loader(
         &mut self,
         player: Weak<Mutex<Player>>,
-        fetch: OwnedFuture<Vec<u8>, Error>,
+        fetch: OwnedFuture<Response, Error>,
     ) -> OwnedFuture<(), Error> {
         let handle = match self {
             Loader::Form { self_handle, .. } => self_handle.expect("Loader not self-introduced"),
@@ -467,7 +592,7 @@ impl<'gc> Loader<'gc> {
             .expect("Could not upgrade weak reference to player");
 
         Box::pin(async move {
-            let data = fetch.await?;
+            let data = fetch.await?.body;
 
             player.lock().unwrap().update(|avm, uc| {
                 let loader = uc.load_manager.get_loader(handle);
@@ -532,7 +657,7 @@ impl<'gc> Loader<'gc> {
     pub fn xml_loader(
         &mut self,
         player: Weak<Mutex<Player>>,
-        fetch: OwnedFuture<Vec<u8>, Error>,
+        fetch: OwnedFuture<Response, Error>,
     ) -> OwnedFuture<(), Error> {
         let handle = match self {
             Loader::XML { self_handle, .. } => self_handle.expect("Loader not self-introduced"),
@@ -545,8 +670,9 @@ impl<'gc> Loader<'gc> {
 
         Box::pin(async move {
             let data = fetch.await;
-            if let Ok(data) = data {
-                let xmlstring = String::from_utf8(data)?;
+            if let Ok(response) = data {
+                let status = response.status;
+                let xmlstring = String::from_utf8(response.body)?;
 
                 player.lock().expect("Could not lock player!!").update(
                     |avm, uc| -> Result<(), Error> {
@@ -568,7 +694,7 @@ impl<'gc> Loader<'gc> {
                             NEWEST_PLAYER_VERSION,
                             uc,
                             "onHTTPStatus",
-                            &[200.into()],
+                            &[status.into()],
                         );
                         avm.run_stack_till_empty(uc)?;
 
@@ -585,7 +711,9 @@ impl<'gc> Loader<'gc> {
                         Ok(())
                     },
                 )?;
-            } else {
+            } else if let Err(e) = data {
+                log::warn!("XML.load: {}", e);
+                let status = e.http_status();
                 player.lock().expect("Could not lock player!!").update(
                     |avm, uc| -> Result<(), Error> {
                         let (mut node, active_clip) = match uc.load_manager.get_loader(handle) {
@@ -606,7 +734,7 @@ impl<'gc> Loader<'gc> {
                             NEWEST_PLAYER_VERSION,
                             uc,
                             "onHTTPStatus",
-                            &[404.into()],
+                            &[status.into()],
                         );
                         avm.run_stack_till_empty(uc)?;
 
@@ -628,4 +756,191 @@ impl<'gc> Loader<'gc> {
             Ok(())
         })
     }
+
+    pub fn load_vars_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        fetch: OwnedFuture<Response, Error>,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::LoadVars { self_handle, .. } => {
+                self_handle.expect("Loader not self-introduced")
+            }
+            _ => return Box::pin(async { Err(Error::NotLoadVarsLoader) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        Box::pin(async move {
+            let data = fetch.await;
+
+            player
+                .lock()
+                .expect("Could not lock player!!")
+                .update(|avm, uc| -> Result<(), Error> {
+                    let (active_clip, that) = match uc.load_manager.get_loader(handle) {
+                        Some(Loader::LoadVars {
+                            active_clip,
+                            target_object,
+                            ..
+                        }) => (*active_clip, *target_object),
+                        None => return Err(Error::Cancelled),
+                        _ => unreachable!(),
+                    };
+
+                    let (status, args) = match data {
+                        Ok(response) => {
+                            let length = response.body.len() as f64;
+                            that.define_value(
+                                uc.gc_context,
+                                "_bytesLoaded",
+                                length.into(),
+                                Attribute::DontEnum.into(),
+                            );
+                            that.define_value(
+                                uc.gc_context,
+                                "_bytesTotal",
+                                length.into(),
+                                Attribute::DontEnum.into(),
+                            );
+                            let text = String::from_utf8_lossy(&response.body).into_owned();
+                            (response.status, vec![text.into()])
+                        }
+                        Err(e) => {
+                            log::warn!("LoadVars: {}", e);
+                            (e.http_status(), vec![])
+                        }
+                    };
+
+                    avm.insert_stack_frame_for_method(
+                        active_clip,
+                        that,
+                        NEWEST_PLAYER_VERSION,
+                        uc,
+                        "onHTTPStatus",
+                        &[status.into()],
+                    );
+                    avm.run_stack_till_empty(uc)?;
+
+                    avm.insert_stack_frame_for_method(
+                        active_clip,
+                        that,
+                        NEWEST_PLAYER_VERSION,
+                        uc,
+                        "onData",
+                        &args,
+                    );
+                    avm.run_stack_till_empty(uc)?;
+
+                    Ok(())
+                })
+        })
+    }
+
+    pub fn sound_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        fetch: OwnedFuture<Response, Error>,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::Sound { self_handle, .. } => self_handle.expect("Loader not self-introduced"),
+            _ => return Box::pin(async { Err(Error::NotSoundLoader) }),
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        Box::pin(async move {
+            let data = fetch.await;
+
+            player
+                .lock()
+                .expect("Could not lock player!!")
+                .update(|avm, uc| -> Result<(), Error> {
+                    let (active_clip, sound_object, is_streaming) =
+                        match uc.load_manager.get_loader(handle) {
+                            Some(Loader::Sound {
+                                active_clip,
+                                target_object,
+                                is_streaming,
+                                ..
+                            }) => (*active_clip, *target_object, *is_streaming),
+                            None => return Err(Error::Cancelled),
+                            _ => unreachable!(),
+                        };
+
+                    let sound = data.ok().and_then(|response| {
+                        let data = response.body;
+                        let sound = uc.audio.register_mp3(&data);
+                        if let Err(e) = &sound {
+                            log::warn!("Sound.loadSound: Unable to decode MP3: {}", e);
+                        }
+                        Some((sound.ok()?, mp3_file::read_id3(&data)))
+                    });
+
+                    let success = if let Some((sound, tags)) = sound {
+                        sound_object.set_sound(uc.gc_context, Some(sound));
+                        sound_object.set_duration(
+                            uc.gc_context,
+                            uc.audio.get_sound_duration(sound).unwrap_or(0),
+                        );
+                        sound_object.set_position(uc.gc_context, 0);
+
+                        if !tags.is_empty() {
+                            let id3 =
+                                ScriptObject::object(uc.gc_context, Some(avm.prototypes().object));
+                            for (name, value) in tags {
+                                id3.set(&name, value.into(), avm, uc)?;
+                            }
+                            sound_object.set_id3(uc.gc_context, Some(id3.into()));
+
+                            avm.insert_stack_frame_for_method(
+                                active_clip,
+                                sound_object.into(),
+                                NEWEST_PLAYER_VERSION,
+                                uc,
+                                "onID3",
+                                &[],
+                            );
+                            avm.run_stack_till_empty(uc)?;
+                        }
+
+                        if is_streaming {
+                            let sound_instance = uc.audio_manager.start_sound(
+                                uc.audio,
+                                sound,
+                                &swf::SoundInfo {
+                                    event: swf::SoundEvent::Start,
+                                    in_sample: None,
+                                    out_sample: None,
+                                    num_loops: 1,
+                                    envelope: None,
+                                },
+                                sound_object.owner(),
+                            );
+                            sound_object.set_sound_instance(uc.gc_context, sound_instance.ok());
+                        }
+
+                        true
+                    } else {
+                        false
+                    };
+
+                    avm.insert_stack_frame_for_method(
+                        active_clip,
+                        sound_object.into(),
+                        NEWEST_PLAYER_VERSION,
+                        uc,
+                        "onLoad",
+                        &[success.into()],
+                    );
+                    avm.run_stack_till_empty(uc)?;
+
+                    Ok(())
+                })
+        })
+    }
 }
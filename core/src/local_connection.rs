@@ -0,0 +1,256 @@
+//! Connections made by the AVM1 `LocalConnection` class.
+
+use crate::avm1::globals::shared_object::deserialize_value;
+use crate::avm1::{Avm1, Object, ScriptObject, TObject, Value};
+use crate::backend::local_connection::{
+    LocalConnectionBackend, LocalConnectionEvent, LocalConnectionMessage, MessageId,
+};
+use crate::context::UpdateContext;
+use crate::tag_utils::SwfMovie;
+use enumset::EnumSet;
+use gc_arena::{Collect, CollectionContext};
+use url::{Host, Url};
+
+/// Manages the `LocalConnection` objects that are listening for messages, and the messages
+/// they sent that are waiting for a status.
+pub struct LocalConnections<'gc> {
+    /// The full name each listening object is connected with.
+    connections: Vec<(String, Object<'gc>)>,
+
+    /// The object that sent each message that is waiting for a status.
+    pending: Vec<(MessageId, Object<'gc>)>,
+}
+
+unsafe impl<'gc> Collect for LocalConnections<'gc> {
+    fn trace(&self, cc: CollectionContext) {
+        for (_, object) in &self.connections {
+            object.trace(cc);
+        }
+        for (_, object) in &self.pending {
+            object.trace(cc);
+        }
+    }
+}
+
+impl<'gc> LocalConnections<'gc> {
+    pub fn new() -> Self {
+        Self {
+            connections: Vec::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Starts listening on a connection name.
+    ///
+    /// Returns `false` if the object is already listening, or the name is taken.
+    pub fn connect(
+        &mut self,
+        backend: &mut dyn LocalConnectionBackend,
+        object: Object<'gc>,
+        name: String,
+    ) -> bool {
+        if self
+            .connections
+            .iter()
+            .any(|(_, o)| Object::ptr_eq(*o, object))
+        {
+            return false;
+        }
+        if !backend.connect(&name) {
+            return false;
+        }
+        self.connections.push((name, object));
+        true
+    }
+
+    /// Stops an object from listening, if it is.
+    pub fn close(&mut self, backend: &mut dyn LocalConnectionBackend, object: Object<'gc>) {
+        if let Some(index) = self
+            .connections
+            .iter()
+            .position(|(_, o)| Object::ptr_eq(*o, object))
+        {
+            let (name, _) = self.connections.remove(index);
+            backend.close(&name);
+        }
+    }
+
+    /// Records a sent message, so that the object that sent it receives its status.
+    pub fn add_pending(&mut self, id: MessageId, object: Object<'gc>) {
+        self.pending.push((id, object));
+    }
+
+    /// Polls the local connection backend, delivering incoming messages and statuses.
+    pub fn update_connections(avm: &mut Avm1<'gc>, context: &mut UpdateContext<'_, 'gc, '_>) {
+        for event in context.local_connection.poll() {
+            match event {
+                LocalConnectionEvent::Message(message) => {
+                    let success = Self::receive(avm, context, &message);
+                    context.local_connection.respond(message.id, success);
+                }
+                LocalConnectionEvent::Status(id, success) => {
+                    let pending = &mut context.local_connections.pending;
+                    let object = match pending.iter().position(|(i, _)| *i == id) {
+                        Some(index) => pending.remove(index).1,
+                        None => continue,
+                    };
+                    let level = if success { "status" } else { "error" };
+                    avm.run_in_root_frame(context, |avm, context| {
+                        let info =
+                            ScriptObject::object(context.gc_context, Some(avm.prototypes().object));
+                        info.define_value(
+                            context.gc_context,
+                            "level",
+                            level.into(),
+                            EnumSet::empty(),
+                        );
+                        let _ = object.call_method("onStatus", &[info.into()], avm, context);
+                    });
+                }
+            }
+        }
+    }
+
+    /// Calls the method of a message on the object listening for it.
+    ///
+    /// Returns whether the message was accepted.
+    fn receive(
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        message: &LocalConnectionMessage,
+    ) -> bool {
+        let object = match context
+            .local_connections
+            .connections
+            .iter()
+            .find(|(name, _)| *name == message.connection_name)
+        {
+            Some((_, object)) => *object,
+            None => return false,
+        };
+
+        let receiver_domain = movie_domain(Some(context.swf.as_ref()));
+        avm.run_in_root_frame(context, |avm, context| {
+            // Messages from other domains must be allowed by the receiver's `allowDomain`.
+            if message.sender_domain != receiver_domain {
+                let allowed = match object.get("allowDomain", avm, context) {
+                    Ok(Value::Object(_)) => object
+                        .call_method(
+                            "allowDomain",
+                            &[message.sender_domain.clone().into()],
+                            avm,
+                            context,
+                        )
+                        .map(|allowed| allowed.as_bool(avm.current_swf_version()))
+                        .unwrap_or(false),
+                    _ => false,
+                };
+                if !allowed {
+                    log::warn!(
+                        "LocalConnection {} refused a message from {}",
+                        message.connection_name,
+                        message.sender_domain
+                    );
+                    return false;
+                }
+            }
+
+            let mut objects = Vec::new();
+            let mut args = Vec::with_capacity(message.args.len());
+            for arg in &message.args {
+                match deserialize_value(avm, context, arg, &mut objects) {
+                    Ok(arg) => args.push(arg),
+                    Err(_) => return false,
+                }
+            }
+            let _ = object.call_method(&message.method_name, &args, avm, context);
+            true
+        })
+        .unwrap_or(false)
+    }
+}
+
+impl<'gc> Default for LocalConnections<'gc> {
+    fn default() -> Self {
+        LocalConnections::new()
+    }
+}
+
+/// Returns the domain that identifies a movie to `LocalConnection`.
+pub fn movie_domain(movie: Option<&SwfMovie>) -> String {
+    match movie {
+        Some(movie) => url_domain(movie.url(), movie.header().version),
+        None => "localhost".to_string(),
+    }
+}
+
+/// Returns the domain of a movie loaded from the given URL.
+///
+/// This is the host the movie was loaded from, or `localhost` for local movies. Movies
+/// before SWF 7 use the superdomain instead, leaving only the last two parts of the host name.
+fn url_domain(url: Option<&str>, swf_version: u8) -> String {
+    match url
+        .and_then(|url| Url::parse(url).ok())
+        .as_ref()
+        .and_then(Url::host)
+    {
+        Some(Host::Domain(domain)) if swf_version < 7 => {
+            let parts: Vec<&str> = domain.split('.').collect();
+            parts[parts.len().saturating_sub(2)..].join(".")
+        }
+        Some(host) => host.to_string(),
+        None => "localhost".to_string(),
+    }
+}
+
+/// Returns the full name of a connection.
+///
+/// Names starting with an underscore are shared between all domains, and names that already
+/// contain a domain are left as is. Other names are prefixed with the domain of the movie.
+/// Connection names are case-insensitive.
+pub fn full_connection_name(name: &str, domain: &str) -> String {
+    if name.starts_with('_') || name.contains(':') {
+        name.to_lowercase()
+    } else {
+        format!("{}:{}", domain, name).to_lowercase()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn connection_names() {
+        assert_eq!(
+            full_connection_name("Content", "example.com"),
+            "example.com:content"
+        );
+        assert_eq!(full_connection_name("_Status", "example.com"), "_status");
+        assert_eq!(
+            full_connection_name("other.com:content", "example.com"),
+            "other.com:content"
+        );
+    }
+
+    #[test]
+    fn movie_domains() {
+        assert_eq!(url_domain(None, 8), "localhost");
+        assert_eq!(
+            url_domain(Some("file:///home/user/movie.swf"), 8),
+            "localhost"
+        );
+        assert_eq!(
+            url_domain(Some("http://www.example.com/movie.swf"), 8),
+            "www.example.com"
+        );
+        assert_eq!(
+            url_domain(Some("http://www.example.com/movie.swf"), 6),
+            "example.com"
+        );
+        assert_eq!(
+            url_domain(Some("http://127.0.0.1:8000/movie.swf"), 6),
+            "127.0.0.1"
+        );
+    }
+}
@@ -3,19 +3,34 @@ use crate::avm1::globals::system::SystemProperties;
 use crate::avm1::listeners::SystemListener;
 use crate::avm1::object::Object;
 use crate::avm1::{Activation, Avm1, TObject, Value};
+use crate::avm2::Avm2;
 use crate::backend::input::{InputBackend, MouseCursor};
+use crate::backend::local_connection::LocalConnectionBackend;
+use crate::backend::socket::SocketBackend;
 use crate::backend::storage::StorageBackend;
+use crate::backend::video::VideoBackend;
 use crate::backend::{
-    audio::AudioBackend, navigator::NavigatorBackend, render::Letterbox, render::RenderBackend,
+    audio::{AudioBackend, AudioManager},
+    navigator::NavigatorBackend,
+    render::Letterbox,
+    render::RenderBackend,
 };
 use crate::context::{ActionQueue, ActionType, RenderContext, UpdateContext};
 use crate::display_object::{EditText, MorphShape, MovieClip};
 use crate::events::{ButtonKeyCode, ClipEvent, ClipEventResult, KeyCode, PlayerEvent};
+use crate::external::{ExternalInterface, ExternalInterfaceProvider, Value as ExternalValue};
+use crate::focus_tracker::FocusTracker;
+use crate::keyboard::KeyboardState;
 use crate::library::Library;
 use crate::loader::LoadManager;
+use crate::local_connection::LocalConnections;
+use crate::policy::PolicyManager;
 use crate::prelude::*;
+use crate::stage::{StageProperties, StageScaleMode};
 use crate::tag_utils::SwfMovie;
+use crate::timer::Timers;
 use crate::transform::TransformStack;
+use crate::xml_socket::XMLSockets;
 use enumset::EnumSet;
 use gc_arena::{make_arena, ArenaParameters, Collect, GcCell};
 use log::info;
@@ -24,6 +39,7 @@ use std::collections::{BTreeMap, HashMap};
 use std::convert::TryFrom;
 use std::ops::DerefMut;
 use std::sync::{Arc, Mutex, Weak};
+use swf::BlendMode;
 
 pub static DEVICE_FONT_TAG: &[u8] = include_bytes!("../assets/noto-sans-definefont3.bin");
 
@@ -31,6 +47,9 @@ pub static DEVICE_FONT_TAG: &[u8] = include_bytes!("../assets/noto-sans-definefo
 /// `player_version`.
 pub const NEWEST_PLAYER_VERSION: u8 = 32;
 
+/// How often the caret of a focused text field blinks, in milliseconds.
+const CARET_BLINK_INTERVAL: f64 = 500.0;
+
 #[derive(Collect)]
 #[collect(no_drop)]
 struct GcRoot<'gc>(GcCell<'gc, GcRootData<'gc>>);
@@ -53,6 +72,7 @@ struct GcRootData<'gc> {
     drag_object: Option<DragObject<'gc>>,
 
     avm: Avm1<'gc>,
+    avm2: Avm2<'gc>,
     action_queue: ActionQueue<'gc>,
 
     /// Object which manages asynchronous processes that need to interact with
@@ -63,6 +83,24 @@ struct GcRootData<'gc> {
 
     /// Text fields with unbound variable bindings.
     unbound_text_fields: Vec<EditText<'gc>>,
+
+    /// The display object that currently has keyboard focus.
+    focus_tracker: FocusTracker<'gc>,
+
+    /// Sounds currently playing, along with the display objects that own them.
+    audio_manager: AudioManager<'gc>,
+
+    /// Timers created by `setInterval` and `setTimeout`.
+    timers: Timers<'gc>,
+
+    /// The connections of `XMLSocket` objects.
+    xml_sockets: XMLSockets<'gc>,
+
+    /// The `LocalConnection` objects that are listening or waiting for a status.
+    local_connections: LocalConnections<'gc>,
+
+    /// The callbacks registered with `ExternalInterface`, and the hosts they are exposed to.
+    external_interface: ExternalInterface<'gc>,
 }
 
 impl<'gc> GcRootData<'gc> {
@@ -76,20 +114,32 @@ impl<'gc> GcRootData<'gc> {
         &mut Library<'gc>,
         &mut ActionQueue<'gc>,
         &mut Avm1<'gc>,
+        &mut Avm2<'gc>,
         &mut Option<DragObject<'gc>>,
         &mut LoadManager<'gc>,
         &mut HashMap<String, Object<'gc>>,
         &mut Vec<EditText<'gc>>,
+        &mut AudioManager<'gc>,
+        &mut Timers<'gc>,
+        &mut XMLSockets<'gc>,
+        &mut LocalConnections<'gc>,
+        &mut ExternalInterface<'gc>,
     ) {
         (
             &mut self.levels,
             &mut self.library,
             &mut self.action_queue,
             &mut self.avm,
+            &mut self.avm2,
             &mut self.drag_object,
             &mut self.load_manager,
             &mut self.shared_objects,
             &mut self.unbound_text_fields,
+            &mut self.audio_manager,
+            &mut self.timers,
+            &mut self.xml_sockets,
+            &mut self.local_connections,
+            &mut self.external_interface,
         )
     }
 }
@@ -102,6 +152,9 @@ type Navigator = Box<dyn NavigatorBackend>;
 type Renderer = Box<dyn RenderBackend>;
 type Input = Box<dyn InputBackend>;
 type Storage = Box<dyn StorageBackend>;
+type Video = Box<dyn VideoBackend>;
+type Sockets = Box<dyn SocketBackend>;
+type LocalConnection = Box<dyn LocalConnectionBackend>;
 
 pub struct Player {
     /// The version of the player we're emulating.
@@ -131,6 +184,12 @@ pub struct Player {
 
     storage: Storage,
 
+    video: Video,
+
+    sockets: Sockets,
+
+    local_connection: LocalConnection,
+
     rng: SmallRng,
 
     gc_arena: GcArena,
@@ -140,10 +199,14 @@ pub struct Player {
     frame_accumulator: f64,
     global_time: u64,
 
-    viewport_width: u32,
-    viewport_height: u32,
-    movie_width: u32,
-    movie_height: u32,
+    /// Time elapsed since the text caret last blinked, in milliseconds.
+    caret_blink_accumulator: f64,
+
+    /// The keyboard state that is not tracked by the input backend.
+    keyboard: KeyboardState,
+
+    /// The sizes of the movie and viewport, and how the movie is scaled.
+    stage: StageProperties,
     letterbox: Letterbox,
 
     mouse_pos: (Twips, Twips),
@@ -154,6 +217,9 @@ pub struct Player {
 
     system: SystemProperties,
 
+    /// The cross-domain policy files and domain permissions known to the player.
+    policies: PolicyManager,
+
     /// The current instance ID. Used to generate default `instanceN` names.
     instance_counter: i32,
 
@@ -166,6 +232,7 @@ pub struct Player {
 }
 
 impl Player {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         mut renderer: Renderer,
         audio: Audio,
@@ -173,6 +240,9 @@ impl Player {
         input: Input,
         movie: SwfMovie,
         storage: Storage,
+        video: Video,
+        sockets: Sockets,
+        local_connection: LocalConnection,
     ) -> Result<Arc<Mutex<Self>>, Error> {
         let movie = Arc::new(movie);
 
@@ -183,9 +253,6 @@ impl Player {
             movie.header().stage_size.y_max
         );
 
-        let movie_width = movie.width();
-        let movie_height = movie.height();
-
         let mut player = Player {
             player_version: NEWEST_PLAYER_VERSION,
 
@@ -231,10 +298,17 @@ impl Player {
                         mouse_hovered_object: None,
                         drag_object: None,
                         avm: Avm1::new(gc_context, NEWEST_PLAYER_VERSION),
+                        avm2: Avm2::new(gc_context),
                         action_queue: ActionQueue::new(),
                         load_manager: LoadManager::new(),
                         shared_objects: HashMap::new(),
                         unbound_text_fields: Vec::new(),
+                        focus_tracker: FocusTracker::new(gc_context),
+                        audio_manager: AudioManager::new(),
+                        timers: Timers::new(),
+                        xml_sockets: XMLSockets::new(),
+                        local_connections: LocalConnections::new(),
+                        external_interface: ExternalInterface::new(),
                     },
                 ))
             }),
@@ -242,11 +316,10 @@ impl Player {
             frame_rate: movie.header().frame_rate.into(),
             frame_accumulator: 0.0,
             global_time: 0,
+            caret_blink_accumulator: 0.0,
+            keyboard: KeyboardState::new(),
 
-            movie_width,
-            movie_height,
-            viewport_width: movie_width,
-            viewport_height: movie_height,
+            stage: StageProperties::new(movie.width(), movie.height()),
             letterbox: Letterbox::None,
 
             mouse_pos: (Twips::new(0), Twips::new(0)),
@@ -259,8 +332,12 @@ impl Player {
             input,
             self_reference: None,
             system: SystemProperties::default(),
+            policies: PolicyManager::new(),
             instance_counter: 0,
             storage,
+            video,
+            sockets,
+            local_connection,
         };
 
         player.mutate_with_update_context(|avm, context| {
@@ -317,6 +394,15 @@ impl Player {
                 self.frame_accumulator = 0.0;
             }
 
+            // Timers fire between frames, after any frames due this tick have run.
+            self.update(|avm, context| Timers::update_timers(avm, context));
+
+            self.update(|avm, context| XMLSockets::update_sockets(avm, context));
+
+            self.update(|avm, context| LocalConnections::update_connections(avm, context));
+
+            self.update_caret_blink(dt);
+
             self.audio.tick();
         }
     }
@@ -352,21 +438,63 @@ impl Player {
     }
 
     pub fn movie_width(&self) -> u32 {
-        self.movie_width
+        self.stage.movie_size().0
     }
 
     pub fn movie_height(&self) -> u32 {
-        self.movie_height
+        self.stage.movie_size().1
     }
 
     pub fn viewport_dimensions(&self) -> (u32, u32) {
-        (self.viewport_width, self.viewport_height)
+        self.stage.viewport_size()
     }
 
+    /// Resize the viewport. `Stage` listeners receive `onResize` if the movie
+    /// is not scaled, as the stage is resized with it.
     pub fn set_viewport_dimensions(&mut self, width: u32, height: u32) {
-        self.viewport_width = width;
-        self.viewport_height = height;
+        let is_resized = self.stage.viewport_size() != (width, height);
+        self.stage.set_viewport_size(width, height);
+        self.build_matrices();
+
+        if is_resized && self.stage.scale_mode == StageScaleMode::NoScale {
+            self.update(|_avm, context| {
+                if let Some(root) = context.levels.get(&0).copied() {
+                    context.action_queue.queue_actions(
+                        root,
+                        ActionType::NotifyListeners {
+                            listener: SystemListener::Stage,
+                            method: "onResize",
+                            args: vec![],
+                        },
+                        false,
+                    );
+                }
+            });
+        }
+    }
+
+    pub fn scale_mode(&self) -> StageScaleMode {
+        self.stage.scale_mode
+    }
+
+    /// Set how the movie is scaled to fit the viewport, until the movie sets
+    /// `Stage.scaleMode` itself.
+    pub fn set_scale_mode(&mut self, scale_mode: StageScaleMode) {
+        self.stage.scale_mode = scale_mode;
         self.build_matrices();
+        self.needs_render = true;
+    }
+
+    pub fn letterbox(&self) -> bool {
+        self.stage.letterbox
+    }
+
+    /// Set whether black bars are drawn over the margins around the movie,
+    /// hiding any content outside of the stage.
+    pub fn set_letterbox(&mut self, letterbox: bool) {
+        self.stage.letterbox = letterbox;
+        self.build_matrices();
+        self.needs_render = true;
     }
 
     pub fn handle_event(&mut self, event: PlayerEvent) {
@@ -402,6 +530,16 @@ impl Player {
             }
         }
 
+        // Track the character code and lock keys for `Key.getAscii` and `Key.isToggled`.
+        match event {
+            PlayerEvent::KeyDown { key_code } => {
+                let is_shift_down = self.input.is_key_down(KeyCode::Shift);
+                self.keyboard.key_down(key_code, is_shift_down);
+            }
+            PlayerEvent::TextInput { codepoint } => self.keyboard.text_input(codepoint),
+            _ => (),
+        }
+
         // Update mouse position from mouse events.
         if let PlayerEvent::MouseMove { x, y }
         | PlayerEvent::MouseDown { x, y }
@@ -450,17 +588,32 @@ impl Player {
             });
         }
 
-        // Propagte clip events.
-        let (clip_event, mouse_event_name) = match event {
-            PlayerEvent::KeyDown { .. } => (Some(ClipEvent::KeyDown), Some("onKeyDown")),
-            PlayerEvent::KeyUp { .. } => (Some(ClipEvent::KeyUp), Some("onKeyUp")),
-            PlayerEvent::MouseMove { .. } => (Some(ClipEvent::MouseMove), Some("onMouseMove")),
-            PlayerEvent::MouseUp { .. } => (Some(ClipEvent::MouseUp), Some("onMouseUp")),
-            PlayerEvent::MouseDown { .. } => (Some(ClipEvent::MouseDown), Some("onMouseDown")),
+        // Propagte clip events, and broadcast them to `Key` or `Mouse` listeners.
+        let (clip_event, listener_event) = match event {
+            PlayerEvent::KeyDown { .. } => (
+                Some(ClipEvent::KeyDown),
+                Some((SystemListener::Key, "onKeyDown")),
+            ),
+            PlayerEvent::KeyUp { .. } => (
+                Some(ClipEvent::KeyUp),
+                Some((SystemListener::Key, "onKeyUp")),
+            ),
+            PlayerEvent::MouseMove { .. } => (
+                Some(ClipEvent::MouseMove),
+                Some((SystemListener::Mouse, "onMouseMove")),
+            ),
+            PlayerEvent::MouseUp { .. } => (
+                Some(ClipEvent::MouseUp),
+                Some((SystemListener::Mouse, "onMouseUp")),
+            ),
+            PlayerEvent::MouseDown { .. } => (
+                Some(ClipEvent::MouseDown),
+                Some((SystemListener::Mouse, "onMouseDown")),
+            ),
             _ => (None, None),
         };
 
-        if clip_event.is_some() || mouse_event_name.is_some() {
+        if clip_event.is_some() || listener_event.is_some() {
             self.mutate_with_update_context(|avm, context| {
                 let levels: Vec<DisplayObject<'_>> = context.levels.values().copied().collect();
 
@@ -470,12 +623,12 @@ impl Player {
                     }
                 }
 
-                if let Some(mouse_event_name) = mouse_event_name {
+                if let Some((listener, method)) = listener_event {
                     context.action_queue.queue_actions(
                         *context.levels.get(&0).expect("root level"),
                         ActionType::NotifyListeners {
-                            listener: SystemListener::Mouse,
-                            method: mouse_event_name,
+                            listener,
+                            method,
                             args: vec![],
                         },
                         false,
@@ -492,6 +645,17 @@ impl Player {
                 }
             }
 
+            // Removed objects lose focus.
+            let focus_tracker = context.focus_tracker;
+            if focus_tracker.get().map(|focus| focus.removed()) == Some(true) {
+                focus_tracker.set(None, context);
+            }
+
+            let focused_text_field = context
+                .focus_tracker
+                .get()
+                .and_then(|focused| focused.as_edit_text());
+
             match event {
                 PlayerEvent::MouseDown { .. } => {
                     is_mouse_down = true;
@@ -499,6 +663,94 @@ impl Player {
                     if let Some(node) = context.mouse_hovered_object {
                         node.handle_clip_event(avm, context, ClipEvent::Press);
                     }
+
+                    // Clicking a text field focuses it and moves the caret;
+                    // clicking anywhere else removes focus.
+                    let text_field = context
+                        .mouse_hovered_object
+                        .and_then(|node| node.as_edit_text());
+                    let focus_tracker = context.focus_tracker;
+                    focus_tracker.set(text_field.map(|text_field| text_field.into()), context);
+                    if let Some(text_field) = text_field {
+                        let is_extending = context.input.is_key_down(KeyCode::Shift);
+                        text_field.select_at_position(
+                            context.gc_context,
+                            *context.mouse_position,
+                            is_extending,
+                        );
+                    }
+                }
+
+                // Dragging across the focused text field selects text.
+                PlayerEvent::MouseMove { .. } if is_mouse_down => {
+                    if let Some(text_field) = focused_text_field {
+                        text_field.select_at_position(
+                            context.gc_context,
+                            *context.mouse_position,
+                            true,
+                        );
+                        needs_render = true;
+                    }
+                }
+
+                // Tab moves focus through the tab order, backwards with Shift.
+                PlayerEvent::KeyDown {
+                    key_code: KeyCode::Tab,
+                } => {
+                    let reverse = context.input.is_key_down(KeyCode::Shift);
+                    let focus_tracker = context.focus_tracker;
+                    focus_tracker.cycle(avm, context, reverse);
+                    needs_render = true;
+                }
+
+                PlayerEvent::KeyDown { key_code } => {
+                    if let Some(text_field) = focused_text_field {
+                        text_field.key_down(key_code, avm, context);
+                        needs_render = true;
+                    } else if key_code == KeyCode::Return || key_code == KeyCode::Space {
+                        // Enter and Space click the focused button.
+                        let focus = context.focus_tracker.get();
+                        if let Some(focus) = focus.filter(|focus| focus.is_focusable(avm, context))
+                        {
+                            focus.handle_clip_event(avm, context, ClipEvent::Press);
+                            focus.handle_clip_event(avm, context, ClipEvent::Release);
+                            needs_render = true;
+                        }
+                    }
+                }
+
+                PlayerEvent::TextInput { codepoint } => {
+                    if let Some(text_field) = focused_text_field {
+                        text_field.text_input(codepoint, avm, context);
+                        needs_render = true;
+                    }
+                }
+
+                // The wheel notifies `Mouse` listeners of the object under the mouse,
+                // and scrolls the focused text field.
+                PlayerEvent::MouseWheel { delta } => {
+                    let delta = delta.lines().round();
+                    let target = context
+                        .mouse_hovered_object
+                        .map(|node| node.object())
+                        .unwrap_or(Value::Undefined);
+                    context.action_queue.queue_actions(
+                        *context.levels.get(&0).expect("root level"),
+                        ActionType::NotifyListeners {
+                            listener: SystemListener::Mouse,
+                            method: "onMouseWheel",
+                            args: vec![delta.into(), target],
+                        },
+                        false,
+                    );
+
+                    if let Some(text_field) = focused_text_field.filter(|text_field| {
+                        text_field.is_multiline() && text_field.mouse_wheel_enabled()
+                    }) {
+                        let scroll = (text_field.scroll() as f64 - delta).max(1.0);
+                        text_field.set_scroll(context.gc_context, scroll as usize);
+                        needs_render = true;
+                    }
                 }
 
                 PlayerEvent::MouseUp { .. } => {
@@ -518,6 +770,32 @@ impl Player {
         self.needs_render = needs_render;
     }
 
+    /// Blink the caret of the focused text field, if any.
+    fn update_caret_blink(&mut self, dt: f64) {
+        self.caret_blink_accumulator += dt;
+        if self.caret_blink_accumulator < CARET_BLINK_INTERVAL {
+            return;
+        }
+        self.caret_blink_accumulator %= CARET_BLINK_INTERVAL;
+
+        let did_blink = self.mutate_with_update_context(|_avm, context| {
+            if let Some(text_field) = context
+                .focus_tracker
+                .get()
+                .and_then(|focused| focused.as_edit_text())
+            {
+                text_field.blink_caret(context.gc_context);
+                true
+            } else {
+                false
+            }
+        });
+
+        if did_blink {
+            self.needs_render = true;
+        }
+    }
+
     /// Update dragged object, if any.
     fn update_drag(&mut self) {
         let mouse_pos = self.mouse_pos;
@@ -581,7 +859,11 @@ impl Player {
                 // RollOver on new node.
                 new_cursor = MouseCursor::Arrow;
                 if let Some(node) = new_hovered {
-                    new_cursor = MouseCursor::Hand;
+                    new_cursor = if node.as_edit_text().is_some() {
+                        MouseCursor::IBeam
+                    } else {
+                        MouseCursor::Hand
+                    };
                     node.handle_clip_event(avm, context, ClipEvent::RollOver);
                 }
 
@@ -615,6 +897,13 @@ impl Player {
                 .unwrap()
                 .preload(avm, context, &mut morph_shapes);
 
+            // The root's document class is only known once its `SymbolClass`
+            // tag has been preloaded.
+            let root_clip = root.as_movie_clip().unwrap();
+            if root_clip.avm2_object().is_none() {
+                root_clip.construct_avm2_object(context, root);
+            }
+
             // Finalize morph shapes.
             for (id, static_data) in morph_shapes {
                 let morph_shape = MorphShape::new(context.gc_context, static_data);
@@ -642,13 +931,15 @@ impl Player {
     }
 
     pub fn render(&mut self) {
+        let (viewport_width, viewport_height) = self.stage.viewport_size();
         let view_bounds = BoundingBox {
             x_min: Twips::new(0),
             y_min: Twips::new(0),
-            x_max: Twips::from_pixels(self.movie_width.into()),
-            y_max: Twips::from_pixels(self.movie_height.into()),
+            x_max: Twips::from_pixels(viewport_width.into()),
+            y_max: Twips::from_pixels(viewport_height.into()),
             valid: true,
-        };
+        }
+        .transform(&self.inverse_view_matrix);
 
         self.renderer.begin_frame(self.background_color.clone());
 
@@ -658,7 +949,7 @@ impl Player {
             matrix: self.view_matrix,
             ..Default::default()
         });
-        self.gc_arena.mutate(|_gc_context, gc_root| {
+        self.gc_arena.mutate(|gc_context, gc_root| {
             let root_data = gc_root.0.read();
             let mut render_context = RenderContext {
                 renderer: renderer.deref_mut(),
@@ -666,11 +957,16 @@ impl Player {
                 transform_stack,
                 view_bounds,
                 clip_depth_stack: vec![],
+                blend_mode: BlendMode::Normal,
             };
 
             for (_depth, level) in root_data.levels.iter() {
                 level.render(&mut render_context);
             }
+
+            root_data
+                .focus_tracker
+                .render_highlight(gc_context, &mut render_context);
         });
         transform_stack.pop();
 
@@ -812,6 +1108,31 @@ impl Player {
                         &args,
                     );
                 }
+
+                // AVM2 frame script or event handler
+                ActionType::Avm2Callable {
+                    callable,
+                    receiver,
+                    args,
+                } => {
+                    if let Err(e) = Avm2::run_callable(context, callable, receiver, &args) {
+                        log::error!("Unhandled AVM2 exception: {}", e);
+                    }
+                }
+
+                // AVM2 event, such as `enterFrame`
+                ActionType::Avm2Event { target, event_type } => {
+                    if let Err(e) = Avm2::dispatch_event(context, target, event_type) {
+                        log::error!("Unhandled AVM2 exception in {} handler: {}", event_type, e);
+                    }
+                }
+
+                // Goto requested by AVM2 code
+                ActionType::Avm2Goto { frame, stop } => {
+                    if let Some(clip) = actions.clip.as_movie_clip() {
+                        clip.goto_frame(avm, context, frame, stop);
+                    }
+                }
             }
             // Execute the stack frame (if any).
             let _ = avm.run_stack_till_empty(context);
@@ -820,39 +1141,11 @@ impl Player {
 
     fn build_matrices(&mut self) {
         // Create  view matrix to scale stage into viewport area.
-        let (movie_width, movie_height) = (self.movie_width as f32, self.movie_height as f32);
-        let (viewport_width, viewport_height) =
-            (self.viewport_width as f32, self.viewport_height as f32);
-        let movie_aspect = movie_width / movie_height;
-        let viewport_aspect = viewport_width / viewport_height;
-        let (scale, margin_width, margin_height) = if viewport_aspect > movie_aspect {
-            let scale = viewport_height / movie_height;
-            (scale, (viewport_width - movie_width * scale) / 2.0, 0.0)
-        } else {
-            let scale = viewport_width / movie_width;
-            (scale, 0.0, (viewport_height - movie_height * scale) / 2.0)
-        };
-        self.view_matrix = Matrix {
-            a: scale,
-            b: 0.0,
-            c: 0.0,
-            d: scale,
-            tx: Twips::from_pixels(margin_width.into()),
-            ty: Twips::from_pixels(margin_height.into()),
-        };
+        let (view_matrix, letterbox) = self.stage.view_transform();
+        self.view_matrix = view_matrix;
         self.inverse_view_matrix = self.view_matrix;
         self.inverse_view_matrix.invert();
-
-        // Calculate letterbox dimensions.
-        // TODO: Letterbox should be an option; the original Flash Player defaults to showing content
-        // in the extra margins.
-        self.letterbox = if margin_width > 0.0 {
-            Letterbox::Pillarbox(margin_width)
-        } else if margin_height > 0.0 {
-            Letterbox::Letterbox(margin_height)
-        } else {
-            Letterbox::None
-        };
+        self.letterbox = letterbox;
     }
 
     /// Runs the closure `f` with an `UpdateContext`.
@@ -861,6 +1154,8 @@ impl Player {
     where
         F: for<'a, 'gc> FnOnce(&mut Avm1<'gc>, &mut UpdateContext<'a, 'gc, '_>) -> R,
     {
+        let old_stage = self.stage.clone();
+
         // We have to do this piecewise borrowing of fields before the closure to avoid
         // completely borrowing `self`.
         let (
@@ -872,14 +1167,18 @@ impl Player {
             audio,
             navigator,
             input,
+            keyboard,
             rng,
             mouse_position,
-            stage_width,
-            stage_height,
+            stage,
             player,
             system_properties,
+            policies,
             instance_counter,
             storage,
+            video,
+            sockets,
+            local_connection,
         ) = (
             self.player_version,
             self.global_time,
@@ -889,28 +1188,39 @@ impl Player {
             self.audio.deref_mut(),
             self.navigator.deref_mut(),
             self.input.deref_mut(),
+            &mut self.keyboard,
             &mut self.rng,
             &self.mouse_pos,
-            Twips::from_pixels(self.movie_width.into()),
-            Twips::from_pixels(self.movie_height.into()),
+            &mut self.stage,
             self.self_reference.clone(),
             &mut self.system,
+            &mut self.policies,
             &mut self.instance_counter,
             self.storage.deref_mut(),
+            self.video.deref_mut(),
+            self.sockets.deref_mut(),
+            self.local_connection.deref_mut(),
         );
 
-        self.gc_arena.mutate(|gc_context, gc_root| {
+        let ret = self.gc_arena.mutate(|gc_context, gc_root| {
             let mut root_data = gc_root.0.write(gc_context);
             let mouse_hovered_object = root_data.mouse_hovered_object;
+            let focus_tracker = root_data.focus_tracker;
             let (
                 levels,
                 library,
                 action_queue,
                 avm,
+                avm2,
                 drag_object,
                 load_manager,
                 shared_objects,
                 unbound_text_fields,
+                audio_manager,
+                timers,
+                xml_sockets,
+                local_connections,
+                external_interface,
             ) = root_data.update_context_params();
 
             let mut update_context = UpdateContext {
@@ -924,29 +1234,54 @@ impl Player {
                 audio,
                 navigator,
                 input,
+                keyboard,
                 action_queue,
                 gc_context,
                 levels,
                 mouse_hovered_object,
                 mouse_position,
                 drag_object,
-                stage_size: (stage_width, stage_height),
+                stage,
                 system_prototypes: avm.prototypes().clone(),
                 player,
                 load_manager,
                 system: system_properties,
                 instance_counter,
                 storage,
+                video,
+                sockets,
+                local_connection,
                 shared_objects,
                 unbound_text_fields,
+                avm2,
+                focus_tracker,
+                audio_manager,
+                timers,
+                policies,
+                xml_sockets,
+                local_connections,
+                external_interface,
             };
 
             let ret = f(avm, &mut update_context);
 
+            // Push any sound transform changes made during this update to the audio backend.
+            update_context
+                .audio_manager
+                .update_sound_transforms(update_context.audio);
+
             // Hovered object may have been updated; copy it back to the GC root.
             root_data.mouse_hovered_object = update_context.mouse_hovered_object;
             ret
-        })
+        });
+
+        // The movie may have changed how it is scaled.
+        if self.stage != old_stage {
+            self.build_matrices();
+            self.needs_render = true;
+        }
+
+        ret
     }
 
     /// Loads font data from the given buffer.
@@ -996,6 +1331,29 @@ impl Player {
         rval
     }
 
+    /// Adds a host that movies can communicate with through `ExternalInterface` and
+    /// `fscommand`.
+    pub fn add_external_interface(&mut self, provider: Box<dyn ExternalInterfaceProvider>) {
+        self.update(|_avm, context| context.external_interface.add_provider(provider));
+    }
+
+    /// Calls a function that the movie registered with `ExternalInterface.addCallback`.
+    ///
+    /// Returns `Null` if there is no callback with the given name.
+    pub fn call_internal_interface(
+        &mut self,
+        name: &str,
+        args: impl IntoIterator<Item = ExternalValue>,
+    ) -> ExternalValue {
+        let args: Vec<ExternalValue> = args.into_iter().collect();
+        self.update(
+            |avm, context| match context.external_interface.get_callback(name) {
+                Some(callback) => callback.call(avm, context, args),
+                None => ExternalValue::Null,
+            },
+        )
+    }
+
     pub fn flush_shared_objects(&mut self) {
         self.update(|avm, update_context| {
             let shared_objects = update_context.shared_objects.clone();
@@ -0,0 +1,428 @@
+//! Cross-domain policy files
+//!
+//! A movie served from one origin may only read data from another origin if
+//! the other server publishes a `crossdomain.xml` policy file that allows the
+//! movie's domain. Movies played from the local filesystem are governed by
+//! their sandbox instead, which is enforced by the navigator backend.
+
+use crate::backend::navigator::{OwnedFuture, RequestOptions, Response};
+use crate::context::UpdateContext;
+use crate::loader::Error;
+use crate::player::Player;
+use crate::tag_utils::SwfMovie;
+use crate::xml::{XMLDocument, XMLName};
+use gc_arena::MutationContext;
+use std::collections::HashMap;
+use std::sync::{Mutex, Weak};
+use url::Url;
+
+/// A parsed policy file, listing the domains that may read data from the
+/// server that hosts it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PolicyFile {
+    /// The `allow-access-from` entries of the policy.
+    allowed: Vec<AllowAccessFrom>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct AllowAccessFrom {
+    /// The domain pattern, such as `example.com`, `*.example.com` or `*`.
+    domain: String,
+
+    /// Whether movies served over plain HTTP are refused by an HTTPS server.
+    secure: bool,
+}
+
+impl PolicyFile {
+    /// Parse the contents of a policy file.
+    ///
+    /// Returns `None` if the data is not a valid policy file.
+    pub fn parse<'gc>(gc_context: MutationContext<'gc, '_>, data: &str) -> Option<Self> {
+        let document = XMLDocument::new(gc_context);
+        let mut root = document.as_node();
+        root.replace_with_str(gc_context, data, true).ok()?;
+
+        let policy = root
+            .children()?
+            .find(|node| node_name(*node).as_deref() == Some("cross-domain-policy"))?;
+
+        let mut allowed = Vec::new();
+        for node in policy.children().into_iter().flatten() {
+            match node_name(node).as_deref() {
+                Some("site-control") => {
+                    let permitted =
+                        node.attribute_value(&XMLName::from_str("permitted-cross-domain-policies"));
+                    if permitted.as_deref() == Some("none") {
+                        return Some(Self::default());
+                    }
+                }
+                Some("allow-access-from") => {
+                    if let Some(domain) = node.attribute_value(&XMLName::from_str("domain")) {
+                        let secure = node.attribute_value(&XMLName::from_str("secure"));
+                        allowed.push(AllowAccessFrom {
+                            domain,
+                            secure: secure.as_deref() != Some("false"),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Some(Self { allowed })
+    }
+
+    /// Whether this policy, served from `policy_url`, allows a movie from
+    /// `requester` to read data.
+    pub fn allows(&self, requester: &Url, policy_url: &Url) -> bool {
+        let host = requester.host_str().unwrap_or("");
+        self.allowed.iter().any(|entry| {
+            domain_matches(&entry.domain, host)
+                && (!entry.secure
+                    || policy_url.scheme() != "https"
+                    || requester.scheme() == "https")
+        })
+    }
+}
+
+/// The local name of an element node.
+fn node_name(node: crate::xml::XMLNode<'_>) -> Option<String> {
+    node.tag_name().map(|name| name.local_name().to_string())
+}
+
+/// Whether a domain pattern from a policy file or `allowDomain` matches a host.
+fn domain_matches(pattern: &str, host: &str) -> bool {
+    let pattern = pattern.to_ascii_lowercase();
+    let host = host.to_ascii_lowercase();
+    if pattern == "*" {
+        true
+    } else if let Some(suffix) = pattern.strip_prefix("*.") {
+        host == suffix || host.ends_with(&format!(".{}", suffix))
+    } else {
+        pattern == host
+    }
+}
+
+/// Whether a movie from `requester` needs permission from a policy file to
+/// read data from `target`.
+fn needs_policy(requester: &Url, target: &Url) -> bool {
+    matches!(requester.scheme(), "http" | "https")
+        && matches!(target.scheme(), "http" | "https")
+        && requester.origin() != target.origin()
+}
+
+/// Resolve a URL requested by a movie against the URL of that movie.
+pub fn resolve_url(movie: Option<&SwfMovie>, url: &str) -> String {
+    movie
+        .and_then(|movie| movie.url())
+        .and_then(|base| Url::parse(base).ok())
+        .and_then(|base| base.join(url).ok())
+        .map(String::from)
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// Keeps track of the policy files and domain permissions known to the player.
+#[derive(Default)]
+pub struct PolicyManager {
+    /// Policy files that have been fetched, by URL.
+    ///
+    /// `None` if the policy file could not be loaded or parsed.
+    policy_files: HashMap<String, Option<PolicyFile>>,
+
+    /// Extra policy files registered with `System.security.loadPolicyFile`.
+    extra_policy_urls: Vec<Url>,
+
+    /// Domains that may access movies of a given origin, as granted by
+    /// `System.security.allowDomain`.
+    allowed_domains: HashMap<String, Vec<String>>,
+}
+
+impl PolicyManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a policy file at a non-default location.
+    ///
+    /// The policy only applies to URLs in the same directory as the policy
+    /// file, or below it.
+    pub fn load_policy_file(&mut self, url: Url) {
+        if !self.extra_policy_urls.contains(&url) {
+            self.extra_policy_urls.push(url);
+        }
+    }
+
+    /// Allow movies from `domain` to access movies loaded from `movie_url`.
+    pub fn allow_domain(&mut self, movie_url: &Url, domain: String) {
+        self.allowed_domains
+            .entry(movie_url.origin().ascii_serialization())
+            .or_default()
+            .push(domain);
+    }
+
+    /// Record the result of fetching a policy file.
+    pub fn add_policy_file(&mut self, url: &Url, policy: Option<PolicyFile>) {
+        self.policy_files.insert(url.to_string(), policy);
+    }
+
+    /// The policy files that may grant access to `target`.
+    fn policy_urls(&self, target: &Url) -> Vec<Url> {
+        let mut urls: Vec<Url> = target.join("/crossdomain.xml").into_iter().collect();
+        for url in &self.extra_policy_urls {
+            let directory = url.join(".").ok();
+            if url.origin() == target.origin()
+                && directory
+                    .iter()
+                    .any(|dir| target.path().starts_with(dir.path()))
+                && !urls.contains(url)
+            {
+                urls.push(url.clone());
+            }
+        }
+        urls
+    }
+
+    /// Check whether a movie from `requester` may read data from `target`.
+    ///
+    /// Returns `Err` with the URL of a policy file that has to be fetched
+    /// before the question can be answered.
+    pub fn can_load(&self, requester: &Url, target: &Url) -> Result<bool, Url> {
+        if !needs_policy(requester, target) {
+            return Ok(true);
+        }
+
+        for policy_url in self.policy_urls(target) {
+            match self.policy_files.get(policy_url.as_str()) {
+                Some(Some(policy)) if policy.allows(requester, &policy_url) => return Ok(true),
+                Some(_) => continue,
+                None => return Err(policy_url),
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Check whether a movie from `requester` may access the content of a
+    /// movie loaded from `movie`, such as by drawing it into a bitmap.
+    ///
+    /// Access is granted to movies of the same origin, by an `allowDomain`
+    /// call in the accessed movie, or by an already loaded policy file.
+    ///
+    /// Movies without a URL were handed to the player directly by the host,
+    /// such as from a local file, and are trusted like local content.
+    pub fn can_access_movie(&self, requester: Option<&str>, movie: Option<&str>) -> bool {
+        let (requester, movie) = match (
+            requester.and_then(|url| Url::parse(url).ok()),
+            movie.and_then(|url| Url::parse(url).ok()),
+        ) {
+            (Some(requester), Some(movie)) => (requester, movie),
+            _ => return true,
+        };
+
+        if !needs_policy(&requester, &movie) {
+            return true;
+        }
+
+        let host = requester.host_str().unwrap_or("");
+        let allowed_by_movie = self
+            .allowed_domains
+            .get(&movie.origin().ascii_serialization())
+            .into_iter()
+            .flatten()
+            .any(|domain| domain_matches(domain, host));
+
+        allowed_by_movie || self.can_load(&requester, &movie) == Ok(true)
+    }
+}
+
+/// Fetch data that a movie wants to read.
+///
+/// If the data lives on another origin than the movie, the returned future
+/// first makes sure that a policy file allows the movie to read it, and fails
+/// with `Error::SandboxViolation` otherwise.
+///
+/// Movies without a URL are trusted like local content, so their requests
+/// are never checked. Frontends should give every movie loaded from the
+/// network its URL.
+pub fn fetch_data(
+    context: &mut UpdateContext<'_, '_, '_>,
+    requester: Option<&SwfMovie>,
+    url: &str,
+    options: RequestOptions,
+) -> OwnedFuture<Response, Error> {
+    let fetch = context.navigator.fetch(url, options);
+
+    let requester_url = match requester
+        .and_then(|movie| movie.url())
+        .and_then(|url| Url::parse(url).ok())
+    {
+        Some(requester_url) => requester_url,
+        None => return fetch,
+    };
+    let target_url = match requester_url.join(url) {
+        Ok(target_url) if needs_policy(&requester_url, &target_url) => target_url,
+        _ => return fetch,
+    };
+    let player = match context.player.clone() {
+        Some(player) => player,
+        None => return fetch,
+    };
+
+    Box::pin(async move {
+        wait_for_policy(player, requester_url, target_url).await?;
+        fetch.await
+    })
+}
+
+/// Fetch policy files until it is known whether `requester` may read `target`.
+async fn wait_for_policy(
+    player: Weak<Mutex<Player>>,
+    requester: Url,
+    target: Url,
+) -> Result<(), Error> {
+    loop {
+        let player = player.upgrade().ok_or(Error::Cancelled)?;
+
+        let next = player
+            .lock()
+            .expect("Could not lock player!!")
+            .update(|_avm, uc| match uc.policies.can_load(&requester, &target) {
+                Ok(allowed) => Ok(allowed),
+                Err(policy_url) => {
+                    let fetch = uc
+                        .navigator
+                        .fetch(policy_url.as_str(), RequestOptions::get());
+                    Err((policy_url, fetch))
+                }
+            });
+
+        let (policy_url, fetch) = match next {
+            Ok(true) => return Ok(()),
+            Ok(false) => return Err(Error::SandboxViolation(target.to_string())),
+            Err(next) => next,
+        };
+
+        let data = fetch.await;
+
+        player
+            .lock()
+            .expect("Could not lock player!!")
+            .update(|_avm, uc| {
+                let policy = data.ok().and_then(|response| {
+                    PolicyFile::parse(uc.gc_context, &String::from_utf8_lossy(&response.body))
+                });
+                if policy.is_none() {
+                    log::warn!("Unable to load policy file {}", policy_url);
+                }
+                uc.policies.add_policy_file(&policy_url, policy);
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gc_arena::rootless_arena;
+
+    fn url(url: &str) -> Url {
+        Url::parse(url).unwrap()
+    }
+
+    #[test]
+    fn parse_policy_file() {
+        rootless_arena(|mc| {
+            let policy = PolicyFile::parse(
+                mc,
+                r#"<?xml version="1.0"?>
+                <!DOCTYPE cross-domain-policy SYSTEM "http://www.adobe.com/xml/dtds/cross-domain-policy.dtd">
+                <cross-domain-policy>
+                    <allow-access-from domain="*.example.com" />
+                    <allow-access-from domain="games.test" secure="false" />
+                </cross-domain-policy>"#,
+            )
+            .unwrap();
+
+            let policy_url = url("http://data.test/crossdomain.xml");
+            assert!(policy.allows(&url("http://example.com/a.swf"), &policy_url));
+            assert!(policy.allows(&url("http://www.example.com/a.swf"), &policy_url));
+            assert!(!policy.allows(&url("http://badexample.com/a.swf"), &policy_url));
+            assert!(policy.allows(&url("http://games.test/a.swf"), &policy_url));
+
+            let secure_policy_url = url("https://data.test/crossdomain.xml");
+            assert!(!policy.allows(&url("http://example.com/a.swf"), &secure_policy_url));
+            assert!(policy.allows(&url("https://example.com/a.swf"), &secure_policy_url));
+            assert!(policy.allows(&url("http://games.test/a.swf"), &secure_policy_url));
+
+            let none = PolicyFile::parse(
+                mc,
+                r#"<cross-domain-policy>
+                    <site-control permitted-cross-domain-policies="none" />
+                    <allow-access-from domain="*" />
+                </cross-domain-policy>"#,
+            )
+            .unwrap();
+            assert!(!none.allows(&url("http://example.com/a.swf"), &policy_url));
+
+            assert_eq!(PolicyFile::parse(mc, "<not-a-policy />"), None);
+        })
+    }
+
+    #[test]
+    fn policy_decisions() {
+        rootless_arena(|mc| {
+            let mut policies = PolicyManager::new();
+            let movie = url("http://example.com/movie.swf");
+
+            // Same origin and local loads never need a policy file.
+            assert_eq!(
+                policies.can_load(&movie, &url("http://example.com/data.xml")),
+                Ok(true)
+            );
+            assert_eq!(
+                policies.can_load(&url("file:///movie.swf"), &url("http://data.test/a")),
+                Ok(true)
+            );
+
+            let target = url("http://data.test/api/data.xml");
+            let default_policy = url("http://data.test/crossdomain.xml");
+            assert_eq!(
+                policies.can_load(&movie, &target),
+                Err(default_policy.clone())
+            );
+
+            policies.add_policy_file(&default_policy, None);
+            assert_eq!(policies.can_load(&movie, &target), Ok(false));
+
+            let api_policy = url("http://data.test/api/policy.xml");
+            policies.load_policy_file(api_policy.clone());
+            assert_eq!(policies.can_load(&movie, &target), Err(api_policy.clone()));
+            assert_eq!(
+                policies.can_load(&movie, &url("http://data.test/other.xml")),
+                Ok(false)
+            );
+
+            let policy = PolicyFile::parse(
+                mc,
+                r#"<cross-domain-policy><allow-access-from domain="example.com" /></cross-domain-policy>"#,
+            );
+            policies.add_policy_file(&api_policy, policy);
+            assert_eq!(policies.can_load(&movie, &target), Ok(true));
+        })
+    }
+
+    #[test]
+    fn movie_access() {
+        let mut policies = PolicyManager::new();
+        let requester = Some("http://example.com/main.swf");
+        let other_url = "http://other.test/child.swf";
+        let other = Some(other_url);
+
+        assert!(policies.can_access_movie(requester, Some("http://example.com/child.swf")));
+        // Movies without a URL are trusted.
+        assert!(policies.can_access_movie(None, other));
+        assert!(!policies.can_access_movie(requester, other));
+
+        policies.allow_domain(&url(other_url), "*.example.com".to_string());
+        assert!(policies.can_access_movie(requester, other));
+    }
+}
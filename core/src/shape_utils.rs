@@ -1,7 +1,7 @@
 use crate::bounding_box::BoundingBox;
 use fnv::FnvHashMap;
 use std::num::NonZeroU32;
-use swf::{CharacterId, FillStyle, LineStyle, Shape, ShapeRecord, Twips};
+use swf::{CharacterId, FillStyle, LineStyle, Matrix, Shape, ShapeRecord, Twips};
 
 pub fn calculate_shape_bounds(shape_records: &[swf::ShapeRecord]) -> swf::Rectangle {
     let mut bounds = swf::Rectangle {
@@ -96,6 +96,149 @@ impl<'a> From<&'a swf::Shape> for DistilledShape<'a> {
     }
 }
 
+/// How to decide which areas of overlapping contours are filled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FillRule {
+    EvenOdd,
+    NonZero,
+}
+
+impl FillRule {
+    /// The fill rule of a shape. Only `DefineShape4` shapes can use the non-zero rule.
+    pub fn of_shape(shape: &Shape) -> Self {
+        if shape.has_fill_winding_rule {
+            FillRule::NonZero
+        } else {
+            FillRule::EvenOdd
+        }
+    }
+}
+
+/// Tests if a point is inside one of the fills of a shape, or on one of its strokes.
+///
+/// The point is in the coordinates of the shape. `local_matrix` transforms the shape to the
+/// stage, and is used to make hairlines one pixel wide.
+pub fn shape_hit_test(shape: &Shape, point: (Twips, Twips), local_matrix: &Matrix) -> bool {
+    let distilled = DistilledShape::from(shape);
+    paths_hit_test(
+        &distilled.paths,
+        point,
+        FillRule::of_shape(shape),
+        local_matrix,
+    )
+}
+
+/// Tests if a point is inside one of the fills of a list of paths, or on one of its strokes.
+pub fn paths_hit_test(
+    paths: &[DrawPath],
+    point: (Twips, Twips),
+    fill_rule: FillRule,
+    local_matrix: &Matrix,
+) -> bool {
+    let point = (point.0.get() as f64, point.1.get() as f64);
+    paths.iter().any(|path| match path {
+        DrawPath::Fill { commands, .. } => {
+            let winding: i32 = flatten_commands(commands)
+                .iter()
+                .map(|contour| winding_number(contour, point))
+                .sum();
+            match fill_rule {
+                FillRule::EvenOdd => winding % 2 != 0,
+                FillRule::NonZero => winding != 0,
+            }
+        }
+        DrawPath::Stroke {
+            style, commands, ..
+        } => {
+            // Hairlines and thinner lines are hit as if they were one pixel wide.
+            let scale =
+                f64::from(local_matrix.a * local_matrix.d - local_matrix.b * local_matrix.c)
+                    .abs()
+                    .sqrt();
+            let min_width = if scale > 0.0 { 20.0 / scale } else { 0.0 };
+            let radius = (style.width.get() as f64).max(min_width) / 2.0;
+            flatten_commands(commands).iter().any(|contour| {
+                contour
+                    .windows(2)
+                    .any(|segment| distance_to_segment(point, segment[0], segment[1]) <= radius)
+            })
+        }
+    })
+}
+
+/// Converts draw commands to contours of points, splitting curves into lines.
+fn flatten_commands(commands: &[DrawCommand]) -> Vec<Vec<(f64, f64)>> {
+    let point = |x: Twips, y: Twips| (x.get() as f64, y.get() as f64);
+
+    let mut contours = Vec::new();
+    let mut contour = vec![(0.0, 0.0)];
+    for command in commands {
+        match *command {
+            DrawCommand::MoveTo { x, y } => {
+                if contour.len() > 1 {
+                    contours.push(contour);
+                }
+                contour = vec![point(x, y)];
+            }
+            DrawCommand::LineTo { x, y } => contour.push(point(x, y)),
+            DrawCommand::CurveTo { x1, y1, x2, y2 } => {
+                let start = *contour.last().unwrap();
+                let control = point(x1, y1);
+                let end = point(x2, y2);
+                // Split curves into lines about two pixels long.
+                let length = distance(start, control) + distance(control, end);
+                let steps = ((length / 40.0).ceil() as usize).max(1).min(64);
+                for step in 1..=steps {
+                    let t = step as f64 / steps as f64;
+                    let u = 1.0 - t;
+                    contour.push((
+                        u * u * start.0 + 2.0 * u * t * control.0 + t * t * end.0,
+                        u * u * start.1 + 2.0 * u * t * control.1 + t * t * end.1,
+                    ));
+                }
+            }
+        }
+    }
+    if contour.len() > 1 {
+        contours.push(contour);
+    }
+    contours
+}
+
+/// The number of times a contour, closed by a line back to its start, winds around a point.
+fn winding_number(contour: &[(f64, f64)], point: (f64, f64)) -> i32 {
+    let closing_edge = [*contour.last().unwrap(), contour[0]];
+    let edges = contour.windows(2).chain(std::iter::once(&closing_edge[..]));
+
+    let mut winding = 0;
+    for edge in edges {
+        let (start, end) = (edge[0], edge[1]);
+        let side =
+            (end.0 - start.0) * (point.1 - start.1) - (point.0 - start.0) * (end.1 - start.1);
+        if start.1 <= point.1 && end.1 > point.1 && side > 0.0 {
+            winding += 1;
+        } else if end.1 <= point.1 && start.1 > point.1 && side < 0.0 {
+            winding -= 1;
+        }
+    }
+    winding
+}
+
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (b.0 - a.0).hypot(b.1 - a.1)
+}
+
+fn distance_to_segment(point: (f64, f64), start: (f64, f64), end: (f64, f64)) -> f64 {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let length_squared = dx * dx + dy * dy;
+    if length_squared == 0.0 {
+        return distance(point, start);
+    }
+    let t = ((point.0 - start.0) * dx + (point.1 - start.1) * dy) / length_squared;
+    let t = t.max(0.0).min(1.0);
+    distance(point, (start.0 + t * dx, start.1 + t * dy))
+}
+
 /// `DrawCommands` trace the outline of a path.
 /// Fills follow the even-odd fill rule, with opposite winding for holes.
 #[derive(Debug, PartialEq, Clone)]
@@ -710,4 +853,84 @@ mod tests {
         }];
         assert_eq!(commands, expected);
     }
+
+    /// A square path with its corners at `min` and `max` pixels.
+    fn square(min: f64, max: f64, clockwise: bool) -> Vec<DrawCommand> {
+        let mut corners = [(min, min), (max, min), (max, max), (min, max)];
+        if !clockwise {
+            corners.reverse();
+        }
+        let mut commands: Vec<DrawCommand> = corners
+            .iter()
+            .map(|&(x, y)| DrawCommand::LineTo {
+                x: Twips::from_pixels(x),
+                y: Twips::from_pixels(y),
+            })
+            .collect();
+        commands[0] = DrawCommand::MoveTo {
+            x: Twips::from_pixels(corners[0].0),
+            y: Twips::from_pixels(corners[0].1),
+        };
+        commands
+    }
+
+    fn pixel(x: f64, y: f64) -> (Twips, Twips) {
+        (Twips::from_pixels(x), Twips::from_pixels(y))
+    }
+
+    /// Holes follow the fill rule, and strokes are hit within half their width.
+    #[test]
+    fn paths_hit_test_fill_rules_and_strokes() {
+        let identity = Matrix::identity();
+        let mut commands = square(0.0, 100.0, true);
+        commands.extend(square(25.0, 75.0, true));
+        let fill = [DrawPath::Fill {
+            style: &FILL_STYLES[0],
+            commands,
+        }];
+        let hit = |point, fill_rule| paths_hit_test(&fill, point, fill_rule, &identity);
+        assert!(hit(pixel(10.0, 10.0), FillRule::EvenOdd));
+        assert!(!hit(pixel(50.0, 50.0), FillRule::EvenOdd));
+        assert!(hit(pixel(50.0, 50.0), FillRule::NonZero));
+        assert!(!hit(pixel(150.0, 50.0), FillRule::NonZero));
+
+        let line_style = LineStyle::new_v1(Twips::from_pixels(10.0), swf::Color::from_rgb(0, 255));
+        let stroke = [DrawPath::Stroke {
+            style: &line_style,
+            is_closed: true,
+            commands: square(0.0, 100.0, false),
+        }];
+        let hit = |point| paths_hit_test(&stroke, point, FillRule::EvenOdd, &identity);
+        assert!(hit(pixel(104.0, 50.0)));
+        assert!(!hit(pixel(106.0, 50.0)));
+        assert!(!hit(pixel(50.0, 50.0)));
+    }
+
+    /// Shapes are tested in their own coordinates.
+    #[test]
+    fn shape_hit_test_basic_shape() {
+        let shape = build_shape(vec![
+            ShapeRecord::StyleChange(swf::StyleChangeData {
+                move_to: Some((Twips::from_pixels(100.0), Twips::from_pixels(100.0))),
+                fill_style_0: None,
+                fill_style_1: Some(1),
+                line_style: None,
+                new_styles: None,
+            }),
+            ShapeRecord::CurvedEdge {
+                control_delta_x: Twips::from_pixels(100.0),
+                control_delta_y: Twips::from_pixels(0.0),
+                anchor_delta_x: Twips::from_pixels(0.0),
+                anchor_delta_y: Twips::from_pixels(100.0),
+            },
+            ShapeRecord::StraightEdge {
+                delta_x: Twips::from_pixels(-100.0),
+                delta_y: Twips::from_pixels(-100.0),
+            },
+        ]);
+        let identity = Matrix::identity();
+        assert!(shape_hit_test(&shape, pixel(170.0, 130.0), &identity));
+        assert!(!shape_hit_test(&shape, pixel(195.0, 105.0), &identity));
+        assert!(!shape_hit_test(&shape, pixel(120.0, 180.0), &identity));
+    }
 }
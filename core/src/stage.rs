@@ -0,0 +1,275 @@
+//! How the stage is scaled and positioned in the viewport.
+
+use crate::backend::render::Letterbox;
+use crate::prelude::*;
+use std::fmt;
+use std::str::FromStr;
+
+/// How the movie is scaled to fit the viewport, as set by `Stage.scaleMode`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StageScaleMode {
+    /// Scale the movie as large as it fits, keeping its aspect ratio.
+    #[default]
+    ShowAll,
+
+    /// Scale the movie to fill the viewport, keeping its aspect ratio and
+    /// cropping the movie if needed.
+    NoBorder,
+
+    /// Stretch the movie to fill the viewport exactly.
+    ExactFit,
+
+    /// Don't scale the movie. The stage takes the size of the viewport.
+    NoScale,
+}
+
+impl fmt::Display for StageScaleMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            StageScaleMode::ShowAll => "showAll",
+            StageScaleMode::NoBorder => "noBorder",
+            StageScaleMode::ExactFit => "exactFit",
+            StageScaleMode::NoScale => "noScale",
+        })
+    }
+}
+
+impl FromStr for StageScaleMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().replace('-', "").as_str() {
+            "showall" => Ok(StageScaleMode::ShowAll),
+            "noborder" => Ok(StageScaleMode::NoBorder),
+            "exactfit" => Ok(StageScaleMode::ExactFit),
+            "noscale" => Ok(StageScaleMode::NoScale),
+            _ => Err(format!(
+                "Unknown scale mode {}, expected one of showAll, noBorder, exactFit or noScale",
+                s
+            )),
+        }
+    }
+}
+
+/// Which edges of the viewport the movie is anchored to, as set by
+/// `Stage.align`.
+///
+/// The movie is centered along any axis without an anchored edge. If both
+/// edges of an axis are set, the top or left edge takes priority.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StageAlign {
+    pub top: bool,
+    pub bottom: bool,
+    pub left: bool,
+    pub right: bool,
+}
+
+impl fmt::Display for StageAlign {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Flash Player lists the edges in this order.
+        for (is_set, letter) in &[
+            (self.left, "L"),
+            (self.top, "T"),
+            (self.right, "R"),
+            (self.bottom, "B"),
+        ] {
+            if *is_set {
+                f.write_str(letter)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<&str> for StageAlign {
+    /// Read the edges from a string such as `"TL"`. Other characters are
+    /// ignored.
+    fn from(s: &str) -> Self {
+        let s = s.to_ascii_uppercase();
+        StageAlign {
+            top: s.contains('T'),
+            bottom: s.contains('B'),
+            left: s.contains('L'),
+            right: s.contains('R'),
+        }
+    }
+}
+
+/// The sizes of the movie and viewport, and how one is fit into the other.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StageProperties {
+    /// The size of the movie, in pixels.
+    movie_size: (u32, u32),
+
+    /// The size of the viewport the movie is displayed in, in pixels.
+    viewport_size: (u32, u32),
+
+    pub scale_mode: StageScaleMode,
+
+    pub align: StageAlign,
+
+    /// Whether black bars are drawn over the margins around a centered
+    /// `showAll` movie. Flash Player instead shows whatever content lies in
+    /// the margins.
+    pub letterbox: bool,
+
+    /// Whether the full context menu is shown, as set by `Stage.showMenu`.
+    pub show_menu: bool,
+}
+
+impl StageProperties {
+    /// Create the stage for a movie, with a viewport of the same size.
+    pub fn new(movie_width: u32, movie_height: u32) -> Self {
+        Self {
+            movie_size: (movie_width, movie_height),
+            viewport_size: (movie_width, movie_height),
+            scale_mode: Default::default(),
+            align: Default::default(),
+            letterbox: true,
+            show_menu: true,
+        }
+    }
+
+    pub fn movie_size(&self) -> (u32, u32) {
+        self.movie_size
+    }
+
+    pub fn viewport_size(&self) -> (u32, u32) {
+        self.viewport_size
+    }
+
+    pub fn set_viewport_size(&mut self, width: u32, height: u32) {
+        self.viewport_size = (width, height);
+    }
+
+    /// The size of the stage, as seen by `Stage.width` and `Stage.height`.
+    ///
+    /// This is the size of the movie, unless the movie is not scaled.
+    pub fn stage_size(&self) -> (u32, u32) {
+        if self.scale_mode == StageScaleMode::NoScale {
+            self.viewport_size
+        } else {
+            self.movie_size
+        }
+    }
+
+    /// Calculate the matrix that transforms the movie into the viewport, and
+    /// the letterbox to draw around it.
+    pub fn view_transform(&self) -> (Matrix, Letterbox) {
+        let (movie_width, movie_height) = (self.movie_size.0 as f32, self.movie_size.1 as f32);
+        let (viewport_width, viewport_height) =
+            (self.viewport_size.0 as f32, self.viewport_size.1 as f32);
+        let (scale_x, scale_y) = (viewport_width / movie_width, viewport_height / movie_height);
+        let (scale_x, scale_y) = match self.scale_mode {
+            StageScaleMode::ShowAll => (scale_x.min(scale_y), scale_x.min(scale_y)),
+            StageScaleMode::NoBorder => (scale_x.max(scale_y), scale_x.max(scale_y)),
+            StageScaleMode::ExactFit => (scale_x, scale_y),
+            StageScaleMode::NoScale => (1.0, 1.0),
+        };
+
+        // The margins may be negative when the movie is cropped.
+        let margin_width = viewport_width - movie_width * scale_x;
+        let margin_height = viewport_height - movie_height * scale_y;
+        let margin_left = if self.align.left {
+            0.0
+        } else if self.align.right {
+            margin_width
+        } else {
+            margin_width / 2.0
+        };
+        let margin_top = if self.align.top {
+            0.0
+        } else if self.align.bottom {
+            margin_height
+        } else {
+            margin_height / 2.0
+        };
+
+        let matrix = Matrix {
+            a: scale_x,
+            b: 0.0,
+            c: 0.0,
+            d: scale_y,
+            tx: Twips::from_pixels(margin_left.into()),
+            ty: Twips::from_pixels(margin_top.into()),
+        };
+
+        // Only centered movies are letterboxed, as the bars are drawn evenly
+        // on both sides.
+        let is_centered_x = !self.align.left && !self.align.right;
+        let is_centered_y = !self.align.top && !self.align.bottom;
+        let letterbox = match self.scale_mode {
+            _ if !self.letterbox => Letterbox::None,
+            StageScaleMode::ShowAll if margin_width > 0.0 && is_centered_x => {
+                Letterbox::Pillarbox(margin_left)
+            }
+            StageScaleMode::ShowAll if margin_height > 0.0 && is_centered_y => {
+                Letterbox::Letterbox(margin_top)
+            }
+            _ => Letterbox::None,
+        };
+
+        (matrix, letterbox)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stage(scale_mode: &str, align: &str) -> StageProperties {
+        let mut stage = StageProperties::new(100, 50);
+        stage.set_viewport_size(400, 100);
+        stage.scale_mode = scale_mode.parse().unwrap();
+        stage.align = align.into();
+        stage
+    }
+
+    #[test]
+    fn scale_modes() {
+        let (matrix, letterbox) = stage("showAll", "").view_transform();
+        assert_eq!((matrix.a, matrix.d), (2.0, 2.0));
+        assert_eq!(matrix.tx, Twips::from_pixels(100.0));
+        assert_eq!(letterbox, Letterbox::Pillarbox(100.0));
+
+        let (matrix, letterbox) = stage("noBorder", "").view_transform();
+        assert_eq!((matrix.a, matrix.d), (4.0, 4.0));
+        assert_eq!(matrix.ty, Twips::from_pixels(-50.0));
+        assert_eq!(letterbox, Letterbox::None);
+
+        let (matrix, _) = stage("EXACTFIT", "").view_transform();
+        assert_eq!((matrix.a, matrix.d), (4.0, 2.0));
+        assert_eq!((matrix.tx, matrix.ty), (Twips::zero(), Twips::zero()));
+
+        let stage = stage("noScale", "");
+        let (matrix, _) = stage.view_transform();
+        assert_eq!((matrix.a, matrix.d), (1.0, 1.0));
+        assert_eq!(matrix.tx, Twips::from_pixels(150.0));
+        assert_eq!(stage.stage_size(), (400, 100));
+
+        assert!("fit".parse::<StageScaleMode>().is_err());
+    }
+
+    #[test]
+    fn letterbox() {
+        let mut stage = stage("showAll", "");
+        stage.letterbox = false;
+        let (matrix, letterbox) = stage.view_transform();
+        assert_eq!(matrix.tx, Twips::from_pixels(100.0));
+        assert_eq!(letterbox, Letterbox::None);
+    }
+
+    #[test]
+    fn align() {
+        let (matrix, letterbox) = stage("showAll", "r").view_transform();
+        assert_eq!(matrix.tx, Twips::from_pixels(200.0));
+        assert_eq!(letterbox, Letterbox::None);
+
+        let (matrix, _) = stage("noScale", "BLR").view_transform();
+        assert_eq!(matrix.tx, Twips::zero());
+        assert_eq!(matrix.ty, Twips::from_pixels(50.0));
+
+        assert_eq!(StageAlign::from("trbl").to_string(), "LTRB");
+        assert_eq!(StageAlign::from("").to_string(), "");
+    }
+}
@@ -17,6 +17,9 @@ pub struct SwfMovie {
 
     /// Uncompressed SWF data.
     data: Vec<u8>,
+
+    /// The URL the SWF was loaded from, if known.
+    url: Option<String>,
 }
 
 impl SwfMovie {
@@ -31,6 +34,7 @@ impl SwfMovie {
                 num_frames: 0,
             },
             data: vec![],
+            url: None,
         }
     }
 
@@ -39,17 +43,18 @@ impl SwfMovie {
         Self {
             header: self.header.clone(),
             data,
+            url: self.url.clone(),
         }
     }
 
     /// Utility method to construct a movie from a file on disk.
     pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
-        let data = std::fs::read(path)?;
-        Self::from_data(&data)
+        let data = std::fs::read(&path)?;
+        Self::from_data(&data, file_url(path.as_ref()))
     }
 
     /// Construct a movie based on the contents of the SWF datastream.
-    pub fn from_data(swf_data: &[u8]) -> Result<Self, Error> {
+    pub fn from_data(swf_data: &[u8], url: Option<String>) -> Result<Self, Error> {
         let swf_stream = swf::read::read_swf_header(&swf_data[..])?;
         let header = swf_stream.header;
         let mut reader = swf_stream.reader;
@@ -74,7 +79,7 @@ impl SwfMovie {
             data
         };
 
-        Ok(Self { header, data })
+        Ok(Self { header, data, url })
     }
 
     pub fn header(&self) -> &Header {
@@ -90,6 +95,11 @@ impl SwfMovie {
         &self.data
     }
 
+    /// The URL the SWF was loaded from, if known.
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
     pub fn width(&self) -> u32 {
         (self.header.stage_size.x_max - self.header.stage_size.x_min).to_pixels() as u32
     }
@@ -97,6 +107,32 @@ impl SwfMovie {
     pub fn height(&self) -> u32 {
         (self.header.stage_size.y_max - self.header.stage_size.y_min).to_pixels() as u32
     }
+
+    /// Whether the movie's `FileAttributes` tag asks for the local-with-network
+    /// sandbox instead of local-with-file when it is played from the filesystem.
+    pub fn use_network_sandbox(&self) -> bool {
+        // If present, `FileAttributes` is always the first tag of the movie.
+        let mut reader = swf::read::Reader::new(&self.data[..], self.version());
+        match reader.read_tag_code_and_length() {
+            Ok((tag_code, _)) if tag_code == TagCode::FileAttributes as u16 => reader
+                .read_file_attributes()
+                .map(|attributes| attributes.use_network_sandbox)
+                .unwrap_or(false),
+            _ => false,
+        }
+    }
+}
+
+/// Convert the path of a SWF on disk into a `file:` URL.
+#[cfg(any(unix, windows))]
+fn file_url(path: &Path) -> Option<String> {
+    let path = path.canonicalize().ok()?;
+    url::Url::from_file_path(path).ok().map(String::from)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn file_url(_path: &Path) -> Option<String> {
+    None
 }
 
 /// A shared-ownership reference to some portion of an SWF datastream.
@@ -0,0 +1,224 @@
+//! Timers created by the AVM1 `setInterval` and `setTimeout` functions.
+
+use crate::avm1::{Activation, Avm1, Object, TObject, Value};
+use crate::context::UpdateContext;
+use crate::display_object::DisplayObject;
+use gc_arena::{Collect, CollectionContext, GcCell};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// The maximum number of timer callbacks to run in a single update.
+///
+/// This prevents a flood of short intervals from locking up the player after a long stall.
+const MAX_TIMER_CALLS_PER_UPDATE: u32 = 100;
+
+/// Manages the active timers of the player.
+///
+/// Timers are kept in a priority queue ordered by the time they are next due to fire.
+/// Timers due at the same time fire in the order they were created.
+pub struct Timers<'gc> {
+    timers: BinaryHeap<Timer<'gc>>,
+
+    /// The ID of the most recently created timer.
+    timer_counter: i32,
+}
+
+unsafe impl<'gc> Collect for Timers<'gc> {
+    fn trace(&self, cc: CollectionContext) {
+        for timer in self.timers.iter() {
+            timer.trace(cc);
+        }
+    }
+}
+
+impl<'gc> Timers<'gc> {
+    pub fn new() -> Self {
+        Self {
+            timers: BinaryHeap::new(),
+            timer_counter: 0,
+        }
+    }
+
+    /// Adds a timer, returning its ID for use with `clearInterval`.
+    ///
+    /// `cur_time` and `interval` are in milliseconds. A timeout fires once and is then removed,
+    /// while an interval keeps firing until it is removed.
+    pub fn add_timer(
+        &mut self,
+        callback: TimerCallback<'gc>,
+        params: Vec<Value<'gc>>,
+        cur_time: u64,
+        interval: u32,
+        is_timeout: bool,
+    ) -> i32 {
+        // SWFs are not allowed to use the ID 0.
+        self.timer_counter = self.timer_counter.wrapping_add(1).max(1);
+        let id = self.timer_counter;
+        self.timers.push(Timer {
+            id,
+            callback,
+            params,
+            tick_time: cur_time + u64::from(interval),
+            interval,
+            is_timeout,
+        });
+        id
+    }
+
+    /// Removes the timer with the given ID.
+    ///
+    /// Returns `true` if the timer existed.
+    pub fn remove(&mut self, id: i32) -> bool {
+        let len = self.timers.len();
+        let mut timers = std::mem::take(&mut self.timers).into_vec();
+        timers.retain(|timer| timer.id != id);
+        self.timers = timers.into();
+        self.timers.len() != len
+    }
+
+    /// Runs the callbacks of every timer that is due.
+    ///
+    /// Intervals are rescheduled before their callback runs, so that the callback may clear its
+    /// own interval.
+    pub fn update_timers(avm: &mut Avm1<'gc>, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let cur_time = context.navigator.time_since_launch().as_millis() as u64;
+
+        let mut num_calls = 0;
+        while num_calls < MAX_TIMER_CALLS_PER_UPDATE {
+            let timer = match context.timers.timers.peek() {
+                Some(timer) if timer.tick_time <= cur_time => context.timers.timers.pop().unwrap(),
+                _ => break,
+            };
+
+            if !timer.is_timeout {
+                // Intervals that fell far behind skip ahead instead of firing in a burst.
+                let interval = u64::from(timer.interval.max(1));
+                let mut tick_time = timer.tick_time + interval;
+                if tick_time + interval <= cur_time {
+                    tick_time = cur_time + interval;
+                }
+                context.timers.timers.push(Timer {
+                    tick_time,
+                    ..timer.clone()
+                });
+            }
+
+            timer.run(avm, context);
+            num_calls += 1;
+        }
+    }
+}
+
+impl<'gc> Default for Timers<'gc> {
+    fn default() -> Self {
+        Timers::new()
+    }
+}
+
+/// The function run by a timer.
+#[derive(Clone, Collect)]
+#[collect(no_drop)]
+pub enum TimerCallback<'gc> {
+    /// A function object, from `setInterval(function, interval)`.
+    Function(Object<'gc>),
+
+    /// A method of an object, from `setInterval(object, "methodName", interval)`.
+    Method {
+        this: Object<'gc>,
+        method_name: String,
+    },
+}
+
+/// A single timer created by `setInterval` or `setTimeout`.
+#[derive(Clone)]
+struct Timer<'gc> {
+    /// The ID of the timer, returned to ActionScript.
+    id: i32,
+
+    /// The function to run when the timer fires.
+    callback: TimerCallback<'gc>,
+
+    /// The extra arguments passed to the callback.
+    params: Vec<Value<'gc>>,
+
+    /// The time in milliseconds at which the timer is next due to fire.
+    tick_time: u64,
+
+    /// The time in milliseconds between each firing of the timer.
+    interval: u32,
+
+    /// Whether the timer fires only once.
+    is_timeout: bool,
+}
+
+unsafe impl<'gc> Collect for Timer<'gc> {
+    fn trace(&self, cc: CollectionContext) {
+        self.callback.trace(cc);
+        self.params.trace(cc);
+    }
+}
+
+impl<'gc> Timer<'gc> {
+    /// Calls the callback of this timer, running its code to completion.
+    fn run(&self, avm: &mut Avm1<'gc>, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let active_clip: DisplayObject<'gc> = match context.levels.get(&0) {
+            Some(root) => *root,
+            None => return,
+        };
+        let swf_version = context.swf.header().version;
+
+        match &self.callback {
+            TimerCallback::Function(function) => {
+                // Native functions expect a stack frame to be present.
+                // The function exec pushes its own stack frame on top of this one.
+                avm.insert_stack_frame(GcCell::allocate(
+                    context.gc_context,
+                    Activation::from_nothing(
+                        swf_version,
+                        avm.global_object_cell(),
+                        context.gc_context,
+                        active_clip,
+                    ),
+                ));
+                let this = avm.global_object_cell();
+                let _ = function.call(avm, context, this, None, &self.params);
+            }
+            TimerCallback::Method { this, method_name } => {
+                avm.insert_stack_frame_for_method(
+                    active_clip,
+                    *this,
+                    swf_version,
+                    context,
+                    method_name,
+                    &self.params,
+                );
+            }
+        }
+
+        let _ = avm.run_stack_till_empty(context);
+    }
+}
+
+impl PartialEq for Timer<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Timer<'_> {}
+
+impl PartialOrd for Timer<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Timer<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // `BinaryHeap` is a max-heap, so reverse the order to pop the earliest timer first.
+        self.tick_time
+            .cmp(&other.tick_time)
+            .then_with(|| self.id.cmp(&other.id))
+            .reverse()
+    }
+}
@@ -0,0 +1,208 @@
+//! Connections made by the AVM1 `XMLSocket` class.
+
+use crate::avm1::{Avm1, Object, Value};
+use crate::backend::socket::{SocketBackend, SocketEvent, SocketHandle};
+use crate::context::UpdateContext;
+use crate::display_object::DisplayObject;
+use gc_arena::{Collect, CollectionContext};
+
+/// Manages the open connections of `XMLSocket` objects.
+///
+/// Each `XMLSocket` object has at most one connection. Incoming data is buffered until a null
+/// byte arrives, at which point the message is passed to the object's `onData` handler.
+pub struct XMLSockets<'gc> {
+    sockets: Vec<XMLSocket<'gc>>,
+}
+
+unsafe impl<'gc> Collect for XMLSockets<'gc> {
+    fn trace(&self, cc: CollectionContext) {
+        for socket in &self.sockets {
+            socket.object.trace(cc);
+        }
+    }
+}
+
+impl<'gc> XMLSockets<'gc> {
+    pub fn new() -> Self {
+        Self {
+            sockets: Vec::new(),
+        }
+    }
+
+    /// Starts connecting an `XMLSocket` object, closing any connection it already has.
+    pub fn connect(
+        &mut self,
+        backend: &mut dyn SocketBackend,
+        object: Object<'gc>,
+        host: &str,
+        port: u16,
+    ) {
+        self.close(backend, object);
+        let handle = backend.connect(host, port);
+        self.sockets.push(XMLSocket {
+            handle,
+            object,
+            is_connected: false,
+            buffer: Vec::new(),
+        });
+    }
+
+    /// Sends a message over the connection of an `XMLSocket` object, terminated by a null byte.
+    ///
+    /// Returns `false` if the object isn't connected.
+    pub fn send(
+        &mut self,
+        backend: &mut dyn SocketBackend,
+        object: Object<'gc>,
+        message: &str,
+    ) -> bool {
+        match self
+            .sockets
+            .iter()
+            .find(|s| Object::ptr_eq(s.object, object))
+        {
+            Some(socket) if socket.is_connected => {
+                let mut data = Vec::with_capacity(message.len() + 1);
+                data.extend_from_slice(message.as_bytes());
+                data.push(0);
+                backend.send(socket.handle, data);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Closes the connection of an `XMLSocket` object, if it has one.
+    ///
+    /// `onClose` is only called when the server closes the connection, so it isn't called here.
+    pub fn close(&mut self, backend: &mut dyn SocketBackend, object: Object<'gc>) {
+        if let Some(index) = self
+            .sockets
+            .iter()
+            .position(|s| Object::ptr_eq(s.object, object))
+        {
+            let socket = self.sockets.remove(index);
+            backend.close(socket.handle);
+        }
+    }
+
+    /// Polls the socket backend and runs the handlers of `XMLSocket` objects for any events.
+    pub fn update_sockets(avm: &mut Avm1<'gc>, context: &mut UpdateContext<'_, 'gc, '_>) {
+        for event in context.sockets.poll() {
+            match event {
+                SocketEvent::Connect(handle, success) => {
+                    let object = match context.xml_sockets.get_mut(handle) {
+                        Some(socket) => {
+                            socket.is_connected = success;
+                            socket.object
+                        }
+                        None => continue,
+                    };
+                    if !success {
+                        context.xml_sockets.remove(handle);
+                        context.sockets.close(handle);
+                    }
+                    run_handler(avm, context, object, "onConnect", &[success.into()]);
+                }
+                SocketEvent::Data(handle, data) => {
+                    let (object, messages) = match context.xml_sockets.get_mut(handle) {
+                        Some(socket) => (socket.object, socket.receive(&data)),
+                        None => continue,
+                    };
+                    for message in messages {
+                        run_handler(avm, context, object, "onData", &[message.into()]);
+                    }
+                }
+                SocketEvent::Close(handle) => {
+                    if let Some(socket) = context.xml_sockets.remove(handle) {
+                        context.sockets.close(handle);
+                        run_handler(avm, context, socket.object, "onClose", &[]);
+                    }
+                }
+            }
+        }
+    }
+
+    fn get_mut(&mut self, handle: SocketHandle) -> Option<&mut XMLSocket<'gc>> {
+        self.sockets.iter_mut().find(|s| s.handle == handle)
+    }
+
+    fn remove(&mut self, handle: SocketHandle) -> Option<XMLSocket<'gc>> {
+        let index = self.sockets.iter().position(|s| s.handle == handle)?;
+        Some(self.sockets.remove(index))
+    }
+}
+
+impl<'gc> Default for XMLSockets<'gc> {
+    fn default() -> Self {
+        XMLSockets::new()
+    }
+}
+
+/// The connection of a single `XMLSocket` object.
+struct XMLSocket<'gc> {
+    handle: SocketHandle,
+    object: Object<'gc>,
+
+    /// Whether the connection attempt has succeeded.
+    is_connected: bool,
+
+    /// Received data that isn't yet terminated by a null byte.
+    buffer: Vec<u8>,
+}
+
+impl<'gc> XMLSocket<'gc> {
+    /// Buffers received data, returning every message that is now complete.
+    fn receive(&mut self, data: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(data);
+        let mut messages = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|&b| b == 0) {
+            let message: Vec<u8> = self.buffer.drain(..=end).collect();
+            messages.push(String::from_utf8_lossy(&message[..end]).into_owned());
+        }
+        messages
+    }
+}
+
+/// Calls a handler method of an `XMLSocket` object, running its code to completion.
+fn run_handler<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+    name: &str,
+    args: &[Value<'gc>],
+) {
+    let active_clip: DisplayObject<'gc> = match context.levels.get(&0) {
+        Some(root) => *root,
+        None => return,
+    };
+    let swf_version = context.swf.header().version;
+    avm.insert_stack_frame_for_method(active_clip, object, swf_version, context, name, args);
+    let _ = avm.run_stack_till_empty(context);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm1::script_object::ScriptObject;
+    use generational_arena::Arena;
+
+    #[test]
+    fn messages_are_split_on_null_bytes() {
+        gc_arena::rootless_arena(|gc_context| {
+            let mut arena = Arena::new();
+            let mut socket = XMLSocket {
+                handle: arena.insert(()),
+                object: ScriptObject::object(gc_context, None).into(),
+                is_connected: true,
+                buffer: Vec::new(),
+            };
+
+            assert!(socket.receive(b"<a>").is_empty());
+            assert_eq!(socket.receive(b"1</a>\0<b/>\0<c"), vec!["<a>1</a>", "<b/>"]);
+            assert_eq!(socket.receive(b"/>\0"), vec!["<c/>"]);
+            assert_eq!(socket.receive(b"\0"), vec![""]);
+            assert!(socket.buffer.is_empty());
+        })
+    }
+}
@@ -7,7 +7,9 @@ use log::{Metadata, Record};
 use ruffle_core::backend::navigator::{NullExecutor, NullNavigatorBackend};
 use ruffle_core::backend::storage::MemoryStorageBackend;
 use ruffle_core::backend::{
-    audio::NullAudioBackend, input::NullInputBackend, render::NullRenderer,
+    audio::NullAudioBackend, input::NullInputBackend,
+    local_connection::InProcessLocalConnectionBackend, render::NullRenderer,
+    socket::NullSocketBackend, video::NullVideoBackend,
 };
 use ruffle_core::tag_utils::SwfMovie;
 use ruffle_core::Player;
@@ -57,6 +59,7 @@ macro_rules! swf_tests_approx {
 // Inside the folder is expected to be "test.swf" and "output.txt" with the correct output.
 swf_tests! {
     (add_property, "avm1/add_property", 1),
+    (avm2_enter_frame, "avm2/enter_frame", 4),
     (as_transformed_flag, "avm1/as_transformed_flag", 3),
     (attach_movie, "avm1/attach_movie", 1),
     (function_base_clip, "avm1/function_base_clip", 2),
@@ -314,6 +317,9 @@ fn run_swf(swf_path: &str, num_frames: u32) -> Result<String, Error> {
         Box::new(NullInputBackend::new()),
         movie,
         Box::new(MemoryStorageBackend::default()),
+        Box::new(NullVideoBackend::new()),
+        Box::new(NullSocketBackend::new()),
+        Box::new(InProcessLocalConnectionBackend::new()),
     )?;
 
     for _ in 0..num_frames {
@@ -0,0 +1,29 @@
+package {
+	import flash.display.MovieClip;
+	import flash.events.Event;
+
+	public class Test extends MovieClip {
+		public function Test() {
+			trace("constructed");
+			addFrameScript(0, frame1, 3, frame4);
+		}
+
+		public function frame1() {
+			trace("frame 1");
+			addEventListener(Event.ENTER_FRAME, onEnterFrame);
+			trace(hasEventListener("enterFrame"));
+		}
+
+		public function frame4() {
+			trace("frame 4");
+			trace(hasEventListener("enterFrame"));
+		}
+
+		public function onEnterFrame(e) {
+			trace(e.type, e.currentTarget.currentFrame, e.target === e.currentTarget);
+			if (e.currentTarget.currentFrame == 3) {
+				e.currentTarget.removeEventListener("enterFrame", e.currentTarget.onEnterFrame);
+			}
+		}
+	}
+}
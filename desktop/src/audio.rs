@@ -4,7 +4,7 @@ use ruffle_core::backend::audio::decoders::{
     self, AdpcmDecoder, Mp3Decoder, PcmDecoder, SeekableDecoder,
 };
 use ruffle_core::backend::audio::{
-    swf, AudioBackend, AudioStreamHandle, SoundHandle, SoundInstanceHandle,
+    swf, AudioBackend, AudioStreamHandle, SoundHandle, SoundInstanceHandle, SoundTransform,
 };
 use ruffle_core::tag_utils::SwfSlice;
 use std::io::Cursor;
@@ -60,6 +60,51 @@ struct SoundInstance {
     /// If this flag is false, the sound will be cleaned up during the
     /// next loop of the sound thread.
     active: bool,
+
+    /// The gains applied to the sound, from `SoundTransform::mix_gains`.
+    transform: [f32; 4],
+
+    /// The number of output sample frames mixed from this sound so far.
+    frames_played: u64,
+
+    /// The position in milliseconds that playback started at.
+    start_position: u32,
+
+    /// The length in milliseconds of one loop of the sound, used to wrap the position.
+    /// `None` for stream sounds.
+    loop_duration: Option<u32>,
+}
+
+impl SoundInstance {
+    fn new(
+        handle: Option<SoundHandle>,
+        clip_id: Option<swf::CharacterId>,
+        signal: Signal,
+        start_position: u32,
+        loop_duration: Option<u32>,
+    ) -> Self {
+        Self {
+            handle,
+            signal,
+            clip_id,
+            active: true,
+            transform: SoundTransform::default().mix_gains(),
+            frames_played: 0,
+            start_position,
+            loop_duration,
+        }
+    }
+
+    /// The current playback position in milliseconds, given the output sample rate.
+    fn position(&self, output_sample_rate: u32) -> u32 {
+        let elapsed = (self.frames_played * 1000 / u64::from(output_sample_rate.max(1))) as u32;
+        match self.loop_duration {
+            Some(duration) if duration > self.start_position => {
+                self.start_position + elapsed % (duration - self.start_position)
+            }
+            _ => self.start_position.saturating_add(elapsed),
+        }
+    }
 }
 
 impl CpalAudioBackend {
@@ -286,8 +331,16 @@ impl CpalAudioBackend {
             let mut output_frame = Stereo::<T::Signed>::equilibrium();
             for (_, sound) in sound_instances.iter_mut() {
                 if sound.active && !sound.signal.is_exhausted() {
-                    let sound_frame = sound.signal.next();
+                    let [left, right] = sound.signal.next();
+                    let (left, right) = (f32::from(left), f32::from(right));
+                    let [ll, lr, rl, rr] = sound.transform;
+                    // Float to int casts saturate, so loud transforms clip rather than wrap.
+                    let sound_frame: Stereo<i16> = [
+                        (left * ll + right * rl) as i16,
+                        (left * lr + right * rr) as i16,
+                    ];
                     let sound_frame: Stereo<T::Signed> = sound_frame.map(Sample::to_sample);
+                    sound.frames_played += 1;
                     output_frame = output_frame.add_amp(sound_frame);
                 } else {
                     sound.active = false;
@@ -339,12 +392,8 @@ impl AudioBackend for CpalAudioBackend {
         let signal = self.make_signal_from_stream(format, clip_data)?;
 
         let mut sound_instances = self.sound_instances.lock().unwrap();
-        let handle = sound_instances.insert(SoundInstance {
-            handle: None,
-            clip_id: Some(clip_id),
-            signal,
-            active: true,
-        });
+        let handle =
+            sound_instances.insert(SoundInstance::new(None, Some(clip_id), signal, 0, None));
         Ok(handle)
     }
 
@@ -374,14 +423,22 @@ impl AudioBackend for CpalAudioBackend {
             self.make_signal_from_event_sound(&sound, settings, data)?
         };
 
+        // `in_sample` is always given at 44.1kHz, regardless of the sound's sample rate.
+        let start_position = settings
+            .in_sample
+            .map(|n| (u64::from(n) * 1000 / 44100) as u32)
+            .unwrap_or(0);
+        let loop_duration = self.get_sound_duration(sound_handle);
+
         // Add sound instance to active list.
         let mut sound_instances = self.sound_instances.lock().unwrap();
-        let handle = sound_instances.insert(SoundInstance {
-            handle: Some(sound_handle),
-            clip_id: None,
+        let handle = sound_instances.insert(SoundInstance::new(
+            Some(sound_handle),
+            None,
             signal,
-            active: true,
-        });
+            start_position,
+            loop_duration,
+        ));
         Ok(handle)
     }
 
@@ -420,6 +477,28 @@ impl AudioBackend for CpalAudioBackend {
             .any(|(_, instance)| instance.handle == handle && instance.active)
     }
 
+    fn is_sound_playing(&mut self, instance: SoundInstanceHandle) -> bool {
+        let sound_instances = self.sound_instances.lock().unwrap();
+        sound_instances
+            .get(instance)
+            .map(|instance| instance.active)
+            .unwrap_or(false)
+    }
+
+    fn get_sound_position(&self, instance: SoundInstanceHandle) -> Option<u32> {
+        let sound_instances = self.sound_instances.lock().unwrap();
+        sound_instances
+            .get(instance)
+            .map(|instance| instance.position(self.output_format.sample_rate.0))
+    }
+
+    fn set_sound_transform(&mut self, instance: SoundInstanceHandle, transform: SoundTransform) {
+        let mut sound_instances = self.sound_instances.lock().unwrap();
+        if let Some(instance) = sound_instances.get_mut(instance) {
+            instance.transform = transform.mix_gains();
+        }
+    }
+
     fn tick(&mut self) {}
 }
 
@@ -459,24 +538,27 @@ impl EventSoundSignal {
         skip_sample_frames: u16,
     ) -> Self {
         let skip_sample_frames = u32::from(skip_sample_frames);
-        let sample_divisor = 44100 / u32::from(decoder.sample_rate());
+        // The in/out points are always given at 44.1kHz, regardless of the sound's sample rate.
+        let sample_rate = u64::from(decoder.sample_rate());
+        let to_sample_frame = |n: u32| (u64::from(n) * sample_rate / 44100) as u32;
         let start_sample_frame =
-            settings.in_sample.unwrap_or(0) / sample_divisor + skip_sample_frames;
+            settings.in_sample.map(to_sample_frame).unwrap_or(0) + skip_sample_frames;
         let end_sample_frame = settings
             .out_sample
-            .map(|n| n / sample_divisor)
+            .map(to_sample_frame)
             .unwrap_or(num_sample_frames)
             + skip_sample_frames;
 
         let envelope_signal = if let Some(envelope) = &settings.envelope {
-            Some(EnvelopeSignal::new(envelope.clone()))
+            Some(EnvelopeSignal::new(envelope.clone(), decoder.sample_rate()))
         } else {
             None
         };
 
         let mut signal = Self {
             decoder,
-            num_loops: settings.num_loops,
+            // A loop count of 0 still plays the sound once.
+            num_loops: settings.num_loops.max(1),
             envelope_signal,
             start_sample_frame,
             end_sample_frame: Some(end_sample_frame),
@@ -510,7 +592,7 @@ impl sample::signal::Signal for EventSoundSignal {
             let frame = if let Some(frame) = self.decoder.next() {
                 self.cur_sample_frame += 1;
                 if let Some(end) = self.end_sample_frame {
-                    if self.cur_sample_frame > end {
+                    if self.cur_sample_frame >= end {
                         self.next_loop();
                     }
                 }
@@ -549,10 +631,13 @@ struct EnvelopeSignal {
 
     /// The current sample index.
     cur_sample: u32,
+
+    /// The number of 44.1kHz envelope samples that each sample frame of the sound lasts.
+    sample_step: u32,
 }
 
 impl EnvelopeSignal {
-    fn new(envelope: swf::SoundEnvelope) -> Self {
+    fn new(envelope: swf::SoundEnvelope, sample_rate: u16) -> Self {
         // TODO: This maybe can be done more clever using the `sample` crate.
         let mut envelope = envelope.into_iter();
         let first_point = envelope.next().unwrap_or_else(|| swf::SoundEnvelopePoint {
@@ -569,6 +654,8 @@ impl EnvelopeSignal {
             },
             next_point: first_point,
             cur_sample: 0,
+            // Envelope points are always given at 44.1kHz, regardless of the sound's sample rate.
+            sample_step: (44100 / u32::from(sample_rate.max(1))).max(1),
             envelope,
         }
     }
@@ -593,7 +680,7 @@ impl sample::signal::Signal for EnvelopeSignal {
         };
 
         // Update envelope endpoints.
-        self.cur_sample = self.cur_sample.saturating_add(1);
+        self.cur_sample = self.cur_sample.saturating_add(self.sample_step);
         while self.cur_sample > self.next_point.sample {
             self.prev_point = self.next_point.clone();
             self.next_point =
@@ -619,3 +706,114 @@ impl sample::signal::Signal for EnvelopeSignal {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sample::signal::Signal as _;
+
+    /// A decoder for mono 16-bit PCM samples.
+    fn decoder(samples: &[i16], sample_rate: u16) -> Box<dyn SeekableDecoder + Send> {
+        let data: Vec<u8> = samples
+            .iter()
+            .flat_map(|s| s.to_le_bytes().to_vec())
+            .collect();
+        Box::new(PcmDecoder::new(Cursor::new(data), false, sample_rate, true))
+    }
+
+    fn sound_info(
+        in_sample: Option<u32>,
+        out_sample: Option<u32>,
+        num_loops: u16,
+    ) -> swf::SoundInfo {
+        swf::SoundInfo {
+            event: swf::SoundEvent::Event,
+            in_sample,
+            out_sample,
+            num_loops,
+            envelope: None,
+        }
+    }
+
+    /// Plays a signal until it is exhausted, and returns the left channel.
+    fn play(mut signal: EventSoundSignal) -> Vec<i16> {
+        let mut samples = vec![];
+        while !signal.is_exhausted() {
+            let [left, _right] = signal.next();
+            samples.push(left);
+            assert!(samples.len() <= 100, "The sound never ends");
+        }
+        samples
+    }
+
+    #[test]
+    fn event_sound_loops() {
+        let samples = [0, 1, 2, 3];
+        let settings = sound_info(None, None, 2);
+        let signal = EventSoundSignal::new_with_settings(decoder(&samples, 44100), &settings, 4, 0);
+        assert_eq!(play(signal), [0, 1, 2, 3, 0, 1, 2, 3]);
+
+        // A loop count of 0 plays the sound once.
+        let settings = sound_info(None, None, 0);
+        let signal = EventSoundSignal::new_with_settings(decoder(&samples, 44100), &settings, 4, 0);
+        assert_eq!(play(signal), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn event_sound_points() {
+        let samples = [0, 1, 2, 3, 4, 5, 6, 7];
+
+        // The in and out points are given at 44.1kHz, so they are scaled to the sample rate.
+        // The out point is the first sample frame that isn't played.
+        let settings = sound_info(Some(4), Some(16), 2);
+        let signal = EventSoundSignal::new_with_settings(decoder(&samples, 11025), &settings, 8, 0);
+        assert_eq!(play(signal), [1, 2, 3, 1, 2, 3]);
+
+        // The points don't include the skipped sample frames at the start of the sound.
+        let settings = sound_info(Some(1), Some(4), 1);
+        let signal = EventSoundSignal::new_with_settings(decoder(&samples, 44100), &settings, 6, 2);
+        assert_eq!(play(signal), [3, 4, 5]);
+        let settings = sound_info(None, None, 1);
+        let signal = EventSoundSignal::new_with_settings(decoder(&samples, 44100), &settings, 6, 2);
+        assert_eq!(play(signal), [2, 3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn envelope_interpolation() {
+        let envelope = vec![
+            swf::SoundEnvelopePoint {
+                sample: 0,
+                left_volume: 0.0,
+                right_volume: 1.0,
+            },
+            swf::SoundEnvelopePoint {
+                sample: 44,
+                left_volume: 1.0,
+                right_volume: 0.5,
+            },
+        ];
+
+        // At 11.025kHz, each sample frame lasts four envelope samples, so the second point
+        // is reached after 11 sample frames.
+        let mut signal = EnvelopeSignal::new(envelope.clone(), 11025);
+        for i in 0..16 {
+            let t = (i as f32 / 11.0).min(1.0);
+            let [left, right] = signal.next();
+            assert!((left - t).abs() < 1e-6, "left volume {} at {}", left, i);
+            assert!(
+                (right - (1.0 - t * 0.5)).abs() < 1e-6,
+                "right volume {} at {}",
+                right,
+                i
+            );
+        }
+
+        // At 44.1kHz, it takes 44 sample frames.
+        let mut signal = EnvelopeSignal::new(envelope, 44100);
+        for i in 0..48 {
+            let t = (i as f32 / 44.0).min(1.0);
+            let [left, _right] = signal.next();
+            assert!((left - t).abs() < 1e-6, "left volume {} at {}", left, i);
+        }
+    }
+}
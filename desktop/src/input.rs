@@ -66,6 +66,7 @@ impl InputBackend for WinitInputBackend {
         match key {
             KeyCode::Unknown => false,
             KeyCode::Backspace => self.keys_down.contains(&VirtualKeyCode::Back),
+            KeyCode::Tab => self.keys_down.contains(&VirtualKeyCode::Tab),
             KeyCode::Return => self.keys_down.contains(&VirtualKeyCode::Return),
             KeyCode::Shift => {
                 self.keys_down.contains(&VirtualKeyCode::LShift)
@@ -155,6 +156,7 @@ impl InputBackend for WinitInputBackend {
             KeyCode::Insert => self.keys_down.contains(&VirtualKeyCode::Insert),
             KeyCode::Delete => self.keys_down.contains(&VirtualKeyCode::Delete),
             KeyCode::Pause => self.keys_down.contains(&VirtualKeyCode::Pause),
+            KeyCode::NumLock => self.keys_down.contains(&VirtualKeyCode::Numlock),
             KeyCode::ScrollLock => self.keys_down.contains(&VirtualKeyCode::Scroll),
             KeyCode::F1 => self.keys_down.contains(&VirtualKeyCode::F1),
             KeyCode::F2 => self.keys_down.contains(&VirtualKeyCode::F2),
@@ -210,6 +212,7 @@ impl InputBackend for WinitInputBackend {
 fn winit_to_ruffle_key_code(key_code: VirtualKeyCode) -> Option<KeyCode> {
     let out = match key_code {
         VirtualKeyCode::Back => KeyCode::Backspace,
+        VirtualKeyCode::Tab => KeyCode::Tab,
         VirtualKeyCode::Return => KeyCode::Return,
         VirtualKeyCode::LShift | VirtualKeyCode::RShift => KeyCode::Shift,
         VirtualKeyCode::LControl | VirtualKeyCode::RControl => KeyCode::Control,
@@ -290,6 +293,7 @@ fn winit_to_ruffle_key_code(key_code: VirtualKeyCode) -> Option<KeyCode> {
         VirtualKeyCode::Insert => KeyCode::Insert,
         VirtualKeyCode::Delete => KeyCode::Delete,
         VirtualKeyCode::Pause => KeyCode::Pause,
+        VirtualKeyCode::Numlock => KeyCode::NumLock,
         VirtualKeyCode::Scroll => KeyCode::ScrollLock,
         VirtualKeyCode::F1 => KeyCode::F1,
         VirtualKeyCode::F2 => KeyCode::F2,
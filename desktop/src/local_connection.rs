@@ -0,0 +1,409 @@
+//! Local connection backend shared between player processes
+
+use ruffle_core::backend::local_connection::{
+    LocalConnectionBackend, LocalConnectionEvent, LocalConnectionMessage, MessageId,
+};
+use ruffle_core::swf::amf0::read::Reader;
+use ruffle_core::swf::amf0::types::Value as AmfValue;
+use ruffle_core::swf::amf0::write::Writer;
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// How long a receiver has to handle a message before the sender gives up on it.
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The largest message that will be accepted from another player.
+const MAX_MESSAGE_SIZE: u32 = 40 * 1024;
+
+/// A local connection backend that passes messages between players on the same machine.
+///
+/// Each backend listens for messages on its own port of the loopback interface. The
+/// connection names it listens on are registered as files in a shared directory, each one
+/// holding the port to send messages for that name to.
+pub struct CrossProcessLocalConnectionBackend {
+    /// The directory connection names are registered in.
+    directory: PathBuf,
+
+    /// The port messages to this backend are sent to, if it could start listening.
+    port: Option<u16>,
+
+    /// The connection names this backend listens on.
+    names: Vec<String>,
+
+    next_message_id: Arc<AtomicU64>,
+
+    /// The channels used to answer the senders of messages waiting for a response.
+    responders: HashMap<MessageId, Sender<bool>>,
+
+    events_sender: Sender<Incoming>,
+    events_receiver: Receiver<Incoming>,
+}
+
+/// Something reported by one of the backend's threads.
+enum Incoming {
+    Message(LocalConnectionMessage, Sender<bool>),
+    Status(MessageId, bool),
+}
+
+impl CrossProcessLocalConnectionBackend {
+    pub fn new() -> Self {
+        Self::with_directory(std::env::temp_dir().join("ruffle-local-connections"))
+    }
+
+    pub fn with_directory(directory: PathBuf) -> Self {
+        let (events_sender, events_receiver) = channel();
+        let next_message_id = Arc::new(AtomicU64::new(0));
+
+        let port = match TcpListener::bind((Ipv4Addr::LOCALHOST, 0)) {
+            Ok(listener) => {
+                let port = listener.local_addr().ok().map(|addr| addr.port());
+                let events = events_sender.clone();
+                let next_message_id = next_message_id.clone();
+                thread::spawn(move || listen(listener, events, next_message_id));
+                port
+            }
+            Err(e) => {
+                log::warn!("Unable to listen for local connections: {}", e);
+                None
+            }
+        };
+
+        Self {
+            directory,
+            port,
+            names: Vec::new(),
+            next_message_id,
+            responders: HashMap::new(),
+            events_sender,
+            events_receiver,
+        }
+    }
+
+    /// Returns the file that registers a connection name.
+    fn name_path(&self, name: &str) -> PathBuf {
+        let file_name: String = name.bytes().map(|b| format!("{:02x}", b)).collect();
+        self.directory.join(file_name)
+    }
+
+    /// Registers this backend as the listener of a connection name.
+    ///
+    /// A registration left behind by a player that is no longer running is replaced.
+    fn register(&self, path: &Path, port: u16) -> io::Result<bool> {
+        fs::create_dir_all(&self.directory)?;
+        for _ in 0..2 {
+            match OpenOptions::new().write(true).create_new(true).open(path) {
+                Ok(mut file) => {
+                    file.write_all(port.to_string().as_bytes())?;
+                    return Ok(true);
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    if registered_port(path).map_or(false, is_listening) {
+                        return Ok(false);
+                    }
+                    fs::remove_file(path)?;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(false)
+    }
+}
+
+impl Default for CrossProcessLocalConnectionBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocalConnectionBackend for CrossProcessLocalConnectionBackend {
+    fn connect(&mut self, name: &str) -> bool {
+        let port = match self.port {
+            Some(port) => port,
+            None => return false,
+        };
+        if self.names.iter().any(|n| n == name) {
+            return false;
+        }
+        match self.register(&self.name_path(name), port) {
+            Ok(true) => {
+                self.names.push(name.to_string());
+                true
+            }
+            Ok(false) => false,
+            Err(e) => {
+                log::warn!("Unable to register local connection {}: {}", name, e);
+                false
+            }
+        }
+    }
+
+    fn close(&mut self, name: &str) {
+        if let Some(index) = self.names.iter().position(|n| n == name) {
+            self.names.remove(index);
+            let _ = fs::remove_file(self.name_path(name));
+        }
+    }
+
+    fn send(
+        &mut self,
+        connection_name: &str,
+        method_name: &str,
+        sender_domain: &str,
+        args: Vec<AmfValue>,
+    ) -> MessageId {
+        let id = self.next_message_id.fetch_add(1, Ordering::Relaxed);
+        let message = AmfValue::StrictArray(vec![
+            AmfValue::String(connection_name.to_string()),
+            AmfValue::String(method_name.to_string()),
+            AmfValue::String(sender_domain.to_string()),
+            AmfValue::StrictArray(args),
+        ]);
+        let path = self.name_path(connection_name);
+        let events = self.events_sender.clone();
+        thread::spawn(move || {
+            let success = registered_port(&path)
+                .and_then(|port| send_message(port, &message).ok())
+                .unwrap_or(false);
+            let _ = events.send(Incoming::Status(id, success));
+        });
+        id
+    }
+
+    fn respond(&mut self, id: MessageId, success: bool) {
+        if let Some(responder) = self.responders.remove(&id) {
+            let _ = responder.send(success);
+        }
+    }
+
+    fn poll(&mut self) -> Vec<LocalConnectionEvent> {
+        let mut events = Vec::new();
+        for incoming in self.events_receiver.try_iter() {
+            match incoming {
+                Incoming::Message(message, responder) => {
+                    // The name may have been closed while the message was on its way.
+                    if !self.names.contains(&message.connection_name) {
+                        let _ = responder.send(false);
+                        continue;
+                    }
+                    self.responders.insert(message.id, responder);
+                    events.push(LocalConnectionEvent::Message(message));
+                }
+                Incoming::Status(id, success) => {
+                    events.push(LocalConnectionEvent::Status(id, success))
+                }
+            }
+        }
+        events
+    }
+}
+
+impl Drop for CrossProcessLocalConnectionBackend {
+    fn drop(&mut self) {
+        for name in std::mem::take(&mut self.names) {
+            let _ = fs::remove_file(self.name_path(&name));
+        }
+    }
+}
+
+/// Returns the port registered for a connection name, if any.
+fn registered_port(path: &Path) -> Option<u16> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Returns whether a player is still listening on a port.
+fn is_listening(port: u16) -> bool {
+    TcpStream::connect((Ipv4Addr::LOCALHOST, port)).is_ok()
+}
+
+/// Sends a message to the player listening on a port, and waits for its response.
+fn send_message(port: u16, message: &AmfValue) -> io::Result<bool> {
+    let mut data = Vec::new();
+    Writer::new(&mut data).write_value(message)?;
+
+    let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port))?;
+    stream.set_read_timeout(Some(RESPONSE_TIMEOUT))?;
+    stream.write_all(&(data.len() as u32).to_be_bytes())?;
+    stream.write_all(&data)?;
+
+    let mut response = [0];
+    stream.read_exact(&mut response)?;
+    Ok(response[0] == 1)
+}
+
+/// Accepts messages from other players.
+///
+/// Messages arriving after the backend is dropped are refused.
+fn listen(listener: TcpListener, events: Sender<Incoming>, next_message_id: Arc<AtomicU64>) {
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(_) => continue,
+        };
+        let events = events.clone();
+        let id = next_message_id.fetch_add(1, Ordering::Relaxed);
+        thread::spawn(move || {
+            if let Err(e) = receive_message(stream, id, &events) {
+                log::warn!("Unable to receive local connection message: {}", e);
+            }
+        });
+    }
+}
+
+/// Reads a message sent by another player, passes it on to the backend, then sends back
+/// whether it was handled.
+fn receive_message(
+    mut stream: TcpStream,
+    id: MessageId,
+    events: &Sender<Incoming>,
+) -> io::Result<()> {
+    let mut length = [0; 4];
+    if stream.read_exact(&mut length).is_err() {
+        // Players connect without sending anything to check whether a port is in use.
+        return Ok(());
+    }
+    let length = u32::from_be_bytes(length);
+    if length > MAX_MESSAGE_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "message too large",
+        ));
+    }
+    let mut data = vec![0; length as usize];
+    stream.read_exact(&mut data)?;
+
+    let message = match Reader::new(&data).read_value() {
+        Ok(AmfValue::StrictArray(values)) => parse_message(id, values),
+        _ => None,
+    };
+    let success = match message {
+        Some(message) => {
+            let (responder, response) = channel();
+            if events.send(Incoming::Message(message, responder)).is_err() {
+                return Ok(());
+            }
+            response.recv().unwrap_or(false)
+        }
+        None => false,
+    };
+    stream.write_all(&[success as u8])
+}
+
+fn parse_message(id: MessageId, values: Vec<AmfValue>) -> Option<LocalConnectionMessage> {
+    let mut values = values.into_iter();
+    match (values.next(), values.next(), values.next(), values.next()) {
+        (
+            Some(AmfValue::String(connection_name)),
+            Some(AmfValue::String(method_name)),
+            Some(AmfValue::String(sender_domain)),
+            Some(AmfValue::StrictArray(args)),
+        ) => Some(LocalConnectionMessage {
+            id,
+            connection_name,
+            method_name,
+            sender_domain,
+            args,
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    /// Polls the backend until it reports at least `count` events, answering every message.
+    fn poll_events(
+        backend: &mut CrossProcessLocalConnectionBackend,
+        count: usize,
+    ) -> Vec<LocalConnectionEvent> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut events = Vec::new();
+        while events.len() < count && Instant::now() < deadline {
+            events.extend(backend.poll());
+            thread::sleep(Duration::from_millis(10));
+        }
+        events
+    }
+
+    fn test_directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!(
+            "ruffle-local-connections-test-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&directory);
+        directory
+    }
+
+    #[test]
+    fn messages_between_backends() {
+        let directory = test_directory("messages");
+        let mut sender = CrossProcessLocalConnectionBackend::with_directory(directory.clone());
+        let mut receiver = CrossProcessLocalConnectionBackend::with_directory(directory.clone());
+
+        assert!(receiver.connect("localhost:content"));
+        assert!(!sender.connect("localhost:content"));
+
+        let args = vec![AmfValue::Number(1.0), AmfValue::String("a".to_string())];
+        let id = sender.send("localhost:content", "show", "localhost", args.clone());
+        let events = poll_events(&mut receiver, 1);
+        let message = match &events[..] {
+            [LocalConnectionEvent::Message(message)] => message.clone(),
+            events => panic!("unexpected events {:?}", events),
+        };
+        assert_eq!(message.connection_name, "localhost:content");
+        assert_eq!(message.method_name, "show");
+        assert_eq!(message.sender_domain, "localhost");
+        assert_eq!(message.args, args);
+
+        receiver.respond(message.id, true);
+        assert_eq!(
+            poll_events(&mut sender, 1),
+            vec![LocalConnectionEvent::Status(id, true)]
+        );
+
+        let missing = sender.send("localhost:other", "show", "localhost", vec![]);
+        assert_eq!(
+            poll_events(&mut sender, 1),
+            vec![LocalConnectionEvent::Status(missing, false)]
+        );
+
+        let _ = fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn closed_and_dropped_backends_release_names() {
+        let directory = test_directory("release");
+        let mut first = CrossProcessLocalConnectionBackend::with_directory(directory.clone());
+        let mut second = CrossProcessLocalConnectionBackend::with_directory(directory.clone());
+
+        assert!(first.connect("_status"));
+        first.close("_status");
+        assert!(second.connect("_status"));
+        drop(second);
+        assert!(first.connect("_status"));
+
+        // A registration left behind by a player that isn't listening anymore is replaced.
+        first.close("_status");
+        fs::create_dir_all(&directory).unwrap();
+        let port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        fs::write(first.name_path("_stale"), port.to_string()).unwrap();
+        assert!(first.connect("_stale"));
+
+        drop(first);
+        let _ = fs::remove_dir_all(&directory);
+    }
+}
@@ -5,26 +5,32 @@ mod audio;
 mod custom_event;
 mod executor;
 mod input;
+mod local_connection;
 mod navigator;
+mod socket;
 mod storage;
 mod task;
 
 use crate::custom_event::RuffleEvent;
 use crate::executor::GlutinAsyncExecutor;
+use crate::navigator::{FetchPolicy, SandboxType, UrlRewrite};
 use ruffle_core::{
     backend::audio::{AudioBackend, NullAudioBackend},
-    Player,
+    backend::video::software::SoftwareVideoBackend,
+    MouseWheelDelta, Player, StageScaleMode,
 };
 use ruffle_render_wgpu::WgpuRenderBackend;
 use std::path::PathBuf;
 use std::time::Instant;
 use structopt::StructOpt;
 
+use crate::local_connection::CrossProcessLocalConnectionBackend;
+use crate::socket::TcpSocketBackend;
 use crate::storage::DiskStorageBackend;
 use ruffle_core::tag_utils::SwfMovie;
 use std::rc::Rc;
 use winit::dpi::{LogicalSize, PhysicalPosition};
-use winit::event::{ElementState, MouseButton, WindowEvent};
+use winit::event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent};
 use winit::event_loop::{ControlFlow, EventLoop};
 use winit::window::{Icon, WindowBuilder};
 
@@ -33,6 +39,29 @@ use winit::window::{Icon, WindowBuilder};
 struct Opt {
     #[structopt(name = "FILE", parse(from_os_str))]
     input_path: PathBuf,
+
+    /// Security sandbox to play the movie in: local-with-file, local-with-network,
+    /// local-trusted or remote. Defaults to the sandbox requested by the movie.
+    #[structopt(long)]
+    sandbox: Option<SandboxType>,
+
+    /// Only allow network loads from URLs starting with this prefix. May be repeated.
+    #[structopt(long = "allow-url", value_name = "PREFIX")]
+    allowed_urls: Vec<String>,
+
+    /// Load URLs starting with FROM from TO instead, written as FROM=TO. May be repeated.
+    #[structopt(long = "rewrite-url", value_name = "FROM=TO")]
+    url_rewrites: Vec<UrlRewrite>,
+
+    /// How the movie is scaled to fit the window: show-all, no-border, exact-fit or no-scale.
+    /// The movie may change this with `Stage.scaleMode`.
+    #[structopt(long, default_value = "show-all")]
+    scale_mode: StageScaleMode,
+
+    /// Show the content outside of the stage instead of covering it with black bars, like
+    /// Flash Player does.
+    #[structopt(long)]
+    no_letterbox: bool,
 }
 
 fn main() {
@@ -40,7 +69,7 @@ fn main() {
 
     let opt = Opt::from_args();
 
-    let ret = run_player(opt.input_path);
+    let ret = run_player(opt);
 
     if let Err(e) = ret {
         eprintln!("Fatal error:\n{}", e);
@@ -48,8 +77,21 @@ fn main() {
     }
 }
 
-fn run_player(input_path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+fn run_player(opt: Opt) -> Result<(), Box<dyn std::error::Error>> {
+    let input_path = opt.input_path;
     let movie = SwfMovie::from_path(&input_path)?;
+    let sandbox_type = opt.sandbox.unwrap_or_else(|| {
+        if movie.use_network_sandbox() {
+            SandboxType::LocalWithNetwork
+        } else {
+            SandboxType::LocalWithFile
+        }
+    });
+    let fetch_policy = FetchPolicy {
+        sandbox_type,
+        allowed_urls: opt.allowed_urls,
+        url_rewrites: opt.url_rewrites,
+    };
     let movie_size = LogicalSize::new(movie.width(), movie.height());
 
     let icon_bytes = include_bytes!("../assets/favicon-32.rgba");
@@ -84,6 +126,7 @@ fn run_player(input_path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
         input_path
             .parent()
             .unwrap_or_else(|| std::path::Path::new("")),
+        fetch_policy,
         chan,
         event_loop.create_proxy(),
     )); //TODO: actually implement this backend type
@@ -91,8 +134,23 @@ fn run_player(input_path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
     let storage = Box::new(DiskStorageBackend::new(
         input_path.file_name().unwrap_or_default().as_ref(),
     ));
-    let player = Player::new(renderer, audio, navigator, input, movie, storage)?;
+    let video = Box::new(SoftwareVideoBackend::new());
+    let sockets = Box::new(TcpSocketBackend::new(sandbox_type.allows_network()));
+    let local_connection = Box::new(CrossProcessLocalConnectionBackend::new());
+    let player = Player::new(
+        renderer,
+        audio,
+        navigator,
+        input,
+        movie,
+        storage,
+        video,
+        sockets,
+        local_connection,
+    )?;
     player.lock().unwrap().set_is_playing(true); // Desktop player will auto-play.
+    player.lock().unwrap().set_scale_mode(opt.scale_mode);
+    player.lock().unwrap().set_letterbox(!opt.no_letterbox);
 
     player
         .lock()
@@ -172,6 +230,17 @@ fn run_player(input_path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
                             window.request_redraw();
                         }
                     }
+                    WindowEvent::MouseWheel { delta, .. } => {
+                        let mut player_lock = player.lock().unwrap();
+                        let delta = match delta {
+                            MouseScrollDelta::LineDelta(_, dy) => MouseWheelDelta::Lines(dy.into()),
+                            MouseScrollDelta::PixelDelta(pos) => MouseWheelDelta::Pixels(pos.y),
+                        };
+                        player_lock.handle_event(ruffle_core::PlayerEvent::MouseWheel { delta });
+                        if player_lock.needs_render() {
+                            window.request_redraw();
+                        }
+                    }
                     WindowEvent::CursorLeft { .. } => {
                         let mut player_lock = player.lock().unwrap();
                         player_lock.handle_event(ruffle_core::PlayerEvent::MouseLeft);
@@ -1,18 +1,164 @@
 //! Navigator backend for web
 
 use crate::custom_event::RuffleEvent;
+use chrono::{Local, TimeZone, Utc};
+use futures::io::AsyncReadExt;
+use isahc::config::RedirectPolicy;
+use isahc::prelude::*;
 use ruffle_core::backend::navigator::{
-    NavigationMethod, NavigatorBackend, OwnedFuture, RequestOptions,
+    NavigationMethod, NavigatorBackend, OwnedFuture, RequestOptions, Response,
 };
 use ruffle_core::loader::Error;
 use std::collections::HashMap;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
 use std::sync::mpsc::Sender;
 use std::time::{Duration, Instant};
 use url::Url;
 use winit::event_loop::EventLoopProxy;
 
+/// The maximum number of HTTP redirects followed by a single fetch.
+const MAX_REDIRECTS: u32 = 10;
+
+/// The Flash Player security sandbox a movie plays in, which decides the
+/// kinds of URLs it is allowed to load data from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SandboxType {
+    /// Local movies that may only read from the local filesystem.
+    LocalWithFile,
+
+    /// Local movies that may only load from the network.
+    LocalWithNetwork,
+
+    /// Local movies trusted by the user, which may load from anywhere.
+    LocalTrusted,
+
+    /// Movies served from the network, which may only load from the network.
+    Remote,
+}
+
+impl SandboxType {
+    fn allows_filesystem(self) -> bool {
+        match self {
+            SandboxType::LocalWithFile | SandboxType::LocalTrusted => true,
+            SandboxType::LocalWithNetwork | SandboxType::Remote => false,
+        }
+    }
+
+    pub fn allows_network(self) -> bool {
+        match self {
+            SandboxType::LocalWithNetwork | SandboxType::LocalTrusted | SandboxType::Remote => true,
+            SandboxType::LocalWithFile => false,
+        }
+    }
+}
+
+impl FromStr for SandboxType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "local-with-file" | "localWithFile" => Ok(SandboxType::LocalWithFile),
+            "local-with-network" | "localWithNetwork" => Ok(SandboxType::LocalWithNetwork),
+            "local-trusted" | "localTrusted" => Ok(SandboxType::LocalTrusted),
+            "remote" => Ok(SandboxType::Remote),
+            _ => Err(format!(
+                "Unknown sandbox type {}, expected one of local-with-file, local-with-network, local-trusted or remote",
+                s
+            )),
+        }
+    }
+}
+
+/// Rewrites URLs starting with one prefix to start with another instead.
+///
+/// Written on the command line as `FROM=TO`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UrlRewrite {
+    from: String,
+    to: String,
+}
+
+impl FromStr for UrlRewrite {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(2, '=');
+        match (parts.next(), parts.next()) {
+            (Some(from), Some(to)) if !from.is_empty() => Ok(UrlRewrite {
+                from: from.to_string(),
+                to: to.to_string(),
+            }),
+            _ => Err(format!("Invalid URL rewrite {}, expected FROM=TO", s)),
+        }
+    }
+}
+
+/// Decides which URLs a movie may load from and where those loads really go.
+#[derive(Clone, Debug)]
+pub struct FetchPolicy {
+    /// The sandbox the movie plays in.
+    pub sandbox_type: SandboxType,
+
+    /// URL prefixes that network loads are restricted to.
+    ///
+    /// If empty, any network URL permitted by the sandbox may be loaded.
+    pub allowed_urls: Vec<String>,
+
+    /// Rewrites applied to every URL before the sandbox rules are checked.
+    pub url_rewrites: Vec<UrlRewrite>,
+}
+
+impl FetchPolicy {
+    /// A policy that allows everything the given sandbox allows.
+    pub fn new(sandbox_type: SandboxType) -> Self {
+        Self {
+            sandbox_type,
+            allowed_urls: Vec::new(),
+            url_rewrites: Vec::new(),
+        }
+    }
+
+    /// Resolve a URL requested by the movie against the movie's base URL,
+    /// returning the URL to actually load if the load is permitted.
+    pub fn resolve(&self, base_url: &Url, url: &str) -> Result<Url, Error> {
+        let mut resolved = base_url
+            .join(url)
+            .map_err(|e| Error::NetworkError(io::Error::new(io::ErrorKind::InvalidInput, e)))?;
+
+        if let Some(rewrite) = self
+            .url_rewrites
+            .iter()
+            .find(|rewrite| resolved.as_str().starts_with(&rewrite.from))
+        {
+            let rewritten = format!("{}{}", rewrite.to, &resolved.as_str()[rewrite.from.len()..]);
+            resolved = Url::parse(&rewritten)
+                .map_err(|e| Error::NetworkError(io::Error::new(io::ErrorKind::InvalidInput, e)))?;
+        }
+
+        let allowed = match resolved.scheme() {
+            "file" => self.sandbox_type.allows_filesystem(),
+            "http" | "https" => {
+                self.sandbox_type.allows_network()
+                    && (self.allowed_urls.is_empty()
+                        || self
+                            .allowed_urls
+                            .iter()
+                            .any(|prefix| resolved.as_str().starts_with(prefix.as_str())))
+            }
+            _ => false,
+        };
+
+        if allowed {
+            Ok(resolved)
+        } else {
+            Err(Error::SandboxViolation(resolved.to_string()))
+        }
+    }
+}
+
 /// Implementation of `NavigatorBackend` for non-web environments that can call
 /// out to a web browser.
 pub struct ExternalNavigatorBackend {
@@ -22,8 +168,14 @@ pub struct ExternalNavigatorBackend {
     /// Event sink to trigger a new task poll.
     event_loop: EventLoopProxy<RuffleEvent>,
 
-    /// The base path for all relative fetches.
-    relative_base_path: PathBuf,
+    /// The base URL for all relative fetches.
+    base_url: Url,
+
+    /// The sandbox rules that fetches must follow.
+    policy: FetchPolicy,
+
+    /// The client used for network fetches, if one could be created.
+    client: Option<HttpClient>,
 
     /// The time that the SWF was launched.
     start_time: Instant,
@@ -35,33 +187,96 @@ impl ExternalNavigatorBackend {
         channel: Sender<OwnedFuture<(), Error>>,
         event_loop: EventLoopProxy<RuffleEvent>,
     ) -> Self {
-        Self {
+        Self::with_base_path(
+            "",
+            FetchPolicy::new(SandboxType::LocalWithFile),
             channel,
             event_loop,
-            relative_base_path: PathBuf::new(),
-            start_time: Instant::now(),
-        }
+        )
     }
 
     /// Construct a navigator backend with fetch and async capability.
     pub fn with_base_path<P: AsRef<Path>>(
         path: P,
+        policy: FetchPolicy,
         channel: Sender<OwnedFuture<(), Error>>,
         event_loop: EventLoopProxy<RuffleEvent>,
     ) -> Self {
-        let mut relative_base_path = PathBuf::new();
+        let base_url = std::env::current_dir()
+            .ok()
+            .and_then(|dir| Url::from_directory_path(dir.join(path)).ok())
+            .unwrap_or_else(|| Url::parse("file:///").unwrap());
 
-        relative_base_path.push(path);
+        let client = HttpClient::builder()
+            .redirect_policy(RedirectPolicy::Limit(MAX_REDIRECTS))
+            .build();
+        let client = match client {
+            Ok(client) => Some(client),
+            Err(e) => {
+                log::error!(
+                    "Unable to create HTTP client, network loads will fail: {}",
+                    e
+                );
+                None
+            }
+        };
 
         Self {
             channel,
             event_loop,
-            relative_base_path,
+            base_url,
+            policy,
+            client,
             start_time: Instant::now(),
         }
     }
 }
 
+/// Perform an HTTP request, returning the response if the server reported
+/// success.
+async fn fetch_http(
+    client: HttpClient,
+    url: Url,
+    options: RequestOptions,
+) -> Result<Response, Error> {
+    let mut request = Request::builder()
+        .uri(url.as_str())
+        .method(match options.method() {
+            NavigationMethod::GET => "GET",
+            NavigationMethod::POST => "POST",
+        });
+    for (name, value) in options.headers() {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    let body = match options.body() {
+        Some((data, mime)) => {
+            request = request.header("Content-Type", mime.as_str());
+            Body::from(data.clone())
+        }
+        None => Body::empty(),
+    };
+    let request = request
+        .body(body)
+        .map_err(|e| Error::NetworkError(io::Error::new(io::ErrorKind::InvalidInput, e)))?;
+
+    let mut response = client
+        .send_async(request)
+        .await
+        .map_err(|e| Error::NetworkError(io::Error::new(io::ErrorKind::Other, e)))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(Error::HttpNotOk(url.to_string(), status.as_u16()));
+    }
+
+    let mut body = Vec::new();
+    response.body_mut().read_to_end(&mut body).await?;
+    Ok(Response {
+        status: status.as_u16(),
+        body,
+    })
+}
+
 impl NavigatorBackend for ExternalNavigatorBackend {
     fn navigate_to_url(
         &self,
@@ -111,13 +326,44 @@ impl NavigatorBackend for ExternalNavigatorBackend {
         Instant::now().duration_since(self.start_time)
     }
 
-    fn fetch(&self, url: &str, _options: RequestOptions) -> OwnedFuture<Vec<u8>, Error> {
-        // Load from local filesystem.
-        // TODO: Support network loads, honor sandbox type (local-with-filesystem, local-with-network, remote, ...)
-        let mut path = self.relative_base_path.clone();
-        path.push(url);
+    fn current_time(&self) -> f64 {
+        Utc::now().timestamp_millis() as f64
+    }
 
-        Box::pin(async move { fs::read(path).map_err(Error::NetworkError) })
+    fn local_timezone_offset(&self, time: f64) -> i32 {
+        Local
+            .timestamp_millis_opt(time as i64)
+            .single()
+            .map(|date| date.offset().local_minus_utc() / 60)
+            .unwrap_or(0)
+    }
+
+    fn fetch(&self, url: &str, options: RequestOptions) -> OwnedFuture<Response, Error> {
+        let url = match self.policy.resolve(&self.base_url, url) {
+            Ok(url) => url,
+            Err(e) => return Box::pin(async move { Err(e) }),
+        };
+
+        if url.scheme() == "file" {
+            let path = url.to_file_path();
+            return Box::pin(async move {
+                let path = path
+                    .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Invalid file URL"))?;
+                fs::read(path)
+                    .map(Response::ok)
+                    .map_err(Error::NetworkError)
+            });
+        }
+
+        match self.client.clone() {
+            Some(client) => Box::pin(fetch_http(client, url, options)),
+            None => Box::pin(async move {
+                Err(Error::NetworkError(io::Error::new(
+                    io::ErrorKind::Other,
+                    "No HTTP client available",
+                )))
+            }),
+        }
     }
 
     fn spawn_future(&mut self, future: OwnedFuture<(), Error>) {
@@ -130,3 +376,159 @@ impl NavigatorBackend for ExternalNavigatorBackend {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Serve a single HTTP request with the given status line and body,
+    /// returning the raw request that was received.
+    fn serve_once(status: &'static str, body: &'static str) -> (Url, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(length) = line.to_lowercase().strip_prefix("content-length:") {
+                    content_length = length.trim().parse().unwrap();
+                }
+                request.push_str(&line);
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let mut request_body = vec![0; content_length];
+            reader.read_exact(&mut request_body).unwrap();
+            request.push_str(&String::from_utf8(request_body).unwrap());
+
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+            request
+        });
+        (url, server)
+    }
+
+    /// Lowercase a request and strip spaces after header names, since the
+    /// exact formatting of headers depends on the HTTP client.
+    fn normalize_headers(request: &str) -> String {
+        request.to_lowercase().replace(": ", ":")
+    }
+
+    fn client() -> HttpClient {
+        HttpClient::builder()
+            .redirect_policy(RedirectPolicy::Limit(MAX_REDIRECTS))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn sandbox_rules() {
+        let base_url = Url::parse("file:///movies/").unwrap();
+
+        let policy = FetchPolicy::new(SandboxType::LocalWithFile);
+        assert_eq!(
+            policy.resolve(&base_url, "data.txt").unwrap().as_str(),
+            "file:///movies/data.txt"
+        );
+        assert!(policy.resolve(&base_url, "http://example.com/").is_err());
+
+        let policy = FetchPolicy::new(SandboxType::LocalWithNetwork);
+        assert!(policy.resolve(&base_url, "data.txt").is_err());
+        assert!(policy.resolve(&base_url, "http://example.com/").is_ok());
+        assert!(policy.resolve(&base_url, "ftp://example.com/").is_err());
+
+        let policy = FetchPolicy::new(SandboxType::LocalTrusted);
+        assert!(policy.resolve(&base_url, "data.txt").is_ok());
+        assert!(policy.resolve(&base_url, "https://example.com/").is_ok());
+
+        let base_url = Url::parse("http://example.com/movies/").unwrap();
+        let policy = FetchPolicy::new(SandboxType::Remote);
+        assert_eq!(
+            policy.resolve(&base_url, "data.txt").unwrap().as_str(),
+            "http://example.com/movies/data.txt"
+        );
+        assert!(policy.resolve(&base_url, "file:///etc/passwd").is_err());
+    }
+
+    #[test]
+    fn allowlist_and_rewrites() {
+        let base_url = Url::parse("file:///movies/").unwrap();
+        let policy = FetchPolicy {
+            sandbox_type: SandboxType::LocalWithNetwork,
+            allowed_urls: vec!["http://localhost:8000/".to_string()],
+            url_rewrites: vec!["http://example.com/=http://localhost:8000/"
+                .parse()
+                .unwrap()],
+        };
+
+        assert_eq!(
+            policy
+                .resolve(&base_url, "http://example.com/api?a=1")
+                .unwrap()
+                .as_str(),
+            "http://localhost:8000/api?a=1"
+        );
+        assert!(policy.resolve(&base_url, "http://localhost:8000/x").is_ok());
+        assert!(policy.resolve(&base_url, "http://other.com/").is_err());
+
+        assert!("no-separator".parse::<UrlRewrite>().is_err());
+        assert_eq!(
+            "local-with-network".parse::<SandboxType>(),
+            Ok(SandboxType::LocalWithNetwork)
+        );
+    }
+
+    #[test]
+    fn http_get() {
+        let (url, server) = serve_once("200 OK", "a=1&b=2");
+        let options = RequestOptions::get().with_headers(vec![("X-Test".into(), "yes".into())]);
+        let data = futures::executor::block_on(fetch_http(client(), url, options)).unwrap();
+        assert_eq!(data, b"a=1&b=2");
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET / HTTP/1.1\r\n"));
+        assert!(normalize_headers(&request).contains("x-test:yes\r\n"));
+    }
+
+    #[test]
+    fn http_post() {
+        let (url, server) = serve_once("200 OK", "ok");
+        let options = RequestOptions::post(Some((
+            b"name=value".to_vec(),
+            "application/x-www-form-urlencoded".to_string(),
+        )));
+        let data = futures::executor::block_on(fetch_http(client(), url, options)).unwrap();
+        assert_eq!(data, b"ok");
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST / HTTP/1.1\r\n"));
+        assert!(normalize_headers(&request)
+            .contains("content-type:application/x-www-form-urlencoded\r\n"));
+        assert!(request.ends_with("\r\n\r\nname=value"));
+    }
+
+    #[test]
+    fn http_error_status() {
+        let (url, server) = serve_once("404 Not Found", "");
+        let result = futures::executor::block_on(fetch_http(client(), url, RequestOptions::get()));
+        match result {
+            Err(Error::HttpNotOk(_, status)) => assert_eq!(status, 404),
+            _ => panic!("Expected an HTTP error"),
+        }
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,210 @@
+//! TCP socket backend
+
+use generational_arena::Arena;
+use ruffle_core::backend::socket::{SocketBackend, SocketEvent, SocketHandle};
+use std::io::{Read, Write};
+use std::net::{Shutdown, TcpStream};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+
+/// The size of the buffer used to read from sockets.
+const READ_BUFFER_SIZE: usize = 4096;
+
+/// A socket backend that connects to servers over TCP.
+///
+/// Each connection runs on its own thread, which reports back through a channel that is
+/// drained whenever the player polls the backend.
+pub struct TcpSocketBackend {
+    /// The channel used to send outgoing data to each connection's thread.
+    sockets: Arena<Sender<Vec<u8>>>,
+
+    events_sender: Sender<SocketEvent>,
+    events_receiver: Receiver<SocketEvent>,
+
+    /// Whether the sandbox of the movie allows network access at all.
+    allow_network: bool,
+}
+
+impl TcpSocketBackend {
+    pub fn new(allow_network: bool) -> Self {
+        let (events_sender, events_receiver) = channel();
+        Self {
+            sockets: Arena::new(),
+            events_sender,
+            events_receiver,
+            allow_network,
+        }
+    }
+}
+
+impl SocketBackend for TcpSocketBackend {
+    fn connect(&mut self, host: &str, port: u16) -> SocketHandle {
+        let (data_sender, data_receiver) = channel();
+        let handle = self.sockets.insert(data_sender);
+
+        if !self.allow_network {
+            log::warn!(
+                "Blocked connection to {}:{} from a local-with-file movie",
+                host,
+                port
+            );
+            let _ = self.events_sender.send(SocketEvent::Connect(handle, false));
+            return handle;
+        }
+
+        let host = host.to_string();
+        let events = self.events_sender.clone();
+        thread::spawn(move || run_connection(handle, host, port, data_receiver, events));
+
+        handle
+    }
+
+    fn send(&mut self, handle: SocketHandle, data: Vec<u8>) {
+        if let Some(sender) = self.sockets.get(handle) {
+            let _ = sender.send(data);
+        }
+    }
+
+    fn close(&mut self, handle: SocketHandle) {
+        // Dropping the sender stops the connection's thread, which then shuts the socket down.
+        self.sockets.remove(handle);
+    }
+
+    fn poll(&mut self) -> Vec<SocketEvent> {
+        self.events_receiver
+            .try_iter()
+            .filter(|event| match event {
+                SocketEvent::Connect(handle, _)
+                | SocketEvent::Data(handle, _)
+                | SocketEvent::Close(handle) => self.sockets.contains(*handle),
+            })
+            .collect()
+    }
+}
+
+/// Connects to a server, then writes outgoing data until the connection is closed.
+///
+/// Incoming data is read on a second thread.
+fn run_connection(
+    handle: SocketHandle,
+    host: String,
+    port: u16,
+    data_receiver: Receiver<Vec<u8>>,
+    events: Sender<SocketEvent>,
+) {
+    let mut stream = match TcpStream::connect((host.as_str(), port)) {
+        Ok(stream) => stream,
+        Err(e) => {
+            log::warn!("Unable to connect to {}:{}: {}", host, port, e);
+            let _ = events.send(SocketEvent::Connect(handle, false));
+            return;
+        }
+    };
+    let reader = match stream.try_clone() {
+        Ok(reader) => reader,
+        Err(e) => {
+            log::warn!("Unable to read from {}:{}: {}", host, port, e);
+            let _ = events.send(SocketEvent::Connect(handle, false));
+            return;
+        }
+    };
+    let _ = events.send(SocketEvent::Connect(handle, true));
+
+    let reader_events = events.clone();
+    thread::spawn(move || read_connection(handle, reader, reader_events));
+
+    for data in data_receiver {
+        if let Err(e) = stream.write_all(&data) {
+            log::warn!("Unable to write to {}:{}: {}", host, port, e);
+            break;
+        }
+    }
+    let _ = stream.shutdown(Shutdown::Both);
+}
+
+/// Reads incoming data until the connection is closed.
+fn read_connection(handle: SocketHandle, mut stream: TcpStream, events: Sender<SocketEvent>) {
+    let mut buffer = [0; READ_BUFFER_SIZE];
+    loop {
+        match stream.read(&mut buffer) {
+            Ok(0) | Err(_) => break,
+            Ok(len) => {
+                if events
+                    .send(SocketEvent::Data(handle, buffer[..len].to_vec()))
+                    .is_err()
+                {
+                    return;
+                }
+            }
+        }
+    }
+    let _ = events.send(SocketEvent::Close(handle));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::time::{Duration, Instant};
+
+    /// Polls the backend until it reports at least `count` events.
+    fn poll_events(backend: &mut TcpSocketBackend, count: usize) -> Vec<SocketEvent> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut events = Vec::new();
+        while events.len() < count && Instant::now() < deadline {
+            events.extend(backend.poll());
+            thread::sleep(Duration::from_millis(10));
+        }
+        events
+    }
+
+    #[test]
+    fn connect_send_and_close() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut message = [0; 6];
+            stream.read_exact(&mut message).unwrap();
+            stream.write_all(b"<ok/>\0").unwrap();
+            message
+        });
+
+        let mut backend = TcpSocketBackend::new(true);
+        let handle = backend.connect("127.0.0.1", port);
+        assert_eq!(
+            poll_events(&mut backend, 1),
+            vec![SocketEvent::Connect(handle, true)]
+        );
+
+        backend.send(handle, b"<hi/>\0".to_vec());
+        assert_eq!(&server.join().unwrap(), b"<hi/>\0");
+        assert_eq!(
+            poll_events(&mut backend, 2),
+            vec![
+                SocketEvent::Data(handle, b"<ok/>\0".to_vec()),
+                SocketEvent::Close(handle)
+            ]
+        );
+    }
+
+    #[test]
+    fn connection_failures() {
+        // Nothing is listening on this port once the listener is dropped.
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let mut backend = TcpSocketBackend::new(true);
+        let handle = backend.connect("127.0.0.1", port);
+        assert_eq!(
+            poll_events(&mut backend, 1),
+            vec![SocketEvent::Connect(handle, false)]
+        );
+
+        let mut backend = TcpSocketBackend::new(false);
+        let handle = backend.connect("127.0.0.1", port);
+        assert_eq!(backend.poll(), vec![SocketEvent::Connect(handle, false)]);
+    }
+}
@@ -1,7 +1,7 @@
 use ruffle_core::backend::storage::StorageBackend;
 use std::fs;
 use std::fs::File;
-use std::io::{Read, Write};
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
 pub struct DiskStorageBackend {
@@ -28,32 +28,31 @@ impl DiskStorageBackend {
 }
 
 impl StorageBackend for DiskStorageBackend {
-    fn get_string(&self, name: &str) -> Option<String> {
-        let full_path = self.base_path.join(Path::new(name));
+    fn get(&self, name: &str) -> Option<Vec<u8>> {
+        let full_path = self.base_path.join(Path::new(&format!("{}.sol", name)));
 
-        match File::open(full_path) {
-            Ok(mut file) => {
-                let mut buffer = String::new();
-                if let Err(r) = file.read_to_string(&mut buffer) {
-                    log::warn!("Unable to read file content {:?}", r);
-                    None
-                } else {
-                    Some(buffer)
-                }
-            }
+        // Older versions saved shared objects without an extension.
+        let full_path = if full_path.exists() {
+            full_path
+        } else {
+            self.base_path.join(Path::new(name))
+        };
+
+        match fs::read(full_path) {
+            Ok(data) => Some(data),
             Err(r) => {
-                log::warn!("Unable to open file {:?}", r);
+                log::warn!("Unable to read file {:?}", r);
                 None
             }
         }
     }
 
-    fn put_string(&mut self, name: &str, value: String) -> bool {
-        let full_path = self.base_path.join(Path::new(name));
+    fn put(&mut self, name: &str, value: &[u8]) -> bool {
+        let full_path = self.base_path.join(Path::new(&format!("{}.sol", name)));
 
         match File::create(full_path) {
             Ok(mut file) => {
-                if let Err(r) = file.write_all(value.as_bytes()) {
+                if let Err(r) = file.write_all(value) {
                     log::warn!("Unable to write file content {:?}", r);
                     false
                 } else {
@@ -68,7 +67,7 @@ impl StorageBackend for DiskStorageBackend {
     }
 
     fn remove_key(&mut self, name: &str) {
-        let full_path = self.base_path.join(Path::new(name));
-        let _ = fs::remove_file(full_path);
+        let _ = fs::remove_file(self.base_path.join(Path::new(&format!("{}.sol", name))));
+        let _ = fs::remove_file(self.base_path.join(Path::new(name)));
     }
 }
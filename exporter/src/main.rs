@@ -3,8 +3,11 @@ use image::RgbaImage;
 use indicatif::{ProgressBar, ProgressStyle};
 use ruffle_core::backend::audio::NullAudioBackend;
 use ruffle_core::backend::input::NullInputBackend;
+use ruffle_core::backend::local_connection::InProcessLocalConnectionBackend;
 use ruffle_core::backend::navigator::NullNavigatorBackend;
+use ruffle_core::backend::socket::NullSocketBackend;
 use ruffle_core::backend::storage::MemoryStorageBackend;
+use ruffle_core::backend::video::software::SoftwareVideoBackend;
 use ruffle_core::tag_utils::SwfMovie;
 use ruffle_core::Player;
 use ruffle_render_wgpu::target::TextureTarget;
@@ -86,6 +89,9 @@ fn take_screenshot(
         Box::new(NullInputBackend::new()),
         movie,
         Box::new(MemoryStorageBackend::default()),
+        Box::new(SoftwareVideoBackend::new()),
+        Box::new(NullSocketBackend::new()),
+        Box::new(InProcessLocalConnectionBackend::new()),
     )?;
 
     player
@@ -1,17 +1,20 @@
 use ruffle_core::backend::render::{
-    swf::{self, CharacterId, GradientInterpolation, GradientSpread},
-    Bitmap, BitmapFormat, BitmapHandle, BitmapInfo, Color, JpegTagFormat, Letterbox, RenderBackend,
-    ShapeHandle, Transform,
+    blend::blend_layer,
+    filters::apply_filters,
+    premultiply_alpha_rgba,
+    swf::{self, BlendMode, CharacterId, GradientInterpolation, GradientSpread},
+    unmultiply_alpha_rgba, Bitmap, BitmapFormat, BitmapHandle, BitmapInfo, Color, JpegTagFormat,
+    Letterbox, RenderBackend, ShapeHandle, Transform,
 };
 use ruffle_core::color_transform::ColorTransform;
 use ruffle_core::shape_utils::{DistilledShape, DrawCommand};
 use ruffle_web_common::JsResult;
 use std::collections::HashMap;
 use std::convert::TryInto;
-use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen::{Clamped, JsCast, JsValue};
 use web_sys::{
     CanvasGradient, CanvasPattern, CanvasRenderingContext2d, Element, HtmlCanvasElement,
-    HtmlImageElement, Path2d, SvgsvgElement,
+    HtmlImageElement, ImageData, Path2d, SvgsvgElement,
 };
 
 type Error = Box<dyn std::error::Error>;
@@ -397,11 +400,7 @@ impl WebCanvasRenderBackend {
         })
     }
 
-    fn register_bitmap_raw(
-        &mut self,
-        id: CharacterId,
-        bitmap: Bitmap,
-    ) -> Result<BitmapInfo, Error> {
+    fn register_bitmap(&mut self, id: CharacterId, bitmap: Bitmap) -> Result<BitmapInfo, Error> {
         let (width, height) = (bitmap.width, bitmap.height);
         let png = Self::bitmap_to_png_data_uri(bitmap)?;
 
@@ -529,7 +528,7 @@ impl RenderBackend for WebCanvasRenderBackend {
             self.register_bitmap_pure_jpeg(id, data)
         } else {
             let bitmap = ruffle_core::backend::render::decode_define_bits_jpeg(data, None)?;
-            self.register_bitmap_raw(id, bitmap)
+            self.register_bitmap(id, bitmap)
         }
     }
 
@@ -541,7 +540,7 @@ impl RenderBackend for WebCanvasRenderBackend {
     ) -> Result<BitmapInfo, Error> {
         let bitmap =
             ruffle_core::backend::render::decode_define_bits_jpeg(jpeg_data, Some(alpha_data))?;
-        self.register_bitmap_raw(id, bitmap)
+        self.register_bitmap(id, bitmap)
     }
 
     fn register_bitmap_png(
@@ -570,6 +569,55 @@ impl RenderBackend for WebCanvasRenderBackend {
         })
     }
 
+    fn register_bitmap_raw(
+        &mut self,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> Result<BitmapHandle, Error> {
+        let png = Self::bitmap_to_png_data_uri(Bitmap {
+            width,
+            height,
+            data: BitmapFormat::Rgba(rgba),
+        })?;
+
+        let image = HtmlImageElement::new().unwrap();
+        image.set_src(&png);
+
+        let handle = BitmapHandle(self.bitmaps.len());
+        self.bitmaps.push(BitmapData {
+            image,
+            width,
+            height,
+            data: png,
+        });
+        Ok(handle)
+    }
+
+    fn update_texture(
+        &mut self,
+        bitmap: BitmapHandle,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> Result<BitmapHandle, Error> {
+        let png = Self::bitmap_to_png_data_uri(Bitmap {
+            width,
+            height,
+            data: BitmapFormat::Rgba(rgba),
+        })?;
+
+        let bitmap_data = self
+            .bitmaps
+            .get_mut(bitmap.0)
+            .ok_or("Unknown bitmap handle")?;
+        bitmap_data.image.set_src(&png);
+        bitmap_data.width = width;
+        bitmap_data.height = height;
+        bitmap_data.data = png;
+        Ok(bitmap)
+    }
+
     fn begin_frame(&mut self, clear: Color) {
         // Reset canvas transform in case it was left in a dirty state.
         self.context.reset_transform().unwrap();
@@ -736,6 +784,91 @@ impl RenderBackend for WebCanvasRenderBackend {
             .draw_image_with_html_canvas_element(&maskee_canvas, 0.0, 0.0)
             .unwrap();
     }
+
+    fn push_offscreen(&mut self) {
+        self.push_render_target();
+    }
+
+    fn pop_offscreen(&mut self, filters: &[swf::Filter], blend_mode: BlendMode) {
+        let (offscreen_canvas, offscreen_context) = self.pop_render_target();
+        let width = offscreen_canvas.width();
+        let height = offscreen_canvas.height();
+
+        // Canvas has no equivalent for most SWF filters, so they are applied in software.
+        // Canvas image data isn't premultiplied, but the software filters expect it to be.
+        if !filters.is_empty() {
+            if let Ok(image_data) =
+                offscreen_context.get_image_data(0.0, 0.0, width.into(), height.into())
+            {
+                let mut rgba = image_data.data().0;
+                premultiply_alpha_rgba(&mut rgba);
+                apply_filters(&mut rgba, width, height, filters);
+                unmultiply_alpha_rgba(&mut rgba);
+                if let Ok(filtered) =
+                    ImageData::new_with_u8_clamped_array_and_sh(Clamped(&mut rgba), width, height)
+                {
+                    offscreen_context
+                        .put_image_data(&filtered, 0.0, 0.0)
+                        .warn_on_error();
+                }
+            }
+        }
+
+        self.context.reset_transform().warn_on_error();
+        if let Some(operation) = composite_operation(blend_mode) {
+            self.context
+                .set_global_composite_operation(operation)
+                .unwrap();
+            self.context
+                .draw_image_with_html_canvas_element(&offscreen_canvas, 0.0, 0.0)
+                .unwrap();
+            self.context
+                .set_global_composite_operation("source-over")
+                .unwrap();
+        } else {
+            // The remaining blend modes have to read the target beneath the layer.
+            let layer_data =
+                offscreen_context.get_image_data(0.0, 0.0, width.into(), height.into());
+            let backdrop_data = self
+                .context
+                .get_image_data(0.0, 0.0, width.into(), height.into());
+            if let (Ok(layer_data), Ok(backdrop_data)) = (layer_data, backdrop_data) {
+                let mut layer = layer_data.data().0;
+                let mut backdrop = backdrop_data.data().0;
+                premultiply_alpha_rgba(&mut layer);
+                premultiply_alpha_rgba(&mut backdrop);
+                blend_layer(&layer, &mut backdrop, blend_mode);
+                unmultiply_alpha_rgba(&mut backdrop);
+                if let Ok(blended) = ImageData::new_with_u8_clamped_array_and_sh(
+                    Clamped(&mut backdrop),
+                    width,
+                    height,
+                ) {
+                    self.context
+                        .put_image_data(&blended, 0.0, 0.0)
+                        .warn_on_error();
+                }
+            }
+        }
+    }
+}
+
+/// The canvas composite operation for a blend mode, if there is one.
+fn composite_operation(blend_mode: BlendMode) -> Option<&'static str> {
+    match blend_mode {
+        BlendMode::Normal | BlendMode::Layer => Some("source-over"),
+        BlendMode::Multiply => Some("multiply"),
+        BlendMode::Screen => Some("screen"),
+        BlendMode::Lighten => Some("lighten"),
+        BlendMode::Darken => Some("darken"),
+        BlendMode::Difference => Some("difference"),
+        BlendMode::Add => Some("lighter"),
+        BlendMode::Overlay => Some("overlay"),
+        BlendMode::HardLight => Some("hard-light"),
+        BlendMode::Alpha => Some("destination-in"),
+        BlendMode::Erase => Some("destination-out"),
+        BlendMode::Subtract | BlendMode::Invert => None,
+    }
 }
 
 #[allow(clippy::cognitive_complexity)]
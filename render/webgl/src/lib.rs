@@ -795,6 +795,50 @@ impl RenderBackend for WebGlRenderBackend {
         self.register_bitmap(swf_tag.id, bitmap)
     }
 
+    fn register_bitmap_raw(
+        &mut self,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> Result<BitmapHandle, Error> {
+        let bitmap = Bitmap {
+            width,
+            height,
+            data: BitmapFormat::Rgba(rgba),
+        };
+        Ok(self.register_bitmap(0, bitmap)?.handle)
+    }
+
+    fn update_texture(
+        &mut self,
+        bitmap: BitmapHandle,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> Result<BitmapHandle, Error> {
+        let (_id, texture) = self
+            .textures
+            .get_mut(bitmap.0)
+            .ok_or("Unknown bitmap handle")?;
+        self.gl.bind_texture(Gl::TEXTURE_2D, Some(&texture.texture));
+        self.gl
+            .tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+                Gl::TEXTURE_2D,
+                0,
+                Gl::RGBA as i32,
+                width as i32,
+                height as i32,
+                0,
+                Gl::RGBA,
+                Gl::UNSIGNED_BYTE,
+                Some(&rgba),
+            )
+            .into_js_result()?;
+        texture.width = width;
+        texture.height = height;
+        Ok(bitmap)
+    }
+
     fn begin_frame(&mut self, clear: Color) {
         self.num_masks = 0;
         self.num_masks_active = 0;
@@ -1180,6 +1224,13 @@ impl RenderBackend for WebGlRenderBackend {
             log::warn!("Mask stack underflow\n");
         }
     }
+
+    fn push_offscreen(&mut self) {
+        // TODO: Render filtered display objects to a framebuffer.
+        // Until then, they are drawn directly without their filters.
+    }
+
+    fn pop_offscreen(&mut self, _filters: &[swf::Filter], _blend_mode: swf::BlendMode) {}
 }
 
 struct Texture {
@@ -3,7 +3,9 @@ use lyon::tessellation::{
     geometry_builder::{BuffersBuilder, FillVertexConstructor, VertexBuffers},
     FillAttributes, FillTessellator, StrokeAttributes, StrokeTessellator, StrokeVertexConstructor,
 };
-use ruffle_core::backend::render::swf::{self, FillStyle};
+use ruffle_core::backend::render::blend::blend_layer;
+use ruffle_core::backend::render::filters::apply_filters;
+use ruffle_core::backend::render::swf::{self, BlendMode, FillStyle, Filter};
 use ruffle_core::backend::render::{
     srgb_to_linear, Bitmap, BitmapFormat, BitmapHandle, BitmapInfo, Color, Letterbox,
     RenderBackend, ShapeHandle, Transform,
@@ -16,7 +18,7 @@ use bytemuck::{Pod, Zeroable};
 use futures::executor::block_on;
 use raw_window_handle::HasRawWindowHandle;
 
-use crate::pipelines::Pipelines;
+use crate::pipelines::{CompositeBlend, Pipelines};
 use crate::shapes::{Draw, DrawType, GradientUniforms, IncompleteDrawType, Mesh};
 use crate::target::{RenderTarget, RenderTargetFrame, SwapChainTarget};
 use crate::utils::{
@@ -44,6 +46,8 @@ pub struct WgpuRenderBackend<T: RenderTarget> {
     pipelines: Pipelines,
     frame_buffer_view: wgpu::TextureView,
     depth_texture_view: wgpu::TextureView,
+    frame_texture: wgpu::Texture,
+    frame_texture_view: wgpu::TextureView,
     current_frame: Option<(T::Frame, wgpu::CommandEncoder)>,
     register_encoder: wgpu::CommandEncoder,
     meshes: Vec<Mesh>,
@@ -57,6 +61,7 @@ pub struct WgpuRenderBackend<T: RenderTarget> {
     test_stencil_mask: u32,
     next_stencil_mask: u32,
     mask_stack: Vec<(u32, u32)>,
+    offscreen_stack: Vec<OffscreenTarget>,
     quad_vbo: wgpu::Buffer,
     quad_ibo: wgpu::Buffer,
     quad_tex_transforms: wgpu::Buffer,
@@ -192,6 +197,15 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
 
         let depth_texture_view = depth_texture.create_default_view();
 
+        let frame_texture = create_layer_texture(
+            &device,
+            target.format(),
+            target.width(),
+            target.height(),
+            create_debug_label!("Frame texture"),
+        );
+        let frame_texture_view = frame_texture.create_default_view();
+
         let (quad_vbo, quad_ibo, quad_tex_transforms) = create_quad_buffers(&device);
 
         let viewport_width = target.width() as f32;
@@ -206,6 +220,8 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
             pipelines,
             frame_buffer_view,
             depth_texture_view,
+            frame_texture,
+            frame_texture_view,
             current_frame: None,
             register_encoder,
             meshes: Vec::new(),
@@ -219,6 +235,7 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
             test_stencil_mask: 0,
             next_stencil_mask: 1,
             mask_stack: Vec::new(),
+            offscreen_stack: Vec::new(),
             quad_vbo,
             quad_ibo,
             quad_tex_transforms,
@@ -615,18 +632,12 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
         bitmap: Bitmap,
         debug_str: &str,
     ) -> Result<BitmapInfo, Error> {
-        let extent = wgpu::Extent3d {
-            width: bitmap.width,
-            height: bitmap.height,
-            depth: 1,
-        };
-
         let data = match bitmap.data {
             BitmapFormat::Rgba(data) => data,
             BitmapFormat::Rgb(data) => {
                 // Expand to RGBA.
                 let mut as_rgba =
-                    Vec::with_capacity(extent.width as usize * extent.height as usize * 4);
+                    Vec::with_capacity(bitmap.width as usize * bitmap.height as usize * 4);
                 for i in (0..data.len()).step_by(3) {
                     as_rgba.push(data[i]);
                     as_rgba.push(data[i + 1]);
@@ -637,6 +648,32 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
             }
         };
 
+        let texture = self.create_texture(id, bitmap.width, bitmap.height, &data, debug_str);
+        let handle = BitmapHandle(self.textures.len());
+        self.textures.push((id, texture));
+
+        Ok(BitmapInfo {
+            handle,
+            width: bitmap.width.try_into().unwrap(),
+            height: bitmap.height.try_into().unwrap(),
+        })
+    }
+
+    /// Creates a texture and queues the upload of its RGBA pixels.
+    fn create_texture(
+        &mut self,
+        id: swf::CharacterId,
+        width: u32,
+        height: u32,
+        data: &[u8],
+        debug_str: &str,
+    ) -> Texture {
+        let extent = wgpu::Extent3d {
+            width,
+            height,
+            depth: 1,
+        };
+
         let texture_label = create_debug_label!("{} Texture {}", debug_str, id);
         let texture = self.device.create_texture(&wgpu::TextureDescriptor {
             label: texture_label.as_deref(),
@@ -651,7 +688,7 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
 
         let buffer = create_buffer_with_data(
             &self.device,
-            &data,
+            data,
             wgpu::BufferUsage::COPY_SRC,
             create_debug_label!("{} transfer buffer {}", debug_str, id),
         );
@@ -672,21 +709,11 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
             extent,
         );
 
-        let handle = BitmapHandle(self.textures.len());
-        self.textures.push((
-            id,
-            Texture {
-                texture,
-                width: bitmap.width,
-                height: bitmap.height,
-            },
-        ));
-
-        Ok(BitmapInfo {
-            handle,
-            width: bitmap.width.try_into().unwrap(),
-            height: bitmap.height.try_into().unwrap(),
-        })
+        Texture {
+            texture,
+            width,
+            height,
+        }
     }
 
     pub fn target(&self) -> &T {
@@ -698,9 +725,8 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
     }
 
     fn draw_rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: Color) {
-        let (frame_output, encoder) = if let Some((frame_output, encoder)) = &mut self.current_frame
-        {
-            (frame_output, encoder)
+        let encoder = if let Some((_frame_output, encoder)) = &mut self.current_frame {
+            encoder
         } else {
             return;
         };
@@ -763,10 +789,11 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
             label: bind_group_label.as_deref(),
         });
 
+        let target_view = current_target_view(&self.offscreen_stack, &self.frame_texture_view);
         let (color_attachment, resolve_target) = if self.msaa_sample_count >= 2 {
-            (&self.frame_buffer_view, Some(frame_output.view()))
+            (&self.frame_buffer_view, Some(target_view))
         } else {
-            (frame_output.view(), None)
+            (target_view, None)
         };
         let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
@@ -805,6 +832,303 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
 
         render_pass.draw_indexed(0..6, 0, 0..1);
     }
+
+    /// Creates a bind group for drawing a texture with the bitmap pipeline.
+    fn texture_bind_group(
+        &self,
+        texture_view: &wgpu::TextureView,
+        world_matrix: [[f32; 4]; 4],
+        colors: ColorAdjustments,
+    ) -> wgpu::BindGroup {
+        let transforms_ubo = create_buffer_with_data(
+            &self.device,
+            bytemuck::cast_slice(&[Transforms {
+                view_matrix: self.view_matrix,
+                world_matrix,
+            }]),
+            wgpu::BufferUsage::UNIFORM,
+            create_debug_label!("Texture transforms transfer buffer"),
+        );
+
+        let colors_ubo = create_buffer_with_data(
+            &self.device,
+            bytemuck::cast_slice(&[colors]),
+            wgpu::BufferUsage::UNIFORM,
+            create_debug_label!("Texture colors transfer buffer"),
+        );
+
+        let sampler = self.device.create_sampler(&wgpu::SamplerDescriptor {
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            lod_min_clamp: 0.0,
+            lod_max_clamp: 100.0,
+            compare: wgpu::CompareFunction::Undefined,
+        });
+
+        let bind_group_label = create_debug_label!("Texture bind group");
+        self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.pipelines.bitmap.bind_layout,
+            bindings: &[
+                wgpu::Binding {
+                    binding: 0,
+                    resource: wgpu::BindingResource::Buffer {
+                        buffer: &transforms_ubo,
+                        range: 0..std::mem::size_of::<Transforms>() as u64,
+                    },
+                },
+                wgpu::Binding {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Buffer {
+                        buffer: &self.quad_tex_transforms,
+                        range: 0..std::mem::size_of::<TextureTransforms>() as u64,
+                    },
+                },
+                wgpu::Binding {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Buffer {
+                        buffer: &colors_ubo,
+                        range: 0..std::mem::size_of::<ColorAdjustments>() as u64,
+                    },
+                },
+                wgpu::Binding {
+                    binding: 3,
+                    resource: wgpu::BindingResource::TextureView(texture_view),
+                },
+                wgpu::Binding {
+                    binding: 4,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+            label: bind_group_label.as_deref(),
+        })
+    }
+
+    /// Draws a texture onto the current render target with the bitmap pipeline.
+    fn draw_texture(
+        &mut self,
+        texture_view: &wgpu::TextureView,
+        world_matrix: [[f32; 4]; 4],
+        colors: ColorAdjustments,
+        blend: CompositeBlend,
+    ) {
+        if self.current_frame.is_none() {
+            return;
+        }
+
+        let bind_group = self.texture_bind_group(texture_view, world_matrix, colors);
+
+        // Blending doesn't matter while drawing a mask, since only the stencil is written.
+        let writing_mask = self.num_masks_active < self.num_masks;
+        let pipeline = if blend == CompositeBlend::Normal || writing_mask {
+            self.pipelines.bitmap.pipeline_for(
+                self.num_masks,
+                self.num_masks_active,
+                self.test_stencil_mask,
+                self.write_stencil_mask,
+            )
+        } else {
+            self.pipelines
+                .composite_pipeline(&self.device, blend, self.test_stencil_mask)
+        };
+
+        let encoder = if let Some((_frame_output, encoder)) = &mut self.current_frame {
+            encoder
+        } else {
+            return;
+        };
+        let target_view = current_target_view(&self.offscreen_stack, &self.frame_texture_view);
+        let (color_attachment, resolve_target) = if self.msaa_sample_count >= 2 {
+            (&self.frame_buffer_view, Some(target_view))
+        } else {
+            (target_view, None)
+        };
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
+                attachment: color_attachment,
+                load_op: wgpu::LoadOp::Load,
+                store_op: wgpu::StoreOp::Store,
+                clear_color: wgpu::Color::WHITE,
+                resolve_target,
+            }],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachmentDescriptor {
+                attachment: &self.depth_texture_view,
+                depth_load_op: wgpu::LoadOp::Load,
+                depth_store_op: wgpu::StoreOp::Store,
+                stencil_load_op: wgpu::LoadOp::Load,
+                stencil_store_op: wgpu::StoreOp::Store,
+                clear_depth: 0.0,
+                clear_stencil: 0,
+            }),
+        });
+
+        render_pass.set_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.set_vertex_buffer(0, &self.quad_vbo, 0, 0);
+        render_pass.set_index_buffer(&self.quad_ibo, 0, 0);
+
+        if writing_mask {
+            render_pass.set_stencil_reference(self.write_stencil_mask);
+        } else {
+            render_pass.set_stencil_reference(self.test_stencil_mask);
+        }
+
+        render_pass.draw_indexed(0..6, 0, 0..1);
+    }
+
+    /// Resets the mask state, returning the previous state so that it can be restored later.
+    fn take_mask_state(&mut self) -> MaskState {
+        let state = MaskState {
+            num_masks: self.num_masks,
+            num_masks_active: self.num_masks_active,
+            write_stencil_mask: self.write_stencil_mask,
+            test_stencil_mask: self.test_stencil_mask,
+            next_stencil_mask: self.next_stencil_mask,
+            mask_stack: std::mem::take(&mut self.mask_stack),
+        };
+        self.num_masks = 0;
+        self.num_masks_active = 0;
+        self.write_stencil_mask = 0;
+        self.test_stencil_mask = 0;
+        self.next_stencil_mask = 1;
+        state
+    }
+
+    fn restore_mask_state(&mut self, state: MaskState) {
+        self.num_masks = state.num_masks;
+        self.num_masks_active = state.num_masks_active;
+        self.write_stencil_mask = state.write_stencil_mask;
+        self.test_stencil_mask = state.test_stencil_mask;
+        self.next_stencil_mask = state.next_stencil_mask;
+        self.mask_stack = state.mask_stack;
+    }
+
+    /// Submits all pending draws and reads back the contents of an offscreen layer,
+    /// and optionally of the target beneath it.
+    ///
+    /// The returned data is tightly packed, in the target's own format.
+    fn read_layer(
+        &mut self,
+        layer: &wgpu::Texture,
+        include_backdrop: bool,
+    ) -> Option<(Vec<u8>, Option<Vec<u8>>)> {
+        let (frame_output, mut encoder) = self.current_frame.take()?;
+
+        let (width, height) = (self.target.width(), self.target.height());
+        let layer_buffer = copy_texture_to_buffer(&self.device, &mut encoder, layer, width, height);
+        let backdrop_buffer = if include_backdrop {
+            let backdrop = self
+                .offscreen_stack
+                .last()
+                .map(|offscreen| &offscreen.texture)
+                .unwrap_or(&self.frame_texture);
+            Some(copy_texture_to_buffer(
+                &self.device,
+                &mut encoder,
+                backdrop,
+                width,
+                height,
+            ))
+        } else {
+            None
+        };
+
+        // The draws so far have to run before we can read the results,
+        // so submit them and continue the frame with a fresh encoder.
+        let register_encoder_label = create_debug_label!("Register encoder");
+        let new_register_encoder =
+            self.device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: register_encoder_label.as_deref(),
+                });
+        let register_buffer = replace(&mut self.register_encoder, new_register_encoder).finish();
+        self.queue.submit(&[register_buffer, encoder.finish()]);
+        let frame_encoder_label = create_debug_label!("Frame encoder");
+        let frame_encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: frame_encoder_label.as_deref(),
+            });
+        self.current_frame = Some((frame_output, frame_encoder));
+
+        let buffer_size = u64::from(padded_bytes_per_row(width)) * u64::from(height);
+        let layer_future = layer_buffer.map_read(0, buffer_size);
+        let backdrop_future = backdrop_buffer
+            .as_ref()
+            .map(|buffer| buffer.map_read(0, buffer_size));
+        self.device.poll(wgpu::Maintain::Wait);
+
+        let layer = unpad_mapped_buffer(block_on(layer_future), width, height)?;
+        let backdrop = match backdrop_future {
+            Some(future) => Some(unpad_mapped_buffer(block_on(future), width, height)?),
+            None => None,
+        };
+        Some((layer, backdrop))
+    }
+
+    /// Uploads tightly packed RGBA data into a new texture as part of the current frame.
+    fn create_frame_texture(
+        &mut self,
+        rgba: &[u8],
+        width: u32,
+        height: u32,
+    ) -> Option<wgpu::Texture> {
+        let (_frame_output, encoder) = self.current_frame.as_mut()?;
+
+        let extent = wgpu::Extent3d {
+            width,
+            height,
+            depth: 1,
+        };
+        let texture_label = create_debug_label!("Filtered offscreen texture");
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: texture_label.as_deref(),
+            size: extent,
+            array_layer_count: 1,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
+        });
+
+        let row_bytes = (width * 4) as usize;
+        let padded_row_bytes = padded_bytes_per_row(width);
+        let mut padded = vec![0; padded_row_bytes as usize * height as usize];
+        for (src, dst) in rgba
+            .chunks(row_bytes)
+            .zip(padded.chunks_mut(padded_row_bytes as usize))
+        {
+            dst[..row_bytes].copy_from_slice(src);
+        }
+        let buffer = create_buffer_with_data(
+            &self.device,
+            &padded,
+            wgpu::BufferUsage::COPY_SRC,
+            create_debug_label!("Filtered offscreen transfer buffer"),
+        );
+
+        encoder.copy_buffer_to_texture(
+            wgpu::BufferCopyView {
+                buffer: &buffer,
+                offset: 0,
+                bytes_per_row: padded_row_bytes,
+                rows_per_image: 0,
+            },
+            wgpu::TextureCopyView {
+                texture: &texture,
+                mip_level: 0,
+                array_layer: 0,
+                origin: wgpu::Origin3d::ZERO,
+            },
+            extent,
+        );
+
+        Some(texture)
+    }
 }
 
 impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
@@ -849,6 +1173,15 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
         });
         self.depth_texture_view = depth_texture.create_default_view();
 
+        self.frame_texture = create_layer_texture(
+            &self.device,
+            self.target.format(),
+            width,
+            height,
+            create_debug_label!("Frame texture"),
+        );
+        self.frame_texture_view = self.frame_texture.create_default_view();
+
         self.viewport_width = width as f32;
         self.viewport_height = height as f32;
         self.view_matrix = build_view_matrix(width, height);
@@ -923,6 +1256,35 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
         self.register_bitmap(swf_tag.id, bitmap, "PNG")
     }
 
+    fn register_bitmap_raw(
+        &mut self,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> Result<BitmapHandle, Error> {
+        let texture = self.create_texture(0, width, height, &rgba, "Raw");
+        let handle = BitmapHandle(self.textures.len());
+        self.textures.push((0, texture));
+        Ok(handle)
+    }
+
+    fn update_texture(
+        &mut self,
+        bitmap: BitmapHandle,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> Result<BitmapHandle, Error> {
+        let id = self
+            .textures
+            .get(bitmap.0)
+            .map(|(id, _texture)| *id)
+            .ok_or("Unknown bitmap handle")?;
+        let texture = self.create_texture(id, width, height, &rgba, "Updated");
+        self.textures[bitmap.0] = (id, texture);
+        Ok(bitmap)
+    }
+
     fn begin_frame(&mut self, clear: Color) {
         assert!(self.current_frame.is_none());
         self.current_frame = match self.target.get_next_texture() {
@@ -947,11 +1309,12 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
         self.test_stencil_mask = 0;
         self.next_stencil_mask = 1;
 
-        if let Some((frame_output, encoder)) = &mut self.current_frame {
+        if let Some((_frame_output, encoder)) = &mut self.current_frame {
+            let target_view = current_target_view(&self.offscreen_stack, &self.frame_texture_view);
             let (color_attachment, resolve_target) = if self.msaa_sample_count >= 2 {
-                (&self.frame_buffer_view, Some(frame_output.view()))
+                (&self.frame_buffer_view, Some(target_view))
             } else {
-                (frame_output.view(), None)
+                (target_view, None)
             };
             encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
@@ -981,13 +1344,6 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
 
     fn render_bitmap(&mut self, bitmap: BitmapHandle, transform: &Transform) {
         if let Some((_id, texture)) = self.textures.get(bitmap.0) {
-            let (frame_output, encoder) =
-                if let Some((frame_output, encoder)) = &mut self.current_frame {
-                    (frame_output, encoder)
-                } else {
-                    return;
-                };
-
             use ruffle_core::swf::Matrix;
             let transform = Transform {
                 matrix: transform.matrix
@@ -1011,121 +1367,19 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
                 ],
             ];
 
-            let transforms_ubo = create_buffer_with_data(
-                &self.device,
-                bytemuck::cast_slice(&[Transforms {
-                    view_matrix: self.view_matrix,
-                    world_matrix,
-                }]),
-                wgpu::BufferUsage::UNIFORM,
-                create_debug_label!("Bitmap {} transforms transfer buffer", bitmap.0),
-            );
-
-            let colors_ubo = create_buffer_with_data(
-                &self.device,
-                bytemuck::cast_slice(&[ColorAdjustments::from(transform.color_transform)]),
-                wgpu::BufferUsage::UNIFORM,
-                create_debug_label!("Bitmap {} colors transfer buffer", bitmap.0),
-            );
-
             let texture_view = texture.texture.create_default_view();
-            let sampler = self.device.create_sampler(&wgpu::SamplerDescriptor {
-                address_mode_u: wgpu::AddressMode::ClampToEdge,
-                address_mode_v: wgpu::AddressMode::ClampToEdge,
-                address_mode_w: wgpu::AddressMode::ClampToEdge,
-                mag_filter: wgpu::FilterMode::Linear,
-                min_filter: wgpu::FilterMode::Linear,
-                mipmap_filter: wgpu::FilterMode::Linear,
-                lod_min_clamp: 0.0,
-                lod_max_clamp: 100.0,
-                compare: wgpu::CompareFunction::Undefined,
-            });
-
-            let bind_group_label = create_debug_label!("Bitmap {} bind group", bitmap.0);
-            let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-                layout: &self.pipelines.bitmap.bind_layout,
-                bindings: &[
-                    wgpu::Binding {
-                        binding: 0,
-                        resource: wgpu::BindingResource::Buffer {
-                            buffer: &transforms_ubo,
-                            range: 0..std::mem::size_of::<Transforms>() as u64,
-                        },
-                    },
-                    wgpu::Binding {
-                        binding: 1,
-                        resource: wgpu::BindingResource::Buffer {
-                            buffer: &self.quad_tex_transforms,
-                            range: 0..std::mem::size_of::<TextureTransforms>() as u64,
-                        },
-                    },
-                    wgpu::Binding {
-                        binding: 2,
-                        resource: wgpu::BindingResource::Buffer {
-                            buffer: &colors_ubo,
-                            range: 0..std::mem::size_of::<ColorAdjustments>() as u64,
-                        },
-                    },
-                    wgpu::Binding {
-                        binding: 3,
-                        resource: wgpu::BindingResource::TextureView(&texture_view),
-                    },
-                    wgpu::Binding {
-                        binding: 4,
-                        resource: wgpu::BindingResource::Sampler(&sampler),
-                    },
-                ],
-                label: bind_group_label.as_deref(),
-            });
-
-            let (color_attachment, resolve_target) = if self.msaa_sample_count >= 2 {
-                (&self.frame_buffer_view, Some(frame_output.view()))
-            } else {
-                (frame_output.view(), None)
-            };
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
-                    attachment: color_attachment,
-                    load_op: wgpu::LoadOp::Load,
-                    store_op: wgpu::StoreOp::Store,
-                    clear_color: wgpu::Color::WHITE,
-                    resolve_target,
-                }],
-                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachmentDescriptor {
-                    attachment: &self.depth_texture_view,
-                    depth_load_op: wgpu::LoadOp::Load,
-                    depth_store_op: wgpu::StoreOp::Store,
-                    stencil_load_op: wgpu::LoadOp::Load,
-                    stencil_store_op: wgpu::StoreOp::Store,
-                    clear_depth: 0.0,
-                    clear_stencil: 0,
-                }),
-            });
-
-            render_pass.set_pipeline(&self.pipelines.bitmap.pipeline_for(
-                self.num_masks,
-                self.num_masks_active,
-                self.test_stencil_mask,
-                self.write_stencil_mask,
-            ));
-            render_pass.set_bind_group(0, &bind_group, &[]);
-            render_pass.set_vertex_buffer(0, &self.quad_vbo, 0, 0);
-            render_pass.set_index_buffer(&self.quad_ibo, 0, 0);
-
-            if self.num_masks_active < self.num_masks {
-                render_pass.set_stencil_reference(self.write_stencil_mask);
-            } else {
-                render_pass.set_stencil_reference(self.test_stencil_mask);
-            }
-
-            render_pass.draw_indexed(0..6, 0, 0..1);
+            self.draw_texture(
+                &texture_view,
+                world_matrix,
+                ColorAdjustments::from(transform.color_transform),
+                CompositeBlend::Normal,
+            );
         }
     }
 
     fn render_shape(&mut self, shape: ShapeHandle, transform: &Transform) {
-        let (frame_output, encoder) = if let Some((frame_output, encoder)) = &mut self.current_frame
-        {
-            (frame_output, encoder)
+        let encoder = if let Some((_frame_output, encoder)) = &mut self.current_frame {
+            encoder
         } else {
             return;
         };
@@ -1181,10 +1435,11 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
             std::mem::size_of::<Transforms>() as u64,
         );
 
+        let target_view = current_target_view(&self.offscreen_stack, &self.frame_texture_view);
         let (color_attachment, resolve_target) = if self.msaa_sample_count >= 2 {
-            (&self.frame_buffer_view, Some(frame_output.view()))
+            (&self.frame_buffer_view, Some(target_view))
         } else {
-            (frame_output.view(), None)
+            (target_view, None)
         };
         let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
@@ -1248,7 +1503,57 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
     }
 
     fn end_frame(&mut self) {
-        if let Some((_frame, encoder)) = self.current_frame.take() {
+        if let Some((frame, mut encoder)) = self.current_frame.take() {
+            // The frame is drawn into a texture of our own so that blend modes can read it back,
+            // so copy it to the actual output.
+            let world_matrix = [
+                [self.viewport_width, 0.0, 0.0, 0.0],
+                [0.0, self.viewport_height, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ];
+            let bind_group = self.texture_bind_group(
+                &self.frame_texture_view,
+                world_matrix,
+                ColorAdjustments::from(ColorTransform::default()),
+            );
+            let pipeline =
+                self.pipelines
+                    .composite_pipeline(&self.device, CompositeBlend::Replace, 0);
+            let (color_attachment, resolve_target) = if self.msaa_sample_count >= 2 {
+                (&self.frame_buffer_view, Some(frame.view()))
+            } else {
+                (frame.view(), None)
+            };
+            {
+                let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
+                        attachment: color_attachment,
+                        load_op: wgpu::LoadOp::Load,
+                        store_op: wgpu::StoreOp::Store,
+                        clear_color: wgpu::Color::WHITE,
+                        resolve_target,
+                    }],
+                    depth_stencil_attachment: Some(
+                        wgpu::RenderPassDepthStencilAttachmentDescriptor {
+                            attachment: &self.depth_texture_view,
+                            depth_load_op: wgpu::LoadOp::Load,
+                            depth_store_op: wgpu::StoreOp::Store,
+                            stencil_load_op: wgpu::LoadOp::Load,
+                            stencil_store_op: wgpu::StoreOp::Store,
+                            clear_depth: 0.0,
+                            clear_stencil: 0,
+                        },
+                    ),
+                });
+                render_pass.set_pipeline(pipeline);
+                render_pass.set_bind_group(0, &bind_group, &[]);
+                render_pass.set_vertex_buffer(0, &self.quad_vbo, 0, 0);
+                render_pass.set_index_buffer(&self.quad_ibo, 0, 0);
+                render_pass.set_stencil_reference(0);
+                render_pass.draw_indexed(0..6, 0, 0..1);
+            }
+
             let register_encoder_label = create_debug_label!("Register encoder");
             let new_register_encoder =
                 self.device
@@ -1336,11 +1641,13 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
                 );
             }
             self.next_stencil_mask = 1;
-            if let Some((frame_output, encoder)) = &mut self.current_frame {
+            if let Some((_frame_output, encoder)) = &mut self.current_frame {
+                let target_view =
+                    current_target_view(&self.offscreen_stack, &self.frame_texture_view);
                 let (color_attachment, resolve_target) = if self.msaa_sample_count >= 2 {
-                    (&self.frame_buffer_view, Some(frame_output.view()))
+                    (&self.frame_buffer_view, Some(target_view))
                 } else {
-                    (frame_output.view(), None)
+                    (target_view, None)
                 };
                 encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                     color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
@@ -1385,6 +1692,312 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
             self.test_stencil_mask = test;
         }
     }
+
+    fn push_offscreen(&mut self) {
+        let extent = wgpu::Extent3d {
+            width: self.target.width(),
+            height: self.target.height(),
+            depth: 1,
+        };
+
+        let texture = create_layer_texture(
+            &self.device,
+            self.target.format(),
+            extent.width,
+            extent.height,
+            create_debug_label!("Offscreen texture"),
+        );
+        let view = texture.create_default_view();
+
+        let frame_buffer_label = create_debug_label!("Offscreen framebuffer texture");
+        let frame_buffer = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: frame_buffer_label.as_deref(),
+            size: extent,
+            array_layer_count: 1,
+            mip_level_count: 1,
+            sample_count: self.msaa_sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.target.format(),
+            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
+        });
+
+        let depth_label = create_debug_label!("Offscreen depth texture");
+        let depth_texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: depth_label.as_deref(),
+            size: extent,
+            array_layer_count: 1,
+            mip_level_count: 1,
+            sample_count: self.msaa_sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Depth24PlusStencil8,
+            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
+        });
+
+        // Masks from outside of the offscreen target don't apply while drawing into it;
+        // they apply when the result is composited back onto the parent target.
+        let parent_mask_state = self.take_mask_state();
+        let parent_frame_buffer_view = replace(
+            &mut self.frame_buffer_view,
+            frame_buffer.create_default_view(),
+        );
+        let parent_depth_texture_view = replace(
+            &mut self.depth_texture_view,
+            depth_texture.create_default_view(),
+        );
+        self.offscreen_stack.push(OffscreenTarget {
+            texture,
+            view,
+            parent_frame_buffer_view,
+            parent_depth_texture_view,
+            parent_mask_state,
+        });
+
+        if let Some((_frame_output, encoder)) = &mut self.current_frame {
+            let target_view = current_target_view(&self.offscreen_stack, &self.frame_texture_view);
+            let (color_attachment, resolve_target) = if self.msaa_sample_count >= 2 {
+                (&self.frame_buffer_view, Some(target_view))
+            } else {
+                (target_view, None)
+            };
+            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
+                    attachment: color_attachment,
+                    load_op: wgpu::LoadOp::Clear,
+                    store_op: wgpu::StoreOp::Store,
+                    clear_color: wgpu::Color::TRANSPARENT,
+                    resolve_target,
+                }],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachmentDescriptor {
+                    attachment: &self.depth_texture_view,
+                    depth_load_op: wgpu::LoadOp::Clear,
+                    depth_store_op: wgpu::StoreOp::Store,
+                    stencil_load_op: wgpu::LoadOp::Clear,
+                    stencil_store_op: wgpu::StoreOp::Store,
+                    clear_depth: 0.0,
+                    clear_stencil: 0,
+                }),
+            });
+        }
+    }
+
+    fn pop_offscreen(&mut self, filters: &[Filter], blend_mode: BlendMode) {
+        let offscreen = if let Some(offscreen) = self.offscreen_stack.pop() {
+            offscreen
+        } else {
+            log::warn!("Offscreen stack underflow");
+            return;
+        };
+        self.frame_buffer_view = offscreen.parent_frame_buffer_view;
+        self.depth_texture_view = offscreen.parent_depth_texture_view;
+        self.restore_mask_state(offscreen.parent_mask_state);
+
+        let (width, height) = (self.target.width(), self.target.height());
+        let world_matrix = [
+            [width as f32, 0.0, 0.0, 0.0],
+            [0.0, height as f32, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+        let composite_blend = composite_blend(blend_mode);
+
+        if filters.is_empty() {
+            if let Some(blend) = composite_blend {
+                self.draw_texture(&offscreen.view, world_matrix, blend_colors(blend), blend);
+                return;
+            }
+        }
+
+        // There are no filter or blend shaders yet, so these run on the CPU.
+        let (mut rgba, backdrop) =
+            if let Some(data) = self.read_layer(&offscreen.texture, composite_blend.is_none()) {
+                data
+            } else {
+                return;
+            };
+        let is_bgra = self.target.format() == wgpu::TextureFormat::Bgra8Unorm;
+        if is_bgra {
+            swap_red_blue(&mut rgba);
+        }
+        apply_filters(&mut rgba, width, height, filters);
+
+        let (rgba, blend) = if let Some(mut backdrop) = backdrop {
+            if is_bgra {
+                swap_red_blue(&mut backdrop);
+            }
+            blend_layer(&rgba, &mut backdrop, blend_mode);
+            (backdrop, CompositeBlend::Replace)
+        } else {
+            (rgba, composite_blend.unwrap_or(CompositeBlend::Normal))
+        };
+
+        if let Some(texture) = self.create_frame_texture(&rgba, width, height) {
+            self.draw_texture(
+                &texture.create_default_view(),
+                world_matrix,
+                blend_colors(blend),
+                blend,
+            );
+        }
+    }
+}
+
+/// An offscreen render target used to draw a display object before its filters are applied.
+///
+/// While it is in use, its multisample and depth buffers are swapped into the backend,
+/// and the parent target's buffers and mask state are kept here.
+struct OffscreenTarget {
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    parent_frame_buffer_view: wgpu::TextureView,
+    parent_depth_texture_view: wgpu::TextureView,
+    parent_mask_state: MaskState,
+}
+
+struct MaskState {
+    num_masks: u32,
+    num_masks_active: u32,
+    write_stencil_mask: u32,
+    test_stencil_mask: u32,
+    next_stencil_mask: u32,
+    mask_stack: Vec<(u32, u32)>,
+}
+
+/// The view that draws should resolve to: the innermost offscreen target, or the frame texture.
+fn current_target_view<'a>(
+    offscreen_stack: &'a [OffscreenTarget],
+    frame_texture_view: &'a wgpu::TextureView,
+) -> &'a wgpu::TextureView {
+    offscreen_stack
+        .last()
+        .map(|offscreen| &offscreen.view)
+        .unwrap_or(frame_texture_view)
+}
+
+/// The fixed-function blend for a blend mode, if it has one.
+fn composite_blend(blend_mode: BlendMode) -> Option<CompositeBlend> {
+    match blend_mode {
+        BlendMode::Normal | BlendMode::Layer => Some(CompositeBlend::Normal),
+        BlendMode::Add => Some(CompositeBlend::Add),
+        BlendMode::Subtract => Some(CompositeBlend::Subtract),
+        BlendMode::Multiply => Some(CompositeBlend::Multiply),
+        BlendMode::Screen => Some(CompositeBlend::Screen),
+        BlendMode::Lighten => Some(CompositeBlend::Lighten),
+        BlendMode::Invert => Some(CompositeBlend::Invert),
+        BlendMode::Alpha => Some(CompositeBlend::Alpha),
+        BlendMode::Erase => Some(CompositeBlend::Erase),
+        BlendMode::Darken | BlendMode::Difference | BlendMode::Overlay | BlendMode::HardLight => {
+            None
+        }
+    }
+}
+
+/// The color adjustments to composite a layer with the given blend.
+fn blend_colors(blend: CompositeBlend) -> ColorAdjustments {
+    if blend == CompositeBlend::Invert {
+        // Invert only uses the coverage of the layer, so draw it in white.
+        ColorAdjustments {
+            mult_color: [0.0, 0.0, 0.0, 1.0],
+            add_color: [1.0, 1.0, 1.0, 0.0],
+        }
+    } else {
+        ColorAdjustments::from(ColorTransform::default())
+    }
+}
+
+fn swap_red_blue(pixels: &mut [u8]) {
+    for pixel in pixels.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+}
+
+/// Records a copy of a texture into a new buffer that can be mapped for reading.
+fn copy_texture_to_buffer(
+    device: &wgpu::Device,
+    encoder: &mut wgpu::CommandEncoder,
+    texture: &wgpu::Texture,
+    width: u32,
+    height: u32,
+) -> wgpu::Buffer {
+    let padded_row_bytes = padded_bytes_per_row(width);
+    let buffer_label = create_debug_label!("Offscreen readback buffer");
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: buffer_label.as_deref(),
+        size: u64::from(padded_row_bytes) * u64::from(height),
+        usage: wgpu::BufferUsage::COPY_DST | wgpu::BufferUsage::MAP_READ,
+    });
+    encoder.copy_texture_to_buffer(
+        wgpu::TextureCopyView {
+            texture,
+            mip_level: 0,
+            array_layer: 0,
+            origin: wgpu::Origin3d::ZERO,
+        },
+        wgpu::BufferCopyView {
+            buffer: &buffer,
+            offset: 0,
+            bytes_per_row: padded_row_bytes,
+            rows_per_image: 0,
+        },
+        wgpu::Extent3d {
+            width,
+            height,
+            depth: 1,
+        },
+    );
+    buffer
+}
+
+/// Strips the row padding from a buffer filled by `copy_texture_to_buffer`.
+fn unpad_mapped_buffer(
+    map: Result<wgpu::BufferReadMapping, wgpu::BufferAsyncErr>,
+    width: u32,
+    height: u32,
+) -> Option<Vec<u8>> {
+    match map {
+        Ok(map) => {
+            let row_bytes = (width * 4) as usize;
+            let mut data = Vec::with_capacity(row_bytes * height as usize);
+            for row in map.as_slice().chunks(padded_bytes_per_row(width) as usize) {
+                data.extend_from_slice(&row[..row_bytes]);
+            }
+            Some(data)
+        }
+        Err(e) => {
+            log::error!("Unknown error reading offscreen buffer: {:?}", e);
+            None
+        }
+    }
+}
+
+/// Creates a texture that can be drawn into, sampled, and read back.
+fn create_layer_texture(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    width: u32,
+    height: u32,
+    label: Option<String>,
+) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: label.as_deref(),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth: 1,
+        },
+        array_layer_count: 1,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT
+            | wgpu::TextureUsage::SAMPLED
+            | wgpu::TextureUsage::COPY_SRC,
+    })
+}
+
+/// Buffer copies require each row of pixels to be aligned to 256 bytes.
+fn padded_bytes_per_row(width: u32) -> u32 {
+    (width * 4 + 255) & !255
 }
 
 fn create_quad_buffers(device: &wgpu::Device) -> (wgpu::Buffer, wgpu::Buffer, wgpu::Buffer) {
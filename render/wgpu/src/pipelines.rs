@@ -1,4 +1,5 @@
 use crate::{Error, GPUVertex};
+use std::collections::HashMap;
 use wgpu::vertex_attr_array;
 
 #[derive(Debug)]
@@ -6,6 +7,7 @@ pub struct ShapePipeline {
     pub write_mask_pipelines: Vec<wgpu::RenderPipeline>,
     pub read_mask_pipelines: Vec<wgpu::RenderPipeline>,
     pub bind_layout: wgpu::BindGroupLayout,
+    pub pipeline_layout: wgpu::PipelineLayout,
 }
 
 #[derive(Debug)]
@@ -13,6 +15,94 @@ pub struct Pipelines {
     pub color: ShapePipeline,
     pub bitmap: ShapePipeline,
     pub gradient: ShapePipeline,
+    texture_vs: wgpu::ShaderModule,
+    bitmap_fs: wgpu::ShaderModule,
+    msaa_sample_count: u32,
+    composite: HashMap<(CompositeBlend, u32), wgpu::RenderPipeline>,
+}
+
+/// How a texture drawn with the bitmap shader is combined with the target beneath it.
+///
+/// These are the blend modes that can be expressed as a fixed-function blend of
+/// premultiplied colors. The other blend modes are blended in software.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CompositeBlend {
+    Normal,
+    Add,
+    Subtract,
+    Multiply,
+    Screen,
+    Lighten,
+    /// Inverts the target. The texture must be drawn in white for this to work.
+    Invert,
+    Alpha,
+    Erase,
+    /// Overwrites the target with the texture.
+    Replace,
+}
+
+impl CompositeBlend {
+    fn color_blend(self) -> wgpu::BlendDescriptor {
+        use wgpu::{BlendFactor, BlendOperation};
+        let (src_factor, dst_factor, operation) = match self {
+            CompositeBlend::Normal => (
+                BlendFactor::One,
+                BlendFactor::OneMinusSrcAlpha,
+                BlendOperation::Add,
+            ),
+            CompositeBlend::Add => (BlendFactor::One, BlendFactor::One, BlendOperation::Add),
+            CompositeBlend::Subtract => (
+                BlendFactor::One,
+                BlendFactor::One,
+                BlendOperation::ReverseSubtract,
+            ),
+            CompositeBlend::Multiply => (
+                BlendFactor::DstColor,
+                BlendFactor::OneMinusSrcAlpha,
+                BlendOperation::Add,
+            ),
+            CompositeBlend::Screen => (
+                BlendFactor::One,
+                BlendFactor::OneMinusSrcColor,
+                BlendOperation::Add,
+            ),
+            CompositeBlend::Lighten => (BlendFactor::One, BlendFactor::One, BlendOperation::Max),
+            CompositeBlend::Invert => (
+                BlendFactor::OneMinusDstColor,
+                BlendFactor::OneMinusSrcAlpha,
+                BlendOperation::Add,
+            ),
+            CompositeBlend::Alpha => (
+                BlendFactor::Zero,
+                BlendFactor::SrcAlpha,
+                BlendOperation::Add,
+            ),
+            CompositeBlend::Erase => (
+                BlendFactor::Zero,
+                BlendFactor::OneMinusSrcAlpha,
+                BlendOperation::Add,
+            ),
+            CompositeBlend::Replace => (BlendFactor::One, BlendFactor::Zero, BlendOperation::Add),
+        };
+        wgpu::BlendDescriptor {
+            src_factor,
+            dst_factor,
+            operation,
+        }
+    }
+
+    fn alpha_blend(self) -> wgpu::BlendDescriptor {
+        match self {
+            CompositeBlend::Alpha | CompositeBlend::Erase | CompositeBlend::Replace => {
+                self.color_blend()
+            }
+            _ => wgpu::BlendDescriptor {
+                src_factor: wgpu::BlendFactor::One,
+                dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                operation: wgpu::BlendOperation::Add,
+            },
+        }
+    }
 }
 
 impl ShapePipeline {
@@ -54,15 +144,68 @@ impl Pipelines {
             &bitmap_fs_bytes[..],
         ))?);
 
+        let color = create_color_pipelines(&device, &color_vs, &color_fs, msaa_sample_count);
+        let bitmap = create_bitmap_pipeline(&device, &texture_vs, &bitmap_fs, msaa_sample_count);
+        let gradient =
+            create_gradient_pipeline(&device, &texture_vs, &gradient_fs, msaa_sample_count);
+
         Ok(Self {
-            color: create_color_pipelines(&device, &color_vs, &color_fs, msaa_sample_count),
-            bitmap: create_bitmap_pipeline(&device, &texture_vs, &bitmap_fs, msaa_sample_count),
-            gradient: create_gradient_pipeline(
-                &device,
-                &texture_vs,
-                &gradient_fs,
+            color,
+            bitmap,
+            gradient,
+            texture_vs,
+            bitmap_fs,
+            msaa_sample_count,
+            composite: HashMap::new(),
+        })
+    }
+
+    /// Returns a bitmap pipeline that draws with the given blend, testing against the given
+    /// stencil mask.
+    ///
+    /// There are too many combinations to create up front, so these are created on first use.
+    pub fn composite_pipeline(
+        &mut self,
+        device: &wgpu::Device,
+        blend: CompositeBlend,
+        read_mask: u32,
+    ) -> &wgpu::RenderPipeline {
+        let texture_vs = &self.texture_vs;
+        let bitmap_fs = &self.bitmap_fs;
+        let pipeline_layout = &self.bitmap.pipeline_layout;
+        let msaa_sample_count = self.msaa_sample_count;
+        self.composite.entry((blend, read_mask)).or_insert_with(|| {
+            device.create_render_pipeline(&create_pipeline_descriptor(
+                texture_vs,
+                bitmap_fs,
+                pipeline_layout,
+                Some(wgpu::DepthStencilStateDescriptor {
+                    format: wgpu::TextureFormat::Depth24PlusStencil8,
+                    depth_write_enabled: true,
+                    depth_compare: wgpu::CompareFunction::Always,
+                    stencil_front: wgpu::StencilStateFaceDescriptor {
+                        compare: wgpu::CompareFunction::Equal,
+                        fail_op: wgpu::StencilOperation::Keep,
+                        depth_fail_op: wgpu::StencilOperation::Keep,
+                        pass_op: wgpu::StencilOperation::Keep,
+                    },
+                    stencil_back: wgpu::StencilStateFaceDescriptor {
+                        compare: wgpu::CompareFunction::Equal,
+                        fail_op: wgpu::StencilOperation::Keep,
+                        depth_fail_op: wgpu::StencilOperation::Keep,
+                        pass_op: wgpu::StencilOperation::Keep,
+                    },
+                    stencil_read_mask: read_mask,
+                    stencil_write_mask: 0,
+                }),
+                &[wgpu::ColorStateDescriptor {
+                    format: wgpu::TextureFormat::Bgra8Unorm,
+                    color_blend: blend.color_blend(),
+                    alpha_blend: blend.alpha_blend(),
+                    write_mask: wgpu::ColorWrite::ALL,
+                }],
                 msaa_sample_count,
-            ),
+            ))
         })
     }
 }
@@ -230,6 +373,7 @@ fn create_color_pipelines(
         write_mask_pipelines,
         read_mask_pipelines,
         bind_layout,
+        pipeline_layout,
     }
 }
 
@@ -370,6 +514,7 @@ fn create_bitmap_pipeline(
         write_mask_pipelines,
         read_mask_pipelines,
         bind_layout,
+        pipeline_layout,
     }
 }
 
@@ -504,5 +649,6 @@ fn create_gradient_pipeline(
         write_mask_pipelines,
         read_mask_pipelines,
         bind_layout,
+        pipeline_layout,
     }
 }
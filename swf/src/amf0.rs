@@ -0,0 +1,6 @@
+//! Reading and writing of AMF0, the serialization format used by local shared objects
+//! (`.sol` files) and Flash Remoting.
+
+pub mod read;
+pub mod types;
+pub mod write;
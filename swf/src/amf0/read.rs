@@ -0,0 +1,266 @@
+use crate::amf0::types::*;
+use crate::error::{Error, Result};
+use byteorder::{BigEndian, ReadBytesExt};
+use std::io::{Cursor, Read};
+
+/// The deepest nesting of objects that will be read, to protect against stack overflows.
+const MAX_DEPTH: usize = 256;
+
+/// Reads a local shared object from the contents of a `.sol` file.
+pub fn read_lso(input: &[u8]) -> Result<Lso> {
+    let mut reader = Reader::new(input);
+
+    if reader.read_u16()? != 0x00BF {
+        return Err(Error::invalid_data("Invalid .sol file signature"));
+    }
+    let length = reader.read_u32()? as usize;
+    if length > input.len() - 6 {
+        return Err(Error::invalid_data("Truncated .sol file"));
+    }
+    let mut signature = [0u8; 10];
+    reader.inner.read_exact(&mut signature)?;
+    if &signature[..4] != b"TCSO" {
+        return Err(Error::invalid_data("Invalid .sol file signature"));
+    }
+
+    let name = reader.read_string()?;
+    let amf_version = reader.read_u32()?;
+    if amf_version != 0 {
+        return Err(Error::unsupported(
+            "Only AMF0 local shared objects are supported",
+        ));
+    }
+
+    let mut body = Vec::new();
+    while (reader.inner.position() as usize) < length + 6 {
+        let key = reader.read_string()?;
+        let value = reader.read_value()?;
+        // Each property is followed by a padding byte.
+        reader.read_u8()?;
+        body.push((key, value));
+    }
+
+    Ok(Lso { name, body })
+}
+
+pub struct Reader<'a> {
+    inner: Cursor<&'a [u8]>,
+    depth: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(input: &'a [u8]) -> Self {
+        Self {
+            inner: Cursor::new(input),
+            depth: 0,
+        }
+    }
+
+    #[inline]
+    pub fn pos(&self) -> usize {
+        self.inner.position() as usize
+    }
+
+    pub fn read_value(&mut self) -> Result<Value> {
+        let value = match self.read_u8()? {
+            marker::NUMBER => Value::Number(self.read_f64()?),
+            marker::BOOLEAN => Value::Bool(self.read_u8()? != 0),
+            marker::STRING => Value::String(self.read_string()?),
+            marker::OBJECT => Value::Object(self.read_properties()?),
+            marker::NULL => Value::Null,
+            marker::UNDEFINED => Value::Undefined,
+            marker::REFERENCE => Value::Reference(self.read_u16()?),
+            marker::ECMA_ARRAY => {
+                let length = self.read_u32()?;
+                let properties = self.read_properties()?;
+                Value::ECMAArray { length, properties }
+            }
+            marker::STRICT_ARRAY => {
+                let length = self.read_u32()?;
+                let mut values = Vec::new();
+                for _ in 0..length {
+                    values.push(self.read_nested_value()?);
+                }
+                Value::StrictArray(values)
+            }
+            marker::DATE => {
+                let time = self.read_f64()?;
+                let timezone = self.read_i16()?;
+                Value::Date(time, timezone)
+            }
+            marker::LONG_STRING => Value::String(self.read_long_string()?),
+            marker::UNSUPPORTED => Value::Unsupported,
+            marker::XML_DOCUMENT => Value::XMLDocument(self.read_long_string()?),
+            marker::TYPED_OBJECT => {
+                let class_name = self.read_string()?;
+                let properties = self.read_properties()?;
+                Value::TypedObject(class_name, properties)
+            }
+            marker::MOVIE_CLIP | marker::RECORD_SET => {
+                return Err(Error::unsupported("Reserved AMF0 type"))
+            }
+            marker::AVMPLUS_OBJECT => {
+                return Err(Error::unsupported("AMF3 values are not supported"))
+            }
+            _ => return Err(Error::invalid_data("Invalid AMF0 type marker")),
+        };
+        Ok(value)
+    }
+
+    /// Reads a value that is part of another value, guarding against deep nesting.
+    fn read_nested_value(&mut self) -> Result<Value> {
+        if self.depth >= MAX_DEPTH {
+            return Err(Error::invalid_data("AMF0 values are nested too deeply"));
+        }
+        self.depth += 1;
+        let value = self.read_value();
+        self.depth -= 1;
+        value
+    }
+
+    /// Reads the properties of an object, up to and including the object end marker.
+    fn read_properties(&mut self) -> Result<Vec<(String, Value)>> {
+        let mut properties = Vec::new();
+        loop {
+            let key = self.read_string()?;
+            if key.is_empty() && self.peek_u8()? == marker::OBJECT_END {
+                self.read_u8()?;
+                return Ok(properties);
+            }
+            let value = self.read_nested_value()?;
+            properties.push((key, value));
+        }
+    }
+
+    fn read_string(&mut self) -> Result<String> {
+        let length = self.read_u16()? as usize;
+        self.read_utf8(length)
+    }
+
+    fn read_long_string(&mut self) -> Result<String> {
+        let length = self.read_u32()? as usize;
+        self.read_utf8(length)
+    }
+
+    fn read_utf8(&mut self, length: usize) -> Result<String> {
+        let pos = self.pos();
+        let bytes = self.inner.get_ref().get(pos..pos + length).ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "Buffer underrun")
+        })?;
+        self.inner.set_position((pos + length) as u64);
+        Ok(String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    fn peek_u8(&mut self) -> Result<u8> {
+        let byte = self.read_u8()?;
+        self.inner.set_position(self.inner.position() - 1);
+        Ok(byte)
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.inner.read_u8()?)
+    }
+
+    fn read_u16(&mut self) -> Result<u16> {
+        Ok(self.inner.read_u16::<BigEndian>()?)
+    }
+
+    fn read_i16(&mut self) -> Result<i16> {
+        Ok(self.inner.read_i16::<BigEndian>()?)
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        Ok(self.inner.read_u32::<BigEndian>()?)
+    }
+
+    fn read_f64(&mut self) -> Result<f64> {
+        Ok(self.inner.read_f64::<BigEndian>()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amf0::write::write_lso;
+
+    fn test_lso() -> Lso {
+        Lso {
+            name: "save".to_string(),
+            body: vec![
+                ("score".to_string(), Value::Number(100.0)),
+                ("name".to_string(), Value::String("Player".to_string())),
+                ("empty".to_string(), Value::Undefined),
+                (
+                    "inventory".to_string(),
+                    Value::ECMAArray {
+                        length: 2,
+                        properties: vec![
+                            ("0".to_string(), Value::String("sword".to_string())),
+                            ("1".to_string(), Value::Null),
+                        ],
+                    },
+                ),
+                (
+                    "player".to_string(),
+                    Value::Object(vec![
+                        ("alive".to_string(), Value::Bool(true)),
+                        ("self".to_string(), Value::Reference(1)),
+                        ("saved".to_string(), Value::Date(949363200000.0, -60)),
+                    ]),
+                ),
+                (
+                    "xml".to_string(),
+                    Value::XMLDocument("<a b=\"c\" />".to_string()),
+                ),
+                ("long".to_string(), Value::String("x".repeat(70000))),
+            ],
+        }
+    }
+
+    #[test]
+    fn read_flash_lso() {
+        // A shared object written by Flash Player with `so.data.a = 1; so.data.b = "x";`.
+        let data = [
+            0x00, 0xBF, 0x00, 0x00, 0x00, 0x29, b'T', b'C', b'S', b'O', 0x00, 0x04, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x04, b't', b'e', b's', b't', 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+            b'a', 0x00, 0x3F, 0xF0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, b'b',
+            0x02, 0x00, 0x01, b'x', 0x00,
+        ];
+        let lso = read_lso(&data).unwrap();
+        assert_eq!(
+            lso,
+            Lso {
+                name: "test".to_string(),
+                body: vec![
+                    ("a".to_string(), Value::Number(1.0)),
+                    ("b".to_string(), Value::String("x".to_string())),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn round_trip_lso() {
+        let lso = test_lso();
+        let mut data = Vec::new();
+        write_lso(&lso, &mut data).unwrap();
+        assert_eq!(read_lso(&data).unwrap(), lso);
+    }
+
+    #[test]
+    fn read_invalid_lso() {
+        assert!(read_lso(b"not a shared object").is_err());
+
+        let lso = test_lso();
+        let mut data = Vec::new();
+        write_lso(&lso, &mut data).unwrap();
+        assert!(read_lso(&data[..data.len() - 10]).is_err());
+    }
+
+    #[test]
+    fn read_deeply_nested_value() {
+        let mut data = [marker::STRICT_ARRAY, 0, 0, 0, 1].repeat(1000);
+        data.push(marker::NULL);
+        assert!(Reader::new(&data).read_value().is_err());
+    }
+}
@@ -0,0 +1,71 @@
+/// The type markers of AMF0 values.
+pub(crate) mod marker {
+    pub const NUMBER: u8 = 0x00;
+    pub const BOOLEAN: u8 = 0x01;
+    pub const STRING: u8 = 0x02;
+    pub const OBJECT: u8 = 0x03;
+    pub const MOVIE_CLIP: u8 = 0x04;
+    pub const NULL: u8 = 0x05;
+    pub const UNDEFINED: u8 = 0x06;
+    pub const REFERENCE: u8 = 0x07;
+    pub const ECMA_ARRAY: u8 = 0x08;
+    pub const OBJECT_END: u8 = 0x09;
+    pub const STRICT_ARRAY: u8 = 0x0A;
+    pub const DATE: u8 = 0x0B;
+    pub const LONG_STRING: u8 = 0x0C;
+    pub const UNSUPPORTED: u8 = 0x0D;
+    pub const RECORD_SET: u8 = 0x0E;
+    pub const XML_DOCUMENT: u8 = 0x0F;
+    pub const TYPED_OBJECT: u8 = 0x10;
+    pub const AVMPLUS_OBJECT: u8 = 0x11;
+}
+
+/// A value in the AMF0 format.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Number(f64),
+    Bool(bool),
+    String(String),
+
+    /// An anonymous object, with its properties in the order they were written.
+    Object(Vec<(String, Value)>),
+    Null,
+    Undefined,
+
+    /// A reference to an earlier complex value.
+    ///
+    /// Objects, ECMA arrays, strict arrays and typed objects are numbered from 0 in the order
+    /// they appear in the stream, which allows cyclic data to be stored.
+    Reference(u16),
+
+    /// An associative array, as written for ActionScript `Array`s.
+    ///
+    /// The elements of the array are stored as properties named after their index, alongside
+    /// any other properties of the array.
+    ECMAArray {
+        length: u32,
+        properties: Vec<(String, Value)>,
+    },
+    StrictArray(Vec<Value>),
+
+    /// A date in milliseconds since the Unix epoch, and the timezone offset in minutes of the
+    /// machine that wrote it.
+    Date(f64, i16),
+    XMLDocument(String),
+
+    /// An object of a registered class, with its class name and properties.
+    TypedObject(String, Vec<(String, Value)>),
+
+    /// A value that has no AMF0 representation.
+    Unsupported,
+}
+
+/// A local shared object, as stored in a `.sol` file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lso {
+    /// The name of the shared object.
+    pub name: String,
+
+    /// The properties of the `data` object of the shared object.
+    pub body: Vec<(String, Value)>,
+}
@@ -0,0 +1,164 @@
+use crate::amf0::types::*;
+use crate::error::{Error, Result};
+use byteorder::{BigEndian, WriteBytesExt};
+use std::io::{self, Write};
+
+/// Writes a local shared object in the `.sol` file format.
+pub fn write_lso<W: Write>(lso: &Lso, mut output: W) -> Result<()> {
+    let mut body = Vec::new();
+    {
+        let mut writer = Writer::new(&mut body);
+        writer.inner.write_all(b"TCSO")?;
+        writer
+            .inner
+            .write_all(&[0x00, 0x04, 0x00, 0x00, 0x00, 0x00])?;
+        writer.write_string(&lso.name)?;
+        // AMF version.
+        writer.inner.write_u32::<BigEndian>(0)?;
+
+        for (key, value) in &lso.body {
+            writer.write_string(key)?;
+            writer.write_value(value)?;
+            // Each property is followed by a padding byte.
+            writer.inner.write_u8(0)?;
+        }
+    }
+
+    if body.len() > u32::MAX as usize {
+        return Err(Error::invalid_data("Shared object is too large"));
+    }
+    output.write_u16::<BigEndian>(0x00BF)?;
+    output.write_u32::<BigEndian>(body.len() as u32)?;
+    output.write_all(&body)?;
+    Ok(())
+}
+
+pub struct Writer<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(inner: W) -> Writer<W> {
+        Writer { inner }
+    }
+
+    pub fn write_value(&mut self, value: &Value) -> io::Result<()> {
+        match value {
+            Value::Number(n) => {
+                self.inner.write_u8(marker::NUMBER)?;
+                self.inner.write_f64::<BigEndian>(*n)
+            }
+            Value::Bool(b) => {
+                self.inner.write_u8(marker::BOOLEAN)?;
+                self.inner.write_u8(*b as u8)
+            }
+            Value::String(s) if s.len() > u16::MAX as usize => {
+                self.inner.write_u8(marker::LONG_STRING)?;
+                self.write_long_string(s)
+            }
+            Value::String(s) => {
+                self.inner.write_u8(marker::STRING)?;
+                self.write_string(s)
+            }
+            Value::Object(properties) => {
+                self.inner.write_u8(marker::OBJECT)?;
+                self.write_properties(properties)
+            }
+            Value::Null => self.inner.write_u8(marker::NULL),
+            Value::Undefined => self.inner.write_u8(marker::UNDEFINED),
+            Value::Reference(index) => {
+                self.inner.write_u8(marker::REFERENCE)?;
+                self.inner.write_u16::<BigEndian>(*index)
+            }
+            Value::ECMAArray { length, properties } => {
+                self.inner.write_u8(marker::ECMA_ARRAY)?;
+                self.inner.write_u32::<BigEndian>(*length)?;
+                self.write_properties(properties)
+            }
+            Value::StrictArray(values) => {
+                self.inner.write_u8(marker::STRICT_ARRAY)?;
+                self.inner.write_u32::<BigEndian>(values.len() as u32)?;
+                for value in values {
+                    self.write_value(value)?;
+                }
+                Ok(())
+            }
+            Value::Date(time, timezone) => {
+                self.inner.write_u8(marker::DATE)?;
+                self.inner.write_f64::<BigEndian>(*time)?;
+                self.inner.write_i16::<BigEndian>(*timezone)
+            }
+            Value::XMLDocument(xml) => {
+                self.inner.write_u8(marker::XML_DOCUMENT)?;
+                self.write_long_string(xml)
+            }
+            Value::TypedObject(class_name, properties) => {
+                self.inner.write_u8(marker::TYPED_OBJECT)?;
+                self.write_string(class_name)?;
+                self.write_properties(properties)
+            }
+            Value::Unsupported => self.inner.write_u8(marker::UNSUPPORTED),
+        }
+    }
+
+    /// Writes the properties of an object, followed by the object end marker.
+    fn write_properties(&mut self, properties: &[(String, Value)]) -> io::Result<()> {
+        for (key, value) in properties {
+            self.write_string(key)?;
+            self.write_value(value)?;
+        }
+        self.write_string("")?;
+        self.inner.write_u8(marker::OBJECT_END)
+    }
+
+    /// Writes a string with a 16-bit length, truncating it if it is too long.
+    fn write_string(&mut self, s: &str) -> io::Result<()> {
+        let mut len = s.len().min(u16::MAX as usize);
+        while !s.is_char_boundary(len) {
+            len -= 1;
+        }
+        self.inner.write_u16::<BigEndian>(len as u16)?;
+        self.inner.write_all(&s.as_bytes()[..len])
+    }
+
+    fn write_long_string(&mut self, s: &str) -> io::Result<()> {
+        self.inner.write_u32::<BigEndian>(s.len() as u32)?;
+        self.inner.write_all(s.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amf0::read::Reader;
+
+    #[test]
+    fn write_values() {
+        let tests = vec![
+            (Value::Number(1.5), vec![0x00, 0x3F, 0xF8, 0, 0, 0, 0, 0, 0]),
+            (Value::Bool(true), vec![0x01, 0x01]),
+            (
+                Value::String("ab".to_string()),
+                vec![0x02, 0x00, 0x02, b'a', b'b'],
+            ),
+            (Value::Null, vec![0x05]),
+            (Value::Undefined, vec![0x06]),
+            (Value::Reference(2), vec![0x07, 0x00, 0x02]),
+            (
+                Value::Object(vec![("a".to_string(), Value::Null)]),
+                vec![0x03, 0x00, 0x01, b'a', 0x05, 0x00, 0x00, 0x09],
+            ),
+            (
+                Value::StrictArray(vec![Value::Undefined]),
+                vec![0x0A, 0x00, 0x00, 0x00, 0x01, 0x06],
+            ),
+        ];
+
+        for (value, expected) in tests {
+            let mut data = Vec::new();
+            Writer::new(&mut data).write_value(&value).unwrap();
+            assert_eq!(data, expected, "{:?}", value);
+            assert_eq!(Reader::new(&data).read_value().unwrap(), value);
+        }
+    }
+}
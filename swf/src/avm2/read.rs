@@ -90,11 +90,13 @@ impl<R: Read> Reader<R> {
         self.read_u30()
     }
 
-    #[allow(dead_code)]
     fn read_i24(&mut self) -> Result<i32> {
-        Ok(i32::from(self.read_u8()?)
+        // Sign-extend the 24-bit little-endian value.
+        Ok(((i32::from(self.read_u8()?)
             | (i32::from(self.read_u8()?) << 8)
             | (i32::from(self.read_u8()?) << 16))
+            << 8)
+            >> 8)
     }
     fn read_i32(&mut self) -> Result<i32> {
         let mut n: i32 = 0;
@@ -517,8 +519,8 @@ impl<R: Read> Reader<R> {
         })
     }
 
-    #[allow(dead_code)]
-    fn read_op(&mut self) -> Result<Option<Op>> {
+    /// Read a single opcode and its operands from a method body.
+    pub fn read_op(&mut self) -> Result<Option<Op>> {
         use crate::avm2::opcode::OpCode;
         use num_traits::FromPrimitive;
 
@@ -78,9 +78,9 @@ impl<W: Write> Writer<W> {
     #[allow(dead_code)]
     fn write_i24(&mut self, n: i32) -> Result<()> {
         // TODO: Verify n fits in 24-bits.
-        self.write_u8(((n >> 16) & 0xff) as u8)?;
-        self.write_u8(((n >> 8) & 0xff) as u8)?;
         self.write_u8((n & 0xff) as u8)?;
+        self.write_u8(((n >> 8) & 0xff) as u8)?;
+        self.write_u8(((n >> 16) & 0xff) as u8)?;
         Ok(())
     }
 
@@ -18,6 +18,7 @@ extern crate num_traits;
 #[cfg(feature = "lzma")]
 extern crate xz2;
 
+pub mod amf0;
 pub mod avm1;
 pub mod avm2;
 pub mod error;
@@ -448,7 +448,9 @@ impl<R: Read> Reader<R> {
             Some(TagCode::DefineText2) => {
                 Tag::DefineText(Box::new(tag_reader.read_define_text(2)?))
             }
-            Some(TagCode::DefineVideoStream) => tag_reader.read_define_video_stream()?,
+            Some(TagCode::DefineVideoStream) => {
+                Tag::DefineVideoStream(tag_reader.read_define_video_stream()?)
+            }
             Some(TagCode::EnableTelemetry) => {
                 tag_reader.read_u16()?; // Reserved
                 let password_hash = if length > 2 {
@@ -539,17 +541,7 @@ impl<R: Read> Reader<R> {
                 splitter_rect: tag_reader.read_rectangle()?,
             },
 
-            Some(TagCode::DoAbc) => {
-                let flags = tag_reader.read_u32()?;
-                let name = tag_reader.read_c_string()?;
-                let mut abc_data = Vec::with_capacity(length - 4 - name.len());
-                tag_reader.input.read_to_end(&mut abc_data)?;
-                Tag::DoAbc(DoAbc {
-                    name,
-                    is_lazy_initialize: flags & 1 != 0,
-                    data: abc_data,
-                })
-            }
+            Some(TagCode::DoAbc) => Tag::DoAbc(tag_reader.read_do_abc(length)?),
 
             Some(TagCode::DoAction) => {
                 let mut action_data = Vec::with_capacity(length);
@@ -580,17 +572,7 @@ impl<R: Read> Reader<R> {
                 tab_index: tag_reader.read_u16()?,
             },
 
-            Some(TagCode::SymbolClass) => {
-                let num_symbols = tag_reader.read_u16()?;
-                let mut symbols = Vec::with_capacity(num_symbols as usize);
-                for _ in 0..num_symbols {
-                    symbols.push(SymbolClassLink {
-                        id: tag_reader.read_u16()?,
-                        class_name: tag_reader.read_c_string()?,
-                    });
-                }
-                Tag::SymbolClass(symbols)
-            }
+            Some(TagCode::SymbolClass) => Tag::SymbolClass(tag_reader.read_symbol_class()?),
 
             Some(TagCode::ExportAssets) => Tag::ExportAssets(tag_reader.read_export_assets()?),
 
@@ -2050,6 +2032,43 @@ impl<R: Read> Reader<R> {
         })
     }
 
+    pub fn read_do_abc(&mut self, length: usize) -> Result<DoAbc> {
+        let flags = self.read_u32()?;
+        let name = self.read_c_string()?;
+
+        // The ABC data fills the rest of the tag, after the flags and the null-terminated name.
+        // The input may run past the end of the tag, so only read up to there.
+        let data_len = length
+            .checked_sub(4 + name.len() + 1)
+            .ok_or_else(|| Error::invalid_data("DoAbc tag is too short"))?;
+        let mut abc_data = Vec::new();
+        self.input
+            .by_ref()
+            .take(data_len as u64)
+            .read_to_end(&mut abc_data)?;
+        if abc_data.len() != data_len {
+            return Err(Error::invalid_data("DoAbc tag is truncated"));
+        }
+
+        Ok(DoAbc {
+            name,
+            is_lazy_initialize: flags & 1 != 0,
+            data: abc_data,
+        })
+    }
+
+    pub fn read_symbol_class(&mut self) -> Result<Vec<SymbolClassLink>> {
+        let num_symbols = self.read_u16()?;
+        let mut symbols = Vec::with_capacity(num_symbols as usize);
+        for _ in 0..num_symbols {
+            symbols.push(SymbolClassLink {
+                id: self.read_u16()?,
+                class_name: self.read_c_string()?,
+            });
+        }
+        Ok(symbols)
+    }
+
     pub fn read_export_assets(&mut self) -> Result<ExportAssets> {
         let num_exports = self.read_u16()?;
         let mut exports = Vec::with_capacity(num_exports.into());
@@ -2083,9 +2102,9 @@ impl<R: Read> Reader<R> {
             name: None,
             clip_depth: None,
             class_name: None,
-            filters: vec![],
+            filters: None,
             background_color: None,
-            blend_mode: BlendMode::Normal,
+            blend_mode: None,
             clip_actions: vec![],
             is_image: false,
             is_bitmap_cached: false,
@@ -2150,17 +2169,20 @@ impl<R: Read> Reader<R> {
         };
 
         // PlaceObject3
-        let mut filters = vec![];
-        if (flags & 0b1_00000000) != 0 {
+        let filters = if (flags & 0b1_00000000) != 0 {
+            let mut filters = vec![];
             let num_filters = self.read_u8()?;
             for _ in 0..num_filters {
                 filters.push(self.read_filter()?);
             }
-        }
+            Some(filters)
+        } else {
+            None
+        };
         let blend_mode = if (flags & 0b10_00000000) != 0 {
-            self.read_blend_mode()?
+            Some(self.read_blend_mode()?)
         } else {
-            BlendMode::Normal
+            None
         };
         let is_bitmap_cached = (flags & 0b100_00000000) != 0 && self.read_u8()? != 0;
         let is_visible = (flags & 0b100000_00000000) == 0 || self.read_u8()? != 0;
@@ -2719,7 +2741,7 @@ impl<R: Read> Reader<R> {
         })
     }
 
-    fn read_define_video_stream(&mut self) -> Result<Tag> {
+    pub fn read_define_video_stream(&mut self) -> Result<DefineVideoStream> {
         let id = self.read_character_id()?;
         let num_frames = self.read_u16()?;
         let width = self.read_u16()?;
@@ -2733,14 +2755,14 @@ impl<R: Read> Reader<R> {
             5 => VideoCodec::VP6WithAlpha,
             _ => return Err(Error::invalid_data("Invalid video codec.")),
         };
-        Ok(Tag::DefineVideoStream(DefineVideoStream {
+        Ok(DefineVideoStream {
             id,
             num_frames,
             width,
             height,
             is_smoothed: flags & 0b1 != 0,
             codec,
-            deblocking: match flags & 0b100_0 {
+            deblocking: match flags & 0b111_0 {
                 0b000_0 => VideoDeblocking::UseVideoPacketValue,
                 0b001_0 => VideoDeblocking::None,
                 0b010_0 => VideoDeblocking::Level1,
@@ -2749,7 +2771,7 @@ impl<R: Read> Reader<R> {
                 0b101_0 => VideoDeblocking::Level4,
                 _ => return Err(Error::invalid_data("Invalid video deblocking value.")),
             },
-        }))
+        })
     }
 
     fn read_video_frame(&mut self) -> Result<Tag> {
@@ -3151,6 +3173,39 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn read_do_abc() {
+        // Flags, "ab", then two bytes of ABC data followed by the next tag.
+        let buf = [1, 0, 0, 0, b'a', b'b', 0, 10, 20, 0x40, 0];
+        let mut reader = Reader::new(&buf[..], 9);
+        let do_abc = reader.read_do_abc(9).unwrap();
+        assert_eq!(do_abc.name, "ab");
+        assert!(do_abc.is_lazy_initialize);
+        assert_eq!(do_abc.data, [10, 20]);
+
+        // Tags too short to hold their own name are invalid.
+        let mut reader = Reader::new(&buf[..], 9);
+        assert!(reader.read_do_abc(5).is_err());
+
+        // So are tags that run past the end of the input.
+        let mut reader = Reader::new(&buf[..], 9);
+        assert!(reader.read_do_abc(20).is_err());
+    }
+
+    #[test]
+    fn read_place_object_3_filters() {
+        // Modify depth 1 with the has filters flag and an empty filter list.
+        let buf = [0b1, 0b1, 1, 0, 0];
+        let place_object = reader(&buf).read_place_object_2_or_3(3).unwrap();
+        assert_eq!(place_object.action, PlaceObjectAction::Modify);
+        assert_eq!(place_object.filters, Some(vec![]));
+
+        // Without the flag, the filters are left unchanged.
+        let buf = [0b1, 0, 1, 0];
+        let place_object = reader(&buf).read_place_object_2_or_3(3).unwrap();
+        assert_eq!(place_object.filters, None);
+    }
+
     #[test]
     fn read_shape_styles() {}
 
@@ -2096,9 +2096,9 @@ pub fn tag_tests() -> Vec<TagTestData> {
                 name: None,
                 clip_depth: None,
                 class_name: None,
-                filters: vec![],
+                filters: None,
                 background_color: None,
-                blend_mode: BlendMode::Normal,
+                blend_mode: None,
                 clip_actions: vec![],
                 is_image: false,
                 is_bitmap_cached: false,
@@ -2119,9 +2119,9 @@ pub fn tag_tests() -> Vec<TagTestData> {
                 name: None,
                 clip_depth: None,
                 class_name: None,
-                filters: vec![],
+                filters: None,
                 background_color: None,
-                blend_mode: BlendMode::Normal,
+                blend_mode: None,
                 clip_actions: vec![ClipAction {
                     events: ClipEventFlag::EnterFrame.into(),
                     key_code: None,
@@ -2149,9 +2149,9 @@ pub fn tag_tests() -> Vec<TagTestData> {
                 name: None,
                 clip_depth: None,
                 class_name: None,
-                filters: vec![],
+                filters: None,
                 background_color: None,
-                blend_mode: BlendMode::Normal,
+                blend_mode: None,
                 clip_actions: vec![
                     ClipAction {
                         events: ClipEventFlag::Press | ClipEventFlag::Release,
@@ -2198,9 +2198,9 @@ pub fn tag_tests() -> Vec<TagTestData> {
                 name: None,
                 clip_depth: None,
                 class_name: None,
-                filters: vec![],
+                filters: None,
                 background_color: None,
-                blend_mode: BlendMode::Normal,
+                blend_mode: None,
                 clip_actions: vec![],
                 is_image: true,
                 is_bitmap_cached: false,
@@ -2237,7 +2237,7 @@ pub fn tag_tests() -> Vec<TagTestData> {
                 name: Some("test".to_string()),
                 clip_depth: None,
                 class_name: None,
-                filters: vec![
+                filters: Some(vec![
                     Filter::GradientBevelFilter(Box::new(GradientBevelFilter {
                         colors: vec![
                             GradientRecord {
@@ -2314,14 +2314,14 @@ pub fn tag_tests() -> Vec<TagTestData> {
                         blur_y: 20f64,
                         num_passes: 2,
                     })),
-                ],
+                ]),
                 background_color: Some(Color {
                     r: 255,
                     g: 0,
                     b: 0,
                     a: 255,
                 }),
-                blend_mode: BlendMode::Difference,
+                blend_mode: Some(BlendMode::Difference),
                 clip_actions: vec![
                     ClipAction {
                         events: ClipEventFlag::ReleaseOutside | ClipEventFlag::RollOver,
@@ -2364,9 +2364,9 @@ pub fn tag_tests() -> Vec<TagTestData> {
                 name: None,
                 clip_depth: None,
                 class_name: None,
-                filters: vec![],
+                filters: None,
                 background_color: None,
-                blend_mode: BlendMode::Normal,
+                blend_mode: None,
                 clip_actions: vec![],
                 is_image: false,
                 is_bitmap_cached: false,
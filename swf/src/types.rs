@@ -266,9 +266,9 @@ pub struct PlaceObject {
     pub name: Option<String>,
     pub clip_depth: Option<Depth>,
     pub class_name: Option<String>,
-    pub filters: Vec<Filter>,
+    pub filters: Option<Vec<Filter>>,
     pub background_color: Option<Color>,
-    pub blend_mode: BlendMode,
+    pub blend_mode: Option<BlendMode>,
     pub clip_actions: Vec<ClipAction>,
     pub is_image: bool,
     pub is_bitmap_cached: bool,
@@ -576,7 +576,7 @@ pub struct SymbolClassLink {
     pub class_name: String,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Shape {
     pub version: u8,
     pub id: CharacterId,
@@ -1980,12 +1980,12 @@ impl<W: Write> Writer<W> {
                         } else {
                             0
                         }
-                        | if place_object.blend_mode != BlendMode::Normal {
+                        | if place_object.blend_mode.is_some() {
                             0b10
                         } else {
                             0
                         }
-                        | if !place_object.filters.is_empty() {
+                        | if place_object.filters.is_some() {
                             0b1
                         } else {
                             0
@@ -2022,15 +2022,15 @@ impl<W: Write> Writer<W> {
             }
 
             if place_object_version >= 3 {
-                if !place_object.filters.is_empty() {
-                    writer.write_u8(place_object.filters.len() as u8)?;
-                    for filter in &place_object.filters {
+                if let Some(ref filters) = place_object.filters {
+                    writer.write_u8(filters.len() as u8)?;
+                    for filter in filters {
                         writer.write_filter(filter)?;
                     }
                 }
 
-                if place_object.blend_mode != BlendMode::Normal {
-                    writer.write_blend_mode(place_object.blend_mode)?;
+                if let Some(blend_mode) = place_object.blend_mode {
+                    writer.write_blend_mode(blend_mode)?;
                 }
 
                 if place_object.is_bitmap_cached {
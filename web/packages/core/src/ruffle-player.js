@@ -127,7 +127,7 @@ exports.RufflePlayer = class RufflePlayer extends HTMLElement {
 
                 if (response.ok) {
                     let data = await response.arrayBuffer();
-                    await this.play_swf_data(data);
+                    await this.play_swf_data(data, abs_url);
                     console.log("Playing " + url);
                 } else {
                     console.error(
@@ -159,7 +159,11 @@ exports.RufflePlayer = class RufflePlayer extends HTMLElement {
         }
     }
 
-    async play_swf_data(data) {
+    /*
+     * Plays SWF data. `swf_url` is the absolute URL the data was loaded from, if any; it
+     * is used to resolve the movie's relative URLs and to enforce cross-domain policies.
+     */
+    async play_swf_data(data, swf_url) {
         if (this.isConnected && !this.is_unused_fallback_object()) {
             console.log("Got SWF data");
 
@@ -174,7 +178,22 @@ exports.RufflePlayer = class RufflePlayer extends HTMLElement {
                 throw e;
             });
 
-            this.instance = Ruffle.new(this.container, new Uint8Array(data));
+            const config = window.RufflePlayer && window.RufflePlayer.config;
+            const socket_proxy = (config && config.socketProxy) || null;
+            const scale_mode = (config && config.scaleMode) || null;
+            const letterbox =
+                config && typeof config.letterbox === "boolean"
+                    ? config.letterbox
+                    : null;
+            this.instance = Ruffle.new(
+                this.container,
+                this,
+                new Uint8Array(data),
+                swf_url || null,
+                socket_proxy,
+                scale_mode,
+                letterbox
+            );
             console.log("New Ruffle instance created.");
 
             if (this.play_button) {
@@ -187,6 +206,29 @@ exports.RufflePlayer = class RufflePlayer extends HTMLElement {
         }
     }
 
+    /*
+     * Exposes a callback that the movie registered with `ExternalInterface.addCallback`
+     * as a method of this element.
+     */
+    on_callback_available(name) {
+        this[name] = (...args) => {
+            return this.instance.call_exposed_callback(name, args);
+        };
+    }
+
+    /*
+     * Passes an `fscommand` to the page's `<id>_DoFSCommand` function, as Flash Player does.
+     * Returns whether the page handled it.
+     */
+    on_fs_command(command, args) {
+        const handler = this.id && window[this.id + "_DoFSCommand"];
+        if (typeof handler !== "function") {
+            return false;
+        }
+        handler(command, args);
+        return true;
+    }
+
     /*
      * Copies attributes and children from another element to this player element.
      * Used by the polyfill elements, RuffleObject and RuffleEmbed.
@@ -101,7 +101,9 @@ function localFileSelected() {
 
 function loadRemoteFile(url) {
     fetch(url).then((response) => {
-        response.arrayBuffer().then((data) => player.play_swf_data(data));
+        response.arrayBuffer().then((data) => {
+            player.play_swf_data(data, response.url);
+        });
     });
 }
 
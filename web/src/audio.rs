@@ -3,7 +3,7 @@ use generational_arena::Arena;
 use ruffle_core::backend::audio::decoders::{AdpcmDecoder, Mp3Decoder};
 use ruffle_core::backend::audio::swf::{self, AudioCompression};
 use ruffle_core::backend::audio::{
-    AudioBackend, AudioStreamHandle, SoundHandle, SoundInstanceHandle,
+    AudioBackend, AudioStreamHandle, SoundHandle, SoundInstanceHandle, SoundTransform,
 };
 use ruffle_web_common::JsResult;
 use std::cell::{Cell, RefCell};
@@ -86,6 +86,44 @@ struct SoundInstance {
     /// either decoded on the fly with Decoder, or pre-decoded
     /// and played with and AudioBufferSourceNode.
     instance_type: SoundInstanceType,
+
+    /// The gains applied to the sound, from `SoundTransform::mix_gains`.
+    /// Used by `Decoder` instances, which mix the samples themselves.
+    transform: [f32; 4],
+
+    /// The gain nodes that apply the sound transform to `AudioBuffer` instances,
+    /// in the order `[left_to_left, left_to_right, right_to_left, right_to_right]`.
+    transform_nodes: Option<[web_sys::GainNode; 4]>,
+
+    /// The `AudioContext` time at which the sound started playing.
+    start_time: f64,
+
+    /// The position in seconds that playback started at.
+    start_position: f64,
+
+    /// The length in seconds of one loop of the sound, used to wrap the position.
+    loop_duration: Option<f64>,
+
+    /// The `AudioContext` time at which the sound will finish playing, if known.
+    end_time: Option<f64>,
+}
+
+impl SoundInstance {
+    /// The playback position in milliseconds at the given `AudioContext` time.
+    fn position(&self, current_time: f64) -> u32 {
+        let elapsed = (current_time - self.start_time).max(0.0);
+        let elapsed = match self.loop_duration {
+            Some(duration) if duration > 0.0 => elapsed % duration,
+            _ => elapsed,
+        };
+        ((self.start_position + elapsed) * 1000.0) as u32
+    }
+
+    /// Whether the sound is still playing at the given `AudioContext` time.
+    fn is_playing(&self, current_time: f64) -> bool {
+        self.end_time
+            .map_or(true, |end_time| current_time < end_time)
+    }
 }
 
 #[allow(dead_code)]
@@ -136,6 +174,11 @@ impl WebAudioBackend {
                 node.set_buffer(Some(&*audio_buffer));
 
                 let sound_sample_rate = f64::from(sound.format.sample_rate);
+                let current_time = self.context.current_time();
+                let mut is_stereo = sound.format.is_stereo;
+                let mut start_position = 0.0;
+                let mut loop_duration = None;
+                let mut end_time = Some(current_time + audio_buffer.duration());
                 let node: web_sys::AudioNode = match settings {
                     Some(settings)
                         if sound.skip_sample_frames > 0
@@ -145,37 +188,39 @@ impl WebAudioBackend {
                             || settings.envelope.is_some() =>
                     {
                         // Event sound with non-default parameters.
-                        // Note that start/end values are in 44.1kHZ samples regardless of the sound's sample rate.
-                        let start_sample_frame = f64::from(settings.in_sample.unwrap_or(0))
-                            / 44100.0
-                            + f64::from(sound.skip_sample_frames) / sound_sample_rate;
+                        let (start_sample_frame, end_sample_frame) = event_sound_loop_bounds(
+                            settings,
+                            sound_sample_rate,
+                            sound.num_sample_frames,
+                            sound.skip_sample_frames,
+                        );
                         node.set_loop(settings.num_loops > 1);
                         node.set_loop_start(start_sample_frame);
                         node.start_with_when_and_grain_offset(0.0, start_sample_frame)
                             .warn_on_error();
+                        start_position = f64::from(settings.in_sample.unwrap_or(0)) / 44100.0;
+                        end_time =
+                            Some(current_time + audio_buffer.duration() - start_sample_frame);
 
-                        let current_time = self.context.current_time();
                         // If we are looping or have a custom end point, we have to manually stop the sound.
                         if settings.out_sample.is_some() || settings.num_loops > 1 {
-                            let end_sample_frame = if let Some(out_sample) = settings.out_sample {
-                                f64::from(out_sample) / 44100.0
-                            } else {
-                                f64::from(
-                                    sound.num_sample_frames + u32::from(sound.skip_sample_frames),
-                                ) / sound_sample_rate
-                            };
                             // `AudioSourceBufferNode.loop` is a bool, so we have to stop the loop at the proper time.
                             // `start_with_when_and_grain_offset_and_grain_duration` unfortunately doesn't work
                             // as you might expect with loops, so we use `stop_with_when` to stop the loop.
+                            // A loop count of 0 still plays the sound once.
                             let total_len = (end_sample_frame - start_sample_frame)
-                                * f64::from(settings.num_loops);
+                                * f64::from(settings.num_loops.max(1));
                             node.set_loop_end(end_sample_frame);
                             node.stop_with_when(current_time + total_len)
                                 .warn_on_error();
+                            loop_duration = Some(end_sample_frame - start_sample_frame);
+                            end_time = Some(current_time + total_len);
                         }
 
                         // For envelopes, we rig the node up to some splitter/gain nodes.
                         if let Some(envelope) = &settings.envelope {
+                            // The envelope merges the channels back into a stereo signal.
+                            is_stereo = true;
                             self.create_sound_envelope(
                                 node.into(),
                                 envelope,
@@ -194,6 +239,9 @@ impl WebAudioBackend {
                     }
                 };
 
+                // Rig the node up to the gain nodes for the sound transform.
+                let (node, transform_nodes) = self.create_sound_transform(node, is_stereo)?;
+
                 node.connect_with_audio_node(&self.context.destination())
                     .warn_on_error();
 
@@ -201,6 +249,12 @@ impl WebAudioBackend {
                     handle: Some(handle),
                     format: sound.format.clone(),
                     instance_type: SoundInstanceType::AudioBuffer(node),
+                    transform: SoundTransform::default().mix_gains(),
+                    transform_nodes: Some(transform_nodes),
+                    start_time: current_time,
+                    start_position,
+                    loop_duration,
+                    end_time,
                 };
                 SOUND_INSTANCES.with(|instances| {
                     let mut instances = instances.borrow_mut();
@@ -239,11 +293,17 @@ impl WebAudioBackend {
                     handle: Some(handle),
                     format: sound.format.clone(),
                     instance_type: SoundInstanceType::Decoder(decoder),
+                    transform: SoundTransform::default().mix_gains(),
+                    transform_nodes: None,
+                    start_time: self.context.current_time(),
+                    start_position: 0.0,
+                    loop_duration: None,
+                    end_time: None,
                 };
                 SOUND_INSTANCES.with(|instances| {
                     let mut instances = instances.borrow_mut();
                     let instance_handle = instances.insert(instance);
-                    let script_processor_node = self.context.create_script_processor_with_buffer_size_and_number_of_input_channels_and_number_of_output_channels(4096, 0, if sound.format.is_stereo { 2 } else { 1 }).unwrap();
+                    let script_processor_node = self.context.create_script_processor_with_buffer_size_and_number_of_input_channels_and_number_of_output_channels(4096, 0, 2).unwrap();
                     let script_node = script_processor_node.clone();
                     let closure = Closure::wrap(Box::new(move |event| {
                             SOUND_INSTANCES.with(|instances| {
@@ -341,6 +401,51 @@ impl WebAudioBackend {
         Ok(merger)
     }
 
+    /// Wires up the sound transform using `ChannelSplitter`, `Gain`, and `ChannelMerger` nodes.
+    ///
+    /// Returns the output node, along with the gain nodes in the order
+    /// `[left_to_left, left_to_right, right_to_left, right_to_right]`.
+    fn create_sound_transform(
+        &self,
+        node: web_sys::AudioNode,
+        is_stereo: bool,
+    ) -> Result<(web_sys::AudioNode, [web_sys::GainNode; 4]), Box<dyn std::error::Error>> {
+        let splitter = self
+            .context
+            .create_channel_splitter_with_number_of_outputs(2)
+            .into_js_result()?;
+        let merger: web_sys::AudioNode = self
+            .context
+            .create_channel_merger_with_number_of_inputs(2)
+            .into_js_result()?
+            .into();
+
+        let gains = [
+            self.context.create_gain().into_js_result()?,
+            self.context.create_gain().into_js_result()?,
+            self.context.create_gain().into_js_result()?,
+            self.context.create_gain().into_js_result()?,
+        ];
+        for (gain, value) in gains.iter().zip(&SoundTransform::default().mix_gains()) {
+            gain.gain().set_value(*value);
+        }
+
+        // Note that for mono tracks, we want to use channel 0 (left) for both the left and right.
+        let right_channel = if is_stereo { 1 } else { 0 };
+        let routing = [(0, 0), (0, 1), (right_channel, 0), (right_channel, 1)];
+
+        node.connect_with_audio_node(&splitter).into_js_result()?;
+        for (gain, &(input, output)) in gains.iter().zip(&routing) {
+            splitter
+                .connect_with_audio_node_and_output(gain, input)
+                .into_js_result()?;
+            gain.connect_with_audio_node_and_output_and_input(&merger, 0, output)
+                .into_js_result()?;
+        }
+
+        Ok((merger, gains))
+    }
+
     fn decompress_to_audio_buffer(
         &mut self,
         format: &swf::SoundFormat,
@@ -513,27 +618,19 @@ impl WebAudioBackend {
             let output_buffer = event.output_buffer().unwrap();
             let num_frames = output_buffer.length() as usize;
 
+            let [ll, lr, rl, rr] = instance.transform;
             for _ in 0..num_frames {
                 if let Some(frame) = decoder.next() {
-                    let (l, r) = (frame[0], frame[1]);
-                    left_samples.push(f32::from(l) / 32767.0);
-                    if instance.format.is_stereo {
-                        right_samples.push(f32::from(r) / 32767.0);
-                    }
+                    let (l, r) = (f32::from(frame[0]) / 32767.0, f32::from(frame[1]) / 32767.0);
+                    // Mono decoders duplicate the sample into both channels.
+                    left_samples.push(l * ll + r * rl);
+                    right_samples.push(l * lr + r * rr);
                 } else {
                     complete = true;
                     break;
                 }
             }
-            copy_to_audio_buffer(
-                &output_buffer,
-                Some(&left_samples),
-                if instance.format.is_stereo {
-                    Some(&right_samples)
-                } else {
-                    None
-                },
-            );
+            copy_to_audio_buffer(&output_buffer, Some(&left_samples), Some(&right_samples));
         }
 
         complete
@@ -788,12 +885,60 @@ impl AudioBackend for WebAudioBackend {
     }
 
     fn is_sound_playing_with_handle(&mut self, handle: SoundHandle) -> bool {
+        let current_time = self.context.current_time();
         SOUND_INSTANCES.with(|instances| {
             let instances = instances.borrow();
             let handle = Some(handle);
+            // Finished `AudioBuffer` sounds linger until cleaned up, so check their end time.
             instances
                 .iter()
-                .any(|(_, instance)| instance.handle == handle)
+                .any(|(_, instance)| instance.handle == handle && instance.is_playing(current_time))
+        })
+    }
+
+    fn is_sound_playing(&mut self, instance: SoundInstanceHandle) -> bool {
+        let current_time = self.context.current_time();
+        SOUND_INSTANCES.with(|instances| {
+            let mut instances = instances.borrow_mut();
+            match instances.get(instance) {
+                Some(sound_instance) if sound_instance.is_playing(current_time) => true,
+                Some(_) => {
+                    // Clean up the finished sound.
+                    if let Some(sound_instance) = instances.remove(instance) {
+                        if let SoundInstanceType::AudioBuffer(ref node) =
+                            sound_instance.instance_type
+                        {
+                            let _ = node.disconnect();
+                        }
+                    }
+                    false
+                }
+                None => false,
+            }
+        })
+    }
+
+    fn get_sound_position(&self, instance: SoundInstanceHandle) -> Option<u32> {
+        let current_time = self.context.current_time();
+        SOUND_INSTANCES.with(|instances| {
+            let instances = instances.borrow();
+            instances
+                .get(instance)
+                .map(|instance| instance.position(current_time))
+        })
+    }
+
+    fn set_sound_transform(&mut self, instance: SoundInstanceHandle, transform: SoundTransform) {
+        SOUND_INSTANCES.with(|instances| {
+            let mut instances = instances.borrow_mut();
+            if let Some(instance) = instances.get_mut(instance) {
+                instance.transform = transform.mix_gains();
+                if let Some(transform_nodes) = &instance.transform_nodes {
+                    for (node, value) in transform_nodes.iter().zip(&instance.transform) {
+                        node.gain().set_value(*value);
+                    }
+                }
+            }
         })
     }
 
@@ -821,6 +966,26 @@ extern "C" {
     );
 }
 
+/// The times within the `AudioBuffer` of a sound, in seconds, at which each loop of an event
+/// sound with the given settings starts and ends.
+///
+/// The in and out points are given at 44.1kHz regardless of the sound's sample rate, and
+/// don't include the sample frames skipped at the start of the sound.
+fn event_sound_loop_bounds(
+    settings: &swf::SoundInfo,
+    sample_rate: f64,
+    num_sample_frames: u32,
+    skip_sample_frames: u16,
+) -> (f64, f64) {
+    let skip_time = f64::from(skip_sample_frames) / sample_rate;
+    let start = f64::from(settings.in_sample.unwrap_or(0)) / 44100.0 + skip_time;
+    let end = match settings.out_sample {
+        Some(out_sample) => f64::from(out_sample) / 44100.0,
+        None => f64::from(num_sample_frames) / sample_rate,
+    } + skip_time;
+    (start, end)
+}
+
 // Janky resmapling code.
 // TODO: Clean this up.
 #[allow(unused_assignments)]
@@ -871,3 +1036,36 @@ fn resample(
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sound_info(in_sample: Option<u32>, out_sample: Option<u32>) -> swf::SoundInfo {
+        swf::SoundInfo {
+            event: swf::SoundEvent::Event,
+            in_sample,
+            out_sample,
+            num_loops: 2,
+            envelope: None,
+        }
+    }
+
+    #[test]
+    fn loop_bounds() {
+        // Without points, the whole sound plays after the skipped sample frames.
+        let (start, end) = event_sound_loop_bounds(&sound_info(None, None), 22050.0, 22050, 2205);
+        assert!((start - 0.1).abs() < 1e-9);
+        assert!((end - 1.1).abs() < 1e-9);
+
+        // The points are given at 44.1kHz, and start after the skipped sample frames.
+        let settings = sound_info(Some(22050), Some(66150));
+        let (start, end) = event_sound_loop_bounds(&settings, 22050.0, 44100, 2205);
+        assert!((start - 0.6).abs() < 1e-9);
+        assert!((end - 1.6).abs() < 1e-9);
+
+        let (start, end) = event_sound_loop_bounds(&settings, 11025.0, 22050, 0);
+        assert!((start - 0.5).abs() < 1e-9);
+        assert!((end - 1.5).abs() < 1e-9);
+    }
+}
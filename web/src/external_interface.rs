@@ -0,0 +1,157 @@
+//! `ExternalInterface` and `fscommand` bridge to the embedding page
+
+use js_sys::{Array, Function, Object, Reflect};
+use ruffle_core::external::{
+    ExternalInterfaceMethod, ExternalInterfaceProvider, Value as ExternalValue,
+};
+use std::collections::BTreeMap;
+use wasm_bindgen::{JsCast, JsValue};
+
+/// Lets movies call JavaScript functions on the page, and the page call the callbacks that
+/// movies expose.
+///
+/// `ExternalInterface.call` looks functions up by name on `window`, following dotted names
+/// such as `console.log` through nested objects. Callbacks and `fscommand`s are passed to
+/// the `on_callback_available` and `on_fs_command` methods of the player element.
+pub struct JavascriptInterface {
+    js_player: JsValue,
+}
+
+impl JavascriptInterface {
+    pub fn new(js_player: JsValue) -> Self {
+        Self { js_player }
+    }
+
+    /// Calls a method of the player element.
+    fn call_player_method(&self, name: &str, args: &Array) -> Option<JsValue> {
+        let method = Reflect::get(&self.js_player, &name.into())
+            .ok()?
+            .dyn_into::<Function>()
+            .ok()?;
+        match method.apply(&self.js_player, args) {
+            Ok(result) => Some(result),
+            Err(e) => {
+                log::warn!("Error calling player method {}: {:?}", name, e);
+                None
+            }
+        }
+    }
+}
+
+impl ExternalInterfaceProvider for JavascriptInterface {
+    fn get_method(&self, name: &str) -> Option<Box<dyn ExternalInterfaceMethod>> {
+        let mut this: JsValue = web_sys::window()?.into();
+        let mut value = this.clone();
+        for key in name.split('.') {
+            this = value;
+            value = Reflect::get(&this, &key.into()).ok()?;
+        }
+        let function = value.dyn_into::<Function>().ok()?;
+        Some(Box::new(JavascriptMethod { this, function }))
+    }
+
+    fn on_callback_available(&self, name: &str) {
+        let args = Array::new();
+        args.push(&name.into());
+        self.call_player_method("on_callback_available", &args);
+    }
+
+    fn on_fs_command(&self, command: &str, args: &str) -> bool {
+        let js_args = Array::new();
+        js_args.push(&command.into());
+        js_args.push(&args.into());
+        self.call_player_method("on_fs_command", &js_args)
+            .and_then(|handled| handled.as_bool())
+            .unwrap_or(false)
+    }
+}
+
+/// A JavaScript function, along with the object it is called on.
+struct JavascriptMethod {
+    this: JsValue,
+    function: Function,
+}
+
+impl ExternalInterfaceMethod for JavascriptMethod {
+    fn call(&self, args: &[ExternalValue]) -> ExternalValue {
+        let js_args = Array::new();
+        for arg in args {
+            js_args.push(&value_to_js(arg));
+        }
+        match self.function.apply(&self.this, &js_args) {
+            Ok(result) => js_to_value(&result),
+            Err(e) => {
+                log::warn!("Error in ExternalInterface call: {:?}", e);
+                ExternalValue::Null
+            }
+        }
+    }
+}
+
+/// Converts a value from a movie to pass it to JavaScript.
+pub fn value_to_js(value: &ExternalValue) -> JsValue {
+    match value {
+        ExternalValue::Null => JsValue::NULL,
+        ExternalValue::Bool(value) => (*value).into(),
+        ExternalValue::Number(value) => (*value).into(),
+        ExternalValue::String(value) => JsValue::from_str(value),
+        ExternalValue::Object(values) => {
+            let object = Object::new();
+            for (key, value) in values {
+                let _ = Reflect::set(&object, &key.into(), &value_to_js(value));
+            }
+            object.into()
+        }
+        ExternalValue::List(values) => {
+            let array = Array::new();
+            for value in values {
+                array.push(&value_to_js(value));
+            }
+            array.into()
+        }
+    }
+}
+
+/// Converts a JavaScript value to pass it to a movie.
+///
+/// `undefined`, functions and objects that contain themselves become `Null`.
+pub fn js_to_value(value: &JsValue) -> ExternalValue {
+    js_to_value_inner(value, &mut Vec::new())
+}
+
+fn js_to_value_inner(value: &JsValue, parents: &mut Vec<JsValue>) -> ExternalValue {
+    if let Some(value) = value.as_bool() {
+        return ExternalValue::Bool(value);
+    }
+    if let Some(value) = value.as_f64() {
+        return ExternalValue::Number(value);
+    }
+    if let Some(value) = value.as_string() {
+        return ExternalValue::String(value);
+    }
+    if !value.is_object() || value.is_function() || parents.iter().any(|p| Object::is(p, value)) {
+        return ExternalValue::Null;
+    }
+
+    parents.push(value.clone());
+    let result = if Array::is_array(value) {
+        let array: &Array = value.unchecked_ref();
+        let values = (0..array.length())
+            .map(|i| js_to_value_inner(&array.get(i), parents))
+            .collect();
+        ExternalValue::List(values)
+    } else {
+        let keys = Object::keys(value.unchecked_ref());
+        let mut values = BTreeMap::new();
+        for i in 0..keys.length() {
+            let key = keys.get(i);
+            if let (Some(name), Ok(property)) = (key.as_string(), Reflect::get(value, &key)) {
+                values.insert(name, js_to_value_inner(&property, parents));
+            }
+        }
+        ExternalValue::Object(values)
+    };
+    parents.pop();
+
+    result
+}
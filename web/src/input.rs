@@ -59,6 +59,7 @@ impl InputBackend for WebInputBackend {
         match key {
             KeyCode::Unknown => false,
             KeyCode::Backspace => self.keys_down.contains("Backspace"),
+            KeyCode::Tab => self.keys_down.contains("Tab"),
             KeyCode::Return => self.keys_down.contains("Enter"),
             KeyCode::Shift => {
                 self.keys_down.contains("ShiftLeft") || self.keys_down.contains("ShiftRight")
@@ -145,6 +146,7 @@ impl InputBackend for WebInputBackend {
             KeyCode::Insert => self.keys_down.contains("Insert"),
             KeyCode::Delete => self.keys_down.contains("Delete"),
             KeyCode::Pause => self.keys_down.contains("Pause"),
+            KeyCode::NumLock => self.keys_down.contains("NumLock"),
             KeyCode::ScrollLock => self.keys_down.contains("ScrollLock"),
             KeyCode::F1 => self.keys_down.contains("F1"),
             KeyCode::F2 => self.keys_down.contains("F2"),
@@ -194,6 +196,7 @@ impl InputBackend for WebInputBackend {
 pub fn web_to_ruffle_key_code(key_code: &str) -> Option<KeyCode> {
     let out = match key_code {
         "Backspace" => KeyCode::Backspace,
+        "Tab" => KeyCode::Tab,
         "Enter" => KeyCode::Return,
         "ShiftLeft" | "ShiftRight" => KeyCode::Shift,
         "ControlLeft" | "ControlRight" => KeyCode::Control,
@@ -274,6 +277,7 @@ pub fn web_to_ruffle_key_code(key_code: &str) -> Option<KeyCode> {
         "Insert" => KeyCode::Insert,
         "Delete" => KeyCode::Delete,
         "Pause" => KeyCode::Pause,
+        "NumLock" => KeyCode::NumLock,
         "ScrollLock" => KeyCode::ScrollLock,
         "F1" => KeyCode::F1,
         "F2" => KeyCode::F2,
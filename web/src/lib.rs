@@ -1,30 +1,42 @@
 //! Ruffle web frontend.
 mod audio;
+mod external_interface;
 mod input;
 mod navigator;
+mod socket;
 mod storage;
 
+use crate::external_interface::{js_to_value, value_to_js, JavascriptInterface};
+use crate::socket::WebSocketProxyBackend;
 use crate::storage::LocalStorageBackend;
 use crate::{audio::WebAudioBackend, input::WebInputBackend, navigator::WebNavigatorBackend};
 use generational_arena::{Arena, Index};
 use js_sys::Uint8Array;
+use ruffle_core::backend::local_connection::LocalConnectionRegistry;
 use ruffle_core::backend::render::RenderBackend;
 use ruffle_core::backend::storage::MemoryStorageBackend;
 use ruffle_core::backend::storage::StorageBackend;
+use ruffle_core::backend::video::software::SoftwareVideoBackend;
+use ruffle_core::external::Value as ExternalValue;
 use ruffle_core::tag_utils::SwfMovie;
-use ruffle_core::PlayerEvent;
+use ruffle_core::{MouseWheelDelta, PlayerEvent};
 use ruffle_web_common::JsResult;
 use std::mem::drop;
 use std::sync::{Arc, Mutex};
 use std::{cell::RefCell, error::Error, num::NonZeroI32};
 use wasm_bindgen::{prelude::*, JsCast, JsValue};
-use web_sys::{Element, EventTarget, HtmlCanvasElement, HtmlElement, KeyboardEvent, PointerEvent};
+use web_sys::{
+    Element, EventTarget, HtmlCanvasElement, HtmlElement, KeyboardEvent, PointerEvent, WheelEvent,
+};
 
 thread_local! {
     /// We store the actual instances of the ruffle core in a static pool.
     /// This gives us a clear boundary between the JS side and Rust side, avoiding
     /// issues with lifetimes and type paramters (which cannot be exported with wasm-bindgen).
     static INSTANCES: RefCell<Arena<RuffleInstance>> = RefCell::new(Arena::new());
+
+    /// The local connections shared by every instance on the page.
+    static LOCAL_CONNECTIONS: LocalConnectionRegistry = LocalConnectionRegistry::new();
 }
 
 type AnimationHandler = Closure<dyn FnMut(f64)>;
@@ -42,6 +54,7 @@ struct RuffleInstance {
     mouse_move_callback: Option<Closure<dyn FnMut(PointerEvent)>>,
     mouse_down_callback: Option<Closure<dyn FnMut(PointerEvent)>>,
     mouse_up_callback: Option<Closure<dyn FnMut(PointerEvent)>>,
+    mouse_wheel_callback: Option<Closure<dyn FnMut(WheelEvent)>>,
     window_mouse_down_callback: Option<Closure<dyn FnMut(PointerEvent)>>,
     key_down_callback: Option<Closure<dyn FnMut(KeyboardEvent)>>,
     key_up_callback: Option<Closure<dyn FnMut(KeyboardEvent)>>,
@@ -57,8 +70,64 @@ pub struct Ruffle(Index);
 
 #[wasm_bindgen]
 impl Ruffle {
-    pub fn new(parent: HtmlElement, swf_data: Uint8Array) -> Result<Ruffle, JsValue> {
-        Ruffle::new_internal(parent, swf_data).map_err(|_| "Error creating player".into())
+    /// Creates a player for the given movie.
+    ///
+    /// `swf_url` is the absolute URL the movie was loaded from, if any. Relative URLs
+    /// requested by the movie are resolved against it, and cross-domain policy files are
+    /// enforced for requests to other origins. A movie without a URL, such as one read from
+    /// a local file, is trusted like local content.
+    ///
+    /// `socket_proxy` is the URL of a WebSocket proxy that `XMLSocket` connections are
+    /// tunneled through. Without one, socket connections always fail.
+    ///
+    /// `js_player` is the player element, which is told about `ExternalInterface` callbacks
+    /// and `fscommand`s.
+    ///
+    /// `scale_mode` is how the movie is scaled to fit the player, such as `"noScale"`, until
+    /// the movie sets `Stage.scaleMode` itself. Defaults to `"showAll"`.
+    ///
+    /// `letterbox` is whether black bars cover the content outside of the stage. Defaults to
+    /// `true`.
+    pub fn new(
+        parent: HtmlElement,
+        js_player: JsValue,
+        swf_data: Uint8Array,
+        swf_url: Option<String>,
+        socket_proxy: Option<String>,
+        scale_mode: Option<String>,
+        letterbox: Option<bool>,
+    ) -> Result<Ruffle, JsValue> {
+        Ruffle::new_internal(
+            parent,
+            js_player,
+            swf_data,
+            swf_url,
+            socket_proxy,
+            scale_mode,
+            letterbox,
+        )
+        .map_err(|_| "Error creating player".into())
+    }
+
+    /// Calls a function that the movie exposed with `ExternalInterface.addCallback`.
+    pub fn call_exposed_callback(&self, name: &str, args: Box<[JsValue]>) -> JsValue {
+        let args: Vec<ExternalValue> = args.iter().map(js_to_value).collect();
+
+        // The player is busy if the page is called back from inside one of the movie's own
+        // calls to the page, so the callback can't run.
+        let result = INSTANCES.with(|instances| {
+            let instances = instances.try_borrow().ok()?;
+            let instance = instances.get(self.0)?;
+            let mut core = instance.core.try_lock().ok()?;
+            Some(core.call_internal_interface(name, args))
+        });
+        match result {
+            Some(result) => value_to_js(&result),
+            None => {
+                log::warn!("Unable to call {} while the player is busy", name);
+                JsValue::NULL
+            }
+        }
     }
 
     pub fn play(&mut self) {
@@ -90,6 +159,7 @@ impl Ruffle {
             instance.mouse_down_callback = None;
             instance.mouse_move_callback = None;
             instance.mouse_up_callback = None;
+            instance.mouse_wheel_callback = None;
             instance.window_mouse_down_callback = None;
 
             // Cancel the animation handler, if it's still active.
@@ -106,14 +176,22 @@ impl Ruffle {
 }
 
 impl Ruffle {
-    fn new_internal(parent: HtmlElement, swf_data: Uint8Array) -> Result<Ruffle, Box<dyn Error>> {
+    fn new_internal(
+        parent: HtmlElement,
+        js_player: JsValue,
+        swf_data: Uint8Array,
+        swf_url: Option<String>,
+        socket_proxy: Option<String>,
+        scale_mode: Option<String>,
+        letterbox: Option<bool>,
+    ) -> Result<Ruffle, Box<dyn Error>> {
         console_error_panic_hook::set_once();
         let _ = console_log::init_with_level(log::Level::Trace);
 
         let movie = {
             let mut data = vec![0; swf_data.length() as usize];
             swf_data.copy_to(&mut data[..]);
-            SwfMovie::from_data(&data)?
+            SwfMovie::from_data(&data, swf_url)?
         };
 
         let window = web_sys::window().ok_or_else(|| "Expected window")?;
@@ -138,11 +216,34 @@ impl Ruffle {
             })
             .unwrap_or_else(|| Box::new(MemoryStorageBackend::default()));
 
-        let core =
-            ruffle_core::Player::new(renderer, audio, navigator, input, movie, local_storage)?;
+        let video = Box::new(SoftwareVideoBackend::new());
+        let sockets = Box::new(WebSocketProxyBackend::new(socket_proxy));
+        let local_connection = Box::new(LOCAL_CONNECTIONS.with(|registry| registry.backend()));
+
+        let core = ruffle_core::Player::new(
+            renderer,
+            audio,
+            navigator,
+            input,
+            movie,
+            local_storage,
+            video,
+            sockets,
+            local_connection,
+        )?;
         let mut core_lock = core.lock().unwrap();
         let frame_rate = core_lock.frame_rate();
         core_lock.audio_mut().set_frame_rate(frame_rate);
+        core_lock.add_external_interface(Box::new(JavascriptInterface::new(js_player)));
+        if let Some(scale_mode) = scale_mode {
+            match scale_mode.parse() {
+                Ok(scale_mode) => core_lock.set_scale_mode(scale_mode),
+                Err(e) => log::warn!("{}", e),
+            }
+        }
+        if let Some(letterbox) = letterbox {
+            core_lock.set_letterbox(letterbox);
+        }
         drop(core_lock);
 
         // Create instance.
@@ -158,6 +259,7 @@ impl Ruffle {
             mouse_down_callback: None,
             window_mouse_down_callback: None,
             mouse_up_callback: None,
+            mouse_wheel_callback: None,
             key_down_callback: None,
             key_up_callback: None,
             timestamp: None,
@@ -304,6 +406,42 @@ impl Ruffle {
                 instance.mouse_up_callback = Some(mouse_up_callback);
             }
 
+            // Create mouse wheel handler.
+            {
+                let mouse_wheel_callback = Closure::wrap(Box::new(move |js_event: WheelEvent| {
+                    INSTANCES.with(move |instances| {
+                        let mut instances = instances.borrow_mut();
+                        if let Some(instance) = instances.get_mut(index) {
+                            // The browser reports downwards scrolling as positive.
+                            let delta = match js_event.delta_mode() {
+                                WheelEvent::DOM_DELTA_LINE => {
+                                    MouseWheelDelta::Lines(-js_event.delta_y())
+                                }
+                                _ => MouseWheelDelta::Pixels(-js_event.delta_y()),
+                            };
+                            instance
+                                .core
+                                .lock()
+                                .unwrap()
+                                .handle_event(PlayerEvent::MouseWheel { delta });
+                            if instance.has_focus {
+                                js_event.prevent_default();
+                            }
+                        }
+                    });
+                })
+                    as Box<dyn FnMut(WheelEvent)>);
+                let canvas_events: &EventTarget = canvas.as_ref();
+                canvas_events
+                    .add_event_listener_with_callback(
+                        "wheel",
+                        mouse_wheel_callback.as_ref().unchecked_ref(),
+                    )
+                    .unwrap();
+                let instance = instances.get_mut(index).unwrap();
+                instance.mouse_wheel_callback = Some(mouse_wheel_callback);
+            }
+
             // Create click event handler.
             // {
             //     let click_callback = Closure::wrap(Box::new(move |_| {
@@ -344,22 +482,22 @@ impl Ruffle {
                                     .unwrap()
                                     .keydown(code.clone());
 
-                                if let Some(codepoint) =
-                                    input::web_key_to_codepoint(&js_event.key())
-                                {
+                                if let Some(key_code) = input::web_to_ruffle_key_code(&code) {
                                     instance
                                         .core
                                         .lock()
                                         .unwrap()
-                                        .handle_event(PlayerEvent::TextInput { codepoint });
+                                        .handle_event(PlayerEvent::KeyDown { key_code });
                                 }
 
-                                if let Some(key_code) = input::web_to_ruffle_key_code(&code) {
+                                if let Some(codepoint) =
+                                    input::web_key_to_codepoint(&js_event.key())
+                                {
                                     instance
                                         .core
                                         .lock()
                                         .unwrap()
-                                        .handle_event(PlayerEvent::KeyDown { key_code });
+                                        .handle_event(PlayerEvent::TextInput { codepoint });
                                 }
 
                                 js_event.prevent_default();
@@ -1,15 +1,17 @@
 //! Navigator backend for web
 
-use js_sys::{Array, ArrayBuffer, Uint8Array};
+use js_sys::{Array, ArrayBuffer, Date, Uint8Array};
 use ruffle_core::backend::navigator::{
-    NavigationMethod, NavigatorBackend, OwnedFuture, RequestOptions,
+    NavigationMethod, NavigatorBackend, OwnedFuture, RequestOptions, Response as FetchResponse,
 };
 use ruffle_core::loader::Error;
 use std::collections::HashMap;
 use std::time::Duration;
 use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::{spawn_local, JsFuture};
-use web_sys::{window, Blob, BlobPropertyBag, Performance, Request, RequestInit, Response};
+use web_sys::{
+    window, Blob, BlobPropertyBag, Headers, Performance, Request, RequestInit, Response,
+};
 
 pub struct WebNavigatorBackend {
     performance: Performance,
@@ -93,7 +95,16 @@ impl NavigatorBackend for WebNavigatorBackend {
         Duration::from_millis(dt as u64)
     }
 
-    fn fetch(&self, url: &str, options: RequestOptions) -> OwnedFuture<Vec<u8>, Error> {
+    fn current_time(&self) -> f64 {
+        Date::now()
+    }
+
+    fn local_timezone_offset(&self, time: f64) -> i32 {
+        // JavaScript reports the offset of UTC from local time, so flip the sign.
+        -Date::new(&time.into()).get_timezone_offset() as i32
+    }
+
+    fn fetch(&self, url: &str, options: RequestOptions) -> OwnedFuture<FetchResponse, Error> {
         let url = url.to_string();
         Box::pin(async move {
             let mut init = RequestInit::new();
@@ -126,6 +137,16 @@ impl NavigatorBackend for WebNavigatorBackend {
                 init.body(Some(&datablob));
             }
 
+            if !options.headers().is_empty() {
+                let headers = Headers::new().unwrap();
+                for (name, value) in options.headers() {
+                    if let Err(e) = headers.append(name, value) {
+                        log::warn!("Unable to add request header {}: {:?}", name, e);
+                    }
+                }
+                init.headers(&headers);
+            }
+
             let request = Request::new_with_str_and_init(&url, &init).unwrap();
 
             let window = web_sys::window().unwrap();
@@ -138,6 +159,10 @@ impl NavigatorBackend for WebNavigatorBackend {
             }
 
             let resp: Response = fetchval.unwrap().dyn_into().unwrap();
+            if !resp.ok() {
+                return Err(Error::HttpNotOk(url, resp.status()));
+            }
+
             let data: ArrayBuffer = JsFuture::from(resp.array_buffer().unwrap())
                 .await
                 .unwrap()
@@ -147,7 +172,10 @@ impl NavigatorBackend for WebNavigatorBackend {
             let mut rust_array = vec![0; jsarray.length() as usize];
             jsarray.copy_to(&mut rust_array);
 
-            Ok(rust_array)
+            Ok(FetchResponse {
+                status: resp.status(),
+                body: rust_array,
+            })
         })
     }
 
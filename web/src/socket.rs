@@ -0,0 +1,163 @@
+//! WebSocket proxy socket backend
+
+use generational_arena::Arena;
+use js_sys::{ArrayBuffer, Uint8Array};
+use ruffle_core::backend::socket::{SocketBackend, SocketEvent, SocketHandle};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use url::Url;
+use wasm_bindgen::{prelude::*, JsCast};
+use web_sys::{BinaryType, Event, MessageEvent, WebSocket};
+
+/// A socket backend that tunnels TCP connections through a WebSocket proxy, as browsers can't
+/// open raw TCP connections.
+///
+/// Each connection opens a WebSocket to the proxy URL, with the target server given by the
+/// `host` and `port` query parameters. Data is passed through unchanged in binary messages.
+pub struct WebSocketProxyBackend {
+    proxy_url: Option<Url>,
+    sockets: Arena<Option<Connection>>,
+
+    /// Events reported by the WebSocket callbacks since the last poll.
+    events: Rc<RefCell<Vec<SocketEvent>>>,
+}
+
+/// An open WebSocket, along with the callbacks that must live as long as it does.
+struct Connection {
+    socket: WebSocket,
+    _open_callback: Closure<dyn FnMut(Event)>,
+    _message_callback: Closure<dyn FnMut(MessageEvent)>,
+    _close_callback: Closure<dyn FnMut(Event)>,
+}
+
+impl WebSocketProxyBackend {
+    pub fn new(proxy_url: Option<String>) -> Self {
+        let proxy_url = proxy_url.and_then(|url| match Url::parse(&url) {
+            Ok(url) => Some(url),
+            Err(e) => {
+                log::error!("Invalid socket proxy URL {}: {}", url, e);
+                None
+            }
+        });
+        Self {
+            proxy_url,
+            sockets: Arena::new(),
+            events: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    fn open(&self, handle: SocketHandle, host: &str, port: u16) -> Option<Connection> {
+        let mut url = match &self.proxy_url {
+            Some(url) => url.clone(),
+            None => {
+                log::warn!(
+                    "Unable to connect to {}:{} because no socket proxy is configured",
+                    host,
+                    port
+                );
+                return None;
+            }
+        };
+        url.query_pairs_mut()
+            .append_pair("host", host)
+            .append_pair("port", &port.to_string());
+
+        let socket = match WebSocket::new(url.as_str()) {
+            Ok(socket) => socket,
+            Err(e) => {
+                log::warn!("Unable to open socket proxy {}: {:?}", url, e);
+                return None;
+            }
+        };
+        socket.set_binary_type(BinaryType::Arraybuffer);
+
+        let is_open = Rc::new(Cell::new(false));
+
+        let open_callback = {
+            let events = self.events.clone();
+            let is_open = is_open.clone();
+            Closure::wrap(Box::new(move |_: Event| {
+                is_open.set(true);
+                events.borrow_mut().push(SocketEvent::Connect(handle, true));
+            }) as Box<dyn FnMut(Event)>)
+        };
+        socket.set_onopen(Some(open_callback.as_ref().unchecked_ref()));
+
+        let message_callback = {
+            let events = self.events.clone();
+            Closure::wrap(Box::new(move |event: MessageEvent| {
+                let data = event.data();
+                let data = if let Some(buffer) = data.dyn_ref::<ArrayBuffer>() {
+                    Uint8Array::new(buffer).to_vec()
+                } else if let Some(text) = data.as_string() {
+                    text.into_bytes()
+                } else {
+                    return;
+                };
+                events.borrow_mut().push(SocketEvent::Data(handle, data));
+            }) as Box<dyn FnMut(MessageEvent)>)
+        };
+        socket.set_onmessage(Some(message_callback.as_ref().unchecked_ref()));
+
+        // A failed connection also fires `close`, so errors don't need their own callback.
+        let close_callback = {
+            let events = self.events.clone();
+            Closure::wrap(Box::new(move |_: Event| {
+                let event = if is_open.get() {
+                    SocketEvent::Close(handle)
+                } else {
+                    SocketEvent::Connect(handle, false)
+                };
+                events.borrow_mut().push(event);
+            }) as Box<dyn FnMut(Event)>)
+        };
+        socket.set_onclose(Some(close_callback.as_ref().unchecked_ref()));
+
+        Some(Connection {
+            socket,
+            _open_callback: open_callback,
+            _message_callback: message_callback,
+            _close_callback: close_callback,
+        })
+    }
+}
+
+impl SocketBackend for WebSocketProxyBackend {
+    fn connect(&mut self, host: &str, port: u16) -> SocketHandle {
+        let handle = self.sockets.insert(None);
+        match self.open(handle, host, port) {
+            Some(connection) => self.sockets[handle] = Some(connection),
+            None => self
+                .events
+                .borrow_mut()
+                .push(SocketEvent::Connect(handle, false)),
+        }
+        handle
+    }
+
+    fn send(&mut self, handle: SocketHandle, data: Vec<u8>) {
+        if let Some(Some(connection)) = self.sockets.get(handle) {
+            if let Err(e) = connection.socket.send_with_u8_array(&data) {
+                log::warn!("Unable to send to socket proxy: {:?}", e);
+            }
+        }
+    }
+
+    fn close(&mut self, handle: SocketHandle) {
+        if let Some(Some(connection)) = self.sockets.remove(handle) {
+            connection.socket.set_onopen(None);
+            connection.socket.set_onmessage(None);
+            connection.socket.set_onclose(None);
+            let _ = connection.socket.close();
+        }
+        self.events.borrow_mut().retain(|event| match event {
+            SocketEvent::Connect(h, _) | SocketEvent::Data(h, _) | SocketEvent::Close(h) => {
+                *h != handle
+            }
+        });
+    }
+
+    fn poll(&mut self) -> Vec<SocketEvent> {
+        std::mem::take(&mut *self.events.borrow_mut())
+    }
+}
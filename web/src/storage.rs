@@ -13,16 +13,29 @@ impl LocalStorageBackend {
 }
 
 impl StorageBackend for LocalStorageBackend {
-    fn get_string(&self, name: &str) -> Option<String> {
-        self.storage
+    fn get(&self, name: &str) -> Option<Vec<u8>> {
+        let value = self
+            .storage
             .get(&format!("{}-{}", self.prefix, name))
-            .unwrap_or_default()
+            .unwrap_or_default()?;
+
+        // Values are stored as base64, but older versions stored plain strings.
+        match web_sys::window().and_then(|window| window.atob(&value).ok()) {
+            Some(decoded) => Some(decoded.chars().map(|c| c as u8).collect()),
+            None => Some(value.into_bytes()),
+        }
     }
 
-    fn put_string(&mut self, name: &str, value: String) -> bool {
-        self.storage
-            .set(&format!("{}-{}", self.prefix, name), &value)
-            .is_ok()
+    fn put(&mut self, name: &str, value: &[u8]) -> bool {
+        // `btoa` encodes a string of byte-sized characters.
+        let binary: String = value.iter().map(|&b| char::from(b)).collect();
+        match web_sys::window().and_then(|window| window.btoa(&binary).ok()) {
+            Some(encoded) => self
+                .storage
+                .set(&format!("{}-{}", self.prefix, name), &encoded)
+                .is_ok(),
+            None => false,
+        }
     }
 
     fn remove_key(&mut self, name: &str) {